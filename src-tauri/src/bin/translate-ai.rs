@@ -0,0 +1,4 @@
+// Headless extraction, translation and rebuild; see `app_lib::cli`.
+fn main() -> std::process::ExitCode {
+    app_lib::cli::run()
+}
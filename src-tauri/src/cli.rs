@@ -0,0 +1,233 @@
+// Headless `translate-ai` command-line tool (built with the `cli` feature), for scripts and CI.
+// It runs the same pipeline as the app: extraction, batch translation and packaging.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::core::rpgmv::project::extract_translatable_strings_until;
+use crate::core::rpgmv::snapshot;
+use crate::core::session::{load_session, project_session};
+use crate::error::{AppError, ErrorKind};
+use crate::models::extraction::ExtractionFilter;
+use crate::models::metrics::{EntryTimings, PipelineMetrics};
+use crate::models::provider::ProviderConfig;
+use crate::models::translation::{BatchProgress, BatchTranslationOptions, SourceStringData, WorkingTranslation};
+use crate::services::job_manager::JobManager;
+use crate::services::packaging::{package_translated_project, save_packaged_project};
+use crate::services::pipeline::{self, BatchHost};
+use crate::services::temp_workspace::TempWorkspaces;
+
+const USAGE: &str = "Usage:
+  translate-ai extract <project> --out <entries.json> [--filter <filter.json>]
+  translate-ai translate <project> --from <language> --to <language> --out <translations.json>
+               [--entries <entries.json>] [--provider <provider.json>] [--options <options.json>]
+               [--replace-session]
+  translate-ai rebuild <project> --out <archive.zip> [--translations <translations.json>] [--force]
+
+translate extracts the project when no --entries are given; rebuild uses the project's session
+when no --translations are given. rebuild refuses a translation that fails the project's quality
+gates unless --force is given. translate refuses to replace a saved session for another language
+pair unless --replace-session is given.";
+
+// Options that take a value; `--force` and `--replace-session` are switches.
+const VALUE_OPTIONS: &[&str] = &["filter", "out", "from", "to", "entries", "provider", "options", "translations"];
+
+#[derive(Debug, Default, PartialEq)]
+struct CliArgs {
+    command: String,
+    project_path: String,
+    options: HashMap<String, String>,
+    force: bool,
+    replace_session: bool,
+}
+
+impl CliArgs {
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn required(&self, name: &str) -> Result<&str, AppError> {
+        self.option(name).ok_or_else(|| usage_error(format!("{} needs --{}", self.command, name)))
+    }
+}
+
+fn usage_error(message: String) -> AppError {
+    AppError::new(ErrorKind::Config, message)
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, AppError> {
+    let mut parsed = CliArgs::default();
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some("force") => parsed.force = true,
+            Some("replace-session") => parsed.replace_session = true,
+            Some(name) if VALUE_OPTIONS.contains(&name) => {
+                let value = args.next().ok_or_else(|| usage_error(format!("--{} needs a value", name)))?;
+                parsed.options.insert(name.to_string(), value.clone());
+            }
+            Some(name) => return Err(usage_error(format!("Unknown option --{}", name))),
+            None => positional.push(arg.clone()),
+        }
+    }
+    match <[String; 2]>::try_from(positional) {
+        Ok([command, project_path]) => {
+            parsed.command = command;
+            parsed.project_path = project_path;
+            Ok(parsed)
+        }
+        Err(_) => Err(usage_error("Expected a command and a project folder".to_string())),
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &str) -> Result<T, AppError> {
+    let content = fs::read_to_string(path).map_err(|e| AppError::from(e).with_file(path))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::new(ErrorKind::JsonParse, format!("Invalid JSON: {}", e)).with_file(path))
+}
+
+fn write_json<T: Serialize>(value: &T, path: &str) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::new(ErrorKind::JsonSerialize, format!("Failed to serialize the output: {}", e)))?;
+    fs::write(path, json).map_err(|e| AppError::from(e).with_file(path))
+}
+
+/// Extracts the project and records the extraction, as the app does.
+fn extract(project_path: &str, filter: &ExtractionFilter) -> Result<Vec<SourceStringData>, AppError> {
+    let entries = extract_translatable_strings_until(project_path, filter, &|| false).map_err(AppError::from)?;
+    Ok(snapshot::record_extraction(project_path, entries, false).map_err(AppError::from)?.entries)
+}
+
+/// Prints the batch's progress.
+#[derive(Default)]
+struct CliHost {
+    jobs: JobManager,
+}
+
+impl BatchHost for CliHost {
+    fn jobs(&self) -> &JobManager {
+        &self.jobs
+    }
+
+    fn progress(&self, progress: &BatchProgress, _timings: &EntryTimings) {
+        eprintln!("[{}/{}] {}", progress.processed, progress.total, progress.current_file);
+    }
+
+    fn metrics(&self, metrics: &PipelineMetrics) {
+        eprintln!("Translated {} entries in {:.1} s.", metrics.entries, metrics.wall_ms / 1000.0);
+    }
+}
+
+async fn translate(args: &CliArgs) -> Result<(), AppError> {
+    let project_path = args.project_path.as_str();
+    let (source_language, target_language) = (args.required("from")?, args.required("to")?);
+    let out = args.required("out")?;
+    let entries = match args.option("entries") {
+        Some(path) => read_json(path)?,
+        None => extract(project_path, &ExtractionFilter::default())?,
+    };
+    let provider = args.option("provider").map(read_json::<ProviderConfig>).transpose()?;
+    let options = args.option("options").map(read_json::<BatchTranslationOptions>).transpose()?.unwrap_or_default();
+    let options = BatchTranslationOptions { project_path: Some(project_path.to_string()), ..options };
+    let replace_session = options.replace_session.unwrap_or(false) || args.replace_session;
+    let session =
+        project_session(project_path, source_language, target_language, replace_session).map_err(AppError::from)?;
+    let (results, _) = pipeline::run_batch(
+        &CliHost::default(),
+        entries,
+        source_language,
+        target_language,
+        provider,
+        options,
+        Some(session),
+    )
+    .await
+    .map_err(AppError::from)?;
+    let failed = results.iter().filter(|t| t.error.is_some()).count();
+    if failed > 0 {
+        eprintln!("{} translation(s) failed; they are kept untranslated with their error.", failed);
+    }
+    write_json(&results, out)
+}
+
+fn rebuild(args: &CliArgs) -> Result<(), AppError> {
+    let project_path = args.project_path.as_str();
+    let out = args.required("out")?;
+    let translations: Vec<WorkingTranslation> = match args.option("translations") {
+        Some(path) => read_json(path)?,
+        None => load_session(project_path)
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::new(ErrorKind::NotFound, "The project has no translation session"))?
+            .translations,
+    };
+    let workspaces = TempWorkspaces::default();
+    let zip_path = package_translated_project(&workspaces, project_path, &translations)?;
+    save_packaged_project(&workspaces, Path::new(&zip_path), Path::new(out), args.force)?;
+    eprintln!("Wrote {}.", out);
+    Ok(())
+}
+
+async fn run_command(args: &CliArgs) -> Result<(), AppError> {
+    match args.command.as_str() {
+        "extract" => {
+            let out = args.required("out")?;
+            let filter = args.option("filter").map(read_json).transpose()?.unwrap_or_default();
+            let entries = extract(&args.project_path, &filter)?;
+            eprintln!("Extracted {} entries.", entries.len());
+            write_json(&entries, out)
+        }
+        "translate" => translate(args).await,
+        "rebuild" => rebuild(args),
+        other => Err(usage_error(format!("Unknown command '{}'", other))),
+    }
+}
+
+/// Entry point of the `translate-ai` binary.
+pub fn run() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let result = parse_args(&args).and_then(|args| {
+        let runtime = tokio::runtime::Runtime::new().map_err(AppError::from)?;
+        runtime.block_on(run_command(&args))
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            if e.kind == ErrorKind::Config {
+                eprintln!("\n{}", USAGE);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&args(&["rebuild", "game", "--out", "game.zip", "--force"])).unwrap();
+        assert_eq!(parsed.command, "rebuild");
+        assert_eq!(parsed.project_path, "game");
+        assert_eq!(parsed.option("out"), Some("game.zip"));
+        assert!(parsed.force);
+        assert!(parsed.required("translations").is_err());
+
+        assert!(parse_args(&args(&["extract"])).is_err());
+        assert!(parse_args(&args(&["extract", "game", "--out"])).is_err());
+        assert!(parse_args(&args(&["extract", "game", "--verbose"])).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+// Commands applying one operation to many entries at once.
+
+use crate::error::{AppError, ErrorKind};
+use crate::core::bulk::{find_replace, run_bulk_operation, update_entry};
+use crate::core::glossary::load_project_glossary;
+use crate::core::session::{load_session, save_session};
+use crate::core::string_store::StringStore;
+use crate::core::translation_sets::{load_translation_set, save_translation_set};
+use crate::models::bulk::{
+    BulkOperation, BulkResult, EntryFilter, EntryUpdate, FindReplaceRequest, FindReplaceResult, PostProcessRule,
+};
+use crate::models::session::TranslationSession;
+use crate::models::translation::{TranslationSet, TranslationStatus, WorkingTranslation};
+
+/// Runs `operation` on the entries matching `filter` and returns the updated set with a
+/// summary. Nothing is changed if the filter or operation is invalid. `project_path` is only
+/// needed to apply the glossary.
+#[tauri::command]
+pub async fn bulk_operation_command(
+    translations: Vec<WorkingTranslation>,
+    filter: EntryFilter,
+    operation: BulkOperation,
+    project_path: Option<String>,
+) -> Result<BulkResult, AppError> {
+    let glossary = match (&operation, &project_path) {
+        (BulkOperation::PostProcess { rule: PostProcessRule::ApplyGlossary }, Some(project_path)) => {
+            Some(load_project_glossary(project_path).map_err(AppError::from)?)
+        }
+        _ => None,
+    };
+    run_bulk_operation(translations, &filter, &operation, glossary.as_ref()).map_err(AppError::from)
+}
+
+/// Sets the review status of the entries matching `filter`, e.g. approving a whole file.
+/// Entries without a usable translation can't be approved, rejected or marked as edited. With
+/// `project_path`, the statuses are also set in the project's session (or its `target_language`
+/// translation set) and string store.
+#[tauri::command]
+pub async fn set_review_status_command(
+    translations: Vec<WorkingTranslation>,
+    filter: EntryFilter,
+    status: TranslationStatus,
+    project_path: Option<String>,
+    target_language: Option<String>,
+) -> Result<BulkResult, AppError> {
+    let operation = BulkOperation::SetReviewStatus { status };
+    if let Some(project_path) = &project_path {
+        let mut held = HeldTranslations::load(project_path, target_language.as_deref())?;
+        let updated = run_bulk_operation(std::mem::take(held.translations()), &filter, &operation, None)
+            .map_err(AppError::from)?;
+        *held.translations() = updated.translations;
+        held.save(project_path, None)?;
+    }
+    run_bulk_operation(translations, &filter, &operation, None).map_err(AppError::from)
+}
+
+/// Translations kept by the backend: the project's session or one of its translation sets.
+enum HeldTranslations {
+    Session(TranslationSession),
+    Set(TranslationSet),
+}
+
+impl HeldTranslations {
+    /// The session or, with `target_language`, the translation set for that language.
+    fn load(project_path: &str, target_language: Option<&str>) -> Result<Self, AppError> {
+        let held = match target_language {
+            Some(language) => load_translation_set(project_path, language).map_err(AppError::from)?.map(Self::Set),
+            None => load_session(project_path).map_err(AppError::from)?.map(Self::Session),
+        };
+        held.ok_or_else(|| {
+            let name = target_language.map_or("translation session".to_string(), |l| format!("'{}' translation set", l));
+            AppError::new(ErrorKind::NotFound, format!("No {} in {}", name, project_path))
+        })
+    }
+
+    fn translations(&mut self) -> &mut Vec<WorkingTranslation> {
+        match self {
+            Self::Session(session) => &mut session.translations,
+            Self::Set(set) => &mut set.translations,
+        }
+    }
+
+    /// Saves the translations, and `changed` of them (all by default) to the project's string
+    /// store when it holds their language. The store failing only logs a warning, since the
+    /// translations themselves are saved.
+    fn save(&self, project_path: &str, changed: Option<&[WorkingTranslation]>) -> Result<(), AppError> {
+        let (language, translations) = match self {
+            Self::Session(session) => {
+                save_session(project_path, session).map_err(AppError::from)?;
+                (&session.target_language, &session.translations)
+            }
+            Self::Set(set) => {
+                save_translation_set(project_path, set).map_err(AppError::from)?;
+                (&set.target_language, &set.translations)
+            }
+        };
+        let changed = changed.unwrap_or(translations);
+        let recorded = StringStore::open_for_project(project_path).and_then(|mut store| {
+            // Only the session may change the store's language; a translation set for another
+            // language isn't in it.
+            let is_session = matches!(self, Self::Session(_));
+            match is_session || store.target_language()?.as_ref() == Some(language) {
+                true => store.record_translations(language, changed).map(|_| ()),
+                false => Ok(()),
+            }
+        });
+        if let Err(e) = recorded {
+            eprintln!("Warning: failed to save the translations to the string store: {}", e);
+        }
+        Ok(())
+    }
+}
+
+/// Post-edits one entry of the project's session (or of its `target_language` translation set)
+/// in place and returns it, so the frontend doesn't have to send the whole set back.
+#[tauri::command]
+pub async fn update_translation_entry_command(
+    project_path: String,
+    target_language: Option<String>,
+    update: EntryUpdate,
+) -> Result<WorkingTranslation, AppError> {
+    let mut held = HeldTranslations::load(&project_path, target_language.as_deref())?;
+    let entry = update_entry(held.translations(), &update).map_err(AppError::from)?.ok_or_else(|| {
+        AppError::new(ErrorKind::NotFound, format!("No translation for {}", update.json_path))
+            .with_file(&update.source_file)
+    })?;
+    held.save(&project_path, Some(std::slice::from_ref(&entry)))?;
+    Ok(entry)
+}
+
+/// Regex find/replace across the translations of the project's session (or of its
+/// `target_language` translation set). Returns each change; with `request.dryRun` nothing is
+/// saved, so the changes can be previewed first.
+#[tauri::command]
+pub async fn bulk_find_replace_command(
+    project_path: String,
+    target_language: Option<String>,
+    request: FindReplaceRequest,
+) -> Result<FindReplaceResult, AppError> {
+    let mut held = HeldTranslations::load(&project_path, target_language.as_deref())?;
+    let (translations, mut result) =
+        find_replace(std::mem::take(held.translations()), &request).map_err(AppError::from)?;
+    if !request.dry_run && !result.changes.is_empty() {
+        *held.translations() = translations;
+        held.save(&project_path, None)?;
+        result.applied = true;
+    }
+    Ok(result)
+}
@@ -0,0 +1,137 @@
+// Commands backing the characters tab.
+
+use crate::error::{AppError, ErrorKind};
+use crate::core::catchphrases::{self, MIN_CATCHPHRASE_OCCURRENCES};
+use crate::core::config::load_project_config;
+use crate::core::control_codes::ControlCodes;
+use crate::core::presets::resolve_preset;
+use crate::core::references::{index_references, load_project_references};
+use crate::core::rpgmv::project::extract_translatable_strings_from_project;
+use crate::core::validation::load_project_ruleset;
+use crate::core::{characters, glossary};
+use crate::models::character::{Catchphrase, Catchphrases, CharacterProfile, CharacterSummary};
+use crate::models::extraction::ExtractionFilter;
+use crate::models::provider::ProviderConfig;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::services::batch_translation::{translate_entry, BatchContext};
+use crate::services::translation_provider::build_provider;
+
+/// Per-character line counts, appearances, name translation, style profile and sample lines.
+/// `translations` (the current working translations, if any) fill in translated names and samples.
+#[tauri::command]
+pub async fn get_character_dashboard_command(
+    project_path: String,
+    translations: Option<Vec<WorkingTranslation>>,
+    sample_count: Option<usize>,
+) -> Result<Vec<CharacterSummary>, AppError> {
+    let glossary = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    characters::character_dashboard(&project_path, &glossary, &translations.unwrap_or_default(), sample_count)
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn save_character_profile_command(
+    project_path: String,
+    profile: CharacterProfile,
+) -> Result<Vec<CharacterProfile>, AppError> {
+    characters::save_character_profile(&project_path, profile).map_err(AppError::from)
+}
+
+fn project_strings(project_path: &str) -> Result<Vec<SourceStringData>, AppError> {
+    extract_translatable_strings_from_project(project_path, &ExtractionFilter::default()).map_err(AppError::from)
+}
+
+fn detect_catchphrases(
+    project_path: &str,
+    entries: &[SourceStringData],
+    min_occurrences: Option<usize>,
+) -> Result<Catchphrases, AppError> {
+    let found = catchphrases::find_catchphrases(entries, min_occurrences.unwrap_or(MIN_CATCHPHRASE_OCCURRENCES));
+    let stored = catchphrases::load_catchphrases(project_path).map_err(AppError::from)?;
+    Ok(catchphrases::merge_catchphrases(found, &stored))
+}
+
+/// Lines each speaker says verbatim at least `min_occurrences` times (2 by default) across the
+/// project, with their stored translations.
+#[tauri::command]
+pub async fn find_catchphrases_command(
+    project_path: String,
+    min_occurrences: Option<usize>,
+) -> Result<Catchphrases, AppError> {
+    detect_catchphrases(&project_path, &project_strings(&project_path)?, min_occurrences)
+}
+
+/// Translates every detected catchphrase that isn't locked yet, once, telling the provider it is
+/// a catchphrase, and locks the results so batches give each occurrence the same translation.
+/// Catchphrases the provider failed on stay unlocked.
+#[tauri::command]
+pub async fn translate_catchphrases_command(
+    project_path: String,
+    provider: Option<ProviderConfig>,
+    min_occurrences: Option<usize>,
+) -> Result<Catchphrases, AppError> {
+    let config = load_project_config(&project_path).map_err(AppError::from)?;
+    let preset = resolve_preset(&config).map_err(AppError::from)?;
+    let entries = project_strings(&project_path)?;
+    let mut detected = detect_catchphrases(&project_path, &entries, min_occurrences)?;
+    let glossary = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    let references = index_references(load_project_references(&project_path).map_err(AppError::from)?);
+    let control_codes = ControlCodes::from_ruleset(&load_project_ruleset(&project_path).map_err(AppError::from)?);
+    let source_language = config.source_language.unwrap_or_else(|| "ja".to_string());
+    let target_language = config.target_language.unwrap_or_else(|| "en".to_string());
+    let provider = build_provider(provider.or(config.provider));
+
+    let prompts = detected.clone();
+    let batch = BatchContext {
+        source_language: &source_language,
+        target_language: &target_language,
+        glossary: &glossary,
+        references: &references,
+        include_references: true,
+        cancellation: None,
+        concurrency: 1,
+        unit_modes: None,
+        control_codes: Some(&control_codes),
+        prompt_templates: None,
+        dialogue_blocks: false,
+        surrounding_lines: None,
+        scope_summaries: None,
+        on_partial: None,
+        catchphrases: Some(&prompts),
+        skip_target_language: false,
+        preset: preset.as_ref(),
+        characters: Some(&config.characters),
+    };
+    let mut failed = 0;
+    for phrase in detected.catchphrases.iter_mut().filter(|c| !c.locked) {
+        let Some(entry) = entries
+            .iter()
+            .find(|e| e.speaker.as_deref() == Some(phrase.speaker.as_str()) && e.original_text == phrase.text)
+        else {
+            continue;
+        };
+        let translated = translate_entry(provider.as_ref(), entry.clone(), &batch).await;
+        match translated.error {
+            Some(e) => {
+                eprintln!("Failed to translate the catchphrase {:?} of {}: {}", phrase.text, phrase.speaker, e);
+                failed += 1;
+            }
+            None => {
+                phrase.translation = Some(translated.translated_text);
+                phrase.locked = true;
+            }
+        }
+    }
+    catchphrases::save_catchphrases(&project_path, &detected).map_err(AppError::from)?;
+    if failed > 0 && detected.catchphrases.iter().all(|c| !c.locked) {
+        return Err(AppError::new(ErrorKind::Provider, format!("Failed to translate the {} catchphrase(s)", failed)));
+    }
+    Ok(detected)
+}
+
+/// Stores a catchphrase translation written or corrected by hand; unlocking it lets batches
+/// translate its occurrences again.
+#[tauri::command]
+pub async fn save_catchphrase_command(project_path: String, catchphrase: Catchphrase) -> Result<Catchphrases, AppError> {
+    catchphrases::store_catchphrase(&project_path, catchphrase).map_err(AppError::from)
+}
@@ -0,0 +1,149 @@
+// Commands for exchanging translations with external tools.
+
+use std::path::Path;
+use crate::error::{AppError, ErrorKind};
+use crate::core::exchange::patch::{self, PatchApplication};
+use crate::core::exchange::{self, po, review_site, tmx, xliff};
+use crate::core::session::load_session;
+use crate::core::quality;
+use crate::core::translation_memory::TranslationMemory;
+use crate::models::exchange::{ReviewComment, ReviewCommentImport, TmxImportReport, TranslationImport};
+use crate::models::translation::WorkingTranslation;
+
+/// Writes the entries and their current translations to an XLIFF 2.0 file for CAT tools.
+#[tauri::command]
+pub async fn export_xliff_command(
+    translations: Vec<WorkingTranslation>,
+    source_language: String,
+    target_language: String,
+    output_path: String,
+) -> Result<(), AppError> {
+    xliff::export_xliff_file(&translations, &source_language, &target_language, Path::new(&output_path))
+        .map_err(AppError::from)
+}
+
+/// Writes the entries to a gettext PO file; machine translations are flagged fuzzy.
+#[tauri::command]
+pub async fn export_po_command(
+    translations: Vec<WorkingTranslation>,
+    source_language: String,
+    target_language: String,
+    output_path: String,
+) -> Result<(), AppError> {
+    po::export_po_file(&translations, &source_language, &target_language, Path::new(&output_path))
+        .map_err(AppError::from)
+}
+
+/// Reads an XLIFF, PO or CSV file and merges its translations into `translations`, matching
+/// entries by source file, JSON path and original text.
+#[tauri::command]
+pub async fn import_translations_command(
+    translations: Vec<WorkingTranslation>,
+    file_path: String,
+) -> Result<TranslationImport, AppError> {
+    let rows = exchange::read_import_file(Path::new(&file_path)).map_err(|e| AppError::from(e).with_file(&file_path))?;
+    Ok(exchange::merge_imports(translations, rows))
+}
+
+/// Writes the translated entries as a JSONL patch (see `core::exchange::patch` for the format).
+/// The quality gates of the project at `project_path` must pass unless `override_quality_gates`
+/// is set; without a project the gates can't be checked, so the override is required. Returns
+/// the number of lines written.
+#[tauri::command]
+pub async fn export_patch_command(
+    translations: Vec<WorkingTranslation>,
+    output_path: String,
+    project_path: Option<String>,
+    override_quality_gates: Option<bool>,
+) -> Result<usize, AppError> {
+    if !override_quality_gates.unwrap_or(false) {
+        let project_path = project_path.ok_or_else(|| {
+            AppError::new(
+                ErrorKind::QualityGate,
+                "Exporting a patch without a project requires overriding the quality gates".to_string(),
+            )
+        })?;
+        let report = quality::project_quality_gates(&project_path, &translations).map_err(AppError::from)?;
+        if !report.passed {
+            return Err(AppError::new(
+                ErrorKind::QualityGate,
+                format!("The translation fails the project's quality gates: {}", quality::describe_gate_failures(&report)),
+            ));
+        }
+    }
+    patch::export_patch_file(&translations, Path::new(&output_path)).map_err(AppError::from)
+}
+
+/// Applies a JSONL patch to the project at `project_path`, which can be any copy of the game the
+/// patch was made for: lines whose string is missing or changed are reported and skipped, the
+/// others written in place with a backup snapshot.
+#[tauri::command]
+pub async fn apply_patch_command(project_path: String, file_path: String) -> Result<PatchApplication, AppError> {
+    patch::apply_patch_file(&project_path, Path::new(&file_path)).map_err(|e| AppError::from(e).with_file(&file_path))
+}
+
+/// Writes the project translation memory to a TMX 1.4 file, optionally only one language pair.
+/// Returns the number of exported pairs.
+#[tauri::command]
+pub async fn export_tmx_command(
+    project_path: String,
+    output_path: String,
+    source_language: Option<String>,
+    target_language: Option<String>,
+) -> Result<usize, AppError> {
+    let memory = TranslationMemory::open_for_project(&project_path).map_err(AppError::from)?;
+    let language_pair = source_language.as_deref().zip(target_language.as_deref());
+    tmx::export_tmx_file(&memory, language_pair, Path::new(&output_path)).map_err(AppError::from)
+}
+
+/// Seeds the project translation memory from a TMX file. Existing pairs are kept unless `overwrite` is set.
+#[tauri::command]
+pub async fn import_tmx_command(
+    project_path: String,
+    file_path: String,
+    overwrite: Option<bool>,
+) -> Result<TmxImportReport, AppError> {
+    let memory = TranslationMemory::open_for_project(&project_path).map_err(AppError::from)?;
+    tmx::import_tmx_file(&memory, Path::new(&file_path), overwrite.unwrap_or(false))
+        .map_err(|e| AppError::from(e).with_file(&file_path))
+}
+
+/// Writes a static HTML review site for the project's saved session into `output_dir`: one page
+/// per file with original and translation side by side. Returns the number of file pages.
+#[tauri::command]
+pub async fn export_review_site_command(project_path: String, output_dir: String) -> Result<usize, AppError> {
+    let session = load_session(&project_path)
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::new(ErrorKind::NotFound, format!("No saved translation session in {}", project_path)))?;
+    review_site::export_review_site(
+        &project_path,
+        &session.translations,
+        &session.source_language,
+        &session.target_language,
+        Path::new(&output_dir),
+    )
+    .map_err(AppError::from)
+}
+
+/// Imports the comments JSON downloaded from the review site into the project's comments.
+#[tauri::command]
+pub async fn import_review_comments_command(
+    project_path: String,
+    file_path: String,
+) -> Result<ReviewCommentImport, AppError> {
+    let content = std::fs::read_to_string(&file_path).map_err(|e| AppError::from(e).with_file(&file_path))?;
+    let incoming = review_site::parse_review_comments(&content).map_err(|e| AppError::from(e).with_file(&file_path))?;
+    let translations = load_session(&project_path)
+        .map_err(AppError::from)?
+        .map(|session| session.translations)
+        .unwrap_or_default();
+    let stored = review_site::load_review_comments(&project_path).map_err(AppError::from)?;
+    let (stored, report) = review_site::merge_review_comments(stored, incoming, &translations);
+    review_site::save_review_comments(&project_path, &stored).map_err(AppError::from)?;
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_review_comments_command(project_path: String) -> Result<Vec<ReviewComment>, AppError> {
+    review_site::load_review_comments(&project_path).map_err(AppError::from)
+}
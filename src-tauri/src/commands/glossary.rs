@@ -0,0 +1,68 @@
+// Commands for managing the project glossary (fixed term translations).
+
+use std::path::Path;
+use crate::error::AppError;
+use crate::core::glossary;
+use crate::core::translation_memory::TranslationMemory;
+use crate::models::glossary::{CacheImpact, Glossary, GlossaryTerm};
+use crate::models::translation::WorkingTranslation;
+
+#[tauri::command]
+pub async fn get_project_glossary_command(project_path: String) -> Result<Glossary, AppError> {
+    glossary::load_project_glossary(&project_path).map_err(AppError::from)
+}
+
+/// Creates or overwrites the project glossary.
+#[tauri::command]
+pub async fn save_project_glossary_command(project_path: String, glossary: Glossary) -> Result<(), AppError> {
+    glossary::save_project_glossary(&project_path, &glossary).map_err(AppError::from)
+}
+
+/// Adds (or updates) terms in the project glossary and returns the result.
+#[tauri::command]
+pub async fn add_glossary_terms_command(project_path: String, terms: Vec<GlossaryTerm>) -> Result<Glossary, AppError> {
+    let mut current = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    glossary::merge_terms(&mut current, terms);
+    glossary::save_project_glossary(&project_path, &current).map_err(AppError::from)?;
+    Ok(current)
+}
+
+/// Imports a CSV (`source,target`) or TSV file. `replace` discards the current terms first.
+#[tauri::command]
+pub async fn import_glossary_command(
+    project_path: String,
+    file_path: String,
+    replace: Option<bool>,
+) -> Result<Glossary, AppError> {
+    glossary::import_glossary_file(&project_path, Path::new(&file_path), replace.unwrap_or(false))
+        .map_err(|e| AppError::from(e).with_file(&file_path))
+}
+
+/// Exports the project glossary as CSV, including reference notes and URLs.
+#[tauri::command]
+pub async fn export_glossary_command(project_path: String, output_path: String) -> Result<(), AppError> {
+    let current = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    glossary::export_glossary_file(&current, Path::new(&output_path)).map_err(AppError::from)
+}
+
+/// Applies the project glossary to existing translations, replacing terms left untranslated.
+#[tauri::command]
+pub async fn apply_glossary_command(
+    project_path: String,
+    translations: Vec<WorkingTranslation>,
+) -> Result<Vec<WorkingTranslation>, AppError> {
+    let current = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    let mut translations = translations;
+    for entry in translations.iter_mut() {
+        glossary::apply_glossary(entry, &current);
+    }
+    Ok(translations)
+}
+
+/// Cached provider responses that saving `glossary` would invalidate, compared with the current one.
+#[tauri::command]
+pub async fn preview_glossary_cache_impact_command(project_path: String, glossary: Glossary) -> Result<CacheImpact, AppError> {
+    let current = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    let memory = TranslationMemory::open_for_project(&project_path).map_err(AppError::from)?;
+    glossary::glossary_cache_impact(&memory, &current, &glossary).map_err(AppError::from)
+}
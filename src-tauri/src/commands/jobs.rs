@@ -0,0 +1,16 @@
+// Commands for controlling long-running jobs (batch translation, extraction).
+
+use tauri::State;
+use crate::error::AppError;
+use crate::services::job_manager::JobManager;
+
+/// Requests cancellation of a running job. Returns false if no job with that id is running.
+#[tauri::command]
+pub async fn cancel_job_command(jobs: State<'_, JobManager>, job_id: String) -> Result<bool, AppError> {
+    Ok(jobs.cancel(&job_id))
+}
+
+#[tauri::command]
+pub async fn get_running_jobs_command(jobs: State<'_, JobManager>) -> Result<Vec<String>, AppError> {
+    Ok(jobs.running_jobs())
+}
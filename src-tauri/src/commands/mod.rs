@@ -1,6 +1,21 @@
 pub mod project;
 pub mod translation;
+pub mod validation;
+pub mod session;
+pub mod glossary;
+pub mod references;
+pub mod characters;
+pub mod jobs;
+pub mod providers;
+pub mod exchange;
+pub mod bulk;
+pub mod workspaces;
+pub mod presets;
+pub mod prompts;
+pub mod queue;
+pub mod watch;
+pub mod search;
+pub mod strings;
 // Add other command modules here as they are created, e.g.:
-// pub mod glossary;
 // pub mod settings;
 // pub mod history; 
\ No newline at end of file
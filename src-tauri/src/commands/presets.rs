@@ -0,0 +1,17 @@
+// Commands for the translation presets (honorifics, SFX and punctuation per source language).
+
+use crate::error::AppError;
+use crate::core::presets;
+use crate::models::preset::TranslationPreset;
+
+/// The built-in presets, for the settings screen.
+#[tauri::command]
+pub async fn get_translation_presets_command() -> Result<Vec<TranslationPreset>, AppError> {
+    Ok(presets::builtin_presets())
+}
+
+/// The preset the project translates with, overrides applied; `null` when none applies.
+#[tauri::command]
+pub async fn get_project_preset_command(project_path: String) -> Result<Option<TranslationPreset>, AppError> {
+    presets::project_preset(&project_path).map_err(AppError::from)
+}
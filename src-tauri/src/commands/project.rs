@@ -1,19 +1,48 @@
 // This file will house commands related to project selection, detection, and processing.
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_dialog::{DialogExt, FilePath};
 use tauri_plugin_opener::OpenerExt;
 // walkdir, std::fs, std::path::Path are now primarily used in the core module
 
 // Import the detection result and function from the core module
+use crate::error::{AppError, ErrorKind};
 use crate::core::game_detection::{detect_rpg_maker_mv, RpgMakerDetectionResult};
-use std::collections::HashMap;
-use tokio::fs;
 use std::path::Path;
-use crate::models::translation::{SourceStringData, WorkingTranslation} ;
+use crate::models::translation::{MultiTargetLayout, SourceStringData, TranslationSet, WorkingTranslation};
+use crate::models::validation::{GateResult, QualityGateReport};
+use crate::core::bulk::entries_to_apply;
+use crate::core::rpgmv::backups::{self, BackupReport};
+use crate::core::rpgmv::inspect::{self, PathInspection};
+use crate::core::rpgmv::event_codes::{self, EventCodeReport};
+use crate::core::rpgmv::sample_project::{self, SampleProject};
+use crate::core::rpgmv::snapshot::{self, ExtractionResult};
+use crate::core::config::{self, load_project_config};
+use crate::core::postprocess::postprocess_translations;
+use crate::core::quality;
+use crate::core::translation_sets::{list_translation_sets, load_translation_set};
+use crate::models::archive::{ArchiveFormat, ZipContent, ZipSaveOptions};
+use crate::models::note_tag::NoteTagConfig;
+use crate::models::extraction::{ExtractionChunk, ExtractionFilter};
+use crate::models::project_config::ProjectConfig;
+use crate::core::rpgmv::schema::{self, SchemaReport};
+use crate::core::rpgmv::verification::{self, VerificationReport};
+use crate::core::rpgmv::{language_pack, locale_output, word_wrap};
+use crate::core::rpgmv::preview::{self, ReconstructionPreview};
+use crate::core::rpgmv::project::ReconstructedProject;
+use crate::core::rpgmv::patching::{self, BackupSummary, InPlaceReport, RestoreReport};
+use crate::core::rpgmv::warnings::{self, ReconstructionReport, ReconstructionWarning};
+use crate::services::job_manager::JobManager;
+use crate::services::packaging;
+use crate::services::temp_workspace::{TempWorkspaces, WorkspaceKind};
+use crate::services::zip_service::{self, ArchiveOptions, DEFAULT_ARCHIVE_TIMESTAMP};
 
+/// Returns the selected folder, its detection result and its project config (defaults when the
+/// project has none or it can't be read).
 #[tauri::command]
-pub async fn select_project_folder_command(app_handle: AppHandle) -> Result<Option<(String, RpgMakerDetectionResult)>, ()> {
+pub async fn select_project_folder_command(
+    app_handle: AppHandle,
+) -> Result<Option<(String, RpgMakerDetectionResult, ProjectConfig)>, AppError> {
     let folder_path_dialog: Option<FilePath> = app_handle
         .dialog()
         .file()
@@ -26,7 +55,11 @@ pub async fn select_project_folder_command(app_handle: AppHandle) -> Result<Opti
                     match path_buf.to_str() {
                         Some(path_str) => {
                             let detection_result = detect_rpg_maker_mv(path_str);
-                            Ok(Some((path_str.to_owned(), detection_result)))
+                            let config = load_project_config(path_str).unwrap_or_else(|e| {
+                                eprintln!("Using default project config: {}", e);
+                                ProjectConfig::default()
+                            });
+                            Ok(Some((path_str.to_owned(), detection_result, config)))
                         }
                         None => {
                             eprintln!("Selected path is not valid UTF-8");
@@ -45,173 +78,503 @@ pub async fn select_project_folder_command(app_handle: AppHandle) -> Result<Opti
 }
 
 #[tauri::command]
-pub async fn detect_rpg_maker_mv_project_command(project_path: String) -> Result<RpgMakerDetectionResult, String> {
-    // This command now simply calls the core detection logic.
-    // The error type `String` here is a placeholder if we wanted to add command-specific errors;
-    // for now, detect_rpg_maker_mv doesn't return a Result itself, so we just wrap its output.
+pub async fn detect_rpg_maker_mv_project_command(project_path: String) -> Result<RpgMakerDetectionResult, AppError> {
     Ok(detect_rpg_maker_mv(&project_path))
 }
 
+/// Engine (MV/MZ) and core version of the project, plus data fields the parsers don't know.
 #[tauri::command]
-pub async fn extract_project_strings_command(project_path: String) -> Result<Vec<SourceStringData>, String> {
+pub async fn check_project_schema_command(project_path: String) -> Result<SchemaReport, AppError> {
+    schema::check_project_schema(&project_path).map_err(AppError::from)
+}
+
+/// Writes a small sample MV game into an empty folder, for trying the app without a real game.
+#[tauri::command]
+pub async fn generate_sample_project_command(target_dir: String) -> Result<SampleProject, AppError> {
+    sample_project::generate_sample_project(&target_dir).map_err(|e| AppError::from(e).with_file(&target_dir))
+}
+
+/// Histogram of the event command codes in the project, marking which ones extraction handles and
+/// flagging frequent text-bearing codes it doesn't (e.g. plugin commands).
+#[tauri::command]
+pub async fn analyze_event_codes_command(project_path: String) -> Result<EventCodeReport, AppError> {
+    event_codes::analyze_event_codes(&project_path).map_err(AppError::from)
+}
+
+/// Compares the data folder with backup copies found in the project (`data_backup`, "Map001 - Copy.json",
+/// ...). `mixedVersions` is set when a backup differs, i.e. the folder holds several versions of a file.
+#[tauri::command]
+pub async fn compare_data_backups_command(project_path: String) -> Result<BackupReport, AppError> {
+    backups::compare_data_with_backups(&project_path).map_err(AppError::from)
+}
+
+/// The project's `translate-ai.json`, or the defaults if it has none.
+#[tauri::command]
+pub async fn get_project_config_command(project_path: String) -> Result<ProjectConfig, AppError> {
+    load_project_config(&project_path).map_err(AppError::from)
+}
+
+/// Writes `translate-ai.json` after checking its ignore globs and note tag patterns.
+#[tauri::command]
+pub async fn save_project_config_command(project_path: String, config: ProjectConfig) -> Result<(), AppError> {
+    config::save_project_config(&project_path, &config).map_err(AppError::from)
+}
+
+/// Note tags whose values are extracted instead of whole notes (empty = whole notes).
+#[tauri::command]
+pub async fn get_note_tag_config_command(project_path: String) -> Result<NoteTagConfig, AppError> {
+    load_project_config(&project_path).map(|c| c.note_tags).map_err(AppError::from)
+}
+
+/// Saves the note tag patterns into the project config after checking that each one compiles.
+#[tauri::command]
+pub async fn save_note_tag_config_command(project_path: String, config: NoteTagConfig) -> Result<(), AppError> {
+    config::update_project_config(&project_path, |project| project.note_tags = config)
+        .map(|_| ())
+        .map_err(AppError::from)
+}
+
+/// Emitted with an `ExtractionChunk` payload for each part of a streamed extraction.
+pub const EXTRACTION_CHUNK_EVENT: &str = "extraction://chunk";
+
+/// With a `job_id`, the extraction can be stopped via `cancel_job_command`; the strings
+/// extracted up to that point are returned. `filter` limits the files, file kinds and fields
+/// extracted so a project can be translated in stages. With `incremental`, only the strings
+/// new or changed since the previous extraction are returned, plus the removed ones.
+///
+/// With `chunk_size`, the entries are emitted as `extraction://chunk` events of that many
+/// entries instead of being returned, which keeps large projects out of a single IPC message;
+/// every chunk is sent before the command returns, with `streamedEntries` set.
+#[tauri::command]
+pub async fn extract_project_strings_command(
+    app_handle: AppHandle,
+    jobs: State<'_, JobManager>,
+    project_path: String,
+    job_id: Option<String>,
+    filter: Option<ExtractionFilter>,
+    incremental: Option<bool>,
+    chunk_size: Option<usize>,
+) -> Result<ExtractionResult, AppError> {
     // The command now delegates to the new core RPGMV project logic
-    crate::core::rpgmv::project::extract_translatable_strings_from_project(&project_path)
+    let job = job_id.as_deref().map(|id| jobs.start(id));
+    let should_stop = || job.as_ref().is_some_and(|job| job.token().is_cancelled());
+    let filter = filter.unwrap_or_default();
+    let entries = crate::core::rpgmv::project::extract_translatable_strings_until(&project_path, &filter, &should_stop)
+        .map_err(AppError::from)?;
+    let mut result =
+        snapshot::record_extraction(&project_path, entries, incremental.unwrap_or(false)).map_err(AppError::from)?;
+    if let Some(chunk_size) = chunk_size {
+        let entries = std::mem::take(&mut result.entries);
+        result.streamed_entries = entries.len();
+        emit_extraction_chunks(&app_handle, job_id, entries, chunk_size)?;
+    }
+    Ok(result)
 }
 
+/// Sends `entries` in chunks of `chunk_size` (at least one chunk, so listeners always see the
+/// last one).
+fn emit_extraction_chunks(
+    app_handle: &AppHandle,
+    job_id: Option<String>,
+    entries: Vec<SourceStringData>,
+    chunk_size: usize,
+) -> Result<(), AppError> {
+    let chunk_size = chunk_size.max(1);
+    let chunk_count = entries.len().div_ceil(chunk_size).max(1);
+    let mut entries = entries.into_iter();
+    for index in 0..chunk_count {
+        let chunk = ExtractionChunk {
+            job_id: job_id.clone(),
+            index,
+            chunk_count,
+            entries: entries.by_ref().take(chunk_size).collect(),
+        };
+        app_handle.emit(EXTRACTION_CHUNK_EVENT, chunk).map_err(|e| {
+            AppError::new(ErrorKind::Other, format!("Failed to send extracted strings: {}", e))
+        })?;
+    }
+    Ok(())
+}
+
+/// With `approved_only`, only translations marked approved in review are applied; the other
+/// entries keep their original text. The project's post-processing runs before reconstruction.
 #[tauri::command]
 pub async fn reconstruct_translated_project_files(
+    workspaces: State<'_, TempWorkspaces>,
     project_path: String,
     translated_entries: Vec<WorkingTranslation>,
-) -> Result<String, String> {
-    let mut grouped_translations: HashMap<String, Vec<&WorkingTranslation>> = HashMap::new();
-    for entry in &translated_entries {
-        grouped_translations.entry(entry.source_file.clone()).or_default().push(entry);
+    approved_only: Option<bool>,
+) -> Result<String, AppError> {
+    let translated_entries = entries_to_apply(translated_entries, approved_only.unwrap_or(false));
+    packaging::package_translated_project(&workspaces, &project_path, &translated_entries)
+}
+
+/// Post-processed, reconstructed and word-wrapped output of one language's translations, with
+/// its quality gates.
+pub(crate) fn reconstruct_language(
+    project_path: &str,
+    language: &str,
+    translations: &[WorkingTranslation],
+) -> Result<(ReconstructedProject, QualityGateReport), AppError> {
+    let translations = postprocess_translations(project_path, translations).map_err(AppError::from)?;
+    let ReconstructionReport { output, warnings: reconstruction_warnings } =
+        crate::core::rpgmv::project::reconstruct_project_files(project_path, &translations);
+    if !reconstruction_warnings.is_empty() {
+        eprintln!("Reconstruction skipped {} '{}' translation(s).", reconstruction_warnings.len(), language);
+    }
+    let output = word_wrap::apply_word_wrap(project_path, output).map_err(AppError::from)?;
+    let gates = quality::project_quality_gates(project_path, &translations).map_err(AppError::from)?;
+    Ok((output, gates))
+}
+
+/// Adds the gates of one language to the report of a package holding several, prefixed with the
+/// language ("fr.percentTranslated").
+fn add_language_gates(combined: &mut QualityGateReport, language: &str, gates: QualityGateReport) {
+    combined.passed &= gates.passed;
+    if let Some(e) = gates.error {
+        combined.error = Some(format!("{}: {}", language, e));
     }
+    let prefixed = gates.gates.into_iter().map(|g| GateResult { gate: format!("{}.{}", language, g.gate), ..g });
+    combined.gates.extend(prefixed);
+}
 
-    let mut all_reconstructed_content: HashMap<String, String> = HashMap::new();
-    let mut reconstruction_errors: Vec<String> = Vec::new();
-
-    for (relative_file_path, entries_for_file) in grouped_translations {
-        let original_file_full_path = Path::new(&project_path).join(&relative_file_path);
-
-        let original_content_bytes = match fs::read(&original_file_full_path).await {
-            Ok(bytes) => bytes,
-            Err(e) => {
-                let error_msg = format!(
-                    "Failed to read original file {}: {}",
-                    original_file_full_path.display(),
-                    e
-                );
-                eprintln!("{}", error_msg);
-                reconstruction_errors.push(error_msg);
-                continue; // Skip this file
-            }
-        };
-        
-        let original_content_str = String::from_utf8_lossy(&original_content_bytes).to_string();
-
-        match crate::core::rpgmv::project::reconstruct_file_content(
-            &original_content_str, 
-            &relative_file_path, 
-            entries_for_file
-        ) {
-            Ok(reconstructed_json_string) => {
-                all_reconstructed_content.insert(relative_file_path.clone(), reconstructed_json_string);
+/// Zips `output` as `file_name` in a new output workspace, with its quality gate report for
+/// `save_zip_archive_command`. Returns the ZIP's path.
+fn package_output(
+    workspaces: &TempWorkspaces,
+    file_name: &str,
+    output: &ReconstructedProject,
+    gates: &QualityGateReport,
+    archive_options: &ArchiveOptions,
+) -> Result<String, AppError> {
+    let workspace = workspaces.create(WorkspaceKind::Output).map_err(AppError::from)?;
+    let zip_path = workspace.path().join(file_name);
+    crate::services::zip_service::create_zip_archive_with_binary_files(
+        &output.files,
+        &output.binary_files,
+        &zip_path,
+        archive_options,
+    )
+    .map_err(|e| AppError::from(e).with_file(zip_path.to_string_lossy()))?;
+    quality::save_gate_report(workspace.path(), gates).map_err(AppError::from)?;
+    workspace.keep();
+    Ok(zip_path.to_string_lossy().into_owned())
+}
+
+/// Packages the project's translation sets (see `batch_translate_multi_target_command`) for
+/// `target_languages`, all of them when absent. With the `zipPerLanguage` layout (default), each
+/// language gets its own ZIP (`translated_<language>.zip`); with `dataFolders`, one ZIP holds
+/// the data files of every language under `data_<language>/`. Returns the ZIPs, to be saved with
+/// `save_zip_archive_command`, which checks the quality gates of every language it holds.
+#[tauri::command]
+pub async fn reconstruct_multi_target_command(
+    workspaces: State<'_, TempWorkspaces>,
+    project_path: String,
+    target_languages: Option<Vec<String>>,
+    layout: Option<MultiTargetLayout>,
+    approved_only: Option<bool>,
+) -> Result<Vec<String>, AppError> {
+    let target_languages = match target_languages {
+        Some(languages) => languages,
+        None => list_translation_sets(&project_path).map_err(AppError::from)?,
+    };
+    if target_languages.is_empty() {
+        return Err(AppError::new(ErrorKind::NotFound, format!("No translation sets in {}", project_path)));
+    }
+    let config = load_project_config(&project_path).map_err(AppError::from)?;
+    let archive_options =
+        ArchiveOptions { timestamp: config.output_timestamp.unwrap_or(DEFAULT_ARCHIVE_TIMESTAMP), ..Default::default() };
+    let layout = layout.unwrap_or_default();
+
+    let mut outputs = Vec::new();
+    let mut combined = ReconstructedProject::default();
+    let mut combined_gates = QualityGateReport { passed: true, ..Default::default() };
+    for language in &target_languages {
+        let set = load_translation_set(&project_path, language)
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::new(ErrorKind::NotFound, format!("No '{}' translation set in {}", language, project_path)))?;
+        let translations = entries_to_apply(set.translations, approved_only.unwrap_or(false));
+        let (output, gates) = reconstruct_language(&project_path, language, &translations)?;
+        match layout {
+            MultiTargetLayout::ZipPerLanguage => {
+                for error_msg in &output.errors {
+                    eprintln!("[{}] {}", language, error_msg);
+                }
+                let file_name = format!("translated_{}.zip", language);
+                outputs.push(package_output(&workspaces, &file_name, &output, &gates, &archive_options)?);
             }
-            Err(core_error) => {
-                let error_msg = format!(
-                    "Error reconstructing file {}: {}", 
-                    relative_file_path, 
-                    core_error.to_string()
-                );
-                eprintln!("{}", error_msg);
-                reconstruction_errors.push(error_msg);
-                // Continue to attempt other files
+            MultiTargetLayout::DataFolders => {
+                let output = locale_output::apply_data_folder(&project_path, language, output).map_err(AppError::from)?;
+                combined.files.extend(output.files);
+                combined.binary_files.extend(output.binary_files);
+                combined.errors.extend(output.errors.into_iter().map(|e| format!("[{}] {}", language, e)));
+                add_language_gates(&mut combined_gates, language, gates);
             }
         }
     }
 
-    if !reconstruction_errors.is_empty() && all_reconstructed_content.is_empty() {
-        // All files failed reconstruction or reading
-        return Err(format!("All file processing failed. Errors: {}", reconstruction_errors.join("; ")));
-    }
-    
-    if all_reconstructed_content.is_empty() && !translated_entries.is_empty() {
-        return Err("No files were successfully reconstructed, though translated entries were provided.".to_string());
+    if layout == MultiTargetLayout::DataFolders {
+        for error_msg in &combined.errors {
+            eprintln!("{}", error_msg);
+        }
+        let file_name = "translated_project_languages.zip";
+        outputs.push(package_output(&workspaces, file_name, &combined, &combined_gates, &archive_options)?);
     }
-    if all_reconstructed_content.is_empty() && translated_entries.is_empty() {
-        // This is not an error, just means nothing to zip.
-        // However, the frontend should ideally not call this if there are no entries.
-        // For now, let's return an empty path or a specific signal if we decide so.
-        // Or, let the zip creation handle an empty map (it should create an empty zip).
-        println!("No reconstructed content to package into ZIP.");
-        // To create an empty zip, we'd still proceed. If an error is preferred:
-        // return Err("No content to package.".to_string()); 
+    Ok(outputs)
+}
+
+/// Language pack output mode for MV/MZ games: instead of replacing the data files, packages one
+/// pack per language of `sets` (the project's translation sets when absent) with the
+/// values its translation changes, and a plugin that swaps them in at runtime so players pick
+/// the language in the Options menu. `approved_only` works as for
+/// `reconstruct_translated_project_files`. Returns the ZIP, to be saved with
+/// `save_zip_archive_command`.
+#[tauri::command]
+pub async fn generate_language_pack_command(
+    workspaces: State<'_, TempWorkspaces>,
+    project_path: String,
+    sets: Option<Vec<TranslationSet>>,
+    approved_only: Option<bool>,
+) -> Result<String, AppError> {
+    let sets = match sets {
+        Some(sets) => sets,
+        None => list_translation_sets(&project_path)
+            .map_err(AppError::from)?
+            .iter()
+            .filter_map(|language| load_translation_set(&project_path, language).transpose())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(AppError::from)?,
+    };
+    if sets.is_empty() {
+        return Err(AppError::new(ErrorKind::NotFound, format!("No translation sets in {}", project_path)));
     }
+    let config = load_project_config(&project_path).map_err(AppError::from)?;
+    let archive_options =
+        ArchiveOptions { timestamp: config.output_timestamp.unwrap_or(DEFAULT_ARCHIVE_TIMESTAMP), ..Default::default() };
 
-    // Define output path for the ZIP file (temporary for now)
-    let target_dir = std::env::temp_dir();
-    let output_zip_file_path = target_dir.join("translated_project_output.zip");
-
-    match crate::services::zip_service::create_zip_archive_from_memory(&all_reconstructed_content, &output_zip_file_path) {
-        Ok(_) => {
-            if !reconstruction_errors.is_empty() {
-                // Partial success: ZIP created, but some files had errors
-                // The frontend should be notified of these errors separately.
-                // For now, returning the ZIP path but logging errors.
-                eprintln!("ZIP created with some reconstruction errors: {}", reconstruction_errors.join("; "));
-            }
-            Ok(output_zip_file_path.to_string_lossy().into_owned())
+    let mut packs = Vec::new();
+    let mut gates = QualityGateReport { passed: true, ..Default::default() };
+    for set in sets {
+        let translations = entries_to_apply(set.translations, approved_only.unwrap_or(false));
+        let (output, language_gates) = reconstruct_language(&project_path, &set.target_language, &translations)?;
+        let (pack, errors) = language_pack::build_language_pack(&project_path, &output).map_err(AppError::from)?;
+        for error_msg in output.errors.iter().chain(&errors) {
+            eprintln!("[{}] {}", set.target_language, error_msg);
         }
-        Err(e) => Err(format!("Failed to create ZIP archive: {}", e.to_string())),
+        add_language_gates(&mut gates, &set.target_language, language_gates);
+        packs.push((set.target_language, pack));
     }
+    let output = language_pack::language_pack_output(&project_path, &packs).map_err(AppError::from)?;
+    package_output(&workspaces, "translated_language_packs.zip", &output, &gates, &archive_options)
 }
 
+/// Writes the translations of a Ren'Py project as `translate` blocks under
+/// `game/tl/<language>/` (e.g. "french"). Returns the written files.
 #[tauri::command]
-pub async fn save_zip_archive_command(app_handle: AppHandle, temp_zip_path: String) -> Result<Option<String>, String> {
+pub async fn generate_renpy_translations_command(
+    project_path: String,
+    language: String,
+    translated_entries: Vec<WorkingTranslation>,
+) -> Result<Vec<String>, AppError> {
+    crate::core::renpy::project::write_translation_files(&project_path, &language, &translated_entries)
+        .map_err(AppError::from)
+}
+
+/// Writes the reconstructed files straight into the project instead of a ZIP, after backing up
+/// every file it touches to a snapshot that can be rolled back. `approved_only` works as for
+/// `reconstruct_translated_project_files`.
+#[tauri::command]
+pub async fn write_translations_in_place_command(
+    project_path: String,
+    translated_entries: Vec<WorkingTranslation>,
+    approved_only: Option<bool>,
+) -> Result<InPlaceReport, AppError> {
+    let translated_entries = entries_to_apply(translated_entries, approved_only.unwrap_or(false));
+    let report = patching::write_translations_in_place(&project_path, &translated_entries).map_err(AppError::from)?;
+    if let Err(e) = warnings::save_warnings(&project_path, &report.warnings) {
+        eprintln!("Failed to save reconstruction warnings: {}", e);
+    }
+    for error_msg in &report.errors {
+        eprintln!("{}", error_msg);
+    }
+    Ok(report)
+}
+
+/// In-place patch snapshots of the project, newest first, with any file failing its hash check.
+#[tauri::command]
+pub async fn list_backups_command(project_path: String) -> Result<Vec<BackupSummary>, AppError> {
+    patching::list_backups(&project_path).map_err(AppError::from)
+}
+
+/// Rolls the project back to before the patch `backup_id`; a damaged snapshot is refused.
+#[tauri::command]
+pub async fn restore_backup_command(project_path: String, backup_id: String) -> Result<RestoreReport, AppError> {
+    patching::restore_backup(&project_path, &backup_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn verify_reconstruction_command(
+    project_path: String,
+    translated_entries: Vec<WorkingTranslation>,
+) -> Result<VerificationReport, AppError> {
+    let translated_entries = postprocess_translations(&project_path, &translated_entries).map_err(AppError::from)?;
+    let ReconstructionReport { output: reconstructed, warnings } =
+        crate::core::rpgmv::project::reconstruct_project_files(&project_path, &translated_entries);
+    let mut report = verification::verify_against_project(&project_path, &reconstructed.files, &translated_entries);
+    report.errors.extend(reconstructed.errors);
+    report.warnings = warnings;
+    report.passed = report.passed && report.errors.is_empty();
+    Ok(report)
+}
+
+/// Dry run of `reconstruct_translated_project_files`: reconstructs in memory and returns a
+/// unified diff per changed file. Nothing is written to the project or a ZIP.
+#[tauri::command]
+pub async fn preview_reconstruction_command(
+    project_path: String,
+    translated_entries: Vec<WorkingTranslation>,
+) -> Result<ReconstructionPreview, AppError> {
+    Ok(preview::preview_reconstruction(&project_path, &translated_entries))
+}
+
+/// Translations the last reconstruction had to skip, with the file and path of each.
+#[tauri::command]
+pub async fn get_reconstruction_warnings_command(project_path: String) -> Result<Vec<ReconstructionWarning>, AppError> {
+    warnings::load_warnings(&project_path).map_err(AppError::from)
+}
+
+/// Read-only view of the value at `json_path` in a project data file (e.g. "www/data/Map001.json"),
+/// with the session's translation for it. Used to debug paths reported by the verification.
+#[tauri::command]
+pub async fn get_value_at_path_command(
+    project_path: String,
+    file: String,
+    json_path: String,
+) -> Result<PathInspection, AppError> {
+    inspect::inspect_path(&project_path, &file, &json_path)
+        .map_err(|e| AppError::from(e).with_file(&file).with_path(&json_path))
+}
+
+/// Refuses archives whose translation failed the project's quality gates when it was packaged,
+/// unless `override_quality_gates` is set, and never replaces an existing file (see
+/// `services::packaging`). `options` can add the untranslated files of the project's data folder,
+/// or the whole project for a playable copy, set the compression level and save it as `.tar.gz`
+/// or `.7z` instead; entries stay in path order. The staged ZIP is left as it is until the
+/// archive is saved, so cancelling the dialog or a failed save can be retried.
+#[tauri::command]
+pub async fn save_zip_archive_command(
+    app_handle: AppHandle,
+    workspaces: State<'_, TempWorkspaces>,
+    temp_zip_path: String,
+    override_quality_gates: Option<bool>,
+    options: Option<ZipSaveOptions>,
+) -> Result<Option<String>, AppError> {
     let temp_path = Path::new(&temp_zip_path);
-    let file_name = temp_path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("translated.zip"));
+    packaging::check_packaged_gates(&workspaces, temp_path, override_quality_gates.unwrap_or(false))?;
+    let options = options.unwrap_or_default();
+    // Checked before the dialog so the user isn't asked where to save an archive that can't be written.
+    if options.format == ArchiveFormat::SevenZip && zip_service::seven_zip_program().is_none() {
+        return Err(AppError::from(zip_service::seven_zip_missing()));
+    }
+    if options.content != ZipContent::ModifiedOnly && options.project_path.is_none() {
+        return Err(AppError::new(ErrorKind::Config, "A project path is needed to add its files to the ZIP"));
+    }
+    let converted_name = temp_path.with_extension(options.format.extension());
+    let file_name = converted_name.file_name().unwrap_or_else(|| std::ffi::OsStr::new("translated.zip"));
 
+    let (filter_name, extension) = match options.format {
+        ArchiveFormat::Zip => ("ZIP Archive", "zip"),
+        ArchiveFormat::TarGz => ("Gzipped Tar Archive", "gz"),
+        ArchiveFormat::SevenZip => ("7z Archive", "7z"),
+    };
     let dialog_result = app_handle
         .dialog()
         .file()
-        .add_filter("ZIP Archive", &["zip"])
+        .add_filter(filter_name, &[extension])
         .set_file_name(file_name.to_string_lossy().as_ref())
         .blocking_save_file();
+    let target_path_buf = match dialog_result {
+        Some(FilePath::Path(target_path_buf)) => target_path_buf,
+        Some(_) => {
+            eprintln!("Unexpected FilePath variant from save dialog on desktop.");
+            return Err(AppError::from("Save dialog returned an unexpected path format.".to_string()));
+        }
+        None => return Ok(None), // User cancelled the save dialog
+    };
 
-    match dialog_result {
-        Some(target_file_path_wrapper) => {
-            match target_file_path_wrapper {
-                FilePath::Path(target_path_buf) => {
-                    match std::fs::rename(&temp_zip_path, &target_path_buf) {
-                        Ok(_) => Ok(Some(target_path_buf.to_string_lossy().into_owned())),
-                        Err(e) => {
-                            // Attempt copy and delete if rename fails (e.g., across filesystems)
-                            match std::fs::copy(&temp_zip_path, &target_path_buf) {
-                                Ok(_) => {
-                                    if let Err(del_err) = std::fs::remove_file(&temp_zip_path) {
-                                        eprintln!("Failed to delete temporary ZIP after copy: {}", del_err);
-                                        // Still return success as copy worked
-                                        Ok(Some(target_path_buf.to_string_lossy().into_owned()))
-                                    } else {
-                                        Ok(Some(target_path_buf.to_string_lossy().into_owned()))
-                                    }
-                                }
-                                Err(copy_err) => {
-                                    let err_msg = format!("Failed to move/copy ZIP file from {} to {:?}: Rename error: {}, Copy error: {}", temp_zip_path, target_path_buf, e, copy_err);
-                                    eprintln!("{}", err_msg);
-                                    Err(err_msg)
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => {
-                    eprintln!("Unexpected FilePath variant from save dialog on desktop.");
-                    Err("Save dialog returned an unexpected path format.".to_string())
-                }
-            }
+    // Any other variant is written to a new file; the staged ZIP is only read.
+    let recompress = options.compression_level.is_some() && options.format == ArchiveFormat::Zip;
+    let as_staged = options.content == ZipContent::ModifiedOnly && !recompress && options.format == ArchiveFormat::Zip;
+    let variant = if as_staged {
+        None
+    } else {
+        let staging = workspaces.create(WorkspaceKind::Staging).map_err(AppError::from)?;
+        let variant_path = staging.path().join(file_name);
+        write_archive_variant(&workspaces, temp_path, &variant_path, &options)?;
+        Some((staging, variant_path))
+    };
+    let source_path = variant.as_ref().map_or(temp_path, |(_, path)| path.as_path());
+    packaging::move_packaged_archive(&workspaces, temp_path, source_path, &target_path_buf)?;
+    Ok(Some(target_path_buf.to_string_lossy().into_owned()))
+}
+
+/// Writes the archive `options` ask for at `output_path`: the staged ZIP's files, plus the
+/// project's files it doesn't hold, which are streamed from disk.
+fn write_archive_variant(
+    workspaces: &TempWorkspaces,
+    staged_zip: &Path,
+    output_path: &Path,
+    options: &ZipSaveOptions,
+) -> Result<(), AppError> {
+    let timestamp = match &options.project_path {
+        Some(project_path) => load_project_config(project_path).map_err(AppError::from)?.output_timestamp,
+        None => None,
+    };
+    let archive_options = ArchiveOptions {
+        timestamp: timestamp.unwrap_or(DEFAULT_ARCHIVE_TIMESTAMP),
+        compression_level: options.compression_level,
+    };
+    let mut files = match (options.content, &options.project_path) {
+        (ZipContent::WholeData, Some(project_path)) => {
+            let data_dir = schema::detect_schema(project_path).map_or("www/data".to_string(), |s| s.data_dir);
+            zip_service::project_files(project_path, &data_dir).map_err(AppError::from)?
         }
-        None => Ok(None), // User cancelled the save dialog
-    }
+        (ZipContent::FullCopy, Some(project_path)) => {
+            zip_service::project_files(project_path, "").map_err(AppError::from)?
+        }
+        _ => zip_service::ArchiveFiles::new(),
+    };
+    // The staged version of a file wins.
+    let staged = zip_service::read_zip_archive(staged_zip)
+        .map_err(|e| AppError::from(e).with_file(staged_zip.to_string_lossy()))?;
+    files.extend(staged);
+    let written = match options.format {
+        ArchiveFormat::Zip => zip_service::write_zip_archive(&files, output_path, &archive_options),
+        ArchiveFormat::TarGz => zip_service::create_tar_gz_archive(&files, output_path, &archive_options),
+        ArchiveFormat::SevenZip => {
+            let staging = workspaces.create(WorkspaceKind::Staging).map_err(AppError::from)?;
+            zip_service::create_7z_archive(&files, output_path, staging.path(), &archive_options)
+        }
+    };
+    written.map_err(|e| AppError::from(e).with_file(output_path.to_string_lossy()))
+}
+
+/// Formats `save_zip_archive_command` can write here: ZIP and tar.gz always, 7z when the 7-Zip
+/// command line tool is installed.
+#[tauri::command]
+pub async fn get_archive_formats_command() -> Result<Vec<ArchiveFormat>, AppError> {
+    Ok(zip_service::available_archive_formats())
 }
 
 #[tauri::command]
-pub async fn open_folder_command(app_handle: AppHandle, folder_path: String) -> Result<(), String> {
+pub async fn open_folder_command(app_handle: AppHandle, folder_path: String) -> Result<(), AppError> {
     // Ensure opener is available (it should be if plugin is registered)
     match app_handle.opener().open_path(folder_path.clone(), None::<&str>) {
         Ok(_) => Ok(()),
         Err(e) => {
             let err_msg = format!("Failed to open folder {}: {}", folder_path, e.to_string());
             eprintln!("{}", err_msg);
-            Err(err_msg)
+            Err(AppError::new(ErrorKind::Io, err_msg).with_file(&folder_path))
         }
     }
-}
-
-// Example (to be implemented in Task 1.2 of Phase 3):
-// #[tauri::command]
-// async fn select_project_folder_command() -> Result<Option<String>, String> {
-//    // ... implementation using tauri_plugin_dialog ...
-//    Ok(None)
-// } 
\ No newline at end of file
+} 
@@ -0,0 +1,105 @@
+// Commands for editing the project's prompt templates and scene summaries.
+
+use crate::error::{AppError, ErrorKind};
+use crate::core::config::load_project_config;
+use crate::core::prompts;
+use crate::core::rpgmv::project::extract_translatable_strings_from_project;
+use crate::core::scope_summaries;
+use crate::models::extraction::ExtractionFilter;
+use crate::models::prompt::{PromptKind, PromptTemplate, ScopeSummaries, ScopeSummary};
+use crate::models::provider::ProviderConfig;
+use crate::services::translation_provider::build_provider;
+
+/// The template in effect for every kind of content.
+#[tauri::command]
+pub async fn list_prompt_templates_command(project_path: String) -> Result<Vec<PromptTemplate>, AppError> {
+    let templates = prompts::load_prompt_templates(&project_path).map_err(AppError::from)?;
+    Ok(prompts::list_templates(&templates))
+}
+
+#[tauri::command]
+pub async fn get_prompt_template_command(project_path: String, kind: PromptKind) -> Result<PromptTemplate, AppError> {
+    let templates = prompts::load_prompt_templates(&project_path).map_err(AppError::from)?;
+    Ok(PromptTemplate {
+        kind,
+        template: prompts::template_for(&templates, kind),
+        customized: templates.templates.contains_key(&kind),
+    })
+}
+
+/// Creates or replaces the project's template for `kind`. Unknown variables and templates
+/// without `{text}` are refused.
+#[tauri::command]
+pub async fn save_prompt_template_command(
+    project_path: String,
+    kind: PromptKind,
+    template: String,
+) -> Result<PromptTemplate, AppError> {
+    let mut templates = prompts::load_prompt_templates(&project_path).map_err(AppError::from)?;
+    templates.templates.insert(kind, template.clone());
+    prompts::save_prompt_templates(&project_path, &templates).map_err(AppError::from)?;
+    Ok(PromptTemplate { kind, template, customized: true })
+}
+
+/// Deletes the project's template for `kind`; returns the built-in template now in effect.
+#[tauri::command]
+pub async fn delete_prompt_template_command(project_path: String, kind: PromptKind) -> Result<PromptTemplate, AppError> {
+    let mut templates = prompts::load_prompt_templates(&project_path).map_err(AppError::from)?;
+    if templates.templates.remove(&kind).is_some() {
+        prompts::save_prompt_templates(&project_path, &templates).map_err(AppError::from)?;
+    }
+    Ok(PromptTemplate { kind, template: prompts::builtin_template(kind), customized: false })
+}
+
+/// Asks the language model (by default the project's provider) for a synopsis of a map or scene,
+/// or of the entries under `path_prefix` in it (e.g. one event), and stores it so later prompts
+/// for that scope include it.
+#[tauri::command]
+pub async fn summarize_scope_command(
+    project_path: String,
+    source_file: String,
+    path_prefix: Option<String>,
+    provider: Option<ProviderConfig>,
+) -> Result<ScopeSummary, AppError> {
+    let config = load_project_config(&project_path).map_err(AppError::from)?;
+    let filter = ExtractionFilter { include_files: vec![source_file.clone()], ..Default::default() };
+    let entries = extract_translatable_strings_from_project(&project_path, &filter)
+        .map_err(|e| AppError::from(e).with_file(&source_file))?;
+    let scope = scope_summaries::scope_entries(&entries, &source_file, path_prefix.as_deref());
+    if scope.is_empty() {
+        return Err(AppError::new(ErrorKind::NotFound, "No text to summarize in this scope").with_file(&source_file));
+    }
+
+    let provider = build_provider(provider.or(config.provider));
+    let source_language = config.source_language.unwrap_or_else(|| "ja".to_string());
+    let summary = provider
+        .complete(&scope_summaries::summary_prompt(&scope, &source_language))
+        .await
+        .map_err(|e| AppError::new(ErrorKind::Provider, e))?;
+    let summary = ScopeSummary { source_file, path_prefix, summary, provider: provider.name().to_string() };
+    scope_summaries::store_scope_summary(&project_path, summary.clone()).map_err(AppError::from)?;
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn list_scope_summaries_command(project_path: String) -> Result<ScopeSummaries, AppError> {
+    scope_summaries::load_scope_summaries(&project_path).map_err(AppError::from)
+}
+
+/// Stores a summary written or corrected by hand, replacing the one of the same scope.
+#[tauri::command]
+pub async fn save_scope_summary_command(project_path: String, summary: ScopeSummary) -> Result<ScopeSummaries, AppError> {
+    scope_summaries::store_scope_summary(&project_path, summary).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn delete_scope_summary_command(
+    project_path: String,
+    source_file: String,
+    path_prefix: Option<String>,
+) -> Result<ScopeSummaries, AppError> {
+    let mut summaries = scope_summaries::load_scope_summaries(&project_path).map_err(AppError::from)?;
+    summaries.summaries.retain(|s| s.source_file != source_file || s.path_prefix != path_prefix);
+    scope_summaries::save_scope_summaries(&project_path, &summaries).map_err(AppError::from)?;
+    Ok(summaries)
+}
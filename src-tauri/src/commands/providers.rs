@@ -0,0 +1,108 @@
+// Commands for keeping the translation provider ready while a project is open.
+
+use std::time::Duration;
+use tauri::State;
+use crate::core::config::{load_project_config, save_project_config};
+use crate::core::cost::estimate_cost;
+use crate::core::glossary::load_project_glossary;
+use crate::error::{AppError, ErrorKind};
+use crate::models::glossary::Glossary;
+use crate::models::provider::{CostEstimate, ProviderConfig, ProviderDiagnostic, WarmupStatus};
+use crate::models::translation::SourceStringData;
+use crate::services::deepl::create_glossary;
+use crate::services::provider_health::check_provider;
+use crate::services::provider_warmup::{ProviderWarmup, DEFAULT_WARMUP_INTERVAL_SECS};
+use crate::services::translation_provider::build_provider;
+
+// Shorter intervals only add load on the local server.
+const MIN_WARMUP_INTERVAL_SECS: u64 = 30;
+
+/// Starts (or restarts with new settings) periodic warm-up pings for the given provider.
+#[tauri::command]
+pub async fn start_provider_warmup_command(
+    warmup: State<'_, ProviderWarmup>,
+    provider: Option<ProviderConfig>,
+    interval_secs: Option<u64>,
+) -> Result<WarmupStatus, AppError> {
+    let interval_secs = interval_secs.unwrap_or(DEFAULT_WARMUP_INTERVAL_SECS).max(MIN_WARMUP_INTERVAL_SECS);
+    warmup.start(build_provider(provider), Duration::from_secs(interval_secs));
+    Ok(warmup.status())
+}
+
+/// Called when the project is closed or warm-up is disabled in the settings.
+#[tauri::command]
+pub async fn stop_provider_warmup_command(warmup: State<'_, ProviderWarmup>) -> Result<bool, AppError> {
+    Ok(warmup.stop())
+}
+
+#[tauri::command]
+pub async fn get_provider_warmup_status_command(warmup: State<'_, ProviderWarmup>) -> Result<WarmupStatus, AppError> {
+    Ok(warmup.status())
+}
+
+/// Checks the provider (by default the project's) before a long batch: whether it answers, how
+/// fast, whether its model is installed, and whether a sample line translates.
+#[tauri::command]
+pub async fn check_provider_connection_command(
+    provider: Option<ProviderConfig>,
+    project_path: Option<String>,
+    source_language: Option<String>,
+    target_language: Option<String>,
+) -> Result<ProviderDiagnostic, AppError> {
+    let config = match &project_path {
+        Some(project_path) => load_project_config(project_path).map_err(AppError::from)?,
+        None => Default::default(),
+    };
+    let provider = build_provider(provider.or(config.provider));
+    let source_language = source_language.or(config.source_language).unwrap_or_else(|| "ja".to_string());
+    let target_language = target_language.or(config.target_language).unwrap_or_else(|| "en".to_string());
+    Ok(check_provider(provider.as_ref(), &source_language, &target_language).await)
+}
+
+/// Estimates requests, tokens and, for paid APIs, the price of translating `entries` with each
+/// of `providers` (by default the project's), so a large batch doesn't come with a surprise bill.
+#[tauri::command]
+pub async fn estimate_translation_cost_command(
+    entries: Vec<SourceStringData>,
+    providers: Option<Vec<ProviderConfig>>,
+    project_path: Option<String>,
+) -> Result<Vec<CostEstimate>, AppError> {
+    let (config, glossary) = match &project_path {
+        Some(project_path) => (
+            load_project_config(project_path).map_err(AppError::from)?,
+            load_project_glossary(project_path).map_err(AppError::from)?,
+        ),
+        None => (Default::default(), Glossary::default()),
+    };
+    let providers = providers.unwrap_or_else(|| vec![config.provider.unwrap_or_default()]);
+    Ok(providers.iter().map(|provider| estimate_cost(&entries, provider, &glossary)).collect())
+}
+
+/// Creates a glossary on the DeepL account from the project glossary and stores its id in the
+/// project's DeepL settings, so DeepL applies the terms itself. Returns the glossary id. The
+/// languages default to the project's; a glossary created earlier is left on the account.
+#[tauri::command]
+pub async fn create_deepl_glossary_command(
+    project_path: String,
+    source_language: Option<String>,
+    target_language: Option<String>,
+) -> Result<String, AppError> {
+    let mut config = load_project_config(&project_path).map_err(AppError::from)?;
+    let Some(ProviderConfig::Deepl(settings)) = &mut config.provider else {
+        return Err(AppError::new(ErrorKind::Config, "The project's translation provider is not DeepL"));
+    };
+    let glossary = load_project_glossary(&project_path).map_err(AppError::from)?;
+    let entries: Vec<(String, String)> = glossary.terms.into_iter().map(|term| (term.source, term.target)).collect();
+    if entries.is_empty() {
+        return Err(AppError::new(ErrorKind::Config, "The project glossary has no terms"));
+    }
+    let source_language = source_language.or(config.source_language.clone()).unwrap_or_else(|| "ja".to_string());
+    let target_language = target_language.or(config.target_language.clone()).unwrap_or_else(|| "en".to_string());
+    let name = format!("Translate-AI {}", project_path);
+    let glossary_id = create_glossary(settings, &name, &source_language, &target_language, &entries)
+        .await
+        .map_err(|e| AppError::new(ErrorKind::Provider, e.to_string()))?;
+    settings.glossary_id = Some(glossary_id.clone());
+    save_project_config(&project_path, &config).map_err(AppError::from)?;
+    Ok(glossary_id)
+}
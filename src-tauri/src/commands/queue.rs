@@ -0,0 +1,150 @@
+// Commands that run the whole pipeline over several projects, one after the other.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+use crate::commands::translation::run_batch;
+use crate::core::rpgmv::snapshot;
+use crate::core::session::project_session;
+use crate::error::AppError;
+use crate::models::queue::{ProjectQueueResult, ProjectQueueSettings, QueueProgress, QueueStage, QueueSummary};
+use crate::models::translation::BatchTranslationOptions;
+use crate::services::packaging::{package_translated_project, save_packaged_project};
+use crate::services::temp_workspace::TempWorkspaces;
+
+/// Emitted with a `QueueProgress` payload each time a project enters a stage.
+pub const QUEUE_PROGRESS_EVENT: &str = "queue://progress";
+/// Emitted once with the `QueueSummary` when the queue is finished.
+pub const QUEUE_SUMMARY_EVENT: &str = "queue://summary";
+
+fn emit_progress(app_handle: &AppHandle, project_path: &str, index: usize, total: usize, stage: QueueStage) {
+    let progress = QueueProgress { project_path: project_path.to_string(), index, total, stage };
+    if let Err(e) = app_handle.emit(QUEUE_PROGRESS_EVENT, progress) {
+        eprintln!("Failed to emit queue progress: {}", e);
+    }
+}
+
+/// `<output_dir>/<project folder>_translated.zip`, or `<project folder>_translated_2.zip` and so
+/// on when that name is taken, e.g. by another queued project in a folder of the same name.
+fn archive_path(output_dir: &str, project_path: &str) -> PathBuf {
+    let name = Path::new(project_path).file_name().map_or("project".into(), |name| name.to_string_lossy());
+    let mut path = Path::new(output_dir).join(format!("{}_translated.zip", name));
+    let mut suffix = 1;
+    while path.exists() {
+        suffix += 1;
+        path = Path::new(output_dir).join(format!("{}_translated_{}.zip", name, suffix));
+    }
+    path
+}
+
+/// Extracts, translates, reconstructs and archives one project. A cancelled translation is
+/// returned as `Skipped`, without an archive.
+async fn process_project(
+    app_handle: &AppHandle,
+    workspaces: &TempWorkspaces,
+    project_path: &str,
+    index: usize,
+    total: usize,
+    settings: &ProjectQueueSettings,
+) -> Result<ProjectQueueResult, AppError> {
+    emit_progress(app_handle, project_path, index, total, QueueStage::Extracting);
+    let entries = crate::core::rpgmv::project::extract_translatable_strings_until(project_path, &settings.filter, &|| false)
+        .map_err(AppError::from)?;
+    let entries = snapshot::record_extraction(project_path, entries, false).map_err(AppError::from)?.entries;
+
+    emit_progress(app_handle, project_path, index, total, QueueStage::Translating);
+    let (source_language, target_language) = (&settings.source_language, &settings.target_language);
+    let options = BatchTranslationOptions { project_path: Some(project_path.to_string()), ..settings.options.clone() };
+    let replace_session = settings.options.replace_session.unwrap_or(false);
+    let session =
+        project_session(project_path, source_language, target_language, replace_session).map_err(AppError::from)?;
+    let entry_count = entries.len();
+    let (results, _) =
+        run_batch(app_handle, entries, source_language, target_language, settings.provider.clone(), options, Some(session))
+            .await?;
+    let mut result = ProjectQueueResult {
+        project_path: project_path.to_string(),
+        stage: QueueStage::Done,
+        archive_path: None,
+        entries: entry_count,
+        translated: results.iter().filter(|t| t.error.is_none() && !t.translated_text.is_empty()).count(),
+        failed: results.iter().filter(|t| t.error.is_some()).count(),
+        error: None,
+    };
+    // A cancelled batch returns fewer results than it was given entries.
+    if results.len() < entry_count {
+        result.stage = QueueStage::Skipped;
+        result.error = Some("Cancelled during translation".to_string());
+        return Ok(result);
+    }
+
+    emit_progress(app_handle, project_path, index, total, QueueStage::Reconstructing);
+    let zip_path = package_translated_project(workspaces, project_path, &results)?;
+    let zip_path = Path::new(&zip_path);
+
+    emit_progress(app_handle, project_path, index, total, QueueStage::Archiving);
+    let target = archive_path(&settings.output_dir, project_path);
+    save_packaged_project(workspaces, zip_path, &target, settings.override_quality_gates)?;
+    result.archive_path = Some(target.to_string_lossy().into_owned());
+    Ok(result)
+}
+
+/// Processes `project_paths` one after the other with the same settings: extraction,
+/// translation (recorded in each project's session), reconstruction and an archive in
+/// `settings.outputDir`. A project that fails doesn't stop the queue; cancelling
+/// `settings.options.jobId` stops it after the project being translated, and the projects left
+/// are reported as skipped. Progress and the summary are also emitted as events.
+#[tauri::command]
+pub async fn process_project_queue_command(
+    app_handle: AppHandle,
+    workspaces: State<'_, TempWorkspaces>,
+    project_paths: Vec<String>,
+    settings: ProjectQueueSettings,
+) -> Result<QueueSummary, AppError> {
+    fs::create_dir_all(&settings.output_dir).map_err(|e| AppError::from(e).with_file(&settings.output_dir))?;
+    let total = project_paths.len();
+    let mut summary = QueueSummary::default();
+    let mut cancelled = false;
+    for (index, project_path) in project_paths.into_iter().enumerate() {
+        let result = if cancelled {
+            ProjectQueueResult {
+                project_path: project_path.clone(),
+                stage: QueueStage::Skipped,
+                archive_path: None,
+                entries: 0,
+                translated: 0,
+                failed: 0,
+                error: None,
+            }
+        } else {
+            match process_project(&app_handle, &workspaces, &project_path, index, total, &settings).await {
+                Ok(result) => result,
+                Err(e) => ProjectQueueResult {
+                    project_path: project_path.clone(),
+                    stage: QueueStage::Failed,
+                    archive_path: None,
+                    entries: 0,
+                    translated: 0,
+                    failed: 0,
+                    error: Some(e.to_string()),
+                },
+            }
+        };
+        cancelled |= result.stage == QueueStage::Skipped;
+        match result.stage {
+            QueueStage::Done => summary.succeeded += 1,
+            QueueStage::Failed => summary.failed += 1,
+            _ => summary.skipped += 1,
+        }
+        emit_progress(&app_handle, &project_path, index, total, result.stage);
+        summary.projects.push(result);
+    }
+    println!(
+        "Project queue: {} done, {} failed, {} skipped.",
+        summary.succeeded, summary.failed, summary.skipped
+    );
+    if let Err(e) = app_handle.emit(QUEUE_SUMMARY_EVENT, summary.clone()) {
+        eprintln!("Failed to emit the queue summary: {}", e);
+    }
+    Ok(summary)
+}
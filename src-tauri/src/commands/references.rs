@@ -0,0 +1,21 @@
+// Commands for reference links and notes attached to individual entries.
+
+use crate::error::AppError;
+use crate::core::references;
+use crate::models::reference::{EntryReferences, ReferenceLink};
+
+#[tauri::command]
+pub async fn get_entry_references_command(project_path: String) -> Result<Vec<EntryReferences>, AppError> {
+    references::load_project_references(&project_path).map_err(AppError::from)
+}
+
+/// Replaces the references of one entry (an empty list removes them) and returns all entry references.
+#[tauri::command]
+pub async fn set_entry_references_command(
+    project_path: String,
+    source_file: String,
+    json_path: String,
+    references: Vec<ReferenceLink>,
+) -> Result<Vec<EntryReferences>, AppError> {
+    references::set_entry_references(&project_path, &source_file, &json_path, references).map_err(AppError::from)
+}
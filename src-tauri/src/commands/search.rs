@@ -0,0 +1,23 @@
+// Commands searching a project's extracted strings and their translations.
+
+use tauri::State;
+use crate::error::{AppError, ErrorKind};
+use crate::models::search::{SearchQuery, SearchResults};
+use crate::services::search_indexes::SearchIndexes;
+
+/// Searches the strings of the project's last extraction, with their session translations, by
+/// plain text or regex in the original and/or translated text, filtered by file, status and
+/// object id. Returns one page of `query.limit` hits from `query.offset`, with the total. The
+/// project's index is built on the first search and kept until the extraction or the session
+/// changes.
+#[tauri::command]
+pub async fn search_strings_command(
+    indexes: State<'_, SearchIndexes>,
+    project_path: String,
+    query: SearchQuery,
+) -> Result<SearchResults, AppError> {
+    let index = indexes.get(&project_path).map_err(AppError::from)?.ok_or_else(|| {
+        AppError::new(ErrorKind::NotFound, format!("{} has not been extracted yet", project_path))
+    })?;
+    index.search(&query).map_err(AppError::from)
+}
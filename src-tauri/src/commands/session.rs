@@ -0,0 +1,112 @@
+// Commands related to the translation session stored in the project's `.translate-ai` folder.
+
+use std::path::Path;
+use crate::error::{AppError, ErrorKind};
+use crate::core::project_document::{build_document, load_project_document, save_project_document};
+use crate::core::rpgmv::recovery::{align_translations, read_output_data_files, read_project_data_files};
+use crate::core::session::{check_session_migration, load_session, migrate_session_file, save_session, SESSION_FILE_NAME};
+use crate::models::migration::MigrationReport;
+use crate::core::translation_memory::TranslationMemory;
+use crate::models::project_document::ProjectDocument;
+use crate::models::session::{SessionRecovery, TranslationSession};
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::services::encryption::{self, SessionLockState};
+
+#[tauri::command]
+pub async fn get_session_lock_state_command(project_path: String) -> Result<SessionLockState, AppError> {
+    encryption::session_lock_state(&project_path).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn lock_session_command(project_path: String, passphrase: String) -> Result<SessionLockState, AppError> {
+    encryption::lock_session(&project_path, &passphrase).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn unlock_session_command(project_path: String, passphrase: String) -> Result<SessionLockState, AppError> {
+    encryption::unlock_session(&project_path, &passphrase).map_err(AppError::from)
+}
+
+/// The project's saved session, if any; `pending` entries mean a batch was interrupted and
+/// can be continued with `resume_translation_session_command`.
+#[tauri::command]
+pub async fn get_translation_session_command(project_path: String) -> Result<Option<TranslationSession>, AppError> {
+    load_session(&project_path).map_err(AppError::from)
+}
+
+/// Dry run of the session format upgrade: what would be renamed, defaulted or left out.
+/// `None` when the project has no session.
+#[tauri::command]
+pub async fn check_session_migration_command(project_path: String) -> Result<Option<MigrationReport>, AppError> {
+    check_session_migration(&project_path).map_err(AppError::from)
+}
+
+/// Upgrades the session file to the current format, keeping the old file next to it.
+#[tauri::command]
+pub async fn migrate_session_command(project_path: String) -> Result<Option<MigrationReport>, AppError> {
+    migrate_session_file(&project_path).map_err(AppError::from)
+}
+
+/// Rebuilds a lost session from the original project and a previously exported translation
+/// (the output folder or `.zip`). Entries are aligned by file and json_path; the result is saved
+/// as the project's session and, unless `store_in_memory` is false, added to the translation memory.
+#[tauri::command]
+pub async fn recover_session_command(
+    project_path: String,
+    output_path: String,
+    source_language: String,
+    target_language: String,
+    store_in_memory: Option<bool>,
+) -> Result<SessionRecovery, AppError> {
+    let original_files = read_project_data_files(Path::new(&project_path)).map_err(AppError::from)?;
+    let output_files = read_output_data_files(Path::new(&output_path)).map_err(AppError::from)?;
+    let (translations, mut report) = align_translations(&original_files, &output_files);
+    if report.recovered == 0 {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("No translated entries found in {} for this project; the session was not replaced", output_path),
+        ));
+    }
+
+    if store_in_memory.unwrap_or(true) {
+        let memory = TranslationMemory::open_for_project(&project_path).map_err(AppError::from)?;
+        for entry in translations.iter().filter(|t| !t.translated_text.is_empty()) {
+            memory
+                .store(&source_language, &target_language, &entry.original_text, &entry.translated_text, &entry.translation_source)
+                .map_err(AppError::from)?;
+            report.stored_in_memory += 1;
+        }
+    }
+
+    let session = TranslationSession { source_language, target_language, translations, pending: Vec::new() };
+    save_session(&project_path, &session).map_err(AppError::from)?;
+    eprintln!("Recovered {} translations into {}", report.recovered, SESSION_FILE_NAME);
+    Ok(SessionRecovery { translations: session.translations, report })
+}
+
+/// Saves the extracted strings and their translations as a `.taiproj` document, by default in
+/// the project's `.translate-ai` folder. Returns the path of the written document.
+#[tauri::command]
+pub async fn save_project_command(
+    project_path: String,
+    source_language: String,
+    target_language: String,
+    extracted: Vec<SourceStringData>,
+    translations: Vec<WorkingTranslation>,
+    provider: Option<String>,
+    document_path: Option<String>,
+) -> Result<String, AppError> {
+    let document =
+        build_document(&project_path, &source_language, &target_language, provider, &extracted, &translations);
+    let path = save_project_document(&project_path, document_path.as_deref(), document).map_err(AppError::from)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// The project's `.taiproj` document (or the one at `document_path`); `None` if never saved.
+#[tauri::command]
+pub async fn load_project_command(
+    project_path: String,
+    document_path: Option<String>,
+) -> Result<Option<ProjectDocument>, AppError> {
+    load_project_document(&project_path, document_path.as_deref()).map_err(AppError::from)
+}
@@ -0,0 +1,19 @@
+// Commands reading a project's extracted strings and their translations from its string store
+// a page at a time, so large projects aren't sent over IPC whole. Extraction fills the store,
+// and `batch_translate_strings_command` translates it by range.
+
+use crate::core::string_store::StringStore;
+use crate::error::AppError;
+use crate::models::string_store::{StoreCounts, StringPage, StringPageQuery};
+
+/// One page of the project's stored strings with their translations, and the total matching
+/// `query`.
+#[tauri::command]
+pub async fn get_string_page_command(project_path: String, query: StringPageQuery) -> Result<StringPage, AppError> {
+    StringStore::open_for_project(&project_path).and_then(|store| store.page(&query)).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_store_counts_command(project_path: String) -> Result<StoreCounts, AppError> {
+    StringStore::open_for_project(&project_path).and_then(|store| store.counts()).map_err(AppError::from)
+}
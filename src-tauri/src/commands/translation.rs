@@ -1,10 +1,39 @@
 // This file will house commands related to text translation.
 
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::error::{AppError, ErrorKind};
 use crate::services::ollama_client; // Correct path to ollama_client within services module
-use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::models::translation::{
+    BatchProgress, BatchTranslationOptions, DedupScope, PartialTranslation, PreviewOptions, QualityPreview,
+    SourceStringData, TranslationSet, WorkingTranslation,
+};
+use crate::core::catchphrases::load_catchphrases;
+use crate::core::config::load_project_config;
+use crate::core::control_codes::ControlCodes;
+use crate::core::dedup::{find_duplicate_groups, DuplicateGroup};
+use crate::models::provider::ProviderConfig;
+use crate::core::glossary::load_project_glossary;
+use crate::core::language_detection::is_in_target_language;
+use crate::core::presets::project_preset;
+use crate::core::prompts::load_prompt_templates;
+use crate::core::quality::{stratified_sample, SampleCategory};
+use crate::core::references::{index_references, load_project_references, ReferenceIndex};
+use crate::core::rpgmv::locale_output::validate_locale;
+use crate::core::scope_summaries::load_scope_summaries;
+use crate::core::session::{entries_to_resume, load_session, project_session};
+use crate::core::string_store::StringStore;
+use crate::core::translation_sets::{list_translation_sets, load_translation_set, record_translations};
+use crate::core::validation::load_project_ruleset;
+use crate::models::glossary::Glossary;
+use crate::models::metrics::{EntryTimings, PipelineMetrics};
+use crate::models::session::TranslationSession;
+use crate::services::batch_translation::{self, BatchContext};
+use crate::services::job_manager::JobManager;
+use crate::services::pipeline::{self, streamed_item, BatchHost};
+use crate::services::translation_provider::build_provider;
 
 #[tauri::command]
-pub async fn translate_text_command(text: String, source_lang: String, target_lang: String) -> Result<String, String> {
+pub async fn translate_text_command(text: String, source_lang: String, target_lang: String) -> Result<String, AppError> {
     // For now, hardcode the model. Later, this could come from frontend settings.
     // let model_name = "mistral".to_string(); // model_name is now hardcoded in ollama_client
     println!(
@@ -14,49 +43,292 @@ pub async fn translate_text_command(text: String, source_lang: String, target_la
         target_lang,
         // model_name // No longer passed
     );
-    ollama_client::translate_with_ollama(text, source_lang, target_lang).await // model_name no longer passed
+    ollama_client::translate_with_ollama(text, source_lang, target_lang)
+        .await // model_name no longer passed
+        .map_err(|e| AppError::new(ErrorKind::Provider, e))
 }
 
+/// Event emitted with a `BatchProgress` payload after each entry of a batch.
+pub const TRANSLATION_PROGRESS_EVENT: &str = "translation://progress";
+/// Emitted after each entry with its `EntryTimings`.
+pub const TRANSLATION_ENTRY_METRICS_EVENT: &str = "translation://entry-metrics";
+/// Emitted once per run with the aggregated `PipelineMetrics`.
+pub const TRANSLATION_METRICS_EVENT: &str = "translation://metrics";
+/// Emitted while a streamed translation is generated, with its `PartialTranslation`.
+pub const TRANSLATION_PARTIAL_EVENT: &str = "translation://partial";
+
+// Batches run from the app report their progress as the events above.
+impl BatchHost for AppHandle {
+    fn jobs(&self) -> &JobManager {
+        self.state::<JobManager>().inner()
+    }
+
+    fn progress(&self, progress: &BatchProgress, timings: &EntryTimings) {
+        if let Err(e) = self.emit(TRANSLATION_PROGRESS_EVENT, progress.clone()) {
+            eprintln!("Failed to emit translation progress: {}", e);
+        }
+        if let Err(e) = self.emit(TRANSLATION_ENTRY_METRICS_EVENT, timings.clone()) {
+            eprintln!("Failed to emit translation metrics: {}", e);
+        }
+    }
+
+    fn partial(&self, partial: &PartialTranslation) {
+        if let Err(e) = self.emit(TRANSLATION_PARTIAL_EVENT, partial.clone()) {
+            eprintln!("Failed to emit partial translation: {}", e);
+        }
+    }
+
+    fn metrics(&self, metrics: &PipelineMetrics) {
+        if let Err(e) = self.emit(TRANSLATION_METRICS_EVENT, metrics.clone()) {
+            eprintln!("Failed to emit translation metrics: {}", e);
+        }
+    }
+}
+
+/// Stops the translation being streamed for an entry of a batch started with `options.stream`
+/// and `options.jobId`; the entry is left untranslated with an error and the batch goes on.
+/// Returns false if no job with that id is running.
+#[tauri::command]
+pub async fn abort_streamed_translation_command(
+    jobs: State<'_, JobManager>,
+    job_id: String,
+    source_file: String,
+    json_path: String,
+) -> Result<bool, AppError> {
+    Ok(jobs.skip(&job_id, &streamed_item(&source_file, &json_path)))
+}
+
+/// With `options.jobId`, the batch can be stopped via `cancel_job_command`; the entries
+/// translated before that are returned. With `options.projectPath`, progress is saved to the
+/// project's session so an interrupted batch can be resumed, and to its string store.
+///
+/// With `options.range`, the batch is that page of the project's string store (e.g. the next
+/// untranslated strings) instead of `entries`, so a large project is translated a page at a time
+/// without sending it over IPC whole; the results are the page's translations.
 #[tauri::command]
 pub async fn batch_translate_strings_command(
-    entries: Vec<SourceStringData>,
+    app_handle: AppHandle,
+    entries: Option<Vec<SourceStringData>>,
     source_language: String,
     target_language: String,
-    // TODO: engine_name will be used later to select between Ollama, DeepL, etc.
-    _engine_name: String // For now, it's implicitly Ollama
-) -> Result<Vec<WorkingTranslation>, String> {
-    let mut results: Vec<WorkingTranslation> = Vec::new();
-
-    for entry in entries {
-        match translate_text_command(
-            entry.original_text.clone(),
-            source_language.clone(),
-            target_language.clone()
-        ).await {
-            Ok(translated_text) => {
-                results.push(WorkingTranslation {
-                    object_id: entry.object_id,
-                    original_text: entry.original_text,
-                    translated_text,
-                    source_file: entry.source_file,
-                    json_path: entry.json_path,
-                    translation_source: "ollama".to_string(),
-                    error: None,
-                });
-            }
-            Err(e) => {
-                results.push(WorkingTranslation {
-                    object_id: entry.object_id,
-                    original_text: entry.original_text,
-                    translated_text: String::new(),
-                    source_file: entry.source_file,
-                    json_path: entry.json_path,
-                    translation_source: "ollama".to_string(),
-                    error: Some(e.to_string()),
-                });
-            }
+    // Legacy engine selector kept for frontend compatibility; `provider` takes precedence.
+    _engine_name: String,
+    // Provider and its settings (e.g. DeepL API key). Defaults to Ollama when omitted.
+    provider: Option<ProviderConfig>,
+    options: Option<BatchTranslationOptions>,
+) -> Result<Vec<WorkingTranslation>, AppError> {
+    let options = options.unwrap_or_default();
+    let entries = match (&options.range, &options.project_path) {
+        (Some(range), Some(path)) => {
+            let store = StringStore::open_for_project(path).map_err(AppError::from)?;
+            store.page(range).map_err(AppError::from)?.entries.iter().map(|entry| entry.source()).collect()
+        }
+        (Some(_), None) => {
+            return Err(AppError::new(
+                ErrorKind::Config,
+                "Translating a range of the string store requires options.projectPath".to_string(),
+            ))
+        }
+        (None, _) => entries.unwrap_or_default(),
+    };
+    let session = match &options.project_path {
+        Some(path) => {
+            let replace_session = options.replace_session.unwrap_or(false);
+            Some(project_session(path, &source_language, &target_language, replace_session).map_err(AppError::from)?)
+        }
+        None => None,
+    };
+    let (results, _) =
+        run_batch(&app_handle, entries, &source_language, &target_language, provider, options, session).await?;
+    Ok(results)
+}
+
+/// Continues the project's saved session: translates the entries that were still pending when
+/// it was interrupted and retries failed ones. Returns every translation of the session.
+#[tauri::command]
+pub async fn resume_translation_session_command(
+    app_handle: AppHandle,
+    project_path: String,
+    provider: Option<ProviderConfig>,
+    options: Option<BatchTranslationOptions>,
+) -> Result<Vec<WorkingTranslation>, AppError> {
+    let session = load_session(&project_path)
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::new(ErrorKind::NotFound, format!("No saved translation session in {}", project_path)))?;
+    let entries = entries_to_resume(&session);
+    println!("Resuming translation session: {} entries left.", entries.len());
+    let (source_language, target_language) = (session.source_language.clone(), session.target_language.clone());
+    let options = BatchTranslationOptions { project_path: Some(project_path), ..options.unwrap_or_default() };
+    let (_, all) =
+        run_batch(&app_handle, entries, &source_language, &target_language, provider, options, Some(session)).await?;
+    Ok(all.unwrap_or_default())
+}
+
+/// Translates the same extracted `entries` into each of `target_languages` in turn, sharing the
+/// translation memory. With `options.projectPath`, each language's results are added to its own
+/// translation set (the single-language session is left alone), ready for
+/// `reconstruct_multi_target_command`. Cancelling `options.jobId` stops after the language
+/// being translated; the languages finished by then are returned.
+#[tauri::command]
+pub async fn batch_translate_multi_target_command(
+    app_handle: AppHandle,
+    entries: Vec<SourceStringData>,
+    source_language: String,
+    target_languages: Vec<String>,
+    provider: Option<ProviderConfig>,
+    options: Option<BatchTranslationOptions>,
+) -> Result<Vec<TranslationSet>, AppError> {
+    let options = options.unwrap_or_default();
+    for target_language in &target_languages {
+        validate_locale(target_language).map_err(AppError::from)?;
+    }
+    let mut sets = Vec::with_capacity(target_languages.len());
+    for target_language in target_languages {
+        println!("Multi-language batch: translating {} entries into '{}'.", entries.len(), target_language);
+        let (results, _) = run_batch(
+            &app_handle,
+            entries.clone(),
+            &source_language,
+            &target_language,
+            provider.clone(),
+            options.clone(),
+            None,
+        )
+        .await?;
+        // A cancelled batch returns fewer results than it was given entries.
+        let cancelled = results.len() < entries.len();
+        if let Some(path) = &options.project_path {
+            record_translations(path, &target_language, &results).map_err(AppError::from)?;
+        }
+        sets.push(TranslationSet { target_language, translations: results });
+        if cancelled {
+            break;
         }
     }
+    Ok(sets)
+}
 
-    Ok(results)
-} 
\ No newline at end of file
+/// Languages the project has a translation set for.
+#[tauri::command]
+pub async fn list_translation_sets_command(project_path: String) -> Result<Vec<String>, AppError> {
+    list_translation_sets(&project_path).map_err(AppError::from)
+}
+
+/// Every translation of the project into `target_language`, or `None` if it has none.
+#[tauri::command]
+pub async fn get_translation_set_command(
+    project_path: String,
+    target_language: String,
+) -> Result<Option<TranslationSet>, AppError> {
+    load_translation_set(&project_path, &target_language).map_err(AppError::from)
+}
+
+/// Extracted strings that are already in the target language, which batches started with
+/// `options.skipTargetLanguage` keep as they are.
+#[tauri::command]
+pub async fn detect_target_language_strings_command(
+    entries: Vec<SourceStringData>,
+    source_language: String,
+    target_language: String,
+) -> Result<Vec<SourceStringData>, AppError> {
+    Ok(entries
+        .into_iter()
+        .filter(|entry| is_in_target_language(&entry.original_text, &source_language, &target_language))
+        .collect())
+}
+
+/// Strings of `entries` that batches started with `options.dedup` translate once, with their
+/// occurrences, so the ones the shared translation doesn't fit can be marked as separate.
+#[tauri::command]
+pub async fn find_duplicate_strings_command(
+    entries: Vec<SourceStringData>,
+    scope: Option<DedupScope>,
+) -> Result<Vec<DuplicateGroup>, AppError> {
+    Ok(find_duplicate_groups(&entries, scope.unwrap_or_default()))
+}
+
+/// Shared pipeline of the batch, resume and project queue commands, see `pipeline::run_batch`.
+pub(crate) async fn run_batch(
+    app_handle: &AppHandle,
+    entries: Vec<SourceStringData>,
+    source_language: &str,
+    target_language: &str,
+    provider: Option<ProviderConfig>,
+    options: BatchTranslationOptions,
+    session: Option<TranslationSession>,
+) -> Result<(Vec<WorkingTranslation>, Option<Vec<WorkingTranslation>>), AppError> {
+    pipeline::run_batch(app_handle, entries, source_language, target_language, provider, options, session)
+        .await
+        .map_err(AppError::from)
+}
+
+
+const PREVIEW_DIALOGUE_LINES: usize = 30;
+const PREVIEW_DESCRIPTIONS: usize = 10;
+const PREVIEW_OTHER_ENTRIES: usize = 10;
+const PREVIEW_TIME_LIMIT_MS: u64 = 90_000;
+
+/// Translates a small stratified sample of `entries` (dialogue, descriptions, other strings)
+/// with the given provider and the project's glossary, so the user can judge quality before
+/// starting the full batch. Stops at the time limit and returns what was finished.
+#[tauri::command]
+pub async fn preview_quality_command(
+    entries: Vec<SourceStringData>,
+    source_language: String,
+    target_language: String,
+    provider: Option<ProviderConfig>,
+    options: Option<PreviewOptions>,
+) -> Result<QualityPreview, AppError> {
+    let options = options.unwrap_or_default();
+    let sample = stratified_sample(
+        &entries,
+        &[
+            (SampleCategory::Dialogue, options.dialogue_lines.unwrap_or(PREVIEW_DIALOGUE_LINES)),
+            (SampleCategory::Description, options.descriptions.unwrap_or(PREVIEW_DESCRIPTIONS)),
+            (SampleCategory::Other, options.other_entries.unwrap_or(PREVIEW_OTHER_ENTRIES)),
+        ],
+    );
+    let (glossary, references, control_codes, prompt_templates, scope_summaries, catchphrases) = match &options.project_path {
+        Some(path) => (
+            load_project_glossary(path).map_err(AppError::from)?,
+            index_references(load_project_references(path).map_err(AppError::from)?),
+            ControlCodes::from_ruleset(&load_project_ruleset(path).map_err(AppError::from)?),
+            Some(load_prompt_templates(path).map_err(AppError::from)?),
+            Some(load_scope_summaries(path).map_err(AppError::from)?),
+            Some(load_catchphrases(path).map_err(AppError::from)?),
+        ),
+        None => (Glossary::default(), ReferenceIndex::new(), ControlCodes::default(), None, None, None),
+    };
+    let preset = options.project_path.as_deref().map(project_preset).transpose().map_err(AppError::from)?.flatten();
+    let characters =
+        options.project_path.as_deref().map(load_project_config).transpose().map_err(AppError::from)?.map(|c| c.characters);
+    let batch = BatchContext {
+        source_language: &source_language,
+        target_language: &target_language,
+        glossary: &glossary,
+        references: &references,
+        include_references: true,
+        cancellation: None,
+        concurrency: 1,
+        unit_modes: None,
+        control_codes: Some(&control_codes),
+        prompt_templates: prompt_templates.as_ref(),
+        // The preview translates entries one by one.
+        dialogue_blocks: false,
+        surrounding_lines: None,
+        scope_summaries: scope_summaries.as_ref(),
+        on_partial: None,
+        catchphrases: catchphrases.as_ref(),
+        skip_target_language: false,
+        preset: preset.as_ref(),
+        characters: characters.as_deref(),
+    };
+
+    let provider = build_provider(provider);
+    let started = tokio::time::Instant::now();
+    let time_limit = std::time::Duration::from_millis(options.time_limit_ms.unwrap_or(PREVIEW_TIME_LIMIT_MS));
+    let sample_size = sample.len();
+    let (results, timed_out) =
+        batch_translation::translate_until(provider.as_ref(), sample, &batch, started + time_limit).await;
+    Ok(QualityPreview { results, sample_size, timed_out, elapsed_ms: started.elapsed().as_millis() as u64 })
+}
@@ -0,0 +1,104 @@
+// Commands for managing translation validation rulesets.
+
+use std::path::Path;
+use crate::error::AppError;
+use crate::core::control_codes::ControlCodes;
+use crate::core::{font_coverage, glossary, name_policy, presets, qa_report, quality, validation};
+use crate::models::coverage::FileCoverage;
+use crate::models::qa_report::{QaReport, QaReportFormat};
+use crate::models::translation::WorkingTranslation;
+use crate::models::validation::{CharacterCoverageIssue, ValidationIssue, ValidationRuleSet};
+
+#[tauri::command]
+pub async fn get_project_validation_ruleset_command(project_path: String) -> Result<ValidationRuleSet, AppError> {
+    validation::load_project_ruleset(&project_path).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn save_project_validation_ruleset_command(
+    project_path: String,
+    ruleset: ValidationRuleSet,
+) -> Result<(), AppError> {
+    validation::save_project_ruleset(&project_path, &ruleset).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn export_validation_ruleset_command(
+    ruleset: ValidationRuleSet,
+    output_path: String,
+) -> Result<(), AppError> {
+    validation::export_ruleset(&ruleset, Path::new(&output_path)).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn import_validation_ruleset_command(
+    project_path: String,
+    ruleset_path: String,
+) -> Result<ValidationRuleSet, AppError> {
+    validation::import_ruleset_into_project(&project_path, Path::new(&ruleset_path))
+        .map_err(|e| AppError::from(e).with_file(&ruleset_path))
+}
+
+#[tauri::command]
+pub async fn validate_translations_command(
+    project_path: String,
+    translations: Vec<WorkingTranslation>,
+) -> Result<Vec<ValidationIssue>, AppError> {
+    let mut ruleset = validation::load_project_ruleset(&project_path).map_err(AppError::from)?;
+    let glossary = glossary::load_project_glossary(&project_path).map_err(AppError::from)?;
+    ruleset.locked_terms.extend(glossary::as_locked_terms(&glossary));
+    let preset = presets::project_preset(&project_path).map_err(AppError::from)?;
+    let codes = ControlCodes::from_ruleset(&ruleset);
+    Ok(translations
+        .iter()
+        .flat_map(|entry| {
+            let mut issues = validation::validate_translation_with(entry, &ruleset, &codes);
+            if let Some(preset) = &preset {
+                issues.extend(name_policy::check_name_policy(entry, preset, &glossary));
+            }
+            issues
+        })
+        .collect())
+}
+
+/// Per-file coverage badges (percent translated, percent reviewed, validation pass rate), files
+/// needing the most attention first.
+#[tauri::command]
+pub async fn get_file_coverage_command(
+    project_path: String,
+    translations: Vec<WorkingTranslation>,
+) -> Result<Vec<FileCoverage>, AppError> {
+    quality::project_file_coverage(&project_path, &translations).map_err(AppError::from)
+}
+
+/// Translated strings with characters outside the project's font ranges (e.g. Cyrillic in a
+/// Japanese game), which would render as blanks or boxes in-game.
+#[tauri::command]
+pub async fn check_character_coverage_command(
+    project_path: String,
+    translations: Vec<WorkingTranslation>,
+) -> Result<Vec<CharacterCoverageIssue>, AppError> {
+    font_coverage::project_character_coverage(&project_path, &translations).map_err(AppError::from)
+}
+
+/// Placeholder mismatches, untranslated strings, long lines, glossary violations, translations
+/// identical to the source and the last reconstruction's warnings, grouped by file. With
+/// `output_path` the report is also written as HTML or CSV (`format`, or the file extension).
+#[tauri::command]
+pub async fn generate_qa_report_command(
+    project_path: String,
+    translations: Vec<WorkingTranslation>,
+    output_path: Option<String>,
+    format: Option<QaReportFormat>,
+) -> Result<QaReport, AppError> {
+    let report = qa_report::project_qa_report(&project_path, &translations).map_err(AppError::from)?;
+    if let Some(output_path) = output_path {
+        let path = Path::new(&output_path);
+        let format = format.unwrap_or_else(|| match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => QaReportFormat::Csv,
+            _ => QaReportFormat::Html,
+        });
+        qa_report::export_qa_report(&report, format, path).map_err(|e| AppError::from(e).with_file(&output_path))?;
+    }
+    Ok(report)
+}
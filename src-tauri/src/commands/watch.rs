@@ -0,0 +1,132 @@
+// Commands that keep a translated build of a project up to date while its data files are edited.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+use crate::commands::project::reconstruct_language;
+use crate::commands::translation::run_batch;
+use crate::core::rpgmv::build_output::{prepare_build_dir, write_build_files};
+use crate::core::rpgmv::project::extract_translatable_strings_until;
+use crate::core::rpgmv::schema::detect_schema;
+use crate::core::rpgmv::snapshot;
+use crate::core::session::{project_session, untranslated_entries};
+use crate::error::{AppError, ErrorKind};
+use crate::models::extraction::ExtractionFilter;
+use crate::models::translation::BatchTranslationOptions;
+use crate::models::watch::{WatchSettings, WatchUpdate};
+use crate::services::project_watcher::{next_changes, watch_json_files, ProjectWatchers, WATCH_DEBOUNCE};
+
+/// Emitted with a `WatchUpdate` payload after each pass over a watched project.
+pub const WATCH_UPDATE_EVENT: &str = "watch://update";
+
+/// Extracts `changed_files` (every file when empty), translates their new and changed strings
+/// into the project's session and rewrites the build files whose translation changed. A pass over
+/// every file translates all the strings the session has no translation of, including those
+/// extracted before but never translated.
+async fn sync_project(
+    app_handle: &AppHandle,
+    project_path: &str,
+    settings: &WatchSettings,
+    changed_files: Vec<String>,
+) -> Result<WatchUpdate, AppError> {
+    let every_file = changed_files.is_empty();
+    let filter = ExtractionFilter { include_files: changed_files.clone(), ..Default::default() };
+    let entries = extract_translatable_strings_until(project_path, &filter, &|| false).map_err(AppError::from)?;
+    let entries = snapshot::record_extraction(project_path, entries, !every_file).map_err(AppError::from)?.entries;
+
+    let (source_language, target_language) = (&settings.source_language, &settings.target_language);
+    let replace_session = settings.options.replace_session.unwrap_or(false);
+    let session =
+        project_session(project_path, source_language, target_language, replace_session).map_err(AppError::from)?;
+    let entries = if every_file { untranslated_entries(&session, entries) } else { entries };
+    let mut update = WatchUpdate {
+        project_path: project_path.to_string(),
+        changed_files,
+        new_entries: entries.len(),
+        ..Default::default()
+    };
+    let translations = if entries.is_empty() {
+        session.translations
+    } else {
+        let options = BatchTranslationOptions { project_path: Some(project_path.to_string()), ..settings.options.clone() };
+        let (results, session_translations) =
+            run_batch(app_handle, entries, source_language, target_language, settings.provider.clone(), options, Some(session))
+                .await?;
+        update.translated = results.iter().filter(|t| t.error.is_none() && !t.translated_text.is_empty()).count();
+        update.failed = results.iter().filter(|t| t.error.is_some()).count();
+        session_translations.unwrap_or(results)
+    };
+
+    let (output, _) = reconstruct_language(project_path, target_language, &translations)?;
+    update.files_written = write_build_files(&settings.output_dir, &output)
+        .map_err(|e| AppError::from(e).with_file(&settings.output_dir))?;
+    Ok(update)
+}
+
+/// Changed data files relative to the project, as extraction filters expect them. `project_root`
+/// must be the path the data folder was watched under, as the changed paths start with it.
+fn relative_paths(project_root: &Path, paths: impl IntoIterator<Item = PathBuf>) -> Vec<String> {
+    paths
+        .into_iter()
+        .filter_map(|path| Some(path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/")))
+        .collect()
+}
+
+/// Watches the project's data folder (`www/data`, or `data` for MZ) and keeps
+/// `settings.outputDir` a translated copy of the project: a first pass translates whatever
+/// wasn't extracted before, then each time data files change, their new strings are
+/// translated (using the translation memory) into the project's session and the changed
+/// output files are rewritten. Each pass emits a `watch://update` event. Watching a project
+/// again replaces its previous watch.
+#[tauri::command]
+pub async fn watch_project_command(
+    app_handle: AppHandle,
+    watchers: State<'_, ProjectWatchers>,
+    project_path: String,
+    settings: WatchSettings,
+) -> Result<(), AppError> {
+    let data_dir = detect_schema(&project_path).map_or("www/data".to_string(), |schema| schema.data_dir);
+    // Watchers may report canonical paths (e.g. with symlinks resolved), so the data folder is
+    // watched under the canonical project path, which changed paths are then made relative to.
+    let project_root = std::fs::canonicalize(&project_path).unwrap_or_else(|_| PathBuf::from(&project_path));
+    let data_path = project_root.join(data_dir);
+    if !data_path.is_dir() {
+        return Err(AppError::new(ErrorKind::NotFound, "The project has no RPG Maker data folder to watch")
+            .with_file(data_path.to_string_lossy()));
+    }
+    prepare_build_dir(&project_path, &settings.output_dir)
+        .map_err(|e| AppError::from(e).with_file(&settings.output_dir))?;
+    let (watcher, mut changes) = watch_json_files(&data_path).map_err(AppError::from)?;
+
+    let (task_handle, task_project) = (app_handle.clone(), project_path.clone());
+    let handle = tokio::spawn(async move {
+        let mut changed_files = Vec::new();
+        loop {
+            let update = sync_project(&task_handle, &task_project, &settings, changed_files).await.unwrap_or_else(|e| {
+                eprintln!("Watch pass over {} failed: {}", task_project, e);
+                WatchUpdate { project_path: task_project.clone(), error: Some(e.to_string()), ..Default::default() }
+            });
+            if let Err(e) = task_handle.emit(WATCH_UPDATE_EVENT, update) {
+                eprintln!("Failed to emit watch update: {}", e);
+            }
+            let Some(changed) = next_changes(&mut changes, WATCH_DEBOUNCE).await else { break };
+            changed_files = relative_paths(&project_root, changed);
+        }
+    });
+    watchers.start(&project_path, watcher, handle);
+    Ok(())
+}
+
+/// Stops watching the project. Returns false if it wasn't watched.
+#[tauri::command]
+pub async fn unwatch_project_command(
+    watchers: State<'_, ProjectWatchers>,
+    project_path: String,
+) -> Result<bool, AppError> {
+    Ok(watchers.stop(&project_path))
+}
+
+/// Paths of the projects being watched.
+#[tauri::command]
+pub async fn get_watched_projects_command(watchers: State<'_, ProjectWatchers>) -> Result<Vec<String>, AppError> {
+    Ok(watchers.watched())
+}
@@ -0,0 +1,17 @@
+// Commands for the app's temp workspaces (packaged output, unpacked archives, staging copies).
+
+use tauri::State;
+use crate::error::AppError;
+use crate::services::temp_workspace::{CleanupReport, TempUsage, TempWorkspaces};
+
+/// Disk space used by each temp workspace and in total, against the quota.
+#[tauri::command]
+pub async fn get_temp_usage_command(workspaces: State<'_, TempWorkspaces>) -> Result<TempUsage, AppError> {
+    Ok(workspaces.usage())
+}
+
+/// Removes the workspaces no running command uses, including packaged ZIPs that were never saved.
+#[tauri::command]
+pub async fn cleanup_temp_workspaces_command(workspaces: State<'_, TempWorkspaces>) -> Result<CleanupReport, AppError> {
+    workspaces.cleanup_orphans().map_err(AppError::from)
+}
@@ -0,0 +1,378 @@
+use regex::Regex;
+use crate::core::escaping::sanitize_translation;
+use crate::core::glossary::apply_glossary;
+use crate::core::quality::path_category;
+use crate::error::CoreError;
+use crate::models::bulk::{
+    BulkOperation, BulkResult, BulkSummary, EntryFilter, EntryStatus, EntryUpdate, FindReplaceChange, FindReplaceRequest,
+    FindReplaceResult, PostProcessRule,
+};
+use crate::models::glossary::Glossary;
+use crate::models::translation::{TranslationStatus, WorkingTranslation};
+
+/// Error recorded on entries marked as failed by hand.
+pub const MARKED_FAILED_ERROR: &str = "Marked for retranslation";
+
+pub fn entry_status(entry: &WorkingTranslation) -> EntryStatus {
+    if entry.error.is_some() {
+        EntryStatus::Failed
+    } else if entry.translated_text.is_empty() {
+        EntryStatus::Untranslated
+    } else {
+        EntryStatus::Translated
+    }
+}
+
+fn compile(pattern: &Option<String>) -> Result<Option<Regex>, CoreError> {
+    pattern
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .map(|p| Regex::new(p).map_err(|e| CoreError::Config(format!("Invalid pattern '{}': {}", p, e))))
+        .transpose()
+}
+
+struct CompiledFilter<'a> {
+    filter: &'a EntryFilter,
+    original: Option<Regex>,
+    translation: Option<Regex>,
+}
+
+impl CompiledFilter<'_> {
+    fn matches(&self, entry: &WorkingTranslation) -> bool {
+        let filter = self.filter;
+        (filter.source_files.is_empty() || filter.source_files.contains(&entry.source_file))
+            && (filter.categories.is_empty() || filter.categories.contains(&path_category(&entry.json_path)))
+            && (filter.statuses.is_empty() || filter.statuses.contains(&entry_status(entry)))
+            && (filter.review_statuses.is_empty() || filter.review_statuses.contains(&entry.status))
+            && self.original.as_ref().map_or(true, |re| re.is_match(&entry.original_text))
+            && self.translation.as_ref().map_or(true, |re| re.is_match(&entry.translated_text))
+    }
+}
+
+// An operation with its regex compiled and glossary resolved, so applying it cannot fail.
+enum PreparedOperation<'a> {
+    SetStatus(EntryStatus),
+    SetReviewStatus(TranslationStatus),
+    ClearTranslation,
+    AppendSuffix(&'a str),
+    TrimWhitespace,
+    ApplyGlossary(&'a Glossary),
+    EscapeEngineCharacters,
+    RegexReplace(Regex, &'a str),
+}
+
+fn prepare<'a>(operation: &'a BulkOperation, glossary: Option<&'a Glossary>) -> Result<PreparedOperation<'a>, CoreError> {
+    Ok(match operation {
+        BulkOperation::SetStatus { status } => PreparedOperation::SetStatus(*status),
+        BulkOperation::SetReviewStatus { status } => PreparedOperation::SetReviewStatus(*status),
+        BulkOperation::ClearTranslation => PreparedOperation::ClearTranslation,
+        BulkOperation::AppendSuffix { suffix } => PreparedOperation::AppendSuffix(suffix),
+        BulkOperation::PostProcess { rule: PostProcessRule::TrimWhitespace } => PreparedOperation::TrimWhitespace,
+        BulkOperation::PostProcess { rule: PostProcessRule::ApplyGlossary } => PreparedOperation::ApplyGlossary(
+            glossary.ok_or_else(|| CoreError::Config("Applying the glossary needs a project".to_string()))?,
+        ),
+        BulkOperation::PostProcess { rule: PostProcessRule::EscapeEngineCharacters } => {
+            PreparedOperation::EscapeEngineCharacters
+        }
+        BulkOperation::PostProcess { rule: PostProcessRule::RegexReplace { pattern, replacement } } => {
+            let regex = compile(&Some(pattern.clone()))?
+                .ok_or_else(|| CoreError::Config("The replacement pattern is empty".to_string()))?;
+            PreparedOperation::RegexReplace(regex, replacement)
+        }
+    })
+}
+
+/// Returns true if the entry was changed. Text operations skip entries without a usable
+/// translation, and mark the ones they change as edited.
+fn apply(operation: &PreparedOperation, entry: &mut WorkingTranslation) -> bool {
+    let has_translation = entry_status(entry) == EntryStatus::Translated;
+    let new_text = match operation {
+        PreparedOperation::SetStatus(status) => return set_status(entry, *status),
+        PreparedOperation::SetReviewStatus(status) => return set_review_status(entry, *status),
+        PreparedOperation::ClearTranslation => {
+            let changed = !entry.translated_text.is_empty() || entry.error.is_some();
+            entry.translated_text.clear();
+            entry.error = None;
+            entry.status = TranslationStatus::Untranslated;
+            return changed;
+        }
+        PreparedOperation::ApplyGlossary(glossary) => return apply_glossary(entry, glossary) && mark_edited(entry),
+        PreparedOperation::EscapeEngineCharacters => return sanitize_translation(entry) && mark_edited(entry),
+        _ if !has_translation => return false,
+        PreparedOperation::AppendSuffix(suffix) => format!("{}{}", entry.translated_text, suffix),
+        PreparedOperation::TrimWhitespace => entry.translated_text.trim().to_string(),
+        PreparedOperation::RegexReplace(regex, replacement) => {
+            regex.replace_all(&entry.translated_text, *replacement).into_owned()
+        }
+    };
+    if new_text == entry.translated_text {
+        return false;
+    }
+    entry.translated_text = new_text;
+    mark_edited(entry)
+}
+
+fn mark_edited(entry: &mut WorkingTranslation) -> bool {
+    entry.status = TranslationStatus::Edited;
+    true
+}
+
+fn set_status(entry: &mut WorkingTranslation, status: EntryStatus) -> bool {
+    if entry_status(entry) == status {
+        return false;
+    }
+    match status {
+        EntryStatus::Untranslated => {
+            entry.translated_text.clear();
+            entry.error = None;
+            entry.status = TranslationStatus::Untranslated;
+        }
+        EntryStatus::Failed => {
+            entry.error = Some(MARKED_FAILED_ERROR.to_string());
+            entry.status = TranslationStatus::Untranslated;
+        }
+        // Only a failed entry that still has text can become translated again.
+        EntryStatus::Translated if entry.translated_text.is_empty() => return false,
+        EntryStatus::Translated => {
+            entry.error = None;
+            entry.status = TranslationStatus::MachineTranslated;
+        }
+    }
+    true
+}
+
+fn set_review_status(entry: &mut WorkingTranslation, status: TranslationStatus) -> bool {
+    if status == TranslationStatus::Untranslated {
+        return set_status(entry, EntryStatus::Untranslated);
+    }
+    if entry.status == status || entry_status(entry) != EntryStatus::Translated {
+        return false;
+    }
+    entry.status = status;
+    true
+}
+
+/// The entries reconstruction writes: all of them, or with `approved_only` only those approved
+/// in review.
+pub fn entries_to_apply(mut entries: Vec<WorkingTranslation>, approved_only: bool) -> Vec<WorkingTranslation> {
+    if approved_only {
+        entries.retain(|entry| entry.status == TranslationStatus::Approved);
+    }
+    entries
+}
+
+/// Applies `operation` to every entry matching `filter`. Patterns and the operation are
+/// validated before any entry is touched, so on error nothing is changed.
+pub fn run_bulk_operation(
+    mut translations: Vec<WorkingTranslation>,
+    filter: &EntryFilter,
+    operation: &BulkOperation,
+    glossary: Option<&Glossary>,
+) -> Result<BulkResult, CoreError> {
+    let filter = CompiledFilter {
+        filter,
+        original: compile(&filter.original_pattern)?,
+        translation: compile(&filter.translation_pattern)?,
+    };
+    let operation = prepare(operation, glossary)?;
+
+    let mut summary = BulkSummary::default();
+    for entry in translations.iter_mut().filter(|e| filter.matches(e)) {
+        summary.matched += 1;
+        if apply(&operation, entry) {
+            summary.changed += 1;
+            *summary.changed_by_file.entry(entry.source_file.clone()).or_default() += 1;
+        }
+    }
+    Ok(BulkResult { translations, summary })
+}
+
+/// Applies a hand edit to its entry and returns the entry, or `None` if no entry has its source
+/// file and JSON path. A changed text clears the entry's error and marks it as edited (or
+/// untranslated when empty). On error the entry may already be changed, so it shouldn't be saved.
+pub fn update_entry(
+    translations: &mut [WorkingTranslation],
+    update: &EntryUpdate,
+) -> Result<Option<WorkingTranslation>, CoreError> {
+    let Some(entry) = translations
+        .iter_mut()
+        .find(|entry| entry.source_file == update.source_file && entry.json_path == update.json_path)
+    else {
+        return Ok(None);
+    };
+    if entry.translated_text != update.translated_text || entry.error.is_some() {
+        entry.translated_text = update.translated_text.clone();
+        entry.error = None;
+        entry.status = match entry.translated_text.is_empty() {
+            true => TranslationStatus::Untranslated,
+            false => TranslationStatus::Edited,
+        };
+    }
+    if let Some(status) = update.status.filter(|status| *status != entry.status) {
+        if !set_review_status(entry, status) {
+            return Err(CoreError::Config(format!("An entry without a translation can't be set to {:?}", status)));
+        }
+    }
+    Ok(Some(entry.clone()))
+}
+
+/// Runs a regex find/replace over the translations matching the request's filter, like the
+/// `regexReplace` bulk operation. Returns the updated translations with every change made;
+/// whether to keep them (`dryRun`) is up to the caller.
+pub fn find_replace(
+    translations: Vec<WorkingTranslation>,
+    request: &FindReplaceRequest,
+) -> Result<(Vec<WorkingTranslation>, FindReplaceResult), CoreError> {
+    let pattern = match request.case_insensitive {
+        true => format!("(?i){}", request.pattern),
+        false => request.pattern.clone(),
+    };
+    let before: Vec<String> = translations.iter().map(|entry| entry.translated_text.clone()).collect();
+    let operation = BulkOperation::PostProcess {
+        rule: PostProcessRule::RegexReplace { pattern, replacement: request.replacement.clone() },
+    };
+    let BulkResult { translations, summary } = run_bulk_operation(translations, &request.filter, &operation, None)?;
+    let changes = before
+        .into_iter()
+        .zip(&translations)
+        .filter(|(before, entry)| *before != entry.translated_text)
+        .map(|(before, entry)| FindReplaceChange {
+            source_file: entry.source_file.clone(),
+            json_path: entry.json_path.clone(),
+            before,
+            after: entry.translated_text.clone(),
+        })
+        .collect();
+    Ok((translations, FindReplaceResult { changes, summary, applied: false }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::core::quality::SampleCategory;
+
+    fn entry(source_file: &str, json_path: &str, original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    fn sample() -> Vec<WorkingTranslation> {
+        vec![
+            entry("www/data/Map001.json", "events[1].pages[0].list[1].parameters[0]", "おはよう", "Good morning "),
+            entry("www/data/Map001.json", "events[1].pages[0].list[2].parameters[0]", "元気？", ""),
+            entry("www/data/Items.json", "[1].name", "ポーション", "Potion "),
+        ]
+    }
+
+    #[test]
+    fn test_filter_by_category_and_pattern() {
+        let filter = EntryFilter {
+            categories: vec![SampleCategory::Dialogue],
+            translation_pattern: Some(r"\s$".to_string()),
+            ..Default::default()
+        };
+        let operation = BulkOperation::PostProcess { rule: PostProcessRule::TrimWhitespace };
+        let result = run_bulk_operation(sample(), &filter, &operation, None).unwrap();
+        assert_eq!(result.summary.matched, 1);
+        assert_eq!(result.summary.changed_by_file.get("www/data/Map001.json"), Some(&1));
+        assert_eq!(result.translations[0].translated_text, "Good morning");
+        assert_eq!(result.translations[2].translated_text, "Potion ");
+    }
+
+    #[test]
+    fn test_set_status_and_skip_untranslated() {
+        let filter = EntryFilter { source_files: vec!["www/data/Map001.json".to_string()], ..Default::default() };
+        let failed = run_bulk_operation(
+            sample(),
+            &filter,
+            &BulkOperation::SetStatus { status: EntryStatus::Failed },
+            None,
+        )
+        .unwrap();
+        assert_eq!(failed.summary.changed, 2);
+        assert!(failed.translations[..2].iter().all(|e| entry_status(e) == EntryStatus::Failed));
+
+        let suffixed =
+            run_bulk_operation(sample(), &filter, &BulkOperation::AppendSuffix { suffix: "!".to_string() }, None)
+                .unwrap();
+        assert_eq!((suffixed.summary.matched, suffixed.summary.changed), (2, 1));
+        assert!(suffixed.translations[1].translated_text.is_empty());
+    }
+
+    #[test]
+    fn test_review_status_workflow() {
+        let approve = BulkOperation::SetReviewStatus { status: TranslationStatus::Approved };
+        let approved = run_bulk_operation(sample(), &EntryFilter::default(), &approve, None).unwrap();
+        // The untranslated entry can't be approved.
+        assert_eq!((approved.summary.matched, approved.summary.changed), (3, 2));
+        assert_eq!(approved.translations[1].status, TranslationStatus::MachineTranslated);
+
+        // Changing an approved text sends it back to review.
+        let filter = EntryFilter { review_statuses: vec![TranslationStatus::Approved], ..Default::default() };
+        let trim = BulkOperation::PostProcess { rule: PostProcessRule::TrimWhitespace };
+        let trimmed = run_bulk_operation(approved.translations, &filter, &trim, None).unwrap();
+        assert_eq!(trimmed.summary.changed, 2);
+        assert!(trimmed.translations.iter().all(|e| e.status != TranslationStatus::Approved));
+
+        let items = EntryFilter { source_files: vec!["www/data/Items.json".to_string()], ..Default::default() };
+        let result = run_bulk_operation(trimmed.translations, &items, &approve, None).unwrap();
+        let applied = entries_to_apply(result.translations, true);
+        assert_eq!(applied.iter().map(|e| e.translated_text.as_str()).collect::<Vec<_>>(), vec!["Potion"]);
+    }
+
+    #[test]
+    fn test_invalid_operation_changes_nothing() {
+        let operation = BulkOperation::PostProcess {
+            rule: PostProcessRule::RegexReplace { pattern: "(".to_string(), replacement: String::new() },
+        };
+        assert!(run_bulk_operation(sample(), &EntryFilter::default(), &operation, None).is_err());
+        let glossary = BulkOperation::PostProcess { rule: PostProcessRule::ApplyGlossary };
+        assert!(run_bulk_operation(sample(), &EntryFilter::default(), &glossary, None).is_err());
+    }
+
+    #[test]
+    fn test_update_entry() {
+        let mut translations = sample();
+        let mut update = EntryUpdate {
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "events[1].pages[0].list[2].parameters[0]".to_string(),
+            translated_text: "How are you?".to_string(),
+            status: None,
+        };
+        let updated = update_entry(&mut translations, &update).unwrap().unwrap();
+        assert_eq!(updated.status, TranslationStatus::Edited);
+        assert_eq!(translations[1].translated_text, "How are you?");
+
+        update.status = Some(TranslationStatus::Approved);
+        assert_eq!(update_entry(&mut translations, &update).unwrap().unwrap().status, TranslationStatus::Approved);
+        update.translated_text.clear();
+        assert!(update_entry(&mut translations, &update).is_err());
+        update.json_path = "[9].name".to_string();
+        assert!(update_entry(&mut translations, &update).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_find_replace_lists_changes() {
+        let request = FindReplaceRequest {
+            pattern: "potion".to_string(),
+            replacement: "Elixir".to_string(),
+            case_insensitive: true,
+            ..Default::default()
+        };
+        let (translations, result) = find_replace(sample(), &request).unwrap();
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!((result.changes[0].before.as_str(), result.changes[0].after.as_str()), ("Potion ", "Elixir "));
+        assert_eq!(result.summary.changed, 1);
+        assert_eq!(translations[2].status, TranslationStatus::Edited);
+        assert!(find_replace(sample(), &FindReplaceRequest { pattern: "(".to_string(), ..request }).is_err());
+    }
+}
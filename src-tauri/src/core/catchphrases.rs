@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fs;
+use crate::error::CoreError;
+use crate::models::character::{Catchphrase, Catchphrases};
+use crate::models::translation::SourceStringData;
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// Kept in the project's app data folder.
+pub const CATCHPHRASES_FILE_NAME: &str = "catchphrases.json";
+
+/// Times a speaker must say a line before it counts as a catchphrase, by default.
+pub const MIN_CATCHPHRASE_OCCURRENCES: usize = 2;
+
+/// Lines said verbatim by the same speaker at least `min_occurrences` times, most frequent first.
+pub fn find_catchphrases(entries: &[SourceStringData], min_occurrences: usize) -> Vec<Catchphrase> {
+    let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+    for entry in entries {
+        let Some(speaker) = entry.speaker.as_deref() else { continue };
+        if !entry.original_text.trim().is_empty() {
+            *counts.entry((speaker, entry.original_text.as_str())).or_default() += 1;
+        }
+    }
+    let mut found: Vec<Catchphrase> = counts
+        .into_iter()
+        .filter(|(_, occurrences)| *occurrences >= min_occurrences.max(2))
+        .map(|((speaker, text), occurrences)| Catchphrase {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            occurrences,
+            translation: None,
+            locked: false,
+        })
+        .collect();
+    found.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| (&a.speaker, &a.text).cmp(&(&b.speaker, &b.text))));
+    found
+}
+
+/// Detected catchphrases with the translations already stored for them. Stored ones no longer
+/// detected are kept while locked, so a translation isn't lost to a stricter threshold.
+pub fn merge_catchphrases(found: Vec<Catchphrase>, stored: &Catchphrases) -> Catchphrases {
+    let mut merged: Vec<Catchphrase> = found
+        .into_iter()
+        .map(|phrase| match stored.catchphrases.iter().find(|s| s.speaker == phrase.speaker && s.text == phrase.text) {
+            Some(s) => Catchphrase { translation: s.translation.clone(), locked: s.locked, ..phrase },
+            None => phrase,
+        })
+        .collect();
+    for phrase in &stored.catchphrases {
+        if phrase.locked && !merged.iter().any(|m| m.speaker == phrase.speaker && m.text == phrase.text) {
+            merged.push(phrase.clone());
+        }
+    }
+    Catchphrases { catchphrases: merged }
+}
+
+/// The catchphrase an entry says, if any.
+pub fn catchphrase_for<'a>(catchphrases: &'a Catchphrases, entry: &SourceStringData) -> Option<&'a Catchphrase> {
+    let speaker = entry.speaker.as_deref()?;
+    catchphrases.catchphrases.iter().find(|c| c.speaker == speaker && c.text == entry.original_text)
+}
+
+/// The locked translation of the catchphrase an entry says.
+pub fn locked_translation<'a>(catchphrases: &'a Catchphrases, entry: &SourceStringData) -> Option<&'a str> {
+    catchphrase_for(catchphrases, entry).filter(|c| c.locked).and_then(|c| c.translation.as_deref())
+}
+
+/// Prompt instruction for translating a catchphrase.
+pub fn catchphrase_instruction(phrase: &Catchphrase) -> String {
+    format!(
+        "This is a catchphrase {} says {} times in the game. Translate it as a short, memorable line that keeps its tone and reads naturally every time it comes up.",
+        phrase.speaker, phrase.occurrences
+    )
+}
+
+pub fn load_catchphrases(project_path: &str) -> Result<Catchphrases, CoreError> {
+    let path = app_data_dir(project_path).join(CATCHPHRASES_FILE_NAME);
+    if !path.exists() {
+        return Ok(Catchphrases::default());
+    }
+    let json = fs::read_to_string(&path)?;
+    serde_json::from_str(&json).map_err(|e| CoreError::JsonParse(format!("Failed to parse catchphrases: {}", e)))
+}
+
+pub fn save_catchphrases(project_path: &str, catchphrases: &Catchphrases) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, CATCHPHRASES_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(catchphrases)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize catchphrases: {}", e)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Adds a catchphrase or replaces the one with the same speaker and text, e.g. to correct or
+/// unlock its translation.
+pub fn store_catchphrase(project_path: &str, catchphrase: Catchphrase) -> Result<Catchphrases, CoreError> {
+    let mut catchphrases = load_catchphrases(project_path)?;
+    match catchphrases.catchphrases.iter_mut().find(|c| c.speaker == catchphrase.speaker && c.text == catchphrase.text) {
+        Some(existing) => *existing = catchphrase,
+        None => catchphrases.catchphrases.push(catchphrase),
+    }
+    save_catchphrases(project_path, &catchphrases)?;
+    Ok(catchphrases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(speaker: Option<&str>, text: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "events[1].pages[0].list[1].parameters[0]".to_string(),
+            dialogue_block_id: None,
+            speaker: speaker.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_catchphrases_are_repeated_lines_of_one_speaker() {
+        let entries = vec![
+            line(Some("ハロルド"), "任せとけ！"),
+            line(Some("ハロルド"), "任せとけ！"),
+            line(Some("ハロルド"), "任せとけ！"),
+            line(Some("マーシャ"), "任せとけ！"),
+            line(Some("マーシャ"), "ふふっ"),
+            line(Some("マーシャ"), "ふふっ"),
+            line(None, "……"),
+            line(None, "……"),
+        ];
+        let found = find_catchphrases(&entries, MIN_CATCHPHRASE_OCCURRENCES);
+        let keys: Vec<(&str, &str, usize)> = found.iter().map(|c| (c.speaker.as_str(), c.text.as_str(), c.occurrences)).collect();
+        assert_eq!(keys, vec![("ハロルド", "任せとけ！", 3), ("マーシャ", "ふふっ", 2)]);
+
+        let stored = Catchphrases {
+            catchphrases: vec![Catchphrase {
+                translation: Some("Leave it to me!".to_string()),
+                locked: true,
+                ..found[0].clone()
+            }],
+        };
+        let merged = merge_catchphrases(found, &stored);
+        assert_eq!(locked_translation(&merged, &entries[1]), Some("Leave it to me!"));
+        assert_eq!(locked_translation(&merged, &entries[3]), None);
+        assert!(catchphrase_for(&merged, &entries[4]).is_some_and(|c| !c.locked));
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use crate::core::rpgmv::characters::{scan_project_dialogue, DialogueScan};
+use crate::error::CoreError;
+use crate::models::character::{CharacterAppearance, CharacterProfile, CharacterSampleLine, CharacterSheet, CharacterSummary};
+use crate::models::glossary::Glossary;
+use crate::models::translation::WorkingTranslation;
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// File name of the character style profiles (inside `.translate-ai/`).
+pub const CHARACTER_PROFILES_FILE_NAME: &str = "characters.json";
+
+const DEFAULT_SAMPLE_LINES: usize = 5;
+
+pub fn load_character_profiles(project_path: &str) -> Result<Vec<CharacterProfile>, CoreError> {
+    let path = app_data_dir(project_path).join(CHARACTER_PROFILES_FILE_NAME);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read character profiles {:?}: {}", path, e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| CoreError::JsonParse(format!("Invalid character profiles file {:?}: {}", path, e)))
+}
+
+/// Creates or replaces the profile with the same key. An empty style removes the profile.
+pub fn save_character_profile(project_path: &str, profile: CharacterProfile) -> Result<Vec<CharacterProfile>, CoreError> {
+    let mut profiles = load_character_profiles(project_path)?;
+    profiles.retain(|p| p.key != profile.key);
+    if !profile.style.trim().is_empty() {
+        profiles.push(profile);
+    }
+    let path = ensure_app_data_file(project_path, CHARACTER_PROFILES_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(&profiles)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize character profiles: {}", e)))?;
+    fs::write(&path, json)
+        .map_err(|e| CoreError::Io(format!("Failed to write character profiles to {:?}: {}", path, e)))?;
+    Ok(profiles)
+}
+
+/// Current translation of a character name: the glossary first, then the actor's name entry
+/// (`Actors.json [id].name`), then any translated entry with exactly that text.
+fn name_translation(
+    name: &str,
+    actor_id: Option<u32>,
+    glossary: &Glossary,
+    translations: &[WorkingTranslation],
+) -> Option<String> {
+    if let Some(term) = glossary.terms.iter().find(|t| t.source == name) {
+        return Some(term.target.clone());
+    }
+    let translated = |t: &&WorkingTranslation| t.error.is_none() && !t.translated_text.is_empty();
+    actor_id
+        .and_then(|id| {
+            translations.iter().filter(translated).find(|t| {
+                t.source_file.ends_with("Actors.json") && t.json_path == format!("[{}].name", id)
+            })
+        })
+        .or_else(|| translations.iter().filter(translated).find(|t| t.original_text == name))
+        .map(|t| t.translated_text.clone())
+}
+
+/// Builds the dashboard rows from a dialogue scan, most talkative characters first.
+pub fn build_character_summaries(
+    scan: &DialogueScan,
+    glossary: &Glossary,
+    translations: &[WorkingTranslation],
+    profiles: &[CharacterProfile],
+    sample_count: usize,
+) -> Vec<CharacterSummary> {
+    let translated_lines: HashMap<(&str, &str), &str> = translations
+        .iter()
+        .filter(|t| t.error.is_none() && !t.translated_text.is_empty())
+        .map(|t| ((t.source_file.as_str(), t.json_path.as_str()), t.translated_text.as_str()))
+        .collect();
+
+    let mut summaries: Vec<CharacterSummary> = scan
+        .speakers
+        .iter()
+        .map(|speaker| {
+            let mut appearances: Vec<CharacterAppearance> = Vec::new();
+            for line in &speaker.lines {
+                match appearances.iter_mut().find(|a| a.source_file == line.source_file) {
+                    Some(appearance) => appearance.line_count += 1,
+                    None => appearances.push(CharacterAppearance {
+                        source_file: line.source_file.clone(),
+                        map_name: scan.map_names.get(&line.source_file).cloned(),
+                        line_count: 1,
+                    }),
+                }
+            }
+            CharacterSummary {
+                key: speaker.key.clone(),
+                name: speaker.name.clone(),
+                actor_id: speaker.actor_id,
+                line_count: speaker.lines.len(),
+                appearances,
+                name_translation: name_translation(&speaker.name, speaker.actor_id, glossary, translations),
+                style_profile: profiles.iter().find(|p| p.key == speaker.key).map(|p| p.style.clone()),
+                sample_lines: speaker
+                    .lines
+                    .iter()
+                    .take(sample_count)
+                    .map(|line| CharacterSampleLine {
+                        source_file: line.source_file.clone(),
+                        json_path: line.json_path.clone(),
+                        text: line.text.clone(),
+                        translation: translated_lines
+                            .get(&(line.source_file.as_str(), line.json_path.as_str()))
+                            .map(|t| t.to_string()),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.line_count));
+    summaries
+}
+
+/// Scans the project and assembles the characters tab data.
+pub fn character_dashboard(
+    project_path: &str,
+    glossary: &Glossary,
+    translations: &[WorkingTranslation],
+    sample_count: Option<usize>,
+) -> Result<Vec<CharacterSummary>, CoreError> {
+    let scan = scan_project_dialogue(project_path)?;
+    if !scan.errors.is_empty() {
+        eprintln!("Warnings while scanning dialogue:\n{}", scan.errors.join("\n"));
+    }
+    let profiles = load_character_profiles(project_path)?;
+    Ok(build_character_summaries(
+        &scan,
+        glossary,
+        translations,
+        &profiles,
+        sample_count.unwrap_or(DEFAULT_SAMPLE_LINES),
+    ))
+}
+
+/// Checks that every character sheet has a name of its own.
+pub fn validate_character_sheets(sheets: &[CharacterSheet]) -> Result<(), CoreError> {
+    let mut names = HashSet::new();
+    for sheet in sheets {
+        let name = sheet.name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Config("Character sheets need a name".to_string()));
+        }
+        if !names.insert(name) {
+            return Err(CoreError::Config(format!("Two character sheets are named '{}'", name)));
+        }
+    }
+    Ok(())
+}
+
+/// The sheet of the character speaking a line, by their original name.
+pub fn sheet_for<'a>(sheets: &'a [CharacterSheet], speaker: &str) -> Option<&'a CharacterSheet> {
+    sheets.iter().find(|sheet| sheet.name.trim() == speaker.trim())
+}
+
+/// The sheet as an instruction for the provider, e.g. "Voice of ハロルド: male; brash, short
+/// sentences; refers to themself as 俺 (rough); referred to as he/him."
+pub fn character_instruction(sheet: &CharacterSheet) -> String {
+    let mut traits: Vec<String> = Vec::new();
+    if let Some(gender) = sheet.gender.as_deref().filter(|g| !g.trim().is_empty()) {
+        traits.push(gender.trim().to_string());
+    }
+    if !sheet.speech_style.trim().is_empty() {
+        traits.push(sheet.speech_style.trim().to_string());
+    }
+    if let Some(first_person) = sheet.first_person.as_deref().filter(|p| !p.trim().is_empty()) {
+        traits.push(format!("refers to themself as {}", first_person.trim()));
+    }
+    if let Some(third_person) = sheet.third_person.as_deref().filter(|p| !p.trim().is_empty()) {
+        traits.push(format!("referred to as {}", third_person.trim()));
+    }
+    format!("Voice of {}: {}. Keep it consistent with their other lines.", sheet.name.trim(), traits.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use std::path::Path;
+    use crate::models::glossary::GlossaryTerm;
+    use tempfile::tempdir;
+
+    fn write(project: &Path, name: &str, content: &str) {
+        let dir = project.join("www").join("data");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_character_dashboard() {
+        let dir = tempdir().unwrap();
+        let project = dir.path();
+        write(project, "Actors.json", r#"[null,{"id":1,"name":"ハロルド","faceName":"Actor1","faceIndex":0}]"#);
+        write(project, "MapInfos.json", r#"[null,{"id":1,"name":"王都"}]"#);
+        write(
+            project,
+            "Map001.json",
+            r#"{"events":[null,{"id":1,"name":"EV","pages":[{"list":[
+                {"code":101,"parameters":["Actor1",0,0,2]},{"code":401,"parameters":["行くぞ"]},
+                {"code":101,"parameters":["",0,0,2,"村人"]},{"code":401,"parameters":["ようこそ"]},
+                {"code":0,"parameters":[]}]}]}]}"#,
+        );
+        write(
+            project,
+            "CommonEvents.json",
+            r#"[null,{"id":1,"name":"CE","list":[
+                {"code":101,"parameters":["Actor1",0,0,2]},{"code":401,"parameters":["待て"]},{"code":0,"parameters":[]}]}]"#,
+        );
+        let project_path = project.to_str().unwrap();
+        save_character_profile(project_path, CharacterProfile { key: "actor:1".to_string(), style: "Brash, short sentences".to_string() })
+            .unwrap();
+
+        let glossary = Glossary {
+            terms: vec![GlossaryTerm { source: "村人".to_string(), target: "Villager".to_string(), references: Vec::new() }],
+        };
+        let translations = vec![WorkingTranslation {
+            object_id: 1,
+            original_text: "ハロルド".to_string(),
+            translated_text: "Harold".to_string(),
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: "[1].name".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }];
+
+        let dashboard = character_dashboard(project_path, &glossary, &translations, None).unwrap();
+        assert_eq!(dashboard.len(), 2);
+        let harold = &dashboard[0];
+        assert_eq!((harold.line_count, harold.name_translation.as_deref()), (2, Some("Harold")));
+        assert_eq!(harold.style_profile.as_deref(), Some("Brash, short sentences"));
+        assert_eq!(harold.appearances[1].map_name.as_deref(), Some("王都"));
+        assert_eq!(harold.appearances[0].source_file, "www/data/CommonEvents.json");
+        assert_eq!(dashboard[1].name_translation.as_deref(), Some("Villager"));
+    }
+
+    #[test]
+    fn test_character_sheets() {
+        let sheets = vec![
+            CharacterSheet {
+                name: "ハロルド".to_string(),
+                gender: Some("male".to_string()),
+                speech_style: "Brash, short sentences".to_string(),
+                first_person: Some("俺 (rough)".to_string()),
+                third_person: Some("he/him".to_string()),
+            },
+            CharacterSheet { name: "村人".to_string(), speech_style: "Polite".to_string(), ..Default::default() },
+        ];
+        assert!(validate_character_sheets(&sheets).is_ok());
+        assert_eq!(
+            character_instruction(sheet_for(&sheets, "ハロルド").unwrap()),
+            "Voice of ハロルド: male; Brash, short sentences; refers to themself as 俺 (rough); referred to as he/him. Keep it consistent with their other lines."
+        );
+        assert!(sheet_for(&sheets, "セレン").is_none());
+
+        let duplicate = vec![sheets[1].clone(), sheets[1].clone()];
+        assert!(validate_character_sheets(&duplicate).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::Regex;
+use crate::core::characters::validate_character_sheets;
+use crate::core::do_not_translate::DoNotTranslateRules;
+use crate::core::font_coverage::parse_range;
+use crate::core::generic::project::GenericRules;
+use crate::core::presets::validate_overrides;
+use crate::core::rpgmv::locale_output::validate_locale;
+use crate::core::rpgmv::note_tags::NoteTagMatcher;
+use crate::core::rpgmv::script_calls::ScriptCallMatcher;
+use crate::core::rpgmv::word_wrap::validate_word_wrap;
+use crate::error::CoreError;
+use crate::models::project_config::{FileParser, ParserOverride, ProjectConfig};
+use crate::utils::archive_time::archive_timestamp;
+use crate::utils::project_paths::app_data_dir;
+
+/// File name of the project configuration, at the project root.
+pub const PROJECT_CONFIG_FILE_NAME: &str = "translate-ai.json";
+
+/// Default glossary location (inside `.translate-ai/`).
+pub const DEFAULT_GLOSSARY_FILE_NAME: &str = "glossary.json";
+
+pub fn project_config_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(PROJECT_CONFIG_FILE_NAME)
+}
+
+/// Loads the project configuration, or the defaults if the project has none.
+pub fn load_project_config(project_path: &str) -> Result<ProjectConfig, CoreError> {
+    let path = project_config_path(project_path);
+    if !path.is_file() {
+        return Ok(ProjectConfig::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read project config {:?}: {}", path, e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| CoreError::Config(format!("Invalid project config {:?}: {}", path, e)))
+}
+
+/// Saves the configuration after checking its file globs, generic rule selectors, output locale
+/// and timestamp, note tag and script call patterns, preset, do-not-translate rules, word wrap,
+/// font ranges and character sheets.
+pub fn save_project_config(project_path: &str, config: &ProjectConfig) -> Result<(), CoreError> {
+    IgnoredFiles::new(&config.ignored_files)?;
+    ParserOverrides::new(&config.parser_overrides)?;
+    GenericRules::new(&config.generic_rules)?;
+    if let Some(locale) = &config.output_locale {
+        validate_locale(locale)?;
+    }
+    if let Some(timestamp) = config.output_timestamp {
+        archive_timestamp(timestamp)?;
+    }
+    NoteTagMatcher::new(&config.note_tags)?;
+    ScriptCallMatcher::new(&config.script_calls)?;
+    if let Some(overrides) = &config.preset {
+        validate_overrides(overrides)?;
+    }
+    DoNotTranslateRules::new(&config.do_not_translate)?;
+    if let Some(word_wrap) = &config.word_wrap {
+        validate_word_wrap(word_wrap)?;
+    }
+    for range in config.font_ranges.iter().filter(|range| !range.trim().is_empty()) {
+        parse_range(range)?;
+    }
+    validate_character_sheets(&config.characters)?;
+    let path = project_config_path(project_path);
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize project config: {}", e)))?;
+    fs::write(&path, json).map_err(|e| CoreError::Io(format!("Failed to write project config to {:?}: {}", path, e)))
+}
+
+/// Loads, changes and saves the project configuration.
+pub fn update_project_config(
+    project_path: &str,
+    change: impl FnOnce(&mut ProjectConfig),
+) -> Result<ProjectConfig, CoreError> {
+    let mut config = load_project_config(project_path)?;
+    change(&mut config);
+    save_project_config(project_path, &config)?;
+    Ok(config)
+}
+
+/// Where the project's glossary lives: the configured path, or `.translate-ai/glossary.json`.
+pub fn glossary_file_path(project_path: &str) -> Result<PathBuf, CoreError> {
+    Ok(match load_project_config(project_path)?.glossary_path {
+        Some(path) if !path.trim().is_empty() => Path::new(project_path).join(path.trim()),
+        _ => app_data_dir(project_path).join(DEFAULT_GLOSSARY_FILE_NAME),
+    })
+}
+
+/// A `*`/`?` glob matched against the path relative to the project root, or against the file
+/// name when it has no `/`.
+pub(crate) struct FileGlob {
+    whole_path: bool,
+    re: Regex,
+}
+
+impl FileGlob {
+    pub(crate) fn new(glob: &str) -> Result<Self, CoreError> {
+        let glob = glob.trim().replace('\\', "/");
+        let mut pattern = String::from("(?i)^");
+        for c in glob.chars() {
+            match c {
+                '*' => pattern.push_str(".*"),
+                '?' => pattern.push('.'),
+                c => pattern.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        pattern.push('$');
+        let re = Regex::new(&pattern).map_err(|e| CoreError::Config(format!("Invalid file pattern '{}': {}", glob, e)))?;
+        Ok(FileGlob { whole_path: glob.contains('/'), re })
+    }
+
+    pub(crate) fn matches(&self, relative_path: &str) -> bool {
+        let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+        self.re.is_match(if self.whole_path { relative_path } else { file_name })
+    }
+}
+
+/// The configured `ignoredFiles` globs, compiled.
+pub struct IgnoredFiles {
+    globs: Vec<FileGlob>,
+}
+
+impl IgnoredFiles {
+    pub fn new(globs: &[String]) -> Result<Self, CoreError> {
+        let globs = globs
+            .iter()
+            .filter(|glob| !glob.trim().is_empty())
+            .map(|glob| FileGlob::new(glob))
+            .collect::<Result<_, _>>()?;
+        Ok(IgnoredFiles { globs })
+    }
+
+    /// `relative_path` is relative to the project root, e.g. "www/data/Map001.json".
+    pub fn is_ignored(&self, relative_path: &str) -> bool {
+        self.globs.iter().any(|glob| glob.matches(relative_path))
+    }
+}
+
+/// The configured `parserOverrides`, compiled.
+#[derive(Default)]
+pub struct ParserOverrides {
+    overrides: Vec<(FileGlob, FileParser)>,
+}
+
+impl ParserOverrides {
+    pub fn new(overrides: &[ParserOverride]) -> Result<Self, CoreError> {
+        let overrides = overrides
+            .iter()
+            .map(|o| Ok((FileGlob::new(&o.files)?, o.parser)))
+            .collect::<Result<_, CoreError>>()?;
+        Ok(ParserOverrides { overrides })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn parser_for(&self, relative_path: &str) -> Option<FileParser> {
+        self.overrides.iter().find(|(glob, _)| glob.matches(relative_path)).map(|(_, parser)| *parser)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::note_tag::NoteTagConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_globs() {
+        let ignored = IgnoredFiles::new(&["Map9??.json".to_string(), "www/js/*".to_string(), " ".to_string()]).unwrap();
+        assert!(ignored.is_ignored("www/data/Map901.json"));
+        assert!(!ignored.is_ignored("www/data/Map001.json"));
+        assert!(ignored.is_ignored("www/js/plugins.js"));
+        assert!(!ignored.is_ignored("js/plugins.js"));
+
+        let overrides = ParserOverrides::new(&[
+            ParserOverride { files: "www/*.txt".to_string(), parser: FileParser::PlainText },
+            ParserOverride { files: "*plugins*.js".to_string(), parser: FileParser::Plugins },
+        ])
+        .unwrap();
+        assert_eq!(overrides.parser_for("www/Credits.TXT"), Some(FileParser::PlainText));
+        assert_eq!(overrides.parser_for("www/js/plugins_extra.js"), Some(FileParser::Plugins));
+        assert_eq!(overrides.parser_for("www/data/Map001.json"), None);
+    }
+
+    #[test]
+    fn test_save_and_load_project_config() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        assert!(load_project_config(project_path).unwrap().glossary_path.is_none());
+
+        let invalid = ProjectConfig {
+            note_tags: NoteTagConfig { tag_patterns: vec!["(".to_string()] },
+            ..Default::default()
+        };
+        assert!(matches!(save_project_config(project_path, &invalid), Err(CoreError::Config(_))));
+        let invalid = ProjectConfig { output_timestamp: Some(0), ..Default::default() };
+        assert!(matches!(save_project_config(project_path, &invalid), Err(CoreError::Config(_))));
+
+        update_project_config(project_path, |config| {
+            config.target_language = Some("en".to_string());
+            config.glossary_path = Some("terms/glossary.json".to_string());
+        })
+        .unwrap();
+        let loaded = load_project_config(project_path).unwrap();
+        assert_eq!(loaded.target_language.as_deref(), Some("en"));
+        assert_eq!(glossary_file_path(project_path).unwrap(), dir.path().join("terms/glossary.json"));
+    }
+}
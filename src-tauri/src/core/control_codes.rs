@@ -0,0 +1,139 @@
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::error::CoreError;
+use crate::models::validation::ValidationRuleSet;
+
+// RPG Maker MV control codes (\V[1], \N[2], \C[0], \I[64], \G, \{, \!, ...) and %1-style
+// message arguments. These must be carried over verbatim into the translation.
+pub const BUILTIN_CODE_PATTERN: &str = r"\\[A-Za-z]+\[\d+\]|\\[A-Za-z{}!.|<>^$]|%\d+";
+
+/// The control codes of a project: the engine's own plus those added by its message plugins
+/// (`\AF[Harold]`, `\FS[+2]`, `<WordWrap>`, ...), registered as regexes in the project's
+/// validation ruleset. Each match is an atomic token that must not be translated or split.
+#[derive(Debug, Clone)]
+pub struct ControlCodes {
+    // Custom patterns first, so they win over a shorter built-in match at the same position.
+    all: Regex,
+    custom: Option<Regex>,
+}
+
+// Compiled once; clones share it.
+fn builtin_codes() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(BUILTIN_CODE_PATTERN).expect("built-in code pattern is valid"))
+}
+
+impl Default for ControlCodes {
+    fn default() -> Self {
+        ControlCodes { all: builtin_codes().clone(), custom: None }
+    }
+}
+
+/// A text whose custom codes were replaced by numbered tokens before translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedText {
+    pub text: String,
+    // Codes by token number.
+    pub codes: Vec<String>,
+}
+
+fn mask_token(index: usize) -> String {
+    format!("⟦{}⟧", index + 1)
+}
+
+impl MaskedText {
+    /// Puts the codes back in place of their tokens. Tokens the provider dropped leave their
+    /// code missing, which validation reports.
+    pub fn unmask(&self, translated: &str) -> String {
+        self.codes
+            .iter()
+            .enumerate()
+            .fold(translated.to_string(), |text, (index, code)| text.replace(&mask_token(index), code))
+    }
+}
+
+impl ControlCodes {
+    pub fn new(custom_patterns: &[String]) -> Result<Self, CoreError> {
+        let patterns: Vec<&String> = custom_patterns.iter().filter(|p| !p.trim().is_empty()).collect();
+        if patterns.is_empty() {
+            return Ok(ControlCodes::default());
+        }
+        for pattern in &patterns {
+            Regex::new(pattern)
+                .map_err(|e| CoreError::Config(format!("Invalid control code pattern '{}': {}", pattern, e)))?;
+        }
+        let custom = patterns.iter().map(|p| format!("(?:{})", p)).collect::<Vec<_>>().join("|");
+        let compile = |pattern: &str| {
+            Regex::new(pattern).map_err(|e| CoreError::Config(format!("Invalid control code patterns: {}", e)))
+        };
+        Ok(ControlCodes {
+            all: compile(&format!("{}|{}", custom, BUILTIN_CODE_PATTERN))?,
+            custom: Some(compile(&custom)?),
+        })
+    }
+
+    /// The codes of a ruleset; invalid patterns (which `check_ruleset` refuses) fall back to the
+    /// built-in codes.
+    pub fn from_ruleset(ruleset: &ValidationRuleSet) -> Self {
+        ControlCodes::new(&ruleset.control_codes).unwrap_or_else(|e| {
+            eprintln!("Ignoring the ruleset's control codes: {}", e);
+            ControlCodes::default()
+        })
+    }
+
+    /// Codes found in `text`, sorted so two texts can be compared regardless of word order.
+    pub fn extract(&self, text: &str) -> Vec<String> {
+        let mut found: Vec<String> = self.all.find_iter(text).map(|m| m.as_str().to_string()).collect();
+        found.sort();
+        found
+    }
+
+    /// `text` without its codes, e.g. to measure how wide it is drawn.
+    pub fn strip(&self, text: &str) -> String {
+        self.all.replace_all(text, "").into_owned()
+    }
+
+    /// `text` without its custom codes, for checks that only know the engine's codes.
+    pub fn strip_custom(&self, text: &str) -> String {
+        match &self.custom {
+            Some(custom) => custom.replace_all(text, "").into_owned(),
+            None => text.to_string(),
+        }
+    }
+
+    /// Replaces the custom codes with tokens a provider leaves alone (the engine's own codes are
+    /// known to providers and sent as they are).
+    pub fn mask_custom(&self, text: &str) -> MaskedText {
+        let Some(custom) = &self.custom else {
+            return MaskedText { text: text.to_string(), codes: Vec::new() };
+        };
+        let mut codes = Vec::new();
+        let masked = custom.replace_all(text, |captures: &regex::Captures| {
+            codes.push(captures[0].to_string());
+            mask_token(codes.len() - 1)
+        });
+        MaskedText { text: masked.into_owned(), codes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_codes_are_atomic() {
+        let codes = ControlCodes::new(&[r"\\AF\[[^\]]+\]".to_string(), r"<WordWrap>".to_string()]).unwrap();
+        let text = r"<WordWrap>\AF[Harold]\C[2]Hello\C[0], %1!";
+        assert_eq!(codes.extract(text), vec!["%1", r"<WordWrap>", r"\AF[Harold]", r"\C[0]", r"\C[2]"]);
+        // Without the definition, only the "\A" of the plugin code is recognized.
+        assert_eq!(ControlCodes::default().extract(text), vec!["%1", r"\A", r"\C[0]", r"\C[2]"]);
+        assert_eq!(codes.strip(text), "Hello, !");
+        assert_eq!(codes.strip_custom(text), r"\C[2]Hello\C[0], %1!");
+
+        let masked = codes.mask_custom(text);
+        assert_eq!(masked.text, r"⟦1⟧⟦2⟧\C[2]Hello\C[0], %1!");
+        assert_eq!(masked.unmask(r"⟦1⟧⟦2⟧\C[2]Bonjour\C[0], %1 !"), r"<WordWrap>\AF[Harold]\C[2]Bonjour\C[0], %1 !");
+
+        assert!(ControlCodes::new(&["(".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+use crate::core::glossary::terms_in_text;
+use crate::core::quality::is_cjk_char;
+use crate::models::glossary::Glossary;
+use crate::models::provider::{CostEstimate, ModelPricing, ProviderConfig};
+use crate::models::translation::SourceStringData;
+
+/// Tokens of the built-in instructions sent with every request to a prompt-based provider.
+pub const PROMPT_OVERHEAD_TOKENS: u64 = 60;
+
+/// Translations come back somewhat longer than their source, counted in tokens.
+const OUTPUT_TOKEN_RATIO: f64 = 1.2;
+
+/// DeepL API Pro usage price, in USD per million billed characters (the monthly base fee aside).
+pub const DEEPL_PRO_USD_PER_MILLION_CHARACTERS: f64 = 25.0;
+
+/// Characters a month included in DeepL API Free.
+pub const DEEPL_FREE_MONTHLY_CHARACTERS: u64 = 500_000;
+
+/// List prices of common hosted models, in USD per million input and output tokens. Matched by
+/// prefix, longest first, so dated versions ("gpt-4o-2024-08-06") find their model.
+const MODEL_PRICES: [(&str, f64, f64); 8] = [
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-3-5-sonnet", 3.00, 15.00),
+];
+
+/// Rough token count without the model's tokenizer: about one token per CJK character and one
+/// per four characters of other scripts.
+pub fn estimate_tokens(text: &str) -> u64 {
+    let (cjk, other) = text.chars().fold((0u64, 0u64), |(cjk, other), c| {
+        if is_cjk_char(c) {
+            (cjk + 1, other)
+        } else {
+            (cjk, other + 1)
+        }
+    });
+    cjk + other.div_ceil(4)
+}
+
+/// The built-in price of a hosted model, if known.
+pub fn builtin_model_pricing(model: &str) -> Option<ModelPricing> {
+    let model = model.to_ascii_lowercase();
+    let model = model.rsplit('/').next().unwrap_or_default();
+    MODEL_PRICES
+        .iter()
+        .filter(|(prefix, _, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _, _)| prefix.len())
+        .map(|&(_, input_per_million, output_per_million)| ModelPricing { input_per_million, output_per_million })
+}
+
+fn is_local_url(url: &str) -> bool {
+    let host = url.split("://").nth(1).unwrap_or(url).split(['/', ':']).next().unwrap_or_default();
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]")
+}
+
+/// Estimates what translating `entries` with `provider` takes: requests (one per entry, or per
+/// message box), source characters, tokens including the instructions and the glossary terms
+/// each entry would be sent with, and the approximate price for paid APIs. Translation memory
+/// hits and retries aren't anticipated.
+pub fn estimate_cost(entries: &[SourceStringData], provider: &ProviderConfig, glossary: &Glossary) -> CostEstimate {
+    let mut blocks = HashSet::new();
+    let (mut requests, mut characters, mut text_tokens, mut glossary_tokens) = (0u64, 0u64, 0u64, 0u64);
+    for entry in entries {
+        if entry.dialogue_block_id.as_ref().map_or(true, |id| blocks.insert(id.clone())) {
+            requests += 1;
+        }
+        characters += entry.original_text.chars().count() as u64;
+        text_tokens += estimate_tokens(&entry.original_text);
+        glossary_tokens += terms_in_text(glossary, &entry.original_text)
+            .iter()
+            .map(|term| estimate_tokens(&format!("- {} => {}", term.source, term.target)))
+            .sum::<u64>();
+    }
+    let input_tokens = text_tokens + glossary_tokens + requests * PROMPT_OVERHEAD_TOKENS;
+    let output_tokens = (text_tokens as f64 * OUTPUT_TOKEN_RATIO).ceil() as u64;
+    let mut estimate = CostEstimate {
+        provider: String::new(),
+        model: None,
+        requests,
+        characters,
+        input_tokens,
+        output_tokens,
+        estimated_cost_usd: None,
+        notes: Vec::new(),
+    };
+
+    match provider {
+        ProviderConfig::Ollama => {
+            estimate.provider = "ollama".to_string();
+            estimate.estimated_cost_usd = Some(0.0);
+            estimate.notes.push("Runs on this machine; there is no API cost.".to_string());
+        }
+        ProviderConfig::Deepl(settings) => {
+            estimate.provider = "deepl".to_string();
+            // DeepL bills source characters; prompts and tokens don't apply.
+            (estimate.input_tokens, estimate.output_tokens) = (0, 0);
+            if settings.uses_free_api() {
+                estimate.estimated_cost_usd = Some(0.0);
+                if characters > DEEPL_FREE_MONTHLY_CHARACTERS {
+                    estimate.notes.push(format!(
+                        "{} characters exceed the {} a month of DeepL API Free; the batch will stop at the quota.",
+                        characters, DEEPL_FREE_MONTHLY_CHARACTERS
+                    ));
+                }
+            } else {
+                estimate.estimated_cost_usd =
+                    Some(characters as f64 / 1_000_000.0 * DEEPL_PRO_USD_PER_MILLION_CHARACTERS);
+                estimate.notes.push("Excludes the monthly DeepL API Pro subscription fee.".to_string());
+            }
+        }
+        ProviderConfig::OpenAiCompatible(settings) => {
+            estimate.provider = "openai".to_string();
+            estimate.model = Some(settings.model.clone());
+            if is_local_url(&settings.base_url) {
+                estimate.estimated_cost_usd = Some(0.0);
+                estimate.notes.push("Local server; there is no API cost.".to_string());
+            } else if let Some(pricing) = settings.pricing.or_else(|| builtin_model_pricing(&settings.model)) {
+                estimate.estimated_cost_usd = Some(
+                    (input_tokens as f64 * pricing.input_per_million + output_tokens as f64 * pricing.output_per_million)
+                        / 1_000_000.0,
+                );
+                estimate.notes.push("Token counts are approximate; expect the bill within ±30%.".to_string());
+            } else {
+                estimate.notes.push(format!(
+                    "No known price for model {}; set its pricing in the provider settings.",
+                    settings.model
+                ));
+            }
+        }
+    }
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::glossary::GlossaryTerm;
+    use crate::models::provider::{DeeplSettings, OpenAiSettings};
+
+    fn entry(text: &str, block: Option<&str>) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "a".to_string(),
+            dialogue_block_id: block.map(str::to_string),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_estimate_cost_per_provider() {
+        assert_eq!(estimate_tokens("ハロルド"), 4);
+        assert_eq!(estimate_tokens("Hello!"), 2);
+        assert_eq!(builtin_model_pricing("openai/gpt-4o-mini-2024-07-18").unwrap().input_per_million, 0.15);
+        assert!(builtin_model_pricing("mistral").is_none());
+
+        let entries = vec![entry("ハロルドが来た", Some("b1")), entry("やあ", Some("b1")), entry("ポーション", None)];
+        let glossary = Glossary {
+            terms: vec![GlossaryTerm { source: "ハロルド".to_string(), target: "Harold".to_string(), references: Vec::new() }],
+        };
+
+        let ollama = estimate_cost(&entries, &ProviderConfig::Ollama, &glossary);
+        assert_eq!((ollama.requests, ollama.characters), (2, 14));
+        // 14 text tokens, 7 for the glossary line and the instructions of 2 requests.
+        assert_eq!((ollama.input_tokens, ollama.output_tokens), (14 + 7 + 2 * PROMPT_OVERHEAD_TOKENS, 17));
+        assert_eq!(ollama.estimated_cost_usd, Some(0.0));
+
+        let pro = DeeplSettings { api_key: "key".to_string(), ..Default::default() };
+        let deepl = estimate_cost(&entries, &ProviderConfig::Deepl(pro), &glossary);
+        assert_eq!(deepl.estimated_cost_usd, Some(14.0 * 25.0 / 1_000_000.0));
+
+        let mut settings = OpenAiSettings {
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: None,
+            model: "my-fine-tune".to_string(),
+            temperature: None,
+            system_prompt: None,
+            pricing: None,
+        };
+        assert_eq!(estimate_cost(&entries, &ProviderConfig::OpenAiCompatible(settings.clone()), &glossary).estimated_cost_usd, None);
+        settings.pricing = Some(ModelPricing { input_per_million: 1.0, output_per_million: 2.0 });
+        let openai = estimate_cost(&entries, &ProviderConfig::OpenAiCompatible(settings), &glossary);
+        assert_eq!(openai.estimated_cost_usd, Some((141.0 + 17.0 * 2.0) / 1_000_000.0));
+    }
+}
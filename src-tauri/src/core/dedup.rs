@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::core::quality::{sample_category, SampleCategory};
+use crate::models::translation::{DedupScope, DedupSettings, DuplicateOccurrence, SourceStringData, WorkingTranslation};
+
+type DedupKey = (String, Option<SampleCategory>);
+
+fn dedup_key(entry: &SourceStringData, scope: DedupScope) -> DedupKey {
+    let context = match scope {
+        DedupScope::Text => None,
+        DedupScope::TextAndContext => Some(sample_category(entry)),
+    };
+    (entry.original_text.clone(), context)
+}
+
+fn occurrence(entry: &SourceStringData) -> DuplicateOccurrence {
+    DuplicateOccurrence { source_file: entry.source_file.clone(), json_path: entry.json_path.clone() }
+}
+
+/// Lines of message boxes are translated together with their box and never shared.
+fn is_shareable(entry: &SourceStringData, separate: &HashSet<&DuplicateOccurrence>) -> bool {
+    entry.dialogue_block_id.is_none() && !separate.contains(&occurrence(entry))
+}
+
+/// A string found more than once in a batch, with where it occurs.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub original_text: String,
+    // Set when grouping by text and context.
+    pub context: Option<SampleCategory>,
+    pub occurrences: Vec<DuplicateOccurrence>,
+}
+
+/// Strings that would share one translation under `scope`, most repeated first, so occurrences
+/// the shared translation doesn't fit can be marked to be translated separately.
+pub fn find_duplicate_groups(entries: &[SourceStringData], scope: DedupScope) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    let mut positions: HashMap<DedupKey, usize> = HashMap::new();
+    for entry in entries.iter().filter(|e| is_shareable(e, &HashSet::new())) {
+        let key = dedup_key(entry, scope);
+        match positions.get(&key) {
+            Some(&position) => groups[position].occurrences.push(occurrence(entry)),
+            None => {
+                positions.insert(key.clone(), groups.len());
+                groups.push(DuplicateGroup { original_text: key.0, context: key.1, occurrences: vec![occurrence(entry)] });
+            }
+        }
+    }
+    groups.retain(|group| group.occurrences.len() > 1);
+    groups.sort_by_key(|group| std::cmp::Reverse(group.occurrences.len()));
+    groups
+}
+
+/// Where each entry of the batch gets its translation from: itself, or the first occurrence of
+/// its string.
+pub struct DedupPlan {
+    occurrences: Vec<(SourceStringData, DuplicateOccurrence)>,
+}
+
+impl DedupPlan {
+    /// Entries whose translation is copied from another one.
+    pub fn copies(&self) -> usize {
+        self.occurrences.iter().filter(|(entry, from)| occurrence(entry) != *from).count()
+    }
+
+    /// Every entry of the batch, in its order, with the translation of the entry it was
+    /// deduplicated to. Entries whose translation is missing (cancelled batch) are left out.
+    pub fn fan_out(&self, results: Vec<WorkingTranslation>) -> Vec<WorkingTranslation> {
+        let translated: HashMap<DuplicateOccurrence, WorkingTranslation> = results
+            .into_iter()
+            .map(|t| (DuplicateOccurrence { source_file: t.source_file.clone(), json_path: t.json_path.clone() }, t))
+            .collect();
+        self.occurrences
+            .iter()
+            .filter_map(|(entry, from)| {
+                let mut translation = translated.get(from)?.clone();
+                translation.object_id = entry.object_id;
+                translation.source_file = entry.source_file.clone();
+                translation.json_path = entry.json_path.clone();
+                Some(translation)
+            })
+            .collect()
+    }
+}
+
+/// The entries to send for translation, one per distinct string, and the plan that copies
+/// their translations back to every occurrence.
+pub fn deduplicate(entries: Vec<SourceStringData>, settings: &DedupSettings) -> (Vec<SourceStringData>, DedupPlan) {
+    let separate: HashSet<&DuplicateOccurrence> = settings.separate.iter().collect();
+    let mut first: HashMap<DedupKey, DuplicateOccurrence> = HashMap::new();
+    let mut unique = Vec::new();
+    let mut occurrences = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let own = occurrence(&entry);
+        let from = match is_shareable(&entry, &separate) {
+            true => first.entry(dedup_key(&entry, settings.scope)).or_insert_with(|| own.clone()).clone(),
+            false => own.clone(),
+        };
+        if from == own {
+            unique.push(entry.clone());
+        }
+        occurrences.push((entry, from));
+    }
+    (unique, DedupPlan { occurrences })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    fn entry(source_file: &str, json_path: &str, text: &str, block: Option<&str>) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: block.map(str::to_string),
+            speaker: None,
+        }
+    }
+
+    fn translate(entry: &SourceStringData) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: entry.object_id,
+            original_text: entry.original_text.clone(),
+            translated_text: format!("<{}>", entry.original_text),
+            source_file: entry.source_file.clone(),
+            json_path: entry.json_path.clone(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_repeated_strings_are_translated_once() {
+        let entries = vec![
+            entry("www/data/Map001.json", "events[1].pages[0].list[1].parameters[0][0]", "はい", None),
+            entry("www/data/Map001.json", "events[1].pages[0].list[3].parameters[0]", "はい", Some("b1")),
+            entry("www/data/Map002.json", "events[4].pages[0].list[1].parameters[0][0]", "はい", None),
+            entry("www/data/System.json", "terms.messages.yes", "はい", None),
+            entry("www/data/Map003.json", "events[2].pages[0].list[1].parameters[0][0]", "はい", None),
+        ];
+        let settings = DedupSettings {
+            scope: DedupScope::TextAndContext,
+            separate: vec![DuplicateOccurrence {
+                source_file: "www/data/Map003.json".to_string(),
+                json_path: "events[2].pages[0].list[1].parameters[0][0]".to_string(),
+            }],
+        };
+        let (unique, plan) = deduplicate(entries.clone(), &settings);
+        let sent: Vec<&str> = unique.iter().map(|e| e.source_file.as_str()).collect();
+        assert_eq!(sent, vec!["www/data/Map001.json", "www/data/Map001.json", "www/data/System.json", "www/data/Map003.json"]);
+        assert_eq!(plan.copies(), 1);
+
+        let results = plan.fan_out(unique.iter().map(translate).collect());
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[2].json_path, entries[2].json_path);
+        assert_eq!(results[2].translated_text, "<はい>");
+
+        let groups = find_duplicate_groups(&entries, DedupScope::Text);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].occurrences.len(), 4);
+        assert_eq!(find_duplicate_groups(&entries, DedupScope::TextAndContext)[0].occurrences.len(), 3);
+    }
+}
@@ -0,0 +1,87 @@
+use regex::Regex;
+use crate::core::config::FileGlob;
+use crate::error::CoreError;
+use crate::models::project_config::DoNotTranslate;
+use crate::models::translation::SourceStringData;
+
+/// The project's `doNotTranslate` rules, compiled. Applied to the strings of every extractor, so
+/// event names, file names and script snippets never reach a provider.
+#[derive(Default)]
+pub struct DoNotTranslateRules {
+    exact: Vec<String>,
+    patterns: Vec<Regex>,
+    json_paths: Vec<FileGlob>,
+}
+
+impl DoNotTranslateRules {
+    pub fn new(rules: &DoNotTranslate) -> Result<Self, CoreError> {
+        let exact = rules.exact.iter().map(|text| text.trim().to_string()).filter(|text| !text.is_empty()).collect();
+        let patterns = rules
+            .patterns
+            .iter()
+            .filter(|pattern| !pattern.trim().is_empty())
+            .map(|pattern| {
+                Regex::new(&format!("^(?:{})$", pattern.trim()))
+                    .map_err(|e| CoreError::Config(format!("Invalid do-not-translate pattern '{}': {}", pattern, e)))
+            })
+            .collect::<Result<_, _>>()?;
+        let json_paths = rules
+            .json_paths
+            .iter()
+            .filter(|glob| !glob.trim().is_empty())
+            .map(|glob| FileGlob::new(glob))
+            .collect::<Result<_, _>>()?;
+        Ok(DoNotTranslateRules { exact, patterns, json_paths })
+    }
+
+    pub fn excludes(&self, entry: &SourceStringData) -> bool {
+        let text = entry.original_text.trim();
+        self.exact.iter().any(|exact| exact == text)
+            || self.patterns.iter().any(|pattern| pattern.is_match(text))
+            || self.json_paths.iter().any(|glob| glob.matches(&entry.json_path))
+    }
+
+    /// `entries` without the ones the rules exclude.
+    pub fn apply(&self, mut entries: Vec<SourceStringData>) -> Vec<SourceStringData> {
+        entries.retain(|entry| !self.excludes(entry));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(json_path: &str, text: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_exclude_matching_entries() {
+        let rules = DoNotTranslateRules::new(&DoNotTranslate {
+            exact: vec!["ＯＫ".to_string()],
+            patterns: vec![r"EV\d+".to_string(), r"[\w-]+\.(png|ogg)".to_string()],
+            json_paths: vec!["events[*].note".to_string()],
+        })
+        .unwrap();
+        let entries = vec![
+            entry("events[1].name", "EV001"),
+            entry("events[1].pages[0].list[0].parameters[0]", "EV001で待ち合わせ"),
+            entry("events[2].pages[0].list[1].parameters[0]", "  ＯＫ "),
+            entry("events[2].pages[0].list[2].parameters[0]", "battle-theme.ogg"),
+            entry("events[3].note", "ハロルドの家"),
+            entry("displayName", "はじまりの村"),
+        ];
+        let kept: Vec<String> = rules.apply(entries).into_iter().map(|e| e.original_text).collect();
+        assert_eq!(kept, vec!["EV001で待ち合わせ", "はじまりの村"]);
+
+        assert!(DoNotTranslateRules::new(&DoNotTranslate { patterns: vec!["(".to_string()], ..Default::default() }).is_err());
+    }
+}
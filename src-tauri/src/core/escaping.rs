@@ -0,0 +1,291 @@
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::core::rpgmv::note_tags::NOTE_TAG_PATH_SUFFIX;
+use crate::models::translation::WorkingTranslation;
+
+/// How the game reads an entry's text, which decides the characters that can break it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeContext {
+    // Text drawn by RPG Maker's message/window code: `\` starts a control code.
+    Message,
+    // plugins.js parameters: control codes as in messages, and many plugins `eval` the value,
+    // so a bare `"` can end a string early.
+    PluginParameter,
+    // A note tag value read by a plugin's `<Tag:value>` regex: `<` and `>` end the tag.
+    NoteTag,
+    // Line-based text files (assets, Wolf RPG dumps): a line starting with `>` is a dump
+    // control line.
+    TextFile,
+    // Ren'Py and TyranoScript scripts, generic JSON/YAML files: none of RPG Maker's escaping
+    // applies, so the text is left as it is.
+    Verbatim,
+}
+
+// MV/MZ data files whose text is drawn by the message code, besides the maps.
+const RPG_MAKER_DATA_FILES: [&str; 11] = [
+    "Actors.json", "Items.json", "Armors.json", "Weapons.json", "Skills.json", "Enemies.json",
+    "CommonEvents.json", "Troops.json", "System.json", "Classes.json", "States.json",
+];
+
+/// MV/MZ data files (`Map001.json`, `Items.json`...) and VX Ace's `.rvdata2`.
+fn is_rpg_maker_data(file_name: &str) -> bool {
+    RPG_MAKER_DATA_FILES.contains(&file_name)
+        || (file_name.starts_with("Map") && file_name.ends_with(".json"))
+        || file_name.ends_with(".rvdata2")
+}
+
+pub fn escape_context(source_file: &str, json_path: &str) -> EscapeContext {
+    let file_name = source_file.rsplit('/').next().unwrap_or(source_file);
+    if source_file.ends_with(".js") {
+        EscapeContext::PluginParameter
+    } else if source_file.ends_with(".txt") {
+        EscapeContext::TextFile
+    } else if !is_rpg_maker_data(file_name) {
+        EscapeContext::Verbatim
+    } else if json_path.contains(NOTE_TAG_PATH_SUFFIX) {
+        EscapeContext::NoteTag
+    } else {
+        EscapeContext::Message
+    }
+}
+
+// RPG Maker MV/MZ codes (Window_Base.processEscapeCharacter); the first group takes a `[n]` parameter.
+const CODES_WITH_PARAMETER: [&str; 8] = ["V", "N", "P", "C", "I", "PX", "PY", "FS"];
+const CODES_WITHOUT_PARAMETER: [&str; 11] = ["G", "{", "}", "$", ".", "|", "!", ">", "<", "^", "\\"];
+
+fn escape_code() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // Same shape as the engine's obtainEscapeCode, followed by an optional parameter.
+    RE.get_or_init(|| Regex::new(r"^(?:([$.|^!><{}\\])|([A-Za-z]+))(\[[^\]]*\])?").expect("escape code pattern is valid"))
+}
+
+/// A control code as the engine would read it after a `\`, e.g. "C" and "[2]".
+struct EscapeCode<'a> {
+    name: &'a str,
+    parameter: Option<&'a str>,
+    len: usize,
+}
+
+fn read_escape_code(after_backslash: &str) -> Option<EscapeCode<'_>> {
+    let captures = escape_code().captures(after_backslash)?;
+    let name = captures.get(1).or_else(|| captures.get(2))?.as_str();
+    let parameter = captures.get(3).map(|m| m.as_str());
+    Some(EscapeCode { name, parameter, len: captures.get(0)?.end() })
+}
+
+/// Code names used in the original text, upper-cased (plugins add their own codes).
+fn original_codes(original: &str) -> Vec<String> {
+    original
+        .split('\\')
+        .skip(1)
+        .filter_map(read_escape_code)
+        .map(|code| code.name.to_uppercase())
+        .collect()
+}
+
+// Why the engine would not read this code as intended, if it would not.
+fn code_problem(code: &EscapeCode, known: &[String]) -> Option<String> {
+    let name = code.name.to_uppercase();
+    if known.iter().any(|known| name.starts_with(known.as_str())) {
+        return None;
+    }
+    if CODES_WITH_PARAMETER.contains(&name.as_str()) {
+        let numeric = code.parameter.is_some_and(|p| p[1..p.len() - 1].parse::<u32>().is_ok());
+        return (!numeric).then(|| format!("\\{} needs a numeric [n] parameter", code.name));
+    }
+    if CODES_WITHOUT_PARAMETER.contains(&name.as_str()) {
+        return None;
+    }
+    Some(format!("\\{} is read as an unknown control code and its letters are dropped", code.name))
+}
+
+fn sanitize_backslashes(original: &str, translated: &str) -> String {
+    let known = original_codes(original);
+    let mut out = String::with_capacity(translated.len());
+    let mut rest = translated;
+    while let Some(position) = rest.find('\\') {
+        out.push_str(&rest[..position]);
+        let after = &rest[position + 1..];
+        // Providers often write a line break as a literal "\n".
+        if after.starts_with('n') && !after[1..].starts_with(['[', '<']) && !known.iter().any(|code| code == "N") {
+            out.push('\n');
+            rest = &after[1..];
+            continue;
+        }
+        match read_escape_code(after) {
+            Some(code) if code_problem(&code, &known).is_none() => {
+                out.push('\\');
+                out.push_str(&after[..code.len]);
+                rest = &after[code.len..];
+            }
+            // A stray backslash: doubled, so the engine draws it instead of eating the next letters.
+            _ => {
+                out.push_str("\\\\");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn curly_quotes(text: &str) -> String {
+    let mut open = true;
+    text.chars()
+        .map(|c| match c {
+            '"' => {
+                open = !open;
+                if open { '\u{201d}' } else { '\u{201c}' }
+            }
+            c => c,
+        })
+        .collect()
+}
+
+fn looks_like_json(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with('{') || text.starts_with('[')
+}
+
+/// Rewrites characters that would break the engine or plugins reading `translated`. Characters
+/// the original text already uses in the same way are left alone.
+pub fn sanitize_text(original: &str, translated: &str, context: EscapeContext) -> String {
+    match context {
+        EscapeContext::Message => sanitize_backslashes(original, translated),
+        EscapeContext::PluginParameter => {
+            let text = sanitize_backslashes(original, translated);
+            if original.contains('"') || looks_like_json(&text) {
+                text
+            } else {
+                curly_quotes(&text)
+            }
+        }
+        EscapeContext::NoteTag if original.contains(['<', '>']) => translated.to_string(),
+        EscapeContext::NoteTag => translated.replace('<', "\u{ff1c}").replace('>', "\u{ff1e}"),
+        EscapeContext::TextFile => translated
+            .split('\n')
+            .map(|line| match line.strip_prefix('>') {
+                Some(rest) => format!("\u{ff1e}{}", rest),
+                None => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        EscapeContext::Verbatim => translated.to_string(),
+    }
+}
+
+/// Sanitizes an entry's translation in place. Returns true if it was changed.
+pub fn sanitize_translation(entry: &mut WorkingTranslation) -> bool {
+    if entry.error.is_some() || entry.translated_text.is_empty() {
+        return false;
+    }
+    let context = escape_context(&entry.source_file, &entry.json_path);
+    let sanitized = sanitize_text(&entry.original_text, &entry.translated_text, context);
+    if sanitized == entry.translated_text {
+        return false;
+    }
+    entry.translated_text = sanitized;
+    true
+}
+
+/// Reads `translated` the way the engine would and describes what would break. Only texts with
+/// risky characters are inspected.
+pub fn simulate_engine_parse(original: &str, translated: &str, context: EscapeContext) -> Vec<String> {
+    let mut problems = Vec::new();
+    match context {
+        EscapeContext::Message | EscapeContext::PluginParameter => {
+            let known = original_codes(original);
+            let mut rest = translated;
+            while let Some(position) = rest.find('\\') {
+                let after = &rest[position + 1..];
+                match read_escape_code(after) {
+                    Some(code) => {
+                        problems.extend(code_problem(&code, &known));
+                        rest = &after[code.len..];
+                    }
+                    None => {
+                        problems.push("A stray backslash is dropped by the message parser".to_string());
+                        rest = after;
+                    }
+                }
+            }
+            if context == EscapeContext::PluginParameter {
+                if looks_like_json(original)
+                    && serde_json::from_str::<serde_json::Value>(original).is_ok()
+                    && serde_json::from_str::<serde_json::Value>(translated).is_err()
+                {
+                    problems.push("The structured parameter is no longer valid JSON".to_string());
+                } else if !original.contains('"') && translated.contains('"') {
+                    problems.push("A double quote can end the string early in plugins that evaluate this parameter".to_string());
+                }
+            }
+        }
+        EscapeContext::NoteTag => {
+            if !original.contains(['<', '>']) && translated.contains(['<', '>']) {
+                problems.push("'<' or '>' ends the note tag early".to_string());
+            }
+        }
+        EscapeContext::TextFile => {
+            if translated.split('\n').any(|line| line.starts_with('>')) {
+                problems.push("A line starting with '>' is read as a dump control line".to_string());
+            }
+        }
+        EscapeContext::Verbatim => {}
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_context() {
+        assert_eq!(escape_context("www/js/plugins.js", "[1].parameters.Title"), EscapeContext::PluginParameter);
+        assert_eq!(escape_context("www/data/Items.json", "[1].note@noteTag[0]"), EscapeContext::NoteTag);
+        assert_eq!(escape_context("patch/dump/mps/Map001.txt", "[0]"), EscapeContext::TextFile);
+        assert_eq!(escape_context("www/data/Map001.json", "events[1].pages[0].list[2].parameters[0]"), EscapeContext::Message);
+        assert_eq!(escape_context("Data/Items.rvdata2", "[1].description"), EscapeContext::Message);
+        for source_file in ["game/script.rpy", "data/scenario/first.ks", "mods/quests/forest.yaml", "lang/ja.json"] {
+            assert_eq!(escape_context(source_file, "[0]"), EscapeContext::Verbatim);
+        }
+        assert_eq!(sanitize_text("C:\\", "C:\\Games \"Hero\"", EscapeContext::Verbatim), "C:\\Games \"Hero\"");
+    }
+
+    #[test]
+    fn test_sanitize_message_backslashes() {
+        let original = "\\C[2]ハロルド\\C[0]は\\V[3]ゴールド持っている";
+        let translated = "\\C[2]Harold\\C[0] has \\V[3] gold. C:\\Games\\save";
+        assert_eq!(
+            sanitize_text(original, translated, EscapeContext::Message),
+            "\\C[2]Harold\\C[0] has \\V[3] gold. C:\\\\Games\\\\save"
+        );
+        assert_eq!(sanitize_text("はい。いいえ", "Yes.\\nNo", EscapeContext::Message), "Yes.\nNo");
+        assert_eq!(sanitize_text("待って\\!", "Wait\\! \\\\o/", EscapeContext::Message), "Wait\\! \\\\o/");
+        // Plugin codes used by the original are kept.
+        assert_eq!(sanitize_text("\\fb太字", "\\fbBold", EscapeContext::Message), "\\fbBold");
+    }
+
+    #[test]
+    fn test_sanitize_other_contexts() {
+        assert_eq!(
+            sanitize_text("勇者の剣", "The \"Hero\" Sword", EscapeContext::PluginParameter),
+            "The \u{201c}Hero\u{201d} Sword"
+        );
+        assert_eq!(sanitize_text("[\"a\"]", "[\"b\"]", EscapeContext::PluginParameter), "[\"b\"]");
+        assert_eq!(sanitize_text("剣", "<Sword>", EscapeContext::NoteTag), "\u{ff1c}Sword\u{ff1e}");
+        assert_eq!(sanitize_text("あ\nい", "A\n> END STRING", EscapeContext::TextFile), "A\n\u{ff1e} END STRING");
+    }
+
+    #[test]
+    fn test_simulate_engine_parse() {
+        assert!(simulate_engine_parse("\\C[2]剣", "\\C[2]Sword", EscapeContext::Message).is_empty());
+        assert_eq!(simulate_engine_parse("剣", "C:\\Games", EscapeContext::Message).len(), 1);
+        assert_eq!(simulate_engine_parse("剣", "Sword \\V[x]", EscapeContext::Message).len(), 1);
+        assert_eq!(simulate_engine_parse("剣", "Sword\\", EscapeContext::Message).len(), 1);
+        assert_eq!(simulate_engine_parse("剣", "\"Sword\"", EscapeContext::PluginParameter).len(), 1);
+        assert_eq!(simulate_engine_parse("{\"a\":1}", "{\"a\":1", EscapeContext::PluginParameter).len(), 1);
+        assert_eq!(simulate_engine_parse("剣", "<Sword>", EscapeContext::NoteTag).len(), 1);
+        assert!(simulate_engine_parse("剣", "Sword", EscapeContext::TextFile).is_empty());
+    }
+}
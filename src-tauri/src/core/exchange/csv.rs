@@ -0,0 +1,141 @@
+use crate::error::CoreError;
+use crate::models::exchange::ImportedTranslation;
+
+/// Splits CSV/TSV content into records, following RFC 4180: dialogue lines keep their line
+/// breaks inside quoted fields.
+pub fn parse_records(content: &str, delimiter: char) -> Result<Vec<Vec<String>>, CoreError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(CoreError::Custom(format!("Unterminated quoted field at the end of the file (line {})", line)));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    Ok(records)
+}
+
+/// A CSV field, quoted when needed; line breaks are kept inside the quotes for `parse_records`.
+pub fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Header names are matched loosely so both `json_path` and `jsonPath` (or `Json Path`) work.
+fn normalize_header(header: &str) -> String {
+    header.trim().to_lowercase().replace(['_', ' ', '-'], "")
+}
+
+fn column(headers: &[String], names: &[&str]) -> Option<usize> {
+    headers.iter().position(|h| names.contains(&h.as_str()))
+}
+
+/// Reads translation rows. The first record is the header and must name the
+/// `source_file`, `json_path`, `original_text` and `translated_text` columns
+/// (`source`/`target` are accepted for the texts); other columns are ignored.
+pub fn parse_csv_translations(content: &str, delimiter: char) -> Result<Vec<ImportedTranslation>, CoreError> {
+    let mut records = parse_records(content, delimiter)?.into_iter();
+    let headers: Vec<String> = records
+        .next()
+        .ok_or_else(|| CoreError::Config("The CSV file is empty".to_string()))?
+        .iter()
+        .map(|h| normalize_header(h))
+        .collect();
+
+    let required = [
+        ("source_file", column(&headers, &["sourcefile", "file"])),
+        ("json_path", column(&headers, &["jsonpath", "path"])),
+        ("original_text", column(&headers, &["originaltext", "original", "source"])),
+        ("translated_text", column(&headers, &["translatedtext", "translation", "target"])),
+    ];
+    let missing: Vec<&str> = required.iter().filter(|(_, index)| index.is_none()).map(|(name, _)| *name).collect();
+    if !missing.is_empty() {
+        return Err(CoreError::Config(format!("The CSV header is missing the column(s): {}", missing.join(", "))));
+    }
+    let [source_file, json_path, original_text, translated_text] = required.map(|(_, index)| index.unwrap_or_default());
+
+    let field = |record: &[String], index: usize| record.get(index).cloned().unwrap_or_default();
+    Ok(records
+        .map(|record| ImportedTranslation {
+            source_file: field(&record, source_file),
+            json_path: field(&record, json_path),
+            original_text: field(&record, original_text),
+            translated_text: field(&record, translated_text),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_fields_keep_line_breaks() {
+        let content = "\u{feff}a,b\r\n\"line 1\nline \"\"2\"\"\",\"x,y\"\r\n\nlast,\n";
+        let records = parse_records(content, ',').unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["line 1\nline \"2\"".to_string(), "x,y".to_string()],
+                vec!["last".to_string(), String::new()],
+            ]
+        );
+        assert!(parse_records("a,\"open\n", ',').is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_translations_maps_headers() {
+        let content = "Note\tjsonPath\tSource File\tsource\ttarget\nok\t[1].name\twww/data/Items.json\tポーション\tPotion\n";
+        let rows = parse_csv_translations(content, '\t').unwrap();
+        assert_eq!(
+            rows,
+            vec![ImportedTranslation {
+                source_file: "www/data/Items.json".to_string(),
+                json_path: "[1].name".to_string(),
+                original_text: "ポーション".to_string(),
+                translated_text: "Potion".to_string(),
+            }]
+        );
+
+        let error = parse_csv_translations("source,target\na,b\n", ',').unwrap_err().to_string();
+        assert!(error.contains("source_file, json_path"));
+    }
+}
@@ -0,0 +1,163 @@
+// Interchange formats for handing a project to external translation tools (CAT tools,
+// translation memories) and bringing their work back.
+
+pub mod csv;
+pub mod patch;
+pub mod po;
+pub mod review_site;
+pub mod tmx;
+pub mod xliff;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use quick_xml::events::BytesStart;
+use crate::error::CoreError;
+use crate::models::exchange::{ImportReport, ImportedTranslation, TranslationImport, UnmatchedImport};
+use crate::models::translation::{TranslationStatus, WorkingTranslation};
+
+/// `translation_source` of entries merged from an imported file.
+pub const IMPORT_SOURCE: &str = "import";
+
+fn is_invalid_xml_char(c: char) -> bool {
+    ((c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r')) || c == '\u{FFFE}' || c == '\u{FFFF}'
+}
+
+/// Escapes XML text content. Carriage returns are kept as a character reference so parsers
+/// don't normalize them away; characters XML 1.0 cannot contain are replaced by `invalid`.
+pub(crate) fn escape_xml_text(text: &str, invalid: fn(char) -> String) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#13;"),
+            c if is_invalid_xml_char(c) => out.push_str(&invalid(c)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Attribute values hold names and language codes; characters XML cannot contain are dropped.
+pub(crate) fn escape_xml_attribute(value: &str) -> String {
+    escape_xml_text(value, |_| String::new()).replace('"', "&quot;")
+}
+
+pub(crate) fn xml_attribute(element: &BytesStart, name: &str) -> Result<Option<String>, CoreError> {
+    match element.try_get_attribute(name) {
+        Ok(Some(attribute)) => attribute
+            .unescape_value()
+            .map(|value| Some(value.into_owned()))
+            .map_err(|e| CoreError::Custom(format!("Invalid XML attribute '{}': {}", name, e))),
+        Ok(None) => Ok(None),
+        Err(e) => Err(CoreError::Custom(format!("Invalid XML attribute '{}': {}", name, e))),
+    }
+}
+
+/// Reads an XLIFF (`.xlf`, `.xliff`, `.xml`), gettext PO, CSV or TSV file of translations.
+pub fn read_import_file(file_path: &Path) -> Result<Vec<ImportedTranslation>, CoreError> {
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", file_path, e)))?;
+    let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "xlf" | "xliff" | "xml" => xliff::parse_xliff(&content),
+        "po" => po::parse_po(&content),
+        "csv" => csv::parse_csv_translations(&content, ','),
+        "tsv" | "txt" => csv::parse_csv_translations(&content, '\t'),
+        _ if content.trim_start().starts_with('<') => xliff::parse_xliff(&content),
+        _ => csv::parse_csv_translations(&content, ','),
+    }
+}
+
+/// Merges imported rows into the working set. A row applies to the entry with the same
+/// source file and JSON path, and only if the entry's original text is still the one the row
+/// was translated from; anything else is reported as unmatched. Updated entries count as edited.
+pub fn merge_imports(mut translations: Vec<WorkingTranslation>, rows: Vec<ImportedTranslation>) -> TranslationImport {
+    let mut report = ImportReport { rows: rows.len(), ..Default::default() };
+    let index: HashMap<(String, String), usize> = translations
+        .iter()
+        .enumerate()
+        .map(|(i, t)| ((t.source_file.clone(), t.json_path.clone()), i))
+        .collect();
+
+    for row in rows {
+        if row.translated_text.is_empty() {
+            report.empty += 1;
+            continue;
+        }
+        let reason = if row.json_path.is_empty() {
+            "The row has no JSON path".to_string()
+        } else {
+            match index.get(&(row.source_file.clone(), row.json_path.clone())) {
+                None => format!("No entry at {} in {}", row.json_path, row.source_file),
+                Some(&i) if translations[i].original_text != row.original_text => {
+                    "The original text has changed since the file was exported".to_string()
+                }
+                Some(&i) => {
+                    let entry = &mut translations[i];
+                    if entry.translated_text == row.translated_text && entry.error.is_none() {
+                        report.unchanged += 1;
+                    } else {
+                        entry.translated_text = row.translated_text;
+                        entry.translation_source = IMPORT_SOURCE.to_string();
+                        entry.error = None;
+                        entry.status = TranslationStatus::Edited;
+                        report.updated += 1;
+                    }
+                    continue;
+                }
+            }
+        };
+        report.unmatched.push(UnmatchedImport { row, reason });
+    }
+    TranslationImport { translations, report }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(json_path: &str, original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Items.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    fn row(json_path: &str, original: &str, translated: &str) -> ImportedTranslation {
+        ImportedTranslation {
+            source_file: "www/data/Items.json".to_string(),
+            json_path: json_path.to_string(),
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_imports() {
+        let translations = vec![entry("[1].name", "ポーション", "Potion"), entry("[2].name", "エーテル", "")];
+        let rows = vec![
+            row("[1].name", "ポーション", "Potion"),
+            row("[2].name", "エーテル", "Ether"),
+            row("[3].name", "エリクサー", "Elixir"),
+            row("[1].description", "", ""),
+            ImportedTranslation { original_text: "ポーション改".to_string(), ..row("[1].name", "", "Hi-Potion") },
+        ];
+        let import = merge_imports(translations, rows);
+
+        assert_eq!((import.report.rows, import.report.updated, import.report.unchanged, import.report.empty), (5, 1, 1, 1));
+        assert_eq!(import.translations[1].translated_text, "Ether");
+        assert_eq!(import.translations[1].translation_source, IMPORT_SOURCE);
+        assert_eq!(import.translations[0].translation_source, "ollama");
+        let unmatched: Vec<&str> = import.report.unmatched.iter().map(|u| u.row.translated_text.as_str()).collect();
+        assert_eq!(unmatched, vec!["Elixir", "Hi-Potion"]);
+    }
+}
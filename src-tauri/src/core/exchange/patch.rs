@@ -0,0 +1,186 @@
+// Translation patches: the json_path → translation mapping of a project as JSON Lines, one
+// object per translated string:
+//
+//   {"file":"www/data/Items.json","path":"[1].name","original_hash":"<md5>","translation":"Potion"}
+//
+// `original_hash` is the lowercase hex MD5 of the original text (UTF-8), so a patch only applies
+// to copies of the game whose string is still the one that was translated. Blank lines are
+// ignored; unknown keys are allowed so tools can add their own.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use crate::core::exchange::IMPORT_SOURCE;
+use crate::core::rpgmv::patching::{write_translations_in_place, InPlaceReport};
+use crate::core::rpgmv::project::extract_translatable_strings_from_project;
+use crate::error::CoreError;
+use crate::models::exchange::{PatchLine, PatchMatchReport, UnmatchedPatchLine};
+use crate::models::extraction::ExtractionFilter;
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+
+/// Result of `apply_patch_command`.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchApplication {
+    pub report: PatchMatchReport,
+    pub written: InPlaceReport,
+}
+
+pub fn original_hash(text: &str) -> String {
+    format!("{:x}", md5::compute(text.as_bytes()))
+}
+
+/// One line per entry with a translation; failed entries are left out.
+pub fn export_patch(translations: &[WorkingTranslation]) -> Result<String, CoreError> {
+    let mut out = String::new();
+    for entry in translations.iter().filter(|t| t.error.is_none() && !t.translated_text.is_empty()) {
+        let line = PatchLine {
+            file: entry.source_file.clone(),
+            path: entry.json_path.clone(),
+            original_hash: original_hash(&entry.original_text),
+            translation: entry.translated_text.clone(),
+        };
+        let json = serde_json::to_string(&line)
+            .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize patch line: {}", e)))?;
+        out.push_str(&json);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+pub fn export_patch_file(translations: &[WorkingTranslation], output_path: &Path) -> Result<usize, CoreError> {
+    let patch = export_patch(translations)?;
+    fs::write(output_path, &patch).map_err(|e| CoreError::Io(format!("Failed to write {:?}: {}", output_path, e)))?;
+    Ok(patch.lines().count())
+}
+
+pub fn parse_patch(content: &str) -> Result<Vec<PatchLine>, CoreError> {
+    content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| CoreError::JsonParse(format!("Invalid patch line {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// Matches patch lines to a project's extracted strings by file, path and original hash. Matched
+/// lines become edited working entries ready to be written.
+pub fn match_patch(extracted: &[SourceStringData], lines: Vec<PatchLine>) -> (Vec<WorkingTranslation>, PatchMatchReport) {
+    let mut report = PatchMatchReport { lines: lines.len(), ..Default::default() };
+    let index: HashMap<(&str, &str), &SourceStringData> =
+        extracted.iter().map(|s| ((s.source_file.as_str(), s.json_path.as_str()), s)).collect();
+    let mut entries = Vec::new();
+    for line in lines {
+        let reason = match index.get(&(line.file.as_str(), line.path.as_str())) {
+            None => format!("No string at {} in {}", line.path, line.file),
+            Some(source) if original_hash(&source.original_text) != line.original_hash.to_ascii_lowercase() => {
+                "The original text differs from the one the patch was made for".to_string()
+            }
+            Some(source) => {
+                entries.push(WorkingTranslation {
+                    object_id: source.object_id,
+                    original_text: source.original_text.clone(),
+                    translated_text: line.translation,
+                    source_file: source.source_file.clone(),
+                    json_path: source.json_path.clone(),
+                    translation_source: IMPORT_SOURCE.to_string(),
+                    error: None,
+                    status: TranslationStatus::Edited,
+                });
+                report.matched += 1;
+                continue;
+            }
+        };
+        report.unmatched.push(UnmatchedPatchLine { line, reason });
+    }
+    (entries, report)
+}
+
+/// Applies a patch file to the project at `project_path`: its strings are extracted, matched
+/// against the patch and the matches written in place (with a backup snapshot, as for any
+/// in-place write).
+pub fn apply_patch_file(project_path: &str, patch_path: &Path) -> Result<PatchApplication, CoreError> {
+    let content = fs::read_to_string(patch_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", patch_path, e)))?;
+    let lines = parse_patch(&content)?;
+    let extracted = extract_translatable_strings_from_project(project_path, &ExtractionFilter::default())
+        .map_err(CoreError::Custom)?;
+    let (entries, report) = match_patch(&extracted, lines);
+    let written = if entries.is_empty() {
+        InPlaceReport::default()
+    } else {
+        write_translations_in_place(project_path, &entries)?
+    };
+    Ok(PatchApplication { report, written })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(json_path: &str, original: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: original.to_string(),
+            source_file: "www/data/Items.json".to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_patch_round_trip_and_matching() {
+        let translations = vec![
+            WorkingTranslation {
+                object_id: 1,
+                original_text: "ポーション".to_string(),
+                translated_text: "Potion".to_string(),
+                source_file: "www/data/Items.json".to_string(),
+                json_path: "[1].name".to_string(),
+                translation_source: "ollama".to_string(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            },
+            WorkingTranslation {
+                object_id: 2,
+                original_text: "エーテル".to_string(),
+                translated_text: "Ether".to_string(),
+                source_file: "www/data/Items.json".to_string(),
+                json_path: "[2].name".to_string(),
+                translation_source: "ollama".to_string(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            },
+            WorkingTranslation {
+                object_id: 3,
+                original_text: "エリクサー".to_string(),
+                translated_text: String::new(),
+                source_file: "www/data/Items.json".to_string(),
+                json_path: "[3].name".to_string(),
+                translation_source: "ollama".to_string(),
+                error: Some("timeout".to_string()),
+                status: TranslationStatus::Untranslated,
+            },
+        ];
+        let patch = export_patch(&translations).unwrap();
+        assert_eq!(patch.lines().count(), 2);
+        assert!(patch.starts_with(r#"{"file":"www/data/Items.json","path":"[1].name","original_hash":""#));
+
+        let lines = parse_patch(&format!("\n{}\n", patch)).unwrap();
+        // The second item was renamed in this copy of the game.
+        let extracted = vec![source("[1].name", "ポーション"), source("[2].name", "ハイエーテル")];
+        let (entries, report) = match_patch(&extracted, lines);
+        assert_eq!((report.lines, report.matched), (2, 1));
+        assert_eq!(entries[0].translated_text, "Potion");
+        assert_eq!(entries[0].status, TranslationStatus::Edited);
+        assert_eq!(report.unmatched[0].line.translation, "Ether");
+
+        assert!(parse_patch("{\"file\":\"a\"}").is_err());
+    }
+}
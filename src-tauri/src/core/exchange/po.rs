@@ -0,0 +1,257 @@
+use std::fs;
+use std::path::Path;
+use crate::core::rpgmv::recovery::RECOVERED_SOURCE;
+use crate::core::translation_memory::MEMORY_SOURCE_PREFIX;
+use crate::error::CoreError;
+use crate::models::exchange::ImportedTranslation;
+use crate::models::translation::WorkingTranslation;
+use super::tmx::TMX_PROVIDER;
+use super::IMPORT_SOURCE;
+
+/// Translations that came from a person (imported files, a previous release) rather than
+/// from a provider are not marked fuzzy.
+fn is_machine_translation(entry: &WorkingTranslation) -> bool {
+    let source = entry.translation_source.strip_prefix(MEMORY_SOURCE_PREFIX).unwrap_or(&entry.translation_source);
+    ![IMPORT_SOURCE, RECOVERED_SOURCE, TMX_PROVIDER].contains(&source)
+}
+
+fn escape_po(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// `keyword "text"`, or the multi-line form with one string per line when the text has breaks.
+fn push_po_string(out: &mut String, keyword: &str, text: &str) {
+    if !text.contains('\n') {
+        out.push_str(&format!("{} \"{}\"\n", keyword, escape_po(text)));
+        return;
+    }
+    out.push_str(&format!("{} \"\"\n", keyword));
+    for line in text.split_inclusive('\n') {
+        out.push_str(&format!("\"{}\"\n", escape_po(line)));
+    }
+}
+
+/// Serializes entries as a gettext PO file. Each entry gets a `#: source_file:json_path`
+/// reference and the same value as `msgctxt`, which keeps identical strings from different
+/// places apart. Machine translations are flagged `fuzzy` for review.
+pub fn to_po(translations: &[WorkingTranslation], source_language: &str, target_language: &str) -> String {
+    let mut out = String::from("msgid \"\"\nmsgstr \"\"\n");
+    for header in [
+        "Content-Type: text/plain; charset=UTF-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+        format!("Language: {}", target_language),
+        format!("X-Source-Language: {}", source_language),
+        "X-Generator: Translate-AI".to_string(),
+    ] {
+        out.push_str(&format!("\"{}\\n\"\n", escape_po(&header)));
+    }
+
+    for entry in translations {
+        let location = format!("{}:{}", entry.source_file, entry.json_path);
+        out.push('\n');
+        if let Some(error) = &entry.error {
+            out.push_str(&format!("#. Translation failed: {}\n", error.replace('\n', " ")));
+        }
+        out.push_str(&format!("#: {}\n", location));
+        let translated = entry.error.is_none() && !entry.translated_text.is_empty();
+        if translated && is_machine_translation(entry) {
+            out.push_str("#, fuzzy\n");
+        }
+        push_po_string(&mut out, "msgctxt", &location);
+        push_po_string(&mut out, "msgid", &entry.original_text);
+        push_po_string(&mut out, "msgstr", if translated { &entry.translated_text } else { "" });
+    }
+    out
+}
+
+pub fn export_po_file(
+    translations: &[WorkingTranslation],
+    source_language: &str,
+    target_language: &str,
+    output_path: &Path,
+) -> Result<(), CoreError> {
+    fs::write(output_path, to_po(translations, source_language, target_language))
+        .map_err(|e| CoreError::Io(format!("Failed to write PO file to {:?}: {}", output_path, e)))
+}
+
+fn unescape_po(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// Fields of the PO entry being read.
+#[derive(Default)]
+struct PoEntry {
+    fuzzy: bool,
+    reference: Option<String>,
+    context: Option<String>,
+    id: Option<String>,
+    translation: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PoField {
+    Context,
+    Id,
+    Translation,
+    // msgid_plural and msgstr[1..], which have no counterpart in the data files.
+    Ignored,
+}
+
+impl PoEntry {
+    fn field(&mut self, field: PoField) -> Option<&mut String> {
+        match field {
+            PoField::Context => Some(self.context.get_or_insert_with(String::new)),
+            PoField::Id => Some(self.id.get_or_insert_with(String::new)),
+            PoField::Translation => Some(self.translation.get_or_insert_with(String::new)),
+            PoField::Ignored => None,
+        }
+    }
+
+    /// The header (empty msgid) produces no row. Fuzzy translations are not confirmed, so they
+    /// come back empty and the current translation is kept.
+    fn into_row(self) -> Option<ImportedTranslation> {
+        let id = self.id.filter(|id| !id.is_empty())?;
+        let location = self.context.or(self.reference).unwrap_or_default();
+        let (source_file, json_path) = location.split_once(':').unwrap_or((&location, ""));
+        Some(ImportedTranslation {
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            original_text: id,
+            translated_text: if self.fuzzy { String::new() } else { self.translation.unwrap_or_default() },
+        })
+    }
+}
+
+/// Reads the entries of a PO file. The location comes from `msgctxt` when it has the
+/// `source_file:json_path` form written by `to_po`, otherwise from the first `#:` reference.
+pub fn parse_po(content: &str) -> Result<Vec<ImportedTranslation>, CoreError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut rows = Vec::new();
+    let mut entry = PoEntry::default();
+    let mut field: Option<PoField> = None;
+
+    for (line_index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        // An entry ends with its msgstr; the next comment or keyword line begins another one.
+        let starts_entry = line.starts_with('#') || keyword == "msgctxt" || keyword == "msgid";
+        if starts_entry && entry.translation.is_some() {
+            rows.extend(std::mem::take(&mut entry).into_row());
+            field = None;
+        }
+
+        if line.is_empty() || line.starts_with("#~") {
+            continue;
+        } else if let Some(flags) = line.strip_prefix("#,") {
+            entry.fuzzy |= flags.split(',').any(|f| f.trim() == "fuzzy");
+        } else if let Some(references) = line.strip_prefix("#:") {
+            if entry.reference.is_none() {
+                entry.reference = references.split_whitespace().next().map(str::to_string);
+            }
+        } else if line.starts_with('#') {
+            continue;
+        } else if line.starts_with('"') {
+            let Some(target) = field.and_then(|f| entry.field(f)) else { continue };
+            target.push_str(&unescape_po(quoted(line, line_index)?));
+        } else {
+            field = Some(match keyword {
+                "msgctxt" => PoField::Context,
+                "msgid" => PoField::Id,
+                "msgstr" | "msgstr[0]" => PoField::Translation,
+                "msgid_plural" => PoField::Ignored,
+                k if k.starts_with("msgstr[") => PoField::Ignored,
+                _ => return Err(CoreError::Custom(format!("Unexpected PO keyword '{}' on line {}", keyword, line_index + 1))),
+            });
+            let value = unescape_po(quoted(rest.trim(), line_index)?);
+            if let Some(target) = field.and_then(|f| entry.field(f)) {
+                target.push_str(&value);
+            }
+        }
+    }
+    rows.extend(entry.into_row());
+    Ok(rows)
+}
+
+fn quoted(text: &str, line_index: usize) -> Result<&str, CoreError> {
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .ok_or_else(|| CoreError::Custom(format!("Expected a quoted string on line {}", line_index + 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    fn entry(json_path: &str, original: &str, translated: &str, source: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: source.to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_export_marks_machine_translations_fuzzy() {
+        let translations = vec![
+            entry("events[1].pages[0].list[1].parameters[0]", "「はい」\nそうだ", "\"Yes\"\nThat's right", "ollama"),
+            entry("events[1].pages[0].list[2].parameters[0]", "はい", "Yes", IMPORT_SOURCE),
+        ];
+        let po = to_po(&translations, "ja", "en");
+        assert!(po.contains("\"Language: en\\n\"\n"));
+        assert!(po.contains(
+            "#: www/data/Map001.json:events[1].pages[0].list[1].parameters[0]\n#, fuzzy\nmsgctxt \"www/data/Map001.json:events[1].pages[0].list[1].parameters[0]\"\nmsgid \"\"\n\"「はい」\\n\"\n\"そうだ\"\nmsgstr \"\"\n\"\\\"Yes\\\"\\n\"\n\"That's right\"\n"
+        ));
+        assert!(po.contains("list[2].parameters[0]\"\nmsgid \"はい\"\nmsgstr \"Yes\"\n"));
+        assert_eq!(po.matches("#, fuzzy").count(), 1);
+    }
+
+    #[test]
+    fn test_parse_po_roundtrip_skips_fuzzy() {
+        let translations = vec![
+            entry("events[1].pages[0].list[1].parameters[0]", "「はい」\nそうだ", "\"Yes\"\nThat's right", IMPORT_SOURCE),
+            entry("events[1].pages[0].list[2].parameters[0]", "はい\\C[2]", "Yes\\C[2]", "deepl"),
+        ];
+        let rows = parse_po(&to_po(&translations, "ja", "en")).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].json_path, "events[1].pages[0].list[1].parameters[0]");
+        assert_eq!(rows[0].original_text, "「はい」\nそうだ");
+        assert_eq!(rows[0].translated_text, "\"Yes\"\nThat's right");
+        assert_eq!((rows[1].original_text.as_str(), rows[1].translated_text.as_str()), ("はい\\C[2]", ""));
+    }
+
+    #[test]
+    fn test_parse_po_uses_references_without_context() {
+        let po = "# translator comment\n#: www/data/Items.json:[1].name other.json:1\nmsgid \"ポーション\"\nmsgstr \"Potion\"\n\n#~ msgid \"old\"\n#~ msgstr \"gone\"\n#: www/data/Items.json:[2].name\nmsgid \"エーテル\"\nmsgstr \"\"\n";
+        let rows = parse_po(po).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].source_file.as_str(), rows[0].json_path.as_str()), ("www/data/Items.json", "[1].name"));
+        assert_eq!(rows[0].translated_text, "Potion");
+        assert_eq!(rows[1].json_path, "[2].name");
+        assert!(parse_po("msgid \"x\"\nbogus \"y\"\n").is_err());
+    }
+}
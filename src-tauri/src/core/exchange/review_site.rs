@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+use crate::core::bulk::entry_status;
+use crate::error::CoreError;
+use crate::models::bulk::EntryStatus;
+use crate::models::exchange::{ReviewComment, ReviewCommentImport};
+use crate::models::translation::WorkingTranslation;
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// File name of the imported reviewer comments (inside `.translate-ai/`).
+pub const REVIEW_COMMENTS_FILE_NAME: &str = "review_comments.json";
+
+const REVIEW_CSS: &str = r#"body{font-family:system-ui,sans-serif;margin:0;color:#222}
+header{position:sticky;top:0;background:#f6f6f6;border-bottom:1px solid #ddd;padding:.5rem 1rem;display:flex;gap:1rem;align-items:center;flex-wrap:wrap}
+h1{font-size:1.1rem;margin:0}
+table{border-collapse:collapse;width:100%}
+th,td{border-bottom:1px solid #eee;padding:.4rem .6rem;text-align:left;vertical-align:top}
+td.text{white-space:pre-wrap;width:32%}
+td.path{font-family:monospace;font-size:.8rem;color:#666}
+textarea{width:100%;min-height:2.5rem}
+.badge{border-radius:.6rem;padding:.1rem .5rem;font-size:.75rem;white-space:nowrap}
+.translated{background:#d8f5dd}.untranslated{background:#eee}.failed{background:#fbd5d5}
+"#;
+
+// Comments are kept in localStorage so they survive page changes, and downloaded together as
+// the JSON file `import_review_comments_command` reads. Keys include the project path: pages
+// opened from disk may share one localStorage with the review sites of other projects.
+const REVIEW_JS: &str = r#"(function () {
+  var PREFIX = 'translate-ai-review:' + JSON.stringify(document.body.dataset.project || '') + ':';
+  var search = document.getElementById('search');
+  if (search) {
+    search.addEventListener('input', function () {
+      var query = search.value.toLowerCase();
+      document.querySelectorAll('tr[data-search]').forEach(function (row) {
+        row.hidden = query !== '' && row.textContent.toLowerCase().indexOf(query) < 0;
+      });
+    });
+  }
+  document.querySelectorAll('textarea.comment').forEach(function (area) {
+    var row = area.closest('tr');
+    var key = PREFIX + JSON.stringify([row.dataset.file, row.dataset.path]);
+    area.value = localStorage.getItem(key) || '';
+    area.addEventListener('input', function () {
+      if (area.value) { localStorage.setItem(key, area.value); } else { localStorage.removeItem(key); }
+    });
+  });
+  var download = document.getElementById('download');
+  if (download) {
+    download.addEventListener('click', function () {
+      var reviewer = document.getElementById('reviewer').value || undefined;
+      var comments = [];
+      for (var i = 0; i < localStorage.length; i++) {
+        var key = localStorage.key(i);
+        if (key.indexOf(PREFIX) !== 0) continue;
+        var id = JSON.parse(key.slice(PREFIX.length));
+        comments.push({ sourceFile: id[0], jsonPath: id[1], comment: localStorage.getItem(key), reviewer: reviewer });
+      }
+      var blob = new Blob([JSON.stringify({ comments: comments }, null, 2)], { type: 'application/json' });
+      var link = document.createElement('a');
+      link.href = URL.createObjectURL(blob);
+      link.download = 'review_comments.json';
+      link.click();
+    });
+  }
+})();
+"#;
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn badge(status: EntryStatus) -> &'static str {
+    match status {
+        EntryStatus::Translated => r#"<span class="badge translated">Translated</span>"#,
+        EntryStatus::Untranslated => r#"<span class="badge untranslated">Untranslated</span>"#,
+        EntryStatus::Failed => r#"<span class="badge failed">Failed</span>"#,
+    }
+}
+
+fn page_name(index: usize, source_file: &str) -> String {
+    let slug: String = source_file.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    format!("{:03}-{}.html", index + 1, slug)
+}
+
+fn page(project_path: &str, title: &str, language: &str, header: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <link rel=\"stylesheet\" href=\"review.css\">\n</head>\n<body data-project=\"{}\">\n<header>\n{}\n\
+         <input id=\"search\" type=\"search\" placeholder=\"Search\">\n</header>\n{}\n\
+         <script src=\"review.js\"></script>\n</body>\n</html>\n",
+        escape_html(language),
+        escape_html(title),
+        escape_html(project_path),
+        header,
+        body
+    )
+}
+
+fn counts(entries: &[&WorkingTranslation]) -> String {
+    [EntryStatus::Translated, EntryStatus::Untranslated, EntryStatus::Failed]
+        .into_iter()
+        .map(|status| (status, entries.iter().filter(|e| entry_status(e) == status).count()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(status, count)| format!("{} {}", badge(status), count))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renders the review site: `index.html` listing the files, one page per source file with the
+/// original and the translation side by side, plus the shared stylesheet and script. Returns
+/// relative path -> content.
+pub fn render_review_site(
+    project_path: &str,
+    translations: &[WorkingTranslation],
+    source_language: &str,
+    target_language: &str,
+) -> BTreeMap<String, String> {
+    let mut by_file: Vec<(&str, Vec<&WorkingTranslation>)> = Vec::new();
+    for entry in translations {
+        match by_file.iter_mut().find(|(file, _)| *file == entry.source_file) {
+            Some((_, entries)) => entries.push(entry),
+            None => by_file.push((&entry.source_file, vec![entry])),
+        }
+    }
+
+    let mut site = BTreeMap::new();
+    let mut index_rows = String::new();
+    for (index, (source_file, entries)) in by_file.iter().enumerate() {
+        let name = page_name(index, source_file);
+        index_rows.push_str(&format!(
+            "<tr data-search><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+            name,
+            escape_html(source_file),
+            entries.len(),
+            counts(entries)
+        ));
+
+        let mut rows = String::new();
+        for entry in entries {
+            rows.push_str(&format!(
+                "<tr data-search data-file=\"{}\" data-path=\"{}\"><td class=\"path\">{}</td>\
+                 <td class=\"text\" lang=\"{}\">{}</td><td class=\"text\" lang=\"{}\">{}</td><td>{}</td>\
+                 <td><textarea class=\"comment\"></textarea></td></tr>\n",
+                escape_html(&entry.source_file),
+                escape_html(&entry.json_path),
+                escape_html(&entry.json_path),
+                escape_html(source_language),
+                escape_html(&entry.original_text),
+                escape_html(target_language),
+                escape_html(&entry.translated_text),
+                badge(entry_status(entry)),
+            ));
+        }
+        let header = format!(
+            "<a href=\"index.html\">All files</a>\n<h1>{}</h1>\n<span>{}</span>\n\
+             <input id=\"reviewer\" placeholder=\"Your name\">\n<button id=\"download\">Download comments</button>",
+            escape_html(source_file),
+            counts(entries)
+        );
+        let body = format!(
+            "<table>\n<thead><tr><th>Path</th><th>Original ({})</th><th>Translation ({})</th><th>Status</th><th>Comment</th></tr></thead>\n<tbody>\n{}</tbody>\n</table>",
+            escape_html(source_language),
+            escape_html(target_language),
+            rows
+        );
+        let title = format!("{} - Translation review", source_file);
+        site.insert(name, page(project_path, &title, target_language, &header, &body));
+    }
+
+    let all: Vec<&WorkingTranslation> = translations.iter().collect();
+    let header = format!(
+        "<h1>Translation review ({} → {})</h1>\n<span>{}</span>\n\
+         <input id=\"reviewer\" placeholder=\"Your name\">\n<button id=\"download\">Download comments</button>",
+        escape_html(source_language),
+        escape_html(target_language),
+        counts(&all)
+    );
+    let body = format!(
+        "<table>\n<thead><tr><th>File</th><th>Entries</th><th>Status</th></tr></thead>\n<tbody>\n{}</tbody>\n</table>",
+        index_rows
+    );
+    site.insert("index.html".to_string(), page(project_path, "Translation review", target_language, &header, &body));
+    site.insert("review.css".to_string(), REVIEW_CSS.to_string());
+    site.insert("review.js".to_string(), REVIEW_JS.to_string());
+    site
+}
+
+/// Writes the review site into `output_dir` and returns the number of file pages.
+pub fn export_review_site(
+    project_path: &str,
+    translations: &[WorkingTranslation],
+    source_language: &str,
+    target_language: &str,
+    output_dir: &Path,
+) -> Result<usize, CoreError> {
+    let site = render_review_site(project_path, translations, source_language, target_language);
+    fs::create_dir_all(output_dir)
+        .map_err(|e| CoreError::Io(format!("Failed to create {:?}: {}", output_dir, e)))?;
+    for (name, content) in &site {
+        let path = output_dir.join(name);
+        fs::write(&path, content).map_err(|e| CoreError::Io(format!("Failed to write {:?}: {}", path, e)))?;
+    }
+    Ok(site.len() - 3)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CommentsFile {
+    Wrapped { comments: Vec<ReviewComment> },
+    List(Vec<ReviewComment>),
+}
+
+/// Reads the comments JSON downloaded from the review site (`{"comments": [...]}` or a bare list).
+pub fn parse_review_comments(content: &str) -> Result<Vec<ReviewComment>, CoreError> {
+    match serde_json::from_str(content.trim_start_matches('\u{feff}')) {
+        Ok(CommentsFile::Wrapped { comments } | CommentsFile::List(comments)) => Ok(comments),
+        Err(e) => Err(CoreError::JsonParse(format!("Invalid review comments file: {}", e))),
+    }
+}
+
+/// Adds incoming comments to the stored ones. A reviewer's new comment on an entry replaces
+/// their earlier one; a blank comment clears it. Comments on unknown entries are reported.
+pub fn merge_review_comments(
+    mut stored: Vec<ReviewComment>,
+    incoming: Vec<ReviewComment>,
+    translations: &[WorkingTranslation],
+) -> (Vec<ReviewComment>, ReviewCommentImport) {
+    let known: HashSet<(&str, &str)> =
+        translations.iter().map(|t| (t.source_file.as_str(), t.json_path.as_str())).collect();
+    let mut report = ReviewCommentImport { rows: incoming.len(), ..Default::default() };
+    for comment in incoming {
+        if !known.contains(&(comment.source_file.as_str(), comment.json_path.as_str())) {
+            report.unmatched.push(comment);
+            continue;
+        }
+        stored.retain(|c| {
+            !(c.source_file == comment.source_file && c.json_path == comment.json_path && c.reviewer == comment.reviewer)
+        });
+        if comment.comment.trim().is_empty() {
+            report.cleared += 1;
+        } else {
+            report.imported += 1;
+            stored.push(comment);
+        }
+    }
+    (stored, report)
+}
+
+/// Comments imported so far, or none if the project has no comments file yet.
+pub fn load_review_comments(project_path: &str) -> Result<Vec<ReviewComment>, CoreError> {
+    let path = app_data_dir(project_path).join(REVIEW_COMMENTS_FILE_NAME);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read review comments {:?}: {}", path, e)))?;
+    parse_review_comments(&content)
+}
+
+pub fn save_review_comments(project_path: &str, comments: &[ReviewComment]) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, REVIEW_COMMENTS_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(&serde_json::json!({ "comments": comments }))
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize review comments: {}", e)))?;
+    fs::write(&path, json).map_err(|e| CoreError::Io(format!("Failed to write review comments to {:?}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    fn entry(source_file: &str, json_path: &str, original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_render_review_site() {
+        let translations = vec![
+            entry("www/data/Items.json", "[1].name", "<薬草>", "Herb & Co"),
+            entry("www/data/Map001.json", "events[1].pages[0].list[0].parameters[0]", "こんにちは", ""),
+        ];
+        let site = render_review_site("/games/quest", &translations, "ja", "en");
+        assert_eq!(
+            site.keys().collect::<Vec<_>>(),
+            vec!["001-www_data_Items_json.html", "002-www_data_Map001_json.html", "index.html", "review.css", "review.js"]
+        );
+        let items = &site["001-www_data_Items_json.html"];
+        assert!(items.contains("&lt;薬草&gt;") && items.contains("Herb &amp; Co"));
+        assert!(items.contains(r#"data-path="[1].name""#));
+        assert!(items.contains(r#"<body data-project="/games/quest">"#));
+        assert!(site["002-www_data_Map001_json.html"].contains("badge untranslated"));
+        assert!(site["index.html"].contains(r#"<a href="002-www_data_Map001_json.html">www/data/Map001.json</a>"#));
+    }
+
+    #[test]
+    fn test_merge_review_comments() {
+        let translations = vec![entry("www/data/Items.json", "[1].name", "薬草", "Herb")];
+        let comment = |json_path: &str, text: &str| ReviewComment {
+            source_file: "www/data/Items.json".to_string(),
+            json_path: json_path.to_string(),
+            comment: text.to_string(),
+            reviewer: Some("Ann".to_string()),
+        };
+        let incoming = parse_review_comments(
+            r#"{"comments":[{"sourceFile":"www/data/Items.json","jsonPath":"[1].name","comment":"Use 'Medicinal Herb'","reviewer":"Ann"},
+                            {"sourceFile":"www/data/Items.json","jsonPath":"[9].name","comment":"?"}]}"#,
+        )
+        .unwrap();
+        let (stored, report) = merge_review_comments(vec![comment("[1].name", "old")], incoming, &translations);
+        assert_eq!((report.rows, report.imported, report.cleared, report.unmatched.len()), (2, 1, 0, 1));
+        assert_eq!(stored, vec![comment("[1].name", "Use 'Medicinal Herb'")]);
+
+        let (stored, report) = merge_review_comments(stored, vec![comment("[1].name", " ")], &translations);
+        assert_eq!(report.cleared, 1);
+        assert!(stored.is_empty());
+    }
+}
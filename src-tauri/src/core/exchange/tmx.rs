@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::core::prompts::language_family;
+use crate::core::translation_memory::{MemoryEntry, TranslationMemory};
+use crate::error::CoreError;
+use crate::models::exchange::TmxImportReport;
+use super::{escape_xml_attribute, escape_xml_text, xml_attribute};
+
+/// Provider recorded for imported pairs that don't carry one.
+pub const TMX_PROVIDER: &str = "tmx";
+const PROVIDER_PROP: &str = "x-provider";
+
+// TMX has no escape for characters XML cannot contain, so they are left out.
+fn escape_text(text: &str) -> String {
+    escape_xml_text(text, |_| String::new())
+}
+
+/// Serializes memory entries as a TMX 1.4 document, one `<tu>` per entry. The provider that
+/// produced each translation is kept in an `x-provider` property.
+pub fn to_tmx(entries: &[MemoryEntry]) -> String {
+    let source_languages: HashSet<&str> = entries.iter().map(|e| e.source_lang.as_str()).collect();
+    let header_source = match source_languages.iter().next() {
+        Some(lang) if source_languages.len() == 1 => lang,
+        _ => "*all*",
+    };
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tmx version=\"1.4\">\n");
+    out.push_str(&format!(
+        "  <header creationtool=\"Translate-AI\" creationtoolversion=\"{}\" segtype=\"block\" o-tmf=\"translate-ai\" adminlang=\"en\" srclang=\"{}\" datatype=\"plaintext\"/>\n",
+        env!("CARGO_PKG_VERSION"),
+        escape_xml_attribute(header_source)
+    ));
+    out.push_str("  <body>\n");
+    for entry in entries {
+        out.push_str(&format!("    <tu srclang=\"{}\">\n", escape_xml_attribute(&entry.source_lang)));
+        out.push_str(&format!("      <prop type=\"{}\">{}</prop>\n", PROVIDER_PROP, escape_text(&entry.provider)));
+        for (lang, text) in [(&entry.source_lang, &entry.original_text), (&entry.target_lang, &entry.translated_text)] {
+            out.push_str(&format!(
+                "      <tuv xml:lang=\"{}\"><seg>{}</seg></tuv>\n",
+                escape_xml_attribute(lang),
+                escape_text(text)
+            ));
+        }
+        out.push_str("    </tu>\n");
+    }
+    out.push_str("  </body>\n</tmx>\n");
+    out
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedTmx {
+    pub units: usize,
+    pub entries: Vec<MemoryEntry>,
+    pub skipped: usize,
+}
+
+#[derive(Default)]
+struct UnitState {
+    source_lang: Option<String>,
+    provider: Option<String>,
+    // (language, segment text) per <tuv>.
+    variants: Vec<(String, String)>,
+}
+
+impl UnitState {
+    /// One entry per target variant. The source variant is the one in the unit's (or the
+    /// header's) source language, or the first one when the file says `*all*`.
+    fn into_entries(self, header_source: Option<&str>) -> Vec<MemoryEntry> {
+        let source_lang = self
+            .source_lang
+            .as_deref()
+            .or(header_source)
+            .filter(|lang| *lang != "*all*")
+            .map(language_family);
+        let source_index = match &source_lang {
+            Some(lang) => self.variants.iter().position(|(l, _)| l == lang),
+            None => (!self.variants.is_empty()).then_some(0),
+        };
+        let Some(source_index) = source_index else { return Vec::new() };
+        let (source_lang, original_text) = &self.variants[source_index];
+        if original_text.is_empty() {
+            return Vec::new();
+        }
+        let provider = self.provider.unwrap_or_else(|| TMX_PROVIDER.to_string());
+        self.variants
+            .iter()
+            .enumerate()
+            .filter(|(i, (lang, text))| *i != source_index && lang != source_lang && !text.is_empty())
+            .map(|(_, (target_lang, translated_text))| MemoryEntry {
+                source_lang: source_lang.clone(),
+                target_lang: target_lang.clone(),
+                original_text: original_text.clone(),
+                translated_text: translated_text.clone(),
+                provider: provider.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Reads the translation pairs of a TMX file. Inline markup inside `<seg>` (`<ph>`, `<bpt>`,
+/// ...) holds the native codes, so its text is kept as part of the segment.
+pub fn parse_tmx(content: &str) -> Result<ParsedTmx, CoreError> {
+    let mut reader = Reader::from_str(content);
+    let mut parsed = ParsedTmx::default();
+    let mut header_source: Option<String> = None;
+    let mut unit: Option<UnitState> = None;
+    let mut variant_lang: Option<String> = None;
+    let mut segment: Option<String> = None;
+    let mut prop_type: Option<String> = None;
+    let mut prop_value = String::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| CoreError::Custom(format!("Invalid TMX at byte {}: {}", reader.buffer_position(), e)))?;
+        match event {
+            Event::Start(element) | Event::Empty(element) if element.local_name().as_ref() == b"header" => {
+                header_source = xml_attribute(&element, "srclang")?;
+            }
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"tu" => {
+                    unit = Some(UnitState { source_lang: xml_attribute(&element, "srclang")?, ..Default::default() })
+                }
+                b"tuv" => {
+                    // TMX 1.1 used `lang` instead of `xml:lang`.
+                    let lang = match xml_attribute(&element, "xml:lang")? {
+                        Some(lang) => Some(lang),
+                        None => xml_attribute(&element, "lang")?,
+                    };
+                    variant_lang = lang.map(|l| language_family(&l));
+                }
+                b"seg" if variant_lang.is_some() => segment = Some(String::new()),
+                b"prop" if unit.is_some() && segment.is_none() => {
+                    prop_type = xml_attribute(&element, "type")?;
+                    prop_value.clear();
+                }
+                _ => {}
+            },
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| CoreError::Custom(format!("Invalid TMX text: {}", e)))?;
+                match (&mut segment, &prop_type) {
+                    (Some(segment), _) => segment.push_str(&text),
+                    (None, Some(_)) => prop_value.push_str(&text),
+                    _ => {}
+                }
+            }
+            Event::CData(text) => {
+                if let Some(segment) = segment.as_mut() {
+                    segment.push_str(&String::from_utf8_lossy(&text.into_inner()));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"seg" => {
+                    if let (Some(unit), Some(lang), Some(text)) = (unit.as_mut(), &variant_lang, segment.take()) {
+                        unit.variants.push((lang.clone(), text));
+                    }
+                }
+                b"tuv" => variant_lang = None,
+                b"prop" => {
+                    if let (Some(unit), Some(PROVIDER_PROP)) = (unit.as_mut(), prop_type.as_deref()) {
+                        unit.provider = Some(prop_value.trim().to_string()).filter(|p| !p.is_empty());
+                    }
+                    prop_type = None;
+                }
+                b"tu" => {
+                    if let Some(unit) = unit.take() {
+                        parsed.units += 1;
+                        let entries = unit.into_entries(header_source.as_deref());
+                        if entries.is_empty() {
+                            parsed.skipped += 1;
+                        }
+                        parsed.entries.extend(entries);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(parsed)
+}
+
+/// Writes the memory to a TMX file, optionally limited to one language pair.
+pub fn export_tmx_file(
+    memory: &TranslationMemory,
+    language_pair: Option<(&str, &str)>,
+    output_path: &Path,
+) -> Result<usize, CoreError> {
+    let mut entries = memory.entries()?;
+    if let Some((source_lang, target_lang)) = language_pair {
+        entries.retain(|e| e.source_lang == source_lang && e.target_lang == target_lang);
+    }
+    fs::write(output_path, to_tmx(&entries))
+        .map_err(|e| CoreError::Io(format!("Failed to write TMX to {:?}: {}", output_path, e)))?;
+    Ok(entries.len())
+}
+
+/// Adds the pairs of a TMX file to the memory. Pairs repeated in the file are imported once;
+/// pairs the memory already has are only replaced when `overwrite` is set.
+pub fn import_tmx_file(memory: &TranslationMemory, file_path: &Path, overwrite: bool) -> Result<TmxImportReport, CoreError> {
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", file_path, e)))?;
+    let parsed = parse_tmx(&content)?;
+    let mut report = TmxImportReport { units: parsed.units, skipped: parsed.skipped, ..Default::default() };
+
+    let mut seen = HashSet::new();
+    let mut to_store = Vec::new();
+    for entry in parsed.entries {
+        if !seen.insert((entry.source_lang.clone(), entry.target_lang.clone(), entry.original_text.clone())) {
+            report.duplicates += 1;
+            continue;
+        }
+        match memory.lookup(&entry.source_lang, &entry.target_lang, &entry.original_text)? {
+            Some(existing) if existing.translated_text == entry.translated_text => report.unchanged += 1,
+            Some(_) if !overwrite => report.kept_existing += 1,
+            _ => to_store.push(entry),
+        }
+    }
+    memory.store_entries(&to_store)?;
+    report.imported = to_store.len();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_tmx_roundtrip() {
+        let memory = TranslationMemory::open_in_memory().unwrap();
+        memory.store("ja", "en", "勇者<A&B>", "Hero <A&B>", "ollama").unwrap();
+        memory.store("ja", "en", "はい\r\n", "Yes\r\n", "deepl").unwrap();
+        let tmx = to_tmx(&memory.entries().unwrap());
+        assert!(tmx.contains("srclang=\"ja\" datatype=\"plaintext\""));
+
+        let parsed = parse_tmx(&tmx).unwrap();
+        assert_eq!((parsed.units, parsed.skipped), (2, 0));
+        assert_eq!(parsed.entries, memory.entries().unwrap());
+    }
+
+    #[test]
+    fn test_parse_tmx_from_other_tools() {
+        let tmx = r#"<?xml version="1.0"?>
+<tmx version="1.4"><header srclang="ja-JP" segtype="sentence" datatype="plaintext" adminlang="en" creationtool="x" creationtoolversion="1" o-tmf="x"/>
+<body>
+  <tu><tuv xml:lang="en-US"><seg>Go to <ph x="1">\C[2]</ph>town</seg></tuv><tuv xml:lang="ja-JP"><seg><ph x="1">\C[2]</ph>町へ行け</seg></tuv><tuv xml:lang="fr"><seg>Va en ville</seg></tuv></tu>
+  <tu><tuv xml:lang="ja-JP"><seg>孤独</seg></tuv></tu>
+</body></tmx>"#;
+        let parsed = parse_tmx(tmx).unwrap();
+        assert_eq!((parsed.units, parsed.skipped, parsed.entries.len()), (2, 1, 2));
+        let english = &parsed.entries[0];
+        assert_eq!(
+            (english.source_lang.as_str(), english.target_lang.as_str(), english.original_text.as_str()),
+            ("ja", "en", "\\C[2]町へ行け")
+        );
+        assert_eq!((english.translated_text.as_str(), english.provider.as_str()), ("Go to \\C[2]town", TMX_PROVIDER));
+        assert_eq!(parsed.entries[1].target_lang, "fr");
+    }
+
+    #[test]
+    fn test_import_deduplicates() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("shared.tmx");
+        let entry = |original: &str, translated: &str| MemoryEntry {
+            source_lang: "ja".to_string(),
+            target_lang: "en".to_string(),
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            provider: "openai".to_string(),
+        };
+        let file_entries = vec![entry("はい", "Yes"), entry("はい", "Yeah"), entry("いいえ", "No"), entry("剣", "Blade")];
+        fs::write(&file_path, to_tmx(&file_entries)).unwrap();
+
+        let memory = TranslationMemory::open_in_memory().unwrap();
+        memory.store("ja", "en", "いいえ", "No", "ollama").unwrap();
+        memory.store("ja", "en", "剣", "Sword", "ollama").unwrap();
+
+        let report = import_tmx_file(&memory, &file_path, false).unwrap();
+        assert_eq!(
+            report,
+            TmxImportReport { units: 4, imported: 1, duplicates: 1, unchanged: 1, kept_existing: 1, skipped: 0 }
+        );
+        assert_eq!(memory.lookup("ja", "en", "はい").unwrap().unwrap().translated_text, "Yes");
+        assert_eq!(memory.lookup("ja", "en", "剣").unwrap().unwrap().translated_text, "Sword");
+
+        let report = import_tmx_file(&memory, &file_path, true).unwrap();
+        assert_eq!((report.imported, report.unchanged), (1, 2));
+        assert_eq!(memory.lookup("ja", "en", "剣").unwrap().unwrap().translated_text, "Blade");
+    }
+}
@@ -0,0 +1,334 @@
+use std::fs;
+use std::path::Path;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::error::CoreError;
+use crate::models::exchange::ImportedTranslation;
+use crate::models::translation::WorkingTranslation;
+use super::{escape_xml_attribute, escape_xml_text, xml_attribute};
+
+const XLIFF_NAMESPACE: &str = "urn:oasis:names:tc:xliff:document:2.0";
+const METADATA_NAMESPACE: &str = "urn:oasis:names:tc:xliff:metadata:2.0";
+
+/// `category` of the metadata group holding our entry identifiers.
+pub const META_CATEGORY: &str = "translate-ai";
+pub const META_SOURCE_FILE: &str = "sourceFile";
+pub const META_JSON_PATH: &str = "jsonPath";
+pub const META_OBJECT_ID: &str = "objectId";
+
+// Characters XML 1.0 cannot contain (e.g. ESC) are written as XLIFF `<cp/>` elements.
+fn escape_text(text: &str) -> String {
+    escape_xml_text(text, |c| format!("<cp hex=\"{:04X}\"/>", c as u32))
+}
+
+fn push_meta(out: &mut String, meta_type: &str, value: &str) {
+    out.push_str(&format!("          <mda:meta type=\"{}\">{}</mda:meta>\n", meta_type, escape_text(value)));
+}
+
+fn push_unit(out: &mut String, unit_id: usize, entry: &WorkingTranslation) {
+    out.push_str(&format!("    <unit id=\"u{}\">\n", unit_id));
+    out.push_str("      <mda:metadata>\n");
+    out.push_str(&format!("        <mda:metaGroup category=\"{}\">\n", META_CATEGORY));
+    push_meta(out, META_SOURCE_FILE, &entry.source_file);
+    push_meta(out, META_JSON_PATH, &entry.json_path);
+    push_meta(out, META_OBJECT_ID, &entry.object_id.to_string());
+    out.push_str("        </mda:metaGroup>\n");
+    out.push_str("      </mda:metadata>\n");
+
+    // Failed entries are exported as untranslated, with the error as a note for the translator.
+    let translated = entry.error.is_none() && !entry.translated_text.is_empty();
+    if let Some(error) = &entry.error {
+        out.push_str(&format!("      <notes>\n        <note category=\"error\">{}</note>\n      </notes>\n", escape_text(error)));
+    }
+    out.push_str(&format!("      <segment state=\"{}\">\n", if translated { "translated" } else { "initial" }));
+    out.push_str(&format!("        <source>{}</source>\n", escape_text(&entry.original_text)));
+    if translated {
+        out.push_str(&format!("        <target>{}</target>\n", escape_text(&entry.translated_text)));
+    }
+    out.push_str("      </segment>\n");
+    out.push_str("    </unit>\n");
+}
+
+/// Serializes entries as an XLIFF 2.0 document, one `<file>` per source data file (in order
+/// of first appearance). Each unit carries the entry's source file, JSON path and object id
+/// as metadata so the document can be merged back after a round-trip through a CAT tool.
+pub fn to_xliff(translations: &[WorkingTranslation], source_language: &str, target_language: &str) -> String {
+    let mut files: Vec<(&str, Vec<&WorkingTranslation>)> = Vec::new();
+    for entry in translations {
+        match files.iter_mut().find(|(name, _)| *name == entry.source_file) {
+            Some((_, entries)) => entries.push(entry),
+            None => files.push((&entry.source_file, vec![entry])),
+        }
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<xliff xmlns=\"{}\" xmlns:mda=\"{}\" version=\"2.0\" srcLang=\"{}\" trgLang=\"{}\" xml:space=\"preserve\">\n",
+        XLIFF_NAMESPACE,
+        METADATA_NAMESPACE,
+        escape_xml_attribute(source_language),
+        escape_xml_attribute(target_language)
+    ));
+    let mut unit_id = 0;
+    for (file_index, (source_file, entries)) in files.iter().enumerate() {
+        out.push_str(&format!("  <file id=\"f{}\" original=\"{}\">\n", file_index + 1, escape_xml_attribute(source_file)));
+        for entry in entries {
+            unit_id += 1;
+            push_unit(&mut out, unit_id, entry);
+        }
+        out.push_str("  </file>\n");
+    }
+    out.push_str("</xliff>\n");
+    out
+}
+
+pub fn export_xliff_file(
+    translations: &[WorkingTranslation],
+    source_language: &str,
+    target_language: &str,
+    output_path: &Path,
+) -> Result<(), CoreError> {
+    fs::write(output_path, to_xliff(translations, source_language, target_language))
+        .map_err(|e| CoreError::Io(format!("Failed to write XLIFF to {:?}: {}", output_path, e)))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Capture {
+    Source,
+    Target,
+    Meta,
+}
+
+// Text collected for the unit being read. Units split into several segments by a CAT tool
+// are joined back together.
+#[derive(Default)]
+struct UnitState {
+    json_path: Option<String>,
+    source_file: Option<String>,
+    source: String,
+    target: String,
+    missing_target: bool,
+    // Current <segment> or <ignorable>.
+    part_source: String,
+    part_target: Option<String>,
+}
+
+/// Reads the units of an XLIFF 2.0 document. The entry is identified by our metadata,
+/// falling back to the `<file original>` attribute for the source file. Units whose
+/// segments are not all translated come back with an empty translation.
+pub fn parse_xliff(content: &str) -> Result<Vec<ImportedTranslation>, CoreError> {
+    let mut reader = Reader::from_str(content);
+    let mut rows = Vec::new();
+    let mut file_original: Option<String> = None;
+    let mut unit: Option<UnitState> = None;
+    let mut capture: Option<Capture> = None;
+    let mut meta_type = String::new();
+    let mut meta_value = String::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| CoreError::Custom(format!("Invalid XLIFF at byte {}: {}", reader.buffer_position(), e)))?;
+        match event {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"file" => file_original = xml_attribute(&element, "original")?,
+                b"unit" => unit = Some(UnitState::default()),
+                b"segment" | b"ignorable" => {
+                    if let Some(unit) = unit.as_mut() {
+                        unit.part_source.clear();
+                        unit.part_target = None;
+                    }
+                }
+                b"source" if unit.is_some() => capture = Some(Capture::Source),
+                b"target" if unit.is_some() => {
+                    if let Some(unit) = unit.as_mut() {
+                        unit.part_target = Some(String::new());
+                    }
+                    capture = Some(Capture::Target);
+                }
+                b"meta" if unit.is_some() => {
+                    meta_type = xml_attribute(&element, "type")?.unwrap_or_default();
+                    meta_value.clear();
+                    capture = Some(Capture::Meta);
+                }
+                _ => {}
+            },
+            Event::Empty(element) => match element.local_name().as_ref() {
+                // <target/> is a translation to an empty string; <cp/> is an escaped character.
+                b"target" => {
+                    if let Some(unit) = unit.as_mut() {
+                        unit.part_target = Some(String::new());
+                    }
+                }
+                b"cp" => {
+                    let code_point = xml_attribute(&element, "hex")?
+                        .and_then(|hex| u32::from_str_radix(&hex, 16).ok())
+                        .and_then(char::from_u32);
+                    if let (Some(c), Some(unit)) = (code_point, unit.as_mut()) {
+                        push_captured(unit, capture, &mut meta_value, &c.to_string());
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(text) => {
+                if let Some(unit) = unit.as_mut() {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| CoreError::Custom(format!("Invalid XLIFF text: {}", e)))?;
+                    push_captured(unit, capture, &mut meta_value, &text);
+                }
+            }
+            Event::CData(text) => {
+                if let Some(unit) = unit.as_mut() {
+                    push_captured(unit, capture, &mut meta_value, &String::from_utf8_lossy(&text.into_inner()));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"source" | b"target" => capture = None,
+                b"meta" => {
+                    capture = None;
+                    if let Some(unit) = unit.as_mut() {
+                        match meta_type.as_str() {
+                            META_SOURCE_FILE => unit.source_file = Some(meta_value.clone()),
+                            META_JSON_PATH => unit.json_path = Some(meta_value.clone()),
+                            _ => {}
+                        }
+                    }
+                }
+                b"segment" | b"ignorable" => {
+                    let is_ignorable = element.local_name().as_ref() == b"ignorable";
+                    if let Some(unit) = unit.as_mut() {
+                        unit.source.push_str(&unit.part_source);
+                        match unit.part_target.take() {
+                            Some(target) => unit.target.push_str(&target),
+                            // Ignorable parts (whitespace between segments) are copied as is.
+                            None if is_ignorable => unit.target.push_str(&unit.part_source),
+                            None => unit.missing_target = true,
+                        }
+                    }
+                }
+                b"unit" => {
+                    if let Some(unit) = unit.take() {
+                        rows.push(ImportedTranslation {
+                            source_file: unit.source_file.or_else(|| file_original.clone()).unwrap_or_default(),
+                            json_path: unit.json_path.unwrap_or_default(),
+                            original_text: unit.source,
+                            translated_text: if unit.missing_target { String::new() } else { unit.target },
+                        });
+                    }
+                }
+                b"file" => file_original = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+fn push_captured(unit: &mut UnitState, capture: Option<Capture>, meta_value: &mut String, text: &str) {
+    match capture {
+        Some(Capture::Source) => unit.part_source.push_str(text),
+        Some(Capture::Target) => unit.part_target.get_or_insert_with(String::new).push_str(text),
+        Some(Capture::Meta) => meta_value.push_str(text),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    fn entry(source_file: &str, json_path: &str, original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 3,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_xliff_groups_files_and_keeps_metadata() {
+        let translations = vec![
+            entry("www/data/Map001.json", "events[1].pages[0].list[2].parameters[0]", "\\C[2]勇者\\C[0]<来い>", "\\C[2]Hero\\C[0] <come> & go"),
+            entry("www/data/Items.json", "[1].name", "ポーション", ""),
+            entry("www/data/Map001.json", "events[2].pages[0].list[0].parameters[0]", "はい", "Yes"),
+        ];
+        let xliff = to_xliff(&translations, "ja", "en");
+
+        assert!(xliff.contains("version=\"2.0\" srcLang=\"ja\" trgLang=\"en\""));
+        assert_eq!(xliff.matches("<file ").count(), 2);
+        let map_file = xliff.find("original=\"www/data/Map001.json\"").unwrap();
+        let items_file = xliff.find("original=\"www/data/Items.json\"").unwrap();
+        assert!(map_file < items_file);
+        // The second Map001 entry is grouped with the first one, before Items.json.
+        assert!(xliff.find("<source>はい</source>").unwrap() < items_file);
+
+        assert!(xliff.contains("<mda:meta type=\"jsonPath\">events[1].pages[0].list[2].parameters[0]</mda:meta>"));
+        assert!(xliff.contains("<mda:meta type=\"objectId\">3</mda:meta>"));
+        assert!(xliff.contains("<source>\\C[2]勇者\\C[0]&lt;来い&gt;</source>"));
+        assert!(xliff.contains("<target>\\C[2]Hero\\C[0] &lt;come&gt; &amp; go</target>"));
+        assert!(xliff.contains("<segment state=\"initial\">\n        <source>ポーション</source>\n      </segment>"));
+    }
+
+    #[test]
+    fn test_parse_xliff_roundtrip() {
+        let mut failed = entry("www/data/Items.json", "[1].name", "ポーション", "");
+        failed.error = Some("timeout".to_string());
+        let translations = vec![
+            entry("www/data/Map001.json", "events[1].pages[0].list[2].parameters[0]", "勇者<来い>\r\n\u{1b}", "Hero <come> & go\r\n"),
+            failed,
+        ];
+        let rows = parse_xliff(&to_xliff(&translations, "ja", "en")).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ImportedTranslation {
+                    source_file: "www/data/Map001.json".to_string(),
+                    json_path: "events[1].pages[0].list[2].parameters[0]".to_string(),
+                    original_text: "勇者<来い>\r\n\u{1b}".to_string(),
+                    translated_text: "Hero <come> & go\r\n".to_string(),
+                },
+                ImportedTranslation {
+                    source_file: "www/data/Items.json".to_string(),
+                    json_path: "[1].name".to_string(),
+                    original_text: "ポーション".to_string(),
+                    translated_text: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_xliff_joins_segments() {
+        let xliff = r#"<?xml version="1.0"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="ja" trgLang="en">
+  <file id="f1" original="www/data/Map002.json">
+    <unit id="u1">
+      <segment><source>おはよう。</source><target>Good morning.</target></segment>
+      <ignorable><source> </source></ignorable>
+      <segment><source>元気？</source><target><![CDATA[How are you?]]></target></segment>
+    </unit>
+  </file>
+</xliff>"#;
+        let rows = parse_xliff(xliff).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].source_file, "www/data/Map002.json");
+        assert_eq!(rows[0].json_path, "");
+        assert_eq!(rows[0].original_text, "おはよう。 元気？");
+        assert_eq!(rows[0].translated_text, "Good morning. How are you?");
+    }
+
+    #[test]
+    fn test_escape_text_handles_invalid_xml_characters() {
+        assert_eq!(escape_text("a\u{1b}b\r\n\t"), "a<cp hex=\"001B\"/>b&#13;\n\t");
+        assert_eq!(escape_xml_attribute("\"a\u{1b}\""), "&quot;a&quot;");
+    }
+}
@@ -0,0 +1,124 @@
+use std::ops::RangeInclusive;
+use crate::core::config::load_project_config;
+use crate::core::prompts::language_family;
+use crate::error::CoreError;
+use crate::models::translation::WorkingTranslation;
+use crate::models::validation::CharacterCoverageIssue;
+
+/// Printable ASCII, Latin-1 and general punctuation: drawn by the fonts of every game.
+const BASE_RANGES: [RangeInclusive<char>; 3] = ['\u{0020}'..='\u{007E}', '\u{00A0}'..='\u{00FF}', '\u{2000}'..='\u{206F}'];
+
+/// CJK symbols and punctuation, full-width forms and ideographs.
+const CJK_RANGES: [RangeInclusive<char>; 3] = ['\u{3000}'..='\u{303F}', '\u{FF00}'..='\u{FFEF}', '\u{4E00}'..='\u{9FFF}'];
+
+/// Kana.
+const KANA_RANGES: [RangeInclusive<char>; 1] = ['\u{3040}'..='\u{30FF}'];
+
+/// Hangul syllables and compatibility jamo.
+const HANGUL_RANGES: [RangeInclusive<char>; 2] = ['\u{AC00}'..='\u{D7AF}', '\u{3130}'..='\u{318F}'];
+
+/// What the bundled font of a game in `source_language` is assumed to draw.
+pub fn default_ranges(source_language: &str) -> Vec<RangeInclusive<char>> {
+    let mut ranges = BASE_RANGES.to_vec();
+    match language_family(source_language).as_str() {
+        "ja" => ranges.extend(CJK_RANGES.iter().chain(KANA_RANGES.iter()).cloned()),
+        "zh" => ranges.extend(CJK_RANGES.iter().cloned()),
+        "ko" => ranges.extend(CJK_RANGES.iter().chain(HANGUL_RANGES.iter()).cloned()),
+        _ => {}
+    }
+    ranges
+}
+
+fn parse_code_point(text: &str, range: &str) -> Result<char, CoreError> {
+    let hex = text.trim();
+    let hex = hex.strip_prefix("U+").or_else(|| hex.strip_prefix("u+")).unwrap_or(hex);
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| CoreError::Config(format!("Invalid font range '{}': '{}' isn't a code point", range, text.trim())))
+}
+
+/// A range written as "0020-007E", "U+0400-U+04FF" or a single code point ("00E9").
+pub fn parse_range(range: &str) -> Result<RangeInclusive<char>, CoreError> {
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    let (start, end) = (parse_code_point(start, range)?, parse_code_point(end, range)?);
+    if start > end {
+        return Err(CoreError::Config(format!("Invalid font range '{}': it ends before it starts", range)));
+    }
+    Ok(start..=end)
+}
+
+/// The characters the game's font can draw: the configured `fontRanges`, or the defaults of the
+/// source language when there are none.
+pub struct CharacterCoverage {
+    ranges: Vec<RangeInclusive<char>>,
+}
+
+impl CharacterCoverage {
+    pub fn new(ranges: &[String], source_language: &str) -> Result<Self, CoreError> {
+        let ranges: Vec<_> = ranges
+            .iter()
+            .filter(|range| !range.trim().is_empty())
+            .map(|range| parse_range(range))
+            .collect::<Result<_, _>>()?;
+        let ranges = if ranges.is_empty() { default_ranges(source_language) } else { ranges };
+        Ok(CharacterCoverage { ranges })
+    }
+
+    /// Characters of `text` the font can't draw, each once, in order of appearance. Whitespace
+    /// and line breaks are never reported.
+    pub fn unsupported(&self, text: &str) -> Vec<char> {
+        let mut missing = Vec::new();
+        for c in text.chars() {
+            if !c.is_whitespace() && !self.ranges.iter().any(|range| range.contains(&c)) && !missing.contains(&c) {
+                missing.push(c);
+            }
+        }
+        missing
+    }
+}
+
+/// Translated strings with characters outside the project's font ranges, which the game would
+/// draw as blanks or boxes. Failed and untranslated entries are skipped.
+pub fn project_character_coverage(
+    project_path: &str,
+    translations: &[WorkingTranslation],
+) -> Result<Vec<CharacterCoverageIssue>, CoreError> {
+    let config = load_project_config(project_path)?;
+    let source_language = config.source_language.as_deref().unwrap_or("ja");
+    let coverage = CharacterCoverage::new(&config.font_ranges, source_language)?;
+    Ok(translations
+        .iter()
+        .filter(|entry| entry.error.is_none() && !entry.translated_text.is_empty())
+        .filter_map(|entry| {
+            let characters = coverage.unsupported(&entry.translated_text);
+            (!characters.is_empty()).then(|| CharacterCoverageIssue {
+                source_file: entry.source_file.clone(),
+                json_path: entry.json_path.clone(),
+                translated_text: entry.translated_text.clone(),
+                characters: characters.into_iter().map(String::from).collect(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsupported_characters() {
+        let japanese = CharacterCoverage::new(&[], "ja-JP").unwrap();
+        assert!(japanese.unsupported("\\C[2]Harold\\C[0]: « Café », ポーション！\n…").is_empty());
+        assert_eq!(japanese.unsupported("Привет, мир! Привет"), vec!['П', 'р', 'и', 'в', 'е', 'т', 'м']);
+        assert_eq!(japanese.unsupported("안녕"), vec!['안', '녕']);
+
+        let configured = CharacterCoverage::new(&["U+0020-U+007E".to_string(), "0400-04FF".to_string()], "ja").unwrap();
+        assert!(configured.unsupported("Привет!").is_empty());
+        assert_eq!(configured.unsupported("Café"), vec!['é']);
+
+        assert_eq!(parse_range("00E9").unwrap(), 'é'..='é');
+        assert!(parse_range("007E-0020").is_err());
+        assert!(CharacterCoverage::new(&["Latin".to_string()], "ja").is_err());
+    }
+}
@@ -6,6 +6,11 @@ use std::path::Path;
 pub enum RpgMakerDetectionResult {
     DetectedByProjectFile, // Found Game.rpgproject
     DetectedByWwwData,   // Found www/data structure
+    DetectedWolfRpg,     // Wolf RPG Editor game (Data.wolf or Data/BasicData); extracted from text dumps
+    DetectedRenPy,       // Ren'Py game (game/ folder with .rpy/.rpyc scripts)
+    DetectedRpgVxAce,    // RPG Maker VX Ace game (Game.rvproj2, Data/*.rvdata2 or Game.rgss3a)
+    DetectedTyranoScript, // TyranoScript game (data/scenario/*.ks)
+    DetectedGenericRules, // Any other game with genericRules in translate-ai.json (JSON/YAML files)
     NotDetected,         // Not an RPG Maker MV project by our checks
 }
 
@@ -27,5 +32,25 @@ pub fn detect_rpg_maker_mv(project_path: &str) -> RpgMakerDetectionResult {
         return RpgMakerDetectionResult::DetectedByWwwData;
     }
 
+    if crate::core::wolfrpg::project::is_wolf_rpg_project(project_path) {
+        return RpgMakerDetectionResult::DetectedWolfRpg;
+    }
+
+    if crate::core::renpy::project::is_renpy_project(project_path) {
+        return RpgMakerDetectionResult::DetectedRenPy;
+    }
+
+    if crate::core::rpgvxace::project::is_vx_ace_project(project_path) {
+        return RpgMakerDetectionResult::DetectedRpgVxAce;
+    }
+
+    if crate::core::tyrano::project::is_tyrano_project(project_path) {
+        return RpgMakerDetectionResult::DetectedTyranoScript;
+    }
+
+    if crate::core::generic::project::is_generic_project(project_path) {
+        return RpgMakerDetectionResult::DetectedGenericRules;
+    }
+
     RpgMakerDetectionResult::NotDetected
 } 
\ No newline at end of file
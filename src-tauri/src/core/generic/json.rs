@@ -0,0 +1,122 @@
+use std::ops::Range;
+use super::path::PathPart;
+use super::{Leaf, ScalarStyle};
+
+/// Walks a JSON document and lists its string values with their byte spans, so translations
+/// can be written back without reformatting the file.
+pub fn string_leaves(content: &str) -> Result<Vec<Leaf>, String> {
+    // serde_json reports malformed files with a line and column; the scanner below can then
+    // assume a well-formed document.
+    serde_json::from_str::<serde_json::Value>(content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut scanner = Scanner { bytes: content.as_bytes(), text: content, position: 0, leaves: Vec::new() };
+    scanner.value(&mut Vec::new())?;
+    Ok(scanner.leaves)
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    text: &'a str,
+    position: usize,
+    leaves: Vec<Leaf>,
+}
+
+impl Scanner<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.position).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.bytes.get(self.position).copied()
+    }
+
+    // Span of the string literal starting at the current `"`, quotes included.
+    fn string_span(&mut self) -> Range<usize> {
+        let start = self.position;
+        self.position += 1;
+        while let Some(&byte) = self.bytes.get(self.position) {
+            self.position += 1;
+            match byte {
+                b'\\' => self.position += 1,
+                b'"' => break,
+                _ => {}
+            }
+        }
+        start..self.position
+    }
+
+    fn decode(&self, span: &Range<usize>) -> Result<String, String> {
+        serde_json::from_str(&self.text[span.clone()]).map_err(|e| format!("Invalid JSON string: {}", e))
+    }
+
+    fn value(&mut self, path: &mut Vec<PathPart>) -> Result<(), String> {
+        match self.peek() {
+            Some(b'{') => {
+                self.position += 1;
+                while self.peek() != Some(b'}') {
+                    let span = self.string_span();
+                    let key = self.decode(&span)?;
+                    self.peek(); // ':'
+                    self.position += 1;
+                    path.push(PathPart::Key(key));
+                    self.value(path)?;
+                    path.pop();
+                    if self.peek() == Some(b',') {
+                        self.position += 1;
+                    }
+                }
+                self.position += 1;
+            }
+            Some(b'[') => {
+                self.position += 1;
+                let mut index = 0;
+                while self.peek() != Some(b']') {
+                    path.push(PathPart::Index(index));
+                    self.value(path)?;
+                    path.pop();
+                    index += 1;
+                    if self.peek() == Some(b',') {
+                        self.position += 1;
+                    }
+                }
+                self.position += 1;
+            }
+            Some(b'"') => {
+                let span = self.string_span();
+                let value = self.decode(&span)?;
+                self.leaves.push(Leaf { path: path.clone(), span, value, style: ScalarStyle::Json });
+            }
+            Some(_) => {
+                // Numbers, true, false, null.
+                let is_scalar_byte = |b: &u8| !matches!(b, b',' | b']' | b'}') && !b.is_ascii_whitespace();
+                while self.bytes.get(self.position).is_some_and(is_scalar_byte) {
+                    self.position += 1;
+                }
+            }
+            None => return Err("Unexpected end of JSON".to_string()),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::generic::path::render_path;
+
+    #[test]
+    fn test_string_leaves() {
+        let content = "{\n  \"title\": \"冒険\",\n  \"lines\": [\"a\\\"b\", 3, null],\n  \"npc\": {\"name\": \"ハロルド\", \"hp\": -1.5e3}\n}";
+        let leaves = string_leaves(content).unwrap();
+        let found: Vec<(String, &str)> = leaves.iter().map(|l| (render_path(&l.path), l.value.as_str())).collect();
+        assert_eq!(found, vec![
+            ("title".to_string(), "冒険"),
+            ("lines[0]".to_string(), "a\"b"),
+            ("npc.name".to_string(), "ハロルド"),
+        ]);
+        assert_eq!(&content[leaves[1].span.clone()], "\"a\\\"b\"");
+        assert!(string_leaves("{\"a\": }").is_err());
+    }
+}
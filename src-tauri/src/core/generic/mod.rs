@@ -0,0 +1,179 @@
+// Generic projects: games and mods whose text lives in plain JSON or YAML files. Rules in
+// `translate-ai.json` (a file glob plus path selectors) choose the string values to translate,
+// and translated copies are written by replacing only those values, keeping the rest of each
+// file byte for byte.
+pub mod path;
+pub mod json;
+pub mod yaml;
+pub mod project;
+
+use std::collections::HashMap;
+use std::ops::Range;
+use crate::error::CoreError;
+use crate::models::translation::WorkingTranslation;
+use path::{render_path, PathPart};
+
+/// How a string value is written in its file, so a translation can be written the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarStyle {
+    Json,
+    Plain,
+    SingleQuoted,
+    DoubleQuoted,
+    // `|` and `>` block scalars; `indent` is the indentation of their lines.
+    Literal { indent: usize },
+    Folded { indent: usize },
+}
+
+/// A string value of a JSON or YAML file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaf {
+    pub path: Vec<PathPart>,
+    // Bytes of the value as written (quotes included), in the file content.
+    pub span: Range<usize>,
+    pub value: String,
+    pub style: ScalarStyle,
+}
+
+/// True for the file types generic projects can read.
+pub fn is_supported_file(relative_path: &str) -> bool {
+    let lower = relative_path.to_ascii_lowercase();
+    lower.ends_with(".json") || lower.ends_with(".yaml") || lower.ends_with(".yml")
+}
+
+/// The string values of a `.json`, `.yaml` or `.yml` file. Spans are relative to `content`,
+/// including a leading BOM.
+pub fn string_leaves_for(relative_path: &str, content: &str) -> Result<Vec<Leaf>, String> {
+    let body = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let offset = content.len() - body.len();
+    let mut leaves = if relative_path.to_ascii_lowercase().ends_with(".json") {
+        json::string_leaves(body)?
+    } else if is_supported_file(relative_path) {
+        yaml::string_leaves(body)?
+    } else {
+        return Err(format!("Unsupported file type: {}", relative_path));
+    };
+    for leaf in &mut leaves {
+        leaf.span = leaf.span.start + offset..leaf.span.end + offset;
+    }
+    Ok(leaves)
+}
+
+fn is_plain_safe(text: &str) -> bool {
+    !text.is_empty()
+        && !text.starts_with(['-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`'])
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.ends_with(':')
+        && !text.contains(['\n', '\r', '\t'])
+        && text.trim() == text
+        && !yaml::is_non_string_plain(text)
+}
+
+/// Writes `text` in `style`. Plain and single-quoted values that can't hold the text are
+/// double-quoted instead.
+pub fn encode(style: ScalarStyle, text: &str, newline: &str) -> String {
+    let double_quoted = || serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text));
+    match style {
+        ScalarStyle::Json | ScalarStyle::DoubleQuoted => double_quoted(),
+        ScalarStyle::SingleQuoted if text.contains(['\n', '\r']) => double_quoted(),
+        ScalarStyle::SingleQuoted => format!("'{}'", text.replace('\'', "''")),
+        ScalarStyle::Plain if is_plain_safe(text) => text.to_string(),
+        ScalarStyle::Plain => double_quoted(),
+        ScalarStyle::Literal { indent } => {
+            let pad = " ".repeat(indent);
+            text.trim_end_matches('\n')
+                .split('\n')
+                .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", pad, line) })
+                .collect::<Vec<_>>()
+                .join(newline)
+        }
+        ScalarStyle::Folded { indent } => {
+            // A single line break folds into a space, so each kept break needs a blank line.
+            let pad = " ".repeat(indent);
+            let mut parts = text.trim_end_matches('\n').split('\n');
+            let mut out = format!("{}{}", pad, parts.next().unwrap_or(""));
+            for part in parts {
+                out.push_str(newline);
+                if !part.is_empty() {
+                    out.push_str(newline);
+                    out.push_str(&pad);
+                    out.push_str(part);
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Writes translations into a JSON or YAML file, matching them by path. Entries with an error
+/// or no translation keep the original text.
+pub fn reconstruct(content: &str, relative_path: &str, translations: Vec<&WorkingTranslation>) -> Result<String, CoreError> {
+    let leaves = string_leaves_for(relative_path, content).map_err(CoreError::Custom)?;
+    let by_path: HashMap<&str, &str> = translations
+        .iter()
+        .filter(|entry| entry.error.is_none() && !entry.translated_text.is_empty())
+        .map(|entry| (entry.json_path.as_str(), entry.translated_text.as_str()))
+        .collect();
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut output = content.to_string();
+    // From the end of the file, so earlier spans stay valid.
+    for leaf in leaves.iter().rev() {
+        if let Some(translated) = by_path.get(render_path(&leaf.path).as_str()) {
+            output.replace_range(leaf.span.clone(), &encode(leaf.style, translated, newline));
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    fn entry(json_path: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "quests.yaml".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "test".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_encode_styles() {
+        assert_eq!(encode(ScalarStyle::Plain, "Forest beast", "\n"), "Forest beast");
+        assert_eq!(encode(ScalarStyle::Plain, "Note: read me", "\n"), "\"Note: read me\"");
+        assert_eq!(encode(ScalarStyle::Plain, "yes", "\n"), "\"yes\"");
+        assert_eq!(encode(ScalarStyle::SingleQuoted, "It's", "\n"), "'It''s'");
+        assert_eq!(encode(ScalarStyle::Json, "say \"hi\"\n", "\n"), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(encode(ScalarStyle::Literal { indent: 2 }, "a\n\nb", "\n"), "  a\n\n  b");
+        assert_eq!(encode(ScalarStyle::Folded { indent: 2 }, "a\nb", "\n"), "  a\n\n  b");
+    }
+
+    #[test]
+    fn test_reconstruct_yaml_and_json() {
+        let yaml = "\u{feff}title: 冒険\r\nsteps:\r\n  - 'はい'\r\n  - 倒す\r\nbody: |\r\n  一行目\r\n  二行目\r\nid: 7\r\n";
+        let translations = [
+            entry("title", "Adventure: part 1"),
+            entry("steps[0]", "Yes, it's"),
+            entry("body", "Line one\nLine two"),
+            WorkingTranslation { error: Some("timeout".to_string()), ..entry("steps[1]", "Defeat") },
+        ];
+        let rebuilt = reconstruct(yaml, "quests.yaml", translations.iter().collect()).unwrap();
+        assert_eq!(
+            rebuilt,
+            "\u{feff}title: \"Adventure: part 1\"\r\nsteps:\r\n  - 'Yes, it''s'\r\n  - 倒す\r\nbody: |\r\n  Line one\r\n  Line two\r\nid: 7\r\n"
+        );
+        let values: Vec<String> = string_leaves_for("quests.yaml", &rebuilt).unwrap().into_iter().map(|l| l.value).collect();
+        assert_eq!(values, vec!["Adventure: part 1", "Yes, it's", "倒す", "Line one\nLine two"]);
+
+        let json = "{\n  \"name\": \"ハロルド\",\n  \"hp\": 10\n}\n";
+        let rebuilt = reconstruct(json, "npc.json", vec![&entry("name", "Harold \"the Bold\"")]).unwrap();
+        assert_eq!(rebuilt, "{\n  \"name\": \"Harold \\\"the Bold\\\"\",\n  \"hp\": 10\n}\n");
+    }
+}
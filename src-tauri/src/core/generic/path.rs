@@ -0,0 +1,209 @@
+use crate::error::CoreError;
+
+/// One step of a concrete path to a value: a mapping key or a sequence index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathPart {
+    Key(String),
+    Index(usize),
+}
+
+/// Renders a concrete path in the style of the RPG Maker parsers, e.g. "dialogue[3].text".
+/// Keys that aren't plain identifiers are quoted: "names['Sir Harold']".
+pub fn render_path(parts: &[PathPart]) -> String {
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            PathPart::Index(index) => out.push_str(&format!("[{}]", index)),
+            PathPart::Key(key) if is_identifier(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            PathPart::Key(key) => out.push_str(&format!("['{}']", key.replace('\\', "\\\\").replace('\'', "\\'"))),
+        }
+    }
+    out
+}
+
+fn is_identifier(key: &str) -> bool {
+    key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// One step of a selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    // `*` or `[*]`: any key or index.
+    Any,
+    // `..`: zero or more steps.
+    Descend,
+}
+
+/// A JSONPath-like selector choosing the string values to translate, e.g. `$.quests[*].title`,
+/// `..description` or `npcs.*.lines[0]`. YAML files use the same syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    steps: Vec<Step>,
+}
+
+fn invalid(selector: &str, reason: &str) -> CoreError {
+    CoreError::Config(format!("Invalid selector '{}': {}", selector, reason))
+}
+
+impl Selector {
+    pub fn parse(selector: &str) -> Result<Self, CoreError> {
+        let text = selector.trim();
+        let mut rest = text.strip_prefix('$').unwrap_or(text);
+        let mut steps = Vec::new();
+        let mut expect_key = true; // a leading key needs no dot
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("..") {
+                steps.push(Step::Descend);
+                rest = after;
+                expect_key = true;
+            } else if let Some(after) = rest.strip_prefix('.') {
+                rest = after;
+                expect_key = true;
+                if rest.is_empty() || rest.starts_with(['.', '[']) {
+                    return Err(invalid(selector, "a '.' must be followed by a key"));
+                }
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let (inside, after) = bracket(after).ok_or_else(|| invalid(selector, "unclosed '['"))?;
+                steps.push(match inside.trim() {
+                    "*" => Step::Any,
+                    quoted if quoted.len() >= 2 && (quoted.starts_with('\'') || quoted.starts_with('"')) => {
+                        Step::Key(unquote(quoted).ok_or_else(|| invalid(selector, "bad quoted key"))?)
+                    }
+                    index => Step::Index(index.parse().map_err(|_| invalid(selector, "index must be a number or *"))?),
+                });
+                rest = after;
+                expect_key = false;
+            } else if expect_key {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let key = &rest[..end];
+                if key.is_empty() || key.contains(']') {
+                    return Err(invalid(selector, "expected a key"));
+                }
+                steps.push(if key == "*" { Step::Any } else { Step::Key(key.to_string()) });
+                rest = &rest[end..];
+                expect_key = false;
+            } else {
+                return Err(invalid(selector, "expected '.' or '['"));
+            }
+        }
+        if steps.is_empty() || steps.last() == Some(&Step::Descend) {
+            return Err(invalid(selector, "it must end with a key, index or *"));
+        }
+        Ok(Selector { steps })
+    }
+
+    /// True when the selector chooses the value at `path`.
+    pub fn matches(&self, path: &[PathPart]) -> bool {
+        matches_steps(&self.steps, path)
+    }
+
+    /// The concrete path written by `render_path`, parsed back. `None` for selectors with
+    /// wildcards.
+    pub fn as_path(&self) -> Option<Vec<PathPart>> {
+        self.steps
+            .iter()
+            .map(|step| match step {
+                Step::Key(key) => Some(PathPart::Key(key.clone())),
+                Step::Index(index) => Some(PathPart::Index(*index)),
+                Step::Any | Step::Descend => None,
+            })
+            .collect()
+    }
+}
+
+// Content of a `[...]` (respecting quotes) and the text after the `]`.
+fn bracket(text: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (position, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ']') => return Some((&text[..position], &text[position + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote(quoted: &str) -> Option<String> {
+    let quote = quoted.chars().next()?;
+    let inner = quoted.strip_prefix(quote)?.strip_suffix(quote)?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        out.push(if c == '\\' { chars.next()? } else { c });
+    }
+    Some(out)
+}
+
+fn matches_steps(steps: &[Step], path: &[PathPart]) -> bool {
+    let Some((step, rest)) = steps.split_first() else { return path.is_empty() };
+    match step {
+        Step::Descend => (0..=path.len()).any(|skip| matches_steps(rest, &path[skip..])),
+        _ => {
+            let Some((part, path_rest)) = path.split_first() else { return false };
+            let step_matches = match (step, part) {
+                (Step::Any, _) => true,
+                (Step::Key(key), PathPart::Key(part)) => key == part,
+                (Step::Index(index), PathPart::Index(part)) => index == part,
+                _ => false,
+            };
+            step_matches && matches_steps(rest, path_rest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(parts: &[&str]) -> Vec<PathPart> {
+        parts
+            .iter()
+            .map(|p| p.parse().map(PathPart::Index).unwrap_or_else(|_| PathPart::Key(p.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_selector_matching() {
+        let selector = Selector::parse("$.quests[*].title").unwrap();
+        assert!(selector.matches(&path(&["quests", "2", "title"])));
+        assert!(!selector.matches(&path(&["quests", "2", "id"])));
+        assert!(!selector.matches(&path(&["quests", "title"])));
+
+        let descend = Selector::parse("..description").unwrap();
+        assert!(descend.matches(&path(&["description"])));
+        assert!(descend.matches(&path(&["items", "0", "stats", "description"])));
+
+        let quoted = Selector::parse("npcs['Old Man'].lines[0]").unwrap();
+        assert!(quoted.matches(&path(&["npcs", "Old Man", "lines", "0"])));
+        assert!(Selector::parse("npcs.*.name").unwrap().matches(&path(&["npcs", "guard", "name"])));
+
+        for bad in ["", "a.", "a[", "a[x]", "a..", "a]b"] {
+            assert!(Selector::parse(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_render_path_round_trip() {
+        let parts = path(&["dialogue", "3", "text"]);
+        assert_eq!(render_path(&parts), "dialogue[3].text");
+        let odd = vec![PathPart::Key("names".to_string()), PathPart::Key("Sir Harold's".to_string()), PathPart::Index(0)];
+        let rendered = render_path(&odd);
+        assert_eq!(rendered, "names['Sir Harold\\'s'][0]");
+        assert_eq!(Selector::parse(&rendered).unwrap().as_path(), Some(odd));
+        assert_eq!(render_path(&path(&["0", "name"])), "[0].name");
+        assert_eq!(Selector::parse("[0].name").unwrap().as_path(), Some(path(&["0", "name"])));
+    }
+}
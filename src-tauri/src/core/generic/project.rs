@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::core::config::{load_project_config, FileGlob, IgnoredFiles};
+use crate::core::rpgmv::backups::is_backup_dir_name;
+use crate::core::rpgmv::filter::CompiledExtractionFilter;
+use crate::error::CoreError;
+use crate::models::project_config::GenericRule;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::utils::project_paths::APP_DATA_DIR_NAME;
+use super::path::{render_path, Selector};
+use super::{is_supported_file, string_leaves_for};
+
+/// The configured `genericRules`, compiled.
+#[derive(Default)]
+pub struct GenericRules {
+    rules: Vec<(FileGlob, Vec<Selector>)>,
+}
+
+impl GenericRules {
+    pub fn new(rules: &[GenericRule]) -> Result<Self, CoreError> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                if rule.selectors.is_empty() {
+                    return Err(CoreError::Config(format!("Generic rule for '{}' has no selectors", rule.files)));
+                }
+                let selectors = rule.selectors.iter().map(|s| Selector::parse(s)).collect::<Result<_, _>>()?;
+                Ok((FileGlob::new(&rule.files)?, selectors))
+            })
+            .collect::<Result<_, CoreError>>()?;
+        Ok(GenericRules { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Selectors of the first rule matching the file, for JSON and YAML files only.
+    pub fn selectors_for(&self, relative_path: &str) -> Option<&[Selector]> {
+        if !is_supported_file(relative_path) {
+            return None;
+        }
+        self.rules.iter().find(|(glob, _)| glob.matches(relative_path)).map(|(_, selectors)| selectors.as_slice())
+    }
+}
+
+/// True when the project configuration has generic rules.
+pub fn is_generic_project(project_path: &str) -> bool {
+    load_project_config(project_path).is_ok_and(|config| !config.generic_rules.is_empty())
+}
+
+/// Relative paths (with `/`) of the files matched by a rule, leaving out the app's own folder
+/// and backup folders.
+pub fn matching_files(project_path: &str, rules: &GenericRules) -> Vec<String> {
+    let mut files: Vec<String> = WalkDir::new(project_path)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or("");
+            e.depth() == 0 || !(e.file_type().is_dir() && (name == APP_DATA_DIR_NAME || is_backup_dir_name(name)))
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| Some(e.path().strip_prefix(project_path).ok()?.to_str()?.replace('\\', "/")))
+        .filter(|file| rules.selectors_for(file).is_some())
+        .collect();
+    files.sort();
+    files
+}
+
+/// The string values of one file chosen by `selectors`. Object ids number the file's string
+/// values from 1, so they stay stable when the rules change.
+pub fn extract_strings(
+    content: &str,
+    source_file: &str,
+    selectors: Option<&[Selector]>,
+) -> Result<Vec<SourceStringData>, String> {
+    Ok(string_leaves_for(source_file, content)?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, leaf)| !leaf.value.trim().is_empty())
+        .filter(|(_, leaf)| selectors.map_or(true, |selectors| selectors.iter().any(|s| s.matches(&leaf.path))))
+        .map(|(index, leaf)| SourceStringData {
+            object_id: index as u32 + 1,
+            original_text: leaf.value,
+            source_file: source_file.to_string(),
+            json_path: render_path(&leaf.path),
+            dialogue_block_id: None,
+            speaker: None,
+        })
+        .collect())
+}
+
+/// Extracts the values chosen by the project's generic rules.
+pub fn extract_translatable_strings(
+    project_path: &str,
+    ignored: &IgnoredFiles,
+    filter: &CompiledExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let config = load_project_config(project_path).map_err(|e| e.to_string())?;
+    let rules = GenericRules::new(&config.generic_rules).map_err(|e| e.to_string())?;
+    let files = matching_files(project_path, &rules);
+    if files.is_empty() {
+        return Err(format!("No JSON or YAML files in {} match the generic rules", project_path));
+    }
+    let mut all_strings = Vec::new();
+    let mut parsing_errors = Vec::new();
+    for relative_file_path in files {
+        if should_stop() {
+            eprintln!("Extraction stopped after {} strings.", all_strings.len());
+            break;
+        }
+        if ignored.is_ignored(&relative_file_path) || !filter.allows_file(&relative_file_path) {
+            continue;
+        }
+        eprintln!("Processing file: {}", relative_file_path);
+        match fs::read_to_string(Path::new(project_path).join(&relative_file_path)) {
+            Ok(content) => match extract_strings(&content, &relative_file_path, rules.selectors_for(&relative_file_path)) {
+                Ok(mut file_strings) => all_strings.append(&mut file_strings),
+                Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
+            },
+            Err(e) => parsing_errors.push(format!("Failed to read file {}: {}", relative_file_path, e)),
+        }
+    }
+    all_strings.retain(|entry| filter.allows_entry(entry));
+    if !parsing_errors.is_empty() {
+        eprintln!("Errors during string extraction:\n{}", parsing_errors.join("\n"));
+    }
+    Ok(all_strings)
+}
+
+/// Rebuilds a JSON or YAML file with its translations applied.
+pub fn reconstruct_file(
+    original_content: &str,
+    relative_path: &str,
+    translations: Vec<&WorkingTranslation>,
+) -> Result<String, CoreError> {
+    super::reconstruct(original_content, relative_path, translations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::core::config::save_project_config;
+    use crate::models::extraction::ExtractionFilter;
+    use crate::models::project_config::ProjectConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_generic_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let project_path = root.to_str().unwrap();
+        fs::create_dir_all(root.join("mods/quests")).unwrap();
+        fs::create_dir_all(root.join(".translate-ai")).unwrap();
+        fs::write(root.join("mods/quests/forest.yaml"), "id: forest\ntitle: 森の魔物\nsteps:\n  - 話を聞く\n  - ''\n").unwrap();
+        fs::write(root.join("mods/items.json"), "{\"items\": [{\"id\": \"potion\", \"name\": \"薬草\"}]}").unwrap();
+        fs::write(root.join("mods/readme.txt"), "not data").unwrap();
+        fs::write(root.join(".translate-ai/cache.json"), "{\"name\": \"x\"}").unwrap();
+        assert!(!is_generic_project(project_path));
+
+        let rule = |files: &str, selectors: &[&str]| GenericRule {
+            files: files.to_string(),
+            selectors: selectors.iter().map(|s| s.to_string()).collect(),
+        };
+        let invalid = ProjectConfig { generic_rules: vec![rule("*.yaml", &["a["])], ..Default::default() };
+        assert!(matches!(save_project_config(project_path, &invalid), Err(CoreError::Config(_))));
+        let config = ProjectConfig {
+            generic_rules: vec![rule("mods/quests/*.yaml", &["title", "steps[*]"]), rule("*", &["..name"])],
+            ..Default::default()
+        };
+        save_project_config(project_path, &config).unwrap();
+        assert!(is_generic_project(project_path));
+
+        let filter = ExtractionFilter::default();
+        let compiled = CompiledExtractionFilter::new(&filter).unwrap();
+        let ignored = IgnoredFiles::new(&[]).unwrap();
+        let strings = extract_translatable_strings(project_path, &ignored, &compiled, &|| false).unwrap();
+        let found: Vec<(&str, u32, &str, &str)> = strings
+            .iter()
+            .map(|s| (s.source_file.as_str(), s.object_id, s.json_path.as_str(), s.original_text.as_str()))
+            .collect();
+        assert_eq!(found, vec![
+            ("mods/items.json", 2, "items[0].name", "薬草"),
+            ("mods/quests/forest.yaml", 2, "title", "森の魔物"),
+            ("mods/quests/forest.yaml", 3, "steps[0]", "話を聞く"),
+        ]);
+
+        let translation = WorkingTranslation {
+            object_id: 2,
+            original_text: "森の魔物".to_string(),
+            translated_text: "Forest Beast".to_string(),
+            source_file: "mods/quests/forest.yaml".to_string(),
+            json_path: "title".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let original = fs::read_to_string(root.join("mods/quests/forest.yaml")).unwrap();
+        let rebuilt = reconstruct_file(&original, "mods/quests/forest.yaml", vec![&translation]).unwrap();
+        assert_eq!(rebuilt, "id: forest\ntitle: Forest Beast\nsteps:\n  - 話を聞く\n  - ''\n");
+    }
+}
@@ -0,0 +1,367 @@
+use super::path::PathPart;
+use super::{Leaf, ScalarStyle};
+
+// Block-style YAML as written by hand and by most game/mod tools: nested mappings and sequences
+// with plain, quoted and `|`/`>` block scalars. Flow collections (`[a, b]`, `{a: 1}`), aliases
+// and multi-line quoted scalars are skipped rather than extracted.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Map,
+    Seq,
+}
+
+struct Line<'a> {
+    start: usize,
+    // Without the line ending.
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn indent(&self) -> usize {
+        self.text.len() - self.text.trim_start_matches(' ').len()
+    }
+
+    fn is_blank(&self) -> bool {
+        let body = self.text.trim();
+        body.is_empty() || body.starts_with('#')
+    }
+}
+
+struct Frame {
+    indent: usize,
+    kind: Kind,
+    path: Vec<PathPart>,
+    next_index: usize,
+}
+
+// A key or `-` with nothing after it: its value is the block that follows, if any.
+struct Pending {
+    indent: usize,
+    parent: Kind,
+    path: Vec<PathPart>,
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    stack: Vec<Frame>,
+    pending: Option<Pending>,
+    leaves: Vec<Leaf>,
+}
+
+fn unsupported(line_index: usize, what: &str) -> String {
+    format!("Unsupported YAML at line {}: {}", line_index + 1, what)
+}
+
+/// True for plain scalars YAML reads as null, booleans or numbers.
+pub(super) fn is_non_string_plain(text: &str) -> bool {
+    const WORDS: [&str; 10] = ["~", "null", "true", "false", "yes", "no", "on", "off", ".inf", ".nan"];
+    let lower = text.to_ascii_lowercase();
+    let unsigned = lower.trim_start_matches(['-', '+']);
+    WORDS.contains(&lower.as_str())
+        || WORDS.contains(&unsigned)
+        || unsigned.strip_prefix("0x").is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        || (unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.') && unsigned.replace('_', "").parse::<f64>().is_ok())
+}
+
+/// Position of the `:` ending a mapping key in `body`, if the line is a mapping entry.
+fn key_end(body: &str) -> Option<usize> {
+    if body.starts_with(['"', '\'']) {
+        let quote = body.chars().next()?;
+        let close = body[1..].find(quote)? + 1;
+        return body[close + 1..].trim_start().starts_with(':').then(|| close + 1 + body[close + 1..].find(':').unwrap_or(0));
+    }
+    if body.starts_with(['[', '{', '|', '>', '#']) {
+        return None;
+    }
+    body.char_indices()
+        .find(|(i, c)| *c == ':' && body[i + 1..].chars().next().map_or(true, |next| next == ' '))
+        .map(|(i, _)| i)
+}
+
+fn decode_key(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.starts_with('"') {
+        serde_json::from_str(raw).unwrap_or_else(|_| raw.trim_matches('"').to_string())
+    } else if let Some(inner) = raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')) {
+        inner.replace("''", "'")
+    } else {
+        raw.to_string()
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn new(content: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for raw in content.split_inclusive('\n') {
+            let text = raw.trim_end_matches('\n').trim_end_matches('\r');
+            lines.push(Line { start, text });
+            start += raw.len();
+        }
+        Parser { lines, stack: Vec::new(), pending: None, leaves: Vec::new() }
+    }
+
+    fn run(mut self) -> Result<Vec<Leaf>, String> {
+        let mut index = 0;
+        while index < self.lines.len() {
+            let line = &self.lines[index];
+            let indent = line.indent();
+            let body = &line.text[indent..];
+            if line.is_blank() {
+                index += 1;
+                continue;
+            }
+            if indent == 0 && (body.starts_with("---") || body.starts_with("...") || body.starts_with('%')) {
+                self.stack.clear();
+                self.pending = None;
+                index += 1;
+                continue;
+            }
+            let is_item = body == "-" || body.starts_with("- ");
+            if let Some(pending) = self.pending.take() {
+                if indent > pending.indent || (indent == pending.indent && is_item && pending.parent == Kind::Map) {
+                    let kind = if is_item { Kind::Seq } else { Kind::Map };
+                    self.stack.push(Frame { indent, kind, path: pending.path, next_index: 0 });
+                }
+            }
+            while let Some(top) = self.stack.last() {
+                if top.indent > indent || (top.indent == indent && top.kind == Kind::Seq && !is_item) {
+                    self.stack.pop();
+                } else {
+                    break;
+                }
+            }
+            if self.stack.is_empty() {
+                let kind = if is_item { Kind::Seq } else { Kind::Map };
+                self.stack.push(Frame { indent, kind, path: Vec::new(), next_index: 0 });
+            }
+            let top = self.stack.last().expect("a frame was pushed above");
+            if top.indent != indent || (top.kind == Kind::Seq) != is_item {
+                return Err(unsupported(index, "unexpected indentation"));
+            }
+            index = if is_item { self.item(index, indent)? } else { self.entry(index, indent)? } + 1;
+        }
+        Ok(self.leaves)
+    }
+
+    // `- value` at `column`; returns the last line used.
+    fn item(&mut self, index: usize, column: usize) -> Result<usize, String> {
+        let top = self.stack.last_mut().expect("items are parsed inside a frame");
+        let mut path = top.path.clone();
+        path.push(PathPart::Index(top.next_index));
+        top.next_index += 1;
+        let text = self.lines[index].text;
+        let after_dash = &text[column + 1..];
+        let value_column = column + 1 + (after_dash.len() - after_dash.trim_start().len());
+        let rest = &text[value_column..];
+        if rest.starts_with("- ") || rest == "-" {
+            return Err(unsupported(index, "nested sequence on one line"));
+        }
+        if key_end(rest).is_some() {
+            self.stack.push(Frame { indent: value_column, kind: Kind::Map, path, next_index: 0 });
+            return self.entry(index, value_column);
+        }
+        self.value(index, value_column, path, column, Kind::Seq)
+    }
+
+    // `key: value` at `column`; returns the last line used.
+    fn entry(&mut self, index: usize, column: usize) -> Result<usize, String> {
+        let text = self.lines[index].text;
+        let body = &text[column..];
+        if body.starts_with("? ") {
+            return Err(unsupported(index, "complex mapping key"));
+        }
+        let end = key_end(body).ok_or_else(|| unsupported(index, "expected 'key: value'"))?;
+        let top = self.stack.last().expect("entries are parsed inside a frame");
+        let mut path = top.path.clone();
+        path.push(PathPart::Key(decode_key(&body[..end])));
+        let after_colon = &body[end + 1..];
+        let value_column = column + end + 1 + (after_colon.len() - after_colon.trim_start().len());
+        self.value(index, value_column, path, column, Kind::Map)
+    }
+
+    // Last line (from `index`) continuing a value whose parent is at `parent_indent`: more
+    // indented lines, and blank lines between them.
+    fn continuation_end(&self, index: usize, parent_indent: usize) -> usize {
+        let mut last = index;
+        for (offset, line) in self.lines[index + 1..].iter().enumerate() {
+            if line.text.trim().is_empty() {
+                continue;
+            }
+            if line.indent() <= parent_indent {
+                break;
+            }
+            last = index + 1 + offset;
+        }
+        last
+    }
+
+    fn value(&mut self, index: usize, column: usize, path: Vec<PathPart>, parent_indent: usize, parent: Kind) -> Result<usize, String> {
+        let line = &self.lines[index];
+        let text = &line.text[column..];
+        let start = line.start + column;
+        match text.chars().next() {
+            None | Some('#') => {
+                self.pending = Some(Pending { indent: parent_indent, parent, path });
+                Ok(index)
+            }
+            Some('&') | Some('!') => {
+                // Anchor or tag before the value.
+                let token = text.find(' ').unwrap_or(text.len());
+                let value_column = column + token + (text[token..].len() - text[token..].trim_start().len());
+                self.value(index, value_column, path, parent_indent, parent)
+            }
+            Some('*') | Some('[') | Some('{') => Ok(self.continuation_end(index, parent_indent)),
+            Some('"') => {
+                let mut escaped = false;
+                let close = text[1..].char_indices().find_map(|(i, c)| {
+                    let found = !escaped && c == '"';
+                    escaped = !escaped && c == '\\';
+                    found.then_some(i + 1)
+                });
+                let close = close.ok_or_else(|| unsupported(index, "multi-line quoted scalar"))?;
+                if let Ok(value) = serde_json::from_str::<String>(&text[..=close]) {
+                    self.leaves.push(Leaf { path, span: start..start + close + 1, value, style: ScalarStyle::DoubleQuoted });
+                }
+                Ok(index)
+            }
+            Some('\'') => {
+                let bytes = text.as_bytes();
+                let mut position = 1;
+                let close = loop {
+                    match bytes.get(position) {
+                        Some(b'\'') if bytes.get(position + 1) == Some(&b'\'') => position += 2,
+                        Some(b'\'') => break position,
+                        Some(_) => position += 1,
+                        None => return Err(unsupported(index, "multi-line quoted scalar")),
+                    }
+                };
+                let value = text[1..close].replace("''", "'");
+                self.leaves.push(Leaf { path, span: start..start + close + 1, value, style: ScalarStyle::SingleQuoted });
+                Ok(index)
+            }
+            Some(indicator @ ('|' | '>')) => {
+                let last = self.continuation_end(index, parent_indent);
+                if last == index {
+                    return Ok(index);
+                }
+                let content = &self.lines[index + 1..=last];
+                let first = content.iter().find(|l| !l.text.trim().is_empty()).expect("continuation ends on a non-blank line");
+                let indent = first.indent();
+                let first_index = content.iter().position(|l| std::ptr::eq(l, first)).unwrap_or(0);
+                let lines: Vec<&str> = content[first_index..]
+                    .iter()
+                    .map(|l| if l.text.trim().is_empty() { "" } else { l.text.get(indent..).unwrap_or("") })
+                    .collect();
+                let (value, style) = if indicator == '|' {
+                    (lines.join("\n"), ScalarStyle::Literal { indent })
+                } else {
+                    let mut folded = String::new();
+                    let mut previous_blank = true;
+                    for line in &lines {
+                        if line.is_empty() {
+                            folded.push('\n');
+                        } else {
+                            if !previous_blank {
+                                folded.push(' ');
+                            }
+                            folded.push_str(line);
+                        }
+                        previous_blank = line.is_empty();
+                    }
+                    (folded, ScalarStyle::Folded { indent })
+                };
+                let last_line = &self.lines[last];
+                let span = first.start..last_line.start + last_line.text.len();
+                self.leaves.push(Leaf { path, span, value, style });
+                Ok(last)
+            }
+            Some(_) => {
+                let last = self.continuation_end(index, parent_indent);
+                let mut parts = vec![text];
+                parts.extend(self.lines[index + 1..=last].iter().map(|l| l.text.trim()));
+                let parts: Vec<&str> = parts
+                    .into_iter()
+                    .map(|part| part.find(" #").map_or(part, |comment| &part[..comment]).trim_end())
+                    .collect();
+                let value = parts.iter().filter(|p| !p.is_empty()).copied().collect::<Vec<_>>().join(" ");
+                if is_non_string_plain(&value) {
+                    return Ok(last);
+                }
+                let last_line = &self.lines[last];
+                let end = if last == index {
+                    start + parts[0].len()
+                } else {
+                    last_line.start + last_line.indent() + parts[parts.len() - 1].len()
+                };
+                self.leaves.push(Leaf { path, span: start..end, value, style: ScalarStyle::Plain });
+                Ok(last)
+            }
+        }
+    }
+}
+
+/// Lists the string values of a block-style YAML document with their byte spans.
+pub fn string_leaves(content: &str) -> Result<Vec<Leaf>, String> {
+    Parser::new(content).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::generic::path::render_path;
+
+    const QUESTS: &str = r#"# Quest text
+title: 'It''s a trap'
+version: 3
+quests:
+- id: 1
+  name: 森の魔物 # shown in the log
+  steps:
+    - "話を\"聞く\""
+    - 倒す
+  description: |
+    一行目
+
+    三行目
+  hint: >-
+    折り返し
+    される
+  enabled: true
+npcs:
+  Old Man:
+    greeting: ようこそ
+    aliases: [a, b]
+"#;
+
+    #[test]
+    fn test_string_leaves() {
+        let leaves = string_leaves(QUESTS).unwrap();
+        let found: Vec<(String, &str)> = leaves.iter().map(|l| (render_path(&l.path), l.value.as_str())).collect();
+        assert_eq!(found, vec![
+            ("title".to_string(), "It's a trap"),
+            ("quests[0].name".to_string(), "森の魔物"),
+            ("quests[0].steps[0]".to_string(), "話を\"聞く\""),
+            ("quests[0].steps[1]".to_string(), "倒す"),
+            ("quests[0].description".to_string(), "一行目\n\n三行目"),
+            ("quests[0].hint".to_string(), "折り返し される"),
+            ("npcs['Old Man'].greeting".to_string(), "ようこそ"),
+        ]);
+        assert_eq!(&QUESTS[leaves[1].span.clone()], "森の魔物");
+        assert_eq!(&QUESTS[leaves[4].span.clone()], "    一行目\n\n    三行目");
+        assert_eq!(leaves[4].style, ScalarStyle::Literal { indent: 4 });
+    }
+
+    #[test]
+    fn test_non_string_scalars_and_errors() {
+        for text in ["1", "-2.5", "0x1F", "1_000", "true", "No", "~", "null"] {
+            assert!(is_non_string_plain(text), "{}", text);
+        }
+        for text in ["1st", "Yesterday", "v1.2"] {
+            assert!(!is_non_string_plain(text), "{}", text);
+        }
+        assert!(string_leaves("a:\n    b: 1\n  c: 2\n").is_err());
+        assert!(string_leaves("a: \"unterminated\n").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::Path;
+use crate::error::CoreError;
+use crate::core::references::check_reference;
+use crate::core::translation_memory::TranslationMemory;
+use crate::models::glossary::{CacheImpact, Glossary, GlossaryTerm};
+use crate::models::reference::ReferenceLink;
+use crate::models::translation::WorkingTranslation;
+use crate::models::validation::LockedTerm;
+use crate::core::config::glossary_file_path;
+use crate::core::exchange::csv::{escape_csv_field, parse_records};
+
+/// Parses `source<delimiter>target[<delimiter>note[<delimiter>url]]` rows, read like the
+/// translation exchange files (`parse_records`). Blank rows and `#` comments are skipped, as is a
+/// leading `source,target` header row. Further columns are ignored.
+pub fn parse_delimited_terms(content: &str, delimiter: char) -> Result<Vec<GlossaryTerm>, CoreError> {
+    let mut terms = Vec::new();
+    for (index, fields) in parse_records(content, delimiter)?.into_iter().enumerate() {
+        if fields[0].trim_start().starts_with('#') {
+            continue;
+        }
+        if fields.len() < 2 {
+            return Err(CoreError::Custom(format!(
+                "Glossary row {} needs a source and a target column: '{}'",
+                index + 1,
+                fields[0]
+            )));
+        }
+        let (source, target) = (fields[0].trim(), fields[1].trim());
+        if terms.is_empty() && source.eq_ignore_ascii_case("source") && target.eq_ignore_ascii_case("target") {
+            continue;
+        }
+        if source.is_empty() || target.is_empty() {
+            return Err(CoreError::Custom(format!("Glossary row {} has an empty term", index + 1)));
+        }
+        let optional = |i: usize| fields.get(i).map(|f| f.trim().to_string()).filter(|f| !f.is_empty());
+        let reference = ReferenceLink { note: optional(2), url: optional(3) };
+        let references = if reference == ReferenceLink::default() { Vec::new() } else { vec![reference] };
+        terms.push(GlossaryTerm { source: source.to_string(), target: target.to_string(), references });
+    }
+    Ok(terms)
+}
+
+/// `.tsv` and `.txt` files are tab-separated, anything else is treated as CSV.
+fn delimiter_for_path(path: &Path) -> char {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("tsv") | Some("txt") => '\t',
+        _ => ',',
+    }
+}
+
+/// Adds terms to the glossary. A term whose source already exists replaces the old target,
+/// and its references when the new term has any.
+pub fn merge_terms(glossary: &mut Glossary, terms: Vec<GlossaryTerm>) {
+    for term in terms {
+        match glossary.terms.iter_mut().find(|t| t.source == term.source) {
+            Some(existing) => {
+                existing.target = term.target;
+                if !term.references.is_empty() {
+                    existing.references = term.references;
+                }
+            }
+            None => glossary.terms.push(term),
+        }
+    }
+}
+
+/// Writes the glossary as CSV (`source,target,note,url`), readable by `import_glossary_file`.
+/// Terms with several references get one note/url pair joined with ` | `.
+pub fn export_glossary_file(glossary: &Glossary, output_path: &Path) -> Result<(), CoreError> {
+    let mut out = String::from("source,target,note,url\n");
+    for term in &glossary.terms {
+        let join = |f: fn(&ReferenceLink) -> Option<&str>| {
+            term.references.iter().filter_map(f).collect::<Vec<_>>().join(" | ")
+        };
+        let notes = join(|r| r.note.as_deref());
+        let urls = join(|r| r.url.as_deref());
+        out.push_str(&format!(
+            "{},{},{},{}\n",
+            escape_csv_field(&term.source),
+            escape_csv_field(&term.target),
+            escape_csv_field(&notes),
+            escape_csv_field(&urls)
+        ));
+    }
+    fs::write(output_path, out)
+        .map_err(|e| CoreError::Io(format!("Failed to write glossary to {:?}: {}", output_path, e)))
+}
+
+pub fn save_project_glossary(project_path: &str, glossary: &Glossary) -> Result<(), CoreError> {
+    if let Some(term) = glossary.terms.iter().find(|t| t.source.trim().is_empty() || t.target.trim().is_empty()) {
+        return Err(CoreError::Config(format!(
+            "Glossary terms need both a source and a target (got '{}' -> '{}')",
+            term.source, term.target
+        )));
+    }
+    for reference in glossary.terms.iter().flat_map(|t| &t.references) {
+        check_reference(reference)?;
+    }
+    let path = glossary_file_path(project_path)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(glossary)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize glossary: {}", e)))?;
+    fs::write(&path, json).map_err(|e| CoreError::Io(format!("Failed to write glossary to {:?}: {}", path, e)))
+}
+
+/// Loads the project's glossary (see `glossaryPath` in the project config), or an empty one if
+/// the project has none yet.
+pub fn load_project_glossary(project_path: &str) -> Result<Glossary, CoreError> {
+    let path = glossary_file_path(project_path)?;
+    if !path.is_file() {
+        return Ok(Glossary::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read glossary {:?}: {}", path, e)))?;
+    serde_json::from_str(&content).map_err(|e| CoreError::JsonParse(format!("Invalid glossary file {:?}: {}", path, e)))
+}
+
+/// Imports a CSV/TSV file into the project glossary, either merged into or replacing the current terms.
+pub fn import_glossary_file(project_path: &str, file_path: &Path, replace: bool) -> Result<Glossary, CoreError> {
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read glossary file {:?}: {}", file_path, e)))?;
+    let terms = parse_delimited_terms(&content, delimiter_for_path(file_path))?;
+    let mut glossary = if replace { Glossary::default() } else { load_project_glossary(project_path)? };
+    merge_terms(&mut glossary, terms);
+    save_project_glossary(project_path, &glossary)?;
+    Ok(glossary)
+}
+
+/// Glossary terms occurring in `text`, longest source first.
+pub fn terms_in_text<'a>(glossary: &'a Glossary, text: &str) -> Vec<&'a GlossaryTerm> {
+    let mut found: Vec<&GlossaryTerm> = glossary.terms.iter().filter(|t| text.contains(&t.source)).collect();
+    found.sort_by_key(|t| std::cmp::Reverse(t.source.chars().count()));
+    found
+}
+
+/// Replaces glossary terms the provider left untranslated in the output with their fixed
+/// translation. Returns true if the translation was changed.
+pub fn apply_glossary(entry: &mut WorkingTranslation, glossary: &Glossary) -> bool {
+    if entry.error.is_some() || entry.translated_text.is_empty() {
+        return false;
+    }
+    let mut changed = false;
+    for term in terms_in_text(glossary, &entry.original_text) {
+        if entry.translated_text.contains(&term.source) {
+            entry.translated_text = entry.translated_text.replace(&term.source, &term.target);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Glossary terms as locked terms, so validation reports translations that don't use them.
+pub fn as_locked_terms(glossary: &Glossary) -> Vec<LockedTerm> {
+    glossary
+        .terms
+        .iter()
+        .map(|t| LockedTerm { source: t.source.clone(), target: t.target.clone() })
+        .collect()
+}
+
+const CACHE_IMPACT_EXAMPLES: usize = 10;
+
+/// Source terms whose entry differs between the two glossaries (including added and removed ones).
+pub fn changed_terms(old: &Glossary, new: &Glossary) -> Vec<String> {
+    let mut changed: Vec<String> = old
+        .terms
+        .iter()
+        .filter(|term| !new.terms.contains(term))
+        .chain(new.terms.iter().filter(|term| !old.terms.contains(term)))
+        .map(|term| term.source.clone())
+        .collect();
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
+/// What replacing `old` with `new` does to the translation memory: the cached responses whose
+/// text contains a changed term get a different prompt context and are translated again.
+pub fn glossary_cache_impact(memory: &TranslationMemory, old: &Glossary, new: &Glossary) -> Result<CacheImpact, CoreError> {
+    let changed_terms = changed_terms(old, new);
+    let cached = memory.context_bound_entries()?;
+    let affected: Vec<String> = cached
+        .iter()
+        .filter(|entry| changed_terms.iter().any(|term| entry.original_text.contains(term.as_str())))
+        .map(|entry| entry.original_text.clone())
+        .collect();
+    Ok(CacheImpact {
+        changed_terms,
+        affected_entries: affected.len(),
+        cached_entries: cached.len(),
+        examples: affected.into_iter().take(CACHE_IMPACT_EXAMPLES).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::core::references::describe_reference;
+    use tempfile::tempdir;
+
+    fn term(source: &str, target: &str) -> GlossaryTerm {
+        GlossaryTerm { source: source.to_string(), target: target.to_string(), references: Vec::new() }
+    }
+
+    #[test]
+    fn test_parse_csv_and_tsv() {
+        let csv = "source,target\nハロルド,Harold\n\"王都\",\"Royal Capital, the\"\n# comment\n\n";
+        assert_eq!(
+            parse_delimited_terms(csv, ',').unwrap(),
+            vec![term("ハロルド", "Harold"), term("王都", "Royal Capital, the")]
+        );
+        let tsv = parse_delimited_terms("ポーション\tPotion\tHealing item\t\textra", '\t').unwrap();
+        assert_eq!(tsv[0].references, vec![ReferenceLink { url: None, note: Some("Healing item".to_string()) }]);
+        assert!(parse_delimited_terms("ハロルド", ',').is_err());
+    }
+
+    #[test]
+    fn test_export_roundtrips_references() {
+        let dir = tempdir().unwrap();
+        let mut selen = term("セレン", "Selen");
+        selen.references = vec![ReferenceLink {
+            url: Some("https://example.org/wiki/Selen".to_string()),
+            note: Some("Port town, \"the white city\"\nNorth of the capital".to_string()),
+        }];
+        let glossary = Glossary { terms: vec![selen, term("ハロルド", "Harold")] };
+
+        let path = dir.path().join("glossary.csv");
+        export_glossary_file(&glossary, &path).unwrap();
+        let parsed = parse_delimited_terms(&fs::read_to_string(&path).unwrap(), ',').unwrap();
+        assert_eq!(parsed, glossary.terms);
+        assert!(describe_reference(&parsed[0].references[0]).starts_with("Port town"));
+    }
+
+    #[test]
+    fn test_import_merges_into_project_glossary() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        save_project_glossary(project_path, &Glossary { terms: vec![term("ハロルド", "Harald")] }).unwrap();
+
+        let file = dir.path().join("terms.tsv");
+        fs::write(&file, "ハロルド\tHarold\nマーシャ\tMarsha\n").unwrap();
+        let glossary = import_glossary_file(project_path, &file, false).unwrap();
+
+        assert_eq!(glossary.terms, vec![term("ハロルド", "Harold"), term("マーシャ", "Marsha")]);
+        assert_eq!(load_project_glossary(project_path).unwrap(), glossary);
+    }
+
+    #[test]
+    fn test_apply_glossary_replaces_leftover_terms() {
+        let glossary = Glossary { terms: vec![term("ハロルド", "Harold"), term("ハロルド王", "King Harold")] };
+        let mut entry = WorkingTranslation {
+            object_id: 1,
+            original_text: "ハロルド王が来た".to_string(),
+            translated_text: "ハロルド王 has arrived".to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "events[1].pages[0].list[0].parameters[0]".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        assert!(apply_glossary(&mut entry, &glossary));
+        assert_eq!(entry.translated_text, "King Harold has arrived");
+        assert!(!apply_glossary(&mut entry, &glossary));
+    }
+
+    #[test]
+    fn test_glossary_cache_impact() {
+        let memory = TranslationMemory::open_in_memory().unwrap();
+        memory.store_in_context("ja", "en", "ハロルドが来た", "Harold has arrived", "ollama", "a").unwrap();
+        memory.store_in_context("ja", "en", "マーシャが来た", "Marsha has arrived", "ollama", "b").unwrap();
+        memory.store("ja", "en", "ハロルド", "Harold", "tmx").unwrap();
+
+        let old = Glossary { terms: vec![term("ハロルド", "Harold"), term("マーシャ", "Marsha")] };
+        let new = Glossary { terms: vec![term("マーシャ", "Marsha"), term("ハロルド", "Harald")] };
+        let impact = glossary_cache_impact(&memory, &old, &new).unwrap();
+        assert_eq!(impact.changed_terms, vec!["ハロルド"]);
+        assert_eq!((impact.affected_entries, impact.cached_entries), (1, 2));
+        assert_eq!(impact.examples, vec!["ハロルドが来た"]);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::core::control_codes::ControlCodes;
+use crate::core::prompts::language_family;
+
+/// Common short words of the Latin-script languages told apart by `latin_language`.
+const STOPWORDS: [(&str, &[&str]); 6] = [
+    ("en", &["the", "and", "you", "is", "are", "of", "to", "it", "this", "that", "with", "for", "have", "not", "your", "was", "will", "be", "in", "on", "my", "i'm", "can't", "don't"]),
+    ("fr", &["le", "la", "les", "et", "est", "vous", "une", "un", "des", "du", "pas", "que", "qui", "pour", "avec", "je", "tu", "il", "nous", "ce", "c'est", "mon"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ein", "eine", "ich", "du", "sie", "mit", "zu", "den", "dem", "wir", "auf", "für", "mein"]),
+    ("es", &["el", "los", "las", "y", "es", "que", "un", "una", "no", "por", "con", "para", "tú", "está", "se", "lo", "del", "mi"]),
+    ("it", &["il", "lo", "gli", "le", "e", "è", "che", "un", "una", "di", "non", "per", "con", "sono", "sei", "del", "mio"]),
+    ("pt", &["o", "os", "as", "e", "é", "que", "um", "uma", "não", "por", "com", "para", "você", "do", "da", "meu"]),
+];
+
+/// Letters that only (or mostly) one of those languages uses.
+const DIACRITICS: [(&str, &str); 5] = [("de", "äöüß"), ("es", "ñ¿¡"), ("fr", "çèêëœàù"), ("pt", "ãõ"), ("it", "ìò")];
+
+#[derive(Debug, Default)]
+struct ScriptCounts {
+    kana: usize,
+    han: usize,
+    hangul: usize,
+    cyrillic: usize,
+    latin: usize,
+}
+
+fn count_scripts(text: &str) -> ScriptCounts {
+    let mut counts = ScriptCounts::default();
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}' => counts.kana += 1,
+            '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' => counts.han += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => counts.hangul += 1,
+            '\u{0400}'..='\u{04FF}' => counts.cyrillic += 1,
+            c if c.is_alphabetic() && (c.is_ascii() || ('\u{00C0}'..='\u{024F}').contains(&c)) => counts.latin += 1,
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// The Latin-script language whose common words and letters `text` uses most, if any stands out.
+fn latin_language(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphabetic() && c != '\'').filter(|w| !w.is_empty()).collect();
+    let mut scores: Vec<(&str, usize)> = STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let word_hits = words.iter().filter(|w| stopwords.contains(w)).count();
+            let letter_hits = DIACRITICS
+                .iter()
+                .filter(|(l, _)| l == language)
+                .map(|(_, letters)| lower.chars().filter(|c| letters.contains(*c)).count())
+                .sum::<usize>();
+            (*language, word_hits + letter_hits)
+        })
+        .collect();
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    match (scores[0], scores[1]) {
+        ((language, best), (_, second)) if best > 0 && best > second => Some(language),
+        _ => None,
+    }
+}
+
+/// Language code of `text` as far as its script and common words tell: "ja" for any kana, "ko",
+/// "zh" (Han without kana), "ru" for Cyrillic, and the Latin-script languages by their common
+/// words. `None` when there are no letters or a Latin text can't be told apart.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let counts = count_scripts(text);
+    let non_latin = counts.kana + counts.han + counts.hangul + counts.cyrillic;
+    if counts.kana > 0 {
+        Some("ja")
+    } else if counts.hangul > 0 {
+        Some("ko")
+    } else if counts.han > 0 {
+        Some("zh")
+    } else if counts.cyrillic > counts.latin {
+        Some("ru")
+    } else if counts.latin > 0 && non_latin == 0 {
+        latin_language(text)
+    } else {
+        None
+    }
+}
+
+// Short all-caps terms games keep untranslated ("HP", "EXP", "ATK").
+fn is_abbreviation(text: &str) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    (2..=4).contains(&letters.len()) && letters.iter().all(|c| c.is_ascii_uppercase())
+}
+
+/// Whether a source string is already in the target language, so translating it would waste a
+/// request (and could garble English UI terms). Control codes are ignored. When the source
+/// language uses another script, a Latin-script text without source-script letters counts as
+/// translated if it reads as the target language, or is an abbreviation, or (for English
+/// targets) its language can't be told. Otherwise the detected language must be the target.
+pub fn is_in_target_language(text: &str, source_lang: &str, target_lang: &str) -> bool {
+    let text = ControlCodes::default().strip(text);
+    let (source, target) = (language_family(source_lang), language_family(target_lang));
+    if source == target {
+        return false;
+    }
+    let counts = count_scripts(&text);
+    let letters = counts.kana + counts.han + counts.hangul + counts.cyrillic + counts.latin;
+    if letters == 0 {
+        return false;
+    }
+    let latin_target = STOPWORDS.iter().any(|(language, _)| *language == target);
+    let latin_source = STOPWORDS.iter().any(|(language, _)| *language == source);
+    let detected = detect_language(&text);
+    if latin_target && !latin_source {
+        if counts.latin != letters {
+            return false;
+        }
+        return match detected {
+            Some(language) => language == target,
+            None => target == "en" || is_abbreviation(&text),
+        };
+    }
+    detected == Some(target.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strings_already_in_the_target_language() {
+        assert_eq!(detect_language("ポーションを手に入れた"), Some("ja"));
+        assert_eq!(detect_language("안녕하세요"), Some("ko"));
+        assert_eq!(detect_language("You got the potion!"), Some("en"));
+        assert_eq!(detect_language("Vous avez trouvé une potion"), Some("fr"));
+        assert_eq!(detect_language("Potion"), None);
+
+        assert!(is_in_target_language("\\C[2]You got the potion!\\C[0]", "ja", "en"));
+        assert!(is_in_target_language("Potion", "ja", "en"));
+        assert!(is_in_target_language("HP", "ja", "fr"));
+        assert!(!is_in_target_language("Potion", "ja", "fr"));
+        assert!(!is_in_target_language("You got the potion!", "ja", "fr"));
+        assert!(!is_in_target_language("ポーション", "ja", "en"));
+        assert!(!is_in_target_language("\\V[1] 100", "ja", "en"));
+        assert!(is_in_target_language("Vous avez trouvé une potion", "en", "fr"));
+        assert!(!is_in_target_language("Potion", "en", "fr"));
+    }
+}
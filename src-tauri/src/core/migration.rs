@@ -0,0 +1,243 @@
+use serde_json::{Map, Value};
+use crate::error::CoreError;
+use crate::models::migration::{MigrationReport, UnmigratedField};
+
+/// Format version written into `session.json`. Files without a version are version 1: the
+/// camelCase sessions saved before versioning, and the older snake_case ones from the
+/// `TranslatableStringEntry` era.
+pub const SESSION_FORMAT_VERSION: u32 = 2;
+const UNVERSIONED: u32 = 1;
+const VERSION_KEY: &str = "version";
+
+// Upgrade steps, indexed by the version they upgrade from.
+type MigrationStep = fn(&mut Value, &mut MigrationReport);
+const SESSION_MIGRATIONS: [(u32, MigrationStep); 1] = [(1, normalize_legacy_session)];
+
+/// Upgrades a parsed session file to `SESSION_FORMAT_VERSION`. Values that can't be carried
+/// over are listed in the report instead of failing the load.
+pub fn migrate_session_value(mut value: Value) -> Result<(Value, MigrationReport), CoreError> {
+    let from_version = match value.get(VERSION_KEY) {
+        None => UNVERSIONED,
+        Some(version) => version
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| CoreError::JsonParse(format!("Invalid session version {}", version)))?,
+    };
+    if from_version > SESSION_FORMAT_VERSION {
+        return Err(CoreError::Config(format!(
+            "Session format version {} is newer than supported version {}",
+            from_version, SESSION_FORMAT_VERSION
+        )));
+    }
+    if !value.is_object() {
+        return Err(CoreError::JsonParse("A session file must contain a JSON object".to_string()));
+    }
+    let mut report = MigrationReport { from_version, to_version: SESSION_FORMAT_VERSION, ..Default::default() };
+    for (version, step) in SESSION_MIGRATIONS {
+        if version >= from_version {
+            step(&mut value, &mut report);
+        }
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert(VERSION_KEY.to_string(), Value::from(SESSION_FORMAT_VERSION));
+    }
+    Ok((value, report))
+}
+
+/// Takes the first of `aliases` present in `object`, recording a rename when it isn't the
+/// current name (the first alias).
+fn take_field(object: &mut Map<String, Value>, aliases: &[&str], report: &mut MigrationReport) -> Option<Value> {
+    let (alias, value) = aliases.iter().find_map(|alias| Some((*alias, object.remove(*alias)?)))?;
+    for other in aliases {
+        object.remove(*other);
+    }
+    if alias != aliases[0] {
+        *report.renamed_fields.entry(format!("{} -> {}", alias, aliases[0])).or_default() += 1;
+    }
+    Some(value)
+}
+
+fn default_field(report: &mut MigrationReport, name: &str) {
+    *report.defaulted_fields.entry(name.to_string()).or_default() += 1;
+}
+
+fn unmigrated(report: &mut MigrationReport, path: String, reason: &str) {
+    report.unmigrated.push(UnmigratedField { path, reason: reason.to_string() });
+}
+
+fn string_field(
+    object: &mut Map<String, Value>,
+    aliases: &[&str],
+    path: &str,
+    report: &mut MigrationReport,
+) -> Option<String> {
+    match take_field(object, aliases, report)? {
+        Value::String(text) => Some(text),
+        Value::Number(number) => Some(number.to_string()),
+        _ => {
+            let location = if path.is_empty() { aliases[0].to_string() } else { format!("{}.{}", path, aliases[0]) };
+            unmigrated(report, location, "not a string");
+            None
+        }
+    }
+}
+
+// Legacy and current names of the fields shared by pending and translated entries.
+const OBJECT_ID: [&str; 3] = ["objectId", "object_id", "id"];
+const ORIGINAL_TEXT: [&str; 5] = ["originalText", "original_text", "text", "sourceText", "source_text"];
+const SOURCE_FILE: [&str; 5] = ["sourceFile", "source_file", "file", "filePath", "file_path"];
+const JSON_PATH: [&str; 3] = ["jsonPath", "json_path", "path"];
+const TRANSLATED_TEXT: [&str; 3] = ["translatedText", "translated_text", "translation"];
+const TRANSLATION_SOURCE: [&str; 4] = ["translationSource", "translation_source", "engine", "engineName"];
+
+/// Rebuilds one entry with the current field names; `None` (and a report line) when a required
+/// field is missing.
+fn migrate_entry(value: Value, path: &str, translated: bool, report: &mut MigrationReport) -> Option<Value> {
+    let Value::Object(mut object) = value else {
+        unmigrated(report, path.to_string(), "not an object");
+        report.dropped_entries += 1;
+        return None;
+    };
+    let mut entry = Map::new();
+    let object_id = match take_field(&mut object, &OBJECT_ID, report) {
+        Some(Value::Number(n)) if n.as_u64().is_some() => n.as_u64().unwrap_or(0),
+        Some(Value::String(s)) if s.trim().parse::<u64>().is_ok() => s.trim().parse().unwrap_or(0),
+        Some(_) => {
+            unmigrated(report, format!("{}.objectId", path), "not a number; set to 0");
+            0
+        }
+        None => {
+            default_field(report, "objectId");
+            0
+        }
+    };
+    entry.insert("objectId".to_string(), Value::from(object_id));
+
+    for aliases in [&ORIGINAL_TEXT[..], &SOURCE_FILE[..], &JSON_PATH[..]] {
+        let Some(text) = string_field(&mut object, aliases, path, report) else {
+            unmigrated(report, path.to_string(), &format!("missing {}; entry dropped", aliases[0]));
+            report.dropped_entries += 1;
+            return None;
+        };
+        entry.insert(aliases[0].to_string(), Value::String(text));
+    }
+
+    if translated {
+        let text = string_field(&mut object, &TRANSLATED_TEXT, path, report).unwrap_or_else(|| {
+            default_field(report, "translatedText");
+            String::new()
+        });
+        entry.insert("translatedText".to_string(), Value::String(text));
+        let source = string_field(&mut object, &TRANSLATION_SOURCE, path, report).unwrap_or_else(|| {
+            default_field(report, "translationSource");
+            "unknown".to_string()
+        });
+        entry.insert("translationSource".to_string(), Value::String(source));
+        let error = match object.remove("error") {
+            Some(Value::String(error)) => Value::String(error),
+            Some(Value::Null) | None => Value::Null,
+            Some(other) => {
+                unmigrated(report, format!("{}.error", path), "not a string; kept as text");
+                Value::String(other.to_string())
+            }
+        };
+        entry.insert("error".to_string(), error);
+    }
+
+    for key in object.keys() {
+        unmigrated(report, format!("{}.{}", path, key), "unknown field; dropped");
+    }
+    Some(Value::Object(entry))
+}
+
+fn migrate_entries(value: Option<Value>, name: &str, translated: bool, report: &mut MigrationReport) -> Value {
+    match value {
+        Some(Value::Array(entries)) => Value::Array(
+            entries
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, entry)| migrate_entry(entry, &format!("{}[{}]", name, index), translated, report))
+                .collect(),
+        ),
+        Some(Value::Null) | None => {
+            default_field(report, name);
+            Value::Array(Vec::new())
+        }
+        Some(_) => {
+            unmigrated(report, name.to_string(), "not a list; dropped");
+            Value::Array(Vec::new())
+        }
+    }
+}
+
+/// Version 1 -> 2: snake_case and older field names become the current camelCase ones
+/// (`TranslatableStringEntry` -> `SourceStringData`/`WorkingTranslation`).
+fn normalize_legacy_session(value: &mut Value, report: &mut MigrationReport) {
+    let Some(object) = value.as_object_mut() else { return };
+    let mut session = Map::new();
+    for aliases in [
+        &["sourceLanguage", "source_language", "sourceLang", "source_lang"][..],
+        &["targetLanguage", "target_language", "targetLang", "target_lang"][..],
+    ] {
+        let language = string_field(object, aliases, "", report).unwrap_or_else(|| {
+            default_field(report, aliases[0]);
+            String::new()
+        });
+        session.insert(aliases[0].to_string(), Value::String(language));
+    }
+    let translations = take_field(object, &["translations", "translatedEntries", "translated_entries", "entries"], report);
+    session.insert("translations".to_string(), migrate_entries(translations, "translations", true, report));
+    let pending = take_field(object, &["pending", "pendingEntries", "pending_entries"], report);
+    session.insert("pending".to_string(), migrate_entries(pending, "pending", false, report));
+    object.remove(VERSION_KEY);
+    for key in object.keys() {
+        unmigrated(report, key.clone(), "unknown field; dropped");
+    }
+    *value = Value::Object(session);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session::TranslationSession;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_legacy_session() {
+        let legacy = json!({
+            "source_lang": "ja",
+            "target_lang": "en",
+            "entries": [
+                {"object_id": "3", "text": "ポーション", "file_path": "www/data/Items.json", "json_path": "[3].name",
+                 "translation": "Potion", "engine": "ollama", "status": "done"},
+                {"object_id": 4, "text": "エーテル", "json_path": "[4].name"}
+            ],
+            "window": {"width": 800}
+        });
+        let (migrated, report) = migrate_session_value(legacy).unwrap();
+        assert!(report.is_needed());
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.renamed_fields.get("text -> originalText"), Some(&2));
+        assert_eq!(report.dropped_entries, 1);
+        let paths: Vec<&str> = report.unmigrated.iter().map(|u| u.path.as_str()).collect();
+        assert_eq!(paths, vec!["translations[0].status", "translations[1]", "window"]);
+
+        let session: TranslationSession = serde_json::from_value(migrated.clone()).unwrap();
+        assert_eq!(session.source_language, "ja");
+        assert_eq!(session.translations.len(), 1);
+        assert_eq!(session.translations[0].object_id, 3);
+        assert_eq!(session.translations[0].translation_source, "ollama");
+        assert!(session.pending.is_empty());
+
+        // Already current: nothing to do.
+        let (_, again) = migrate_session_value(migrated).unwrap();
+        assert!(!again.is_needed());
+        assert!(again.unmigrated.is_empty() && again.renamed_fields.is_empty());
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let newer = json!({"version": SESSION_FORMAT_VERSION + 1, "translations": []});
+        assert!(matches!(migrate_session_value(newer), Err(CoreError::Config(_))));
+    }
+}
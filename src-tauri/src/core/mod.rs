@@ -1,5 +1,41 @@
 pub mod game_detection;
 pub mod rpgmv;
+pub mod validation;
+pub mod escaping;
+pub mod control_codes;
+pub mod quality;
+pub mod translation_memory;
+pub mod glossary;
+pub mod references;
+pub mod characters;
+pub mod session;
+pub mod migration;
+pub mod exchange;
+pub mod bulk;
+pub mod config;
+pub mod units;
+pub mod wolfrpg;
+pub mod renpy;
+pub mod rpgvxace;
+pub mod generic;
+pub mod tyrano;
+pub mod presets;
+pub mod prompts;
+pub mod project_document;
+pub mod cost;
+pub mod scope_summaries;
+pub mod catchphrases;
+pub mod transliteration;
+pub mod language_detection;
+pub mod do_not_translate;
+pub mod font_coverage;
+pub mod postprocess;
+pub mod qa_report;
+pub mod dedup;
+pub mod name_policy;
+pub mod translation_sets;
+pub mod search;
+pub mod string_store;
 
 // Remove the line below if it exists, or ensure it's not there:
 // pub mod rpg_maker_mv_parser; 
\ No newline at end of file
@@ -0,0 +1,178 @@
+use crate::core::glossary::terms_in_text;
+use crate::models::glossary::{Glossary, GlossaryTerm};
+use crate::models::preset::{HonorificPolicy, NameOrder, TranslationPreset};
+use crate::models::translation::WorkingTranslation;
+use crate::models::validation::{ValidationIssue, ValidationSeverity};
+
+/// A translated name with a kept honorific: "Seren-san", or "Seren sunbae" for honorifics
+/// romanized as a word of their own.
+fn with_honorific(name: &str, romanized: &str) -> String {
+    if romanized.starts_with('-') {
+        format!("{}{}", name, romanized)
+    } else {
+        format!("{} {}", name, romanized)
+    }
+}
+
+/// Names of several words ("Seren Kirishima") whose order the policy is about.
+fn is_full_name(term: &GlossaryTerm) -> bool {
+    term.target.split_whitespace().count() > 1
+}
+
+/// A glossary name in the order of `order`, and in the other one. The glossary writes full names
+/// given name first ("Seren Kirishima"); `NameOrder::Source` puts the family name first
+/// ("Kirishima Seren"), as in the source.
+fn name_forms(term: &GlossaryTerm, order: NameOrder) -> (String, String) {
+    let reversed = term.target.split_whitespace().rev().collect::<Vec<_>>().join(" ");
+    match order {
+        NameOrder::Western => (term.target.clone(), reversed),
+        NameOrder::Source => (reversed, term.target.clone()),
+    }
+}
+
+/// Instructions telling the provider how to render the honorifics and full names of `text`,
+/// whose glossary terms are `names`. Empty when the text has neither, so the prompts (and memory
+/// keys) of other entries don't change.
+pub fn policy_instructions(preset: &TranslationPreset, text: &str, names: &[GlossaryTerm]) -> Vec<String> {
+    let mut instructions = Vec::new();
+    let honorifics: Vec<_> = preset.honorifics.iter().filter(|h| text.contains(&h.term)).collect();
+    if !honorifics.is_empty() {
+        instructions.push(match preset.honorific_policy {
+            HonorificPolicy::Keep => format!(
+                "Keep honorifics after names in romanized form: {}.",
+                honorifics.iter().map(|h| format!("{} -> {}", h.term, h.romanized)).collect::<Vec<_>>().join(", ")
+            ),
+            HonorificPolicy::Adapt => format!(
+                "Render honorifics with the closest form of the target language, or leave them out: {}.",
+                honorifics.iter().map(|h| format!("{} ({})", h.term, h.meaning)).collect::<Vec<_>>().join(", ")
+            ),
+            HonorificPolicy::Drop => format!(
+                "Leave honorifics out and use the bare name: {}.",
+                honorifics.iter().map(|h| h.term.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        });
+    }
+    let full_names: Vec<String> =
+        names.iter().filter(|term| is_full_name(term)).map(|term| name_forms(term, preset.name_order).0).collect();
+    if !full_names.is_empty() {
+        let order = match preset.name_order {
+            NameOrder::Source => "family name first",
+            NameOrder::Western => "given name first",
+        };
+        instructions.push(format!("Write full names {}: {}.", order, full_names.join(", ")));
+    }
+    instructions
+}
+
+/// Checks a translation against the honorific and name order policy, using the glossary as the
+/// list of names: a kept honorific must follow the glossary name ("Seren-san"), a dropped or
+/// adapted one must not, and a full name must be in the preset's order. Names are matched
+/// longest first, so "Seren" isn't checked again inside "Kirishima Seren".
+pub fn check_name_policy(entry: &WorkingTranslation, preset: &TranslationPreset, glossary: &Glossary) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if entry.error.is_some() || entry.translated_text.is_empty() {
+        return issues;
+    }
+    // Honorifics written alike (様 and さま) are reported once.
+    let mut push_issue = |message: String| {
+        if issues.iter().any(|issue| issue.message == message) {
+            return;
+        }
+        issues.push(ValidationIssue {
+            source_file: entry.source_file.clone(),
+            json_path: entry.json_path.clone(),
+            rule: "namePolicy".to_string(),
+            message,
+            severity: ValidationSeverity::Warning,
+        });
+    };
+
+    let translated = &entry.translated_text;
+    // The original with the names already checked blanked out.
+    let mut original = entry.original_text.clone();
+    for term in terms_in_text(glossary, &entry.original_text) {
+        if !original.contains(&term.source) {
+            continue;
+        }
+        let (name, wrong_order) = match is_full_name(term) {
+            true => name_forms(term, preset.name_order),
+            false => (term.target.clone(), String::new()),
+        };
+        for honorific in &preset.honorifics {
+            let kept = with_honorific(&name, &honorific.romanized);
+            let in_original = original.contains(&format!("{}{}", term.source, honorific.term));
+            match preset.honorific_policy {
+                HonorificPolicy::Keep if in_original && !translated.contains(&kept) => {
+                    push_issue(format!("'{}{}' should be translated as '{}'", term.source, honorific.term, kept));
+                }
+                HonorificPolicy::Adapt | HonorificPolicy::Drop if translated.contains(&kept) => {
+                    push_issue(format!("'{}' keeps an honorific the project drops", kept));
+                }
+                _ => {}
+            }
+        }
+        if !wrong_order.is_empty() && translated.contains(&wrong_order) && !translated.contains(&name) {
+            push_issue(format!("'{}' is in the wrong order; the project writes '{}'", wrong_order, name));
+        }
+        original = original.replace(&term.source, "\u{FFFC}");
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::presets::builtin_preset;
+    use crate::models::translation::TranslationStatus;
+
+    fn term(source: &str, target: &str) -> GlossaryTerm {
+        GlossaryTerm { source: source.to_string(), target: target.to_string(), references: Vec::new() }
+    }
+
+    fn translation(original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "events[1].pages[0].list[1].parameters[0]".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_policy_is_prompted_and_checked() {
+        let glossary = Glossary { terms: vec![term("霧島セレン", "Seren Kirishima"), term("セレン", "Seren")] };
+        let mut preset = builtin_preset("ja").unwrap();
+        let instructions = policy_instructions(&preset, "霧島セレンさん、おはよう", &glossary.terms);
+        assert_eq!(instructions[0], "Keep honorifics after names in romanized form: さん -> -san.");
+        assert_eq!(instructions[1], "Write full names family name first: Kirishima Seren.");
+        assert!(policy_instructions(&preset, "おはよう", &[]).is_empty());
+
+        let rules = |preset: &TranslationPreset, original: &str, translated: &str| -> Vec<String> {
+            check_name_policy(&translation(original, translated), preset, &glossary).into_iter().map(|i| i.message).collect()
+        };
+        assert!(rules(&preset, "セレンさん、おはよう", "Good morning, Seren-san").is_empty());
+        assert_eq!(rules(&preset, "セレンさん、おはよう", "Good morning, Seren"), vec!["'セレンさん' should be translated as 'Seren-san'"]);
+        assert_eq!(
+            rules(&preset, "霧島セレンです", "I'm Seren Kirishima"),
+            vec!["'Seren Kirishima' is in the wrong order; the project writes 'Kirishima Seren'"]
+        );
+        // The full name and its honorific are checked once, not again as "Seren".
+        assert!(rules(&preset, "霧島セレンさん、おはよう", "Good morning, Kirishima Seren-san").is_empty());
+
+        preset.name_order = NameOrder::Western;
+        assert!(rules(&preset, "霧島セレンです", "I'm Seren Kirishima").is_empty());
+        assert_eq!(
+            rules(&preset, "霧島セレンです", "I'm Kirishima Seren"),
+            vec!["'Kirishima Seren' is in the wrong order; the project writes 'Seren Kirishima'"]
+        );
+        assert!(policy_instructions(&preset, "霧島セレンです", &glossary.terms)[0].contains("given name first: Seren Kirishima"));
+
+        preset.honorific_policy = HonorificPolicy::Drop;
+        assert_eq!(rules(&preset, "セレンさん、おはよう", "Good morning, Seren-san"), vec!["'Seren-san' keeps an honorific the project drops"]);
+        assert!(rules(&preset, "セレンさん、おはよう", "Good morning, Seren").is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::core::config::load_project_config;
+use crate::error::CoreError;
+use crate::models::project_config::{EllipsisStyle, PostprocessSettings, QuoteStyle};
+use crate::models::translation::WorkingTranslation;
+
+/// Leftovers of a source ellipsis that translations often keep as they are.
+const SOURCE_ELLIPSES: [&str; 3] = ["。。。", "・・・", "･･･"];
+
+/// One normalization of a translated text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostprocessStep {
+    FullWidthToHalfWidth,
+    Quotes(QuoteStyle),
+    Ellipsis(EllipsisStyle),
+    TrimTrailingSpaces,
+}
+
+impl PostprocessStep {
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            PostprocessStep::FullWidthToHalfWidth => text.chars().map(half_width).collect(),
+            PostprocessStep::Quotes(QuoteStyle::Straight) => text
+                .chars()
+                .map(|c| match c {
+                    '“' | '”' | '„' | '‟' => '"',
+                    '‘' | '’' | '‚' | '‛' => '\'',
+                    c => c,
+                })
+                .collect(),
+            PostprocessStep::Quotes(QuoteStyle::Curly) => map_lines(text, curly_quotes),
+            PostprocessStep::Ellipsis(style) => {
+                let ellipsis = match style {
+                    EllipsisStyle::ThreeDots => "...",
+                    EllipsisStyle::Character => "…",
+                };
+                let mut text = SOURCE_ELLIPSES.iter().fold(text.to_string(), |text, run| text.replace(run, ellipsis));
+                match style {
+                    EllipsisStyle::ThreeDots => text = text.replace('…', "..."),
+                    EllipsisStyle::Character => text = text.replace("...", "…"),
+                }
+                text
+            }
+            PostprocessStep::TrimTrailingSpaces => {
+                map_lines(text, |line| line.trim_end_matches([' ', '\t', '\u{3000}']).to_string())
+            }
+        }
+    }
+}
+
+/// Full-width ASCII forms (U+FF01 to U+FF5E) and the ideographic space to their ASCII
+/// counterparts.
+fn half_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        c => c,
+    }
+}
+
+/// Applies `f` to every line of `text`, keeping `\n` and `\r\n` line breaks as they are.
+fn map_lines(text: &str, f: impl Fn(&str) -> String) -> String {
+    text.split('\n')
+        .map(|line| match line.strip_suffix('\r') {
+            Some(body) => format!("{}\r", f(body)),
+            None => f(line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Straight quotes of a line to curly ones. Double quotes alternate between opening and
+/// closing; a single quote opens after a space or bracket and is an apostrophe otherwise.
+fn curly_quotes(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut double_open = false;
+    let mut previous: Option<char> = None;
+    for c in line.chars() {
+        let after_break = previous.map_or(true, |p| p.is_whitespace() || matches!(p, '(' | '[' | '{' | '<' | '“' | '—'));
+        let curly = match c {
+            '"' => {
+                double_open = !double_open;
+                if double_open { '“' } else { '”' }
+            }
+            '\'' if after_break => '‘',
+            '\'' => '’',
+            c => c,
+        };
+        result.push(curly);
+        previous = Some(c);
+    }
+    result
+}
+
+/// The configured steps, run in order. Built from the project settings, or step by step with
+/// `then`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pipeline {
+    steps: Vec<PostprocessStep>,
+}
+
+impl Pipeline {
+    pub fn new(settings: &PostprocessSettings) -> Self {
+        let mut pipeline = Pipeline::default();
+        if settings.full_width_to_half_width {
+            pipeline = pipeline.then(PostprocessStep::FullWidthToHalfWidth);
+        }
+        if let Some(style) = settings.quotes {
+            pipeline = pipeline.then(PostprocessStep::Quotes(style));
+        }
+        if let Some(style) = settings.ellipsis {
+            pipeline = pipeline.then(PostprocessStep::Ellipsis(style));
+        }
+        if settings.trim_trailing_spaces {
+            pipeline = pipeline.then(PostprocessStep::TrimTrailingSpaces);
+        }
+        pipeline
+    }
+
+    pub fn then(mut self, step: PostprocessStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn run(&self, text: &str) -> String {
+        self.steps.iter().fold(text.to_string(), |text, step| step.apply(&text))
+    }
+}
+
+/// The translations with the project's post-processing applied, as they are written by
+/// reconstruction. Failed and untranslated entries are left alone; statuses don't change.
+pub fn postprocess_translations(
+    project_path: &str,
+    translations: &[WorkingTranslation],
+) -> Result<Vec<WorkingTranslation>, CoreError> {
+    let pipeline = Pipeline::new(&load_project_config(project_path)?.postprocess);
+    let mut translations = translations.to_vec();
+    if pipeline.is_empty() {
+        return Ok(translations);
+    }
+    for entry in translations.iter_mut().filter(|e| e.error.is_none() && !e.translated_text.is_empty()) {
+        entry.translated_text = pipeline.run(&entry.translated_text);
+    }
+    Ok(translations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_runs_configured_steps_in_order() {
+        let settings = PostprocessSettings {
+            full_width_to_half_width: true,
+            quotes: Some(QuoteStyle::Curly),
+            ellipsis: Some(EllipsisStyle::Character),
+            trim_trailing_spaces: true,
+        };
+        let pipeline = Pipeline::new(&settings);
+        assert_eq!(
+            pipeline.run("\\C[2]Harold\\C[0]: \"Ｉｔ'ｓ ｏｋａｙ．．．\"　\r\nI said 'wait'。。。  "),
+            "\\C[2]Harold\\C[0]: “It’s okay…”\r\nI said ‘wait’…"
+        );
+
+        let straight = Pipeline::default()
+            .then(PostprocessStep::Quotes(QuoteStyle::Straight))
+            .then(PostprocessStep::Ellipsis(EllipsisStyle::ThreeDots));
+        assert_eq!(straight.run("“Don’t…” ・・・"), "\"Don't...\" ...");
+        assert!(Pipeline::new(&PostprocessSettings::default()).is_empty());
+    }
+}
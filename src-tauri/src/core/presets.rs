@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+use crate::core::config::load_project_config;
+use crate::core::prompts::language_family;
+use crate::error::CoreError;
+use crate::models::preset::{Honorific, HonorificPolicy, NameOrder, PresetOverrides, TranslationPreset};
+use crate::models::project_config::ProjectConfig;
+
+/// Preset id that turns presets off for a project.
+pub const NO_PRESET_ID: &str = "none";
+
+fn honorifics(entries: &[(&str, &str, &str)]) -> Vec<Honorific> {
+    entries
+        .iter()
+        .map(|(term, romanized, meaning)| Honorific {
+            term: term.to_string(),
+            romanized: romanized.to_string(),
+            meaning: meaning.to_string(),
+        })
+        .collect()
+}
+
+fn string_map(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries.iter().map(|(from, to)| (from.to_string(), to.to_string())).collect()
+}
+
+/// The presets shipped with the app: Japanese, Korean and Chinese to any target language.
+pub fn builtin_presets() -> Vec<TranslationPreset> {
+    vec![
+        TranslationPreset {
+            id: "ja".to_string(),
+            name: "Japanese → any".to_string(),
+            honorific_policy: HonorificPolicy::Keep,
+            name_order: NameOrder::Source,
+            honorifics: honorifics(&[
+                ("さん", "-san", "polite, neutral"),
+                ("様", "-sama", "very respectful (lords, customers, deities)"),
+                ("さま", "-sama", "very respectful (lords, customers, deities)"),
+                ("くん", "-kun", "familiar, for boys or juniors"),
+                ("君", "-kun", "familiar, for boys or juniors"),
+                ("ちゃん", "-chan", "affectionate, for children, girls or close friends"),
+                ("先輩", "-senpai", "senior at school or work"),
+                ("先生", "-sensei", "teacher, doctor or master"),
+                ("殿", "-dono", "formal and old-fashioned"),
+            ]),
+            sfx: string_map(&[
+                ("ドキドキ", "*thump thump*"),
+                ("ゴゴゴ", "*rumble*"),
+                ("バタン", "*slam*"),
+                ("ガタッ", "*clatter*"),
+                ("ピンポーン", "*ding-dong*"),
+                ("ゴクッ", "*gulp*"),
+                ("キラキラ", "*sparkle*"),
+                ("シーン", "*silence*"),
+                ("ザーザー", "*pitter-patter*"),
+                ("パチパチ", "*clap clap*"),
+            ]),
+            punctuation: string_map(&[
+                ("。", "."),
+                ("、", ","),
+                ("！", "!"),
+                ("？", "?"),
+                ("「", "\""),
+                ("」", "\""),
+                ("『", "'"),
+                ("』", "'"),
+                ("（", "("),
+                ("）", ")"),
+                ("：", ":"),
+                ("～", "~"),
+                ("…", "..."),
+                ("\u{3000}", " "),
+            ]),
+        },
+        TranslationPreset {
+            id: "ko".to_string(),
+            name: "Korean → any".to_string(),
+            honorific_policy: HonorificPolicy::Keep,
+            name_order: NameOrder::Source,
+            honorifics: honorifics(&[
+                ("씨", "-ssi", "polite, between equals"),
+                ("님", "-nim", "respectful (superiors, customers)"),
+                ("선배", "sunbae", "senior at school or work"),
+                ("선생님", "seonsaengnim", "teacher or doctor"),
+                ("오빠", "oppa", "older brother or close older man, said by a woman"),
+                ("형", "hyung", "older brother or close older man, said by a man"),
+                ("언니", "unni", "older sister or close older woman, said by a woman"),
+                ("누나", "noona", "older sister or close older woman, said by a man"),
+            ]),
+            sfx: string_map(&[
+                ("두근두근", "*thump thump*"),
+                ("쾅", "*bang*"),
+                ("쿵", "*thud*"),
+                ("휙", "*whoosh*"),
+                ("딩동", "*ding-dong*"),
+                ("꿀꺽", "*gulp*"),
+                ("반짝반짝", "*sparkle*"),
+            ]),
+            punctuation: string_map(&[
+                ("「", "\""),
+                ("」", "\""),
+                ("『", "'"),
+                ("』", "'"),
+                ("～", "~"),
+                ("…", "..."),
+            ]),
+        },
+        TranslationPreset {
+            id: "zh".to_string(),
+            name: "Chinese → any".to_string(),
+            honorific_policy: HonorificPolicy::Adapt,
+            name_order: NameOrder::Source,
+            honorifics: honorifics(&[
+                ("先生", "xiansheng", "Mr., sir"),
+                ("小姐", "xiaojie", "Miss"),
+                ("大人", "daren", "lord, Your Excellency"),
+                ("阁下", "gexia", "Your Excellency"),
+                ("前辈", "qianbei", "senior, elder"),
+                ("师父", "shifu", "master (of a martial art or craft)"),
+                ("师兄", "shixiong", "senior brother disciple"),
+                ("师姐", "shijie", "senior sister disciple"),
+            ]),
+            sfx: string_map(&[
+                ("砰", "*bang*"),
+                ("咚", "*thud*"),
+                ("哗啦", "*splash*"),
+                ("嗖", "*whoosh*"),
+                ("叮咚", "*ding-dong*"),
+                ("咕噜", "*gulp*"),
+            ]),
+            punctuation: string_map(&[
+                ("。", "."),
+                ("，", ","),
+                ("、", ","),
+                ("！", "!"),
+                ("？", "?"),
+                ("：", ":"),
+                ("；", ";"),
+                ("“", "\""),
+                ("”", "\""),
+                ("‘", "'"),
+                ("’", "'"),
+                ("「", "\""),
+                ("」", "\""),
+                ("（", "("),
+                ("）", ")"),
+                ("……", "..."),
+                ("——", "—"),
+            ]),
+        },
+    ]
+}
+
+pub fn builtin_preset(id: &str) -> Option<TranslationPreset> {
+    builtin_presets().into_iter().find(|preset| preset.id == id)
+}
+
+fn merge_map(map: &mut BTreeMap<String, String>, overrides: &BTreeMap<String, Option<String>>) {
+    for (key, value) in overrides {
+        match value {
+            Some(value) => map.insert(key.clone(), value.clone()),
+            None => map.remove(key),
+        };
+    }
+}
+
+/// Applies a project's overrides to a preset.
+pub fn apply_overrides(mut preset: TranslationPreset, overrides: &PresetOverrides) -> TranslationPreset {
+    if let Some(policy) = overrides.honorific_policy {
+        preset.honorific_policy = policy;
+    }
+    if let Some(order) = overrides.name_order {
+        preset.name_order = order;
+    }
+    for honorific in &overrides.honorifics {
+        preset.honorifics.retain(|h| h.term != honorific.term);
+        preset.honorifics.push(honorific.clone());
+    }
+    merge_map(&mut preset.sfx, &overrides.sfx);
+    merge_map(&mut preset.punctuation, &overrides.punctuation);
+    preset
+}
+
+/// Checks that a selected preset exists.
+pub fn validate_overrides(overrides: &PresetOverrides) -> Result<(), CoreError> {
+    match overrides.preset.as_deref() {
+        Some(id) if id != NO_PRESET_ID && builtin_preset(id).is_none() => {
+            Err(CoreError::Config(format!("Unknown translation preset: {}", id)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The preset a project translates with: the selected one, or the one for its source language,
+/// with the project's overrides applied. `None` when presets are disabled or none matches.
+pub fn resolve_preset(config: &ProjectConfig) -> Result<Option<TranslationPreset>, CoreError> {
+    let overrides = config.preset.clone().unwrap_or_default();
+    validate_overrides(&overrides)?;
+    let id = match (&overrides.preset, &config.source_language) {
+        (Some(id), _) => id.clone(),
+        (None, Some(language)) => language_family(language),
+        (None, None) => return Ok(None),
+    };
+    Ok(builtin_preset(&id).map(|preset| apply_overrides(preset, &overrides)))
+}
+
+pub fn project_preset(project_path: &str) -> Result<Option<TranslationPreset>, CoreError> {
+    resolve_preset(&load_project_config(project_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_preset_applies_project_overrides() {
+        let mut config = ProjectConfig { source_language: Some("ja-JP".to_string()), ..Default::default() };
+        let preset = resolve_preset(&config).unwrap().unwrap();
+        assert_eq!((preset.id.as_str(), preset.honorific_policy), ("ja", HonorificPolicy::Keep));
+
+        config.preset = Some(PresetOverrides {
+            honorific_policy: Some(HonorificPolicy::Drop),
+            honorifics: honorifics(&[("さん", "-san", "polite")]),
+            sfx: BTreeMap::from([
+                ("ドキドキ".to_string(), Some("*ba-dump*".to_string())),
+                ("シーン".to_string(), None),
+            ]),
+            ..Default::default()
+        });
+        let preset = resolve_preset(&config).unwrap().unwrap();
+        assert_eq!(preset.honorific_policy, HonorificPolicy::Drop);
+        assert_eq!(preset.honorifics.iter().filter(|h| h.term == "さん").count(), 1);
+        assert_eq!(preset.honorifics.last().unwrap().meaning, "polite");
+        assert_eq!(preset.sfx["ドキドキ"], "*ba-dump*");
+        assert!(!preset.sfx.contains_key("シーン"));
+        assert_eq!(preset.punctuation["。"], ".");
+
+        config.preset.as_mut().unwrap().preset = Some(NO_PRESET_ID.to_string());
+        assert_eq!(resolve_preset(&config).unwrap(), None);
+        config.preset.as_mut().unwrap().preset = Some("xx".to_string());
+        assert!(resolve_preset(&config).is_err());
+        assert_eq!(resolve_preset(&ProjectConfig { source_language: Some("en".to_string()), ..Default::default() }).unwrap(), None);
+    }
+}
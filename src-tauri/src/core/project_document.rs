@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::error::CoreError;
+use crate::models::project_document::{HistoryEvent, ProjectDocument, ProjectEntry};
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+pub const TAIPROJ_EXTENSION: &str = "taiproj";
+/// Default document location, inside `.translate-ai/`.
+pub const PROJECT_DOCUMENT_FILE_NAME: &str = "project.taiproj";
+pub const PROJECT_DOCUMENT_VERSION: u32 = 1;
+// Older save events are dropped beyond this.
+const MAX_HISTORY_EVENTS: usize = 200;
+
+/// The document file: `path` if given (with the `.taiproj` extension added when missing),
+/// otherwise the project's default one.
+pub fn document_path(project_path: &str, path: Option<&str>) -> PathBuf {
+    match path {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if path.extension().is_some_and(|ext| ext == TAIPROJ_EXTENSION) {
+                path
+            } else {
+                let mut name = path.into_os_string();
+                name.push(".");
+                name.push(TAIPROJ_EXTENSION);
+                PathBuf::from(name)
+            }
+        }
+        None => app_data_dir(project_path).join(PROJECT_DOCUMENT_FILE_NAME),
+    }
+}
+
+/// Builds a document from the extracted strings and the working translations. Translations of
+/// strings no longer extracted are kept after the others.
+pub fn build_document(
+    project_path: &str,
+    source_language: &str,
+    target_language: &str,
+    provider: Option<String>,
+    extracted: &[SourceStringData],
+    translations: &[WorkingTranslation],
+) -> ProjectDocument {
+    let translated: HashMap<(&str, &str), &WorkingTranslation> = translations
+        .iter()
+        .map(|t| ((t.source_file.as_str(), t.json_path.as_str()), t))
+        .collect();
+    let entry = |source: SourceStringData| {
+        let key = (source.source_file.as_str(), source.json_path.as_str());
+        let translation = translated.get(&key);
+        let status = match translation {
+            Some(t) if t.error.is_none() && !t.translated_text.is_empty() => t.status,
+            _ => TranslationStatus::Untranslated,
+        };
+        ProjectEntry {
+            translated_text: translation.filter(|t| !t.translated_text.is_empty()).map(|t| t.translated_text.clone()),
+            status,
+            provider: translation.map(|t| t.translation_source.clone()).filter(|s| !s.is_empty()),
+            error: translation.and_then(|t| t.error.clone()),
+            object_id: source.object_id,
+            original_text: source.original_text,
+            source_file: source.source_file,
+            json_path: source.json_path,
+        }
+    };
+
+    let extracted_keys: HashSet<(&str, &str)> =
+        extracted.iter().map(|s| (s.source_file.as_str(), s.json_path.as_str())).collect();
+    let leftovers = translations
+        .iter()
+        .filter(|t| !extracted_keys.contains(&(t.source_file.as_str(), t.json_path.as_str())))
+        .map(|t| SourceStringData {
+            object_id: t.object_id,
+            original_text: t.original_text.clone(),
+            source_file: t.source_file.clone(),
+            json_path: t.json_path.clone(),
+            dialogue_block_id: None,
+            speaker: None,
+        });
+
+    ProjectDocument {
+        version: PROJECT_DOCUMENT_VERSION,
+        project_path: project_path.to_string(),
+        source_language: source_language.to_string(),
+        target_language: target_language.to_string(),
+        provider,
+        entries: extracted.iter().cloned().chain(leftovers).map(entry).collect(),
+        history: Vec::new(),
+    }
+}
+
+/// Approved and rejected entries have been through review; edited ones only changed by hand.
+fn is_reviewed(status: TranslationStatus) -> bool {
+    matches!(status, TranslationStatus::Approved | TranslationStatus::Rejected)
+}
+
+fn read_document(path: &Path) -> Result<Option<ProjectDocument>, CoreError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| CoreError::Io(format!("Failed to read project document {:?}: {}", path, e)))?;
+    let document: ProjectDocument = serde_json::from_str(&content)
+        .map_err(|e| CoreError::JsonParse(format!("Invalid project document {:?}: {}", path, e)))?;
+    if document.version > PROJECT_DOCUMENT_VERSION {
+        return Err(CoreError::Config(format!(
+            "{:?} was saved by a newer version of the app (format {}); update the app to open it",
+            path, document.version
+        )));
+    }
+    Ok(Some(document))
+}
+
+/// Loads the project document, or `None` if it was never saved.
+pub fn load_project_document(project_path: &str, path: Option<&str>) -> Result<Option<ProjectDocument>, CoreError> {
+    read_document(&document_path(project_path, path))
+}
+
+/// Saves `document`, carrying over the history of the file it replaces and recording this save.
+/// Returns the path written.
+pub fn save_project_document(
+    project_path: &str,
+    path: Option<&str>,
+    mut document: ProjectDocument,
+) -> Result<PathBuf, CoreError> {
+    let target = match path {
+        Some(_) => document_path(project_path, path),
+        None => ensure_app_data_file(project_path, PROJECT_DOCUMENT_FILE_NAME)?,
+    };
+    let mut history = match read_document(&target) {
+        Ok(previous) => previous.map(|previous| previous.history).unwrap_or_default(),
+        Err(e) => {
+            eprintln!("Replacing unreadable project document {:?}: {}", target, e);
+            Vec::new()
+        }
+    };
+    history.push(HistoryEvent {
+        at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        entries: document.entries.len(),
+        translated: document.entries.iter().filter(|e| e.status != TranslationStatus::Untranslated).count(),
+        reviewed: document.entries.iter().filter(|e| is_reviewed(e.status)).count(),
+    });
+    let excess = history.len().saturating_sub(MAX_HISTORY_EVENTS);
+    history.drain(..excess);
+    document.history = history;
+    document.version = PROJECT_DOCUMENT_VERSION;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&document)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize project document: {}", e)))?;
+    fs::write(&target, json)
+        .map_err(|e| CoreError::Io(format!("Failed to write project document to {:?}: {}", target, e)))?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn source(json_path: &str, text: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    fn translation(json_path: &str, text: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: text.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_project_document_round_trip_keeps_history() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        assert_eq!(load_project_document(project_path, None).unwrap(), None);
+
+        let extracted = vec![source("[1].name", "ハロルド"), source("[1].nickname", "剣士"), source("[2].name", "マーシャ")];
+        let mut translations = vec![
+            translation("[1].name", "ハロルド", "Harold"),
+            translation("[2].name", "マーシャ", "Marsha"),
+            translation("[3].name", "リード", "Reid"),
+        ];
+        translations[1].status = TranslationStatus::Rejected;
+        translations[2].status = TranslationStatus::Approved;
+        let document = build_document(project_path, "ja", "en", Some("ollama".to_string()), &extracted, &translations);
+        let statuses: Vec<TranslationStatus> = document.entries.iter().map(|e| e.status).collect();
+        assert_eq!(statuses, vec![
+            TranslationStatus::MachineTranslated,
+            TranslationStatus::Untranslated,
+            TranslationStatus::Rejected,
+            TranslationStatus::Approved,
+        ]);
+        assert_eq!(document.entries[3].original_text, "リード");
+
+        save_project_document(project_path, None, document.clone()).unwrap();
+        save_project_document(project_path, None, document.clone()).unwrap();
+        let loaded = load_project_document(project_path, None).unwrap().unwrap();
+        assert_eq!(loaded.entries, document.entries);
+        assert_eq!(loaded.history.len(), 2);
+        assert_eq!((loaded.history[1].translated, loaded.history[1].reviewed), (3, 2));
+
+        let custom = dir.path().join("backup").to_str().unwrap().to_string();
+        let written = save_project_document(project_path, Some(&custom), document).unwrap();
+        assert_eq!(written.extension().unwrap(), TAIPROJ_EXTENSION);
+        assert!(load_project_document(project_path, Some(written.to_str().unwrap())).unwrap().is_some());
+    }
+}
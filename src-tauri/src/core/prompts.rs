@@ -0,0 +1,202 @@
+use std::fs;
+use crate::core::quality::{path_category, SampleCategory};
+use crate::core::rpgmv::note_tags::NOTE_TAG_PATH_SUFFIX;
+use crate::error::CoreError;
+use crate::models::prompt::{PromptKind, PromptTemplate, PromptTemplates};
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// Kept in the project's app data folder; holds only the customized templates.
+pub const PROMPT_TEMPLATES_FILE_NAME: &str = "prompt_templates.json";
+
+/// Variables a template can use, written `{name}`. `{text}` is required.
+pub const TEMPLATE_VARIABLES: [&str; 6] = ["source_lang", "target_lang", "glossary", "speaker", "context", "text"];
+
+pub const PROMPT_KINDS: [PromptKind; 5] = [
+    PromptKind::Dialogue,
+    PromptKind::ItemDescription,
+    PromptKind::SystemTerm,
+    PromptKind::NoteTag,
+    PromptKind::General,
+];
+
+const OUTPUT_RULES: &str = "Output *only* the translated text. Do not include the original text, any explanations, commentary, phonetic transcription, or romanization";
+
+// A helper function to map ISO codes to full language names for the prompt
+pub fn map_language_code_to_name(code: &str) -> &str {
+    match code.to_lowercase().as_str() {
+        "en" => "English",
+        "es" => "Spanish",
+        "fr" => "French",
+        "de" => "German",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "zh" => "Chinese", // Assuming simplified for now, could be more specific
+        _ => code, // Fallback to the code itself if not mapped
+    }
+}
+
+/// The primary subtag of a language code, which the app compares languages on: "ja-JP" -> "ja",
+/// "zh_TW" -> "zh".
+pub(crate) fn language_family(language: &str) -> String {
+    language.split(['-', '_']).next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+pub fn builtin_template(kind: PromptKind) -> String {
+    let task = match kind {
+        PromptKind::Dialogue => "Translate the following line of video game dialogue from {source_lang} to {target_lang}, keeping the speaker's tone and voice.",
+        PromptKind::ItemDescription => "Translate the following video game description from {source_lang} to {target_lang}. Keep it concise: it is shown in a small help window.",
+        PromptKind::SystemTerm => "Translate the following video game menu term or name from {source_lang} to {target_lang}. Keep it short and use the usual wording of games for it.",
+        PromptKind::NoteTag => "Translate the following value of a video game database tag from {source_lang} to {target_lang}. Keep its formatting.",
+        PromptKind::General => "Translate the following text from {source_lang} to {target_lang}.",
+    };
+    let speaker = if kind == PromptKind::Dialogue { "The line is spoken by {speaker}.\n" } else { "" };
+    format!("{{context}}\n{{glossary}}\n{}{} {}:\n\n{{text}}", speaker, task, OUTPUT_RULES)
+}
+
+/// Picks the template kind of an extracted string from its location.
+pub fn prompt_kind_for(source_file: &str, json_path: &str) -> PromptKind {
+    if json_path.contains(NOTE_TAG_PATH_SUFFIX) {
+        return PromptKind::NoteTag;
+    }
+    match path_category(json_path) {
+        SampleCategory::Dialogue => PromptKind::Dialogue,
+        SampleCategory::Description => PromptKind::ItemDescription,
+        SampleCategory::Other
+            if source_file.ends_with("System.json") || json_path.ends_with(".name") || json_path.ends_with(".nickname") =>
+        {
+            PromptKind::SystemTerm
+        }
+        SampleCategory::Other => PromptKind::General,
+    }
+}
+
+/// The `{name}` variables of `template` as (byte range, name), in order of appearance. Braces
+/// around anything else (e.g. JSON in the instructions) are left alone.
+fn template_variables(template: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut variables = Vec::new();
+    let mut from = 0;
+    while let Some(start) = template[from..].find('{').map(|i| from + i) {
+        let name_start = start + 1;
+        match template[name_start..].find('}').map(|i| name_start + i) {
+            Some(end)
+                if end > name_start
+                    && template[name_start..end].chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                variables.push((start..end + 1, &template[name_start..end]));
+                from = end + 1;
+            }
+            _ => from = name_start,
+        }
+    }
+    variables
+}
+
+pub fn validate_template(template: &str) -> Result<(), CoreError> {
+    let variables: Vec<&str> = template_variables(template).into_iter().map(|(_, name)| name).collect();
+    if let Some(unknown) = variables.iter().find(|v| !TEMPLATE_VARIABLES.contains(v)) {
+        return Err(CoreError::Config(format!(
+            "Unknown prompt variable {{{}}}; available: {}",
+            unknown,
+            TEMPLATE_VARIABLES.map(|v| format!("{{{}}}", v)).join(", ")
+        )));
+    }
+    if !variables.contains(&"text") {
+        return Err(CoreError::Config("A prompt template must contain {text}".to_string()));
+    }
+    Ok(())
+}
+
+/// Fills in the variables of a template. A line whose variables are all empty (e.g. no glossary
+/// term in the text, or no known speaker) is left out, so templates can word optional parts.
+pub fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let value = |name: &str| values.iter().find(|(n, _)| *n == name).map_or("", |(_, v)| *v);
+    let mut lines = Vec::new();
+    for line in template.lines() {
+        let variables = template_variables(line);
+        if !variables.is_empty() && variables.iter().all(|(_, name)| value(name).is_empty()) {
+            continue;
+        }
+        // One pass, so values containing braces are never substituted again.
+        let mut rendered = String::new();
+        let mut from = 0;
+        for (range, name) in variables {
+            rendered.push_str(&line[from..range.start]);
+            rendered.push_str(value(name));
+            from = range.end;
+        }
+        rendered.push_str(&line[from..]);
+        lines.push(rendered);
+    }
+    lines.join("\n")
+}
+
+/// The template used for `kind`: the project's own, or the built-in one.
+pub fn template_for(templates: &PromptTemplates, kind: PromptKind) -> String {
+    templates.templates.get(&kind).cloned().unwrap_or_else(|| builtin_template(kind))
+}
+
+/// Every kind with the template in effect.
+pub fn list_templates(templates: &PromptTemplates) -> Vec<PromptTemplate> {
+    PROMPT_KINDS
+        .iter()
+        .map(|&kind| PromptTemplate {
+            kind,
+            template: template_for(templates, kind),
+            customized: templates.templates.contains_key(&kind),
+        })
+        .collect()
+}
+
+pub fn load_prompt_templates(project_path: &str) -> Result<PromptTemplates, CoreError> {
+    let path = app_data_dir(project_path).join(PROMPT_TEMPLATES_FILE_NAME);
+    if !path.exists() {
+        return Ok(PromptTemplates::default());
+    }
+    let json = fs::read_to_string(&path)?;
+    serde_json::from_str(&json).map_err(|e| CoreError::JsonParse(format!("Failed to parse prompt templates: {}", e)))
+}
+
+pub fn save_prompt_templates(project_path: &str, templates: &PromptTemplates) -> Result<(), CoreError> {
+    for template in templates.templates.values() {
+        validate_template(template)?;
+    }
+    let path = ensure_app_data_file(project_path, PROMPT_TEMPLATES_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(templates)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize prompt templates: {}", e)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_by_kind_and_rendering() {
+        assert_eq!(prompt_kind_for("www/data/Map001.json", "events[1].pages[0].list[3].parameters[0]"), PromptKind::Dialogue);
+        assert_eq!(prompt_kind_for("www/data/Items.json", "[1].description"), PromptKind::ItemDescription);
+        assert_eq!(prompt_kind_for("www/data/Items.json", "[1].name"), PromptKind::SystemTerm);
+        assert_eq!(prompt_kind_for("www/data/System.json", "terms.commands[0]"), PromptKind::SystemTerm);
+        assert_eq!(prompt_kind_for("www/data/Items.json", "[1].note@noteTag[0]"), PromptKind::NoteTag);
+        assert_eq!(prompt_kind_for("www/data/Map001.json", "displayName"), PromptKind::General);
+
+        for kind in PROMPT_KINDS {
+            validate_template(&builtin_template(kind)).unwrap();
+        }
+        let rendered = render_template(
+            &builtin_template(PromptKind::Dialogue),
+            &[("source_lang", "Japanese"), ("target_lang", "English"), ("speaker", "Harold"), ("text", "やあ")],
+        );
+        assert!(rendered.starts_with("The line is spoken by Harold.\nTranslate the following line of video game dialogue from Japanese to English"));
+        assert!(rendered.ends_with(":\n\nやあ"));
+
+        let mut templates = PromptTemplates::default();
+        templates.templates.insert(PromptKind::SystemTerm, "{glossary}\nName: {text} ({source_lang}→{target_lang})".to_string());
+        assert_eq!(render_template(&template_for(&templates, PromptKind::SystemTerm), &[("text", "剣")]), "Name: 剣 (→)");
+        assert_eq!(list_templates(&templates).iter().filter(|t| t.customized).count(), 1);
+
+        assert!(validate_template("Translate {text} for {player}").is_err());
+        assert!(validate_template("Translate to {target_lang}").is_err());
+        assert!(validate_template("Keep {\"json\": true} and {text}").is_ok());
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::core::bulk::entry_status;
+use crate::core::config::load_project_config;
+use crate::core::control_codes::ControlCodes;
+use crate::core::exchange::csv::escape_csv_field;
+use crate::core::exchange::review_site::escape_html;
+use crate::core::glossary::{as_locked_terms, load_project_glossary};
+use crate::core::rpgmv::filter::file_kind;
+use crate::core::rpgmv::warnings::{load_warnings, ReconstructionWarning};
+use crate::core::rpgmv::word_wrap::text_width;
+use crate::core::validation::{load_project_ruleset, validate_translation_with};
+use crate::error::CoreError;
+use crate::models::bulk::EntryStatus;
+use crate::models::extraction::FileKind;
+use crate::models::project_config::WordWrapSettings;
+use crate::models::qa_report::{QaCategory, QaFileReport, QaIssue, QaReport, QaReportFormat};
+use crate::models::translation::WorkingTranslation;
+use crate::models::validation::ValidationRuleSet;
+
+const QA_CSS: &str = r#"body{font-family:system-ui,sans-serif;margin:1rem;color:#222}
+table{border-collapse:collapse;width:100%;margin-bottom:1.5rem}
+th,td{border-bottom:1px solid #eee;padding:.4rem .6rem;text-align:left;vertical-align:top}
+td.text{white-space:pre-wrap;width:25%}
+td.path{font-family:monospace;font-size:.8rem;color:#666}
+"#;
+
+fn category_label(category: QaCategory) -> &'static str {
+    match category {
+        QaCategory::PlaceholderMismatch => "Placeholder mismatch",
+        QaCategory::Untranslated => "Untranslated",
+        QaCategory::LongLine => "Long line",
+        QaCategory::GlossaryViolation => "Glossary violation",
+        QaCategory::IdenticalToSource => "Identical to source",
+        QaCategory::ReconstructionWarning => "Reconstruction warning",
+    }
+}
+
+fn entry_issue(entry: &WorkingTranslation, category: QaCategory, message: String) -> QaIssue {
+    QaIssue {
+        category,
+        json_path: Some(entry.json_path.clone()),
+        original_text: Some(entry.original_text.clone()),
+        translated_text: Some(entry.translated_text.clone()),
+        message,
+    }
+}
+
+/// Whether reconstruction re-wraps the entry, i.e. it is a message line of an event command list.
+fn is_rewrapped(entry: &WorkingTranslation) -> bool {
+    matches!(file_kind(&entry.source_file), FileKind::Maps | FileKind::CommonEvents | FileKind::Troops)
+        && entry.json_path.ends_with(".parameters[0]")
+}
+
+fn entry_issues(
+    entry: &WorkingTranslation,
+    ruleset: &ValidationRuleSet,
+    word_wrap: Option<&WordWrapSettings>,
+    codes: &ControlCodes,
+) -> Vec<QaIssue> {
+    if entry_status(entry) != EntryStatus::Translated {
+        let message = entry.error.clone().unwrap_or_else(|| "No translation".to_string());
+        return vec![entry_issue(entry, QaCategory::Untranslated, message)];
+    }
+    let mut issues: Vec<QaIssue> = validate_translation_with(entry, ruleset, codes)
+        .into_iter()
+        .filter_map(|issue| {
+            let category = match issue.rule.as_str() {
+                "placeholder" => QaCategory::PlaceholderMismatch,
+                "length" => QaCategory::LongLine,
+                "lockedTerm" => QaCategory::GlossaryViolation,
+                _ => return None,
+            };
+            Some(entry_issue(entry, category, issue.message))
+        })
+        .collect();
+
+    if let Some(settings) = word_wrap.filter(|_| !is_rewrapped(entry)) {
+        for (index, line) in entry.translated_text.lines().enumerate() {
+            let width = text_width(line, settings.unit, codes);
+            if width > settings.max_width {
+                let message = format!("Line {} is {} wide; the window fits {}", index + 1, width, settings.max_width);
+                issues.push(entry_issue(entry, QaCategory::LongLine, message));
+            }
+        }
+    }
+
+    let original = entry.original_text.trim();
+    if entry.translated_text.trim() == original && codes.strip(original).chars().any(char::is_alphabetic) {
+        issues.push(entry_issue(entry, QaCategory::IdenticalToSource, "The translation is the original text".to_string()));
+    }
+    issues
+}
+
+/// Checks every entry for the QA report. Placeholders are compared even when the ruleset
+/// doesn't; `ruleset` is expected to carry the glossary as locked terms. Long lines are measured
+/// against the word wrap width, except message lines, which reconstruction re-wraps.
+pub fn build_qa_report(
+    translations: &[WorkingTranslation],
+    ruleset: &ValidationRuleSet,
+    word_wrap: Option<&WordWrapSettings>,
+    warnings: &[ReconstructionWarning],
+) -> QaReport {
+    let ruleset = ValidationRuleSet { check_placeholders: true, ..ruleset.clone() };
+    let codes = ControlCodes::from_ruleset(&ruleset);
+    let mut by_file: BTreeMap<&str, Vec<QaIssue>> = BTreeMap::new();
+    for entry in translations {
+        let issues = entry_issues(entry, &ruleset, word_wrap, &codes);
+        if !issues.is_empty() {
+            by_file.entry(&entry.source_file).or_default().extend(issues);
+        }
+    }
+    for warning in warnings {
+        by_file.entry(&warning.source_file).or_default().push(QaIssue {
+            category: QaCategory::ReconstructionWarning,
+            json_path: warning.json_path.clone(),
+            original_text: None,
+            translated_text: None,
+            message: warning.message.clone(),
+        });
+    }
+
+    let mut report = QaReport::default();
+    for (source_file, issues) in by_file {
+        for issue in &issues {
+            *report.counts.entry(issue.category).or_default() += 1;
+        }
+        report.total_issues += issues.len();
+        report.files.push(QaFileReport { source_file: source_file.to_string(), issues });
+    }
+    report
+}
+
+/// The QA report of the project's translations, with its validation ruleset, glossary, word
+/// wrap width and the warnings of the last reconstruction.
+pub fn project_qa_report(project_path: &str, translations: &[WorkingTranslation]) -> Result<QaReport, CoreError> {
+    let mut ruleset = load_project_ruleset(project_path)?;
+    ruleset.locked_terms.extend(as_locked_terms(&load_project_glossary(project_path)?));
+    let word_wrap = load_project_config(project_path)?.word_wrap;
+    let warnings = load_warnings(project_path)?;
+    Ok(build_qa_report(translations, &ruleset, word_wrap.as_ref(), &warnings))
+}
+
+/// `file,category,path,original,translation,message`, one row per issue.
+pub fn render_qa_csv(report: &QaReport) -> String {
+    let mut csv = String::from("file,category,path,original,translation,message\n");
+    for file in &report.files {
+        for issue in &file.issues {
+            let fields = [
+                file.source_file.as_str(),
+                category_label(issue.category),
+                issue.json_path.as_deref().unwrap_or_default(),
+                issue.original_text.as_deref().unwrap_or_default(),
+                issue.translated_text.as_deref().unwrap_or_default(),
+                issue.message.as_str(),
+            ];
+            csv.push_str(&fields.map(escape_csv_field).join(","));
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+/// A standalone page: the counts per category, then a table of issues per file.
+pub fn render_qa_html(report: &QaReport) -> String {
+    let summary: String = report
+        .counts
+        .iter()
+        .map(|(category, count)| format!("<li>{}: {}</li>\n", category_label(*category), count))
+        .collect();
+    let mut body = format!("<h1>QA report</h1>\n<p>{} issue(s)</p>\n<ul>\n{}</ul>\n", report.total_issues, summary);
+    for file in &report.files {
+        let rows: String = file
+            .issues
+            .iter()
+            .map(|issue| {
+                format!(
+                    "<tr><td>{}</td><td class=\"path\">{}</td><td class=\"text\">{}</td><td class=\"text\">{}</td><td>{}</td></tr>\n",
+                    category_label(issue.category),
+                    escape_html(issue.json_path.as_deref().unwrap_or_default()),
+                    escape_html(issue.original_text.as_deref().unwrap_or_default()),
+                    escape_html(issue.translated_text.as_deref().unwrap_or_default()),
+                    escape_html(&issue.message),
+                )
+            })
+            .collect();
+        body.push_str(&format!(
+            "<h2>{} ({})</h2>\n<table>\n<thead><tr><th>Category</th><th>Path</th><th>Original</th><th>Translation</th><th>Message</th></tr></thead>\n<tbody>\n{}</tbody>\n</table>\n",
+            escape_html(&file.source_file),
+            file.issues.len(),
+            rows
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>QA report</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        QA_CSS, body
+    )
+}
+
+/// Writes the report to `output_path` as HTML or CSV.
+pub fn export_qa_report(report: &QaReport, format: QaReportFormat, output_path: &Path) -> Result<(), CoreError> {
+    let content = match format {
+        QaReportFormat::Html => render_qa_html(report),
+        QaReportFormat::Csv => render_qa_csv(report),
+    };
+    fs::write(output_path, content)
+        .map_err(|e| CoreError::Io(format!("Failed to write QA report to {:?}: {}", output_path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::rpgmv::warnings::WarningKind;
+    use crate::models::project_config::WidthUnit;
+    use crate::models::translation::TranslationStatus;
+    use crate::models::validation::LockedTerm;
+
+    fn entry(source_file: &str, json_path: &str, original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_qa_report_groups_issues_by_file() {
+        let translations = vec![
+            entry("www/data/Items.json", "[1].name", "ポーション", "Potion"),
+            entry("www/data/Items.json", "[1].description", "\\C[2]ハロルド\\C[0]の薬", "Harold's medicine, a very long description"),
+            entry("www/data/Items.json", "[2].name", "ハロルドの剣", "Hal's sword"),
+            entry("www/data/Map001.json", "events[1].pages[0].list[0].parameters[0]", "こんにちは、とても長い挨拶", "Hello there, this greeting is rather long"),
+            entry("www/data/Map001.json", "displayName", "Lv", "Lv"),
+            entry("www/data/Map001.json", "events[2].name", "EV002", ""),
+            entry("www/data/System.json", "gameTitle", "Quest", "Quest"),
+        ];
+        let ruleset = ValidationRuleSet {
+            locked_terms: vec![LockedTerm { source: "ハロルド".to_string(), target: "Harold".to_string() }],
+            ..Default::default()
+        };
+        let word_wrap = WordWrapSettings { max_width: 20, max_width_with_face: None, unit: WidthUnit::Columns, max_lines: 4 };
+        let warnings = vec![ReconstructionWarning {
+            kind: WarningKind::OutOfBounds,
+            source_file: "www/data/Troops.json".to_string(),
+            json_path: Some("[1].pages[2].list[0].parameters[0]".to_string()),
+            message: "Troop 1 no longer has page 2".to_string(),
+        }];
+
+        let report = build_qa_report(&translations, &ruleset, Some(&word_wrap), &warnings);
+        let found: Vec<(&str, Vec<QaCategory>)> = report
+            .files
+            .iter()
+            .map(|f| (f.source_file.as_str(), f.issues.iter().map(|i| i.category).collect()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("www/data/Items.json", vec![QaCategory::PlaceholderMismatch, QaCategory::LongLine, QaCategory::GlossaryViolation]),
+                ("www/data/Map001.json", vec![QaCategory::IdenticalToSource, QaCategory::Untranslated]),
+                ("www/data/System.json", vec![QaCategory::IdenticalToSource]),
+                ("www/data/Troops.json", vec![QaCategory::ReconstructionWarning]),
+            ]
+        );
+        assert_eq!(report.total_issues, 7);
+        assert_eq!(report.counts[&QaCategory::IdenticalToSource], 2);
+
+        let csv = render_qa_csv(&report);
+        assert!(csv.starts_with("file,category,path,original,translation,message\n"));
+        assert!(csv.contains("www/data/Items.json,Long line,[1].description,"));
+        assert!(render_qa_html(&report).contains("Harold&#39;s medicine"));
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::core::config::load_project_config;
+use crate::core::control_codes::ControlCodes;
+use crate::core::exchange::review_site::load_review_comments;
+use crate::core::glossary::{as_locked_terms, load_project_glossary};
+use crate::core::prompts::language_family;
+use crate::core::rpgmv::snapshot::load_snapshot;
+use crate::core::validation::{load_project_ruleset, validate_translation_with};
+use crate::error::CoreError;
+use crate::models::coverage::{CoverageBadge, FileCoverage};
+use crate::models::exchange::ReviewComment;
+use crate::models::provider::EscalationSettings;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::models::validation::{GateResult, QualityGateReport, QualityGates, ValidationRuleSet, ValidationSeverity};
+
+/// Why an entry is considered a "hard line" worth re-translating with a stronger model.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "detail")]
+pub enum HardLineSignal {
+    ProviderError,
+    ValidationFailure(String),
+    LowConfidence(String),
+    LongSource,
+}
+
+// Below this many source characters the length ratio is too noisy to mean anything.
+const MIN_CHARS_FOR_RATIO_CHECK: usize = 8;
+const MAX_LENGTH_RATIO: f64 = 8.0;
+const MIN_LENGTH_RATIO: f64 = 0.15;
+
+/// Hiragana, Katakana, CJK ideographs, Hangul and full-width forms.
+pub fn is_cjk_char(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{FF66}'..='\u{FF9F}')
+}
+
+pub fn contains_cjk(text: &str) -> bool {
+    text.chars().any(is_cjk_char)
+}
+
+fn is_cjk_language(code: &str) -> bool {
+    matches!(language_family(code).as_str(), "ja" | "zh" | "ko")
+}
+
+/// Heuristics standing in for a confidence score, since providers don't return one.
+pub fn low_confidence_reason(entry: &WorkingTranslation, target_lang: &str) -> Option<String> {
+    let original = entry.original_text.trim();
+    let translated = entry.translated_text.trim();
+    if translated.is_empty() {
+        return Some("empty translation".to_string());
+    }
+    if original == translated && contains_cjk(original) && !is_cjk_language(target_lang) {
+        return Some("translation is identical to the source".to_string());
+    }
+    if !is_cjk_language(target_lang) && contains_cjk(translated) {
+        return Some("source-script characters left in translation".to_string());
+    }
+    let original_len = original.chars().count();
+    if original_len >= MIN_CHARS_FOR_RATIO_CHECK {
+        let ratio = translated.chars().count() as f64 / original_len as f64;
+        if !(MIN_LENGTH_RATIO..=MAX_LENGTH_RATIO).contains(&ratio) {
+            return Some(format!("unusual length ratio {:.2}", ratio));
+        }
+    }
+    None
+}
+
+/// Collects every hard-line signal that applies to a first-pass translation.
+pub fn hard_line_signals(
+    entry: &WorkingTranslation,
+    target_lang: &str,
+    ruleset: &ValidationRuleSet,
+    codes: &ControlCodes,
+    settings: &EscalationSettings,
+) -> Vec<HardLineSignal> {
+    let mut signals = Vec::new();
+    if entry.error.is_some() {
+        if settings.on_provider_error {
+            signals.push(HardLineSignal::ProviderError);
+        }
+        // Nothing else can be judged without a translation.
+        return signals;
+    }
+    if settings.on_validation_failure {
+        if let Some(issue) = validate_translation_with(entry, ruleset, codes)
+            .into_iter()
+            .find(|i| i.severity == ValidationSeverity::Error)
+        {
+            signals.push(HardLineSignal::ValidationFailure(issue.message));
+        }
+    }
+    if settings.on_low_confidence {
+        if let Some(reason) = low_confidence_reason(entry, target_lang) {
+            signals.push(HardLineSignal::LowConfidence(reason));
+        }
+    }
+    if settings.long_source_chars > 0 && entry.original_text.chars().count() > settings.long_source_chars {
+        signals.push(HardLineSignal::LongSource);
+    }
+    signals
+}
+
+/// Kind of entry a quality preview samples separately, so a preview is not all item names.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SampleCategory {
+    Dialogue,
+    Description,
+    Other,
+}
+
+pub fn sample_category(entry: &SourceStringData) -> SampleCategory {
+    path_category(&entry.json_path)
+}
+
+/// Category of an entry from its JSON path alone: event command lists hold dialogue.
+pub fn path_category(json_path: &str) -> SampleCategory {
+    if json_path.contains("list[") {
+        SampleCategory::Dialogue
+    } else if json_path.ends_with(".description") || json_path.ends_with(".profile") {
+        SampleCategory::Description
+    } else {
+        SampleCategory::Other
+    }
+}
+
+/// Picks up to `quota` entries per category, spread evenly over the project rather than
+/// taken from the first file. The sample keeps the original entry order.
+pub fn stratified_sample(entries: &[SourceStringData], quotas: &[(SampleCategory, usize)]) -> Vec<SourceStringData> {
+    let mut picked: Vec<usize> = Vec::new();
+    for &(category, quota) in quotas {
+        let indices: Vec<usize> = (0..entries.len()).filter(|&i| sample_category(&entries[i]) == category).collect();
+        let take = quota.min(indices.len());
+        picked.extend((0..take).map(|n| indices[n * indices.len() / take]));
+    }
+    picked.sort_unstable();
+    picked.into_iter().map(|i| entries[i].clone()).collect()
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    (part as f64 * 1000.0 / whole as f64).round() / 10.0
+}
+
+fn coverage_badge(percent_translated: f64, validation_pass_rate: Option<f64>) -> CoverageBadge {
+    match validation_pass_rate {
+        None => CoverageBadge::Untranslated,
+        Some(rate) if percent_translated >= 100.0 && rate >= 100.0 => CoverageBadge::Complete,
+        Some(rate) if percent_translated >= 80.0 && rate >= 90.0 => CoverageBadge::Good,
+        Some(_) => CoverageBadge::NeedsWork,
+    }
+}
+
+/// Per-file coverage badges, files needing the most attention first (least translated, then
+/// lowest validation pass rate).
+pub fn file_coverage(
+    translations: &[WorkingTranslation],
+    ruleset: &ValidationRuleSet,
+    comments: &[ReviewComment],
+) -> Vec<FileCoverage> {
+    let commented: HashSet<(&str, &str)> =
+        comments.iter().map(|c| (c.source_file.as_str(), c.json_path.as_str())).collect();
+    let codes = ControlCodes::from_ruleset(ruleset);
+    let mut by_file: BTreeMap<&str, Vec<&WorkingTranslation>> = BTreeMap::new();
+    for entry in translations {
+        by_file.entry(entry.source_file.as_str()).or_default().push(entry);
+    }
+
+    let mut coverage: Vec<FileCoverage> = by_file
+        .into_iter()
+        .map(|(source_file, entries)| {
+            let translated: Vec<&&WorkingTranslation> = entries
+                .iter()
+                .filter(|e| e.error.is_none() && !e.translated_text.is_empty())
+                .collect();
+            let validation_passed = translated
+                .iter()
+                .filter(|e| {
+                    let issues = validate_translation_with(e, ruleset, &codes);
+                    !issues.iter().any(|i| i.severity == ValidationSeverity::Error)
+                })
+                .count();
+            let reviewed = entries
+                .iter()
+                .filter(|e| commented.contains(&(e.source_file.as_str(), e.json_path.as_str())))
+                .count();
+            let percent_translated = percent(translated.len(), entries.len());
+            let validation_pass_rate = (!translated.is_empty()).then(|| percent(validation_passed, translated.len()));
+            FileCoverage {
+                source_file: source_file.to_string(),
+                entries: entries.len(),
+                translated: translated.len(),
+                reviewed,
+                validation_passed,
+                percent_translated,
+                percent_reviewed: percent(reviewed, entries.len()),
+                validation_pass_rate,
+                badge: coverage_badge(percent_translated, validation_pass_rate),
+            }
+        })
+        .collect();
+    coverage.sort_by(|a, b| {
+        a.percent_translated
+            .total_cmp(&b.percent_translated)
+            .then(a.validation_pass_rate.unwrap_or(0.0).total_cmp(&b.validation_pass_rate.unwrap_or(0.0)))
+    });
+    coverage
+}
+
+/// `file_coverage` with the project's validation ruleset (plus glossary terms) and review comments.
+pub fn project_file_coverage(project_path: &str, translations: &[WorkingTranslation]) -> Result<Vec<FileCoverage>, CoreError> {
+    let mut ruleset = load_project_ruleset(project_path)?;
+    ruleset.locked_terms.extend(as_locked_terms(&load_project_glossary(project_path)?));
+    let comments = load_review_comments(project_path)?;
+    Ok(file_coverage(translations, &ruleset, &comments))
+}
+
+/// Written next to a packaged archive, so saving it can refuse one that failed its gates.
+pub const QUALITY_GATE_REPORT_FILE_NAME: &str = "quality_gates.json";
+
+/// Checks translations against quality gates. Validation errors and placeholder mismatches are
+/// counted per translated entry; placeholders are compared even when the ruleset doesn't. The
+/// percentage translated is out of `total_entries`, the strings of the extraction, so leaving
+/// entries out of `translations` doesn't raise it.
+pub fn check_quality_gates(
+    translations: &[WorkingTranslation],
+    total_entries: usize,
+    ruleset: &ValidationRuleSet,
+    gates: &QualityGates,
+) -> QualityGateReport {
+    let ruleset = ValidationRuleSet { check_placeholders: true, ..ruleset.clone() };
+    let codes = ControlCodes::from_ruleset(&ruleset);
+    let (mut translated, mut validation_errors, mut placeholder_mismatches) = (0, 0, 0);
+    for entry in translations.iter().filter(|e| e.error.is_none() && !e.translated_text.is_empty()) {
+        translated += 1;
+        let issues = validate_translation_with(entry, &ruleset, &codes);
+        if issues.iter().any(|i| i.severity == ValidationSeverity::Error) {
+            validation_errors += 1;
+        }
+        if issues.iter().any(|i| i.rule == "placeholder") {
+            placeholder_mismatches += 1;
+        }
+    }
+
+    let mut results = Vec::new();
+    if let Some(max) = gates.max_validation_errors {
+        results.push(("validationErrors", max as f64, validation_errors as f64, validation_errors <= max));
+    }
+    if let Some(min) = gates.min_percent_translated {
+        let actual = percent(translated, total_entries.max(translations.len()));
+        results.push(("percentTranslated", min, actual, actual >= min));
+    }
+    if let Some(max) = gates.max_placeholder_mismatches {
+        results.push(("placeholderMismatches", max as f64, placeholder_mismatches as f64, placeholder_mismatches <= max));
+    }
+    let gates: Vec<GateResult> = results
+        .into_iter()
+        .map(|(gate, threshold, actual, passed)| GateResult { gate: gate.to_string(), threshold, actual, passed })
+        .collect();
+    QualityGateReport { passed: gates.iter().all(|g| g.passed), gates, error: None }
+}
+
+/// `check_quality_gates` with the project's gates and validation ruleset (plus glossary terms),
+/// out of the strings of its last extraction.
+pub fn project_quality_gates(project_path: &str, translations: &[WorkingTranslation]) -> Result<QualityGateReport, CoreError> {
+    let gates = load_project_config(project_path)?.quality_gates;
+    let mut ruleset = load_project_ruleset(project_path)?;
+    ruleset.locked_terms.extend(as_locked_terms(&load_project_glossary(project_path)?));
+    let extracted = load_snapshot(project_path)?.map_or(0, |snapshot| {
+        snapshot.files.values().map(|file| file.entries.len()).sum()
+    });
+    Ok(check_quality_gates(translations, extracted, &ruleset, &gates))
+}
+
+/// A failing report for gates that couldn't be checked, so the archive is only accepted with an
+/// override.
+pub fn unchecked_gates(error: &CoreError) -> QualityGateReport {
+    QualityGateReport { passed: false, gates: Vec::new(), error: Some(error.to_string()) }
+}
+
+/// The failed gates, e.g. "percentTranslated is 80 (needs at least 95)". Gates of a package
+/// holding several languages are prefixed with theirs ("fr.percentTranslated").
+pub fn describe_gate_failures(report: &QualityGateReport) -> String {
+    let unchecked = report.error.iter().map(|e| format!("the gates could not be checked ({})", e));
+    let failed = report.gates.iter().filter(|g| !g.passed).map(|g| {
+        let bound = if g.gate.ends_with("percentTranslated") { "at least" } else { "at most" };
+        format!("{} is {} (needs {} {})", g.gate, g.actual, bound, g.threshold)
+    });
+    unchecked.chain(failed).collect::<Vec<_>>().join("; ")
+}
+
+pub fn save_gate_report(dir: &Path, report: &QualityGateReport) -> Result<(), CoreError> {
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize quality gate report: {}", e)))?;
+    fs::write(dir.join(QUALITY_GATE_REPORT_FILE_NAME), json)?;
+    Ok(())
+}
+
+/// The report saved in `dir`, or `None` when nothing was checked there.
+pub fn load_gate_report(dir: &Path) -> Result<Option<QualityGateReport>, CoreError> {
+    let path = dir.join(QUALITY_GATE_REPORT_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map(Some)
+        .map_err(|e| CoreError::JsonParse(format!("Failed to parse quality gate report: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::models::provider::ProviderConfig;
+
+    fn entry(original: &str, translated: &str, error: Option<&str>) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "events[1].pages[0].list[1].parameters[0]".to_string(),
+            translation_source: "ollama".to_string(),
+            error: error.map(str::to_string),
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    fn settings() -> EscalationSettings {
+        EscalationSettings {
+            premium_provider: ProviderConfig::Ollama,
+            on_validation_failure: true,
+            on_provider_error: true,
+            on_low_confidence: true,
+            long_source_chars: 20,
+        }
+    }
+
+    #[test]
+    fn test_file_coverage_badges() {
+        let in_file = |file: &str, json_path: &str, original: &str, translated: &str| WorkingTranslation {
+            source_file: file.to_string(),
+            json_path: json_path.to_string(),
+            ..entry(original, translated, None)
+        };
+        let translations = vec![
+            in_file("www/data/Actors.json", "[1].name", "ハロルド", "Harold"),
+            in_file("www/data/Actors.json", "[2].name", "テレーゼ", "Therese"),
+            in_file("www/data/Map001.json", "events[1].name", r"\N[1]、おはよう！", "Good morning!"),
+            in_file("www/data/Map001.json", "events[2].name", "はい", "Yes"),
+            in_file("www/data/Map001.json", "events[3].name", "いいえ", ""),
+            in_file("www/data/Items.json", "[1].name", "ポーション", ""),
+        ];
+        let comments = vec![ReviewComment {
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: "[1].name".to_string(),
+            comment: "Keep the German spelling?".to_string(),
+            reviewer: None,
+        }];
+        let coverage = file_coverage(&translations, &ValidationRuleSet::default(), &comments);
+
+        let files: Vec<(&str, CoverageBadge)> = coverage.iter().map(|c| (c.source_file.as_str(), c.badge)).collect();
+        assert_eq!(files, vec![
+            ("www/data/Items.json", CoverageBadge::Untranslated),
+            ("www/data/Map001.json", CoverageBadge::NeedsWork),
+            ("www/data/Actors.json", CoverageBadge::Complete),
+        ]);
+        assert_eq!(coverage[0].validation_pass_rate, None);
+        assert_eq!((coverage[1].percent_translated, coverage[1].validation_pass_rate), (66.7, Some(50.0)));
+        assert_eq!((coverage[2].reviewed, coverage[2].percent_reviewed), (1, 50.0));
+    }
+
+    #[test]
+    fn test_clean_translation_has_no_signals() {
+        let ok = entry(r"\N[1]、おはよう！", r"Good morning, \N[1]!", None);
+        let ruleset = ValidationRuleSet::default();
+        let codes = ControlCodes::from_ruleset(&ruleset);
+        assert!(hard_line_signals(&ok, "en", &ruleset, &codes, &settings()).is_empty());
+    }
+
+    #[test]
+    fn test_signals_are_detected() {
+        let ruleset = ValidationRuleSet::default();
+        let codes = ControlCodes::from_ruleset(&ruleset);
+        assert_eq!(
+            hard_line_signals(&entry("はい", "", Some("timeout")), "en", &ruleset, &codes, &settings()),
+            vec![HardLineSignal::ProviderError]
+        );
+
+        let placeholder_lost = entry(r"\N[1]、おはよう！", "Good morning!", None);
+        assert!(matches!(
+            hard_line_signals(&placeholder_lost, "en", &ruleset, &codes, &settings())[0],
+            HardLineSignal::ValidationFailure(_)
+        ));
+
+        let untranslated = entry("おはよう", "おはよう", None);
+        assert!(matches!(
+            hard_line_signals(&untranslated, "en", &ruleset, &codes, &settings())[0],
+            HardLineSignal::LowConfidence(_)
+        ));
+        // Identical output is fine when translating into a CJK language.
+        assert!(hard_line_signals(&untranslated, "ja", &ruleset, &codes, &settings()).is_empty());
+
+        let long = entry(&"あ".repeat(30), &"a".repeat(60), None);
+        assert_eq!(hard_line_signals(&long, "en", &ruleset, &codes, &settings()), vec![HardLineSignal::LongSource]);
+    }
+
+    #[test]
+    fn test_stratified_sample_spreads_over_categories() {
+        let source = |path: String| SourceStringData {
+            object_id: 1,
+            original_text: "テキスト".to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: path,
+            dialogue_block_id: None,
+            speaker: None,
+        };
+        let mut entries: Vec<SourceStringData> =
+            (0..10).map(|i| source(format!("events[1].pages[0].list[{}].parameters[0]", i))).collect();
+        entries.push(source("[1].description".to_string()));
+        entries.push(source("[1].name".to_string()));
+
+        let sample = stratified_sample(
+            &entries,
+            &[(SampleCategory::Dialogue, 3), (SampleCategory::Description, 5), (SampleCategory::Other, 0)],
+        );
+        let paths: Vec<&str> = sample.iter().map(|e| e.json_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "events[1].pages[0].list[0].parameters[0]",
+                "events[1].pages[0].list[3].parameters[0]",
+                "events[1].pages[0].list[6].parameters[0]",
+                "[1].description",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quality_gates() {
+        let translations = vec![
+            entry("\\N[1]が来た", "\\N[1] arrived", None),
+            entry("\\V[2]ゴールド", "gold", None),
+            entry("やあ", "Hi", None),
+            entry("さようなら", "", Some("timed out")),
+        ];
+        let ruleset = ValidationRuleSet { check_placeholders: false, ..Default::default() };
+        let passed = QualityGateReport { passed: true, ..Default::default() };
+        assert_eq!(check_quality_gates(&translations, 4, &ruleset, &QualityGates::default()), passed);
+
+        let gates = QualityGates {
+            max_validation_errors: Some(1),
+            min_percent_translated: Some(95.0),
+            max_placeholder_mismatches: Some(0),
+        };
+        let report = check_quality_gates(&translations, 4, &ruleset, &gates);
+        let results: Vec<(&str, f64, bool)> = report.gates.iter().map(|g| (g.gate.as_str(), g.actual, g.passed)).collect();
+        assert_eq!(results, vec![("validationErrors", 1.0, true), ("percentTranslated", 75.0, false), ("placeholderMismatches", 1.0, false)]);
+        assert!(!report.passed);
+        assert_eq!(
+            describe_gate_failures(&report),
+            "percentTranslated is 75 (needs at least 95); placeholderMismatches is 1 (needs at most 0)"
+        );
+        // Entries left out of the package still count as untranslated.
+        let percent_translated = |report: QualityGateReport| report.gates[1].actual;
+        assert_eq!(percent_translated(check_quality_gates(&translations[..3], 8, &ruleset, &gates)), 37.5);
+
+        let unchecked = unchecked_gates(&CoreError::Config("bad glossary".to_string()));
+        assert!(!unchecked.passed);
+        assert!(describe_gate_failures(&unchecked).starts_with("the gates could not be checked"));
+    }
+}
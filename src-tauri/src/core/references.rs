@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::fs;
+use crate::error::CoreError;
+use crate::models::reference::{EntryReferences, ReferenceLink};
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// File name of the per-entry references (inside `.translate-ai/`).
+pub const PROJECT_REFERENCES_FILE_NAME: &str = "entry_references.json";
+
+/// (source_file, json_path) -> references, for lookups during a batch.
+pub type ReferenceIndex = HashMap<(String, String), Vec<ReferenceLink>>;
+
+/// A reference needs a URL or a note, and URLs must be http(s).
+pub fn check_reference(reference: &ReferenceLink) -> Result<(), CoreError> {
+    let url = reference.url.as_deref().map(str::trim).filter(|u| !u.is_empty());
+    let note = reference.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if url.is_none() && note.is_none() {
+        return Err(CoreError::Config("A reference needs a URL or a note".to_string()));
+    }
+    if let Some(url) = url {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(CoreError::Config(format!("Reference URL must start with http:// or https:// (got '{}')", url)));
+        }
+    }
+    Ok(())
+}
+
+/// One-line rendering used in prompts and exports: `note (url)`, `note` or `url`.
+pub fn describe_reference(reference: &ReferenceLink) -> String {
+    let url = reference.url.as_deref().map(str::trim).filter(|u| !u.is_empty());
+    let note = reference.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    match (note, url) {
+        (Some(note), Some(url)) => format!("{} ({})", note, url),
+        (Some(note), None) => note.to_string(),
+        (None, Some(url)) => url.to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Loads the project's entry references, or none if the project has no file yet.
+pub fn load_project_references(project_path: &str) -> Result<Vec<EntryReferences>, CoreError> {
+    let path = app_data_dir(project_path).join(PROJECT_REFERENCES_FILE_NAME);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read references {:?}: {}", path, e)))?;
+    serde_json::from_str(&content).map_err(|e| CoreError::JsonParse(format!("Invalid references file {:?}: {}", path, e)))
+}
+
+pub fn save_project_references(project_path: &str, references: &[EntryReferences]) -> Result<(), CoreError> {
+    for entry in references {
+        for reference in &entry.references {
+            check_reference(reference)?;
+        }
+    }
+    let path = ensure_app_data_file(project_path, PROJECT_REFERENCES_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(references)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize references: {}", e)))?;
+    fs::write(&path, json).map_err(|e| CoreError::Io(format!("Failed to write references to {:?}: {}", path, e)))
+}
+
+/// Replaces the references of one entry; an empty list removes the entry.
+pub fn set_entry_references(
+    project_path: &str,
+    source_file: &str,
+    json_path: &str,
+    references: Vec<ReferenceLink>,
+) -> Result<Vec<EntryReferences>, CoreError> {
+    let mut all = load_project_references(project_path)?;
+    all.retain(|e| !(e.source_file == source_file && e.json_path == json_path));
+    if !references.is_empty() {
+        all.push(EntryReferences {
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            references,
+        });
+    }
+    save_project_references(project_path, &all)?;
+    Ok(all)
+}
+
+pub fn index_references(references: Vec<EntryReferences>) -> ReferenceIndex {
+    references
+        .into_iter()
+        .map(|e| ((e.source_file, e.json_path), e.references))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn link(url: Option<&str>, note: Option<&str>) -> ReferenceLink {
+        ReferenceLink { url: url.map(str::to_string), note: note.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_check_and_describe_reference() {
+        assert!(check_reference(&link(None, None)).is_err());
+        assert!(check_reference(&link(Some("wiki/Selen"), None)).is_err());
+        let full = link(Some("https://example.org/wiki/Selen"), Some("Capital city, feminine"));
+        assert!(check_reference(&full).is_ok());
+        assert_eq!(describe_reference(&full), "Capital city, feminine (https://example.org/wiki/Selen)");
+    }
+
+    #[test]
+    fn test_set_entry_references() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        let file = "www/data/MapInfos.json";
+
+        set_entry_references(project_path, file, "[3].name", vec![link(None, Some("Ruined fortress"))]).unwrap();
+        set_entry_references(project_path, file, "[4].name", vec![link(Some("https://example.org"), None)]).unwrap();
+        let all = set_entry_references(project_path, file, "[4].name", Vec::new()).unwrap();
+
+        assert_eq!(all.len(), 1);
+        let index = index_references(load_project_references(project_path).unwrap());
+        assert_eq!(index[&(file.to_string(), "[3].name".to_string())][0].note.as_deref(), Some("Ruined fortress"));
+    }
+}
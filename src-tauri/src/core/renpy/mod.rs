@@ -0,0 +1,5 @@
+// Ren'Py visual novels. Dialogue and menu choices are extracted from the `.rpy` scripts, and
+// translations are written as Ren'Py `translate` blocks under `game/tl/<language>/`, so the
+// original scripts are never modified.
+pub mod script;
+pub mod project;
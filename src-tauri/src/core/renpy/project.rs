@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::core::config::IgnoredFiles;
+use crate::core::rpgmv::backups::is_backup_dir_name;
+use crate::core::rpgmv::filter::CompiledExtractionFilter;
+use crate::core::rpgmv::project::extract_listed_files;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use super::script;
+
+/// Folder of a Ren'Py game's scripts, relative to the project root.
+pub const GAME_DIR: &str = "game";
+/// Folder inside `game/` holding one subfolder of translate blocks per language.
+pub const TRANSLATION_DIR: &str = "tl";
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| extensions.contains(&ext))
+}
+
+/// True for a Ren'Py game: a `game/` folder with scripts (`.rpy`, or compiled `.rpyc`).
+pub fn is_renpy_project(project_path: &str) -> bool {
+    let game = Path::new(project_path).join(GAME_DIR);
+    game.is_dir()
+        && WalkDir::new(&game)
+            .into_iter()
+            .filter_map(Result::ok)
+            .any(|e| e.file_type().is_file() && has_extension(e.path(), &["rpy", "rpyc"]))
+}
+
+/// Relative paths (with `/`) of the `.rpy` scripts under `game/`, leaving out existing
+/// translations (`game/tl`) and backup folders.
+pub fn script_files(project_path: &str) -> Vec<String> {
+    let game = Path::new(project_path).join(GAME_DIR);
+    let mut files: Vec<String> = WalkDir::new(&game)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or("");
+            e.depth() == 0 || !(e.file_type().is_dir() && ((e.depth() == 1 && name == TRANSLATION_DIR) || is_backup_dir_name(name)))
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file() && has_extension(e.path(), &["rpy"]))
+        .filter_map(|e| Some(e.path().strip_prefix(project_path).ok()?.to_str()?.replace('\\', "/")))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Extracts dialogue and menu choices from every script of a Ren'Py project.
+pub fn extract_translatable_strings(
+    project_path: &str,
+    ignored: &IgnoredFiles,
+    filter: &CompiledExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let files = script_files(project_path);
+    if files.is_empty() {
+        return Err(format!(
+            "No .rpy scripts found in {}/{}. Decompile the .rpyc files first.",
+            project_path, GAME_DIR
+        ));
+    }
+    extract_listed_files(project_path, files, script::extract_strings, ignored, filter, should_stop)
+}
+
+/// Where the translate blocks of a script go, e.g. "game/script.rpy" -> "game/tl/french/script.rpy".
+pub fn translation_file_path(source_file: &str, language: &str) -> String {
+    let inside_game = source_file.strip_prefix(&format!("{}/", GAME_DIR)).unwrap_or(source_file);
+    format!("{}/{}/{}/{}", GAME_DIR, TRANSLATION_DIR, language, inside_game)
+}
+
+/// Renders the translation files of every script with translations, keyed by their path
+/// relative to the project root. Nothing is written.
+pub fn render_translation_files(
+    project_path: &str,
+    language: &str,
+    translations: &[WorkingTranslation],
+) -> Result<BTreeMap<String, String>, CoreError> {
+    // Ren'Py language names are identifiers, e.g. "french" or "schinese".
+    let valid = language.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && language != "None";
+    if !valid {
+        return Err(CoreError::Config(format!("'{}' is not a valid Ren'Py language name", language)));
+    }
+    let mut by_file: BTreeMap<&str, Vec<&WorkingTranslation>> = BTreeMap::new();
+    for entry in translations.iter().filter(|entry| entry.source_file.ends_with(".rpy")) {
+        by_file.entry(entry.source_file.as_str()).or_default().push(entry);
+    }
+    let mut seen_choices = HashSet::new();
+    let mut files = BTreeMap::new();
+    for (source_file, entries) in by_file {
+        let path = Path::new(project_path).join(source_file);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| CoreError::Io(format!("Failed to read script {:?}: {}", path, e)))?;
+        let rendered = script::render_translation_file(&content, source_file, language, &entries, &mut seen_choices);
+        if !rendered.is_empty() {
+            files.insert(translation_file_path(source_file, language), rendered);
+        }
+    }
+    Ok(files)
+}
+
+/// Writes the translation files under `game/tl/<language>/`, replacing earlier ones. Returns
+/// the written paths relative to the project root.
+pub fn write_translation_files(
+    project_path: &str,
+    language: &str,
+    translations: &[WorkingTranslation],
+) -> Result<Vec<String>, CoreError> {
+    let files = render_translation_files(project_path, language, translations)?;
+    for (relative_path, content) in &files {
+        let path = Path::new(project_path).join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| CoreError::Io(format!("Failed to write translation file {:?}: {}", path, e)))?;
+    }
+    Ok(files.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::models::extraction::ExtractionFilter;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_and_write_renpy_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("game/tl/french")).unwrap();
+        fs::create_dir_all(root.join("renpy/common")).unwrap();
+        fs::write(root.join("game/script.rpy"), "label start:\n    e \"こんにちは\"\n").unwrap();
+        fs::write(root.join("game/tl/french/script.rpy"), "translate french start_1:\n    e \"Bonjour\"\n").unwrap();
+        fs::write(root.join("renpy/common/00engine.rpy"), "label _engine:\n    \"engine\"\n").unwrap();
+
+        let project_path = root.to_str().unwrap();
+        assert!(is_renpy_project(project_path));
+        assert_eq!(script_files(project_path), vec!["game/script.rpy"]);
+
+        let filter = ExtractionFilter::default();
+        let compiled = CompiledExtractionFilter::new(&filter).unwrap();
+        let ignored = IgnoredFiles::new(&[]).unwrap();
+        let strings = extract_translatable_strings(project_path, &ignored, &compiled, &|| false).unwrap();
+        assert_eq!(strings.len(), 1);
+        assert_eq!(strings[0].json_path, "[1].dialogue");
+
+        let translation = WorkingTranslation {
+            object_id: 2,
+            original_text: strings[0].original_text.clone(),
+            translated_text: "Hello".to_string(),
+            source_file: strings[0].source_file.clone(),
+            json_path: strings[0].json_path.clone(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let written = write_translation_files(project_path, "english", std::slice::from_ref(&translation)).unwrap();
+        assert_eq!(written, vec!["game/tl/english/script.rpy"]);
+        let content = fs::read_to_string(root.join("game/tl/english/script.rpy")).unwrap();
+        assert!(content.contains("translate english start_"));
+        assert!(content.contains("    e \"Hello\"\n"));
+
+        assert!(matches!(write_translation_files(project_path, "en-US", &[translation]), Err(CoreError::Config(_))));
+    }
+}
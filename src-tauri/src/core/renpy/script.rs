@@ -0,0 +1,415 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+
+const BOM: char = '\u{feff}';
+
+/// Statements starting with these words are never dialogue, even when followed by a string
+/// (e.g. `play music "theme.ogg"`).
+const NON_SAY_KEYWORDS: [&str; 31] = [
+    "play", "queue", "stop", "voice", "sound", "music", "show", "scene", "hide", "image", "define", "default",
+    "call", "jump", "with", "window", "pause", "return", "label", "menu", "init", "python", "translate",
+    "screen", "style", "transform", "if", "elif", "else", "while", "nvl",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    // A say statement: `e "Hello"` or narration `"Hello"`.
+    Dialogue,
+    // A menu choice: `"Go left":` inside a `menu:` block.
+    MenuChoice,
+}
+
+/// A translatable string of a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptString {
+    // Zero-based line index in the script.
+    pub line: usize,
+    pub kind: StatementKind,
+    // The text as shown in the game (escapes resolved).
+    pub text: String,
+    // Speaker and attributes before the string, e.g. "e happy".
+    pub who: String,
+    // Clauses after the string (e.g. "with vpunch"), without an explicit `id`.
+    pub rest: String,
+    // Ren'Py's translation identifier of a dialogue line, e.g. "start_636ae3f5".
+    pub identifier: Option<String>,
+}
+
+impl ScriptString {
+    pub fn json_path(&self) -> String {
+        let kind = match self.kind {
+            StatementKind::Dialogue => "dialogue",
+            StatementKind::MenuChoice => "menu",
+        };
+        format!("[{}].{}", self.line, kind)
+    }
+
+    /// The statement as Ren'Py prints it, which is what its identifier hashes.
+    fn code(&self) -> String {
+        join_code(&self.who, &encode_say_string(&self.text), &self.rest)
+    }
+}
+
+fn join_code(who: &str, string: &str, rest: &str) -> String {
+    [who, string, rest].iter().filter(|part| !part.is_empty()).copied().collect::<Vec<_>>().join(" ")
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn say_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"^(?:([A-Za-z_][\w.]*(?:\s+@?[A-Za-z_][\w-]*)*)\s+)?"((?:[^"\\]|\\.)*)"\s*(.*)$"#)
+            .expect("say pattern is valid")
+    })
+}
+
+fn label_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^label\s+(\.?[A-Za-z_][\w.]*)").expect("label pattern is valid"))
+}
+
+fn explicit_id_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|\s)id\s+(\w+)").expect("id pattern is valid"))
+}
+
+// Blocks whose content is code or layout, not dialogue.
+fn starts_skipped_block(statement: &str) -> bool {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"^(?:translate\s|(?:init\s+(?:-?\d+\s+)?)?python\b|screen\s|style\s|transform\s|testcase\s)")
+            .expect("block pattern is valid")
+    });
+    statement.ends_with(':') && re.is_match(statement)
+}
+
+/// Resolves a Ren'Py string literal the way its lexer does: runs of whitespace collapse to one
+/// space, then backslash escapes are applied.
+pub fn decode_string(raw: &str) -> String {
+    let mut collapsed = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            collapsed.push(c);
+            if let Some(escaped) = chars.next() {
+                collapsed.push(escaped);
+            }
+        } else if c.is_whitespace() {
+            while chars.peek().is_some_and(|next| next.is_whitespace()) {
+                chars.next();
+            }
+            collapsed.push(' ');
+        } else {
+            collapsed.push(c);
+        }
+    }
+    let mut text = String::with_capacity(collapsed.len());
+    let mut chars = collapsed.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Quotes a string like Ren'Py's `encode_say_string`.
+pub fn encode_say_string(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('\n', "\\n").replace('"', "\\\"");
+    let mut out = String::with_capacity(escaped.len() + 2);
+    out.push('"');
+    let mut previous = None;
+    for c in escaped.chars() {
+        // A space after a space would be collapsed by the lexer.
+        if c == ' ' && previous == Some(' ') {
+            out.push('\\');
+        }
+        out.push(c);
+        previous = Some(c);
+    }
+    out.push('"');
+    out
+}
+
+/// Ren'Py's identifier for a dialogue line: the label and the first 8 hex digits of the MD5 of
+/// the statement code followed by CRLF.
+fn dialogue_identifier(label: &str, code: &str) -> String {
+    let digest = format!("{:x}", md5::compute(format!("{}\r\n", code).as_bytes()));
+    let label = label.replace('.', "_");
+    if label.is_empty() {
+        digest[..8].to_string()
+    } else {
+        format!("{}_{}", label, &digest[..8])
+    }
+}
+
+/// Finds the dialogue lines and menu choices of a script. Python, screen, style, transform and
+/// existing translate blocks are skipped.
+pub fn parse_script(content: &str) -> Vec<ScriptString> {
+    let content = content.strip_prefix(BOM).unwrap_or(content);
+    let mut strings = Vec::new();
+    let mut skipped_block: Option<usize> = None;
+    let mut menus: Vec<usize> = Vec::new();
+    let (mut global_label, mut label) = (String::new(), String::new());
+    let mut identifiers: HashSet<String> = HashSet::new();
+
+    for (index, raw_line) in content.split('\n').enumerate() {
+        let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        let statement = line.trim();
+        if statement.is_empty() || statement.starts_with('#') {
+            continue;
+        }
+        let indent = indent_of(line);
+        if let Some(block_indent) = skipped_block {
+            if indent > block_indent {
+                continue;
+            }
+            skipped_block = None;
+        }
+        while menus.last().is_some_and(|menu_indent| indent <= *menu_indent) {
+            menus.pop();
+        }
+        if starts_skipped_block(statement) {
+            skipped_block = Some(indent);
+            continue;
+        }
+        if let Some(captures) = label_regex().captures(statement) {
+            let name = &captures[1];
+            label = match name.strip_prefix('.') {
+                Some(_) => format!("{}{}", global_label, name),
+                None => name.to_string(),
+            };
+            if !name.starts_with('.') {
+                global_label = name.split('.').next().unwrap_or(name).to_string();
+            }
+            continue;
+        }
+        if statement.starts_with("menu") && statement.ends_with(':') {
+            let rest = &statement["menu".len()..];
+            if rest.starts_with([' ', ':', '(']) {
+                menus.push(indent);
+                continue;
+            }
+        }
+        let Some(captures) = say_regex().captures(statement) else { continue };
+        let who = captures.get(1).map_or("", |m| m.as_str());
+        if NON_SAY_KEYWORDS.contains(&who.split_whitespace().next().unwrap_or("")) {
+            continue;
+        }
+        let rest = captures[3].trim();
+        // Two strings (`"Narrator" "Hello"`) or an expression: not handled.
+        if rest.starts_with('"') || rest.starts_with('=') {
+            continue;
+        }
+        let text = decode_string(&captures[2]);
+        if text.trim().is_empty() {
+            continue;
+        }
+        if rest.ends_with(':') {
+            if who.is_empty() && !menus.is_empty() {
+                strings.push(ScriptString {
+                    line: index,
+                    kind: StatementKind::MenuChoice,
+                    text,
+                    who: String::new(),
+                    rest: String::new(),
+                    identifier: None,
+                });
+            }
+            continue;
+        }
+        let explicit_id = explicit_id_regex().captures(rest).map(|c| c[1].to_string());
+        let mut string = ScriptString {
+            line: index,
+            kind: StatementKind::Dialogue,
+            text,
+            who: who.split_whitespace().collect::<Vec<_>>().join(" "),
+            rest: explicit_id_regex().replace(rest, "").trim().to_string(),
+            identifier: None,
+        };
+        let identifier = explicit_id.unwrap_or_else(|| {
+            let base = dialogue_identifier(&label, &string.code());
+            let mut identifier = base.clone();
+            let mut suffix = 1;
+            while identifiers.contains(&identifier) {
+                identifier = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            identifier
+        });
+        identifiers.insert(identifier.clone());
+        string.identifier = Some(identifier);
+        strings.push(string);
+    }
+    strings
+}
+
+/// Extracts dialogue and menu choices. The json_path is the zero-based line and the kind, e.g.
+/// "[12].dialogue", and the object id the line number.
+pub fn extract_strings(file_content: &str, source_file: &str) -> Result<Vec<SourceStringData>, String> {
+    Ok(parse_script(file_content)
+        .into_iter()
+        .map(|string| SourceStringData {
+            object_id: string.line as u32 + 1,
+            json_path: string.json_path(),
+            original_text: string.text,
+            source_file: source_file.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        })
+        .collect())
+}
+
+/// Renders the `game/tl/<language>/` file for a script: one `translate <language> <id>:` block
+/// per dialogue line and a `translate <language> strings:` block for menu choices. Choices in
+/// `seen_choices` are already translated by another file and left out, as Ren'Py rejects
+/// duplicates. Failed or empty translations keep the original text.
+pub fn render_translation_file(
+    content: &str,
+    source_file: &str,
+    language: &str,
+    translations: &[&WorkingTranslation],
+    seen_choices: &mut HashSet<String>,
+) -> String {
+    let by_path: HashMap<&str, &WorkingTranslation> =
+        translations.iter().map(|entry| (entry.json_path.as_str(), *entry)).collect();
+    let translated = |string: &ScriptString| -> String {
+        match by_path.get(string.json_path().as_str()) {
+            Some(entry) if entry.error.is_none() && !entry.translated_text.is_empty() => entry.translated_text.clone(),
+            _ => string.text.clone(),
+        }
+    };
+
+    let mut out = String::new();
+    let mut choices = Vec::new();
+    for string in parse_script(content) {
+        if !by_path.contains_key(string.json_path().as_str()) {
+            continue;
+        }
+        match (&string.kind, &string.identifier) {
+            (StatementKind::Dialogue, Some(identifier)) => {
+                out.push_str(&format!("# {}:{}\n", source_file, string.line + 1));
+                out.push_str(&format!("translate {} {}:\n\n", language, identifier));
+                out.push_str(&format!("    # {}\n", string.code()));
+                let line = join_code(&string.who, &encode_say_string(&translated(&string)), &string.rest);
+                out.push_str(&format!("    {}\n\n", line));
+            }
+            (StatementKind::MenuChoice, _) if seen_choices.insert(string.text.clone()) => {
+                choices.push(string);
+            }
+            _ => {}
+        }
+    }
+    if !choices.is_empty() {
+        out.push_str(&format!("translate {} strings:\n\n", language));
+        for string in choices {
+            out.push_str(&format!("    # {}:{}\n", source_file, string.line + 1));
+            out.push_str(&format!("    old {}\n", encode_say_string(&string.text)));
+            out.push_str(&format!("    new {}\n\n", encode_say_string(&translated(&string))));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    const SCRIPT: &str = r#"define e = Character("Eileen")
+
+label start:
+    scene bg room
+    play music "theme.ogg"
+    e "こんにちは！"
+    e happy "二行目の   台詞。\n改行あり" with vpunch
+    "ナレーション \"引用\""
+    menu:
+        "どこへ行く？"
+        "左へ":
+            e "左だ。"
+        "右へ" if has_key:
+            jump right
+    python:
+        name = renpy.input("名前")
+        e("not dialogue")
+
+label .after:
+    e "こんにちは！"
+    e "ID付き" id custom_line
+"#;
+
+    #[test]
+    fn test_parse_script() {
+        let strings = parse_script(SCRIPT);
+        let found: Vec<(String, &str)> = strings.iter().map(|s| (s.json_path(), s.text.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("[5].dialogue".to_string(), "こんにちは！"),
+                ("[6].dialogue".to_string(), "二行目の 台詞。\n改行あり"),
+                ("[7].dialogue".to_string(), "ナレーション \"引用\""),
+                ("[9].dialogue".to_string(), "どこへ行く？"),
+                ("[10].menu".to_string(), "左へ"),
+                ("[11].dialogue".to_string(), "左だ。"),
+                ("[12].menu".to_string(), "右へ"),
+                ("[19].dialogue".to_string(), "こんにちは！"),
+                ("[20].dialogue".to_string(), "ID付き"),
+            ]
+        );
+        // Ren'Py: md5('e "こんにちは！"\r\n').
+        let expected = format!("{:x}", md5::compute("e \"こんにちは！\"\r\n".as_bytes()));
+        assert_eq!(strings[0].identifier.as_deref(), Some(format!("start_{}", &expected[..8]).as_str()));
+        assert_eq!(strings[7].identifier.as_deref(), Some(format!("start_after_{}", &expected[..8]).as_str()));
+        assert_eq!(strings[8].identifier.as_deref(), Some("custom_line"));
+        assert_eq!(strings[1].code(), r#"e happy "二行目の 台詞。\n改行あり" with vpunch"#);
+    }
+
+    #[test]
+    fn test_encode_say_string() {
+        assert_eq!(encode_say_string("a  b\n\"c\"\\"), r#""a \ b\n\"c\"\\""#);
+        assert_eq!(decode_string(r#"a \ b\n\"c\"\\"#), "a  b\n\"c\"\\");
+    }
+
+    #[test]
+    fn test_render_translation_file() {
+        let entry = |json_path: &str, translated: &str| WorkingTranslation {
+            object_id: 1,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "game/script.rpy".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let translations =
+            [entry("[6].dialogue", "Second \"line\""), entry("[10].menu", "Go left"), entry("[12].menu", "")];
+        let refs: Vec<&WorkingTranslation> = translations.iter().collect();
+        let mut seen = HashSet::new();
+        let rendered = render_translation_file(SCRIPT, "game/script.rpy", "english", &refs, &mut seen);
+        let identifier = parse_script(SCRIPT)[1].identifier.clone().unwrap();
+        assert_eq!(
+            rendered,
+            format!(
+                "# game/script.rpy:7\ntranslate english {}:\n\n    # e happy \"二行目の 台詞。\\n改行あり\" with vpunch\n    e happy \"Second \\\"line\\\"\" with vpunch\n\n\
+translate english strings:\n\n    # game/script.rpy:11\n    old \"左へ\"\n    new \"Go left\"\n\n    # game/script.rpy:13\n    old \"右へ\"\n    new \"右へ\"\n\n",
+                identifier
+            )
+        );
+        // Choices are only written once per language.
+        let again = render_translation_file(SCRIPT, "game/other.rpy", "english", &refs[1..], &mut seen);
+        assert!(again.is_empty());
+    }
+}
@@ -59,7 +59,7 @@ pub fn reconstruct_actors_json(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::translation::WorkingTranslation; // Updated import for tests
+    use crate::models::translation::{TranslationStatus, WorkingTranslation};
     use serde_json::{json, Value};
 
     #[test]
@@ -112,6 +112,7 @@ mod tests {
                 translated_text: "Harold (EN)".to_string(),
                 translation_source: "test_source".to_string(), // Added for WorkingTranslation
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct name
                 object_id: 1,
@@ -121,6 +122,7 @@ mod tests {
                 translated_text: "Harry".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct name
                 object_id: 1,
@@ -130,6 +132,7 @@ mod tests {
                 translated_text: "A brave hero indeed.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct name
                 object_id: 1,
@@ -139,6 +142,7 @@ mod tests {
                 translated_text: "Harold's translated note.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             // Translate Seren's (ID 9) name and profile only
             WorkingTranslation { // Updated struct name
@@ -149,6 +153,7 @@ mod tests {
                 translated_text: "Seren (EN)".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct name
                 object_id: 9,
@@ -158,6 +163,7 @@ mod tests {
                 translated_text: "Her origins are unknown. She conceals hidden weapons.\nIf you turn and see her, your life is forfeit!".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         
@@ -206,6 +212,7 @@ mod tests {
                 translated_text: "HLD_TransFail".to_string(), 
                 translation_source: "test_source".to_string(), // Added
                 error: Some("AI translation timed out".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation { // Updated struct name
                 object_id: 1,
@@ -215,6 +222,7 @@ mod tests {
                 translated_text: "Translated Profile".to_string(),
                 translation_source: "test_source".to_string(), // Added
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect(); // Updated type
@@ -258,6 +266,7 @@ mod tests {
                 translated_text: "亡霊".to_string(),
                 translation_source: "test_source".to_string(), // Added
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect(); // Updated type
@@ -294,6 +303,7 @@ mod tests {
                 translated_text: "Translated value for non-existent field".to_string(),
                 translation_source: "test_source".to_string(), // Added
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect(); // Updated type
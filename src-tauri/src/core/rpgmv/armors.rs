@@ -51,6 +51,7 @@ pub fn reconstruct_armors_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::{json, Value};
 
     const TEST_ARMORS_JSON: &str = r#"[
@@ -74,6 +75,7 @@ mod tests {
                 translated_text: "Shield (EN)".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -83,6 +85,7 @@ mod tests {
                 translated_text: "A basic shield.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 3,
@@ -92,6 +95,7 @@ mod tests {
                 translated_text: "Clothes (EN)".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 12,
@@ -101,6 +105,7 @@ mod tests {
                 translated_text: "Training Straitjacket (EN)".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 12,
@@ -110,6 +115,7 @@ mod tests {
                 translated_text: "<Extended Desc: \"Training Straitjacket\"\nA straitjacket that restricts movement and enforces submission. (EN)>".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         
@@ -143,6 +149,7 @@ mod tests {
                 translated_text: "ShieldFail".to_string(),
                 translation_source: "test_source".to_string(),
                 error: Some("AI error".to_string()), 
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -152,6 +159,7 @@ mod tests {
                 translated_text: "A simple hat.".to_string(), 
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -176,6 +184,7 @@ mod tests {
                 translated_text: "Phantom Armor".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -198,6 +207,7 @@ mod tests {
                 translated_text: "Translated Mystery".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -232,6 +242,7 @@ mod tests {
                 translated_text: "盾".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+use serde::Serialize;
+use walkdir::WalkDir;
+use crate::error::CoreError;
+use super::project::extract_strings_from_file_content;
+use super::schema::detect_schema;
+
+// How deep below the project root backup folders are looked for (e.g. "www/data_backup/old").
+const BACKUP_SEARCH_DEPTH: usize = 3;
+
+fn backup_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // "data_backup", "backup", "bak", "old", "data - Copy", "Copy of data", "data (2)", "data~", "コピー".
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)(backup|^bak$|[_\-. ]bak$|^bak[_\-. ]|^old$|[_\-. ]old$|^old[_\-. ]| - copy( \(\d+\))?$|^copy of |コピー| \(\d+\)$|~$)")
+            .unwrap()
+    })
+}
+
+/// Folder names editors and users give to older copies of the data folder.
+pub fn is_backup_dir_name(name: &str) -> bool {
+    backup_name_regex().is_match(name)
+}
+
+/// Copies of a data file next to it, e.g. "Map001 - Copy.json" or "Actors_old.json".
+pub fn is_backup_file_name(name: &str) -> bool {
+    let stem = name.strip_suffix(".json").unwrap_or(name);
+    backup_name_regex().is_match(stem)
+}
+
+/// The data file a backup copy stands for: "Map001 - Copy.json" -> "Map001.json".
+fn original_file_name(backup_name: &str) -> String {
+    let stem = backup_name.strip_suffix(".json").unwrap_or(backup_name);
+    let stem = backup_name_regex().replace_all(stem, "");
+    format!("{}.json", stem.trim_matches(|c: char| c == '_' || c == '-' || c == '.' || c.is_whitespace()))
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupFileStatus {
+    Identical,
+    Differs,
+    // The backup has a data file the project no longer has.
+    MissingFromData,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFileComparison {
+    // Paths relative to the project root.
+    pub backup_file: String,
+    pub data_file: String,
+    pub status: BackupFileStatus,
+    // Extracted strings that are new, missing or different in the backup.
+    pub changed_strings: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupReport {
+    pub data_dir: String,
+    // Backup folders found in the project, plus the data folder itself if it holds copies.
+    pub backup_locations: Vec<String>,
+    pub files: Vec<BackupFileComparison>,
+    // True when a backup differs from the data folder, i.e. the project mixes versions.
+    pub mixed_versions: bool,
+}
+
+fn relative(project_path: &Path, path: &Path) -> String {
+    path.strip_prefix(project_path).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+fn json_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| e.path().is_file())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .filter(|name| name.ends_with(".json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn changed_strings(file_name: &str, data: &str, backup: &str, data_file: &str) -> usize {
+    let extract = |content: &str| -> HashMap<String, String> {
+        match extract_strings_from_file_content(file_name, content, data_file) {
+            Some(Ok(strings)) => strings.into_iter().map(|s| (s.json_path, s.original_text)).collect(),
+            _ => HashMap::new(),
+        }
+    };
+    let (data, backup) = (extract(data), extract(backup));
+    let paths: HashSet<&String> = data.keys().chain(backup.keys()).collect();
+    paths.into_iter().filter(|path| data.get(*path) != backup.get(*path)).count()
+}
+
+fn compare_file(
+    project_path: &Path,
+    data_path: &Path,
+    backup_path: &Path,
+    file_name: &str,
+) -> Result<BackupFileComparison, CoreError> {
+    let data_file_path = data_path.join(file_name);
+    let backup_file = relative(project_path, backup_path);
+    let data_file = relative(project_path, &data_file_path);
+    if !data_file_path.is_file() {
+        return Ok(BackupFileComparison { backup_file, data_file, status: BackupFileStatus::MissingFromData, changed_strings: 0 });
+    }
+    let data = fs::read_to_string(&data_file_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {}: {}", data_file, e)))?;
+    let backup = fs::read_to_string(backup_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {}: {}", backup_file, e)))?;
+    let (status, changed_strings) = if data == backup {
+        (BackupFileStatus::Identical, 0)
+    } else {
+        (BackupFileStatus::Differs, changed_strings(file_name, &data, &backup, &data_file))
+    };
+    Ok(BackupFileComparison { backup_file, data_file, status, changed_strings })
+}
+
+/// Finds backup copies of the data folder (and of single data files inside it) and compares
+/// each with the file the extraction actually reads.
+pub fn compare_data_with_backups(project_path: &str) -> Result<BackupReport, CoreError> {
+    let schema = detect_schema(project_path)
+        .ok_or_else(|| CoreError::Custom(format!("No RPG Maker MV/MZ data folder found in {}", project_path)))?;
+    let root = Path::new(project_path);
+    let data_path = root.join(&schema.data_dir);
+    let mut report = BackupReport { data_dir: schema.data_dir.clone(), ..Default::default() };
+
+    let mut backup_dirs: Vec<_> = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(BACKUP_SEARCH_DEPTH)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_dir() && e.file_name().to_str().is_some_and(is_backup_dir_name))
+        .map(|e| e.into_path())
+        .filter(|dir| !json_files(dir).is_empty())
+        .collect();
+    backup_dirs.sort();
+
+    for dir in &backup_dirs {
+        report.backup_locations.push(relative(root, dir));
+        for file_name in json_files(dir) {
+            report.files.push(compare_file(root, &data_path, &dir.join(&file_name), &file_name)?);
+        }
+    }
+
+    let copies: Vec<String> = json_files(&data_path).into_iter().filter(|name| is_backup_file_name(name)).collect();
+    if !copies.is_empty() {
+        report.backup_locations.push(schema.data_dir.clone());
+    }
+    for copy in copies {
+        report.files.push(compare_file(root, &data_path, &data_path.join(&copy), &original_file_name(&copy))?);
+    }
+
+    report.mixed_versions = report.files.iter().any(|f| f.status == BackupFileStatus::Differs);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_backup_names() {
+        for name in ["data_backup", "Backup", "data.bak", "old", "data_old", "data - Copy", "Copy of data", "data (2)", "data~", "data - コピー"] {
+            assert!(is_backup_dir_name(name), "{}", name);
+        }
+        for name in ["data", "www", "img", "Golden", "bold"] {
+            assert!(!is_backup_dir_name(name), "{}", name);
+        }
+        assert!(is_backup_file_name("Map001 - Copy.json"));
+        assert!(is_backup_file_name("Actors_old.json"));
+        assert!(!is_backup_file_name("Map001.json"));
+        assert_eq!(original_file_name("Map001 - Copy.json"), "Map001.json");
+        assert_eq!(original_file_name("Actors_backup.json"), "Actors.json");
+    }
+
+    #[test]
+    fn test_compare_data_with_backups() {
+        let dir = tempdir().unwrap();
+        let items = |name: &str| format!(r#"[null,{{"id":1,"name":"{}","description":"","note":""}}]"#, name);
+        fs::create_dir_all(dir.path().join("www/data")).unwrap();
+        fs::create_dir_all(dir.path().join("www/data_backup")).unwrap();
+        fs::write(dir.path().join("www/data/Items.json"), items("ポーション")).unwrap();
+        fs::write(dir.path().join("www/data/System.json"), "{}").unwrap();
+        fs::write(dir.path().join("www/data/Items - Copy.json"), items("ポーション")).unwrap();
+        fs::write(dir.path().join("www/data_backup/Items.json"), items("薬草")).unwrap();
+        fs::write(dir.path().join("www/data_backup/Map099.json"), "{}").unwrap();
+
+        let report = compare_data_with_backups(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(report.backup_locations, vec!["www/data_backup", "www/data"]);
+        let statuses: Vec<(&str, BackupFileStatus, usize)> =
+            report.files.iter().map(|f| (f.backup_file.as_str(), f.status, f.changed_strings)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("www/data_backup/Items.json", BackupFileStatus::Differs, 1),
+                ("www/data_backup/Map099.json", BackupFileStatus::MissingFromData, 0),
+                ("www/data/Items - Copy.json", BackupFileStatus::Identical, 0),
+            ]
+        );
+        assert!(report.mixed_versions);
+    }
+}
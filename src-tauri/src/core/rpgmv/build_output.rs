@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+use crate::core::rpgmv::project::ReconstructedProject;
+use crate::error::CoreError;
+use crate::utils::project_paths::APP_DATA_DIR_NAME;
+
+/// Makes `output_dir` a playable copy of the project (without its app data folder) that
+/// translated files can be written to. Files already in the copy are kept, so only the first
+/// call copies the whole project.
+pub fn prepare_build_dir(project_path: &str, output_dir: &str) -> Result<(), CoreError> {
+    fs::create_dir_all(output_dir)
+        .map_err(|e| CoreError::Io(format!("Failed to create build folder {}: {}", output_dir, e)))?;
+    let root = fs::canonicalize(project_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read project folder {}: {}", project_path, e)))?;
+    let output = fs::canonicalize(output_dir)?;
+    if output == root {
+        return Err(CoreError::Config("The build folder can't be the project folder itself".to_string()));
+    }
+    // A build folder inside the project isn't copied into itself.
+    let entries = WalkDir::new(&root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != APP_DATA_DIR_NAME && e.path() != output);
+    for entry in entries {
+        let entry = entry.map_err(|e| CoreError::Io(format!("Failed to list {:?}: {}", root, e)))?;
+        let Ok(relative_path) = entry.path().strip_prefix(&root) else { continue };
+        let target = output.join(relative_path);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() && !target.exists() {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| CoreError::Io(format!("Failed to copy {:?} to {:?}: {}", entry.path(), target, e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the reconstructed files into the build folder, skipping those whose content is
+/// already there so a running game only reloads what changed. Returns the files written.
+pub fn write_build_files(output_dir: &str, reconstructed: &ReconstructedProject) -> Result<Vec<String>, CoreError> {
+    let text_files = reconstructed.files.iter().map(|(path, content)| (path, content.as_bytes()));
+    let binary_files = reconstructed.binary_files.iter().map(|(path, content)| (path, content.as_slice()));
+    let mut written = Vec::new();
+    for (relative_path, content) in text_files.chain(binary_files) {
+        if !Path::new(relative_path).components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(CoreError::Config(format!("Refusing to write outside the build folder: {}", relative_path)));
+        }
+        let target = Path::new(output_dir).join(relative_path);
+        if fs::read(&target).is_ok_and(|current| current == content) {
+            continue;
+        }
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&target, content).map_err(|e| CoreError::Io(format!("Failed to write {:?}: {}", target, e)))?;
+        written.push(relative_path.clone());
+    }
+    written.sort();
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_build_folder_gets_changed_files_only() {
+        let project = tempdir().unwrap();
+        fs::create_dir_all(project.path().join("www/data")).unwrap();
+        fs::create_dir_all(project.path().join(APP_DATA_DIR_NAME)).unwrap();
+        fs::write(project.path().join("www/data/Actors.json"), "[null]").unwrap();
+        fs::write(project.path().join("www/index.html"), "<html>").unwrap();
+        fs::write(project.path().join(APP_DATA_DIR_NAME).join("session.json"), "{}").unwrap();
+        let project_path = project.path().to_str().unwrap();
+        let build = project.path().join("build");
+        let build_dir = build.to_str().unwrap();
+
+        prepare_build_dir(project_path, build_dir).unwrap();
+        assert_eq!(fs::read_to_string(build.join("www/index.html")).unwrap(), "<html>");
+        assert!(!build.join(APP_DATA_DIR_NAME).exists());
+        assert!(!build.join("build").exists());
+        assert!(matches!(prepare_build_dir(project_path, project_path), Err(CoreError::Config(_))));
+
+        let reconstructed = ReconstructedProject {
+            files: HashMap::from([
+                ("www/data/Actors.json".to_string(), "[null]".to_string()),
+                ("www/data/Items.json".to_string(), "[null,{}]".to_string()),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(write_build_files(build_dir, &reconstructed).unwrap(), vec!["www/data/Items.json"]);
+        assert!(write_build_files(build_dir, &reconstructed).unwrap().is_empty());
+
+        let outside = ReconstructedProject {
+            files: HashMap::from([("../Actors.json".to_string(), String::new())]),
+            ..Default::default()
+        };
+        assert!(write_build_files(build_dir, &outside).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use walkdir::WalkDir;
+use crate::error::CoreError;
+use super::common::split_name_box_code;
+use crate::models::character::CharacterLine;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorInfo {
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub face_name: String,
+    #[serde(default)]
+    pub face_index: u32,
+}
+
+/// Message lines attributed to one speaker, in file order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeakerLines {
+    pub key: String,
+    pub name: String,
+    pub actor_id: Option<u32>,
+    pub lines: Vec<CharacterLine>,
+}
+
+/// Result of scanning a project's event data for dialogue.
+#[derive(Debug, Default)]
+pub struct DialogueScan {
+    // Speakers in order of first appearance.
+    pub speakers: Vec<SpeakerLines>,
+    // Map file (relative path) -> map name from MapInfos.json.
+    pub map_names: HashMap<String, String>,
+    pub errors: Vec<String>,
+}
+
+impl DialogueScan {
+    fn speaker_mut(&mut self, key: &str, name: &str, actor_id: Option<u32>) -> &mut SpeakerLines {
+        let position = match self.speakers.iter().position(|s| s.key == key) {
+            Some(position) => position,
+            None => {
+                self.speakers.push(SpeakerLines {
+                    key: key.to_string(),
+                    name: name.to_string(),
+                    actor_id,
+                    lines: Vec::new(),
+                });
+                self.speakers.len() - 1
+            }
+        };
+        &mut self.speakers[position]
+    }
+}
+
+pub fn parse_actors(content: &str) -> Result<Vec<ActorInfo>, String> {
+    let actors: Vec<Option<ActorInfo>> =
+        serde_json::from_str(content).map_err(|e| format!("Failed to parse Actors.json: {}", e))?;
+    Ok(actors.into_iter().flatten().filter(|a| a.id != 0).collect())
+}
+
+/// Map id -> name, from MapInfos.json.
+pub fn parse_map_names(content: &str) -> Result<HashMap<u32, String>, String> {
+    #[derive(Deserialize)]
+    struct MapInfo {
+        id: u32,
+        name: String,
+    }
+    let infos: Vec<Option<MapInfo>> =
+        serde_json::from_str(content).map_err(|e| format!("Failed to parse MapInfos.json: {}", e))?;
+    Ok(infos.into_iter().flatten().map(|i| (i.id, i.name)).collect())
+}
+
+struct Speaker {
+    key: String,
+    name: String,
+    actor_id: Option<u32>,
+}
+
+fn actor_speaker(actor: &ActorInfo) -> Speaker {
+    Speaker { key: format!("actor:{}", actor.id), name: actor.name.clone(), actor_id: Some(actor.id) }
+}
+
+/// `\N[n]` on its own (the usual way MV games put a speaker name in front of a message).
+fn actor_name_code(text: &str) -> Option<u32> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"^\\[Nn]\[(\d+)\]$").expect("name code pattern is valid"));
+    re.captures(text.trim()).and_then(|c| c[1].parse().ok())
+}
+
+/// Works out who speaks a Show Text (101) message: the MZ name box first, then a first line
+/// consisting only of `\N[n]` or starting with a name box code, then the face graphic. Returns the speaker and whether the first
+/// message line was only the name (and so is not dialogue).
+fn resolve_speaker(
+    show_text_parameters: &[Value],
+    first_line: Option<&str>,
+    actors: &[ActorInfo],
+) -> Option<(Speaker, bool)> {
+    let by_id = |id: u32| actors.iter().find(|a| a.id == id).map(actor_speaker);
+
+    let name_box = show_text_parameters.get(4).and_then(Value::as_str).map(str::trim).unwrap_or("");
+    if !name_box.is_empty() {
+        if let Some(speaker) = actor_name_code(name_box).and_then(by_id) {
+            return Some((speaker, false));
+        }
+        if let Some(actor) = actors.iter().find(|a| a.name == name_box) {
+            return Some((actor_speaker(actor), false));
+        }
+        let speaker = Speaker { key: format!("name:{}", name_box), name: name_box.to_string(), actor_id: None };
+        return Some((speaker, false));
+    }
+
+    if let Some(speaker) = first_line.and_then(actor_name_code).and_then(by_id) {
+        return Some((speaker, true));
+    }
+    // Plugin name box code (`\n<Name>`) at the start of the first line.
+    if let Some(name_box) = first_line.and_then(split_name_box_code) {
+        let name = name_box.name.trim();
+        let speaker = actor_name_code(name)
+            .and_then(by_id)
+            .or_else(|| actors.iter().find(|a| a.name == name).map(actor_speaker))
+            .unwrap_or_else(|| Speaker { key: format!("name:{}", name), name: name.to_string(), actor_id: None });
+        return Some((speaker, name_box.rest.trim().is_empty()));
+    }
+
+    let face_name = show_text_parameters.first().and_then(Value::as_str).unwrap_or("");
+    if face_name.is_empty() {
+        return None; // Narration
+    }
+    let face_index = show_text_parameters.get(1).and_then(Value::as_u64).unwrap_or(0) as u32;
+    match actors.iter().find(|a| a.face_name == face_name && a.face_index == face_index) {
+        Some(actor) => Some((actor_speaker(actor), false)),
+        None => Some((
+            Speaker {
+                key: format!("face:{}#{}", face_name, face_index),
+                name: format!("{} #{}", face_name, face_index),
+                actor_id: None,
+            },
+            false,
+        )),
+    }
+}
+
+fn is_command_list(value: &Value) -> bool {
+    value
+        .as_array()
+        .and_then(|list| list.first())
+        .is_some_and(|first| first.get("code").is_some())
+}
+
+/// Calls `visit` with every event command list (`list` arrays) in a data file and its json_path
+/// prefix, built the same way the extractors build them (e.g. `events[1].pages[0].list`).
+fn for_each_command_list(value: &Value, path: &str, visit: &mut dyn FnMut(&[Value], &str)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                if key == "list" && is_command_list(child) {
+                    visit(child.as_array().map(Vec::as_slice).unwrap_or_default(), &child_path);
+                } else {
+                    for_each_command_list(child, &child_path, visit);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                if child.is_object() || child.is_array() {
+                    for_each_command_list(child, &format!("{}[{}]", path, index), visit);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Attributes the message lines (401) of every Show Text in a data file to their speaker.
+pub fn scan_dialogue(data: &Value, source_file: &str, actors: &[ActorInfo], scan: &mut DialogueScan) {
+    for_each_command_list(data, "", &mut |commands, list_path| {
+        let code = |i: usize| commands.get(i).and_then(|c| c.get("code")).and_then(Value::as_i64);
+        let text = |i: usize| {
+            commands
+                .get(i)
+                .and_then(|c| c.get("parameters"))
+                .and_then(|p| p.get(0))
+                .and_then(Value::as_str)
+        };
+        for (index, command) in commands.iter().enumerate() {
+            if code(index) != Some(101) {
+                continue;
+            }
+            let parameters = command
+                .get("parameters")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let first_line = if code(index + 1) == Some(401) { text(index + 1) } else { None };
+            let Some((speaker, skip_first)) = resolve_speaker(parameters, first_line, actors) else {
+                continue;
+            };
+            let speaker = scan.speaker_mut(&speaker.key, &speaker.name, speaker.actor_id);
+            let mut line_index = index + 1 + usize::from(skip_first);
+            while code(line_index) == Some(401) {
+                let line = text(line_index).map(|t| split_name_box_code(t).map_or(t, |n| n.rest));
+                if let Some(line) = line.filter(|t| !t.trim().is_empty()) {
+                    speaker.lines.push(CharacterLine {
+                        source_file: source_file.to_string(),
+                        json_path: format!("{}[{}].parameters[0]", list_path, line_index),
+                        text: line.to_string(),
+                    });
+                }
+                line_index += 1;
+            }
+        }
+    });
+}
+
+fn map_id_from_file_name(file_name: &str) -> Option<u32> {
+    file_name.strip_prefix("Map")?.strip_suffix(".json")?.parse().ok()
+}
+
+/// Scans `www/data` of an RPG Maker MV project for dialogue in maps, common events and troops.
+pub fn scan_project_dialogue(project_path: &str) -> Result<DialogueScan, CoreError> {
+    let data_path = Path::new(project_path).join("www").join("data");
+    if !data_path.is_dir() {
+        return Err(CoreError::Io(format!("Data directory not found: {:?}", data_path)));
+    }
+    let read = |name: &str| fs::read_to_string(data_path.join(name));
+
+    let mut scan = DialogueScan::default();
+    let actors = match read("Actors.json") {
+        Ok(content) => parse_actors(&content).unwrap_or_else(|e| {
+            scan.errors.push(e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    };
+    let map_names = match read("MapInfos.json") {
+        Ok(content) => parse_map_names(&content).unwrap_or_else(|e| {
+            scan.errors.push(e);
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    };
+
+    let mut files: Vec<_> = WalkDir::new(&data_path)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| {
+            name == "CommonEvents.json" || name == "Troops.json" || map_id_from_file_name(name).is_some()
+        })
+        .collect();
+    files.sort();
+
+    for file_name in files {
+        let source_file = format!("www/data/{}", file_name);
+        let data: Value = match read(&file_name).map_err(|e| e.to_string()).and_then(|c| {
+            serde_json::from_str(&c).map_err(|e| e.to_string())
+        }) {
+            Ok(data) => data,
+            Err(e) => {
+                scan.errors.push(format!("{}: {}", source_file, e));
+                continue;
+            }
+        };
+        if let Some(name) = map_id_from_file_name(&file_name).and_then(|id| map_names.get(&id)) {
+            scan.map_names.insert(source_file.clone(), name.clone());
+        }
+        scan_dialogue(&data, &source_file, &actors, &mut scan);
+    }
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn actors() -> Vec<ActorInfo> {
+        parse_actors(r#"[null,{"id":1,"name":"ハロルド","faceName":"Actor1","faceIndex":0}]"#).unwrap()
+    }
+
+    #[test]
+    fn test_scan_attributes_lines_to_speakers() {
+        let map = json!({
+            "events": [null, {"id": 1, "name": "EV001", "pages": [{"list": [
+                {"code": 101, "parameters": ["Actor1", 0, 0, 2]},
+                {"code": 401, "parameters": ["行くぞ！"]},
+                {"code": 401, "parameters": ["みんな"]},
+                {"code": 101, "parameters": ["", 0, 0, 2, "村人"]},
+                {"code": 401, "parameters": ["ようこそ"]},
+                {"code": 101, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "parameters": ["\\N[1]"]},
+                {"code": 401, "parameters": ["ここだ"]},
+                {"code": 101, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "parameters": ["風が吹いている"]},
+                {"code": 101, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "parameters": ["\\n<ハロルド>急げ"]},
+                {"code": 0, "parameters": []}
+            ]}]}]
+        });
+        let mut scan = DialogueScan::default();
+        scan_dialogue(&map, "www/data/Map001.json", &actors(), &mut scan);
+
+        assert_eq!(scan.speakers.len(), 2, "Narration is not attributed");
+        let harold = &scan.speakers[0];
+        assert_eq!((harold.key.as_str(), harold.actor_id), ("actor:1", Some(1)));
+        let lines: Vec<&str> = harold.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(lines, vec!["行くぞ！", "みんな", "ここだ", "急げ"]);
+        assert_eq!(harold.lines[0].json_path, "events[1].pages[0].list[1].parameters[0]");
+
+        assert_eq!(scan.speakers[1].key, "name:村人");
+    }
+
+    #[test]
+    fn test_common_event_paths_match_extraction() {
+        let common_events = json!([null, {"id": 1, "name": "CE", "list": [
+            {"code": 101, "parameters": ["People1", 3, 0, 2]},
+            {"code": 401, "parameters": ["こんにちは"]}
+        ]}]);
+        let mut scan = DialogueScan::default();
+        scan_dialogue(&common_events, "www/data/CommonEvents.json", &actors(), &mut scan);
+        assert_eq!(scan.speakers[0].key, "face:People1#3");
+        assert_eq!(scan.speakers[0].lines[0].json_path, "[1].list[1].parameters[0]");
+    }
+}
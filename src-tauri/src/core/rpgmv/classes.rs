@@ -55,6 +55,8 @@ pub fn extract_strings(
                     original_text: class_data.name.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].name", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -65,6 +67,8 @@ pub fn extract_strings(
                     original_text: class_data.note.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].note", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -76,6 +80,8 @@ pub fn extract_strings(
                         original_text: learning.note.clone(),
                         source_file: source_file.to_string(),
                         json_path: format!("[{}].learnings[{}].note", index, learning_idx),
+                        dialogue_block_id: None,
+                        speaker: None,
                     });
                 }
             }
@@ -102,7 +108,7 @@ pub fn reconstruct_classes_json(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::translation::WorkingTranslation; // Updated import for tests
+    use crate::models::translation::{TranslationStatus, WorkingTranslation};
     use serde_json::{json, Value};
     // Import TranslatableStringEntry from common for tests if not already globally available in this scope
     // No, `use super::*` brings in `TranslatableStringEntry` from the module's top level imports.
@@ -129,12 +135,16 @@ mod tests {
                 original_text: "Hero".to_string(),
                 source_file: "www/data/Classes.json".to_string(),
                 json_path: "[1].name".to_string(),
+                dialogue_block_id: None,
+                speaker: None,
             },
             SourceStringData {
                 object_id: 1,
                 original_text: "The main protagonist.".to_string(),
                 source_file: "www/data/Classes.json".to_string(),
                 json_path: "[1].note".to_string(),
+                dialogue_block_id: None,
+                speaker: None,
             },
         ];
         let result = extract_strings(json_content, "www/data/Classes.json").unwrap();
@@ -167,16 +177,16 @@ mod tests {
         assert_eq!(result.len(), 4); // Name, Note, Learning Note 1, Learning Note 3
 
         let expected_name = SourceStringData {
-            object_id: 1, original_text: "Mage".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].name".to_string()
+            object_id: 1, original_text: "Mage".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].name".to_string(), dialogue_block_id: None, speaker: None
         };
         let expected_note = SourceStringData {
-            object_id: 1, original_text: "Magic user.".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].note".to_string()
+            object_id: 1, original_text: "Magic user.".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].note".to_string(), dialogue_block_id: None, speaker: None
         };
         let expected_learning1_note = SourceStringData {
-            object_id: 1, original_text: "Learns early".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].learnings[0].note".to_string()
+            object_id: 1, original_text: "Learns early".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].learnings[0].note".to_string(), dialogue_block_id: None, speaker: None
         };
          let expected_learning3_note = SourceStringData {
-            object_id: 1, original_text: "Powerful skill!".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].learnings[2].note".to_string()
+            object_id: 1, original_text: "Powerful skill!".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[1].learnings[2].note".to_string(), dialogue_block_id: None, speaker: None
         };
 
         assert!(result.contains(&expected_name));
@@ -195,10 +205,10 @@ mod tests {
         let result = extract_strings(json_content, "www/data/Classes.json").unwrap();
         assert_eq!(result.len(), 2); // Only Warrior name and note
          let expected_warrior_name = SourceStringData {
-            object_id: 2, original_text: "Warrior".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[2].name".to_string()
+            object_id: 2, original_text: "Warrior".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[2].name".to_string(), dialogue_block_id: None, speaker: None
         };
         let expected_warrior_note = SourceStringData {
-            object_id: 2, original_text: "A strong fighter".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[2].note".to_string()
+            object_id: 2, original_text: "A strong fighter".to_string(), source_file: "www/data/Classes.json".to_string(), json_path: "[2].note".to_string(), dialogue_block_id: None, speaker: None
         };
         assert!(result.contains(&expected_warrior_name));
         assert!(result.contains(&expected_warrior_note));
@@ -243,6 +253,7 @@ mod tests {
                 translated_text: "Héroe".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -252,6 +263,7 @@ mod tests {
                 translated_text: "El protagonista principal.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -261,6 +273,7 @@ mod tests {
                 translated_text: "Nota Inicial 1 Traducida".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -270,6 +283,7 @@ mod tests {
                 translated_text: "Nota Aprendizaje 2 Traducida".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -279,6 +293,7 @@ mod tests {
                 translated_text: "Compañero".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -308,6 +323,7 @@ mod tests {
                 translated_text: "FailName".to_string(),
                 translation_source: "test_source".to_string(),
                 error: Some("AI error".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -317,6 +333,7 @@ mod tests {
                 translated_text: "GoodLearningNote".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -340,6 +357,7 @@ mod tests {
                 translated_text: "Fantasma".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -362,6 +380,7 @@ mod tests {
                 translated_text: "Translated Mystery".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -384,6 +403,7 @@ mod tests {
                 translated_text: "Translated Unknown".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -393,6 +413,7 @@ mod tests {
                 translated_text: "Translated Deep Unknown".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
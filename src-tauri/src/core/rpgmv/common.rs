@@ -1,17 +1,90 @@
+use std::sync::OnceLock;
+use regex::Regex;
 use serde::Deserialize;
 use serde_json::Value;
 use crate::models::translation::WorkingTranslation;
 use crate::error::CoreError;
 use crate::utils::json_utils::update_value_at_path;
+use super::warnings::{warn, WarningKind};
 
 // Represents a single command in an event's list.
 // This is used by CommonEvents.json, Troops.json event pages, and MapXXX.json events.
 #[derive(Deserialize, Debug, Clone)]
 pub struct EventCommand {
     pub code: i32,
+    // Nesting depth; the branches of a command share its indent.
+    #[serde(default)]
+    pub indent: i32,
     pub parameters: Vec<Value>,
 }
 
+/// Codes handled by `extract_translatable_strings_from_event_command_list`: Show Text (101) and its
+/// lines (401), Show Choices (102) and Show Scrolling Text (105).
+pub const EXTRACTED_EVENT_CODES: [i32; 4] = [101, 401, 102, 105];
+
+/// Parameters of event commands that name asset files (images, audio, movies), by command code.
+/// The game loads these by name, so whatever commands extraction covers, they are never
+/// extracted nor overwritten by a translation.
+pub const ASSET_REFERENCE_PARAMETERS: [(i32, &[usize]); 15] = [
+    (101, &[0]),       // Show Text: face image
+    (132, &[0]),       // Change Battle BGM
+    (133, &[0]),       // Change Victory ME
+    (139, &[0]),       // Change Defeat ME
+    (140, &[1]),       // Change Vehicle BGM
+    (231, &[1]),       // Show Picture
+    (241, &[0]),       // Play BGM
+    (245, &[0]),       // Play BGS
+    (249, &[0]),       // Play ME
+    (250, &[0]),       // Play SE
+    (261, &[0]),       // Play Movie
+    (283, &[0, 1]),    // Change Battle Back
+    (284, &[0]),       // Change Parallax
+    (322, &[1, 3, 5]), // Change Actor Images: character, face and battler images
+    (323, &[1]),       // Change Vehicle Image
+];
+
+/// Whether `parameter_path` (e.g. "parameters[0]" or "parameters[0].name") of a command with
+/// `code` is, or is inside, an asset reference.
+pub fn is_asset_reference(code: i32, parameter_path: &str) -> bool {
+    let Some(index) = parameter_path
+        .strip_prefix("parameters[")
+        .and_then(|rest| rest.split(']').next())
+        .and_then(|index| index.parse::<usize>().ok())
+    else {
+        return false;
+    };
+    ASSET_REFERENCE_PARAMETERS.iter().any(|(c, indices)| *c == code && indices.contains(&index))
+}
+
+/// Command index and path within the command of an entry of the command list at `prefix`, e.g.
+/// (3, "parameters[0]") for "[1].list[3].parameters[0]".
+fn split_command_path<'a>(json_path: &'a str, prefix: &str) -> Option<(usize, &'a str)> {
+    let rest = json_path.strip_prefix(prefix)?.trim_start_matches('.').strip_prefix('[')?;
+    let (index, path) = rest.split_once(']')?;
+    Some((index.parse().ok()?, path.trim_start_matches('.')))
+}
+
+/// Choices of the Show Choices command at `choice_idx` that have no "When" branch (402) before
+/// its "End" (404). The editor writes one branch per choice, so a choice without one was padded
+/// into the array (by a plugin, or to push the cancel index past the real choices) and is never
+/// shown. Commands without any branch at all are older exports and report none.
+pub fn placeholder_choice_indices(commands: &[EventCommand], choice_idx: usize) -> Vec<usize> {
+    let Some(command) = commands.get(choice_idx) else { return Vec::new() };
+    let Some(Value::Array(choices)) = command.parameters.first() else { return Vec::new() };
+    let mut branches = Vec::new();
+    for branch in commands[choice_idx + 1..].iter().filter(|c| c.indent == command.indent) {
+        match branch.code {
+            402 => branches.extend(branch.parameters.first().and_then(Value::as_u64)),
+            404 => break,
+            _ => {}
+        }
+    }
+    if branches.is_empty() {
+        return Vec::new();
+    }
+    (0..choices.len()).filter(|index| !branches.contains(&(*index as u64))).collect()
+}
+
 // Trait for RPG Maker MV data objects that can be processed by the generic extractor.
 pub trait RpgMvDataObject: for<'de> serde::Deserialize<'de> + std::fmt::Debug + Sized {
     fn get_id(&self) -> u32;
@@ -53,6 +126,8 @@ pub fn extract_strings_from_json_array<T: RpgMvDataObject>(
                         original_text: field_value_ref.clone(),
                         source_file: source_file.to_string(),
                         json_path: format!("[{}].{}", index, field_key),
+                        dialogue_block_id: None,
+                        speaker: None,
                     });
                 }
             }
@@ -65,6 +140,48 @@ pub fn extract_strings_from_json_array<T: RpgMvDataObject>(
     Ok(entries)
 }
 
+/// Suffix appended to a message line's json_path for the speaker name found in its name box code.
+/// Both entries point at the same string; reconstruction splices each back into its own part.
+pub const NAME_BOX_PATH_SUFFIX: &str = "@nameBox";
+
+/// A message line that starts with a plugin name box code, e.g. YEP_MessageCore's `\n<Name>`
+/// (or its positioned forms `\nl<..>`, `\nc<..>`, `\nr<..>`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameBoxLine<'a> {
+    // Everything up to and including the `<`.
+    pub open: &'a str,
+    pub name: &'a str,
+    // The message text after the closing `>`.
+    pub rest: &'a str,
+}
+
+impl NameBoxLine<'_> {
+    pub fn with_name(&self, name: &str) -> String {
+        format!("{}{}>{}", self.open, name, self.rest)
+    }
+
+    pub fn with_rest(&self, rest: &str) -> String {
+        format!("{}{}>{}", self.open, self.name, rest)
+    }
+}
+
+pub fn split_name_box_code(line: &str) -> Option<NameBoxLine<'_>> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let re = PATTERN.get_or_init(|| Regex::new(r"(?s)^(\s*\\n[lcr]{0,2}<)([^<>\r\n]+)>(.*)$").expect("name box pattern is valid"));
+    let captures = re.captures(line)?;
+    Some(NameBoxLine {
+        open: captures.get(1)?.as_str(),
+        name: captures.get(2)?.as_str(),
+        rest: captures.get(3)?.as_str(),
+    })
+}
+
+/// Id shared by the lines of the message box opened at `command_index` of a command list, e.g.
+/// "www/data/Map001.json:events[1].pages[0].list[3]".
+pub fn dialogue_block_id(source_file: &str, json_path_prefix_for_command_list: &str, command_index: usize) -> String {
+    format!("{}:{}[{}]", source_file, json_path_prefix_for_command_list, command_index)
+}
+
 /// Extracts translatable strings from a list of RPG Maker MV event commands.
 ///
 /// # Arguments
@@ -83,13 +200,28 @@ pub fn extract_translatable_strings_from_event_command_list(
     json_path_prefix_for_command_list: &str,
 ) -> Vec<crate::models::translation::SourceStringData> {
     let mut entries = Vec::new();
+    // Index of the command opening the message box being read: its Show Text (101), or its first
+    // line when the 101 is missing.
+    let mut message_box: Option<usize> = None;
+    // Who speaks in that message box: the name of its 101, or of a name box code in its lines.
+    let mut speaker: Option<String> = None;
 
     for (cmd_idx, command) in commands.iter().enumerate() {
+        match command.code {
+            101 => speaker = None,
+            401 => {}
+            _ => {
+                message_box = None;
+                speaker = None;
+            }
+        }
         match command.code {
             101 => { // Show Text (Face/NameBox)
+                message_box = Some(cmd_idx);
                 if command.parameters.len() > 4 {
                     if let Value::String(speaker_name) = &command.parameters[4] {
                         if !speaker_name.trim().is_empty() {
+                            speaker = Some(speaker_name.trim().to_string());
                             entries.push(crate::models::translation::SourceStringData {
                                 object_id: entry_object_id,
                                 original_text: speaker_name.clone(),
@@ -98,6 +230,8 @@ pub fn extract_translatable_strings_from_event_command_list(
                                     "{}[{}].parameters[4]",
                                     json_path_prefix_for_command_list, cmd_idx
                                 ),
+                                dialogue_block_id: None,
+                                speaker: None,
                             });
                         }
                     }
@@ -105,16 +239,42 @@ pub fn extract_translatable_strings_from_event_command_list(
             }
             401 => { // Show Text (Message line)
                 if !command.parameters.is_empty() {
-                    if let Value::String(text_line) = &command.parameters[0] {
+                    if let Value::String(full_line) = &command.parameters[0] {
+                        // A name box code becomes its own entry; the line entry is the text after it.
+                        let name_box = split_name_box_code(full_line);
+                        if let Some(name_box) = &name_box {
+                            if !name_box.name.trim().is_empty() {
+                                speaker = Some(name_box.name.trim().to_string());
+                                entries.push(crate::models::translation::SourceStringData {
+                                    object_id: entry_object_id,
+                                    original_text: name_box.name.to_string(),
+                                    source_file: source_file.to_string(),
+                                    json_path: format!(
+                                        "{}[{}].parameters[0]{}",
+                                        json_path_prefix_for_command_list, cmd_idx, NAME_BOX_PATH_SUFFIX
+                                    ),
+                                    dialogue_block_id: None,
+                                    speaker: None,
+                                });
+                            }
+                        }
+                        let text_line = name_box.as_ref().map_or(full_line.as_str(), |n| n.rest);
+                        let block_start = *message_box.get_or_insert(cmd_idx);
                         if !text_line.trim().is_empty() {
                             entries.push(crate::models::translation::SourceStringData {
                                 object_id: entry_object_id,
-                                original_text: text_line.clone(),
+                                original_text: text_line.to_string(),
                                 source_file: source_file.to_string(),
                                 json_path: format!(
                                     "{}[{}].parameters[0]",
                                     json_path_prefix_for_command_list, cmd_idx
                                 ),
+                                dialogue_block_id: Some(dialogue_block_id(
+                                    source_file,
+                                    json_path_prefix_for_command_list,
+                                    block_start,
+                                )),
+                                speaker: speaker.clone(),
                             });
                         }
                     }
@@ -123,7 +283,16 @@ pub fn extract_translatable_strings_from_event_command_list(
             102 => { // Show Choices
                 if command.parameters.len() > 0 {
                     if let Value::Array(choices) = &command.parameters[0] {
+                        // Placeholders are left untranslated rather than sent and flagged by QA.
+                        let placeholders = placeholder_choice_indices(commands, cmd_idx);
                         for (choice_idx, choice_val) in choices.iter().enumerate() {
+                            if placeholders.contains(&choice_idx) {
+                                eprintln!(
+                                    "Skipping placeholder choice {} of {}[{}] in {}.",
+                                    choice_idx, json_path_prefix_for_command_list, cmd_idx, source_file
+                                );
+                                continue;
+                            }
                             if let Value::String(choice_text) = choice_val {
                                 if !choice_text.trim().is_empty() {
                                     entries.push(crate::models::translation::SourceStringData {
@@ -134,6 +303,8 @@ pub fn extract_translatable_strings_from_event_command_list(
                                             "{}[{}].parameters[0][{}]",
                                             json_path_prefix_for_command_list, cmd_idx, choice_idx
                                         ),
+                                        dialogue_block_id: None,
+                                        speaker: None,
                                     });
                                 }
                             }
@@ -153,6 +324,8 @@ pub fn extract_translatable_strings_from_event_command_list(
                                     "{}[{}].parameters[0]",
                                     json_path_prefix_for_command_list, cmd_idx
                                 ),
+                                dialogue_block_id: None,
+                                speaker: None,
                             });
                         }
                     }
@@ -166,6 +339,15 @@ pub fn extract_translatable_strings_from_event_command_list(
             _ => {}
         }
     }
+    entries.retain(|entry| {
+        let denied = split_command_path(&entry.json_path, json_path_prefix_for_command_list)
+            .and_then(|(cmd_idx, path)| Some((commands.get(cmd_idx)?.code, path)))
+            .is_some_and(|(code, path)| is_asset_reference(code, path));
+        if denied {
+            eprintln!("Not extracting asset reference {} in {}.", entry.json_path, source_file);
+        }
+        !denied
+    });
     entries
 }
 
@@ -191,10 +373,10 @@ pub fn reconstruct_event_command_list(
         if entry.object_id != parent_object_id {
             // This check might be redundant if translations are pre-filtered,
             // but good for safety.
-            eprintln!(
+            warn(WarningKind::IdMismatch, Some(&entry.json_path), format!(
                 "Warning (reconstruct_event_command_list): Translation entry object_id {} does not match parent_object_id {}. Skipping entry: {:?}.",
                 entry.object_id, parent_object_id, entry
-            );
+            ));
             continue;
         }
 
@@ -207,10 +389,10 @@ pub fn reconstruct_event_command_list(
         let parts: Vec<&str> = path_within_command_list.trim_start_matches('.').splitn(2, '.').collect();
         
         if parts.len() < 1 || !parts[0].starts_with('[') || !parts[0].ends_with(']') {
-            eprintln!(
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                 "Warning (reconstruct_event_command_list): Could not parse command index from path {}. Original path: {}, Prefix: {}. Skipping entry: {:?}.",
                 path_within_command_list, entry.json_path, json_path_prefix_for_command_list, entry
-            );
+            ));
             continue;
         }
 
@@ -218,28 +400,37 @@ pub fn reconstruct_event_command_list(
         let cmd_index: usize = match cmd_index_str.parse() {
             Ok(idx) => idx,
             Err(_) => {
-                eprintln!(
+                warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                     "Warning (reconstruct_event_command_list): Failed to parse command index '{}' from path {}. Skipping entry: {:?}.",
                     cmd_index_str, entry.json_path, entry
-                );
+                ));
                 continue;
             }
         };
 
         if cmd_index >= command_list_value_array.len() {
-            eprintln!(
+            warn(WarningKind::OutOfBounds, Some(&entry.json_path), format!(
                 "Warning (reconstruct_event_command_list): Command index {} out of bounds (list len {}). Path: {}. Skipping entry: {:?}.",
                 cmd_index, command_list_value_array.len(), entry.json_path, entry
-            );
+            ));
             continue;
         }
 
         let path_within_command_params = if parts.len() > 1 { parts[1] } else { "" };
         if path_within_command_params.is_empty() {
-            eprintln!(
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                 "Warning (reconstruct_event_command_list): Path within command parameters is empty for {}. Skipping entry: {:?}.",
                 entry.json_path, entry
-            );
+            ));
+            continue;
+        }
+
+        let code = command_list_value_array[cmd_index].get("code").and_then(Value::as_i64).unwrap_or_default();
+        if is_asset_reference(code as i32, path_within_command_params) {
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
+                "Warning (reconstruct_event_command_list): {} names an asset file of command {} and is never translated. Skipping entry.",
+                entry.json_path, code
+            ));
             continue;
         }
 
@@ -249,15 +440,39 @@ pub fn reconstruct_event_command_list(
             &entry.translated_text
         };
 
+        // Lines with a name box code hold two entries (name and text); keep the code around each.
+        let (path_within_command_params, is_name_box) =
+            match path_within_command_params.strip_suffix(NAME_BOX_PATH_SUFFIX) {
+                Some(path) => (path, true),
+                None => (path_within_command_params, false),
+            };
+        let current_line = command_list_value_array
+            .get(cmd_index)
+            .filter(|command| command.get("code").and_then(Value::as_i64) == Some(401) && path_within_command_params == "parameters[0]")
+            .and_then(|command| command.get("parameters")?.get(0)?.as_str());
+        let spliced_line = match (current_line.and_then(split_name_box_code), is_name_box) {
+            (Some(name_box), true) => Some(name_box.with_name(text_to_insert)),
+            (Some(name_box), false) => Some(name_box.with_rest(text_to_insert)),
+            (None, true) => {
+                warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
+                    "Warning (reconstruct_event_command_list): No name box code found at {}. Skipping entry: {:?}.",
+                    entry.json_path, entry
+                ));
+                continue;
+            }
+            (None, false) => None,
+        };
+        let text_to_insert = spliced_line.as_ref().unwrap_or(text_to_insert);
+
         if let Some(command_value_mut) = command_list_value_array.get_mut(cmd_index) {
             // Now use update_value_at_path on command_value_mut with path_within_command_params
             match update_value_at_path(command_value_mut, path_within_command_params, text_to_insert) {
                 Ok(_) => { /* Successfully updated */ }
                 Err(e) => {
-                    eprintln!(
+                    warn(WarningKind::UpdateFailed, Some(&entry.json_path), format!(
                         "Warning (reconstruct_event_command_list): Failed to update path '{}' within command at index {} (id: {}, original full path: {}): {}. Skipping update for this field.", 
                         path_within_command_params, cmd_index, parent_object_id, entry.json_path, e.to_string()
-                    );
+                    ));
                 }
             }
         } // else: cmd_index out of bounds, already handled above
@@ -291,10 +506,10 @@ pub fn reconstruct_json_generically(
         ) {
             Ok(_) => { /* Successfully updated path */ }
             Err(e) => {
-                eprintln!(
+                warn(WarningKind::UpdateFailed, Some(&trans_entry.json_path), format!(
                     "Failed to update path {} for object_id {} in file {}: {}. Using original text.", 
                     trans_entry.json_path, trans_entry.object_id, trans_entry.source_file, e
-                );
+                ));
             }
         }
     }
@@ -334,10 +549,10 @@ pub fn reconstruct_object_array_by_id(
                     // Extract the field part from entry.json_path (e.g., from "[1].name" get "name")
                     let relative_path = entry.json_path.split_once('.').map_or_else(
                         || {
-                            eprintln!(
+                            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                                 "Warning ({}): Could not parse relative_path from json_path '{}' for id {}. It might be missing a '.' separator. Skipping update.",
                                 file_type_name_for_logging, entry.json_path, target_id
-                            );
+                            ));
                             "" // Return an empty string to skip this update path
                         },
                         |(_index_part, field_part)| field_part
@@ -351,10 +566,10 @@ pub fn reconstruct_object_array_by_id(
                     match update_value_at_path(item_value, relative_path, text_to_insert) {
                         Ok(_) => { /* Successfully updated */ }
                         Err(e) => {
-                            eprintln!(
+                            warn(WarningKind::UpdateFailed, Some(&entry.json_path), format!(
                                 "Warning ({}): Failed to update relative path '{}' (original full path '{}') for id {}: {}. Skipping update.", 
                                 file_type_name_for_logging, relative_path, entry.json_path, target_id, e
-                            );
+                            ));
                         }
                     }
                     break; // Found and processed the object, move to next translation entry
@@ -363,10 +578,10 @@ pub fn reconstruct_object_array_by_id(
         }
 
         if !found_object {
-            eprintln!(
+            warn(WarningKind::IdMismatch, Some(&entry.json_path), format!(
                 "Warning ({}): Object with id {} not found for reconstruction. Path: '{}'. Skipping.", 
                 file_type_name_for_logging, target_id, entry.json_path
-            );
+            ));
         }
     }
 
@@ -389,10 +604,10 @@ pub fn reconstruct_object_array_by_path_index(
         // Extract array index and relative path from entry.json_path (e.g., "[1].name" -> index 1, path "name")
         let parts: Vec<&str> = entry.json_path.splitn(2, '.').collect();
         if parts.len() < 1 || !parts[0].starts_with('[') || !parts[0].ends_with(']') {
-            eprintln!(
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                 "Warning ({}): Invalid json_path format for entry (top level index missing): '{}'. Skipping.", 
                 file_type_name_for_logging, entry.json_path
-            );
+            ));
             continue;
         }
 
@@ -400,27 +615,27 @@ pub fn reconstruct_object_array_by_path_index(
         let item_index: usize = match index_str.parse() {
             Ok(idx) => idx,
             Err(_) => {
-                eprintln!(
+                warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                     "Warning ({}): Failed to parse index from path: '{}'. Skipping.", 
                     file_type_name_for_logging, entry.json_path
-                );
+                ));
                 continue;
             }
         };
 
         if item_index >= json_array.len() || json_array[item_index].is_null() {
-            eprintln!(
+            warn(WarningKind::OutOfBounds, Some(&entry.json_path), format!(
                 "Warning ({}): Index {} out of bounds or null for path '{}'. Skipping.", 
                 file_type_name_for_logging, item_index, entry.json_path
-            );
+            ));
             continue;
         }
         
         let path_within_object = if parts.len() > 1 { parts[1] } else {
-            eprintln!(
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                 "Warning ({}): json_path '{}' lacks field part after index. Skipping.", 
                 file_type_name_for_logging, entry.json_path
-            );
+            ));
             continue;
         };
 
@@ -428,10 +643,10 @@ pub fn reconstruct_object_array_by_path_index(
             // Optional: Verify object_id if applicable and present
             if let Some(id_val) = item_value_mut.get("id").and_then(|id| id.as_u64()) {
                 if id_val != entry.object_id as u64 {
-                    eprintln!(
+                    warn(WarningKind::IdMismatch, Some(&entry.json_path), format!(
                         "Warning ({}): Mismatched object_id for index {}. Expected id {}, translation entry has id {}. Path: '{}'. Skipping.",
                         file_type_name_for_logging, item_index, id_val, entry.object_id, entry.json_path
-                    );
+                    ));
                     continue;
                 }
             }
@@ -447,10 +662,10 @@ pub fn reconstruct_object_array_by_path_index(
             match update_value_at_path(item_value_mut, path_within_object, text_to_insert) {
                 Ok(_) => { /* Successfully updated */ }
                 Err(e) => {
-                    eprintln!(
+                    warn(WarningKind::UpdateFailed, Some(&entry.json_path), format!(
                         "Warning ({}): Failed to update path '{}' (relative: '{}') for index {}: {}. Skipping update.", 
                         file_type_name_for_logging, entry.json_path, path_within_object, item_index, e
-                    );
+                    ));
                 }
             }
         } 
@@ -7,6 +7,7 @@ use crate::core::rpgmv::common::{
     reconstruct_event_command_list
 };
 use crate::error::CoreError;
+use super::warnings::{warn, WarningKind};
 use crate::utils::json_utils::update_value_at_path;
 
 // Represents a single command in a move route list.
@@ -62,6 +63,8 @@ pub fn extract_strings(
                             original_text: common_event.name.clone(),
                             source_file: source_file.to_string(),
                             json_path: format!("[{}].name", event_index),
+                            dialogue_block_id: None,
+                            speaker: None,
                         });
                     }
 
@@ -109,7 +112,7 @@ pub fn reconstruct_common_events_json(
         let parts: Vec<&str> = entry.json_path.splitn(3, '.').collect();
         
         if parts.len() < 2 || !parts[0].starts_with('[') || !parts[0].ends_with(']') {
-            eprintln!("Warning (CommonEvents.json): Invalid json_path format for entry: {:?}. Skipping.", entry);
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Warning (CommonEvents.json): Invalid json_path format for entry: {:?}. Skipping.", entry));
             continue;
         }
 
@@ -117,13 +120,13 @@ pub fn reconstruct_common_events_json(
         let event_index: usize = match index_str.parse() {
             Ok(idx) => idx,
             Err(_) => {
-                eprintln!("Warning (CommonEvents.json): Failed to parse event index from path: {}. Skipping.", entry.json_path);
+                warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Warning (CommonEvents.json): Failed to parse event index from path: {}. Skipping.", entry.json_path));
                 continue;
             }
         };
 
         if event_index >= common_events_json_array.len() || common_events_json_array[event_index].is_null() {
-            eprintln!("Warning (CommonEvents.json): Event index {} out of bounds or null. Skipping entry: {:?}.", event_index, entry);
+            warn(WarningKind::OutOfBounds, Some(&entry.json_path), format!("Warning (CommonEvents.json): Event index {} out of bounds or null. Skipping entry: {:?}.", event_index, entry));
             continue;
         }
         
@@ -132,10 +135,10 @@ pub fn reconstruct_common_events_json(
             .map_or(0, |id| id as u32);
 
         if event_object_id != entry.object_id {
-            eprintln!(
+            warn(WarningKind::IdMismatch, Some(&entry.json_path), format!(
                 "Warning (CommonEvents.json): Mismatched object_id for event_index {}. Expected {}, found in translation {}. Skipping entry: {:?}.",
                 event_index, event_object_id, entry.object_id, entry
-            );
+            ));
             continue;
         }
 
@@ -148,10 +151,10 @@ pub fn reconstruct_common_events_json(
         if parts.len() == 2 && parts[1] == "name" {
             if let Some(event_value_mut) = common_events_json_array.get_mut(event_index) {
                 if let Err(e) = update_value_at_path(event_value_mut, "name", text_to_insert) {
-                    eprintln!(
+                    warn(WarningKind::UpdateFailed, Some(&entry.json_path), format!(
                         "Warning (CommonEvents.json): Failed to update name for event id {}: {}. Skipping update for this field.", 
                         entry.object_id, e.to_string()
-                    );
+                    ));
                 }
             } // else already handled by bounds check
         } else if parts.len() > 2 && parts[1] == "list" {
@@ -161,7 +164,7 @@ pub fn reconstruct_common_events_json(
             // This main loop focuses on dispatching to the helper per common event.
         } else {
             // This case should ideally not be hit if json_paths are generated correctly
-            eprintln!("Warning (CommonEvents.json): Unhandled json_path structure: {} for entry: {:?}", entry.json_path, entry);
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Warning (CommonEvents.json): Unhandled json_path structure: {} for entry: {:?}", entry.json_path, entry));
         }
     }
     
@@ -193,18 +196,18 @@ pub fn reconstruct_common_events_json(
                         match reconstruct_event_command_list(list_array, common_event_id, &relevant_translations, &format!("[{}].list", event_index)) {
                             Ok(_) => { /* Successfully updated list */ }
                             Err(e) => {
-                                eprintln!(
+                                warn(WarningKind::UpdateFailed, Some(&format!("[{}].list", event_index)), format!(
                                     "Error reconstructing command list for common event id {}: {}. List might be partially updated or inconsistent.", 
                                     common_event_id, e
-                                );
+                                ));
                                 // Decide on error handling: continue, or propagate as a partial success/failure
                             }
                         }
                     } else {
-                        eprintln!("Warning (CommonEvents.json): 'list' field for event id {} is not an array. Skipping command reconstruction.", common_event_id);
+                        warn(WarningKind::SkippedPath, Some(&format!("[{}].list", event_index)), format!("Warning (CommonEvents.json): 'list' field for event id {} is not an array. Skipping command reconstruction.", common_event_id));
                     }
                 } else {
-                     eprintln!("Warning (CommonEvents.json): No 'list' field found for event id {}. Skipping command reconstruction.", common_event_id);
+                     warn(WarningKind::SkippedPath, Some(&format!("[{}].list", event_index)), format!("Warning (CommonEvents.json): No 'list' field found for event id {}. Skipping command reconstruction.", common_event_id));
                 }
             } // else: event_index out of bounds, already handled or impossible here
         }
@@ -218,6 +221,8 @@ pub fn reconstruct_common_events_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::rpgmv::common::is_asset_reference;
+    use crate::models::translation::TranslationStatus;
 
     const TEST_COMMON_EVENTS_JSON: &str = r#"[
         null,
@@ -271,6 +276,7 @@ mod tests {
                 translated_text: "Evento de Prueba 1".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -280,6 +286,7 @@ mod tests {
                 translated_text: "NombreDelActor".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -289,6 +296,7 @@ mod tests {
                 translated_text: "Esta es la primera línea de diálogo.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -298,6 +306,7 @@ mod tests {
                 translated_text: "Opción 1".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2, // Event with no name originally
@@ -307,6 +316,7 @@ mod tests {
                 translated_text: "Evento Sin Nombre".to_string(), 
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
              WorkingTranslation {
                 object_id: 2,
@@ -316,6 +326,7 @@ mod tests {
                 translated_text: "Línea de comentario 1".to_string(), 
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -325,6 +336,7 @@ mod tests {
                 translated_text: "Otro diálogo aquí.".to_string(), 
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -361,6 +373,7 @@ mod tests {
                 translated_text: "FailName".to_string(),
                 translation_source: "test_source".to_string(),
                 error: Some("AI error".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -370,6 +383,7 @@ mod tests {
                 translated_text: "Good Dialogue".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -394,6 +408,7 @@ mod tests {
                 translated_text: "Phantom Name".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -416,6 +431,7 @@ mod tests {
                 translated_text: "Mismatched Name".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -438,6 +454,7 @@ mod tests {
                 translated_text: "Translated Invented".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
              WorkingTranslation {
                 object_id: 1,
@@ -447,6 +464,7 @@ mod tests {
                 translated_text: "Translated Deep Invented".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -482,4 +500,111 @@ mod tests {
             other => panic!("Expected JsonParse error, got {:?}", other),
         }
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_extract_skips_placeholder_choices() {
+        // "Pad" has no When branch: it only pushes the cancel index (3) past the real choices.
+        let json = r#"[null, {"id": 1, "name": "", "list": [
+            {"code":102,"indent":0,"parameters":[["はい","いいえ","Pad"],3,0,2,0]},
+            {"code":402,"indent":0,"parameters":[0,"はい"]},
+            {"code":102,"indent":1,"parameters":[["A","B"],1,0,2,0]},
+            {"code":404,"indent":1,"parameters":[]},
+            {"code":402,"indent":0,"parameters":[1,"いいえ"]},
+            {"code":403,"indent":0,"parameters":[6,null]},
+            {"code":404,"indent":0,"parameters":[]},
+            {"code":0,"indent":0,"parameters":[]}
+        ]}]"#;
+        let strings = extract_strings(json, "www/data/CommonEvents.json").unwrap();
+        let paths: Vec<&str> = strings.iter().map(|s| s.json_path.as_str()).collect();
+        assert_eq!(paths, vec![
+            "[1].list[0].parameters[0][0]",
+            "[1].list[0].parameters[0][1]",
+            "[1].list[2].parameters[0][0]",
+            "[1].list[2].parameters[0][1]",
+        ]);
+    }
+
+    #[test]
+    fn test_extract_groups_message_box_lines() {
+        let json = r#"[null, {"id": 1, "name": "", "list": [
+            {"code":101,"indent":0,"parameters":["Actor1",0,0,2,"ハロルド"]},
+            {"code":401,"indent":0,"parameters":["やあ。"]},
+            {"code":401,"indent":0,"parameters":["いい天気だね。"]},
+            {"code":101,"indent":0,"parameters":["",0,0,2,""]},
+            {"code":401,"indent":0,"parameters":["……"]},
+            {"code":230,"indent":0,"parameters":[60]},
+            {"code":401,"indent":0,"parameters":["行こう。"]},
+            {"code":0,"indent":0,"parameters":[]}
+        ]}]"#;
+        let strings = extract_strings(json, "www/data/CommonEvents.json").unwrap();
+        let blocks: Vec<(&str, Option<&str>)> =
+            strings.iter().map(|s| (s.original_text.as_str(), s.dialogue_block_id.as_deref())).collect();
+        assert_eq!(blocks, vec![
+            ("ハロルド", None),
+            ("やあ。", Some("www/data/CommonEvents.json:[1].list[0]")),
+            ("いい天気だね。", Some("www/data/CommonEvents.json:[1].list[0]")),
+            ("……", Some("www/data/CommonEvents.json:[1].list[3]")),
+            ("行こう。", Some("www/data/CommonEvents.json:[1].list[6]")),
+        ]);
+    }
+
+    #[test]
+    fn test_message_lines_carry_their_speaker() {
+        let json = r#"[null, {"id": 1, "name": "", "list": [
+            {"code":101,"indent":0,"parameters":["Actor1",0,0,2,"ハロルド"]},
+            {"code":401,"indent":0,"parameters":["やあ。"]},
+            {"code":401,"indent":0,"parameters":["いい天気だね。"]},
+            {"code":101,"indent":0,"parameters":["",0,0,2,""]},
+            {"code":401,"indent":0,"parameters":["\\n<マーシャ>そうね。"]},
+            {"code":401,"indent":0,"parameters":["行きましょう。"]},
+            {"code":101,"indent":0,"parameters":["",0,0,2,""]},
+            {"code":401,"indent":0,"parameters":["風が吹いた。"]},
+            {"code":0,"indent":0,"parameters":[]}
+        ]}]"#;
+        let strings = extract_strings(json, "www/data/CommonEvents.json").unwrap();
+        let speakers: Vec<(&str, Option<&str>)> =
+            strings.iter().map(|s| (s.original_text.as_str(), s.speaker.as_deref())).collect();
+        assert_eq!(speakers, vec![
+            ("ハロルド", None),
+            ("やあ。", Some("ハロルド")),
+            ("いい天気だね。", Some("ハロルド")),
+            ("マーシャ", None),
+            ("そうね。", Some("マーシャ")),
+            ("行きましょう。", Some("マーシャ")),
+            ("風が吹いた。", None),
+        ]);
+    }
+
+    #[test]
+    fn test_asset_references_are_never_translated() {
+        assert!(is_asset_reference(101, "parameters[0]"));
+        assert!(!is_asset_reference(101, "parameters[4]"));
+        assert!(is_asset_reference(241, "parameters[0].name"));
+        assert!(is_asset_reference(322, "parameters[3]"));
+        assert!(!is_asset_reference(401, "parameters[0]"));
+
+        let json = r#"[null, {"id": 1, "name": "", "list": [
+            {"code":101,"indent":0,"parameters":["ハロルド",0,0,2,"ハロルド"]},
+            {"code":401,"indent":0,"parameters":["やあ。"]},
+            {"code":0,"indent":0,"parameters":[]}
+        ]}]"#;
+        let strings = extract_strings(json, "www/data/CommonEvents.json").unwrap();
+        let paths: Vec<&str> = strings.iter().map(|s| s.json_path.as_str()).collect();
+        assert_eq!(paths, vec!["[1].list[0].parameters[4]", "[1].list[1].parameters[0]"]);
+
+        // A translation of the face image (e.g. from an imported file) is refused.
+        let face = WorkingTranslation {
+            object_id: 1,
+            original_text: "ハロルド".to_string(),
+            translated_text: "Harold".to_string(),
+            source_file: "www/data/CommonEvents.json".to_string(),
+            json_path: "[1].list[0].parameters[0]".to_string(),
+            translation_source: "import".to_string(),
+            error: None,
+            status: TranslationStatus::Edited,
+        };
+        let mut list: Vec<Value> = serde_json::from_str::<Vec<Value>>(json).unwrap()[1]["list"].as_array().unwrap().clone();
+        reconstruct_event_command_list(&mut list, 1, &[&face], "[1].list").unwrap();
+        assert_eq!(list[0]["parameters"][0], "ハロルド");
+    }
+}
@@ -49,6 +49,7 @@ pub fn reconstruct_enemies_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value; // Value is used in tests for assertions
 
     const TEST_ENEMIES_JSON: &str = r#"[
@@ -70,6 +71,7 @@ mod tests {
                 translated_text: "Bat (EN)".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct and fields
                 object_id: 1,
@@ -79,6 +81,7 @@ mod tests {
                 translated_text: "A nocturnal flying mammal.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct and fields
                 object_id: 7,
@@ -88,6 +91,7 @@ mod tests {
                 translated_text: "Sample Enemy 1 (EN)".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation { // Updated struct and fields
                 object_id: 7,
@@ -97,6 +101,7 @@ mod tests {
                 translated_text: "Basic attack is for straitjackets.".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -126,6 +131,7 @@ mod tests {
                 translated_text: "BatFail".to_string(),
                 translation_source: "test_source".to_string(),
                 error: Some("AI error".to_string()), 
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation { // Updated struct and fields
                 object_id: 2, 
@@ -135,6 +141,7 @@ mod tests {
                 translated_text: "A translation for the slime note.".to_string(), 
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -159,6 +166,7 @@ mod tests {
                 translated_text: "Phantom Enemy".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -181,6 +189,7 @@ mod tests {
                 translated_text: "Translated Invented".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -215,6 +224,7 @@ mod tests {
                 translated_text: "こうもり".to_string(),
                 translation_source: "test_source".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::CoreError;
+use super::common::EXTRACTED_EVENT_CODES;
+use super::schema::detect_schema;
+
+// Unextracted codes with fewer text-bearing commands than this are not flagged.
+const MIN_ATTENTION_OCCURRENCES: usize = 3;
+const EXAMPLE_MAX_CHARS: usize = 120;
+
+/// Standard MV/MZ event commands: (code, editor name, whether its text can reach the player).
+/// Scripts and plugin commands count as player text since plugins often pass messages through them.
+const STANDARD_EVENT_CODES: &[(i32, &str, bool)] = &[
+    (0, "End of list", false),
+    (101, "Show Text", true),
+    (401, "Text line", true),
+    (102, "Show Choices", true),
+    (402, "When [Choice]", false),
+    (403, "When Cancel", false),
+    (404, "End Choices", false),
+    (103, "Input Number", false),
+    (104, "Select Item", false),
+    (105, "Show Scrolling Text", true),
+    (405, "Scrolling text line", true),
+    (108, "Comment", false),
+    (408, "Comment line", false),
+    (109, "Skip", false),
+    (111, "Conditional Branch", false),
+    (411, "Else", false),
+    (412, "End Branch", false),
+    (112, "Loop", false),
+    (413, "Repeat Above", false),
+    (113, "Break Loop", false),
+    (115, "Exit Event Processing", false),
+    (117, "Common Event", false),
+    (118, "Label", false),
+    (119, "Jump to Label", false),
+    (121, "Control Switches", false),
+    (122, "Control Variables", false),
+    (123, "Control Self Switch", false),
+    (124, "Control Timer", false),
+    (125, "Change Gold", false),
+    (126, "Change Items", false),
+    (127, "Change Weapons", false),
+    (128, "Change Armors", false),
+    (129, "Change Party Member", false),
+    (132, "Change Battle BGM", false),
+    (133, "Change Victory ME", false),
+    (134, "Change Save Access", false),
+    (135, "Change Menu Access", false),
+    (136, "Change Encounter", false),
+    (137, "Change Formation Access", false),
+    (138, "Change Window Color", false),
+    (139, "Change Defeat ME", false),
+    (140, "Change Vehicle BGM", false),
+    (201, "Transfer Player", false),
+    (202, "Set Vehicle Location", false),
+    (203, "Set Event Location", false),
+    (204, "Scroll Map", false),
+    (205, "Set Movement Route", false),
+    (505, "Movement route step", false),
+    (206, "Get on/off Vehicle", false),
+    (211, "Change Transparency", false),
+    (212, "Show Animation", false),
+    (213, "Show Balloon Icon", false),
+    (214, "Erase Event", false),
+    (216, "Change Player Followers", false),
+    (217, "Gather Followers", false),
+    (221, "Fadeout Screen", false),
+    (222, "Fadein Screen", false),
+    (223, "Tint Screen", false),
+    (224, "Flash Screen", false),
+    (225, "Shake Screen", false),
+    (230, "Wait", false),
+    (231, "Show Picture", false),
+    (232, "Move Picture", false),
+    (233, "Rotate Picture", false),
+    (234, "Tint Picture", false),
+    (235, "Erase Picture", false),
+    (236, "Set Weather Effect", false),
+    (241, "Play BGM", false),
+    (242, "Fadeout BGM", false),
+    (243, "Save BGM", false),
+    (244, "Resume BGM", false),
+    (245, "Play BGS", false),
+    (246, "Fadeout BGS", false),
+    (249, "Play ME", false),
+    (250, "Play SE", false),
+    (251, "Stop SE", false),
+    (261, "Play Movie", false),
+    (281, "Change Map Name Display", false),
+    (282, "Change Tileset", false),
+    (283, "Change Battle Back", false),
+    (284, "Change Parallax", false),
+    (285, "Get Location Info", false),
+    (301, "Battle Processing", false),
+    (601, "If Win", false),
+    (602, "If Escape", false),
+    (603, "If Lose", false),
+    (604, "End Battle Branch", false),
+    (302, "Shop Processing", false),
+    (605, "Shop item", false),
+    (303, "Name Input Processing", false),
+    (311, "Change HP", false),
+    (312, "Change MP", false),
+    (326, "Change TP", false),
+    (313, "Change State", false),
+    (314, "Recover All", false),
+    (315, "Change EXP", false),
+    (316, "Change Level", false),
+    (317, "Change Parameter", false),
+    (318, "Change Skill", false),
+    (319, "Change Equipment", false),
+    (320, "Change Name", true),
+    (321, "Change Class", false),
+    (322, "Change Actor Images", false),
+    (323, "Change Vehicle Image", false),
+    (324, "Change Nickname", true),
+    (325, "Change Profile", true),
+    (331, "Change Enemy HP", false),
+    (332, "Change Enemy MP", false),
+    (342, "Change Enemy TP", false),
+    (333, "Change Enemy State", false),
+    (334, "Enemy Recover All", false),
+    (335, "Enemy Appear", false),
+    (336, "Enemy Transform", false),
+    (337, "Show Battle Animation", false),
+    (339, "Force Action", false),
+    (340, "Abort Battle", false),
+    (351, "Open Menu Screen", false),
+    (352, "Open Save Screen", false),
+    (353, "Game Over", false),
+    (354, "Return to Title Screen", false),
+    (355, "Script", true),
+    (655, "Script line", true),
+    (356, "Plugin Command (MV)", true),
+    (357, "Plugin Command (MZ)", true),
+    (657, "Plugin Command argument (MZ)", true),
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EventCodeStatus {
+    // Handled by the event command extractor.
+    Extracted,
+    // A standard command whose text can reach the player but isn't extracted.
+    NotExtracted,
+    // A standard command without player-facing text (flow control, audio, pictures, comments...).
+    NoText,
+    // Not a standard command: usually added by a plugin.
+    Unknown,
+}
+
+/// First command of a code that carries text, to show users what it looks like.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCodeExample {
+    pub source_file: String,
+    // Path of the command, e.g. "events[3].pages[0].list[12]".
+    pub json_path: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCodeStats {
+    pub code: i32,
+    // Editor name of standard commands.
+    pub name: Option<String>,
+    pub status: EventCodeStatus,
+    pub occurrences: usize,
+    // Commands with at least one non-empty string parameter containing letters.
+    pub text_occurrences: usize,
+    pub files: Vec<String>,
+    pub example: Option<EventCodeExample>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCodeReport {
+    pub total_commands: usize,
+    pub extracted_commands: usize,
+    // Most frequent first.
+    pub codes: Vec<EventCodeStats>,
+    // Unextracted codes that carry text often enough to be worth configuring or requesting
+    // support for, most text-bearing commands first.
+    pub attention: Vec<i32>,
+}
+
+fn first_text(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(text) if text.chars().any(char::is_alphabetic) => Some(text.as_str()),
+        Value::Array(items) => items.iter().find_map(first_text),
+        Value::Object(map) => map.values().find_map(first_text),
+        _ => None,
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(EXAMPLE_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+struct CodeTally {
+    occurrences: usize,
+    text_occurrences: usize,
+    files: BTreeSet<String>,
+    example: Option<EventCodeExample>,
+}
+
+#[derive(Default)]
+struct EventCodeTally {
+    codes: BTreeMap<i32, CodeTally>,
+}
+
+impl EventCodeTally {
+    fn command(&mut self, command: &Value, source_file: &str, json_path: String) -> bool {
+        let Some(code) = command.get("code").and_then(Value::as_i64) else { return false };
+        let tally = self.codes.entry(code as i32).or_insert_with(|| CodeTally {
+            occurrences: 0,
+            text_occurrences: 0,
+            files: BTreeSet::new(),
+            example: None,
+        });
+        tally.occurrences += 1;
+        tally.files.insert(source_file.to_string());
+        if let Some(text) = command.get("parameters").and_then(first_text) {
+            tally.text_occurrences += 1;
+            tally.example.get_or_insert_with(|| EventCodeExample {
+                source_file: source_file.to_string(),
+                json_path,
+                text: truncate(text),
+            });
+        }
+        true
+    }
+
+    /// Tallies every command list (`list` arrays of objects with a `code`) below `value`, which
+    /// covers common events, troop pages, map event pages and plugin data laid out the same way.
+    fn walk(&mut self, value: &Value, source_file: &str, path: &str) {
+        match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.walk(item, source_file, &format!("{}[{}]", path, index));
+                }
+            }
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    if let (true, Some(commands)) = (key == "list", child.as_array()) {
+                        let mut is_command_list = false;
+                        for (index, command) in commands.iter().enumerate() {
+                            is_command_list |= self.command(command, source_file, format!("{}[{}]", child_path, index));
+                        }
+                        if is_command_list {
+                            continue;
+                        }
+                    }
+                    self.walk(child, source_file, &child_path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn into_report(self) -> EventCodeReport {
+        let mut report = EventCodeReport::default();
+        for (code, tally) in self.codes {
+            let standard = STANDARD_EVENT_CODES.iter().find(|(standard_code, _, _)| *standard_code == code);
+            let status = match standard {
+                _ if EXTRACTED_EVENT_CODES.contains(&code) => EventCodeStatus::Extracted,
+                Some((_, _, true)) => EventCodeStatus::NotExtracted,
+                Some((_, _, false)) => EventCodeStatus::NoText,
+                None => EventCodeStatus::Unknown,
+            };
+            report.total_commands += tally.occurrences;
+            if status == EventCodeStatus::Extracted {
+                report.extracted_commands += tally.occurrences;
+            }
+            report.codes.push(EventCodeStats {
+                code,
+                name: standard.map(|(_, name, _)| name.to_string()),
+                status,
+                occurrences: tally.occurrences,
+                text_occurrences: tally.text_occurrences,
+                files: tally.files.into_iter().collect(),
+                example: tally.example,
+            });
+        }
+        report.codes.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then(a.code.cmp(&b.code)));
+
+        let mut attention: Vec<&EventCodeStats> = report
+            .codes
+            .iter()
+            .filter(|stats| matches!(stats.status, EventCodeStatus::NotExtracted | EventCodeStatus::Unknown))
+            .filter(|stats| stats.text_occurrences >= MIN_ATTENTION_OCCURRENCES)
+            .collect();
+        attention.sort_by(|a, b| b.text_occurrences.cmp(&a.text_occurrences).then(a.code.cmp(&b.code)));
+        report.attention = attention.into_iter().map(|stats| stats.code).collect();
+        report
+    }
+}
+
+/// Histogram of the event command codes in the project's common events, troops and maps, with
+/// which of them extraction handles. Files that fail to parse are skipped (the extractor reports those).
+pub fn analyze_event_codes(project_path: &str) -> Result<EventCodeReport, CoreError> {
+    let schema = detect_schema(project_path)
+        .ok_or_else(|| CoreError::Custom(format!("No RPG Maker MV/MZ data folder found in {}", project_path)))?;
+    let data_path = Path::new(project_path).join(&schema.data_dir);
+    let mut file_names: Vec<String> = fs::read_dir(&data_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", data_path, e)))?
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| {
+            name == "CommonEvents.json" || name == "Troops.json" || (name.starts_with("Map") && name.ends_with(".json"))
+        })
+        .collect();
+    file_names.sort();
+
+    let mut tally = EventCodeTally::default();
+    for file_name in file_names {
+        let content = fs::read_to_string(data_path.join(&file_name))
+            .map_err(|e| CoreError::Io(format!("Failed to read {}: {}", file_name, e)))?;
+        let Ok(data) = serde_json::from_str::<Value>(content.trim_start_matches('\u{feff}')) else { continue };
+        tally.walk(&data, &format!("{}/{}", schema.data_dir, file_name), "");
+    }
+    Ok(tally.into_report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_analyze_event_codes_flags_unextracted_text() {
+        let dir = tempdir().unwrap();
+        let data = dir.path().join("www/data");
+        fs::create_dir_all(&data).unwrap();
+        fs::write(
+            data.join("CommonEvents.json"),
+            r#"[null,{"id":1,"list":[
+                {"code":101,"indent":0,"parameters":["",0,0,2]},
+                {"code":401,"indent":0,"parameters":["こんにちは"]},
+                {"code":105,"indent":0,"parameters":[2,false]},
+                {"code":405,"indent":0,"parameters":["遠い昔"]},
+                {"code":405,"indent":0,"parameters":["ある国で"]},
+                {"code":405,"indent":0,"parameters":["……"]},
+                {"code":0,"indent":0,"parameters":[]}]}]"#,
+        )
+        .unwrap();
+        fs::write(
+            data.join("Map001.json"),
+            r#"{"events":[null,{"id":1,"pages":[{"list":[
+                {"code":405,"indent":0,"parameters":["終わり"]},
+                {"code":999,"indent":0,"parameters":[{"text":"宝箱を開けた"}]},
+                {"code":999,"indent":0,"parameters":[{"text":"空っぽだ"}]},
+                {"code":999,"indent":0,"parameters":[{"text":"鍵がかかっている"}]},
+                {"code":250,"indent":0,"parameters":[{"name":"Chest","volume":90}]},
+                {"code":0,"indent":0,"parameters":[]}]}]}]}"#,
+        )
+        .unwrap();
+
+        let report = analyze_event_codes(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!((report.total_commands, report.extracted_commands), (13, 3));
+        assert_eq!(report.attention, vec![405, 999]);
+
+        let code = |code: i32| report.codes.iter().find(|stats| stats.code == code).unwrap();
+        assert_eq!(code(405).status, EventCodeStatus::NotExtracted);
+        assert_eq!((code(405).occurrences, code(405).text_occurrences), (4, 3));
+        assert_eq!(code(405).files, vec!["www/data/CommonEvents.json", "www/data/Map001.json"]);
+        assert_eq!(code(999).status, EventCodeStatus::Unknown);
+        let example = code(999).example.as_ref().unwrap();
+        assert_eq!((example.json_path.as_str(), example.text.as_str()), ("events[1].pages[0].list[1]", "宝箱を開けた"));
+        assert_eq!(code(250).status, EventCodeStatus::NoText);
+        assert_eq!(code(401).status, EventCodeStatus::Extracted);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::core::config::FileGlob;
+use crate::error::CoreError;
+use crate::models::extraction::{ExtractionFilter, FileKind};
+use crate::models::translation::SourceStringData;
+
+const DATABASE_FILES: [&str; 8] = [
+    "Actors.json", "Classes.json", "Skills.json", "Items.json", "Weapons.json", "Armors.json", "Enemies.json",
+    "States.json",
+];
+
+/// Kind of a project file from its path relative to the project root.
+pub fn file_kind(relative_path: &str) -> FileKind {
+    let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+    match file_name {
+        "MapInfos.json" => FileKind::MapInfos,
+        "CommonEvents.json" => FileKind::CommonEvents,
+        "Troops.json" => FileKind::Troops,
+        "System.json" => FileKind::System,
+        _ if DATABASE_FILES.contains(&file_name) => FileKind::Database,
+        _ if file_name.starts_with("Map") && file_name.ends_with(".json") => FileKind::Maps,
+        _ if file_name.ends_with(".js") => FileKind::Plugins,
+        _ => FileKind::Other,
+    }
+}
+
+/// Object field an entry comes from: the last key of its path, e.g. "note" for
+/// `[3].note@noteTag[0]` or "parameters" for an event command.
+pub fn entry_field(json_path: &str) -> &str {
+    let path = json_path.split('@').next().unwrap_or(json_path);
+    let key = path.rsplit('.').next().unwrap_or(path);
+    key.split('[').next().unwrap_or(key)
+}
+
+/// An `ExtractionFilter` with its globs compiled.
+pub struct CompiledExtractionFilter<'a> {
+    filter: &'a ExtractionFilter,
+    include_files: Vec<FileGlob>,
+    exclude_files: Vec<FileGlob>,
+}
+
+fn compile(globs: &[String]) -> Result<Vec<FileGlob>, CoreError> {
+    globs.iter().filter(|glob| !glob.trim().is_empty()).map(|glob| FileGlob::new(glob)).collect()
+}
+
+impl<'a> CompiledExtractionFilter<'a> {
+    pub fn new(filter: &'a ExtractionFilter) -> Result<Self, CoreError> {
+        Ok(CompiledExtractionFilter {
+            filter,
+            include_files: compile(&filter.include_files)?,
+            exclude_files: compile(&filter.exclude_files)?,
+        })
+    }
+
+    pub fn allows_file(&self, relative_path: &str) -> bool {
+        let kind = file_kind(relative_path);
+        (self.include_files.is_empty() || self.include_files.iter().any(|glob| glob.matches(relative_path)))
+            && !self.exclude_files.iter().any(|glob| glob.matches(relative_path))
+            && (self.filter.include_kinds.is_empty() || self.filter.include_kinds.contains(&kind))
+            && !self.filter.exclude_kinds.contains(&kind)
+    }
+
+    pub fn allows_entry(&self, entry: &SourceStringData) -> bool {
+        let field = entry_field(&entry.json_path);
+        !self.filter.exclude_fields.iter().any(|excluded| excluded.trim() == field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(source_file: &str, json_path: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: "テキスト".to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_file_kinds_and_fields() {
+        assert_eq!(file_kind("www/data/Map012.json"), FileKind::Maps);
+        assert_eq!(file_kind("www/data/MapInfos.json"), FileKind::MapInfos);
+        assert_eq!(file_kind("data/Armors.json"), FileKind::Database);
+        assert_eq!(file_kind("www/js/plugins.js"), FileKind::Plugins);
+        assert_eq!(file_kind("www/credits.txt"), FileKind::Other);
+        assert_eq!(entry_field("[3].note@noteTag[0]"), "note");
+        assert_eq!(entry_field("[1].learnings[0].note"), "note");
+        assert_eq!(entry_field("events[1].pages[0].list[3].parameters[0]"), "parameters");
+        assert_eq!(entry_field("gameTitle"), "gameTitle");
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter = ExtractionFilter {
+            include_kinds: vec![FileKind::Maps, FileKind::Database],
+            exclude_files: vec!["Map9??.json".to_string()],
+            exclude_fields: vec!["note".to_string()],
+            ..Default::default()
+        };
+        let compiled = CompiledExtractionFilter::new(&filter).unwrap();
+        assert!(compiled.allows_file("www/data/Map001.json"));
+        assert!(compiled.allows_file("www/data/Items.json"));
+        assert!(!compiled.allows_file("www/data/Map901.json"));
+        assert!(!compiled.allows_file("www/data/System.json"));
+        assert!(compiled.allows_entry(&entry("www/data/Items.json", "[1].name")));
+        assert!(!compiled.allows_entry(&entry("www/data/Items.json", "[1].note")));
+
+        let everything = ExtractionFilter::default();
+        let compiled = CompiledExtractionFilter::new(&everything).unwrap();
+        assert!(compiled.allows_file("www/js/plugins.js"));
+    }
+}
@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::Value;
+use crate::core::session::load_session;
+use crate::error::CoreError;
+use super::project::extract_strings_from_file_content;
+
+// Longer snippets (e.g. the whole System.json for `armorTypes[1]`) are cut.
+const SNIPPET_MAX_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Splits an entry path such as `events[1].pages[0].list[2].parameters[0]` or `[3].name`.
+/// Virtual suffixes (`@nameBox`) address part of a string and are dropped.
+fn parse_json_path(json_path: &str) -> Option<Vec<PathSegment>> {
+    let path = json_path.split('@').next().unwrap_or_default();
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut rest) = match part.find('[') {
+            Some(start) => (&part[..start], &part[start..]),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        }
+        while let Some(stripped) = rest.strip_prefix('[') {
+            let end = stripped.find(']')?;
+            segments.push(PathSegment::Index(stripped[..end].parse().ok()?));
+            rest = &stripped[end + 1..];
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(segments)
+}
+
+/// Values from the root down to the one at `segments` (or as far as the path resolves).
+fn resolve<'a>(root: &'a Value, segments: &[PathSegment]) -> Vec<&'a Value> {
+    let mut chain = vec![root];
+    for segment in segments {
+        let current = chain[chain.len() - 1];
+        let next = match segment {
+            PathSegment::Key(key) => current.get(key),
+            PathSegment::Index(index) => current.get(index),
+        };
+        match next {
+            Some(value) => chain.push(value),
+            None => break,
+        }
+    }
+    chain
+}
+
+fn truncate(text: String) -> String {
+    match text.char_indices().nth(SNIPPET_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Pretty-printed context around the value: the closest enclosing object (an item, an event
+/// command), or the direct parent when that object would be the whole file.
+fn snippet(chain: &[&Value]) -> Option<String> {
+    let parents = &chain[..chain.len().saturating_sub(1)];
+    let context = parents
+        .iter()
+        .skip(1)
+        .rev()
+        .find(|v| v.is_object())
+        .or_else(|| parents.last())?;
+    serde_json::to_string_pretty(context).ok().map(truncate)
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PathInspection {
+    pub source_file: String,
+    pub json_path: String,
+    // Whether the path resolves in the current project file.
+    pub found: bool,
+    pub value: Option<Value>,
+    // Text the extractor produces for this path; differs from `value` for name box entries.
+    pub original_text: Option<String>,
+    // From the saved translation session.
+    pub translated_text: Option<String>,
+    pub translation_source: Option<String>,
+    pub translation_error: Option<String>,
+    pub snippet: Option<String>,
+}
+
+pub fn inspect_content(relative_file_path: &str, content: &str, json_path: &str) -> Result<PathInspection, CoreError> {
+    let root: Value = serde_json::from_str(content)
+        .map_err(|e| CoreError::JsonParse(format!("Failed to parse {}: {}", relative_file_path, e)))?;
+    let segments = parse_json_path(json_path)
+        .ok_or_else(|| CoreError::Custom(format!("Invalid JSON path: {}", json_path)))?;
+    let chain = resolve(&root, &segments);
+    let found = chain.len() == segments.len() + 1;
+
+    let file_name = Path::new(relative_file_path).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let original_text = match extract_strings_from_file_content(file_name, content, relative_file_path) {
+        Some(Ok(entries)) => entries.into_iter().find(|e| e.json_path == json_path).map(|e| e.original_text),
+        _ => None,
+    };
+
+    Ok(PathInspection {
+        source_file: relative_file_path.to_string(),
+        json_path: json_path.to_string(),
+        found,
+        value: if found { chain.last().map(|v| (*v).clone()) } else { None },
+        original_text,
+        snippet: if found { snippet(&chain) } else { None },
+        ..Default::default()
+    })
+}
+
+/// Looks up what is currently stored at `json_path` in one of the project's data files,
+/// and the translation saved for it in the session, if any.
+pub fn inspect_path(project_path: &str, relative_file_path: &str, json_path: &str) -> Result<PathInspection, CoreError> {
+    let file_path = Path::new(project_path).join(relative_file_path);
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", file_path, e)))?;
+    let mut inspection = inspect_content(relative_file_path, &content, json_path)?;
+
+    let session = load_session(project_path)?;
+    if let Some(entry) = session.iter().flat_map(|s| &s.translations).find(|t| {
+        t.source_file == relative_file_path && t.json_path == json_path
+    }) {
+        inspection.translated_text = Some(entry.translated_text.clone()).filter(|t| !t.is_empty());
+        inspection.translation_source = Some(entry.translation_source.clone());
+        inspection.translation_error = entry.error.clone();
+    }
+    Ok(inspection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_json_path() {
+        use PathSegment::{Index, Key};
+        assert_eq!(parse_json_path("[3].name"), Some(vec![Index(3), Key("name".to_string())]));
+        assert_eq!(
+            parse_json_path("events[1].pages[0].list[2].parameters[0]@nameBox"),
+            Some(vec![
+                Key("events".to_string()),
+                Index(1),
+                Key("pages".to_string()),
+                Index(0),
+                Key("list".to_string()),
+                Index(2),
+                Key("parameters".to_string()),
+                Index(0),
+            ])
+        );
+        assert_eq!(parse_json_path("list[2].parameters[0].[1]").map(|s| s.len()), Some(5));
+        assert_eq!(parse_json_path("terms.basic[x]"), None);
+    }
+
+    #[test]
+    fn test_inspect_map_command() {
+        let map = json!({
+            "data": [],
+            "events": [null, {"id": 1, "name": "EV001", "pages": [{"list": [
+                {"code": 101, "indent": 0, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "indent": 0, "parameters": ["\\n<ハロルド>おはよう"]},
+                {"code": 0, "indent": 0, "parameters": []}
+            ]}]}]
+        })
+        .to_string();
+
+        let inspection = inspect_content("www/data/Map001.json", &map, "events[1].pages[0].list[1].parameters[0]@nameBox").unwrap();
+        assert!(inspection.found);
+        assert_eq!(inspection.value, Some(json!("\\n<ハロルド>おはよう")));
+        assert_eq!(inspection.original_text.as_deref(), Some("ハロルド"));
+        let snippet = inspection.snippet.unwrap();
+        assert!(snippet.contains("\"code\": 401") && !snippet.contains("EV001"));
+
+        let missing = inspect_content("www/data/Map001.json", &map, "events[1].pages[0].list[7].parameters[0]").unwrap();
+        assert!(!missing.found && missing.value.is_none() && missing.original_text.is_none());
+    }
+}
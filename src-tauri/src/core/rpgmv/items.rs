@@ -51,6 +51,7 @@ pub fn reconstruct_items_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value; 
 
     const TEST_ITEMS_JSON: &str = r#"[
@@ -75,6 +76,7 @@ mod tests {
                 translated_text: "Potion (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -84,6 +86,7 @@ mod tests {
                 translated_text: "Heals a small amount of HP.".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
              // Translate Magic Water (ID 2) name, leave note empty as it was
             WorkingTranslation {
@@ -94,6 +97,7 @@ mod tests {
                 translated_text: "Magic Water (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             // Translate Item 5 name and note
             WorkingTranslation {
@@ -104,6 +108,7 @@ mod tests {
                 translated_text: "Item Placeholder (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 5,
@@ -113,6 +118,7 @@ mod tests {
                 translated_text: "<Extended Desc: (EN)>".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         
@@ -158,6 +164,7 @@ mod tests {
                 translated_text: "NameFail".to_string(), // This would be some placeholder from translation attempt
                 translation_source: "test".to_string(),
                 error: Some("AI translation failed".to_string()), 
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 1, // Potion
@@ -167,6 +174,7 @@ mod tests {
                 translated_text: "Translated Desc".to_string(),
                 translation_source: "test".to_string(),
                 error: None, // Description translation is successful
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 6, // Jail Key
@@ -176,6 +184,7 @@ mod tests {
                 translated_text: "NoteFail".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("Another AI error".to_string()),
+                status: TranslationStatus::Untranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -207,6 +216,7 @@ mod tests {
                 translated_text: "Ghost Item".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -234,6 +244,7 @@ mod tests {
                 translated_text: "Translated Invented Field".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -278,6 +289,7 @@ mod tests {
                 translated_text: "ポーション".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde_json::Value;
+use crate::error::CoreError;
+use super::locale_output::validate_locale;
+use super::plugins::register_plugin;
+use super::project::ReconstructedProject;
+use super::schema::detect_schema;
+
+/// Name of the generated plugin that swaps in the strings of the chosen language pack.
+pub const LANGUAGE_PACK_PLUGIN_NAME: &str = "TranslateAI_LanguagePack";
+
+/// Folder of the packs, next to the data folder (`www/languages/en.json`).
+pub const LANGUAGE_PACK_DIR_NAME: &str = "languages";
+
+/// Data file name as the game requests it ("Map001.json") -> path in the file (as extracted,
+/// e.g. `events[1].pages[0].list[2].parameters[0]`) -> value that replaces the original one.
+pub type LanguagePack = BTreeMap<String, BTreeMap<String, Value>>;
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) }
+}
+
+/// Records where `translated` differs from `original`. Strings are recorded one by one; an array
+/// whose length changed (a message re-wrapped into more lines) or a value whose type changed is
+/// recorded whole.
+fn changed_values(original: &Value, translated: &Value, path: &str, changes: &mut BTreeMap<String, Value>) {
+    match (original, translated) {
+        (Value::Object(original), Value::Object(translated)) => {
+            for (key, value) in translated {
+                match original.get(key) {
+                    Some(original) => changed_values(original, value, &child_path(path, key), changes),
+                    None => {
+                        changes.insert(child_path(path, key), value.clone());
+                    }
+                }
+            }
+        }
+        (Value::Array(original), Value::Array(translated)) if original.len() == translated.len() => {
+            for (i, (original, value)) in original.iter().zip(translated).enumerate() {
+                changed_values(original, value, &format!("{}[{}]", path, i), changes);
+            }
+        }
+        (original, translated) if original != translated => {
+            changes.insert(path.to_string(), translated.clone());
+        }
+        _ => {}
+    }
+}
+
+/// The language pack of a reconstructed project: every value its data files change, compared
+/// with the project's files. Files outside the data folder can't be swapped at runtime; they are
+/// reported in the returned errors.
+pub fn build_language_pack(
+    project_path: &str,
+    reconstructed: &ReconstructedProject,
+) -> Result<(LanguagePack, Vec<String>), CoreError> {
+    let data_dir = detect_schema(project_path).map_or("www/data".to_string(), |s| s.data_dir);
+    let mut pack = LanguagePack::new();
+    let mut errors = Vec::new();
+    for (relative_path, content) in &reconstructed.files {
+        let file_name = match relative_path.strip_prefix(&format!("{}/", data_dir)) {
+            Some(file_name) if file_name.ends_with(".json") && !file_name.contains('/') => file_name,
+            _ => {
+                errors.push(format!("Left out of the language pack (not a data file): {}", relative_path));
+                continue;
+            }
+        };
+        let original_path = Path::new(project_path).join(relative_path);
+        let original: Value = serde_json::from_str(&fs::read_to_string(&original_path)?)
+            .map_err(|e| CoreError::JsonParse(format!("Failed to parse {:?}: {}", original_path, e)))?;
+        let translated: Value = serde_json::from_str(content)
+            .map_err(|e| CoreError::JsonParse(format!("Failed to parse the translated {}: {}", relative_path, e)))?;
+        let mut changes = BTreeMap::new();
+        changed_values(&original, &translated, "", &mut changes);
+        if !changes.is_empty() {
+            pack.insert(file_name.to_string(), changes);
+        }
+    }
+    for relative_path in reconstructed.binary_files.keys() {
+        errors.push(format!("Left out of the language pack (not a data file): {}", relative_path));
+    }
+    Ok((pack, errors))
+}
+
+/// The plugin: adds a Language option (the original language, then `languages`) and applies the
+/// chosen pack to every data file as it is loaded. The database is loaded at boot, so a change
+/// fully applies after a restart; maps use it from the next one loaded.
+pub fn language_pack_plugin(languages: &[String]) -> String {
+    let languages_literal = serde_json::to_string(languages).unwrap_or_else(|_| "[]".to_string());
+    format!(
+        r#"//=============================================================================
+// {name}.js - generated by Translate-AI
+//=============================================================================
+/*:
+ * @target MZ
+ * @plugindesc Swaps in the strings of the chosen language pack at runtime.
+ * @help Language packs sit in the "{dir}" folder next to the data folder. The
+ * language is chosen in the Options menu; the database uses it after a restart.
+ */
+(function() {{
+    var languages = {languages_literal};
+    var choices = ["original"].concat(languages);
+    var symbol = "translateAiLanguage";
+    var packs = {{}};
+    var sources = {{}};
+
+    function loadPack(language) {{
+        if (!(language in packs)) {{
+            packs[language] = {{}};
+            var xhr = new XMLHttpRequest();
+            xhr.open("GET", "{dir}/" + language + ".json", false);
+            xhr.overrideMimeType("application/json");
+            try {{
+                xhr.send();
+                if (xhr.status < 400) {{
+                    packs[language] = JSON.parse(xhr.responseText);
+                }}
+            }} catch (e) {{
+                console.error("{name}: failed to load the " + language + " pack", e);
+            }}
+        }}
+        return packs[language];
+    }}
+
+    function setAt(object, path, value) {{
+        var segments = path.replace(/\[(\d+)\]/g, ".$1").split(".").filter(function(s) {{ return s !== ""; }});
+        var parent = object;
+        for (var i = 0; i < segments.length - 1; i++) {{
+            parent = parent[segments[i]];
+            if (parent === null || typeof parent !== "object") {{
+                return;
+            }}
+        }}
+        parent[segments[segments.length - 1]] = value;
+    }}
+
+    ConfigManager[symbol] = languages.length > 0 ? languages[0] : "original";
+    var _ConfigManager_makeData = ConfigManager.makeData;
+    ConfigManager.makeData = function() {{
+        var config = _ConfigManager_makeData.call(this);
+        config[symbol] = this[symbol];
+        return config;
+    }};
+    var _ConfigManager_applyData = ConfigManager.applyData;
+    ConfigManager.applyData = function(config) {{
+        _ConfigManager_applyData.call(this, config);
+        if (choices.indexOf(config[symbol]) >= 0) {{
+            this[symbol] = config[symbol];
+        }}
+    }};
+
+    var _DataManager_loadDataFile = DataManager.loadDataFile;
+    DataManager.loadDataFile = function(name, src) {{
+        sources[name] = src;
+        _DataManager_loadDataFile.call(this, name, src);
+    }};
+    var _DataManager_onLoad = DataManager.onLoad;
+    DataManager.onLoad = function(object) {{
+        var language = ConfigManager[symbol];
+        if (language !== "original") {{
+            for (var name in sources) {{
+                var changes = window[name] === object ? loadPack(language)[sources[name]] : null;
+                for (var path in changes || {{}}) {{
+                    setAt(object, path, changes[path]);
+                }}
+            }}
+        }}
+        _DataManager_onLoad.call(this, object);
+    }};
+
+    var _Window_Options_addGeneralOptions = Window_Options.prototype.addGeneralOptions;
+    Window_Options.prototype.addGeneralOptions = function() {{
+        _Window_Options_addGeneralOptions.call(this);
+        this.addCommand("Language", symbol);
+    }};
+    var _Window_Options_statusText = Window_Options.prototype.statusText;
+    Window_Options.prototype.statusText = function(index) {{
+        if (this.commandSymbol(index) === symbol) {{
+            var language = this.getConfigValue(symbol);
+            return language === "original" ? "Original" : language;
+        }}
+        return _Window_Options_statusText.call(this, index);
+    }};
+    function cycle(window, step) {{
+        var index = choices.indexOf(window.getConfigValue(symbol));
+        window.changeValue(symbol, choices[(index + step + choices.length) % choices.length]);
+    }}
+    var _Window_Options_processOk = Window_Options.prototype.processOk;
+    Window_Options.prototype.processOk = function() {{
+        if (this.commandSymbol(this.index()) === symbol) {{
+            cycle(this, 1);
+        }} else {{
+            _Window_Options_processOk.call(this);
+        }}
+    }};
+    var _Window_Options_cursorRight = Window_Options.prototype.cursorRight;
+    Window_Options.prototype.cursorRight = function(wrap) {{
+        if (this.commandSymbol(this.index()) === symbol) {{
+            cycle(this, 1);
+        }} else {{
+            _Window_Options_cursorRight.call(this, wrap);
+        }}
+    }};
+    var _Window_Options_cursorLeft = Window_Options.prototype.cursorLeft;
+    Window_Options.prototype.cursorLeft = function(wrap) {{
+        if (this.commandSymbol(this.index()) === symbol) {{
+            cycle(this, -1);
+        }} else {{
+            _Window_Options_cursorLeft.call(this, wrap);
+        }}
+    }};
+}})();
+"#,
+        name = LANGUAGE_PACK_PLUGIN_NAME,
+        dir = LANGUAGE_PACK_DIR_NAME,
+        languages_literal = languages_literal,
+    )
+}
+
+/// Output of the language pack mode: one pack per language (`www/languages/<language>.json`),
+/// plus the plugin, registered in plugins.js. The project's data files are left as they are.
+pub fn language_pack_output(project_path: &str, packs: &[(String, LanguagePack)]) -> Result<ReconstructedProject, CoreError> {
+    let data_dir = detect_schema(project_path).map_or("www/data".to_string(), |s| s.data_dir);
+    let base_dir = Path::new(&data_dir).parent().unwrap_or(Path::new("")).to_str().unwrap_or_default().replace('\\', "/");
+    let in_base = |path: &str| if base_dir.is_empty() { path.to_string() } else { format!("{}/{}", base_dir, path) };
+
+    let mut output = ReconstructedProject::default();
+    let mut languages = Vec::new();
+    for (language, pack) in packs {
+        validate_locale(language)?;
+        let json = serde_json::to_string(pack)
+            .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize the {} language pack: {}", language, e)))?;
+        output.files.insert(in_base(&format!("{}/{}.json", LANGUAGE_PACK_DIR_NAME, language)), json);
+        languages.push(language.clone());
+    }
+
+    let plugins_relative_path = in_base("js/plugins.js");
+    let plugins_path = Path::new(project_path).join(&plugins_relative_path);
+    let plugins_content = fs::read_to_string(&plugins_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?} to register the language pack plugin: {}", plugins_path, e)))?;
+    let description = "Swaps in the strings of the chosen language pack.";
+    output.files.insert(plugins_relative_path, register_plugin(&plugins_content, LANGUAGE_PACK_PLUGIN_NAME, description)?);
+    output.files.insert(in_base(&format!("js/plugins/{}.js", LANGUAGE_PACK_PLUGIN_NAME)), language_pack_plugin(&languages));
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_language_pack_holds_changed_values() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("www/data")).unwrap();
+        fs::create_dir_all(root.join("www/js")).unwrap();
+        fs::write(root.join("www/js/plugins.js"), "var $plugins =\n[\n];\n").unwrap();
+        let actors = json!([null, {"name": "ハロルド", "nickname": "", "traits": []}]);
+        fs::write(root.join("www/data/Actors.json"), actors.to_string()).unwrap();
+        let map = json!({"events": [null, {"pages": [{"list": [{"code": 401, "parameters": ["長い台詞"]}]}]}]});
+        fs::write(root.join("www/data/Map001.json"), map.to_string()).unwrap();
+        let project_path = root.to_str().unwrap();
+
+        let mut reconstructed = ReconstructedProject::default();
+        let actors = json!([null, {"name": "Harold", "nickname": "", "traits": []}]);
+        reconstructed.files.insert("www/data/Actors.json".to_string(), actors.to_string());
+        let wrapped = json!([{"code": 401, "parameters": ["A long"]}, {"code": 401, "parameters": ["line"]}]);
+        let map = json!({"events": [null, {"pages": [{"list": wrapped}]}]});
+        reconstructed.files.insert("www/data/Map001.json".to_string(), map.to_string());
+        reconstructed.files.insert("www/credits.txt".to_string(), "Thanks".to_string());
+        let (pack, errors) = build_language_pack(project_path, &reconstructed).unwrap();
+
+        assert_eq!(pack["Actors.json"], BTreeMap::from([("[1].name".to_string(), json!("Harold"))]));
+        assert_eq!(pack["Map001.json"]["events[1].pages[0].list"], wrapped);
+        assert_eq!(errors.len(), 1);
+
+        let output = language_pack_output(project_path, &[("en".to_string(), pack)]).unwrap();
+        let mut paths: Vec<&str> = output.files.keys().map(String::as_str).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["www/js/plugins.js", "www/js/plugins/TranslateAI_LanguagePack.js", "www/languages/en.json"]);
+        assert!(output.files["www/js/plugins.js"].contains("\"name\":\"TranslateAI_LanguagePack\",\"status\":true"));
+        assert!(output.files["www/js/plugins/TranslateAI_LanguagePack.js"].contains("var languages = [\"en\"];"));
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::Path;
+use crate::error::CoreError;
+use super::plugins::register_plugin;
+use super::project::ReconstructedProject;
+use super::schema::detect_schema;
+
+/// Name of the generated plugin that makes the game load the suffixed data files.
+pub const LOADER_PLUGIN_NAME: &str = "TranslateAI_Locale";
+
+/// Locales are file name suffixes, e.g. "en" or "pt_BR".
+pub fn validate_locale(locale: &str) -> Result<(), CoreError> {
+    let valid = !locale.is_empty()
+        && locale.len() <= 16
+        && locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(CoreError::Config(format!("'{}' can't be used as a locale suffix", locale)))
+    }
+}
+
+/// "www/data/Map001.json" -> "www/data/Map001_en.json".
+pub fn suffixed_path(relative_path: &str, locale: &str) -> String {
+    match relative_path.strip_suffix(".json") {
+        Some(stem) => format!("{}_{}.json", stem, locale),
+        None => format!("{}_{}", relative_path, locale),
+    }
+}
+
+/// The loader plugin: `DataManager.loadDataFile` asks for the suffixed copy of every translated
+/// data file (`files` are file names as the game requests them, e.g. "Map001.json").
+pub fn loader_plugin(locale: &str, files: &[String]) -> String {
+    let locale_literal = serde_json::Value::from(locale).to_string();
+    let files_literal = serde_json::to_string(files).unwrap_or_else(|_| "[]".to_string());
+    format!(
+        r#"//=============================================================================
+// {name}.js - generated by Translate-AI
+//=============================================================================
+/*:
+ * @target MZ
+ * @plugindesc Loads the {locale} translation of the data files.
+ * @help Translated data files sit next to the originals with a "_{locale}" suffix.
+ * Turn this plugin off to play the original language.
+ */
+(function() {{
+    var locale = {locale_literal};
+    var translatedFiles = {files_literal};
+    var _DataManager_loadDataFile = DataManager.loadDataFile;
+    DataManager.loadDataFile = function(name, src) {{
+        if (translatedFiles.indexOf(src) >= 0) {{
+            src = src.replace(/\.json$/, "_" + locale + ".json");
+        }}
+        _DataManager_loadDataFile.call(this, name, src);
+    }};
+}})();
+"#,
+        name = LOADER_PLUGIN_NAME,
+        locale = locale,
+        locale_literal = locale_literal,
+        files_literal = files_literal,
+    )
+}
+
+/// Turns a reconstructed project into locale output: translated data files are renamed with the
+/// locale suffix, and the loader plugin is added and registered in plugins.js so the game picks
+/// them up. Other files can't be loaded under another name; they are left out and reported in
+/// `errors`.
+pub fn apply_locale_suffix(
+    project_path: &str,
+    locale: &str,
+    reconstructed: ReconstructedProject,
+) -> Result<ReconstructedProject, CoreError> {
+    validate_locale(locale)?;
+    let data_dir = detect_schema(project_path).map_or("www/data".to_string(), |s| s.data_dir);
+    let js_dir = Path::new(&data_dir).parent().unwrap_or(Path::new("")).join("js").to_str().unwrap_or_default().replace('\\', "/");
+    let js_path = |name: &str| if js_dir.is_empty() { name.to_string() } else { format!("{}/{}", js_dir, name) };
+
+    let mut output = ReconstructedProject { errors: reconstructed.errors, ..Default::default() };
+    let mut data_files = Vec::new();
+    for (relative_path, content) in reconstructed.files {
+        match relative_path.strip_prefix(&format!("{}/", data_dir)) {
+            Some(file_name) if file_name.ends_with(".json") && !file_name.contains('/') => {
+                data_files.push(file_name.to_string());
+                output.files.insert(suffixed_path(&relative_path, locale), content);
+            }
+            _ => output.errors.push(format!("Left out of the '{}' output (only data files can be suffixed): {}", locale, relative_path)),
+        }
+    }
+    for relative_path in reconstructed.binary_files.into_keys() {
+        output.errors.push(format!("Left out of the '{}' output (only data files can be suffixed): {}", locale, relative_path));
+    }
+    if data_files.is_empty() {
+        return Ok(output);
+    }
+    data_files.sort();
+
+    let plugins_relative_path = js_path("plugins.js");
+    let plugins_path = Path::new(project_path).join(&plugins_relative_path);
+    let plugins_content = fs::read_to_string(&plugins_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?} to register the loader plugin: {}", plugins_path, e)))?;
+    let description = format!("Loads the {} translation of the data files.", locale);
+    output.files.insert(plugins_relative_path, register_plugin(&plugins_content, LOADER_PLUGIN_NAME, &description)?);
+    output.files.insert(js_path(&format!("plugins/{}.js", LOADER_PLUGIN_NAME)), loader_plugin(locale, &data_files));
+    Ok(output)
+}
+
+/// Moves the translated data files of a reconstructed project to `data_<locale>/`, next to the
+/// data folder ("www/data/Map001.json" -> "www/data_en/Map001.json"), so several languages can
+/// ship side by side. Other files are left out and reported in `errors`, as for
+/// `apply_locale_suffix`.
+pub fn apply_data_folder(
+    project_path: &str,
+    locale: &str,
+    reconstructed: ReconstructedProject,
+) -> Result<ReconstructedProject, CoreError> {
+    validate_locale(locale)?;
+    let data_dir = detect_schema(project_path).map_or("www/data".to_string(), |s| s.data_dir);
+    let mut output = ReconstructedProject { errors: reconstructed.errors, ..Default::default() };
+    for (relative_path, content) in reconstructed.files {
+        match relative_path.strip_prefix(&format!("{}/", data_dir)) {
+            Some(file_name) => {
+                output.files.insert(format!("{}_{}/{}", data_dir, locale, file_name), content);
+            }
+            None => output.errors.push(format!("Left out of the '{}' output (not a data file): {}", locale, relative_path)),
+        }
+    }
+    for (relative_path, content) in reconstructed.binary_files {
+        match relative_path.strip_prefix(&format!("{}/", data_dir)) {
+            Some(file_name) => {
+                output.binary_files.insert(format!("{}_{}/{}", data_dir, locale, file_name), content);
+            }
+            None => output.errors.push(format!("Left out of the '{}' output (not a data file): {}", locale, relative_path)),
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_apply_locale_suffix() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("www/data")).unwrap();
+        fs::create_dir_all(root.join("www/js")).unwrap();
+        fs::write(root.join("www/js/plugins.js"), "var $plugins =\n[\n];\n").unwrap();
+        let project_path = root.to_str().unwrap();
+
+        let mut reconstructed = ReconstructedProject::default();
+        reconstructed.files.insert("www/data/Map001.json".to_string(), "{}".to_string());
+        reconstructed.files.insert("www/data/Actors.json".to_string(), "[]".to_string());
+        reconstructed.files.insert("www/credits.txt".to_string(), "Thanks".to_string());
+        let output = apply_locale_suffix(project_path, "pt_BR", reconstructed).unwrap();
+
+        let mut paths: Vec<&str> = output.files.keys().map(String::as_str).collect();
+        paths.sort();
+        assert_eq!(paths, vec![
+            "www/data/Actors_pt_BR.json",
+            "www/data/Map001_pt_BR.json",
+            "www/js/plugins.js",
+            "www/js/plugins/TranslateAI_Locale.js",
+        ]);
+        assert!(output.files["www/js/plugins.js"].contains("\"name\":\"TranslateAI_Locale\",\"status\":true"));
+        assert!(output.files["www/js/plugins/TranslateAI_Locale.js"].contains("[\"Actors.json\",\"Map001.json\"]"));
+        assert_eq!(output.errors.len(), 1);
+        assert!(output.errors[0].ends_with("www/credits.txt"));
+
+        assert!(validate_locale("en").is_ok());
+        for bad in ["", "../en", "en us"] {
+            assert!(matches!(validate_locale(bad), Err(CoreError::Config(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_apply_data_folder() {
+        let dir = tempdir().unwrap();
+        let mut reconstructed = ReconstructedProject::default();
+        reconstructed.files.insert("www/data/Map001.json".to_string(), "{}".to_string());
+        reconstructed.files.insert("www/credits.txt".to_string(), "Thanks".to_string());
+        let output = apply_data_folder(dir.path().to_str().unwrap(), "fr", reconstructed).unwrap();
+
+        assert_eq!(output.files.keys().collect::<Vec<_>>(), vec!["www/data_fr/Map001.json"]);
+        assert_eq!(output.errors.len(), 1);
+        assert!(apply_data_folder(dir.path().to_str().unwrap(), "../fr", ReconstructedProject::default()).is_err());
+    }
+}
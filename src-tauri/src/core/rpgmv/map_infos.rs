@@ -40,6 +40,8 @@ pub fn extract_strings(
                     original_text: map_info.name.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].name", index), // Use original index from JSON array
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
         }
@@ -63,6 +65,7 @@ pub fn reconstruct_map_infos_json(
 #[cfg(test)]
 mod tests {
     use super::*; 
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value;
 
     const TEST_MAP_INFOS_JSON: &str = r#"[
@@ -85,6 +88,7 @@ mod tests {
                 translated_text: "Carte de Test 1".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 4,
@@ -94,6 +98,7 @@ mod tests {
                 translated_text: "テストマップ (TL)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -121,6 +126,7 @@ mod tests {
                 translated_text: "Fail Map".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI boom".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -130,6 +136,7 @@ mod tests {
                 translated_text: "Autre Carte".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -153,6 +160,7 @@ mod tests {
                 translated_text: "Phantom Name".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -175,6 +183,7 @@ mod tests {
                 translated_text: "Mismatched ID Map".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -197,6 +206,7 @@ mod tests {
                 translated_text: "Bad Path Map".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -206,6 +216,7 @@ mod tests {
                 translated_text: "Bad Field Map".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
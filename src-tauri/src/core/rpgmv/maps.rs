@@ -8,6 +8,7 @@ use crate::core::rpgmv::common::{
 };
 
 use crate::error::CoreError;
+use super::warnings::{warn, WarningKind};
 use crate::utils::json_utils::update_value_at_path;
 
 // Represents an event page within a map event.
@@ -96,6 +97,8 @@ pub fn extract_strings(
                     original_text: event.name.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("events[{}].name", event_idx), // event_idx is the original index in the JSON array
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -146,32 +149,32 @@ pub fn reconstruct_map_json(
                             .map_or(0, |id| id as u32);
 
                         if event_object_id_from_json != entry.object_id {
-                            eprintln!(
+                            warn(WarningKind::IdMismatch, Some(&entry.json_path), format!(
                                 "Warning ({}): Mismatched object_id for event name at index {}. Expected {}, found in translation {}. Path: {}. Skipping.",
                                 source_file_name_for_error_logging, event_idx, event_object_id_from_json, entry.object_id, entry.json_path
-                            );
+                            ));
                             continue;
                         }
                         
                         let text_to_insert = if entry.error.is_some() { &entry.original_text } else { &entry.translated_text };
                         if let Err(e) = update_value_at_path(&mut events_array[event_idx], "name", text_to_insert) {
-                            eprintln!(
+                            warn(WarningKind::UpdateFailed, Some(&entry.json_path), format!(
                                 "Warning ({}): Failed to update event name for event id {} (index {}): {}. Path: {}. Skipping.",
                                 source_file_name_for_error_logging, entry.object_id, event_idx, e, entry.json_path
-                            );
+                            ));
                         }
                     } else {
-                        eprintln!(
+                        warn(WarningKind::OutOfBounds, Some(&entry.json_path), format!(
                             "Warning ({}): Event index {} for name update out of bounds or null. Path: {}. Skipping.",
                             source_file_name_for_error_logging, event_idx, entry.json_path
-                        );
+                        ));
                     }
                 }
             } else {
-                eprintln!(
+                warn(WarningKind::SkippedPath, Some(&entry.json_path), format!(
                     "Warning ({}): Could not parse event index from path for name update: {}. Skipping.",
                     source_file_name_for_error_logging, entry.json_path
-                );
+                ));
             }
         }
     }
@@ -203,17 +206,17 @@ pub fn reconstruct_map_json(
                                 match reconstruct_event_command_list(list_array, current_event_id, &relevant_translations_for_page, &page_json_path_prefix) {
                                     Ok(_) => { /* Successfully updated list */ }
                                     Err(e) => {
-                                        eprintln!(
+                                        warn(WarningKind::UpdateFailed, Some(&page_json_path_prefix), format!(
                                             "Error reconstructing command list for {} event id {}, page {}: {}. List might be partially updated.", 
                                             source_file_name_for_error_logging, current_event_id, page_idx, e
-                                        );
+                                        ));
                                     }
                                 }
                             } else {
-                                eprintln!("Warning ({}): 'list' field for event id {}, page {} is not an array.", source_file_name_for_error_logging, current_event_id, page_idx);
+                                warn(WarningKind::SkippedPath, Some(&page_json_path_prefix), format!("Warning ({}): 'list' field for event id {}, page {} is not an array.", source_file_name_for_error_logging, current_event_id, page_idx));
                             }
                         } else {
-                             eprintln!("Warning ({}): No 'list' field found for event id {}, page {}.", source_file_name_for_error_logging, current_event_id, page_idx);
+                             warn(WarningKind::SkippedPath, Some(&page_json_path_prefix), format!("Warning ({}): No 'list' field found for event id {}, page {}.", source_file_name_for_error_logging, current_event_id, page_idx));
                         }
                     }
                 }
@@ -228,6 +231,7 @@ pub fn reconstruct_map_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value;
 
     const TEST_MAP001_JSON: &str = r#"
@@ -275,6 +279,7 @@ null,
                 translated_text: "Event One (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1, 
@@ -284,6 +289,7 @@ null,
                 translated_text: "C'est un dialogue. (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1, 
@@ -293,6 +299,7 @@ null,
                 translated_text: "Choix A (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2, 
@@ -302,6 +309,7 @@ null,
                 translated_text: "Event Two (DE)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2, 
@@ -311,6 +319,7 @@ null,
                 translated_text: "Das ist ein Kommentar. (DE)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations_data.iter().collect();
@@ -344,6 +353,7 @@ null,
                 translated_text: "Failed Name".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI blew up".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation { 
                 object_id: 1,
@@ -353,6 +363,7 @@ null,
                 translated_text: "Good Dialogue!".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations_data.iter().collect();
@@ -376,6 +387,7 @@ null,
                 translated_text: "Phantom Name".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations_data.iter().collect();
@@ -398,6 +410,7 @@ null,
                 translated_text: "Wrong Event Name".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations_data.iter().collect();
@@ -448,6 +461,7 @@ null,
                 translated_text: "Translated Deep Invented".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations_data.iter().collect();
@@ -457,4 +471,47 @@ null,
         let original_value: Value = serde_json::from_str(original_json_str).expect("Failed to parse original");
         assert_eq!(reconstructed_value, original_value, "JSON should be unchanged for non-existent parameter path");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_name_box_code_roundtrip() {
+        let map_json = r#"{"data":[],"events":[null,{"id":1,"name":"","pages":[{"list":[
+            {"code":101,"parameters":["",0,0,2]},
+            {"code":401,"parameters":["\\n<ハロルド>"]},
+            {"code":401,"parameters":["行くぞ！"]},
+            {"code":101,"parameters":["",0,0,2]},
+            {"code":401,"parameters":["\\nr<マーシャ>待って！"]},
+            {"code":0,"parameters":[]}]}]}]}"#;
+
+        let entries = extract_strings(map_json, "www/data/Map001.json").unwrap();
+        let found: Vec<(&str, &str)> = entries.iter().map(|e| (e.json_path.as_str(), e.original_text.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("events[1].pages[0].list[1].parameters[0]@nameBox", "ハロルド"),
+                ("events[1].pages[0].list[2].parameters[0]", "行くぞ！"),
+                ("events[1].pages[0].list[4].parameters[0]@nameBox", "マーシャ"),
+                ("events[1].pages[0].list[4].parameters[0]", "待って！"),
+            ]
+        );
+
+        let translated: Vec<WorkingTranslation> = entries
+            .into_iter()
+            .zip(["Harold", "Let's go!", "Marsha", "Wait!"])
+            .map(|(e, text)| WorkingTranslation {
+                object_id: e.object_id,
+                original_text: e.original_text,
+                translated_text: text.to_string(),
+                source_file: e.source_file,
+                json_path: e.json_path,
+                translation_source: "test".to_string(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            })
+            .collect();
+        let reconstructed = reconstruct_map_json(map_json, translated.iter().collect(), "Map001.json").unwrap();
+        let value: Value = serde_json::from_str(&reconstructed).unwrap();
+        let list = &value["events"][1]["pages"][0]["list"];
+        assert_eq!(list[1]["parameters"][0], "\\n<Harold>");
+        assert_eq!(list[4]["parameters"][0], "\\nr<Marsha>Wait!");
+    }
+}
@@ -1,5 +1,25 @@
 pub mod common;
 pub mod project;
+pub mod verification;
+pub mod characters;
+pub mod recovery;
+pub mod schema;
+pub mod inspect;
+pub mod backups;
+pub mod note_tags;
+pub mod script_calls;
+pub mod word_wrap;
+pub mod filter;
+pub mod locale_output;
+pub mod language_pack;
+pub mod warnings;
+pub mod preview;
+pub mod event_codes;
+pub mod patching;
+pub mod sample_project;
+pub mod snapshot;
+pub mod build_output;
+pub mod parallel;
 
 // Placeholders for specific file parsers
 pub mod actors; // Example: Will contain logic for Actors.json
@@ -15,6 +35,8 @@ pub mod maps; // Added maps module
 pub mod map_infos; // Added map_infos module
 pub mod classes; // Added classes module
 pub mod states; // Added states module
+pub mod plugins; // $plugins parameters in js/plugins.js
+pub mod plain_text; // Line-based text assets (credits, ending text)
 // pub mod maps;
 // pub mod system;
 // ... etc. 
\ No newline at end of file
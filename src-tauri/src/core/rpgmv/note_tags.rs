@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::error::CoreError;
+use crate::models::note_tag::NoteTagConfig;
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+use super::project::extract_strings_from_file_content;
+use super::warnings::{warn, WarningKind};
+
+/// Suffix appended to a note's json_path for the value of its n-th tag, e.g. `[3].note@noteTag[1]`.
+/// The index counts every tag of the note, so it can be resolved without the configuration.
+pub const NOTE_TAG_PATH_SUFFIX: &str = "@noteTag";
+
+/// A `<Name:value>` or `<Name>value</Name>` tag, with the byte range of its (trimmed) value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteTag {
+    pub name: String,
+    pub value: Range<usize>,
+}
+
+fn trimmed(note: &str, range: Range<usize>) -> Range<usize> {
+    let value = &note[range.clone()];
+    let start = range.start + (value.len() - value.trim_start().len());
+    let end = range.end - (value.len() - value.trim_end().len());
+    start..end.max(start)
+}
+
+/// Tags of a note in order of appearance. Tags inside a block tag are part of its value.
+pub fn parse_note_tags(note: &str) -> Vec<NoteTag> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let re = PATTERN.get_or_init(|| Regex::new(r"<(/?)([^<>:/\r\n]+)(?::([^<>]*))?>").expect("note tag pattern is valid"));
+
+    let mut tags: Vec<NoteTag> = Vec::new();
+    // Open block tags: (name, value start, number of tags found before it).
+    let mut open: Vec<(String, usize, usize)> = Vec::new();
+    for captures in re.captures_iter(note) {
+        let (Some(whole), Some(name)) = (captures.get(0), captures.get(2)) else { continue };
+        let name = name.as_str().trim().to_string();
+        if let Some(value) = captures.get(3) {
+            tags.push(NoteTag { name, value: trimmed(note, value.range()) });
+        } else if &captures[1] == "/" {
+            if let Some(position) = open.iter().rposition(|(open_name, _, _)| *open_name == name) {
+                let (name, start, tags_before) = open.remove(position);
+                open.truncate(position);
+                tags.truncate(tags_before);
+                tags.push(NoteTag { name, value: trimmed(note, start..whole.start()) });
+            }
+        } else {
+            open.push((name, whole.end(), tags.len()));
+        }
+    }
+    tags
+}
+
+/// The configured tag name patterns, compiled.
+pub struct NoteTagMatcher {
+    patterns: Vec<Regex>,
+}
+
+impl NoteTagMatcher {
+    /// `None` when the configuration has no patterns, i.e. notes are extracted whole.
+    pub fn new(config: &NoteTagConfig) -> Result<Option<Self>, CoreError> {
+        let patterns = config
+            .tag_patterns
+            .iter()
+            .filter(|pattern| !pattern.trim().is_empty())
+            .map(|pattern| {
+                Regex::new(&format!("^(?:{})$", pattern.trim()))
+                    .map_err(|e| CoreError::Config(format!("Invalid note tag pattern '{}': {}", pattern, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!patterns.is_empty()).then_some(NoteTagMatcher { patterns }))
+    }
+
+    pub fn matches(&self, tag_name: &str) -> bool {
+        self.patterns.iter().any(|re| re.is_match(tag_name))
+    }
+}
+
+fn is_note_path(json_path: &str) -> bool {
+    json_path == "note" || json_path.ends_with(".note")
+}
+
+/// Replaces whole-note entries by one entry per value of a known tag. Numeric values and notes
+/// without known tags are left out, so tag names and parameters never reach the translator.
+pub fn split_note_entries(entries: Vec<SourceStringData>, matcher: &NoteTagMatcher) -> Vec<SourceStringData> {
+    let mut result = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !is_note_path(&entry.json_path) || entry.source_file.ends_with(".js") {
+            result.push(entry);
+            continue;
+        }
+        for (index, tag) in parse_note_tags(&entry.original_text).into_iter().enumerate() {
+            let value = &entry.original_text[tag.value];
+            if !matcher.matches(&tag.name) || value.is_empty() || value.parse::<f64>().is_ok() {
+                continue;
+            }
+            result.push(SourceStringData {
+                object_id: entry.object_id,
+                original_text: value.to_string(),
+                source_file: entry.source_file.clone(),
+                json_path: format!("{}{}[{}]", entry.json_path, NOTE_TAG_PATH_SUFFIX, index),
+                dialogue_block_id: None,
+                speaker: None,
+            });
+        }
+    }
+    result
+}
+
+fn split_tag_path(json_path: &str) -> Option<(&str, usize)> {
+    let (base, index) = json_path.split_once(NOTE_TAG_PATH_SUFFIX)?;
+    let index = index.strip_prefix('[')?.strip_suffix(']')?.parse().ok()?;
+    Some((base, index))
+}
+
+/// Turns note tag entries back into whole-note translations, splicing each translated value
+/// into the original note. Returns `None` when there are no tag entries.
+pub fn merge_note_tag_translations(
+    original_content: &str,
+    relative_file_path: &str,
+    translations: &[&WorkingTranslation],
+) -> Result<Option<Vec<WorkingTranslation>>, String> {
+    if !translations.iter().any(|t| split_tag_path(&t.json_path).is_some()) {
+        return Ok(None);
+    }
+    let file_name = Path::new(relative_file_path).file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let notes: HashMap<String, String> = extract_strings_from_file_content(file_name, original_content, relative_file_path)
+        .unwrap_or_else(|| Err(format!("No parser available for {}", relative_file_path)))?
+        .into_iter()
+        .filter(|s| is_note_path(&s.json_path))
+        .map(|s| (s.json_path, s.original_text))
+        .collect();
+
+    let mut merged = Vec::new();
+    let mut by_note: BTreeMap<&str, Vec<(usize, &WorkingTranslation)>> = BTreeMap::new();
+    for entry in translations {
+        match split_tag_path(&entry.json_path) {
+            Some((base, index)) => by_note.entry(base).or_default().push((index, entry)),
+            None => merged.push((*entry).clone()),
+        }
+    }
+
+    for (base, mut entries) in by_note {
+        let Some(note) = notes.get(base) else {
+            warn(WarningKind::SkippedPath, Some(base), format!("Note not found at {} in {}; skipping its tag translations.", base, relative_file_path));
+            continue;
+        };
+        let tags = parse_note_tags(note);
+        entries.sort_by_key(|(index, _)| std::cmp::Reverse(*index));
+        let mut text = note.clone();
+        for (index, entry) in &entries {
+            let Some(tag) = tags.get(*index) else {
+                warn(WarningKind::OutOfBounds, Some(base), format!("Note at {} in {} has no tag {}; skipping.", base, relative_file_path, index));
+                continue;
+            };
+            let value = if entry.error.is_some() || entry.translated_text.is_empty() {
+                &entry.original_text
+            } else {
+                &entry.translated_text
+            };
+            text.replace_range(tag.value.clone(), value);
+        }
+        let first = entries[0].1;
+        merged.push(WorkingTranslation {
+            object_id: first.object_id,
+            original_text: note.clone(),
+            translated_text: text,
+            source_file: first.source_file.clone(),
+            json_path: base.to_string(),
+            translation_source: first.translation_source.clone(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        });
+    }
+    Ok(Some(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: &str = r#"[null,
+        {"id":1,"name":"牢屋の鍵","description":"","note":"<拡張説明:\n捕まった人達がいる牢の鍵\nかなり頑丈にできている。>\n<Price:100>\n<SG説明>\n鍵です。\n</SG説明>"},
+        {"id":2,"name":"薬草","description":"","note":"<Price:20>"}
+    ]"#;
+
+    fn matcher() -> NoteTagMatcher {
+        let config = NoteTagConfig { tag_patterns: vec!["拡張説明".to_string(), "SG説明|Price".to_string()] };
+        NoteTagMatcher::new(&config).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_note_tags() {
+        let note = "<拡張説明: 説明文 >\n<SG説明>\n<Inner:1>\n本文\n</SG説明><Unclosed>";
+        let tags = parse_note_tags(note);
+        let tags: Vec<(&str, &str)> = tags.iter().map(|t| (t.name.as_str(), &note[t.value.clone()])).collect();
+        assert_eq!(tags, vec![("拡張説明", "説明文"), ("SG説明", "<Inner:1>\n本文")]);
+    }
+
+    #[test]
+    fn test_split_note_entries() {
+        let entries = extract_strings_from_file_content("Items.json", ITEMS, "www/data/Items.json").unwrap().unwrap();
+        let split = split_note_entries(entries, &matcher());
+        let found: Vec<(&str, &str)> = split.iter().map(|e| (e.json_path.as_str(), e.original_text.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("[1].name", "牢屋の鍵"),
+                ("[1].note@noteTag[0]", "捕まった人達がいる牢の鍵\nかなり頑丈にできている。"),
+                ("[1].note@noteTag[2]", "鍵です。"),
+                ("[2].name", "薬草"),
+            ]
+        );
+        assert!(NoteTagMatcher::new(&NoteTagConfig { tag_patterns: vec!["(".to_string()] }).is_err());
+        assert!(NoteTagMatcher::new(&NoteTagConfig::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_note_tag_translations() {
+        let translation = |json_path: &str, original: &str, translated: &str| WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Items.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let translations = [
+            translation("[1].name", "牢屋の鍵", "Cell Key"),
+            translation("[1].note@noteTag[2]", "鍵です。", "It's a key."),
+            translation("[1].note@noteTag[0]", "捕まった人達がいる牢の鍵", "Key to the cell."),
+        ];
+        let refs: Vec<&WorkingTranslation> = translations.iter().collect();
+        let merged = merge_note_tag_translations(ITEMS, "www/data/Items.json", &refs).unwrap().unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].json_path, "[1].note");
+        assert_eq!(merged[1].translated_text, "<拡張説明:\nKey to the cell.>\n<Price:100>\n<SG説明>\nIt's a key.\n</SG説明>");
+
+        assert!(merge_note_tag_translations(ITEMS, "www/data/Items.json", &refs[..1]).unwrap().is_none());
+    }
+}
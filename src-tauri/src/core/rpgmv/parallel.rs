@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Most files parsed at once; past this, reading the files from disk is the bottleneck.
+pub const MAX_EXTRACTION_THREADS: usize = 8;
+
+/// Runs `extract` on each of `files` on up to `MAX_EXTRACTION_THREADS` threads and returns the
+/// results in the order of `files`, whatever order they finish in. Each thread takes the next
+/// file left, so a few large maps don't hold up the rest.
+///
+/// The calling thread works through files too and checks `should_stop` before each of its own;
+/// once it returns true no file is started, and only the results up to the first file left
+/// unprocessed are returned, as with a serial extraction stopped at that point.
+pub fn extract_files_until<F, R>(
+    files: &[F],
+    should_stop: &dyn Fn() -> bool,
+    extract: impl Fn(&F) -> R + Sync,
+) -> Vec<R>
+where
+    F: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
+    let work = |is_stopped: &dyn Fn() -> bool| {
+        let mut done = Vec::new();
+        while !is_stopped() {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(file) = files.get(index) else { break };
+            done.push((index, extract(file)));
+        }
+        done
+    };
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).clamp(1, MAX_EXTRACTION_THREADS);
+
+    let mut results: Vec<Option<R>> = files.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (1..workers.min(files.len()))
+            .map(|_| scope.spawn(|| work(&|| stopped.load(Ordering::Relaxed))))
+            .collect();
+        let own = work(&|| {
+            let stop = should_stop();
+            stopped.store(stop, Ordering::Relaxed);
+            stop
+        });
+        // A parser panicking on another thread panics here, as it would have on this one.
+        let others =
+            handles.into_iter().flat_map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e)));
+        for (index, result) in own.into_iter().chain(others) {
+            results[index] = Some(result);
+        }
+    });
+    results.into_iter().map_while(|result| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_results_keep_file_order() {
+        let files: Vec<u64> = (0..200).collect();
+        // Earlier files take longer so they finish last.
+        let extract = |file: &u64| {
+            std::thread::sleep(std::time::Duration::from_micros(200 - file));
+            file * 2
+        };
+        let results = extract_files_until(&files, &|| false, extract);
+        assert_eq!(results, files.iter().map(|file| file * 2).collect::<Vec<_>>());
+
+        assert!(extract_files_until(&files, &|| true, extract).is_empty());
+        let checks = Cell::new(0);
+        let stop_later = || {
+            checks.set(checks.get() + 1);
+            checks.get() > 3
+        };
+        let partial = extract_files_until(&files, &stop_later, extract);
+        assert!(partial.len() < files.len());
+        assert_eq!(partial, files[..partial.len()].iter().map(|file| file * 2).collect::<Vec<_>>());
+    }
+}
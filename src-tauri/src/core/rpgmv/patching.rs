@@ -0,0 +1,389 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::core::config::load_project_config;
+use crate::core::postprocess::postprocess_translations;
+use crate::error::CoreError;
+use crate::models::translation::WorkingTranslation;
+use crate::utils::project_paths::{app_data_dir, check_app_data_unlocked};
+use super::locale_output;
+use super::project::reconstruct_project_files;
+use super::word_wrap;
+use super::warnings::{ReconstructionReport, ReconstructionWarning};
+
+/// Folder inside the app data folder holding one snapshot folder per in-place patch.
+pub const PATCH_BACKUPS_DIR_NAME: &str = "backups";
+/// Written in each snapshot folder next to the copied originals.
+pub const PATCH_MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// A project file touched by an in-place patch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackedUpFile {
+    // Relative to the project root, e.g. "www/data/Map001.json".
+    pub path: String,
+    // MD5 of the original, or `None` when the patch created the file (e.g. a locale-suffixed copy);
+    // rolling back deletes those.
+    pub original_md5: Option<String>,
+    pub patched_md5: String,
+}
+
+/// Describes a snapshot: enough to put every touched file back as it was.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchManifest {
+    // Snapshot folder name, e.g. "20261016-142530".
+    pub id: String,
+    // Seconds since the Unix epoch.
+    pub created_at: u64,
+    pub files: Vec<BackedUpFile>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InPlaceReport {
+    // `None` when no file changed, so nothing was written or backed up.
+    pub backup_id: Option<String>,
+    pub written: Vec<String>,
+    // Files that could not be reconstructed; they are left untouched.
+    pub errors: Vec<String>,
+    pub warnings: Vec<ReconstructionWarning>,
+}
+
+pub fn backups_dir(project_path: &str) -> PathBuf {
+    app_data_dir(project_path).join(PATCH_BACKUPS_DIR_NAME)
+}
+
+fn md5_hex(bytes: &[u8]) -> String {
+    format!("{:x}", md5::compute(bytes))
+}
+
+/// "20261016-142530" (UTC) for a Unix timestamp.
+fn timestamp_id(secs: u64) -> String {
+    // Days to a civil date, after Howard Hinnant's `civil_from_days`.
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let seconds_of_day = secs % 86_400;
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year, month, day, seconds_of_day / 3600, seconds_of_day % 3600 / 60, seconds_of_day % 60
+    )
+}
+
+/// Reconstructed JSON is re-serialized, so files are also equal when they hold the same data.
+fn same_content(original: &[u8], reconstructed: &[u8]) -> bool {
+    let parse = |bytes: &[u8]| {
+        let text = std::str::from_utf8(bytes).ok()?;
+        serde_json::from_str::<serde_json::Value>(text.trim_start_matches('\u{feff}')).ok()
+    };
+    original == reconstructed || parse(original).is_some_and(|value| Some(value) == parse(reconstructed))
+}
+
+/// Entry paths come from the frontend; only plain relative paths may be written.
+fn is_plain_relative(relative_path: &str) -> bool {
+    let path = Path::new(relative_path);
+    path.components().next().is_some() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Puts the listed files back from a snapshot: originals are copied back, created files removed.
+fn restore_files(project_path: &str, snapshot_dir: &Path, files: &[BackedUpFile]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for file in files {
+        let target = Path::new(project_path).join(&file.path);
+        let restored = match file.original_md5 {
+            Some(_) => fs::copy(snapshot_dir.join(&file.path), &target).map(|_| ()),
+            None if target.exists() => fs::remove_file(&target),
+            None => Ok(()),
+        };
+        if let Err(e) = restored {
+            errors.push(format!("Failed to restore {}: {}", file.path, e));
+        }
+    }
+    errors
+}
+
+/// Reconstructs the project and writes the changed files over the originals (or next to them
+/// with an output locale), after the project's post-processing. Every touched file is first copied to a timestamped snapshot under
+/// `.translate-ai/backups` with a manifest; if a write fails, the files already written are
+/// restored from it and the error is returned.
+pub fn write_translations_in_place(
+    project_path: &str,
+    translated_entries: &[WorkingTranslation],
+) -> Result<InPlaceReport, CoreError> {
+    let translated_entries = postprocess_translations(project_path, translated_entries)?;
+    let ReconstructionReport { output: reconstructed, warnings } = reconstruct_project_files(project_path, &translated_entries);
+    let reconstructed = word_wrap::apply_word_wrap(project_path, reconstructed)?;
+    let reconstructed = match load_project_config(project_path)?.output_locale {
+        Some(locale) => locale_output::apply_locale_suffix(project_path, &locale, reconstructed)?,
+        None => reconstructed,
+    };
+    let mut report = InPlaceReport { errors: reconstructed.errors, warnings, ..Default::default() };
+
+    let mut changed: Vec<(String, Vec<u8>)> = Vec::new();
+    let contents = reconstructed
+        .files
+        .into_iter()
+        .map(|(path, content)| (path, content.into_bytes()))
+        .chain(reconstructed.binary_files);
+    for (relative_path, content) in contents {
+        if !is_plain_relative(&relative_path) {
+            report.errors.push(format!("Refusing to write outside the project: {}", relative_path));
+            continue;
+        }
+        let unchanged = fs::read(Path::new(project_path).join(&relative_path)).is_ok_and(|original| same_content(&original, &content));
+        if !unchanged {
+            changed.push((relative_path, content));
+        }
+    }
+    if changed.is_empty() {
+        return Ok(report);
+    }
+    changed.sort_by(|a, b| a.0.cmp(&b.0));
+    check_app_data_unlocked(project_path)?;
+
+    let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let mut id = timestamp_id(created_at);
+    let mut suffix = 1;
+    while backups_dir(project_path).join(&id).exists() {
+        suffix += 1;
+        id = format!("{}-{}", timestamp_id(created_at), suffix);
+    }
+    let snapshot_dir = backups_dir(project_path).join(&id);
+
+    let mut manifest = PatchManifest { id: id.clone(), created_at, files: Vec::new() };
+    let snapshot = (|| -> Result<(), CoreError> {
+        for (relative_path, content) in &changed {
+            let original = Path::new(project_path).join(relative_path);
+            let original_md5 = if original.exists() {
+                let bytes = fs::read(&original)?;
+                let backup = snapshot_dir.join(relative_path);
+                if let Some(parent) = backup.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&backup, &bytes)?;
+                Some(md5_hex(&bytes))
+            } else {
+                None
+            };
+            manifest.files.push(BackedUpFile { path: relative_path.clone(), original_md5, patched_md5: md5_hex(content) });
+        }
+        fs::create_dir_all(&snapshot_dir)?;
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize patch manifest: {}", e)))?;
+        fs::write(snapshot_dir.join(PATCH_MANIFEST_FILE_NAME), json)?;
+        Ok(())
+    })();
+    if let Err(e) = snapshot {
+        // Nothing was written to the project yet.
+        let _ = fs::remove_dir_all(&snapshot_dir);
+        return Err(CoreError::Io(format!("Failed to back up files before patching, project left untouched: {}", e)));
+    }
+
+    for (index, (relative_path, content)) in changed.iter().enumerate() {
+        let target = Path::new(project_path).join(relative_path);
+        let written = target.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&target, content));
+        if let Err(e) = written {
+            let restore_errors = restore_files(project_path, &snapshot_dir, &manifest.files[..=index]);
+            let restored = if restore_errors.is_empty() {
+                "the files already written were restored".to_string()
+            } else {
+                format!("restoring the files already written failed ({})", restore_errors.join("; "))
+            };
+            return Err(CoreError::Io(format!(
+                "Failed to write {}: {}; {}. Backup {} kept.",
+                relative_path, e, restored, id
+            )));
+        }
+        report.written.push(relative_path.clone());
+    }
+    report.backup_id = Some(id);
+    Ok(report)
+}
+
+/// A snapshot as listed to the user; `problems` is empty when every backed-up file matches its hash.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub manifest: PatchManifest,
+    pub problems: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub restored: Vec<String>,
+    // Files edited after the patch (neither the patched nor the original content); restoring
+    // discarded those edits.
+    pub modified_since_patch: Vec<String>,
+}
+
+fn load_manifest(snapshot_dir: &Path) -> Result<PatchManifest, CoreError> {
+    let json = fs::read_to_string(snapshot_dir.join(PATCH_MANIFEST_FILE_NAME))?;
+    serde_json::from_str(&json)
+        .map_err(|e| CoreError::JsonParse(format!("Failed to parse patch manifest in {}: {}", snapshot_dir.display(), e)))
+}
+
+/// Backed-up files that are missing or don't match the hash recorded when they were copied.
+fn verify_snapshot(snapshot_dir: &Path, manifest: &PatchManifest) -> Vec<String> {
+    let mut problems = Vec::new();
+    for file in &manifest.files {
+        let Some(expected) = &file.original_md5 else { continue };
+        match fs::read(snapshot_dir.join(&file.path)) {
+            Ok(bytes) if md5_hex(&bytes) == *expected => {}
+            Ok(_) => problems.push(format!("{} does not match its recorded hash", file.path)),
+            Err(e) => problems.push(format!("{} can't be read: {}", file.path, e)),
+        }
+    }
+    problems
+}
+
+/// Snapshots of the project, newest first, each checked against its manifest. Folders without a
+/// readable manifest are skipped.
+pub fn list_backups(project_path: &str) -> Result<Vec<BackupSummary>, CoreError> {
+    let Ok(entries) = fs::read_dir(backups_dir(project_path)) else {
+        return Ok(Vec::new());
+    };
+    let mut backups = Vec::new();
+    for entry in entries.filter_map(Result::ok).filter(|e| e.path().is_dir()) {
+        match load_manifest(&entry.path()) {
+            Ok(manifest) => backups.push(BackupSummary { problems: verify_snapshot(&entry.path(), &manifest), manifest }),
+            Err(e) => eprintln!("Skipping backup {}: {}", entry.path().display(), e),
+        }
+    }
+    backups.sort_by(|a, b| b.manifest.created_at.cmp(&a.manifest.created_at).then(b.manifest.id.cmp(&a.manifest.id)));
+    Ok(backups)
+}
+
+/// Puts the project back as it was before the patch `backup_id`. Refuses, without touching the
+/// project, when a backed-up file is missing or its hash doesn't match the manifest.
+pub fn restore_backup(project_path: &str, backup_id: &str) -> Result<RestoreReport, CoreError> {
+    if !is_plain_relative(backup_id) || Path::new(backup_id).components().count() != 1 {
+        return Err(CoreError::Custom(format!("Invalid backup id: {}", backup_id)));
+    }
+    let snapshot_dir = backups_dir(project_path).join(backup_id);
+    let manifest = load_manifest(&snapshot_dir)?;
+    let problems = verify_snapshot(&snapshot_dir, &manifest);
+    if !problems.is_empty() {
+        return Err(CoreError::Custom(format!(
+            "Backup {} is damaged, project left untouched: {}",
+            backup_id,
+            problems.join("; ")
+        )));
+    }
+
+    let mut report = RestoreReport::default();
+    for file in &manifest.files {
+        let current = fs::read(Path::new(project_path).join(&file.path)).ok().map(|bytes| md5_hex(&bytes));
+        if current.is_some() && current != Some(file.patched_md5.clone()) && current != file.original_md5 {
+            report.modified_since_patch.push(file.path.clone());
+        }
+    }
+    let errors = restore_files(project_path, &snapshot_dir, &manifest.files);
+    if !errors.is_empty() {
+        return Err(CoreError::Io(errors.join("; ")));
+    }
+    report.restored = manifest.files.iter().map(|file| file.path.clone()).collect();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_timestamp_id() {
+        assert_eq!(timestamp_id(0), "19700101-000000");
+        assert_eq!(timestamp_id(1_709_210_096), "20240229-123456");
+    }
+
+    #[test]
+    fn test_write_translations_in_place_backs_up_touched_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("www/data")).unwrap();
+        let actors = r#"[null,{"id":1,"name":"ハロルド","nickname":"","profile":"","note":""}]"#;
+        fs::write(root.join("www/data/Actors.json"), actors).unwrap();
+        let items = r#"[null,{"id":1,"name":"ポーション","description":"","note":""}]"#;
+        fs::write(root.join("www/data/Items.json"), items).unwrap();
+
+        let entry = |file: &str, original: &str, translated: &str| WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: file.to_string(),
+            json_path: "[1].name".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let entries = vec![
+            entry("www/data/Actors.json", "ハロルド", "Harold"),
+            // Translated to itself: the file doesn't change and isn't backed up.
+            entry("www/data/Items.json", "ポーション", "ポーション"),
+            entry("../outside.json", "x", "y"),
+        ];
+        let project_path = root.to_str().unwrap();
+        let report = write_translations_in_place(project_path, &entries).unwrap();
+
+        assert_eq!(report.written, vec!["www/data/Actors.json"]);
+        assert!(fs::read_to_string(root.join("www/data/Actors.json")).unwrap().contains("Harold"));
+        assert_eq!(fs::read_to_string(root.join("www/data/Items.json")).unwrap(), items);
+
+        let snapshot_dir = backups_dir(project_path).join(report.backup_id.unwrap());
+        assert_eq!(fs::read_to_string(snapshot_dir.join("www/data/Actors.json")).unwrap(), actors);
+        let manifest: PatchManifest =
+            serde_json::from_str(&fs::read_to_string(snapshot_dir.join(PATCH_MANIFEST_FILE_NAME)).unwrap()).unwrap();
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].original_md5, Some(md5_hex(actors.as_bytes())));
+    }
+
+    #[test]
+    fn test_restore_backup_verifies_hashes() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let project_path = root.to_str().unwrap();
+        let snapshot_dir = backups_dir(project_path).join("20240229-123456");
+        fs::create_dir_all(snapshot_dir.join("www/data")).unwrap();
+        fs::create_dir_all(root.join("www/data")).unwrap();
+        fs::write(snapshot_dir.join("www/data/Actors.json"), "original").unwrap();
+        fs::write(root.join("www/data/Actors.json"), "edited after the patch").unwrap();
+        fs::write(root.join("www/data/Actors_en.json"), "patched").unwrap();
+        let manifest = PatchManifest {
+            id: "20240229-123456".to_string(),
+            created_at: 1_709_210_096,
+            files: vec![
+                BackedUpFile {
+                    path: "www/data/Actors.json".to_string(),
+                    original_md5: Some(md5_hex(b"original")),
+                    patched_md5: md5_hex(b"patched"),
+                },
+                BackedUpFile { path: "www/data/Actors_en.json".to_string(), original_md5: None, patched_md5: md5_hex(b"patched") },
+            ],
+        };
+        fs::write(snapshot_dir.join(PATCH_MANIFEST_FILE_NAME), serde_json::to_string(&manifest).unwrap()).unwrap();
+
+        fs::write(snapshot_dir.join("www/data/Actors.json"), "corrupted").unwrap();
+        let backups = list_backups(project_path).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].problems, vec!["www/data/Actors.json does not match its recorded hash"]);
+        assert!(restore_backup(project_path, "20240229-123456").is_err());
+        assert_eq!(fs::read_to_string(root.join("www/data/Actors.json")).unwrap(), "edited after the patch");
+        assert!(restore_backup(project_path, "../20240229-123456").is_err());
+
+        fs::write(snapshot_dir.join("www/data/Actors.json"), "original").unwrap();
+        let report = restore_backup(project_path, "20240229-123456").unwrap();
+        assert_eq!(report.modified_since_patch, vec!["www/data/Actors.json"]);
+        assert_eq!(fs::read_to_string(root.join("www/data/Actors.json")).unwrap(), "original");
+        assert!(!root.join("www/data/Actors_en.json").exists());
+    }
+}
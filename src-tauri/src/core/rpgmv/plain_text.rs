@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use super::warnings::{warn, WarningKind};
+
+const BOM: char = '\u{feff}';
+
+/// Line index from a plain text json_path, e.g. "[12]".
+fn line_index(json_path: &str) -> Option<usize> {
+    json_path.strip_prefix('[')?.strip_suffix(']')?.parse().ok()
+}
+
+/// Extracts every non-blank line of a text asset (credits, ending text, ...). The json_path is
+/// the zero-based line index, e.g. "[3]", and the object id the line number.
+pub fn extract_strings(file_content: &str, source_file: &str) -> Result<Vec<SourceStringData>, String> {
+    let content = file_content.strip_prefix(BOM).unwrap_or(file_content);
+    Ok(content
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| SourceStringData {
+            object_id: index as u32 + 1,
+            original_text: line.to_string(),
+            source_file: source_file.to_string(),
+            json_path: format!("[{}]", index),
+            dialogue_block_id: None,
+            speaker: None,
+        })
+        .collect())
+}
+
+/// Writes translated lines back in place. Line endings (LF or CRLF), a BOM and untranslated
+/// lines are kept as they are; failed or empty translations keep the original line.
+pub fn reconstruct_plain_text(
+    original_content: &str,
+    translations: Vec<&WorkingTranslation>,
+) -> Result<String, CoreError> {
+    let (bom, content) = match original_content.strip_prefix(BOM) {
+        Some(rest) => (BOM.to_string(), rest),
+        None => (String::new(), original_content),
+    };
+    let mut by_line: HashMap<usize, &str> = HashMap::new();
+    for entry in translations {
+        let Some(index) = line_index(&entry.json_path) else {
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Skipping translation with invalid line path {} in {}.", entry.json_path, entry.source_file));
+            continue;
+        };
+        if entry.error.is_none() && !entry.translated_text.is_empty() {
+            by_line.insert(index, entry.translated_text.as_str());
+        }
+    }
+
+    let lines: Vec<String> = content
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| match by_line.get(&index) {
+            Some(translated) if line.ends_with('\r') => format!("{}\r", translated),
+            Some(translated) => translated.to_string(),
+            None => line.to_string(),
+        })
+        .collect();
+    Ok(format!("{}{}", bom, lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    #[test]
+    fn test_extract_and_reconstruct_lines() {
+        let content = "\u{feff}スタッフ\r\n\r\nシナリオ：山田\r\nEND";
+        let entries = extract_strings(content, "www/credits.txt").unwrap();
+        let found: Vec<(&str, &str)> = entries.iter().map(|e| (e.json_path.as_str(), e.original_text.as_str())).collect();
+        assert_eq!(found, vec![("[0]", "スタッフ"), ("[2]", "シナリオ：山田"), ("[3]", "END")]);
+
+        let translation = |json_path: &str, translated: &str| WorkingTranslation {
+            object_id: 0,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "www/credits.txt".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let translations = [translation("[0]", "Staff"), translation("[2]", "Scenario: Yamada"), translation("[3]", "")];
+        let rebuilt = reconstruct_plain_text(content, translations.iter().collect()).unwrap();
+        assert_eq!(rebuilt, "\u{feff}Staff\r\n\r\nScenario: Yamada\r\nEND");
+    }
+}
@@ -0,0 +1,428 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use regex::Regex;
+use serde_json::Value;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+
+/// Joins the path of a parameter holding JSON text (struct and list parameters) with the
+/// path inside that JSON, e.g. `[3].parameters.Commands::[0].name`.
+pub const NESTED_JSON_SEPARATOR: &str = "::";
+
+// Parameter names that hold file names, scripts or ids rather than player-facing text.
+const NON_TEXT_PARAMETER_HINTS: [&str; 18] = [
+    "image", "picture", "skin", "filename", "file name", "font", "sound", "bgm", "bgs", "eval", "script",
+    "code", "formula", "switch", "variable", "color", "symbol", "url",
+];
+
+fn file_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\.(png|jpe?g|gif|webp|ogg|m4a|wav|mp3|webm|mp4|js|json|txt|css|ttf|otf|woff2?|rpgmvp|rpgmvo|rpgmvm|png_|ogg_)$")
+            .unwrap()
+    })
+}
+
+fn non_text_value_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // Colors (#fff, rgba(...)) and identifiers with digits or underscores (Actor1, window_skin).
+    RE.get_or_init(|| {
+        Regex::new(r"^(#[0-9a-fA-F]{3,8}|rgba?\(.*\)|[A-Za-z_][A-Za-z0-9_]*[0-9_][A-Za-z0-9_]*)$").unwrap()
+    })
+}
+
+/// Heuristic for plugin parameters: skips numbers, switches, file paths, colors, identifiers
+/// and parameters whose name says they hold a file or a script.
+pub fn is_translatable_parameter(name: &str, value: &str) -> bool {
+    let value = value.trim();
+    let lower_name = name.to_lowercase();
+    if value.is_empty()
+        || value.parse::<f64>().is_ok()
+        || ["true", "false", "null", "on", "off"].contains(&value.to_lowercase().as_str())
+        || NON_TEXT_PARAMETER_HINTS.iter().any(|hint| lower_name.contains(hint))
+        || ((value.contains('/') || value.contains('\\')) && !value.contains(char::is_whitespace))
+        || file_name_regex().is_match(value)
+        || non_text_value_regex().is_match(value)
+    {
+        return false;
+    }
+    value.chars().any(char::is_alphabetic)
+}
+
+/// A string value in JSON text, with its byte range (quotes included).
+#[derive(Debug, Clone)]
+struct StringLiteral {
+    path: String,
+    start: usize,
+    end: usize,
+    value: String,
+}
+
+/// Walks JSON text and records where each string value is, so translations can be spliced in
+/// without re-serializing (and reformatting) the rest of the file.
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    literals: Vec<StringLiteral>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str, pos: usize) -> Self {
+        Scanner { text, pos, literals: Vec::new() }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at byte {}", message, self.pos)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("Expected '{}'", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<(usize, String), String> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut i = start + 1;
+        while i < bytes.len() && bytes[i] != b'"' {
+            i += if bytes[i] == b'\\' { 2 } else { 1 };
+        }
+        if i >= bytes.len() {
+            return Err(self.error("Unterminated string"));
+        }
+        self.pos = i + 1;
+        let value = serde_json::from_str(&self.text[start..self.pos]).map_err(|e| self.error(&e.to_string()))?;
+        Ok((start, value))
+    }
+
+    fn value(&mut self, path: &str) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error("Expected an object key"));
+                    }
+                    let (_, key) = self.string()?;
+                    self.expect(b':')?;
+                    let child = if path.is_empty() { key } else { format!("{}.{}", path, key) };
+                    self.value(&child)?;
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(());
+                        }
+                        _ => return Err(self.error("Expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                let mut index = 0;
+                loop {
+                    self.value(&format!("{}[{}]", path, index))?;
+                    index += 1;
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(());
+                        }
+                        _ => return Err(self.error("Expected ',' or ']'")),
+                    }
+                }
+            }
+            Some(b'"') => {
+                let (start, value) = self.string()?;
+                self.literals.push(StringLiteral { path: path.to_string(), start, end: self.pos, value });
+                Ok(())
+            }
+            Some(_) => {
+                // Numbers, booleans and null.
+                while self.peek().is_some_and(|b| !matches!(b, b',' | b']' | b'}') && !b.is_ascii_whitespace()) {
+                    self.pos += 1;
+                }
+                Ok(())
+            }
+            None => Err(self.error("Unexpected end of JSON")),
+        }
+    }
+}
+
+/// String literals of a complete JSON text.
+fn scan_json(text: &str) -> Result<Vec<StringLiteral>, String> {
+    let mut scanner = Scanner::new(text, 0);
+    scanner.value("")?;
+    Ok(scanner.literals)
+}
+
+/// Byte range of the `$plugins` array in plugins.js.
+fn plugins_array_range(content: &str) -> Result<(usize, usize), String> {
+    let declaration = content.find("$plugins").ok_or("No $plugins declaration found")?;
+    let start = content[declaration..].find('[').map(|i| declaration + i).ok_or("No $plugins array found")?;
+    let mut scanner = Scanner::new(content, start);
+    scanner.value("")?;
+    Ok((start, scanner.pos))
+}
+
+// Struct and list parameters are JSON encoded in a string.
+fn nested_json(value: &str) -> Option<&str> {
+    let trimmed = value.trim();
+    let is_container = (trimmed.starts_with('[') && trimmed.ends_with(']')) || (trimmed.starts_with('{') && trimmed.ends_with('}'));
+    (is_container && serde_json::from_str::<Value>(trimmed).is_ok()).then_some(value)
+}
+
+fn last_key(path: &str) -> &str {
+    path.rsplit('.').next().unwrap_or(path).split('[').next().unwrap_or_default()
+}
+
+fn collect_parameter(
+    name: &str,
+    path: &str,
+    value: &str,
+    object_id: u32,
+    source_file: &str,
+    entries: &mut Vec<SourceStringData>,
+) {
+    if let Some(json) = nested_json(value) {
+        let Ok(literals) = scan_json(json) else { return };
+        for literal in literals {
+            // Items of a list parameter take the list's name.
+            let inner_name = match last_key(&literal.path) {
+                "" => name,
+                key => key,
+            };
+            let inner_path = format!("{}{}{}", path, NESTED_JSON_SEPARATOR, literal.path);
+            collect_parameter(inner_name, &inner_path, &literal.value, object_id, source_file, entries);
+        }
+    } else if is_translatable_parameter(name, value) {
+        entries.push(SourceStringData {
+            object_id,
+            original_text: value.to_string(),
+            source_file: source_file.to_string(),
+            json_path: path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        });
+    }
+}
+
+/// Extracts player-facing plugin parameters from `www/js/plugins.js`, including text inside
+/// struct/list parameters. Parameters of disabled plugins are skipped. The object id is the
+/// plugin's index in `$plugins`.
+pub fn extract_strings(file_content: &str, source_file: &str) -> Result<Vec<SourceStringData>, String> {
+    let (start, end) = plugins_array_range(file_content)?;
+    let plugins: Vec<Value> = serde_json::from_str(&file_content[start..end])
+        .map_err(|e| format!("Failed to parse $plugins in {}: {}", source_file, e))?;
+    let literals = scan_json(&file_content[start..end])?;
+
+    let mut entries = Vec::new();
+    for literal in literals {
+        let Some((index, name)) = literal
+            .path
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once("].parameters."))
+            .and_then(|(index, name)| Some((index.parse::<usize>().ok()?, name)))
+        else {
+            continue;
+        };
+        if plugins.get(index).and_then(|p| p.get("status")).and_then(Value::as_bool) == Some(false) {
+            continue;
+        }
+        collect_parameter(name, &literal.path, &literal.value, index as u32, source_file, &mut entries);
+    }
+    Ok(entries)
+}
+
+/// Replaces the translated string literals of `text`, recursing into JSON-encoded values.
+/// Everything else is kept byte for byte.
+fn apply_translations(
+    text: &str,
+    prefix: &str,
+    translations: &HashMap<&str, &str>,
+    nested_prefixes: &HashSet<&str>,
+) -> Result<String, String> {
+    let mut out = text.to_string();
+    for literal in scan_json(text)?.into_iter().rev() {
+        let path = if prefix.is_empty() {
+            literal.path.clone()
+        } else {
+            format!("{}{}{}", prefix, NESTED_JSON_SEPARATOR, literal.path)
+        };
+        let replacement = match translations.get(path.as_str()) {
+            Some(translated) => translated.to_string(),
+            None if nested_prefixes.contains(path.as_str()) => {
+                apply_translations(&literal.value, &path, translations, nested_prefixes)?
+            }
+            None => continue,
+        };
+        if replacement != literal.value {
+            let encoded = serde_json::to_string(&replacement).map_err(|e| e.to_string())?;
+            out.replace_range(literal.start..literal.end, &encoded);
+        }
+    }
+    Ok(out)
+}
+
+/// Writes translations back into plugins.js, leaving comments, layout and untranslated
+/// parameters untouched. Failed or empty translations keep the original text.
+pub fn reconstruct_plugins_js(
+    original_content: &str,
+    translations: Vec<&WorkingTranslation>,
+) -> Result<String, CoreError> {
+    let (start, end) = plugins_array_range(original_content).map_err(CoreError::JsonParse)?;
+    let by_path: HashMap<&str, &str> = translations
+        .iter()
+        .filter(|t| t.error.is_none() && !t.translated_text.is_empty())
+        .map(|t| (t.json_path.as_str(), t.translated_text.as_str()))
+        .collect();
+    let nested_prefixes: HashSet<&str> = by_path
+        .keys()
+        .flat_map(|path| path.match_indices(NESTED_JSON_SEPARATOR).map(|(i, _)| &path[..i]))
+        .collect();
+
+    let array = apply_translations(&original_content[start..end], "", &by_path, &nested_prefixes)
+        .map_err(|e| CoreError::JsonParse(format!("Failed to rewrite $plugins: {}", e)))?;
+    Ok(format!("{}{}{}", &original_content[..start], array, &original_content[end..]))
+}
+
+/// Adds an enabled plugin with no parameters at the end of `$plugins`, unless one with that name
+/// is already listed. The rest of the file is kept as it was.
+pub fn register_plugin(content: &str, name: &str, description: &str) -> Result<String, CoreError> {
+    let (start, end) = plugins_array_range(content).map_err(CoreError::JsonParse)?;
+    let plugins: Vec<Value> = serde_json::from_str(&content[start..end])
+        .map_err(|e| CoreError::JsonParse(format!("Failed to parse $plugins: {}", e)))?;
+    if plugins.iter().any(|plugin| plugin.get("name").and_then(Value::as_str) == Some(name)) {
+        return Ok(content.to_string());
+    }
+    // Same key order as the editor writes.
+    let entry = format!(
+        "{{\"name\":{},\"status\":true,\"description\":{},\"parameters\":{{}}}}",
+        Value::from(name),
+        Value::from(description)
+    );
+    let body = content[start + 1..end - 1].trim_end();
+    let separator = if body.trim().is_empty() { "" } else { "," };
+    Ok(format!("{}[{}{}\n{}\n]{}", &content[..start], body, separator, entry, &content[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    const PLUGINS_JS: &str = r#"// Generated by RPG Maker.
+// Do not edit this file directly.
+var $plugins =
+[
+{"name":"Community_Basic","status":true,"description":"基本的なパラメータを設定します。","parameters":{"cacheLimit":"20","screenWidth":"816","changeWindowWidthTo":""}},
+{"name":"MenuText","status":true,"description":"","parameters":{"Save Command":"セーブ","Window Skin":"Window","Cursor Sound":"Cursor1","Help Text":"ゲームを終了します。","Commands":"[\"{\\\"name\\\":\\\"アイテム\\\",\\\"symbol\\\":\\\"item\\\"}\",\"{\\\"name\\\":\\\"スキル\\\",\\\"symbol\\\":\\\"skill_1\\\"}\"]","Font Face":"GameFont","Title Image":"img/titles1/Book"}},
+{"name":"Disabled","status":false,"description":"","parameters":{"Text":"使わない"}}
+];
+"#;
+
+    #[test]
+    fn test_parameter_heuristics() {
+        assert!(is_translatable_parameter("Save Command", "Save"));
+        assert!(is_translatable_parameter("Help", "ゲームを終了します。"));
+        for (name, value) in [
+            ("cacheLimit", "20"),
+            ("Show", "true"),
+            ("Face", "img/faces/Actor1.png"),
+            ("Window Skin", "Window"),
+            ("Cursor", "Cursor1"),
+            ("Text Color", "#ffcc00"),
+            ("Symbol", "skill_1"),
+            ("Condition Eval", "$gameSwitches.value(1)"),
+        ] {
+            assert!(!is_translatable_parameter(name, value), "{} = {}", name, value);
+        }
+    }
+
+    #[test]
+    fn test_extract_plugin_parameters() {
+        let entries = extract_strings(PLUGINS_JS, "www/js/plugins.js").unwrap();
+        let found: Vec<(&str, &str)> = entries.iter().map(|e| (e.json_path.as_str(), e.original_text.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("[1].parameters.Save Command", "セーブ"),
+                ("[1].parameters.Help Text", "ゲームを終了します。"),
+                ("[1].parameters.Commands::[0]::name", "アイテム"),
+                ("[1].parameters.Commands::[1]::name", "スキル"),
+            ]
+        );
+        assert!(entries.iter().all(|e| e.object_id == 1 && e.source_file == "www/js/plugins.js"));
+    }
+
+    #[test]
+    fn test_reconstruct_preserves_layout() {
+        let translation = |json_path: &str, translated: &str| WorkingTranslation {
+            object_id: 1,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "www/js/plugins.js".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let translations = [
+            translation("[1].parameters.Save Command", "Save \"now\""),
+            translation("[1].parameters.Commands::[1]::name", "Skills"),
+        ];
+        let rebuilt = reconstruct_plugins_js(PLUGINS_JS, translations.iter().collect()).unwrap();
+
+        let expected = PLUGINS_JS
+            .replace(r#""Save Command":"セーブ""#, r#""Save Command":"Save \"now\"""#)
+            .replace(r#"\\\"name\\\":\\\"スキル\\\""#, r#"\\\"name\\\":\\\"Skills\\\""#);
+        assert_eq!(rebuilt, expected);
+
+        let entries = extract_strings(&rebuilt, "www/js/plugins.js").unwrap();
+        assert!(entries.iter().any(|e| e.json_path.ends_with("::[1]::name") && e.original_text == "Skills"));
+    }
+
+    #[test]
+    fn test_register_plugin() {
+        let registered = register_plugin(PLUGINS_JS, "TranslateAI_Locale", "Loads \"en\" data").unwrap();
+        assert!(registered.starts_with("// Generated by RPG Maker."));
+        assert!(registered.ends_with(
+            "{\"name\":\"Disabled\",\"status\":false,\"description\":\"\",\"parameters\":{\"Text\":\"使わない\"}},\n\
+             {\"name\":\"TranslateAI_Locale\",\"status\":true,\"description\":\"Loads \\\"en\\\" data\",\"parameters\":{}}\n];\n"
+        ));
+        assert_eq!(register_plugin(&registered, "TranslateAI_Locale", "").unwrap(), registered);
+        assert_eq!(
+            register_plugin("var $plugins =\n[\n];\n", "A", "").unwrap(),
+            "var $plugins =\n[\n{\"name\":\"A\",\"status\":true,\"description\":\"\",\"parameters\":{}}\n];\n"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use crate::core::postprocess::postprocess_translations;
+use crate::models::translation::WorkingTranslation;
+use crate::utils::text_diff::unified_diff;
+use super::project::reconstruct_project_files;
+use super::word_wrap;
+use super::warnings::{ReconstructionReport, ReconstructionWarning};
+
+// Unchanged lines shown around each change.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub source_file: String,
+    // Unified diff of the original against the reconstructed file; empty for binary files.
+    pub diff: String,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub binary: bool,
+}
+
+/// What reconstruction would change, computed in memory without writing anything.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconstructionPreview {
+    // Changed files, by path.
+    pub files: Vec<FileDiff>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<ReconstructionWarning>,
+}
+
+/// JSON files are compared pretty-printed: data files are usually minified on a single line.
+fn diffable_text(relative_path: &str, content: &str) -> String {
+    if relative_path.ends_with(".json") {
+        if let Some(pretty) = serde_json::from_str::<serde_json::Value>(content.trim_start_matches('\u{feff}'))
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        {
+            return pretty;
+        }
+    }
+    content.to_string()
+}
+
+pub fn file_diff(relative_path: &str, original: &str, reconstructed: &str) -> FileDiff {
+    let diff = unified_diff(
+        &diffable_text(relative_path, original),
+        &diffable_text(relative_path, reconstructed),
+        &format!("a/{}", relative_path),
+        &format!("b/{}", relative_path),
+        DIFF_CONTEXT_LINES,
+    );
+    let count = |marker: char| diff.lines().filter(|l| l.starts_with(marker) && !l.starts_with("---") && !l.starts_with("+++")).count();
+    FileDiff {
+        source_file: relative_path.to_string(),
+        lines_added: count('+'),
+        lines_removed: count('-'),
+        diff,
+        binary: false,
+    }
+}
+
+/// Reconstructs the project in memory and diffs every reconstructed file against the original.
+/// Files the translations leave unchanged are not listed.
+pub fn preview_reconstruction(project_path: &str, translated_entries: &[WorkingTranslation]) -> ReconstructionPreview {
+    let translated_entries = match postprocess_translations(project_path, translated_entries) {
+        Ok(entries) => entries,
+        Err(e) => return ReconstructionPreview { errors: vec![format!("Post-processing failed: {}", e)], ..Default::default() },
+    };
+    let ReconstructionReport { output, warnings } = reconstruct_project_files(project_path, &translated_entries);
+    let output = match word_wrap::apply_word_wrap(project_path, output) {
+        Ok(output) => output,
+        Err(e) => return ReconstructionPreview { errors: vec![format!("Word wrap failed: {}", e)], warnings, ..Default::default() },
+    };
+    let mut preview = ReconstructionPreview { errors: output.errors, warnings, ..Default::default() };
+
+    for (relative_path, content) in output.files {
+        match fs::read(Path::new(project_path).join(&relative_path)) {
+            Ok(original) => {
+                let diff = file_diff(&relative_path, &String::from_utf8_lossy(&original), &content);
+                if !diff.diff.is_empty() {
+                    preview.files.push(diff);
+                }
+            }
+            Err(e) => preview.errors.push(format!("Failed to read original file {}: {}", relative_path, e)),
+        }
+    }
+    for (relative_path, content) in output.binary_files {
+        let unchanged = fs::read(Path::new(project_path).join(&relative_path)).is_ok_and(|original| original == content);
+        if !unchanged {
+            preview.files.push(FileDiff {
+                source_file: relative_path,
+                diff: String::new(),
+                lines_added: 0,
+                lines_removed: 0,
+                binary: true,
+            });
+        }
+    }
+    preview.files.sort_by(|a, b| a.source_file.cmp(&b.source_file));
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_preview_reconstruction_diffs_without_writing() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("www/data")).unwrap();
+        let original = r#"[null,{"id":1,"name":"ハロルド","nickname":"","profile":"","note":""}]"#;
+        fs::write(root.join("www/data/Actors.json"), original).unwrap();
+
+        let entry = WorkingTranslation {
+            object_id: 1,
+            original_text: "ハロルド".to_string(),
+            translated_text: "Harold".to_string(),
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: "[1].name".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let preview = preview_reconstruction(root.to_str().unwrap(), &[entry]);
+
+        assert!(preview.errors.is_empty(), "{:?}", preview.errors);
+        assert_eq!(preview.files.len(), 1);
+        let file = &preview.files[0];
+        assert_eq!((file.lines_added, file.lines_removed), (1, 1));
+        assert!(file.diff.starts_with("--- a/www/data/Actors.json\n+++ b/www/data/Actors.json\n@@ "));
+        assert!(file.diff.contains("-    \"name\": \"ハロルド\",\n+    \"name\": \"Harold\",\n"));
+        assert_eq!(fs::read_to_string(root.join("www/data/Actors.json")).unwrap(), original);
+    }
+}
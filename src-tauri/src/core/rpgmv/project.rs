@@ -1,8 +1,24 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use crate::models::translation::{SourceStringData, WorkingTranslation};
 use crate::error::CoreError;
+use super::parallel;
+use super::schema::{detect_schema, unknown_fields, SchemaInfo, SchemaWarning};
+use super::backups::{is_backup_dir_name, is_backup_file_name};
+use super::note_tags::{self, NoteTagMatcher};
+use super::script_calls::{self, ScriptCallMatcher};
+use super::filter::file_kind;
+use crate::core::config::{load_project_config, IgnoredFiles, ParserOverrides};
+use crate::core::do_not_translate::DoNotTranslateRules;
+use crate::core::generic::project::GenericRules;
+use crate::models::extraction::{ExtractionFilter, FileKind};
+use crate::models::project_config::FileParser;
+use super::filter::CompiledExtractionFilter;
+use super::warnings::{self, ReconstructionReport};
+use crate::utils::project_paths::APP_DATA_DIR_NAME;
+use crate::core::{generic, renpy, rpgvxace, tyrano, wolfrpg};
 
 // This will be moved from the old parser or defined fresh in common.rs
 // For now, assuming it will be in a sibling module `common`
@@ -25,15 +41,68 @@ use crate::error::CoreError;
  use super::map_infos;
  use super::classes;
  use super::states;
+ use super::plugins;
+ use super::plain_text;
 
-/// Orchestrates the extraction of translatable strings from an RPG Maker MV project.
+/// Orchestrates the extraction of translatable strings from an RPG Maker MV/MZ project.
 ///
-/// It walks through the `www/data` directory of the project, identifies relevant
-/// JSON files, and delegates parsing to specific modules.
+/// It walks through the data directory of the project (`www/data`, or `data` for MZ),
+/// identifies relevant JSON files, and delegates parsing to specific modules. Object fields
+/// the parsers don't know are reported as warnings. `filter` limits the files and fields
+/// extracted (`ExtractionFilter::default()` extracts everything). Whatever the engine, strings
+/// matching the project's do-not-translate rules are left out.
 pub fn extract_translatable_strings_from_project(
     project_path: &str,
+    filter: &ExtractionFilter,
 ) -> Result<Vec<SourceStringData>, String> {
-    let data_path = Path::new(project_path).join("www").join("data");
+    extract_translatable_strings_until(project_path, filter, &|| false)
+}
+
+// Extraction entry point of the engines without a `www/data` folder.
+type EngineExtractor = fn(
+    &str,
+    &IgnoredFiles,
+    &CompiledExtractionFilter,
+    &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String>;
+
+/// Same as `extract_translatable_strings_from_project`, but checks `should_stop` before each
+/// file and returns the strings extracted so far once it returns true.
+pub fn extract_translatable_strings_until(
+    project_path: &str,
+    filter: &ExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let filter = CompiledExtractionFilter::new(filter).map_err(|e| e.to_string())?;
+    // Ignored files, parser overrides, note tag and do-not-translate rules come from the project config.
+    let config = load_project_config(project_path).map_err(|e| e.to_string())?;
+    let ignored = IgnoredFiles::new(&config.ignored_files).map_err(|e| e.to_string())?;
+    let do_not_translate = DoNotTranslateRules::new(&config.do_not_translate).map_err(|e| e.to_string())?;
+    // MV keeps its data in www/data, MZ in data; relative paths below follow whichever it is.
+    let schema = detect_schema(project_path);
+    let data_dir = schema.as_ref().map_or("www/data", |s| s.data_dir.as_str());
+    let data_path = Path::new(project_path).join(data_dir);
+    // Wolf RPG, Ren'Py, VX Ace and TyranoScript games have no JSON data; their text dumps,
+    // scripts, Marshal data files or scenarios are extracted instead. Other games can be extracted with generic rules.
+    if !data_path.is_dir() {
+        let engine_extractor: Option<EngineExtractor> = if wolfrpg::project::is_wolf_rpg_project(project_path) {
+            Some(wolfrpg::project::extract_translatable_strings)
+        } else if renpy::project::is_renpy_project(project_path) {
+            Some(renpy::project::extract_translatable_strings)
+        } else if rpgvxace::project::is_vx_ace_project(project_path) {
+            Some(rpgvxace::project::extract_translatable_strings)
+        } else if tyrano::project::is_tyrano_project(project_path) {
+            Some(tyrano::project::extract_translatable_strings)
+        } else if generic::project::is_generic_project(project_path) {
+            Some(generic::project::extract_translatable_strings)
+        } else {
+            None
+        };
+        if let Some(extract) = engine_extractor {
+            return extract(project_path, &ignored, &filter, should_stop)
+                .map(|strings| do_not_translate.apply(strings));
+        }
+    }
     if !data_path.is_dir() {
         return Err(format!(
             "Data directory not found or is not a directory: {:?}",
@@ -41,11 +110,18 @@ pub fn extract_translatable_strings_from_project(
         ));
     }
 
+    let overrides = ParserOverrides::new(&config.parser_overrides).map_err(|e| e.to_string())?;
+    let note_tag_matcher = NoteTagMatcher::new(&config.note_tags).map_err(|e| e.to_string())?;
+    let script_call_matcher = ScriptCallMatcher::new(&config.script_calls).map_err(|e| e.to_string())?;
+
     let mut all_strings: Vec<SourceStringData> = Vec::new();
     let mut parsing_errors: Vec<String> = Vec::new();
+    let mut schema_warnings: Vec<SchemaWarning> = Vec::new();
 
+    let mut data_files: Vec<DataFile> = Vec::new();
     for entry in WalkDir::new(&data_path)
         .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_backup_entry(e))
         .filter_map(Result::ok)
         .filter(|e| e.file_type().is_file() && e.path().extension().map_or(false, |ext| ext == "json"))
     {
@@ -65,89 +141,100 @@ pub fn extract_translatable_strings_from_project(
             Err(_) => file_name_str.to_string(), // Fallback, though ideally strip_prefix should work
         };
         
-        // Using eprintln for temporary debugging output, can be removed later
-        eprintln!("Processing file: {}", relative_file_path);
+        if ignored.is_ignored(&relative_file_path) {
+            eprintln!("Skipping ignored file: {}", relative_file_path);
+            continue;
+        }
+        if !filter.allows_file(&relative_file_path) {
+            continue;
+        }
+        data_files.push(DataFile {
+            path: file_path.to_path_buf(),
+            file_name: file_name_str.to_string(),
+            relative_path: relative_file_path,
+        });
+    }
 
-        match fs::read_to_string(file_path) {
-            Ok(_content) => {
-                if file_name_str == "Actors.json" {
-                    match actors::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut actor_strings) => all_strings.append(&mut actor_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Items.json" {
-                    match items::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut item_strings) => all_strings.append(&mut item_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Armors.json" {
-                    match armors::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut armor_strings) => all_strings.append(&mut armor_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Weapons.json" {
-                    match weapons::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut weapon_strings) => all_strings.append(&mut weapon_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Skills.json" {
-                    match skills::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut skill_strings) => all_strings.append(&mut skill_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Enemies.json" {
-                    match enemies::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut enemy_strings) => all_strings.append(&mut enemy_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "CommonEvents.json" {
-                    match common_events::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut common_event_strings) => all_strings.append(&mut common_event_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Troops.json" {
-                    match troops::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut troop_strings) => all_strings.append(&mut troop_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "System.json" {
-                    match system::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut system_strings) => all_strings.append(&mut system_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "MapInfos.json" {
-                    match map_infos::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut map_info_strings) => all_strings.append(&mut map_info_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "Classes.json" {
-                    match classes::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut class_strings) => all_strings.append(&mut class_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str == "States.json" {
-                    match states::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut state_strings) => all_strings.append(&mut state_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else if file_name_str.starts_with("Map") && file_name_str.ends_with(".json") && file_name_str != "MapInfos.json" {
-                    match maps::extract_strings(&_content, &relative_file_path) {
-                        Ok(mut map_strings) => all_strings.append(&mut map_strings),
-                        Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
-                    }
-                } else {
-                    // Placeholder for other files or unhandled files
-                    // You might want to log these or handle them specifically later
-                    if file_name_str != "Actors.json" { // Only log if it's not the one we are processing
-                        eprintln!("Skipping file (no specific parser implemented yet or intentionally skipped): {}", relative_file_path);
-                    }
-                }
+    // Files are parsed in parallel; their strings are kept in walk order so extractions stay
+    // comparable.
+    let extract_file =
+        |file: &DataFile| extract_data_file(file, schema.as_ref(), &overrides, script_call_matcher.as_ref());
+    let extracted = parallel::extract_files_until(&data_files, should_stop, extract_file);
+    if extracted.len() < data_files.len() {
+        eprintln!("Extraction stopped after {} of {} data files.", extracted.len(), data_files.len());
+    }
+    for mut file in extracted {
+        all_strings.append(&mut file.strings);
+        schema_warnings.append(&mut file.schema_warnings);
+        parsing_errors.extend(file.error);
+    }
+
+    // Plugin parameters live next to the data folder, in www/js (MV) or js (MZ).
+    let plugins_file = Path::new(data_dir).parent().unwrap_or(Path::new("")).join("js").join("plugins.js");
+    let plugins_path = Path::new(project_path).join(&plugins_file);
+    let plugins_relative_path = plugins_file.to_str().unwrap_or_default().replace('\\', "/");
+    let mut extra_files: Vec<(String, FileParser)> = Vec::new();
+    if plugins_path.is_file() && !ignored.is_ignored(&plugins_relative_path) && filter.allows_file(&plugins_relative_path) {
+        let parser = overrides.parser_for(&plugins_relative_path).unwrap_or(FileParser::Plugins);
+        extra_files.push((plugins_relative_path.clone(), parser));
+    }
+    // Other files (text assets, custom plugin scripts) are only extracted when an override names them.
+    if !overrides.is_empty() {
+        for entry in WalkDir::new(project_path)
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0 || !(e.path() == data_path || e.file_name() == APP_DATA_DIR_NAME || is_backup_entry(e))
+            })
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+        {
+            let relative_file_path = match entry.path().strip_prefix(project_path) {
+                Ok(p) => p.to_str().unwrap_or_default().replace('\\', "/"),
+                Err(_) => continue,
+            };
+            if relative_file_path == plugins_relative_path
+                || ignored.is_ignored(&relative_file_path)
+                || !filter.allows_file(&relative_file_path)
+            {
+                continue;
             }
-            Err(e) => {
-                parsing_errors.push(format!("Failed to read file {}: {}", relative_file_path, e));
+            if let Some(parser) = overrides.parser_for(&relative_file_path) {
+                extra_files.push((relative_file_path, parser));
             }
         }
     }
+    for (relative_file_path, parser) in extra_files {
+        if should_stop() {
+            break;
+        }
+        eprintln!("Processing file: {}", relative_file_path);
+        match fs::read_to_string(Path::new(project_path).join(&relative_file_path)) {
+            Ok(content) => match extract_with_parser(parser, &content, &relative_file_path) {
+                Ok(mut file_strings) => all_strings.append(&mut file_strings),
+                Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
+            },
+            Err(e) => parsing_errors.push(format!("Failed to read file {}: {}", relative_file_path, e)),
+        }
+    }
+
+    all_strings.retain(|entry| filter.allows_entry(entry));
+
+    // With configured note tags, only their values are extracted from notes.
+    if let Some(matcher) = &note_tag_matcher {
+        all_strings = note_tags::split_note_entries(all_strings, matcher);
+    }
+    let all_strings = do_not_translate.apply(all_strings);
+
+    for warning in &schema_warnings {
+        eprintln!(
+            "Unknown field '{}' in {} ({} object(s), e.g. {}){}",
+            warning.field,
+            warning.source_file,
+            warning.occurrences,
+            warning.example_path,
+            if warning.has_text { " - contains text that is not extracted" } else { "" }
+        );
+    }
 
     if !parsing_errors.is_empty() {
         // Depending on strictness, you might return Err here or just log warnings.
@@ -159,16 +246,211 @@ pub fn extract_translatable_strings_from_project(
     Ok(all_strings)
 }
 
+/// A JSON file of the data folder, passed the ignore list and the extraction filter.
+struct DataFile {
+    path: PathBuf,
+    file_name: String,
+    relative_path: String,
+}
+
+/// What one data file gave: its strings, unknown fields, and the error that stopped it if any.
+#[derive(Default)]
+struct FileExtraction {
+    strings: Vec<SourceStringData>,
+    schema_warnings: Vec<SchemaWarning>,
+    error: Option<String>,
+}
+
+fn extract_data_file(
+    file: &DataFile,
+    schema: Option<&SchemaInfo>,
+    overrides: &ParserOverrides,
+    script_call_matcher: Option<&ScriptCallMatcher>,
+) -> FileExtraction {
+    let relative_file_path = file.relative_path.as_str();
+    // Using eprintln for temporary debugging output, can be removed later
+    eprintln!("Processing file: {}", relative_file_path);
+    let content = match fs::read_to_string(&file.path) {
+        Ok(content) => content,
+        Err(e) => {
+            let error = Some(format!("Failed to read file {}: {}", relative_file_path, e));
+            return FileExtraction { error, ..Default::default() };
+        }
+    };
+    let mut result = FileExtraction::default();
+    if let Some(schema) = schema {
+        result.schema_warnings = unknown_fields(&file.file_name, &content, relative_file_path, schema.engine);
+    }
+    let extracted = match overrides.parser_for(relative_file_path) {
+        Some(parser) => Some(extract_with_parser(parser, &content, relative_file_path)),
+        None => extract_strings_from_file_content(&file.file_name, &content, relative_file_path),
+    };
+    // Script calls live in the command lists of maps, common events and troops.
+    let extracted = match (extracted, script_call_matcher) {
+        (Some(Ok(mut file_strings)), Some(matcher))
+            if matches!(file_kind(relative_file_path), FileKind::Maps | FileKind::CommonEvents | FileKind::Troops) =>
+        {
+            Some(script_calls::extract_script_strings(&content, relative_file_path, matcher).map(|mut scripts| {
+                file_strings.append(&mut scripts);
+                file_strings
+            }))
+        }
+        (extracted, _) => extracted,
+    };
+    match extracted {
+        Some(Ok(file_strings)) => result.strings = file_strings,
+        Some(Err(e)) => result.error = Some(format!("Error parsing {}: {}", relative_file_path, e)),
+        None => {
+            // Placeholder for other files or unhandled files
+            // You might want to log these or handle them specifically later
+            eprintln!("Skipping file (no specific parser implemented yet or intentionally skipped): {}", relative_file_path);
+        }
+    }
+    result
+}
+
+/// Extracts the given files (relative to the project root) with one parser, for engines whose
+/// translatable files are listed up front (Wolf RPG dumps, Ren'Py scripts).
+pub fn extract_listed_files(
+    project_path: &str,
+    files: Vec<String>,
+    parser: fn(&str, &str) -> Result<Vec<SourceStringData>, String>,
+    ignored: &IgnoredFiles,
+    filter: &CompiledExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let mut all_strings = Vec::new();
+    let mut parsing_errors = Vec::new();
+    for relative_file_path in files {
+        if should_stop() {
+            eprintln!("Extraction stopped after {} strings.", all_strings.len());
+            break;
+        }
+        if ignored.is_ignored(&relative_file_path) || !filter.allows_file(&relative_file_path) {
+            continue;
+        }
+        eprintln!("Processing file: {}", relative_file_path);
+        match fs::read_to_string(Path::new(project_path).join(&relative_file_path)) {
+            Ok(content) => match parser(&content, &relative_file_path) {
+                Ok(mut file_strings) => all_strings.append(&mut file_strings),
+                Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
+            },
+            Err(e) => parsing_errors.push(format!("Failed to read file {}: {}", relative_file_path, e)),
+        }
+    }
+    all_strings.retain(|entry| filter.allows_entry(entry));
+    if !parsing_errors.is_empty() {
+        eprintln!("Errors during string extraction:\n{}", parsing_errors.join("\n"));
+    }
+    Ok(all_strings)
+}
+
+/// Backup folders and copies of data files (e.g. "data_backup", "Map001 - Copy.json") would
+/// otherwise be extracted alongside the files the game actually loads.
+fn is_backup_entry(entry: &walkdir::DirEntry) -> bool {
+    let Some(name) = entry.file_name().to_str() else { return false };
+    let is_backup = if entry.file_type().is_dir() { is_backup_dir_name(name) } else { is_backup_file_name(name) };
+    if is_backup {
+        eprintln!("Skipping backup copy: {:?}", entry.path());
+    }
+    is_backup
+}
+
+/// Extracts a file with the parser a project override assigned to it.
+pub fn extract_with_parser(
+    parser: FileParser,
+    content: &str,
+    relative_file_path: &str,
+) -> Result<Vec<SourceStringData>, String> {
+    match parser {
+        FileParser::PlainText => plain_text::extract_strings(content, relative_file_path),
+        FileParser::Plugins => plugins::extract_strings(content, relative_file_path),
+    }
+}
+
+/// Dispatches the content of a single `www/data` file (or `plugins.js`, a `.txt` asset or a Wolf
+/// RPG text dump) to the matching parser.
+/// Returns `None` for files no parser handles.
+pub fn extract_strings_from_file_content(
+    file_name: &str,
+    content: &str,
+    relative_file_path: &str,
+) -> Option<Result<Vec<SourceStringData>, String>> {
+    let result = match file_name {
+        "Actors.json" => actors::extract_strings(content, relative_file_path),
+        "Items.json" => items::extract_strings(content, relative_file_path),
+        "Armors.json" => armors::extract_strings(content, relative_file_path),
+        "Weapons.json" => weapons::extract_strings(content, relative_file_path),
+        "Skills.json" => skills::extract_strings(content, relative_file_path),
+        "Enemies.json" => enemies::extract_strings(content, relative_file_path),
+        "CommonEvents.json" => common_events::extract_strings(content, relative_file_path),
+        "Troops.json" => troops::extract_strings(content, relative_file_path),
+        "System.json" => system::extract_strings(content, relative_file_path),
+        "MapInfos.json" => map_infos::extract_strings(content, relative_file_path),
+        "Classes.json" => classes::extract_strings(content, relative_file_path),
+        "States.json" => states::extract_strings(content, relative_file_path),
+        "plugins.js" => plugins::extract_strings(content, relative_file_path),
+        _ if file_name.ends_with(".txt") && wolfrpg::dump::is_dump(content) => {
+            wolfrpg::dump::extract_strings(content, relative_file_path)
+        }
+        _ if file_name.ends_with(".txt") => plain_text::extract_strings(content, relative_file_path),
+        _ if file_name.ends_with(".ks") => tyrano::scenario::extract_strings(content, relative_file_path),
+        _ if file_name.starts_with("Map") && file_name.ends_with(".json") => {
+            maps::extract_strings(content, relative_file_path)
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Reconstructs a file with the parser a project override assigned to it.
+pub fn reconstruct_with_parser(
+    parser: FileParser,
+    original_content: &str,
+    translations: Vec<&WorkingTranslation>,
+) -> Result<String, CoreError> {
+    match parser {
+        FileParser::PlainText => plain_text::reconstruct_plain_text(original_content, translations),
+        FileParser::Plugins => plugins::reconstruct_plugins_js(original_content, translations),
+    }
+}
+
+/// Reconstructs a file by its name, with the warnings for translations that had to be skipped.
 pub fn reconstruct_file_content(
     original_json_str: &str,
     relative_file_path: &str,
     translations: Vec<&WorkingTranslation>,
+) -> Result<ReconstructionReport<String>, CoreError> {
+    let (output, warnings) = warnings::collect(relative_file_path, || {
+        dispatch_file_reconstruction(original_json_str, relative_file_path, translations)
+    });
+    Ok(ReconstructionReport { output: output?, warnings })
+}
+
+fn dispatch_file_reconstruction(
+    original_json_str: &str,
+    relative_file_path: &str,
+    translations: Vec<&WorkingTranslation>,
 ) -> Result<String, CoreError> {
     let file_name = Path::new(relative_file_path)
         .file_name()
         .and_then(|name| name.to_str())
         .unwrap_or("");
 
+    // Script call strings and note tag entries are merged back into whole lines and notes first.
+    let scripts = script_calls::merge_script_string_translations(original_json_str, relative_file_path, &translations)
+        .map_err(CoreError::JsonParse)?;
+    let translations = match &scripts {
+        Some(scripts) => scripts.iter().collect(),
+        None => translations,
+    };
+    let merged = note_tags::merge_note_tag_translations(original_json_str, relative_file_path, &translations)
+        .map_err(CoreError::JsonParse)?;
+    let translations = match &merged {
+        Some(merged) => merged.iter().collect(),
+        None => translations,
+    };
+
     // Placeholder calls to specific reconstructors (Sub-Task 6.4)
     // These functions (e.g., super::actors::reconstruct_actors_json) do not exist yet.
     // For the dispatcher to compile and be callable, we'll return Unimplemented error for now.
@@ -185,10 +467,24 @@ pub fn reconstruct_file_content(
         "MapInfos.json" => super::map_infos::reconstruct_map_infos_json(original_json_str, translations),
         "Classes.json" => super::classes::reconstruct_classes_json(original_json_str, translations),
         "States.json" => super::states::reconstruct_states_json(original_json_str, translations),
+        "plugins.js" => super::plugins::reconstruct_plugins_js(original_json_str, translations),
+        _ if file_name.ends_with(".txt") && wolfrpg::dump::is_dump(original_json_str) => {
+            wolfrpg::dump::reconstruct_dump(original_json_str, translations)
+        }
+        _ if file_name.ends_with(".txt") => super::plain_text::reconstruct_plain_text(original_json_str, translations),
+        _ if file_name.ends_with(".ks") => tyrano::scenario::reconstruct_scenario(original_json_str, translations),
         _ if file_name.starts_with("Map") && file_name.ends_with(".json") => {
             // Pass the file_name itself for more specific error logging inside reconstruct_map_json
             super::maps::reconstruct_map_json(original_json_str, translations, file_name)
         }
+        _ if file_name.ends_with(".rvdata2") => Err(CoreError::Unimplemented(format!(
+            "VX Ace data files are binary; {} is rebuilt by reconstruct_project_files",
+            relative_file_path
+        ))),
+        _ if file_name.ends_with(".rpy") => Err(CoreError::Unimplemented(format!(
+            "Ren'Py scripts are not rewritten; generate translate blocks for {} with generate_renpy_translations_command",
+            relative_file_path
+        ))),
         _ => Err(CoreError::Unimplemented(format!(
             "Reconstruction dispatch not implemented for file type: {}",
             file_name
@@ -200,3 +496,89 @@ pub fn reconstruct_file_content(
     //     // ... other cases ...
     // }
 }
+
+/// Result of reconstructing every file touched by a set of translations.
+#[derive(Debug, Default)]
+pub struct ReconstructedProject {
+    // Relative file path (e.g. "www/data/Map001.json") -> reconstructed content.
+    pub files: HashMap<String, String>,
+    // Relative file path -> reconstructed content of binary files (VX Ace `.rvdata2`).
+    pub binary_files: HashMap<String, Vec<u8>>,
+    // Files that could not be read or reconstructed; other files are still processed.
+    pub errors: Vec<String>,
+}
+
+/// Groups translations by source file, reads each original file from the project and
+/// reconstructs it in memory. Nothing is written to disk. Translations a file reconstructor had
+/// to skip are reported as warnings; files that failed entirely are in `output.errors`.
+pub fn reconstruct_project_files(
+    project_path: &str,
+    translated_entries: &[WorkingTranslation],
+) -> ReconstructionReport<ReconstructedProject> {
+    let mut grouped_translations: HashMap<String, Vec<&WorkingTranslation>> = HashMap::new();
+    for entry in translated_entries {
+        grouped_translations.entry(entry.source_file.clone()).or_default().push(entry);
+    }
+
+    let mut result = ReconstructedProject::default();
+    let mut all_warnings = Vec::new();
+    let (overrides, generic_rules) = load_project_config(project_path)
+        .and_then(|config| {
+            Ok((ParserOverrides::new(&config.parser_overrides)?, GenericRules::new(&config.generic_rules)?))
+        })
+        .unwrap_or_else(|e| {
+            result.errors.push(format!("Parser overrides and generic rules not applied: {}", e));
+            (ParserOverrides::default(), GenericRules::default())
+        });
+    for (relative_file_path, entries_for_file) in grouped_translations {
+        let original_file_full_path = Path::new(project_path).join(&relative_file_path);
+
+        let original_content_bytes = match fs::read(&original_file_full_path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                result.errors.push(format!(
+                    "Failed to read original file {}: {}",
+                    original_file_full_path.display(),
+                    e
+                ));
+                continue; // Skip this file
+            }
+        };
+        if relative_file_path.ends_with(&format!(".{}", rpgvxace::project::DATA_EXTENSION)) {
+            let (reconstructed, file_warnings) = warnings::collect(&relative_file_path, || {
+                rpgvxace::project::reconstruct_data_file(&original_content_bytes, entries_for_file)
+            });
+            all_warnings.extend(file_warnings);
+            match reconstructed {
+                Ok(bytes) => {
+                    result.binary_files.insert(relative_file_path, bytes);
+                }
+                Err(core_error) => {
+                    result.errors.push(format!("Error reconstructing file {}: {}", relative_file_path, core_error));
+                }
+            }
+            continue;
+        }
+        let original_content_str = String::from_utf8_lossy(&original_content_bytes).to_string();
+
+        let (reconstructed, file_warnings) = warnings::collect(&relative_file_path, || {
+            match overrides.parser_for(&relative_file_path) {
+                Some(parser) => reconstruct_with_parser(parser, &original_content_str, entries_for_file),
+                None if generic_rules.selectors_for(&relative_file_path).is_some() => {
+                    generic::project::reconstruct_file(&original_content_str, &relative_file_path, entries_for_file)
+                }
+                None => dispatch_file_reconstruction(&original_content_str, &relative_file_path, entries_for_file),
+            }
+        });
+        all_warnings.extend(file_warnings);
+        match reconstructed {
+            Ok(reconstructed_json_string) => {
+                result.files.insert(relative_file_path, reconstructed_json_string);
+            }
+            Err(core_error) => {
+                result.errors.push(format!("Error reconstructing file {}: {}", relative_file_path, core_error));
+            }
+        }
+    }
+    ReconstructionReport { output: result, warnings: all_warnings }
+}
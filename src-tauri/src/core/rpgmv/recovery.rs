@@ -0,0 +1,200 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+use crate::error::CoreError;
+use crate::models::session::RecoveryReport;
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+use super::project::extract_strings_from_file_content;
+
+/// `translation_source` of entries rebuilt from a translated output.
+pub const RECOVERED_SOURCE: &str = "recovered";
+
+const DATA_DIR: &str = "www/data/";
+
+/// `www/data` files of a project folder, keyed by relative path (e.g. "www/data/Map001.json").
+pub fn read_project_data_files(project_path: &Path) -> Result<HashMap<String, String>, CoreError> {
+    let data_path = project_path.join("www").join("data");
+    if !data_path.is_dir() {
+        return Err(CoreError::Io(format!("Data directory not found: {:?}", data_path)));
+    }
+    let mut files = HashMap::new();
+    for entry in WalkDir::new(&data_path)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "json"))
+    {
+        let Some(file_name) = entry.file_name().to_str() else { continue };
+        let content = fs::read_to_string(entry.path())
+            .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", entry.path(), e)))?;
+        files.insert(format!("{}{}", DATA_DIR, file_name), content);
+    }
+    Ok(files)
+}
+
+/// `www/data` files of a ZIP produced by the export (or any archive with that layout,
+/// possibly nested under a top-level folder).
+fn read_zip_data_files(zip_path: &Path) -> Result<HashMap<String, String>, CoreError> {
+    let file = File::open(zip_path).map_err(|e| CoreError::Io(format!("Failed to open {:?}: {}", zip_path, e)))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| CoreError::Zip(format!("Failed to read ZIP archive {:?}: {}", zip_path, e)))?;
+    let mut files = HashMap::new();
+    for index in 0..archive.len() {
+        let mut zip_file = archive
+            .by_index(index)
+            .map_err(|e| CoreError::Zip(format!("Failed to read ZIP entry {}: {}", index, e)))?;
+        let name = zip_file.name().replace('\\', "/");
+        let Some(start) = name.find(DATA_DIR) else { continue };
+        let relative_path = name[start..].to_string();
+        if !zip_file.is_file() || !relative_path.ends_with(".json") || relative_path[DATA_DIR.len()..].contains('/') {
+            continue;
+        }
+        let mut content = String::new();
+        zip_file
+            .read_to_string(&mut content)
+            .map_err(|e| CoreError::Io(format!("Failed to read {} from ZIP: {}", relative_path, e)))?;
+        files.insert(relative_path, content);
+    }
+    Ok(files)
+}
+
+/// Reads the data files of a translated output, either a folder with the project layout or
+/// the exported `.zip`.
+pub fn read_output_data_files(output_path: &Path) -> Result<HashMap<String, String>, CoreError> {
+    if output_path.is_file() {
+        read_zip_data_files(output_path)
+    } else {
+        read_project_data_files(output_path)
+    }
+}
+
+fn extract_file(relative_path: &str, content: &str) -> Option<Result<Vec<SourceStringData>, String>> {
+    let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+    extract_strings_from_file_content(file_name, content, relative_path)
+}
+
+/// Re-extracts the original and the translated data files and aligns their entries by
+/// (file, json_path). Entries whose text changed become recovered translations; unchanged or
+/// missing ones are kept with an empty translation so the session still covers the project.
+pub fn align_translations(
+    original_files: &HashMap<String, String>,
+    output_files: &HashMap<String, String>,
+) -> (Vec<WorkingTranslation>, RecoveryReport) {
+    let mut report = RecoveryReport::default();
+    let mut translations = Vec::new();
+
+    let mut file_names: Vec<&String> = original_files.keys().collect();
+    file_names.sort();
+    for relative_path in file_names {
+        let originals = match extract_file(relative_path, &original_files[relative_path]) {
+            Some(Ok(strings)) => strings,
+            Some(Err(e)) => {
+                report.errors.push(format!("{}: {}", relative_path, e));
+                continue;
+            }
+            None => continue,
+        };
+
+        let mut translated_by_path: HashMap<String, String> = HashMap::new();
+        if let Some(content) = output_files.get(relative_path) {
+            match extract_file(relative_path, content) {
+                Some(Ok(strings)) => {
+                    report.files_compared += 1;
+                    translated_by_path = strings.into_iter().map(|s| (s.json_path, s.original_text)).collect();
+                }
+                Some(Err(e)) => report.errors.push(format!("{} (output): {}", relative_path, e)),
+                None => {}
+            }
+        }
+
+        let original_paths: HashSet<&str> = originals.iter().map(|s| s.json_path.as_str()).collect();
+        report.unmatched_in_output +=
+            translated_by_path.keys().filter(|path| !original_paths.contains(path.as_str())).count();
+
+        for original in &originals {
+            let translated_text = match translated_by_path.get(&original.json_path) {
+                Some(text) if *text != original.original_text => {
+                    report.recovered += 1;
+                    text.clone()
+                }
+                Some(_) => {
+                    report.unchanged += 1;
+                    String::new()
+                }
+                None => {
+                    report.missing_in_output += 1;
+                    String::new()
+                }
+            };
+            translations.push(WorkingTranslation {
+                object_id: original.object_id,
+                original_text: original.original_text.clone(),
+                translated_text,
+                source_file: original.source_file.clone(),
+                json_path: original.json_path.clone(),
+                translation_source: RECOVERED_SOURCE.to_string(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            });
+        }
+    }
+    (translations, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::zip_service::create_zip_archive_from_memory;
+    use tempfile::tempdir;
+
+    fn items(names: &[&str]) -> String {
+        let items: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| format!(r#"{{"id":{},"name":"{}","description":"","note":""}}"#, i + 1, name))
+            .collect();
+        format!("[null,{}]", items.join(","))
+    }
+
+    #[test]
+    fn test_align_translations() {
+        let original = HashMap::from([
+            ("www/data/Items.json".to_string(), items(&["ポーション", "エーテル", "OK"])),
+            ("www/data/Weapons.json".to_string(), items(&["剣"])),
+        ]);
+        let output = HashMap::from([("www/data/Items.json".to_string(), items(&["Potion", "Ether", "OK", "Elixir"]))]);
+
+        let (translations, report) = align_translations(&original, &output);
+        assert_eq!(
+            report,
+            RecoveryReport {
+                files_compared: 1,
+                recovered: 2,
+                unchanged: 1,
+                missing_in_output: 1,
+                unmatched_in_output: 1,
+                ..Default::default()
+            }
+        );
+        let potion = translations.iter().find(|t| t.json_path == "[1].name").unwrap();
+        assert_eq!((potion.translated_text.as_str(), potion.translation_source.as_str()), ("Potion", RECOVERED_SOURCE));
+        assert!(translations.iter().find(|t| t.original_text == "剣").unwrap().translated_text.is_empty());
+    }
+
+    #[test]
+    fn test_read_output_from_zip() {
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("translated_project_output.zip");
+        let data = HashMap::from([
+            ("www/data/Items.json".to_string(), items(&["Potion"])),
+            ("www/img/readme.txt".to_string(), String::new()),
+        ]);
+        create_zip_archive_from_memory(&data, &zip_path).unwrap();
+
+        let files = read_output_data_files(&zip_path).unwrap();
+        assert_eq!(files.keys().collect::<Vec<_>>(), vec!["www/data/Items.json"]);
+    }
+}
@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::core::config::PROJECT_CONFIG_FILE_NAME;
+use crate::error::CoreError;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleProject {
+    pub project_path: String,
+    // Relative paths of the generated files.
+    pub files: Vec<String>,
+}
+
+fn command(code: i32, indent: i32, parameters: Value) -> Value {
+    json!({ "code": code, "indent": indent, "parameters": parameters })
+}
+
+fn end_of_list() -> Value {
+    command(0, 0, json!([]))
+}
+
+fn event(id: u32, name: &str, x: u32, y: u32, list: Vec<Value>) -> Value {
+    json!({
+        "id": id, "name": name, "note": "", "x": x, "y": y,
+        "pages": [{
+            "conditions": {
+                "actorId": 1, "actorValid": false, "itemId": 1, "itemValid": false, "selfSwitchCh": "A",
+                "selfSwitchValid": false, "switch1Id": 1, "switch1Valid": false, "switch2Id": 1,
+                "switch2Valid": false, "variableId": 1, "variableValid": false, "variableValue": 0
+            },
+            "directionFix": false,
+            "image": { "characterIndex": 0, "characterName": "People1", "direction": 2, "pattern": 1, "tileId": 0 },
+            "list": list,
+            "moveFrequency": 3, "moveRoute": { "list": [{ "code": 0, "parameters": [] }], "repeat": true, "skippable": false, "wait": false },
+            "moveSpeed": 3, "moveType": 0, "priorityType": 1, "stepAnime": false, "through": false, "trigger": 0, "walkAnime": true
+        }]
+    })
+}
+
+fn map(display_name: &str, events: Vec<Value>) -> Value {
+    let (width, height) = (17, 13);
+    let mut all_events = vec![Value::Null];
+    all_events.extend(events);
+    json!({
+        "autoplayBgm": false, "autoplayBgs": false, "battleback1Name": "", "battleback2Name": "",
+        "bgm": { "name": "", "pan": 0, "pitch": 100, "volume": 90 },
+        "bgs": { "name": "", "pan": 0, "pitch": 100, "volume": 90 },
+        "disableDashing": false, "displayName": display_name, "encounterList": [], "encounterStep": 30,
+        "height": height, "note": "", "parallaxLoopX": false, "parallaxLoopY": false, "parallaxName": "",
+        "parallaxShow": true, "parallaxSx": 0, "parallaxSy": 0, "scrollType": 0, "specifyBattleback": false,
+        "tilesetId": 1, "width": width,
+        "data": vec![0; width * height * 6],
+        "events": all_events
+    })
+}
+
+/// Data files of the sample game: two maps with dialogue and choices, actors and items with
+/// tagged notes, a common event and the System terms.
+fn sample_data_files() -> Vec<(&'static str, Value)> {
+    let village = map("はじまりの村", vec![
+        event(1, "村長", 8, 6, vec![
+            command(101, 0, json!(["Actor1", 0, 0, 2, "村長"])),
+            command(401, 0, json!(["ようこそ、旅の方。"])),
+            command(401, 0, json!(["この村は小さいが、平和なところじゃ。"])),
+            command(102, 0, json!([["話を聞く", "立ち去る"], 1, 0, 2, 0])),
+            command(402, 0, json!([0, "話を聞く"])),
+            command(101, 1, json!(["Actor1", 0, 0, 2, "村長"])),
+            command(401, 1, json!(["北の森には魔物が出る。気をつけるのじゃぞ。"])),
+            command(0, 1, json!([])),
+            command(402, 0, json!([1, "立ち去る"])),
+            command(101, 1, json!(["", 0, 0, 2, ""])),
+            command(401, 1, json!(["\\N[1]は会釈して立ち去った。"])),
+            command(0, 1, json!([])),
+            command(404, 0, json!([])),
+            end_of_list(),
+        ]),
+        event(2, "宝箱", 4, 9, vec![
+            command(101, 0, json!(["", 0, 0, 2, ""])),
+            command(401, 0, json!(["\\I[176]ポーションを手に入れた！"])),
+            command(126, 0, json!([1, 0, 0, 1])),
+            end_of_list(),
+        ]),
+    ]);
+    let forest = map("北の森", vec![
+        event(1, "看板", 8, 10, vec![
+            command(101, 0, json!(["", 0, 1, 2, ""])),
+            command(401, 0, json!(["この先、魔物注意。"])),
+            end_of_list(),
+        ]),
+    ]);
+    let actor = |id: u32, name: &str, nickname: &str, profile: &str, note: &str| {
+        json!({
+            "id": id, "battlerName": format!("Actor1_{}", id), "characterIndex": id - 1, "characterName": "Actor1",
+            "classId": 1, "equips": [1, 1, 2, 3, 0], "faceIndex": id - 1, "faceName": "Actor1", "traits": [],
+            "initialLevel": 1, "maxLevel": 99, "name": name, "nickname": nickname, "note": note, "profile": profile
+        })
+    };
+    let item = |id: u32, name: &str, description: &str, note: &str, price: u32| {
+        json!({
+            "id": id, "animationId": 41, "consumable": true,
+            "damage": { "critical": false, "elementId": 0, "formula": "0", "type": 0, "variance": 20 },
+            "description": description, "effects": [], "hitType": 0, "iconIndex": 176, "itypeId": 1,
+            "name": name, "note": note, "occasion": 0, "price": price, "repeats": 1, "scope": 7,
+            "speed": 0, "successRate": 100, "tpGain": 0
+        })
+    };
+    vec![
+        ("Actors.json", json!([
+            null,
+            actor(1, "ハロルド", "見習い剣士", "村で育った心優しい青年。\n剣の腕はまだまだ。", "<拡張説明:村一番の働き者。>"),
+            actor(2, "マーシャ", "森の魔法使い", "森に住む魔法使いの少女。", ""),
+        ])),
+        ("Items.json", json!([
+            null,
+            item(1, "ポーション", "HPを500回復する。", "<拡張説明:苦いが効き目は確か。>", 50),
+            item(2, "毒消し草", "毒状態を治す薬草。", "", 20),
+        ])),
+        ("MapInfos.json", json!([
+            null,
+            { "id": 1, "expanded": false, "name": "村", "order": 1, "parentId": 0, "scrollX": 0, "scrollY": 0 },
+            { "id": 2, "expanded": false, "name": "森", "order": 2, "parentId": 0, "scrollX": 0, "scrollY": 0 },
+        ])),
+        ("Map001.json", village),
+        ("Map002.json", forest),
+        ("CommonEvents.json", json!([
+            null,
+            {
+                "id": 1, "name": "宿屋", "switchId": 1, "trigger": 0,
+                "list": [
+                    command(101, 0, json!(["", 0, 0, 2, "宿屋の主人"])),
+                    command(401, 0, json!(["一晩10Gだよ。泊まっていくかい？"])),
+                    command(102, 0, json!([["泊まる", "やめる"], 1, 0, 2, 0])),
+                    command(402, 0, json!([0, "泊まる"])),
+                    command(314, 1, json!([0, 0])),
+                    command(0, 1, json!([])),
+                    command(402, 0, json!([1, "やめる"])),
+                    command(0, 1, json!([])),
+                    command(404, 0, json!([])),
+                    end_of_list(),
+                ]
+            },
+        ])),
+        ("System.json", json!({
+            "gameTitle": "はじめての翻訳",
+            "currencyUnit": "G",
+            "locale": "ja_JP",
+            "armorTypes": ["", "一般防具", "魔法防具"],
+            "elements": ["", "物理", "炎", "氷"],
+            "equipTypes": ["", "武器", "盾", "頭", "身体", "装飾品"],
+            "skillTypes": ["", "魔法", "必殺技"],
+            "weaponTypes": ["", "短剣", "剣"],
+            "switches": ["", "宿屋に泊まった"],
+            "variables": ["", ""],
+            "partyMembers": [1, 2],
+            "startMapId": 1, "startX": 8, "startY": 8,
+            "terms": {
+                "basic": ["レベル", "Lv", "HP", "HP", "MP", "MP", "TP", "TP", "経験値", "EXP"],
+                "commands": ["戦う", "逃げる", "攻撃", "防御", "アイテム", "スキル", "装備", "ステータス", "並び替え", "セーブ", "ゲーム終了", "オプション", "武器", "防具", "大事なもの", "装備", "最強装備", "全て外す", "ニューゲーム", "コンティニュー", null, "タイトルへ", "やめる", null, "購入する", "売却する"],
+                "params": ["最大HP", "最大MP", "攻撃力", "防御力", "魔法力", "魔法防御", "敏捷性", "運", "命中率", "回避率"],
+                "messages": {
+                    "actorDamage": "%1は %2 のダメージを受けた！",
+                    "levelUp": "%1は%2 %3 に上がった！",
+                    "obtainItem": "%1を手に入れた！",
+                    "victory": "%1の勝利！",
+                    "saveMessage": "どのファイルにセーブしますか？",
+                    "loadMessage": "どのファイルをロードしますか？"
+                }
+            }
+        })),
+    ]
+}
+
+/// Writes a tiny RPG Maker MV game into `target_dir` (created if needed) for trying extraction,
+/// translation and reconstruction without touching a real game. The folder must be empty.
+pub fn generate_sample_project(target_dir: &str) -> Result<SampleProject, CoreError> {
+    let root = Path::new(target_dir);
+    if root.exists() && fs::read_dir(root)?.next().is_some() {
+        return Err(CoreError::Custom(format!("{} is not empty; choose an empty folder for the sample project", target_dir)));
+    }
+
+    let mut files: Vec<(String, String)> = vec![
+        ("Game.rpgproject".to_string(), "RPGMV 1.6.2".to_string()),
+        ("www/js/rpg_core.js".to_string(), "Utils.RPGMAKER_VERSION = \"1.6.2\";\n".to_string()),
+        ("www/js/plugins.js".to_string(), "var $plugins =\n[\n];\n".to_string()),
+        (
+            PROJECT_CONFIG_FILE_NAME.to_string(),
+            serde_json::to_string_pretty(&json!({
+                "sourceLanguage": "ja",
+                "targetLanguage": "en",
+                "noteTags": { "tagPatterns": ["拡張説明"] }
+            }))
+            .map_err(|e| CoreError::JsonSerialize(e.to_string()))?,
+        ),
+    ];
+    for (file_name, data) in sample_data_files() {
+        let json = serde_json::to_string(&data).map_err(|e| CoreError::JsonSerialize(e.to_string()))?;
+        files.push((format!("www/data/{}", file_name), json));
+    }
+
+    for (relative_path, content) in &files {
+        let path = root.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| CoreError::Io(format!("Failed to write sample file {}: {}", path.display(), e)))?;
+    }
+    Ok(SampleProject {
+        project_path: target_dir.to_string(),
+        files: files.into_iter().map(|(relative_path, _)| relative_path).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::game_detection::{detect_rpg_maker_mv, RpgMakerDetectionResult};
+    use crate::core::rpgmv::project::extract_translatable_strings_from_project;
+    use crate::models::extraction::ExtractionFilter;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generated_sample_project_extracts() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().join("sample");
+        let project_path = project_path.to_str().unwrap();
+        let sample = generate_sample_project(project_path).unwrap();
+        assert!(sample.files.contains(&"www/data/Map001.json".to_string()));
+        assert!(matches!(detect_rpg_maker_mv(project_path), RpgMakerDetectionResult::DetectedByProjectFile));
+
+        let strings = extract_translatable_strings_from_project(project_path, &ExtractionFilter::default()).unwrap();
+        let texts: Vec<&str> = strings.iter().map(|s| s.original_text.as_str()).collect();
+        for expected in ["ハロルド", "ようこそ、旅の方。", "話を聞く", "村一番の働き者。", "宿屋の主人", "はじめての翻訳"] {
+            assert!(texts.contains(&expected), "{} not extracted from {:?}", expected, texts);
+        }
+        assert!(generate_sample_project(project_path).is_err());
+    }
+}
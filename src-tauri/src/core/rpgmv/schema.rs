@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::CoreError;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpgMakerEngine {
+    Mv,
+    Mz,
+}
+
+/// Which engine (and core script version) produced a project's data files.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaInfo {
+    pub engine: RpgMakerEngine,
+    // `Utils.RPGMAKER_VERSION` from the core script, when it could be read.
+    pub core_version: Option<String>,
+    // Data folder relative to the project root: "www/data" for MV, "data" for MZ.
+    pub data_dir: String,
+}
+
+fn read_core_version(script_path: &Path) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let script = fs::read_to_string(script_path).ok()?;
+    let re = RE.get_or_init(|| {
+        Regex::new(r#"RPGMAKER_VERSION\s*=\s*["']([^"']+)["']"#).expect("version pattern is valid")
+    });
+    re.captures(&script).map(|c| c[1].to_string())
+}
+
+/// MV keeps its data in `www/data` with `www/js/rpg_core.js`; MZ uses `data` and `js/rmmz_core.js`
+/// at the project root. A data folder without a core script is classified by System.json
+/// (only MZ has the `advanced` section).
+pub fn detect_schema(project_path: &str) -> Option<SchemaInfo> {
+    let root = Path::new(project_path);
+    if root.join("www").join("data").is_dir() {
+        return Some(SchemaInfo {
+            engine: RpgMakerEngine::Mv,
+            core_version: read_core_version(&root.join("www").join("js").join("rpg_core.js")),
+            data_dir: "www/data".to_string(),
+        });
+    }
+    if !root.join("data").is_dir() {
+        return None;
+    }
+    let mz_script = root.join("js").join("rmmz_core.js");
+    let is_mz = mz_script.is_file()
+        || fs::read_to_string(root.join("data").join("System.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .is_some_and(|system| system.get("advanced").is_some());
+    let (engine, script) = if is_mz {
+        (RpgMakerEngine::Mz, mz_script)
+    } else {
+        (RpgMakerEngine::Mv, root.join("js").join("rpg_core.js"))
+    };
+    Some(SchemaInfo { engine, core_version: read_core_version(&script), data_dir: "data".to_string() })
+}
+
+// Object fields each database file has in MV, and the ones MZ added.
+const ACTOR_FIELDS: &[&str] = &[
+    "id", "battlerName", "characterIndex", "characterName", "classId", "equips", "faceIndex", "faceName",
+    "traits", "initialLevel", "maxLevel", "name", "nickname", "note", "profile",
+];
+const CLASS_FIELDS: &[&str] = &["id", "expParams", "traits", "learnings", "name", "note", "params"];
+const SKILL_FIELDS: &[&str] = &[
+    "id", "animationId", "damage", "description", "effects", "hitType", "iconIndex", "message1", "message2",
+    "mpCost", "name", "note", "occasion", "repeats", "requiredWtypeId1", "requiredWtypeId2", "scope", "speed",
+    "stypeId", "successRate", "tpCost", "tpGain",
+];
+const ITEM_FIELDS: &[&str] = &[
+    "id", "animationId", "consumable", "damage", "description", "effects", "hitType", "iconIndex", "itypeId",
+    "name", "note", "occasion", "price", "repeats", "scope", "speed", "successRate", "tpGain",
+];
+const WEAPON_FIELDS: &[&str] =
+    &["id", "animationId", "description", "etypeId", "traits", "iconIndex", "name", "note", "params", "price", "wtypeId"];
+const ARMOR_FIELDS: &[&str] =
+    &["id", "atypeId", "description", "etypeId", "traits", "iconIndex", "name", "note", "params", "price"];
+const ENEMY_FIELDS: &[&str] =
+    &["id", "actions", "battlerHue", "battlerName", "dropItems", "exp", "traits", "gold", "name", "note", "params"];
+const STATE_FIELDS: &[&str] = &[
+    "id", "autoRemovalTiming", "chanceByDamage", "iconIndex", "maxTurns", "message1", "message2", "message3",
+    "message4", "minTurns", "motion", "name", "note", "overlay", "priority", "releaseByDamage", "removeAtBattleEnd",
+    "removeByDamage", "removeByRestriction", "removeByWalking", "restriction", "stepsToRemove", "traits",
+];
+const TROOP_FIELDS: &[&str] = &["id", "members", "name", "pages"];
+const COMMON_EVENT_FIELDS: &[&str] = &["id", "list", "name", "switchId", "trigger"];
+const MAP_INFO_FIELDS: &[&str] = &["id", "expanded", "name", "order", "parentId", "scrollX", "scrollY"];
+const MAP_FIELDS: &[&str] = &[
+    "autoplayBgm", "autoplayBgs", "battleback1Name", "battleback2Name", "bgm", "bgs", "data", "disableDashing",
+    "displayName", "encounterList", "encounterStep", "events", "height", "note", "parallaxLoopX", "parallaxLoopY",
+    "parallaxName", "parallaxShow", "parallaxSx", "parallaxSy", "scrollType", "specifyBattleback", "tilesetId",
+    "width",
+];
+const MAP_EVENT_FIELDS: &[&str] = &["id", "name", "note", "pages", "x", "y"];
+const MZ_MESSAGE_FIELDS: &[&str] = &["messageType"];
+
+/// Fields of `file_name`'s objects the parsers know about for this engine. `None` for files
+/// that are not checked (System.json has too many engine-specific settings to be useful).
+fn known_fields(file_name: &str, engine: RpgMakerEngine) -> Option<Vec<&'static str>> {
+    let (base, mz_extra): (&[&str], &[&str]) = match file_name {
+        "Actors.json" => (ACTOR_FIELDS, &[]),
+        "Classes.json" => (CLASS_FIELDS, &[]),
+        "Skills.json" => (SKILL_FIELDS, MZ_MESSAGE_FIELDS),
+        "Items.json" => (ITEM_FIELDS, &[]),
+        "Weapons.json" => (WEAPON_FIELDS, &[]),
+        "Armors.json" => (ARMOR_FIELDS, &[]),
+        "Enemies.json" => (ENEMY_FIELDS, &[]),
+        "States.json" => (STATE_FIELDS, MZ_MESSAGE_FIELDS),
+        "Troops.json" => (TROOP_FIELDS, &[]),
+        "CommonEvents.json" => (COMMON_EVENT_FIELDS, &[]),
+        "MapInfos.json" => (MAP_INFO_FIELDS, &[]),
+        _ => return None,
+    };
+    let mut fields = base.to_vec();
+    if engine == RpgMakerEngine::Mz {
+        fields.extend_from_slice(mz_extra);
+    }
+    Some(fields)
+}
+
+/// A field the parsers don't know, aggregated over all objects of a file.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaWarning {
+    pub source_file: String,
+    pub field: String,
+    pub occurrences: usize,
+    // Path of the first object that has the field, e.g. "[3]" or "events[2]".
+    pub example_path: String,
+    // Text values are the ones most likely to need translation.
+    pub has_text: bool,
+}
+
+#[derive(Default)]
+struct FieldTally {
+    warnings: BTreeMap<String, SchemaWarning>,
+}
+
+impl FieldTally {
+    fn check(&mut self, object: &Value, known: &[&str], source_file: &str, path: &str) {
+        let Some(map) = object.as_object() else { return };
+        for (field, value) in map {
+            if known.contains(&field.as_str()) {
+                continue;
+            }
+            let warning = self.warnings.entry(field.clone()).or_insert_with(|| SchemaWarning {
+                source_file: source_file.to_string(),
+                field: field.clone(),
+                occurrences: 0,
+                example_path: path.to_string(),
+                has_text: false,
+            });
+            warning.occurrences += 1;
+            warning.has_text |= value.as_str().is_some_and(|text| !text.trim().is_empty());
+        }
+    }
+}
+
+/// Lists object fields in a data file that the parsers don't know for `engine`, instead of
+/// silently skipping them. Files that fail to parse yield no warnings (the extractor reports those).
+pub fn unknown_fields(file_name: &str, content: &str, source_file: &str, engine: RpgMakerEngine) -> Vec<SchemaWarning> {
+    let Ok(data) = serde_json::from_str::<Value>(content) else { return Vec::new() };
+    let mut tally = FieldTally::default();
+    if let Some(known) = known_fields(file_name, engine) {
+        for (index, object) in data.as_array().into_iter().flatten().enumerate() {
+            tally.check(object, &known, source_file, &format!("[{}]", index));
+        }
+    } else if file_name.starts_with("Map") && file_name.ends_with(".json") {
+        tally.check(&data, MAP_FIELDS, source_file, "");
+        let events = data.get("events").and_then(Value::as_array).into_iter().flatten();
+        for (index, event) in events.enumerate() {
+            tally.check(event, MAP_EVENT_FIELDS, source_file, &format!("events[{}]", index));
+        }
+    }
+    tally.warnings.into_values().collect()
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaReport {
+    pub schema: SchemaInfo,
+    pub warnings: Vec<SchemaWarning>,
+}
+
+/// Detects the project's engine and checks every data file for unknown fields.
+pub fn check_project_schema(project_path: &str) -> Result<SchemaReport, CoreError> {
+    let schema = detect_schema(project_path)
+        .ok_or_else(|| CoreError::Custom(format!("No RPG Maker MV/MZ data folder found in {}", project_path)))?;
+    let data_path = Path::new(project_path).join(&schema.data_dir);
+    let mut file_names: Vec<String> = fs::read_dir(&data_path)
+        .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", data_path, e)))?
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| name.ends_with(".json"))
+        .collect();
+    file_names.sort();
+
+    let mut warnings = Vec::new();
+    for file_name in file_names {
+        let content = fs::read_to_string(data_path.join(&file_name))
+            .map_err(|e| CoreError::Io(format!("Failed to read {}: {}", file_name, e)))?;
+        let source_file = format!("{}/{}", schema.data_dir, file_name);
+        warnings.extend(unknown_fields(&file_name, &content, &source_file, schema.engine));
+    }
+    Ok(SchemaReport { schema, warnings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_mv_and_mz() {
+        let mv = tempdir().unwrap();
+        fs::create_dir_all(mv.path().join("www/data")).unwrap();
+        fs::create_dir_all(mv.path().join("www/js")).unwrap();
+        fs::write(mv.path().join("www/js/rpg_core.js"), "Utils.RPGMAKER_VERSION = \"1.6.2\";").unwrap();
+        let schema = detect_schema(mv.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            schema,
+            SchemaInfo { engine: RpgMakerEngine::Mv, core_version: Some("1.6.2".to_string()), data_dir: "www/data".to_string() }
+        );
+
+        let mz = tempdir().unwrap();
+        fs::create_dir_all(mz.path().join("data")).unwrap();
+        fs::write(mz.path().join("data/System.json"), r#"{"advanced":{"gameId":1}}"#).unwrap();
+        let schema = detect_schema(mz.path().to_str().unwrap()).unwrap();
+        assert_eq!((schema.engine, schema.data_dir.as_str()), (RpgMakerEngine::Mz, "data"));
+
+        assert!(detect_schema(tempdir().unwrap().path().to_str().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_unknown_fields_depend_on_engine() {
+        let skills = r#"[null,
+            {"id":1,"name":"Fire","messageType":1,"description":"","note":""},
+            {"id":2,"name":"Ice","messageType":1,"flavorText":"Cold!"}]"#;
+
+        let mv = unknown_fields("Skills.json", skills, "www/data/Skills.json", RpgMakerEngine::Mv);
+        let fields: Vec<(&str, usize, bool)> = mv.iter().map(|w| (w.field.as_str(), w.occurrences, w.has_text)).collect();
+        assert_eq!(fields, vec![("flavorText", 1, true), ("messageType", 2, false)]);
+        assert_eq!(mv[0].example_path, "[2]");
+
+        let mz = unknown_fields("Skills.json", skills, "data/Skills.json", RpgMakerEngine::Mz);
+        assert_eq!(mz.len(), 1);
+        assert_eq!(mz[0].field, "flavorText");
+    }
+}
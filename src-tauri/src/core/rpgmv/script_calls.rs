@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use regex::Regex;
+use serde_json::Value;
+use crate::error::CoreError;
+use crate::models::project_config::ScriptCallConfig;
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+use super::warnings::{warn, WarningKind};
+
+/// Suffix appended to a script line's json_path for its n-th string literal, e.g.
+/// `[1].list[4].parameters[0]@scriptString[1]`. The index counts every literal of the line, so it
+/// can be resolved without the configuration.
+pub const SCRIPT_STRING_PATH_SUFFIX: &str = "@scriptString";
+
+/// Script (355) and script continuation line (655) commands.
+pub const SCRIPT_CALL_CODES: [i64; 2] = [355, 655];
+
+/// A quoted string literal of a script line, with the byte range of its content (quotes excluded).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLiteral {
+    pub quote: char,
+    pub content: Range<usize>,
+}
+
+/// String literals of a JavaScript line in order of appearance. Template literals with `${}`
+/// placeholders are code rather than text and aren't reported; the scan stops at a `//` comment
+/// or an unterminated literal.
+pub fn parse_string_literals(script: &str) -> Vec<ScriptLiteral> {
+    let mut literals = Vec::new();
+    let mut chars = script.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '/') => break,
+            '\'' | '"' | '`' => {
+                let mut end = None;
+                while let Some((index, inner)) = chars.next() {
+                    if inner == '\\' {
+                        chars.next();
+                    } else if inner == c {
+                        end = Some(index);
+                        break;
+                    }
+                }
+                let Some(end) = end else { break };
+                let content = start + 1..end;
+                if !(c == '`' && script[content.clone()].contains("${")) {
+                    literals.push(ScriptLiteral { quote: c, content });
+                }
+            }
+            _ => {}
+        }
+    }
+    literals
+}
+
+/// The text of a literal's content as the game sees it, e.g. `\\C[2]Hi\n` -> `\C[2]Hi` and a newline.
+pub fn unescape_literal(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// A text written back as the content of a literal quoted with `quote`.
+pub fn escape_literal(text: &str, quote: char) -> String {
+    let mut raw = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => raw.push_str("\\\\"),
+            '\n' => raw.push_str("\\n"),
+            '\r' => raw.push_str("\\r"),
+            '\t' => raw.push_str("\\t"),
+            c if c == quote => {
+                raw.push('\\');
+                raw.push(c);
+            }
+            '$' if quote == '`' => raw.push_str("\\$"),
+            c => raw.push(c),
+        }
+    }
+    raw
+}
+
+/// The configured script call patterns, compiled.
+pub struct ScriptCallMatcher {
+    patterns: Vec<Regex>,
+}
+
+impl ScriptCallMatcher {
+    /// `None` when the configuration has no patterns, i.e. script calls aren't extracted.
+    pub fn new(config: &ScriptCallConfig) -> Result<Option<Self>, CoreError> {
+        let patterns = config
+            .patterns
+            .iter()
+            .filter(|pattern| !pattern.trim().is_empty())
+            .map(|pattern| {
+                Regex::new(pattern.trim())
+                    .map_err(|e| CoreError::Config(format!("Invalid script call pattern '{}': {}", pattern, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!patterns.is_empty()).then_some(ScriptCallMatcher { patterns }))
+    }
+
+    /// Whether the literal opening at byte `quote_start` of `script` is inside a pattern match or
+    /// right after it.
+    fn selects(&self, script: &str, quote_start: usize) -> bool {
+        self.patterns
+            .iter()
+            .any(|re| re.find_iter(script).any(|m| (m.start()..=m.end()).contains(&quote_start)))
+    }
+}
+
+/// A script line of an event command list: (object id of the event, json_path of the line, line).
+type ScriptLine = (u32, String, String);
+
+fn collect_script_lines(value: &Value, path: &str, object_id: u32, lines: &mut Vec<ScriptLine>) {
+    match value {
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_script_lines(item, &format!("{}[{}]", path, index), object_id, lines);
+            }
+        }
+        Value::Object(fields) => {
+            let object_id = fields.get("id").and_then(Value::as_u64).map_or(object_id, |id| id as u32);
+            let is_script = fields.get("code").and_then(Value::as_i64).is_some_and(|code| SCRIPT_CALL_CODES.contains(&code));
+            if let (true, Some(Value::String(line))) = (is_script, fields.get("parameters").and_then(|p| p.get(0))) {
+                lines.push((object_id, format!("{}.parameters[0]", path), line.clone()));
+                return;
+            }
+            for (key, field) in fields {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                collect_script_lines(field, &path, object_id, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Every script line of a data file, wherever its command list is (maps, common events, troops).
+pub fn script_lines(content: &str) -> Result<Vec<ScriptLine>, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("Failed to parse script calls: {}", e))?;
+    let mut lines = Vec::new();
+    collect_script_lines(&value, "", 0, &mut lines);
+    Ok(lines)
+}
+
+/// One entry per string literal of a script call that a configured pattern selects. Blank
+/// literals are left out.
+pub fn extract_script_strings(
+    content: &str,
+    relative_file_path: &str,
+    matcher: &ScriptCallMatcher,
+) -> Result<Vec<SourceStringData>, String> {
+    let mut entries = Vec::new();
+    for (object_id, json_path, line) in script_lines(content)? {
+        for (index, literal) in parse_string_literals(&line).into_iter().enumerate() {
+            let text = unescape_literal(&line[literal.content.clone()]);
+            if text.trim().is_empty() || !matcher.selects(&line, literal.content.start - 1) {
+                continue;
+            }
+            entries.push(SourceStringData {
+                object_id,
+                original_text: text,
+                source_file: relative_file_path.to_string(),
+                json_path: format!("{}{}[{}]", json_path, SCRIPT_STRING_PATH_SUFFIX, index),
+                dialogue_block_id: None,
+                speaker: None,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn split_literal_path(json_path: &str) -> Option<(&str, usize)> {
+    let (base, index) = json_path.split_once(SCRIPT_STRING_PATH_SUFFIX)?;
+    let index = index.strip_prefix('[')?.strip_suffix(']')?.parse().ok()?;
+    Some((base, index))
+}
+
+/// Turns script string entries back into whole script line translations, writing each translated
+/// literal in place of the original content and leaving the code around it untouched. Returns
+/// `None` when there are no script string entries.
+pub fn merge_script_string_translations(
+    original_content: &str,
+    relative_file_path: &str,
+    translations: &[&WorkingTranslation],
+) -> Result<Option<Vec<WorkingTranslation>>, String> {
+    if !translations.iter().any(|t| split_literal_path(&t.json_path).is_some()) {
+        return Ok(None);
+    }
+    let lines: HashMap<String, (u32, String)> = script_lines(original_content)?
+        .into_iter()
+        .map(|(object_id, json_path, line)| (json_path, (object_id, line)))
+        .collect();
+
+    let mut merged = Vec::new();
+    let mut by_line: BTreeMap<&str, Vec<(usize, &WorkingTranslation)>> = BTreeMap::new();
+    for entry in translations {
+        match split_literal_path(&entry.json_path) {
+            Some((base, index)) => by_line.entry(base).or_default().push((index, entry)),
+            None => merged.push((*entry).clone()),
+        }
+    }
+
+    for (base, mut entries) in by_line {
+        let Some((object_id, line)) = lines.get(base) else {
+            warn(WarningKind::SkippedPath, Some(base), format!("Script call not found at {} in {}; skipping its string translations.", base, relative_file_path));
+            continue;
+        };
+        let literals = parse_string_literals(line);
+        entries.sort_by_key(|(index, _)| std::cmp::Reverse(*index));
+        let mut text = line.clone();
+        for (index, entry) in &entries {
+            let Some(literal) = literals.get(*index) else {
+                warn(WarningKind::OutOfBounds, Some(base), format!("Script call at {} in {} has no string {}; skipping.", base, relative_file_path, index));
+                continue;
+            };
+            if entry.error.is_some() || entry.translated_text.is_empty() {
+                continue;
+            }
+            text.replace_range(literal.content.clone(), &escape_literal(&entry.translated_text, literal.quote));
+        }
+        let first = entries[0].1;
+        merged.push(WorkingTranslation {
+            object_id: *object_id,
+            original_text: line.clone(),
+            translated_text: text,
+            source_file: first.source_file.clone(),
+            json_path: base.to_string(),
+            translation_source: first.translation_source.clone(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        });
+    }
+    Ok(Some(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMON_EVENTS: &str = r#"[null,
+        {"id":3,"name":"Shop","list":[
+            {"code":355,"indent":0,"parameters":["ShowText(\"Welcome, \\\\C[2]traveler\\\\C[0]!\", 'shop');"]},
+            {"code":655,"indent":0,"parameters":["$gameVariables.setValue(1, \"ignored\"); // ShowText(\"comment\")"]},
+            {"code":0,"indent":0,"parameters":[]}
+        ]}
+    ]"#;
+
+    fn matcher() -> ScriptCallMatcher {
+        ScriptCallMatcher::new(&ScriptCallConfig { patterns: vec![r"ShowText\(".to_string()] }).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_script_strings_round_trip() {
+        let line = r#"ShowText("Say \"hi\"", `x${y}`, 'it\'s')"#;
+        let literals: Vec<&str> = parse_string_literals(line).iter().map(|l| &line[l.content.clone()]).collect();
+        assert_eq!(literals, vec![r#"Say \"hi\""#, r"it\'s"]);
+        assert_eq!(escape_literal(&unescape_literal(r#"Say \"hi\"\n"#), '"'), r#"Say \"hi\"\n"#);
+
+        let entries = extract_script_strings(COMMON_EVENTS, "www/data/CommonEvents.json", &matcher()).unwrap();
+        let found: Vec<(u32, &str, &str)> =
+            entries.iter().map(|e| (e.object_id, e.json_path.as_str(), e.original_text.as_str())).collect();
+        assert_eq!(found, vec![(3, "[1].list[0].parameters[0]@scriptString[0]", r"Welcome, \C[2]traveler\C[0]!")]);
+
+        let translation = WorkingTranslation {
+            object_id: 3,
+            original_text: entries[0].original_text.clone(),
+            translated_text: r#"Bienvenue, \C[2]voyageur\C[0] "ami" !"#.to_string(),
+            source_file: "www/data/CommonEvents.json".to_string(),
+            json_path: entries[0].json_path.clone(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let merged = merge_script_string_translations(COMMON_EVENTS, "www/data/CommonEvents.json", &[&translation])
+            .unwrap()
+            .unwrap();
+        assert_eq!(merged[0].json_path, "[1].list[0].parameters[0]");
+        assert_eq!(merged[0].translated_text, r#"ShowText("Bienvenue, \\C[2]voyageur\\C[0] \"ami\" !", 'shop');"#);
+
+        assert!(ScriptCallMatcher::new(&ScriptCallConfig::default()).unwrap().is_none());
+        assert!(ScriptCallMatcher::new(&ScriptCallConfig { patterns: vec!["(".to_string()] }).is_err());
+    }
+}
@@ -63,6 +63,7 @@ pub fn reconstruct_skills_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value; 
     // Using the provided Skills.json for test data inspiration
     const TEST_SKILLS_JSON: &str = r#"[
@@ -85,6 +86,7 @@ mod tests {
                 translated_text: "Attaque (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -94,6 +96,7 @@ mod tests {
                 translated_text: " attaque ! (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 10,
@@ -103,6 +106,7 @@ mod tests {
                 translated_text: "Feu (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 10,
@@ -112,6 +116,7 @@ mod tests {
                 translated_text: "Exploite la puissance du feu pour blesser un ennemi. (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 10,
@@ -121,6 +126,7 @@ mod tests {
                 translated_text: " lance %1 ! (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
              WorkingTranslation {
                 object_id: 10,
@@ -130,6 +136,7 @@ mod tests {
                 translated_text: "Élément : Feu\nCoût : 5 PM (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -161,6 +168,7 @@ mod tests {
                 translated_text: "AttackFail".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI error".to_string()), 
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 2, // Guard
@@ -170,6 +178,7 @@ mod tests {
                 translated_text: " se protège. (FR)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -194,6 +203,7 @@ mod tests {
                 translated_text: "Phantom Skill".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -216,6 +226,7 @@ mod tests {
                 translated_text: "Divine Intervention".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -250,6 +261,7 @@ mod tests {
                 translated_text: "Feu".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -0,0 +1,196 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::core::string_store::StringStore;
+use crate::error::CoreError;
+use crate::models::translation::SourceStringData;
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// Kept in the project's app data folder; updated by every extraction.
+pub const EXTRACTION_SNAPSHOT_FILE_NAME: &str = "extraction_snapshot.json";
+
+/// A source file as of the last extraction.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSnapshot {
+    // MD5 of the file; empty when it couldn't be read.
+    pub md5: String,
+    pub entries: Vec<SourceStringData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionSnapshot {
+    // By relative source file path.
+    pub files: BTreeMap<String, FileSnapshot>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionResult {
+    // Every extracted string or, when `incremental`, only the new and changed ones.
+    pub entries: Vec<SourceStringData>,
+    // Strings of the previous extraction that are gone from the files extracted this time (or
+    // from deleted files).
+    pub removed: Vec<SourceStringData>,
+    // False for a full extraction, including an incremental one without a previous snapshot.
+    pub incremental: bool,
+    // Entries sent in chunks instead of in `entries`; 0 unless the extraction was streamed.
+    pub streamed_entries: usize,
+}
+
+fn file_md5(project_path: &str, source_file: &str) -> String {
+    fs::read(Path::new(project_path).join(source_file))
+        .map(|bytes| format!("{:x}", md5::compute(bytes)))
+        .unwrap_or_default()
+}
+
+/// The last extraction's snapshot, or `None` if the project was never extracted.
+pub fn load_snapshot(project_path: &str) -> Result<Option<ExtractionSnapshot>, CoreError> {
+    let path = app_data_dir(project_path).join(EXTRACTION_SNAPSHOT_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(&path)?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| CoreError::JsonParse(format!("Failed to parse extraction snapshot: {}", e)))
+}
+
+pub fn save_snapshot(project_path: &str, snapshot: &ExtractionSnapshot) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, EXTRACTION_SNAPSHOT_FILE_NAME)?;
+    let json = serde_json::to_string(snapshot)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize extraction snapshot: {}", e)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Snapshot of the files in `entries`, hashed as they are now on disk.
+pub fn snapshot_entries(project_path: &str, entries: &[SourceStringData]) -> ExtractionSnapshot {
+    let mut snapshot = ExtractionSnapshot::default();
+    for entry in entries {
+        snapshot
+            .files
+            .entry(entry.source_file.clone())
+            .or_insert_with(|| FileSnapshot { md5: file_md5(project_path, &entry.source_file), entries: Vec::new() })
+            .entries
+            .push(entry.clone());
+    }
+    snapshot
+}
+
+/// Compares an extraction with the previous snapshot. Files whose hash didn't change are skipped
+/// whole; in the others strings are matched by path. Files left out of this extraction (by a
+/// filter, or a cancelled run) only report removals when they no longer exist.
+pub fn diff_with_snapshot(
+    project_path: &str,
+    previous: &ExtractionSnapshot,
+    current: &ExtractionSnapshot,
+) -> ExtractionResult {
+    let mut result = ExtractionResult { incremental: true, ..Default::default() };
+    for (source_file, file) in &current.files {
+        let before = previous.files.get(source_file);
+        if before.is_some_and(|before| !file.md5.is_empty() && before.md5 == file.md5) {
+            continue;
+        }
+        let old_texts: HashMap<&str, &str> = before
+            .map(|before| before.entries.iter().map(|e| (e.json_path.as_str(), e.original_text.as_str())).collect())
+            .unwrap_or_default();
+        result.entries.extend(
+            file.entries
+                .iter()
+                .filter(|entry| old_texts.get(entry.json_path.as_str()) != Some(&entry.original_text.as_str()))
+                .cloned(),
+        );
+        if let Some(before) = before {
+            let paths: Vec<&str> = file.entries.iter().map(|e| e.json_path.as_str()).collect();
+            result.removed.extend(before.entries.iter().filter(|e| !paths.contains(&e.json_path.as_str())).cloned());
+        }
+    }
+    for (source_file, before) in &previous.files {
+        if !current.files.contains_key(source_file) && !Path::new(project_path).join(source_file).exists() {
+            result.removed.extend(before.entries.iter().cloned());
+        }
+    }
+    result
+}
+
+/// Records `entries` as the project's latest extraction and returns them, or with `incremental`
+/// only what changed since the previous one. Files not in `entries` keep their previous snapshot
+/// unless they were deleted; the project's string store is updated the same way.
+pub fn record_extraction(
+    project_path: &str,
+    entries: Vec<SourceStringData>,
+    incremental: bool,
+) -> Result<ExtractionResult, CoreError> {
+    let previous = load_snapshot(project_path)?;
+    let current = snapshot_entries(project_path, &entries);
+    let is_gone = |source_file: &str| !Path::new(project_path).join(source_file).exists();
+    StringStore::open_for_project(project_path)?.replace_entries(&entries, &is_gone)?;
+    let result = match (&previous, incremental) {
+        (Some(previous), true) => diff_with_snapshot(project_path, previous, &current),
+        _ => ExtractionResult { entries, ..Default::default() },
+    };
+
+    let mut updated = previous.unwrap_or_default();
+    updated.files.retain(|source_file, _| Path::new(project_path).join(source_file).exists());
+    updated.files.extend(current.files);
+    save_snapshot(project_path, &updated)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn entry(source_file: &str, json_path: &str, text: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_incremental_extraction_returns_changes() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("www/data")).unwrap();
+        fs::write(dir.path().join("www/data/Actors.json"), "v1").unwrap();
+        fs::write(dir.path().join("www/data/Items.json"), "v1").unwrap();
+        fs::write(dir.path().join("www/data/Map001.json"), "v1").unwrap();
+
+        let first = vec![
+            entry("www/data/Actors.json", "[1].name", "ハロルド"),
+            entry("www/data/Actors.json", "[1].nickname", "剣士"),
+            entry("www/data/Items.json", "[1].name", "ポーション"),
+            entry("www/data/Map001.json", "events[1].pages[0].list[0].parameters[0]", "やあ"),
+        ];
+        let result = record_extraction(project_path, first.clone(), true).unwrap();
+        assert!(!result.incremental);
+        assert_eq!(result.entries.len(), 4);
+
+        // The developer renames an actor, drops a nickname, adds one and deletes a map.
+        fs::write(dir.path().join("www/data/Actors.json"), "v2").unwrap();
+        fs::remove_file(dir.path().join("www/data/Map001.json")).unwrap();
+        let second = vec![
+            entry("www/data/Actors.json", "[1].name", "ハロルド王"),
+            entry("www/data/Actors.json", "[2].name", "マーシャ"),
+            entry("www/data/Items.json", "[1].name", "ポーション"),
+        ];
+        let result = record_extraction(project_path, second, true).unwrap();
+        assert!(result.incremental);
+        let entries: Vec<&str> = result.entries.iter().map(|e| e.original_text.as_str()).collect();
+        assert_eq!(entries, vec!["ハロルド王", "マーシャ"]);
+        let removed: Vec<&str> = result.removed.iter().map(|e| e.original_text.as_str()).collect();
+        assert_eq!(removed, vec!["剣士", "やあ"]);
+
+        let snapshot = load_snapshot(project_path).unwrap().unwrap();
+        assert_eq!(snapshot.files.keys().collect::<Vec<_>>(), vec!["www/data/Actors.json", "www/data/Items.json"]);
+    }
+}
@@ -51,6 +51,8 @@ pub fn extract_strings(
                     original_text: state_data.name.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].name", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -61,6 +63,8 @@ pub fn extract_strings(
                     original_text: state_data.note.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].note", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -71,6 +75,8 @@ pub fn extract_strings(
                     original_text: state_data.message1.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].message1", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -81,6 +87,8 @@ pub fn extract_strings(
                     original_text: state_data.message2.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].message2", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -91,6 +99,8 @@ pub fn extract_strings(
                     original_text: state_data.message3.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].message3", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
 
@@ -101,6 +111,8 @@ pub fn extract_strings(
                     original_text: state_data.message4.clone(),
                     source_file: source_file.to_string(),
                     json_path: format!("[{}].message4", index),
+                    dialogue_block_id: None,
+                    speaker: None,
                 });
             }
         }
@@ -125,6 +137,7 @@ pub fn reconstruct_states_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value;
     #[test]
     fn test_extract_states_strings() {
@@ -177,34 +190,34 @@ mod tests {
 
         let expected_entries = vec![
             SourceStringData {
-                object_id: 1, original_text: "Knockout".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].name".to_string()
+                object_id: 1, original_text: "Knockout".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].name".to_string(), dialogue_block_id: None, speaker: None
             },
             SourceStringData {
-                object_id: 1, original_text: "This is a KO state.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].note".to_string()
+                object_id: 1, original_text: "This is a KO state.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].note".to_string(), dialogue_block_id: None, speaker: None
             },
             SourceStringData {
-                object_id: 1, original_text: "is knocked out.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].message1".to_string()
+                object_id: 1, original_text: "is knocked out.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].message1".to_string(), dialogue_block_id: None, speaker: None
             },
             // message2 for state 1 is empty
             SourceStringData {
-                object_id: 1, original_text: "is revived!".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].message3".to_string()
+                object_id: 1, original_text: "is revived!".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[1].message3".to_string(), dialogue_block_id: None, speaker: None
             },
             // message4 for state 1 is empty
             SourceStringData {
-                object_id: 2, original_text: "Guard".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].name".to_string()
+                object_id: 2, original_text: "Guard".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].name".to_string(), dialogue_block_id: None, speaker: None
             },
             // note for state 2 is empty
             SourceStringData {
-                object_id: 2, original_text: "guards.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message1".to_string()
+                object_id: 2, original_text: "guards.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message1".to_string(), dialogue_block_id: None, speaker: None
             },
             SourceStringData {
-                object_id: 2, original_text: "is still guarding.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message2".to_string()
+                object_id: 2, original_text: "is still guarding.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message2".to_string(), dialogue_block_id: None, speaker: None
             },
             SourceStringData {
-                object_id: 2, original_text: "stops guarding.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message3".to_string()
+                object_id: 2, original_text: "stops guarding.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message3".to_string(), dialogue_block_id: None, speaker: None
             },
             SourceStringData {
-                object_id: 2, original_text: "recovers from guard.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message4".to_string()
+                object_id: 2, original_text: "recovers from guard.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message4".to_string(), dialogue_block_id: None, speaker: None
             }
         ];
 
@@ -224,12 +237,12 @@ mod tests {
         assert_eq!(result.len(), 6); // name, note, msg1-4 for state 2
         
         let expected_entries_state2 = vec![
-            SourceStringData {object_id: 2, original_text: "Active State".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].name".to_string()},
-            SourceStringData {object_id: 2, original_text: "Has a note.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].note".to_string()},
-            SourceStringData {object_id: 2, original_text: "Begins".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message1".to_string()},
-            SourceStringData {object_id: 2, original_text: "Continues".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message2".to_string()},
-            SourceStringData {object_id: 2, original_text: "Ends".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message3".to_string()},
-            SourceStringData {object_id: 2, original_text: "Recovers".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message4".to_string()},
+            SourceStringData {object_id: 2, original_text: "Active State".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].name".to_string(), dialogue_block_id: None, speaker: None},
+            SourceStringData {object_id: 2, original_text: "Has a note.".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].note".to_string(), dialogue_block_id: None, speaker: None},
+            SourceStringData {object_id: 2, original_text: "Begins".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message1".to_string(), dialogue_block_id: None, speaker: None},
+            SourceStringData {object_id: 2, original_text: "Continues".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message2".to_string(), dialogue_block_id: None, speaker: None},
+            SourceStringData {object_id: 2, original_text: "Ends".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message3".to_string(), dialogue_block_id: None, speaker: None},
+            SourceStringData {object_id: 2, original_text: "Recovers".to_string(), source_file: "www/data/States.json".to_string(), json_path: "[2].message4".to_string(), dialogue_block_id: None, speaker: None},
         ];
         for expected in expected_entries_state2 {
             assert!(result.contains(&expected), "Missing expected entry for state 2: {:?}", expected);
@@ -273,6 +286,7 @@ mod tests {
                 translated_text: "Veneno".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -282,6 +296,7 @@ mod tests {
                 translated_text: "Causa daño con el tiempo.".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -291,6 +306,7 @@ mod tests {
                 translated_text: "¡%1 sufre daño por veneno!".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -300,6 +316,7 @@ mod tests {
                 translated_text: "Ceguera".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 2,
@@ -309,6 +326,7 @@ mod tests {
                 translated_text: "%1 sigue ciego.".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -338,6 +356,7 @@ mod tests {
                 translated_text: "Veneno Fallido".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI error".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -347,6 +366,7 @@ mod tests {
                 translated_text: "Mensaje Bueno".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -370,6 +390,7 @@ mod tests {
                 translated_text: "Estado Fantasma".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -392,6 +413,7 @@ mod tests {
                 translated_text: "Translated Mystery Message".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -414,6 +436,7 @@ mod tests {
                 translated_text: "Translated Unknown".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -98,6 +98,55 @@ struct SystemData {
     // Other fields are ignored
 }
 
+// --- Message groups ---
+
+/// `terms.messages` keys the battle log shows one after another. Members of a group found in
+/// the same batch are translated in one request, with instructions that keep them consistent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageGroup {
+    pub name: &'static str,
+    pub keys: &'static [&'static str],
+    pub instructions: &'static str,
+}
+
+const MESSAGES_PATH_PREFIX: &str = "terms.messages.";
+
+pub const MESSAGE_GROUPS: [MessageGroup; 4] = [
+    MessageGroup {
+        name: "battleStart",
+        keys: &["emerge", "preemptive", "surprise"],
+        instructions: "These battle start messages are shown as a battle begins (%1 is the party or enemy name). \
+             Use the same tense in all of them and refer to the party the same way.",
+    },
+    MessageGroup {
+        name: "escape",
+        keys: &["escapeStart", "escapeFailure"],
+        instructions: "These escape messages are shown one after the other (%1 is the party name). \
+             Use the same tense in both and refer to the party the same way.",
+    },
+    MessageGroup {
+        name: "battleEnd",
+        keys: &["victory", "defeat"],
+        instructions: "These battle end messages close a battle (%1 is the party name). \
+             Use the same tense in both and refer to the party the same way.",
+    },
+    MessageGroup {
+        name: "rewards",
+        keys: &["obtainExp", "obtainGold", "obtainItem", "levelUp", "obtainSkill"],
+        instructions: "These reward messages are listed one after another after a victory. \
+             Keep the same sentence pattern and tense in all of them and keep every %1/%2/%3 placeholder.",
+    },
+];
+
+/// The message group of a System.json entry, if it belongs to one.
+pub fn message_group_for(source_file: &str, json_path: &str) -> Option<&'static MessageGroup> {
+    if !source_file.ends_with("System.json") {
+        return None;
+    }
+    let key = json_path.strip_prefix(MESSAGES_PATH_PREFIX)?;
+    MESSAGE_GROUPS.iter().find(|group| group.keys.contains(&key))
+}
+
 // --- Helper function to add entries --- 
 fn add_entry_if_some(
     entries: &mut Vec<SourceStringData>,
@@ -112,6 +161,8 @@ fn add_entry_if_some(
                 original_text: text.clone(),
                 source_file: source_file.to_string(),
                 json_path,
+                dialogue_block_id: None,
+                speaker: None,
             });
         }
     }
@@ -242,6 +293,7 @@ pub fn reconstruct_system_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value;
 
     const TEST_SYSTEM_JSON: &str = r#"{
@@ -271,6 +323,7 @@ mod tests {
                 translated_text: "Mon Jeu".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 0,
@@ -280,6 +333,7 @@ mod tests {
                 translated_text: "Or".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 0,
@@ -289,6 +343,7 @@ mod tests {
                 translated_text: "Armure Légère".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 0,
@@ -298,6 +353,7 @@ mod tests {
                 translated_text: "PV".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 0,
@@ -307,6 +363,7 @@ mod tests {
                 translated_text: "%1 a subi %2 dégâts !".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
              WorkingTranslation {
                 object_id: 0,
@@ -316,6 +373,7 @@ mod tests {
                 translated_text: "Translated Var".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -349,6 +407,7 @@ mod tests {
                 translated_text: "Jeu Raté".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI failed".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 0,
@@ -358,6 +417,7 @@ mod tests {
                 translated_text: "Gold".to_string(), 
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -381,6 +441,7 @@ mod tests {
                 translated_text: "Donnée Fantôme".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -415,4 +476,13 @@ mod tests {
             other => panic!("Expected JsonParse error, got {:?}", other),
         }
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_message_group_for() {
+        let group = message_group_for("www/data/System.json", "terms.messages.preemptive").unwrap();
+        assert_eq!(group.name, "battleStart");
+        assert_eq!(group.keys, &["emerge", "preemptive", "surprise"]);
+        assert!(message_group_for("www/data/System.json", "terms.messages.actorDamage").is_none());
+        assert!(message_group_for("www/data/Map001.json", "terms.messages.emerge").is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use serde::Deserialize;
 use serde_json::Value;
 use crate::models::translation::{SourceStringData, WorkingTranslation};
@@ -7,6 +8,7 @@ use crate::core::rpgmv::common::{
     reconstruct_event_command_list
 };
 use crate::error::CoreError;
+use super::warnings::{warn, WarningKind};
 use crate::utils::json_utils::update_value_at_path;
 
 #[derive(Deserialize, Debug, Clone)]
@@ -55,6 +57,8 @@ pub fn extract_strings(
                             original_text: troop.name.clone(),
                             source_file: source_file.to_string(),
                             json_path: format!("[{}].name", troop_idx),
+                            dialogue_block_id: None,
+                            speaker: None,
                         });
                     }
 
@@ -88,6 +92,17 @@ pub fn extract_strings(
 } 
 
 
+/// Command list prefix and page index of a battle event path, e.g. ("[1].pages[0].list", 0) for
+/// "[1].pages[0].list[3].parameters[0]". The older "[1].pages.[0].list.[3]..." form is read too.
+fn split_page_path<'a>(json_path: &'a str, troop_part: &str) -> Option<(&'a str, usize)> {
+    let rest = json_path.strip_prefix(troop_part)?.strip_prefix(".pages")?;
+    let rest = rest.strip_prefix('.').unwrap_or(rest).strip_prefix('[')?;
+    let (page_index, after) = rest.split_once(']')?;
+    after.strip_prefix(".list")?;
+    let prefix_len = json_path.len() - after.len() + ".list".len();
+    Some((&json_path[..prefix_len], page_index.parse().ok()?))
+}
+
 pub fn reconstruct_troops_json(
     original_json_str: &str,
     translations: Vec<&WorkingTranslation>,
@@ -95,11 +110,14 @@ pub fn reconstruct_troops_json(
     let mut troops_json_array: Vec<Value> = serde_json::from_str(original_json_str)
         .map_err(|e| CoreError::JsonParse(format!("Failed to parse Troops.json: {}", e)))?;
 
+    // Battle event translations by (troop index, page index, command list prefix).
+    let mut page_translations: BTreeMap<(usize, usize, &str), Vec<&WorkingTranslation>> = BTreeMap::new();
+
     for entry in &translations {
-        let parts: Vec<&str> = entry.json_path.splitn(4, '.').collect(); // e.g., "[1].name" or "[1].pages.[0].list"
+        let parts: Vec<&str> = entry.json_path.splitn(4, '.').collect(); // e.g., "[1].name" or "[1].pages[0].list[3]..."
 
         if parts.len() < 2 || !parts[0].starts_with('[') || !parts[0].ends_with(']') {
-            eprintln!("Warning (Troops.json): Invalid json_path format for entry: {:?}. Skipping.", entry);
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Warning (Troops.json): Invalid json_path format for entry: {:?}. Skipping.", entry));
             continue;
         }
 
@@ -107,13 +125,13 @@ pub fn reconstruct_troops_json(
         let troop_index: usize = match troop_index_str.parse() {
             Ok(idx) => idx,
             Err(_) => {
-                eprintln!("Warning (Troops.json): Failed to parse troop index from path: {}. Skipping.", entry.json_path);
+                warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Warning (Troops.json): Failed to parse troop index from path: {}. Skipping.", entry.json_path));
                 continue;
             }
         };
 
         if troop_index >= troops_json_array.len() || troops_json_array[troop_index].is_null() {
-            eprintln!("Warning (Troops.json): Troop index {} out of bounds or null. Skipping entry: {:?}.", troop_index, entry);
+            warn(WarningKind::OutOfBounds, Some(&entry.json_path), format!("Warning (Troops.json): Troop index {} out of bounds or null. Skipping entry: {:?}.", troop_index, entry));
             continue;
         }
         
@@ -122,10 +140,10 @@ pub fn reconstruct_troops_json(
             .map_or(0, |id| id as u32);
 
         if troop_object_id != entry.object_id {
-            eprintln!(
+            warn(WarningKind::IdMismatch, Some(&entry.json_path), format!(
                 "Warning (Troops.json): Mismatched object_id for troop_index {}. Expected {}, found in translation {}. Skipping entry: {:?}.",
                 troop_index, troop_object_id, entry.object_id, entry
-            );
+            ));
             continue;
         }
 
@@ -138,66 +156,52 @@ pub fn reconstruct_troops_json(
         if parts.len() == 2 && parts[1] == "name" { // Path like "[1].name"
             if let Some(troop_value_mut) = troops_json_array.get_mut(troop_index) {
                 if let Err(e) = update_value_at_path(troop_value_mut, "name", text_to_insert) {
-                    eprintln!(
+                    warn(WarningKind::UpdateFailed, Some(&entry.json_path), format!(
                         "Warning (Troops.json): Failed to update name for troop id {}: {}. Skipping update for this field.", 
                         entry.object_id, e.to_string()
-                    );
+                    ));
                 }
             }
-        } else if parts.len() > 3 && parts[1] == "pages" && parts[3] == "list" { // Path like "[1].pages.[0].list..."
-            // This will be handled by iterating through pages and calling reconstruct_event_command_list
+        } else if let Some((prefix, page_index)) = split_page_path(&entry.json_path, parts[0]) {
+            // Messages, choices and scrolling text of a page are applied with its command list below.
+            page_translations.entry((troop_index, page_index, prefix)).or_default().push(entry);
         } else {
-             eprintln!("Warning (Troops.json): Unhandled json_path structure for direct update: {} for entry: {:?}", entry.json_path, entry);
+             warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Warning (Troops.json): Unhandled json_path structure for direct update: {} for entry: {:?}", entry.json_path, entry));
         }
     }
 
-    // Iterate again for pages and their command lists
-    for troop_idx_val in 0..troops_json_array.len() {
-        if troops_json_array[troop_idx_val].is_null() { continue; }
-
-        let current_troop_id = troops_json_array[troop_idx_val].get("id")
-            .and_then(|id| id.as_u64())
-            .map_or(0, |id| id as u32);
-        if current_troop_id == 0 { continue; }
-
-        if let Some(pages_value) = troops_json_array[troop_idx_val].get_mut("pages") {
-            if let Value::Array(pages_array) = pages_value {
-                for page_idx in 0..pages_array.len() {
-                    let page_json_path_prefix = format!("[{}].pages.[{}].list", troop_idx_val, page_idx);
-                    
-                    let relevant_translations_for_page: Vec<&WorkingTranslation> = translations
-                        .iter()
-                        .filter(|t| t.object_id == current_troop_id && t.json_path.starts_with(&page_json_path_prefix))
-                        .map(|&t_ref| t_ref)
-                        .collect();
-
-                    if !relevant_translations_for_page.is_empty() {
-                        if let Some(list_value) = pages_array[page_idx].get_mut("list") {
-                            if let Value::Array(list_array) = list_value {
-                                match reconstruct_event_command_list(list_array, current_troop_id, &relevant_translations_for_page, &page_json_path_prefix) {
-                                    Ok(_) => { /* Successfully updated list */ }
-                                    Err(e) => {
-                                        eprintln!(
-                                            "Error reconstructing command list for troop id {}, page {}: {}. List might be partially updated.", 
-                                            current_troop_id, page_idx, e
-                                        );
-                                    }
-                                }
-                            } else {
-                                eprintln!("Warning (Troops.json): 'list' field for troop id {}, page {} is not an array.", current_troop_id, page_idx);
-                            }
-                        } else {
-                            eprintln!("Warning (Troops.json): No 'list' field found for troop id {}, page {}.", current_troop_id, page_idx);
-                        }
-                    }
+    for ((troop_index, page_index, page_json_path_prefix), page_entries) in page_translations {
+        let troop_value = &mut troops_json_array[troop_index];
+        let current_troop_id = troop_value.get("id").and_then(|id| id.as_u64()).map_or(0, |id| id as u32);
+        let Some(pages_array) = troop_value.get_mut("pages").and_then(Value::as_array_mut) else {
+            warn(WarningKind::SkippedPath, Some(page_json_path_prefix), format!(
+                "Warning (Troops.json): Troop id {} has no pages array; skipping {} battle event translation(s).",
+                current_troop_id, page_entries.len()
+            ));
+            continue;
+        };
+        // Pages removed since extraction would otherwise drop their translations without a word.
+        let Some(page) = pages_array.get_mut(page_index) else {
+            warn(WarningKind::OutOfBounds, Some(page_json_path_prefix), format!(
+                "Warning (Troops.json): Troop id {} no longer has page {} (it has {}); skipping {} battle event translation(s).",
+                current_troop_id, page_index, pages_array.len(), page_entries.len()
+            ));
+            continue;
+        };
+        match page.get_mut("list") {
+            Some(Value::Array(list_array)) => {
+                if let Err(e) = reconstruct_event_command_list(list_array, current_troop_id, &page_entries, page_json_path_prefix) {
+                    warn(WarningKind::UpdateFailed, Some(page_json_path_prefix), format!(
+                        "Error reconstructing command list for troop id {}, page {}: {}. List might be partially updated.", 
+                        current_troop_id, page_index, e
+                    ));
                 }
-            } else {
-                 eprintln!("Warning (Troops.json): 'pages' field for troop id {} is not an array.", current_troop_id);
             }
-        } // No pages field, or not an array
+            Some(_) => warn(WarningKind::SkippedPath, Some(page_json_path_prefix), format!("Warning (Troops.json): 'list' field for troop id {}, page {} is not an array.", current_troop_id, page_index)),
+            None => warn(WarningKind::SkippedPath, Some(page_json_path_prefix), format!("Warning (Troops.json): No 'list' field found for troop id {}, page {}.", current_troop_id, page_index)),
+        }
     }
 
-
     serde_json::to_string_pretty(&troops_json_array)
         .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize Troops.json: {}", e)))
 }
@@ -206,6 +210,7 @@ pub fn reconstruct_troops_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
 
     const TEST_TROOPS_JSON: &str = r#"[
         null,
@@ -265,6 +270,7 @@ mod tests {
                 translated_text: "Bat*2 (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -274,6 +280,7 @@ mod tests {
                 translated_text: "Murciélago A".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -283,6 +290,7 @@ mod tests {
                 translated_text: "¡Chillido!".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 9,
@@ -292,6 +300,7 @@ mod tests {
                 translated_text: "Sample 4 Assassin (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 9,
@@ -301,6 +310,7 @@ mod tests {
                 translated_text: "W-who are you!!! (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 9,
@@ -310,6 +320,7 @@ mod tests {
                 translated_text: "Comentario de segunda página".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -331,6 +342,64 @@ mod tests {
         assert_eq!(reconstructed_json[2]["pages"][0]["list"][3]["parameters"][0].as_str().unwrap(), "主人公1-3");
     }
 
+    #[test]
+    fn test_reconstruct_troops_extracted_paths_and_removed_pages() {
+        let troops = r#"[null, {"id":1,"members":[],"name":"スライム","pages":[
+            {"conditions":{},"list":[{"code":0,"indent":0,"parameters":[]}],"span":0},
+            {"conditions":{"turnValid":true,"turnA":2},"list":[
+                {"code":101,"indent":0,"parameters":["",0,0,2,"スライム"]},
+                {"code":401,"indent":0,"parameters":["ぷるぷる"]},
+                {"code":102,"indent":0,"parameters":[["戦う","逃げる"],1,0,2,0]},
+                {"code":402,"indent":0,"parameters":[0,"戦う"]},
+                {"code":0,"indent":1,"parameters":[]},
+                {"code":402,"indent":0,"parameters":[1,"逃げる"]},
+                {"code":0,"indent":1,"parameters":[]},
+                {"code":404,"indent":0,"parameters":[]},
+                {"code":0,"indent":0,"parameters":[]}
+            ],"span":1}
+        ]}]"#;
+        let entries = extract_strings(troops, "www/data/Troops.json").unwrap();
+        fn translated(text: &str) -> &str {
+            match text {
+                "スライム" => "Slime",
+                "ぷるぷる" => "Wobble wobble",
+                "戦う" => "Fight",
+                "逃げる" => "Run",
+                _ => text,
+            }
+        }
+        let mut translations: Vec<WorkingTranslation> = entries
+            .iter()
+            .map(|e| WorkingTranslation {
+                object_id: e.object_id,
+                original_text: e.original_text.clone(),
+                translated_text: translated(&e.original_text).to_string(),
+                source_file: e.source_file.clone(),
+                json_path: e.json_path.clone(),
+                translation_source: "test".to_string(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            })
+            .collect();
+        assert!(translations.iter().any(|t| t.json_path == "[1].pages[1].list[2].parameters[0][1]"));
+        let mut removed_page = translations[1].clone();
+        removed_page.json_path = "[1].pages[5].list[1].parameters[0]".to_string();
+        translations.push(removed_page);
+
+        let refs: Vec<&WorkingTranslation> = translations.iter().collect();
+        let (result, warnings) =
+            super::super::warnings::collect("www/data/Troops.json", || reconstruct_troops_json(troops, refs));
+        let json: Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(json[1]["name"], "Slime");
+        let list = &json[1]["pages"][1]["list"];
+        assert_eq!(list[0]["parameters"][4], "Slime");
+        assert_eq!(list[1]["parameters"][0], "Wobble wobble");
+        assert_eq!(list[2]["parameters"][0], serde_json::json!(["Fight", "Run"]));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::OutOfBounds);
+        assert!(warnings[0].message.contains("no longer has page 5"));
+    }
+
     #[test]
     fn test_reconstruct_troops_with_translation_error() {
         let original_json_str = TEST_TROOPS_JSON;
@@ -343,6 +412,7 @@ mod tests {
                 translated_text: "Bat*2 (Failed)".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI Error".to_string()),
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -352,6 +422,7 @@ mod tests {
                 translated_text: "¡Chillido! (Bueno)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -375,6 +446,7 @@ mod tests {
                 translated_text: "Phantom Name".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -397,6 +469,7 @@ mod tests {
                 translated_text: "Mismatched Name".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -420,6 +493,7 @@ mod tests {
                 translated_text: "Translated Invented".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -429,6 +503,7 @@ mod tests {
                 translated_text: "Translated Deep Invented".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
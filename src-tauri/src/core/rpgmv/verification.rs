@@ -0,0 +1,333 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use serde::Serialize;
+use crate::core::generic;
+use crate::models::coverage::FileCoverage;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::error::CoreError;
+use crate::utils::project_paths::ensure_app_data_file;
+use super::note_tags::merge_note_tag_translations;
+use super::script_calls::{merge_script_string_translations, script_lines};
+use super::project::extract_strings_from_file_content;
+use super::warnings::ReconstructionWarning;
+
+pub const VERIFICATION_REPORT_FILE_NAME: &str = "verification_report.json";
+
+// (expected entries, verified entries, mismatches) for one file.
+type FileVerification = Result<(usize, usize, Vec<VerificationMismatch>), String>;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MismatchKind {
+    // Expected a string at this path but extraction of the output found none.
+    MissingPath,
+    // Extraction of the output found a string at a path that was not expected.
+    UnexpectedPath,
+    // The path exists but holds different text than expected.
+    TextMismatch,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMismatch {
+    pub source_file: String,
+    pub json_path: String,
+    pub kind: MismatchKind,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationReport {
+    pub passed: bool,
+    pub files_checked: usize,
+    pub entries_expected: usize,
+    pub entries_verified: usize,
+    pub mismatches: Vec<VerificationMismatch>,
+    // Files that could not be re-extracted at all.
+    pub errors: Vec<String>,
+    // Translations the reconstruction skipped (bad paths, id mismatches). They show up as
+    // mismatches too; the warning says why.
+    pub warnings: Vec<ReconstructionWarning>,
+    // Per-file coverage badges of the translated set, least covered first.
+    pub coverage: Vec<FileCoverage>,
+}
+
+/// Text the reconstructors write for a translation: the translation, or the original when the
+/// entry failed or is empty.
+fn expected_text(entry: &WorkingTranslation) -> &str {
+    if entry.error.is_some() || entry.translated_text.is_empty() {
+        &entry.original_text
+    } else {
+        &entry.translated_text
+    }
+}
+
+fn by_path(entries: Vec<SourceStringData>) -> BTreeMap<String, String> {
+    entries.into_iter().map(|e| (e.json_path, e.original_text)).collect()
+}
+
+fn extract(relative_file_path: &str, content: &str) -> Result<Vec<SourceStringData>, String> {
+    let file_name = Path::new(relative_file_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    // Files of generic projects are compared on all their string values, selected or not.
+    extract_strings_from_file_content(file_name, content, relative_file_path).unwrap_or_else(|| {
+        if generic::is_supported_file(relative_file_path) {
+            generic::project::extract_strings(content, relative_file_path, None)
+        } else {
+            Err(format!("No parser available for {}", relative_file_path))
+        }
+    })
+}
+
+/// Verifies one reconstructed file: extraction of the output must equal extraction of the
+/// original with every translation applied (same paths, same count, target text in place).
+pub fn verify_file(
+    relative_file_path: &str,
+    original_content: &str,
+    reconstructed_content: &str,
+    translations: &[&WorkingTranslation],
+) -> FileVerification {
+    // Script call strings are checked as the whole script lines they were merged into, which
+    // extraction doesn't report, so they are compared on their own.
+    let scripts = merge_script_string_translations(original_content, relative_file_path, translations)?;
+    let script_refs: Vec<&WorkingTranslation>;
+    let translations = match &scripts {
+        Some(scripts) => {
+            script_refs = scripts.iter().collect();
+            &script_refs
+        }
+        None => translations,
+    };
+    let actual_scripts: HashMap<String, String> = match &scripts {
+        Some(_) => script_lines(reconstructed_content)?.into_iter().map(|(_, path, line)| (path, line)).collect(),
+        None => HashMap::new(),
+    };
+    // Note tag entries are checked as the whole notes they were merged into.
+    let merged = merge_note_tag_translations(original_content, relative_file_path, translations)?;
+    let merged_refs: Vec<&WorkingTranslation>;
+    let translations = match &merged {
+        Some(merged) => {
+            merged_refs = merged.iter().collect();
+            &merged_refs
+        }
+        None => translations,
+    };
+    let mut expected = by_path(extract(relative_file_path, original_content)?);
+    let mut expected_scripts = BTreeMap::new();
+    for entry in translations {
+        let text = expected_text(entry);
+        if actual_scripts.contains_key(&entry.json_path) {
+            expected_scripts.insert(entry.json_path.clone(), text.to_string());
+        } else if text.trim().is_empty() {
+            // Blank strings are never extracted, so the path is expected to disappear.
+            expected.remove(&entry.json_path);
+        } else {
+            expected.insert(entry.json_path.clone(), text.to_string());
+        }
+    }
+    let actual = by_path(extract(relative_file_path, reconstructed_content)?);
+
+    let mut mismatches = Vec::new();
+    let mut verified = 0;
+    for (path, expected_value) in &expected {
+        match actual.get(path) {
+            Some(actual_value) if actual_value == expected_value => verified += 1,
+            Some(actual_value) => mismatches.push(VerificationMismatch {
+                source_file: relative_file_path.to_string(),
+                json_path: path.clone(),
+                kind: MismatchKind::TextMismatch,
+                expected: Some(expected_value.clone()),
+                actual: Some(actual_value.clone()),
+            }),
+            None => mismatches.push(VerificationMismatch {
+                source_file: relative_file_path.to_string(),
+                json_path: path.clone(),
+                kind: MismatchKind::MissingPath,
+                expected: Some(expected_value.clone()),
+                actual: None,
+            }),
+        }
+    }
+    for (path, actual_value) in &actual {
+        if !expected.contains_key(path) {
+            mismatches.push(VerificationMismatch {
+                source_file: relative_file_path.to_string(),
+                json_path: path.clone(),
+                kind: MismatchKind::UnexpectedPath,
+                expected: None,
+                actual: Some(actual_value.clone()),
+            });
+        }
+    }
+    for (path, expected_value) in &expected_scripts {
+        match actual_scripts.get(path) {
+            Some(actual_value) if actual_value == expected_value => verified += 1,
+            actual_value => mismatches.push(VerificationMismatch {
+                source_file: relative_file_path.to_string(),
+                json_path: path.clone(),
+                kind: MismatchKind::TextMismatch,
+                expected: Some(expected_value.clone()),
+                actual: actual_value.cloned(),
+            }),
+        }
+    }
+    Ok((expected.len() + expected_scripts.len(), verified, mismatches))
+}
+
+/// Verifies all reconstructed files in parallel (one worker per available core) and merges
+/// the per-file results into a single report.
+///
+/// * `originals` / `reconstructed` - relative file path -> file content.
+pub fn verify_reconstruction(
+    originals: &HashMap<String, String>,
+    reconstructed: &HashMap<String, String>,
+    translated_entries: &[WorkingTranslation],
+) -> VerificationReport {
+    let mut grouped: HashMap<&str, Vec<&WorkingTranslation>> = HashMap::new();
+    for entry in translated_entries {
+        grouped.entry(entry.source_file.as_str()).or_default().push(entry);
+    }
+
+    let mut files: Vec<&String> = reconstructed.keys().collect();
+    files.sort();
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).max(1);
+    let chunk_size = files.len().div_ceil(workers).max(1);
+
+    let results: Vec<(String, FileVerification)> =
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let grouped = &grouped;
+                    let handle = scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|file| {
+                                let outcome = match originals.get(*file) {
+                                    Some(original) => verify_file(
+                                        file,
+                                        original,
+                                        &reconstructed[*file],
+                                        grouped.get(file.as_str()).map_or(&[][..], |v| v.as_slice()),
+                                    ),
+                                    None => Err(format!("Original content missing for {}", file)),
+                                };
+                                ((*file).clone(), outcome)
+                            })
+                            .collect::<Vec<_>>()
+                    });
+                    (chunk, handle)
+                })
+                .collect();
+            // A worker that panicked fails every file it was given rather than passing them.
+            handles
+                .into_iter()
+                .flat_map(|(chunk, handle)| {
+                    handle.join().unwrap_or_else(|_| {
+                        let failed = || Err("Verification failed unexpectedly".to_string());
+                        chunk.iter().map(|file| ((*file).clone(), failed())).collect()
+                    })
+                })
+                .collect()
+        });
+
+    let mut report = VerificationReport { files_checked: files.len(), ..Default::default() };
+    for (file, outcome) in results {
+        match outcome {
+            Ok((expected, verified, mut mismatches)) => {
+                report.entries_expected += expected;
+                report.entries_verified += verified;
+                report.mismatches.append(&mut mismatches);
+            }
+            Err(e) => report.errors.push(format!("{}: {}", file, e)),
+        }
+    }
+    report.passed = report.mismatches.is_empty() && report.errors.is_empty();
+    report
+}
+
+/// Reads the original files from the project and verifies the reconstructed contents against them.
+pub fn verify_against_project(
+    project_path: &str,
+    reconstructed: &HashMap<String, String>,
+    translated_entries: &[WorkingTranslation],
+) -> VerificationReport {
+    let mut originals = HashMap::new();
+    for relative_file_path in reconstructed.keys() {
+        if let Ok(content) = std::fs::read_to_string(Path::new(project_path).join(relative_file_path)) {
+            originals.insert(relative_file_path.clone(), content);
+        }
+    }
+    verify_reconstruction(&originals, reconstructed, translated_entries)
+}
+
+/// Saves the report as `.translate-ai/verification_report.json` so it can be inspected after the run.
+pub fn save_report(project_path: &str, report: &VerificationReport) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, VERIFICATION_REPORT_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize verification report: {}", e)))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    const ACTORS: &str = r#"[null,{"id":1,"name":"ハロルド","nickname":"","profile":"勇者","note":""}]"#;
+
+    fn translation(json_path: &str, original: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "test".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_verification_passes_for_faithful_reconstruction() {
+        let translations = vec![translation("[1].name", "ハロルド", "Harold")];
+        let refs: Vec<&WorkingTranslation> = translations.iter().collect();
+        let output = super::super::actors::reconstruct_actors_json(ACTORS, refs).unwrap();
+
+        let originals = HashMap::from([("www/data/Actors.json".to_string(), ACTORS.to_string())]);
+        let reconstructed = HashMap::from([("www/data/Actors.json".to_string(), output)]);
+        let report = verify_reconstruction(&originals, &reconstructed, &translations);
+
+        assert!(report.passed, "{:?}", report);
+        assert_eq!(report.entries_expected, 2); // name + untouched profile
+        assert_eq!(report.entries_verified, 2);
+    }
+
+    #[test]
+    fn test_verification_reports_mismatches() {
+        let translations = [
+            translation("[1].name", "ハロルド", "Harold"),
+            translation("[1].profile", "勇者", "Hero"),
+        ];
+        // Output where the name was not applied and the profile was wiped.
+        let broken = r#"[null,{"id":1,"name":"ハロルド","nickname":"","profile":"","note":""}]"#;
+        let (expected, verified, mismatches) = verify_file(
+            "www/data/Actors.json",
+            ACTORS,
+            broken,
+            &translations.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        assert_eq!((expected, verified), (2, 0));
+        let kinds: Vec<MismatchKind> = mismatches.iter().map(|m| m.kind).collect();
+        assert_eq!(kinds, vec![MismatchKind::TextMismatch, MismatchKind::MissingPath]);
+        assert_eq!(mismatches[0].actual.as_deref(), Some("ハロルド"));
+    }
+}
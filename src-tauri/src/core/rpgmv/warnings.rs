@@ -0,0 +1,143 @@
+use std::cell::RefCell;
+use serde::{Deserialize, Serialize};
+use crate::error::CoreError;
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// File in the project's app data folder holding the warnings of the last reconstruction.
+pub const RECONSTRUCTION_WARNINGS_FILE_NAME: &str = "reconstruction_warnings.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WarningKind {
+    // The json_path couldn't be parsed or doesn't fit the file's structure.
+    SkippedPath,
+    // The object at the path has another id than the translation entry.
+    IdMismatch,
+    // The path points past the end of an array, or at a null entry.
+    OutOfBounds,
+    // The path was found but the value couldn't be replaced.
+    UpdateFailed,
+}
+
+/// A translation (or part of a file) the reconstruction had to leave untranslated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconstructionWarning {
+    pub kind: WarningKind,
+    pub source_file: String,
+    pub json_path: Option<String>,
+    pub message: String,
+}
+
+/// Reconstructed output together with the warnings raised while producing it.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconstructionReport<T> {
+    pub output: T,
+    pub warnings: Vec<ReconstructionWarning>,
+}
+
+thread_local! {
+    static COLLECTED: RefCell<Option<Vec<ReconstructionWarning>>> = const { RefCell::new(None) };
+}
+
+/// Records a warning from a file reconstructor. Inside `collect` it is added to the report;
+/// otherwise (reconstructors called directly, e.g. in tests) it is only logged.
+pub fn warn(kind: WarningKind, json_path: Option<&str>, message: String) {
+    let warning = ReconstructionWarning {
+        kind,
+        source_file: String::new(),
+        json_path: json_path.map(str::to_string),
+        message,
+    };
+    COLLECTED.with(|collected| match collected.borrow_mut().as_mut() {
+        Some(warnings) => warnings.push(warning),
+        None => eprintln!("{}", warning.message),
+    });
+}
+
+/// Runs the reconstruction of `source_file` and returns the warnings it raised.
+pub fn collect<T>(source_file: &str, reconstruct: impl FnOnce() -> T) -> (T, Vec<ReconstructionWarning>) {
+    let outer = COLLECTED.with(|collected| collected.replace(Some(Vec::new())));
+    let output = reconstruct();
+    let mut warnings = COLLECTED.with(|collected| collected.replace(outer)).unwrap_or_default();
+    for warning in &mut warnings {
+        if warning.source_file.is_empty() {
+            warning.source_file = source_file.to_string();
+        }
+    }
+    (output, warnings)
+}
+
+/// Saves the warnings as `.translate-ai/reconstruction_warnings.json`, replacing the last run's.
+pub fn save_warnings(project_path: &str, warnings: &[ReconstructionWarning]) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, RECONSTRUCTION_WARNINGS_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(warnings)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize reconstruction warnings: {}", e)))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Warnings of the last reconstruction; empty if the project was never reconstructed.
+pub fn load_warnings(project_path: &str) -> Result<Vec<ReconstructionWarning>, CoreError> {
+    let path = app_data_dir(project_path).join(RECONSTRUCTION_WARNINGS_FILE_NAME);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&json)
+        .map_err(|e| CoreError::JsonParse(format!("Failed to parse {:?}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::rpgmv::project::reconstruct_file_content;
+    use crate::models::translation::{TranslationStatus, WorkingTranslation};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_and_save_warnings() {
+        let (value, warnings) = collect("www/data/Map001.json", || {
+            warn(WarningKind::OutOfBounds, Some("events[9].name"), "Event 9 not found".to_string());
+            let (_, inner) = collect("www/data/Actors.json", || {
+                warn(WarningKind::IdMismatch, None, "Actor id mismatch".to_string());
+            });
+            assert_eq!(inner[0].source_file, "www/data/Actors.json");
+            42
+        });
+        assert_eq!(value, 42);
+        assert_eq!(warnings, vec![ReconstructionWarning {
+            kind: WarningKind::OutOfBounds,
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: Some("events[9].name".to_string()),
+            message: "Event 9 not found".to_string(),
+        }]);
+
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        assert!(load_warnings(project_path).unwrap().is_empty());
+        save_warnings(project_path, &warnings).unwrap();
+        assert_eq!(load_warnings(project_path).unwrap(), warnings);
+    }
+
+    #[test]
+    fn test_reconstruct_file_content_reports_skipped_entries() {
+        let entry = WorkingTranslation {
+            object_id: 7,
+            original_text: "ハロルド".to_string(),
+            translated_text: "Harold".to_string(),
+            source_file: "www/data/Actors.json".to_string(),
+            json_path: "[7].name".to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let report = reconstruct_file_content(r#"[null,{"id":1,"name":"ハロルド"}]"#, "www/data/Actors.json", vec![&entry]).unwrap();
+        assert!(report.output.contains("ハロルド"));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].kind, WarningKind::IdMismatch);
+        assert_eq!(report.warnings[0].source_file, "www/data/Actors.json");
+        assert_eq!(report.warnings[0].json_path.as_deref(), Some("[7].name"));
+    }
+}
@@ -52,6 +52,7 @@ pub fn reconstruct_weapons_json(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::translation::TranslationStatus;
     use serde_json::Value;
     const TEST_WEAPONS_JSON: &str = r#"[
         null,
@@ -72,6 +73,7 @@ mod tests {
                 translated_text: "Sword (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 1,
@@ -81,6 +83,7 @@ mod tests {
                 translated_text: "A basic sword.".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 7,
@@ -90,6 +93,7 @@ mod tests {
                 translated_text: "Hidden Bayonet (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 7,
@@ -99,6 +103,7 @@ mod tests {
                 translated_text: "Concealed weapon for assassination.\nEquipped on the right arm. (EN)".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
             WorkingTranslation {
                 object_id: 7,
@@ -108,6 +113,7 @@ mod tests {
                 translated_text: "<HiddenWeaponTag>".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -138,6 +144,7 @@ mod tests {
                 translated_text: "SwordFail".to_string(),
                 translation_source: "test".to_string(),
                 error: Some("AI error".to_string()), 
+                status: TranslationStatus::Untranslated,
             },
             WorkingTranslation {
                 object_id: 2, // Axe
@@ -147,6 +154,7 @@ mod tests {
                 translated_text: "A mighty axe.".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -171,6 +179,7 @@ mod tests {
                 translated_text: "Phantom Weapon".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -193,6 +202,7 @@ mod tests {
                 translated_text: "Over 9000".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
@@ -227,6 +237,7 @@ mod tests {
                 translated_text: "剣".to_string(),
                 translation_source: "test".to_string(),
                 error: None,
+                status: TranslationStatus::MachineTranslated,
             },
         ];
         let translations_ref: Vec<&WorkingTranslation> = translations.iter().collect();
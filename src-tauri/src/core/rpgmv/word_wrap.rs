@@ -0,0 +1,293 @@
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Value};
+use crate::core::config::load_project_config;
+use crate::core::control_codes::ControlCodes;
+use crate::core::validation::load_project_ruleset;
+use crate::error::CoreError;
+use crate::models::extraction::FileKind;
+use crate::models::project_config::{WidthUnit, WordWrapSettings};
+use super::common::split_name_box_code;
+use super::filter::file_kind;
+use super::project::ReconstructedProject;
+
+/// Drawn width of a character: CJK and full-width forms take two columns, the rest one.
+pub fn char_columns(c: char) -> usize {
+    let wide = matches!(c,
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{303E}'
+        | '\u{3040}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}');
+    if wide { 2 } else { 1 }
+}
+
+/// Width of `text` once its control codes are removed, counted in `unit`.
+pub fn text_width(text: &str, unit: WidthUnit, codes: &ControlCodes) -> usize {
+    let visible = codes.strip(text);
+    match unit {
+        WidthUnit::Columns => visible.chars().map(char_columns).sum(),
+        WidthUnit::Characters => visible.chars().count(),
+        WidthUnit::Bytes => visible.len(),
+    }
+}
+
+fn is_wide(c: char) -> bool {
+    char_columns(c) == 2
+}
+
+pub fn validate_word_wrap(settings: &WordWrapSettings) -> Result<(), CoreError> {
+    if settings.max_width == 0 || settings.max_width_with_face == Some(0) || settings.max_lines == 0 {
+        return Err(CoreError::Config("Word wrap widths and lines per window must be greater than 0".to_string()));
+    }
+    Ok(())
+}
+
+/// Re-wraps the text of a message box to lines of at most `max_width`. Lines are joined with a
+/// space, or directly between CJK characters; breaks go between words, or anywhere between CJK
+/// characters, and a word wider than a whole line is cut. A leading name box code stays on
+/// the first line and isn't counted.
+pub fn wrap_message(lines: &[String], max_width: usize, unit: WidthUnit, codes: &ControlCodes) -> Vec<String> {
+    let mut text = String::new();
+    for line in lines.iter().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+        let joins_directly = text.chars().last().is_some_and(is_wide) && line.chars().next().is_some_and(is_wide);
+        if !text.is_empty() && !joins_directly {
+            text.push(' ');
+        }
+        text.push_str(line);
+    }
+    let (prefix, text) = match split_name_box_code(&text) {
+        Some(name_box) => (format!("{}{}>", name_box.open, name_box.name), name_box.rest.trim_start().to_string()),
+        None => (String::new(), text),
+    };
+
+    // Words, or single wide characters, each with whether a space came before it.
+    let mut tokens: Vec<(String, bool)> = Vec::new();
+    let mut word = String::new();
+    let mut space_before = false;
+    for c in text.chars() {
+        if c.is_whitespace() || is_wide(c) {
+            if !word.is_empty() {
+                tokens.push((std::mem::take(&mut word), space_before));
+                space_before = false;
+            }
+            if c.is_whitespace() {
+                space_before = true;
+            } else {
+                tokens.push((c.to_string(), space_before));
+                space_before = false;
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push((word, space_before));
+    }
+
+    let mut wrapped: Vec<String> = Vec::new();
+    let mut line = String::new();
+    let mut width = 0;
+    for (token, space_before) in tokens {
+        let token_width = text_width(&token, unit, codes);
+        let separator = if space_before && !line.is_empty() { 1 } else { 0 };
+        if !line.is_empty() && width + separator + token_width > max_width {
+            wrapped.push(std::mem::take(&mut line));
+            width = 0;
+        } else if separator == 1 {
+            line.push(' ');
+            width += 1;
+        }
+        if token_width <= max_width {
+            line.push_str(&token);
+            width += token_width;
+            continue;
+        }
+        for c in token.chars() {
+            let c_width = text_width(&c.to_string(), unit, codes);
+            if !line.is_empty() && width + c_width > max_width {
+                wrapped.push(std::mem::take(&mut line));
+                width = 0;
+            }
+            line.push(c);
+            width += c_width;
+        }
+    }
+    if !line.is_empty() || wrapped.is_empty() {
+        wrapped.push(line);
+    }
+    wrapped[0].insert_str(0, &prefix);
+    wrapped
+}
+
+fn line_text(command: &Value) -> Option<&str> {
+    command.get("parameters")?.get(0)?.as_str()
+}
+
+/// Re-wraps the message boxes of a command list whose lines differ from `original` (the same list
+/// before translation), adding or removing Show Text lines (401) as needed. A message longer than
+/// `max_lines` continues in new windows: Show Text (101) commands copied from the box's own, with
+/// a name box code repeated on their first line. Untouched boxes keep their line breaks. Returns
+/// whether anything changed.
+fn wrap_command_list(list: &mut Vec<Value>, original: &[Value], settings: &WordWrapSettings, codes: &ControlCodes) -> bool {
+    let mut output = Vec::with_capacity(list.len());
+    let mut changed = false;
+    let mut index = 0;
+    while index < list.len() {
+        let code = list[index].get("code").and_then(Value::as_i64);
+        if code != Some(401) {
+            output.push(list[index].clone());
+            index += 1;
+            continue;
+        }
+        let indent = list[index].get("indent").cloned();
+        let end = (index..list.len())
+            .find(|&i| list[i].get("code").and_then(Value::as_i64) != Some(401) || list[i].get("indent").cloned() != indent)
+            .unwrap_or(list.len());
+        let translated = (index..end).any(|i| original.get(i).and_then(line_text) != line_text(&list[i]));
+        if !translated {
+            output.extend_from_slice(&list[index..end]);
+            index = end;
+            continue;
+        }
+        let show_text = index
+            .checked_sub(1)
+            .and_then(|i| list.get(i))
+            .filter(|command| command.get("code").and_then(Value::as_i64) == Some(101));
+        let has_face = show_text.and_then(line_text).is_some_and(|face| !face.is_empty());
+        let max_width = settings.max_width_with_face.filter(|_| has_face).unwrap_or(settings.max_width);
+        let lines: Vec<String> = list[index..end].iter().filter_map(line_text).map(str::to_string).collect();
+        let wrapped = wrap_message(&lines, max_width, settings.unit, codes);
+        changed |= wrapped != lines;
+        let name_box = split_name_box_code(&wrapped[0]).map(|name_box| format!("{}{}>", name_box.open, name_box.name));
+        for (window, window_lines) in wrapped.chunks(settings.max_lines).enumerate() {
+            if window > 0 {
+                let mut header = show_text.cloned().unwrap_or_else(|| json!({ "code": 101, "parameters": ["", 0, 0, 2] }));
+                header["indent"] = list[index].get("indent").cloned().unwrap_or(json!(0));
+                output.push(header);
+            }
+            for (line_index, text) in window_lines.iter().enumerate() {
+                let text = match &name_box {
+                    Some(name_box) if window > 0 && line_index == 0 => format!("{}{}", name_box, text),
+                    _ => text.clone(),
+                };
+                let mut command = list[index].clone();
+                command["parameters"] = Value::Array(vec![Value::String(text)]);
+                output.push(command);
+            }
+        }
+        index = end;
+    }
+    if changed {
+        *list = output;
+    }
+    changed
+}
+
+/// Walks a data file for command lists (`list` arrays of events, common events and troop pages)
+/// and wraps each against the list at the same place in the original.
+fn wrap_lists(value: &mut Value, original: Option<&Value>, settings: &WordWrapSettings, codes: &ControlCodes) -> bool {
+    let mut changed = false;
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                let original_field = original.and_then(|o| o.get(key));
+                changed |= match (key.as_str(), field, original_field) {
+                    ("list", Value::Array(list), Some(Value::Array(original_list))) => {
+                        wrap_command_list(list, original_list, settings, codes)
+                    }
+                    (_, field, original_field) => wrap_lists(field, original_field, settings, codes),
+                };
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                changed |= wrap_lists(item, original.and_then(|o| o.get(index)), settings, codes);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// Re-wraps the translated dialogue of reconstructed maps, common events and troops when the
+/// project configures a word wrap width. Runs after verification, which checks translations
+/// against the extracted line structure.
+pub fn apply_word_wrap(project_path: &str, mut reconstructed: ReconstructedProject) -> Result<ReconstructedProject, CoreError> {
+    let Some(settings) = load_project_config(project_path)?.word_wrap else {
+        return Ok(reconstructed);
+    };
+    validate_word_wrap(&settings)?;
+    let codes = load_project_ruleset(project_path).map(|ruleset| ControlCodes::from_ruleset(&ruleset)).unwrap_or_default();
+    for (relative_path, content) in reconstructed.files.iter_mut() {
+        if !matches!(file_kind(relative_path), FileKind::Maps | FileKind::CommonEvents | FileKind::Troops) {
+            continue;
+        }
+        let original = match fs::read_to_string(Path::new(project_path).join(relative_path.as_str())) {
+            Ok(original) => original,
+            Err(e) => {
+                reconstructed.errors.push(format!("Word wrap skipped for {}: {}", relative_path, e));
+                continue;
+            }
+        };
+        let (Ok(mut value), Ok(original)) = (serde_json::from_str::<Value>(content), serde_json::from_str::<Value>(&original)) else {
+            reconstructed.errors.push(format!("Word wrap skipped for {}: not valid JSON", relative_path));
+            continue;
+        };
+        if wrap_lists(&mut value, Some(&original), &settings, &codes) {
+            *content = serde_json::to_string_pretty(&value)
+                .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize {}: {}", relative_path, e)))?;
+        }
+    }
+    Ok(reconstructed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_translated_message_boxes() {
+        let codes = ControlCodes::default();
+        let lines = vec![r"\C[2]Harold\C[0] picked up".to_string(), "a potion and an antidote!".to_string()];
+        assert_eq!(
+            wrap_message(&lines, 20, WidthUnit::Columns, &codes),
+            vec![r"\C[2]Harold\C[0] picked up a", "potion and an", "antidote!"]
+        );
+        let japanese = vec!["ポーションを".to_string(), "手に入れた！".to_string()];
+        assert_eq!(wrap_message(&japanese, 10, WidthUnit::Columns, &codes), vec!["ポーション", "を手に入れ", "た！"]);
+        assert_eq!(wrap_message(&japanese, 6, WidthUnit::Characters, &codes), vec!["ポーションを", "手に入れた！"]);
+        assert_eq!(wrap_message(&[r"\n<Marsha>Hi there".to_string()], 5, WidthUnit::Bytes, &codes), vec![r"\n<Marsha>Hi", "there"]);
+
+        let original = json!([
+            {"code":101,"indent":0,"parameters":["Actor1",0,0,2]},
+            {"code":401,"indent":0,"parameters":["やあ"]},
+            {"code":401,"indent":0,"parameters":["元気？"]},
+            {"code":101,"indent":0,"parameters":["",0,0,2]},
+            {"code":401,"indent":0,"parameters":["そのまま"]},
+            {"code":0,"indent":0,"parameters":[]}
+        ]);
+        let mut list = original.as_array().unwrap().clone();
+        list[1]["parameters"][0] = json!("Hello there, my old friend.");
+        list[2]["parameters"][0] = json!("How are you?");
+        let settings = WordWrapSettings { max_width: 30, max_width_with_face: Some(12), unit: WidthUnit::Columns, max_lines: 3 };
+        assert!(wrap_command_list(&mut list, original.as_array().unwrap(), &settings, &codes));
+        let texts: Vec<(i64, &str)> = list
+            .iter()
+            .map(|c| (c["code"].as_i64().unwrap(), c["parameters"][0].as_str().unwrap_or_default()))
+            .collect();
+        // The fourth line goes to a new window with the same face.
+        assert_eq!(
+            texts,
+            vec![
+                (101, "Actor1"), (401, "Hello there,"), (401, "my old"), (401, "friend. How"),
+                (101, "Actor1"), (401, "are you?"),
+                (101, ""), (401, "そのまま"), (0, ""),
+            ]
+        );
+        assert!(validate_word_wrap(&WordWrapSettings { max_width: 0, ..settings }).is_err());
+    }
+}
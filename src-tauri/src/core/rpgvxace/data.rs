@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use super::marshal::{self, symbol_name, Value};
+
+/// String ivars with player-visible text, by RGSS3 class.
+fn text_fields(class: &[u8]) -> &'static [&'static str] {
+    match class {
+        b"RPG::Actor" => &["name", "nickname", "description", "note"],
+        b"RPG::Class" | b"RPG::Item" | b"RPG::Weapon" | b"RPG::Armor" => &["name", "description", "note"],
+        b"RPG::Skill" => &["name", "description", "note", "message1", "message2"],
+        b"RPG::State" => &["name", "description", "note", "message1", "message2", "message3", "message4"],
+        b"RPG::Enemy" => &["name", "note"],
+        b"RPG::Map" => &["display_name", "note"],
+        b"RPG::MapInfo" => &["name"],
+        b"RPG::System" => &["game_title", "currency_unit"],
+        _ => &[],
+    }
+}
+
+/// Array ivars whose items are all player-visible strings.
+fn text_list_fields(class: &[u8]) -> &'static [&'static str] {
+    match class {
+        b"RPG::System" => &["elements", "skill_types", "weapon_types", "armor_types"],
+        b"RPG::System::Terms" => &["basic", "params", "etypes", "commands"],
+        _ => &[],
+    }
+}
+
+fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() { name.to_string() } else { format!("{}.{}", path, name) }
+}
+
+fn ivar<'v>(symbols: &[Vec<u8>], ivars: &'v [(Value, Value)], name: &[u8]) -> Option<&'v Value> {
+    ivars.iter().find(|(key, _)| symbol_name(symbols, key) == Some(name)).map(|(_, value)| value)
+}
+
+type Visit<'f> = dyn FnMut(u32, String, &mut Vec<u8>) + 'f;
+
+/// Text of an `RPG::EventCommand`, with the same parameter paths as the MV event parsers.
+fn visit_command(symbols: &[Vec<u8>], ivars: &mut [(Value, Value)], path: &str, id: u32, visit: &mut Visit) {
+    let Some(code) = ivar(symbols, ivars, b"@code").and_then(Value::as_fixnum) else { return };
+    let Some((_, Value::Array(parameters))) =
+        ivars.iter_mut().find(|(key, _)| symbol_name(symbols, key) == Some(&b"@parameters"[..]))
+    else {
+        return;
+    };
+    let parameters_path = field_path(path, "parameters");
+    let index = match code {
+        401 | 405 => 0, // Show Text / Show Scrolling Text line
+        320 | 324 => 1, // Change Name / Change Nickname
+        102 => {
+            // Show Choices
+            if let Some(Value::Array(choices)) = parameters.get_mut(0) {
+                for (choice_index, choice) in choices.iter_mut().enumerate() {
+                    if let Some(text) = choice.as_bytes_mut() {
+                        visit(id, format!("{}[0][{}]", parameters_path, choice_index), text);
+                    }
+                }
+            }
+            return;
+        }
+        _ => return,
+    };
+    if let Some(text) = parameters.get_mut(index).and_then(Value::as_bytes_mut) {
+        visit(id, format!("{}[{}]", parameters_path, index), text);
+    }
+}
+
+/// Calls `visit` with the object id, path and bytes of every translatable string under `value`.
+/// Paths follow the MV JSON layout, e.g. "[1].name" or "events[3].pages[0].list[2].parameters[0]".
+fn walk(symbols: &[Vec<u8>], value: &mut Value, path: &str, id: u32, visit: &mut Visit) {
+    match value {
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                walk(symbols, item, &format!("{}[{}]", path, index), id, visit);
+            }
+        }
+        Value::Hash(entries) | Value::HashWithDefault { entries, .. } => {
+            for (key, item) in entries.iter_mut() {
+                // Maps and MapInfos key their events and maps by integer id.
+                if let Some(key) = key.as_fixnum() {
+                    walk(symbols, item, &format!("{}[{}]", path, key), u32::try_from(key).unwrap_or(id), visit);
+                }
+            }
+        }
+        Value::WithIvars { value, .. } => walk(symbols, value, path, id, visit),
+        Value::Object { class, ivars } => {
+            let class = symbol_name(symbols, class).unwrap_or_default();
+            if class == b"RPG::EventCommand" {
+                visit_command(symbols, ivars, path, id, visit);
+                return;
+            }
+            let id = ivar(symbols, ivars, b"@id")
+                .and_then(Value::as_fixnum)
+                .and_then(|id| u32::try_from(id).ok())
+                .unwrap_or(id);
+            let (fields, lists) = (text_fields(class), text_list_fields(class));
+            for (key, item) in ivars.iter_mut() {
+                let Some(name) = symbol_name(symbols, key).and_then(|name| name.strip_prefix(b"@")) else { continue };
+                let name = String::from_utf8_lossy(name);
+                let item_path = field_path(path, &name);
+                if fields.contains(&name.as_ref()) {
+                    if let Some(text) = item.as_bytes_mut() {
+                        visit(id, item_path, text);
+                    }
+                } else if lists.contains(&name.as_ref()) {
+                    if let Value::Array(items) = item {
+                        for (index, text) in items.iter_mut().enumerate().filter_map(|(i, v)| Some((i, v.as_bytes_mut()?))) {
+                            visit(id, format!("{}[{}]", item_path, index), text);
+                        }
+                    }
+                } else {
+                    walk(symbols, item, &item_path, id, visit);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extracts the translatable strings of a `.rvdata2` file. Strings that aren't UTF-8 are skipped.
+pub fn extract_strings(bytes: &[u8], source_file: &str) -> Result<Vec<SourceStringData>, String> {
+    let mut document = marshal::load(bytes).map_err(|e| e.to_string())?;
+    let mut strings = Vec::new();
+    walk(&document.symbols, &mut document.root, "", 0, &mut |object_id, json_path, text| {
+        match std::str::from_utf8(text) {
+            Ok(text) if !text.trim().is_empty() => strings.push(SourceStringData {
+                object_id,
+                original_text: text.to_string(),
+                source_file: source_file.to_string(),
+                json_path,
+                dialogue_block_id: None,
+                speaker: None,
+            }),
+            Ok(_) => {}
+            Err(_) => eprintln!("Skipping non UTF-8 string at {} in {}.", json_path, source_file),
+        }
+    });
+    Ok(strings)
+}
+
+/// Writes translations into a `.rvdata2` file. Everything else, including the bytes of objects
+/// the app doesn't know, is kept as it was.
+pub fn reconstruct_data(bytes: &[u8], translations: Vec<&WorkingTranslation>) -> Result<Vec<u8>, CoreError> {
+    let mut document = marshal::load(bytes)?;
+    let by_path: HashMap<&str, &str> = translations
+        .iter()
+        .filter(|entry| entry.error.is_none() && !entry.translated_text.is_empty())
+        .map(|entry| (entry.json_path.as_str(), entry.translated_text.as_str()))
+        .collect();
+    walk(&document.symbols, &mut document.root, "", 0, &mut |_, json_path, text| {
+        if let Some(translated) = by_path.get(json_path.as_str()) {
+            *text = translated.as_bytes().to_vec();
+        }
+    });
+    Ok(marshal::dump(&document))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    fn symbol(name: &str) -> Value {
+        Value::Symbol(name.as_bytes().to_vec())
+    }
+
+    fn text(value: &str) -> Value {
+        Value::WithIvars {
+            value: Box::new(Value::String(value.as_bytes().to_vec())),
+            ivars: vec![(symbol("E"), Value::True)],
+        }
+    }
+
+    fn object(class: &str, ivars: Vec<(&str, Value)>) -> Value {
+        Value::Object {
+            class: Box::new(symbol(class)),
+            ivars: ivars.into_iter().map(|(name, value)| (symbol(name), value)).collect(),
+        }
+    }
+
+    fn command(code: i32, parameters: Vec<Value>) -> Value {
+        object("RPG::EventCommand", vec![
+            ("@code", Value::Fixnum(code)),
+            ("@indent", Value::Fixnum(0)),
+            ("@parameters", Value::Array(parameters)),
+        ])
+    }
+
+    /// `Marshal.dump` of a small Map001.rvdata2 (symbols are written in full; links are
+    /// equivalent for the walker).
+    fn map_bytes() -> Vec<u8> {
+        let page = object("RPG::Event::Page", vec![(
+            "@list",
+            Value::Array(vec![
+                command(101, vec![text(""), Value::Fixnum(0), Value::Fixnum(0), Value::Fixnum(2)]),
+                command(401, vec![text("こんにちは")]),
+                command(102, vec![Value::Array(vec![text("はい"), text("いいえ")]), Value::Fixnum(2)]),
+                command(355, vec![text("p 'script'")]),
+                command(0, vec![]),
+            ]),
+        )]);
+        let event = object("RPG::Event", vec![
+            ("@id", Value::Fixnum(3)),
+            ("@name", text("EV003")),
+            ("@pages", Value::Array(vec![page])),
+        ]);
+        let map = object("RPG::Map", vec![
+            ("@display_name", text("始まりの村")),
+            ("@data", Value::UserDefined { class: Box::new(symbol("Table")), data: vec![0, 1, 2, 3] }),
+            ("@events", Value::Hash(vec![(Value::Fixnum(3), event)])),
+        ]);
+        marshal::dump(&marshal::Document { root: map, symbols: Vec::new() })
+    }
+
+    #[test]
+    fn test_extract_map_strings() {
+        let strings = extract_strings(&map_bytes(), "Data/Map001.rvdata2").unwrap();
+        let found: Vec<(u32, &str, &str)> =
+            strings.iter().map(|s| (s.object_id, s.json_path.as_str(), s.original_text.as_str())).collect();
+        assert_eq!(found, vec![
+            (0, "display_name", "始まりの村"),
+            (3, "events[3].pages[0].list[1].parameters[0]", "こんにちは"),
+            (3, "events[3].pages[0].list[2].parameters[0][0]", "はい"),
+            (3, "events[3].pages[0].list[2].parameters[0][1]", "いいえ"),
+        ]);
+    }
+
+    #[test]
+    fn test_reconstruct_map() {
+        let original = map_bytes();
+        let entry = |json_path: &str, translated: &str, error: Option<&str>| WorkingTranslation {
+            object_id: 3,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "Data/Map001.rvdata2".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "test".to_string(),
+            error: error.map(str::to_string),
+            status: TranslationStatus::MachineTranslated,
+        };
+        let translations = [
+            entry("events[3].pages[0].list[1].parameters[0]", "Hello", None),
+            entry("events[3].pages[0].list[2].parameters[0][1]", "No", Some("timeout")),
+        ];
+        let rebuilt = reconstruct_data(&original, translations.iter().collect()).unwrap();
+        let strings = extract_strings(&rebuilt, "Data/Map001.rvdata2").unwrap();
+        assert_eq!(strings[1].original_text, "Hello");
+        assert_eq!(strings[3].original_text, "いいえ");
+        // The untouched Table data and encoding ivars survive byte for byte.
+        assert_eq!(reconstruct_data(&original, Vec::new()).unwrap(), original);
+    }
+}
@@ -0,0 +1,426 @@
+use crate::error::CoreError;
+
+/// Ruby Marshal format version written by RGSS3 (Ruby 1.9).
+const MAJOR_VERSION: u8 = 4;
+const MINOR_VERSION: u8 = 8;
+
+/// A Ruby Marshal value. Symbols and object references are kept as they were read (`;` and `@`
+/// links are not resolved), so writing an unchanged tree gives back the same bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Nil,
+    True,
+    False,
+    Fixnum(i32),
+    // Floats are stored as text, e.g. "1.5", "inf" or "nan".
+    Float(Vec<u8>),
+    Bignum { sign: u8, data: Vec<u8> },
+    Symbol(Vec<u8>),
+    // Index into the symbols read so far.
+    SymbolLink(usize),
+    String(Vec<u8>),
+    Regexp { source: Vec<u8>, options: u8 },
+    Array(Vec<Value>),
+    Hash(Vec<(Value, Value)>),
+    HashWithDefault { entries: Vec<(Value, Value)>, default: Box<Value> },
+    Object { class: Box<Value>, ivars: Vec<(Value, Value)> },
+    Struct { class: Box<Value>, members: Vec<(Value, Value)> },
+    // `_dump` data, e.g. RGSS's Table, Color and Tone.
+    UserDefined { class: Box<Value>, data: Vec<u8> },
+    UserMarshal { class: Box<Value>, data: Box<Value> },
+    // A value followed by instance variables; strings carry their encoding this way (`:E`).
+    WithIvars { value: Box<Value>, ivars: Vec<(Value, Value)> },
+    Extended { module: Box<Value>, value: Box<Value> },
+    UserClass { class: Box<Value>, value: Box<Value> },
+    Data { class: Box<Value>, value: Box<Value> },
+    Class(Vec<u8>),
+    Module(Vec<u8>),
+    OldModule(Vec<u8>),
+    // Index into the objects read so far.
+    Link(usize),
+}
+
+impl Value {
+    /// The bytes of a string, looking through its encoding ivars.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::String(bytes) => Some(bytes),
+            Value::WithIvars { value, .. } => value.as_bytes(),
+            _ => None,
+        }
+    }
+
+    /// Mutable bytes of a string, looking through its encoding ivars.
+    pub fn as_bytes_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match self {
+            Value::String(bytes) => Some(bytes),
+            Value::WithIvars { value, .. } => value.as_bytes_mut(),
+            _ => None,
+        }
+    }
+
+    pub fn as_fixnum(&self) -> Option<i32> {
+        match self {
+            Value::Fixnum(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// A parsed Marshal stream with the symbol table needed to read class and ivar names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub root: Value,
+    // Symbols in the order they were read; `SymbolLink` indexes into it.
+    pub symbols: Vec<Vec<u8>>,
+}
+
+impl Document {
+    pub fn symbol_name<'a>(&'a self, value: &'a Value) -> Option<&'a [u8]> {
+        symbol_name(&self.symbols, value)
+    }
+}
+
+/// Name of a symbol value (`:name` or a `;` link to one).
+pub fn symbol_name<'a>(symbols: &'a [Vec<u8>], value: &'a Value) -> Option<&'a [u8]> {
+    match value {
+        Value::Symbol(name) => Some(name),
+        Value::SymbolLink(index) => symbols.get(*index).map(Vec::as_slice),
+        Value::WithIvars { value, .. } => symbol_name(symbols, value),
+        _ => None,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    symbols: Vec<Vec<u8>>,
+}
+
+impl Reader<'_> {
+    fn error(&self, message: &str) -> CoreError {
+        CoreError::Custom(format!("Invalid Marshal data at byte {}: {}", self.position, message))
+    }
+
+    fn byte(&mut self) -> Result<u8, CoreError> {
+        let byte = *self.bytes.get(self.position).ok_or_else(|| self.error("unexpected end of data"))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], CoreError> {
+        let end = self.position.checked_add(len).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or_else(|| self.error("unexpected end of data"))?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn long(&mut self) -> Result<i32, CoreError> {
+        let c = self.byte()? as i8;
+        Ok(match c {
+            0 => 0,
+            5..=127 => c as i32 - 5,
+            -128..=-5 => c as i32 + 5,
+            1..=4 => {
+                let mut x: i32 = 0;
+                for i in 0..c as u32 {
+                    x |= (self.byte()? as i32) << (8 * i);
+                }
+                x
+            }
+            _ => {
+                let mut x: i32 = -1;
+                for i in 0..(-c) as u32 {
+                    x &= !(0xff << (8 * i));
+                    x |= (self.byte()? as i32) << (8 * i);
+                }
+                x
+            }
+        })
+    }
+
+    fn len(&mut self) -> Result<usize, CoreError> {
+        let len = self.long()?;
+        usize::try_from(len).map_err(|_| self.error("negative length"))
+    }
+
+    fn byte_string(&mut self) -> Result<Vec<u8>, CoreError> {
+        let len = self.len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn pairs(&mut self) -> Result<Vec<(Value, Value)>, CoreError> {
+        let count = self.len()?;
+        let mut pairs = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            pairs.push((self.value()?, self.value()?));
+        }
+        Ok(pairs)
+    }
+
+    fn boxed(&mut self) -> Result<Box<Value>, CoreError> {
+        Ok(Box::new(self.value()?))
+    }
+
+    fn value(&mut self) -> Result<Value, CoreError> {
+        let value = match self.byte()? {
+            b'0' => Value::Nil,
+            b'T' => Value::True,
+            b'F' => Value::False,
+            b'i' => Value::Fixnum(self.long()?),
+            b'f' => Value::Float(self.byte_string()?),
+            b'l' => {
+                let sign = self.byte()?;
+                let shorts = self.len()?;
+                Value::Bignum { sign, data: self.take(shorts * 2)?.to_vec() }
+            }
+            b':' => {
+                let name = self.byte_string()?;
+                self.symbols.push(name.clone());
+                Value::Symbol(name)
+            }
+            b';' => {
+                let index = self.len()?;
+                if index >= self.symbols.len() {
+                    return Err(self.error("symbol link out of range"));
+                }
+                Value::SymbolLink(index)
+            }
+            b'"' => Value::String(self.byte_string()?),
+            b'/' => Value::Regexp { source: self.byte_string()?, options: self.byte()? },
+            b'[' => {
+                let count = self.len()?;
+                let mut items = Vec::with_capacity(count.min(1024));
+                for _ in 0..count {
+                    items.push(self.value()?);
+                }
+                Value::Array(items)
+            }
+            b'{' => Value::Hash(self.pairs()?),
+            b'}' => Value::HashWithDefault { entries: self.pairs()?, default: self.boxed()? },
+            b'o' => Value::Object { class: self.boxed()?, ivars: self.pairs()? },
+            b'S' => Value::Struct { class: self.boxed()?, members: self.pairs()? },
+            b'u' => Value::UserDefined { class: self.boxed()?, data: self.byte_string()? },
+            b'U' => Value::UserMarshal { class: self.boxed()?, data: self.boxed()? },
+            b'I' => Value::WithIvars { value: self.boxed()?, ivars: self.pairs()? },
+            b'e' => Value::Extended { module: self.boxed()?, value: self.boxed()? },
+            b'C' => Value::UserClass { class: self.boxed()?, value: self.boxed()? },
+            b'd' => Value::Data { class: self.boxed()?, value: self.boxed()? },
+            b'c' => Value::Class(self.byte_string()?),
+            b'm' => Value::Module(self.byte_string()?),
+            b'M' => Value::OldModule(self.byte_string()?),
+            b'@' => Value::Link(self.len()?),
+            other => return Err(self.error(&format!("unknown type byte 0x{:02x}", other))),
+        };
+        Ok(value)
+    }
+}
+
+/// Parses a Marshal stream such as the content of a `.rvdata2` file.
+pub fn load(bytes: &[u8]) -> Result<Document, CoreError> {
+    let mut reader = Reader { bytes, position: 0, symbols: Vec::new() };
+    let (major, minor) = (reader.byte()?, reader.byte()?);
+    if (major, minor) != (MAJOR_VERSION, MINOR_VERSION) {
+        return Err(CoreError::Custom(format!(
+            "Unsupported Marshal version {}.{} (expected {}.{})",
+            major, minor, MAJOR_VERSION, MINOR_VERSION
+        )));
+    }
+    let root = reader.value()?;
+    if reader.position != bytes.len() {
+        return Err(reader.error("trailing data after the root object"));
+    }
+    Ok(Document { root, symbols: reader.symbols })
+}
+
+fn write_long(out: &mut Vec<u8>, x: i32) {
+    match x {
+        0 => out.push(0),
+        1..=122 => out.push((x + 5) as u8),
+        -123..=-1 => out.push((x - 5) as u8),
+        _ => {
+            let mut bytes = Vec::with_capacity(4);
+            let mut rest = x;
+            loop {
+                bytes.push((rest & 0xff) as u8);
+                rest >>= 8;
+                if rest == 0 || rest == -1 {
+                    break;
+                }
+            }
+            let len = bytes.len() as i8;
+            out.push(if rest == 0 { len } else { -len } as u8);
+            out.extend(bytes);
+        }
+    }
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(out, bytes.len() as i32);
+    out.extend_from_slice(bytes);
+}
+
+fn write_pairs(out: &mut Vec<u8>, pairs: &[(Value, Value)]) {
+    write_long(out, pairs.len() as i32);
+    for (key, value) in pairs {
+        write_value(out, key);
+        write_value(out, value);
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Nil => out.push(b'0'),
+        Value::True => out.push(b'T'),
+        Value::False => out.push(b'F'),
+        Value::Fixnum(n) => {
+            out.push(b'i');
+            write_long(out, *n);
+        }
+        Value::Float(text) => {
+            out.push(b'f');
+            write_bytes(out, text);
+        }
+        Value::Bignum { sign, data } => {
+            out.push(b'l');
+            out.push(*sign);
+            write_long(out, (data.len() / 2) as i32);
+            out.extend_from_slice(data);
+        }
+        Value::Symbol(name) => {
+            out.push(b':');
+            write_bytes(out, name);
+        }
+        Value::SymbolLink(index) => {
+            out.push(b';');
+            write_long(out, *index as i32);
+        }
+        Value::String(bytes) => {
+            out.push(b'"');
+            write_bytes(out, bytes);
+        }
+        Value::Regexp { source, options } => {
+            out.push(b'/');
+            write_bytes(out, source);
+            out.push(*options);
+        }
+        Value::Array(items) => {
+            out.push(b'[');
+            write_long(out, items.len() as i32);
+            for item in items {
+                write_value(out, item);
+            }
+        }
+        Value::Hash(entries) => {
+            out.push(b'{');
+            write_pairs(out, entries);
+        }
+        Value::HashWithDefault { entries, default } => {
+            out.push(b'}');
+            write_pairs(out, entries);
+            write_value(out, default);
+        }
+        Value::Object { class, ivars } => {
+            out.push(b'o');
+            write_value(out, class);
+            write_pairs(out, ivars);
+        }
+        Value::Struct { class, members } => {
+            out.push(b'S');
+            write_value(out, class);
+            write_pairs(out, members);
+        }
+        Value::UserDefined { class, data } => {
+            out.push(b'u');
+            write_value(out, class);
+            write_bytes(out, data);
+        }
+        Value::UserMarshal { class, data } => {
+            out.push(b'U');
+            write_value(out, class);
+            write_value(out, data);
+        }
+        Value::WithIvars { value, ivars } => {
+            out.push(b'I');
+            write_value(out, value);
+            write_pairs(out, ivars);
+        }
+        Value::Extended { module, value } => {
+            out.push(b'e');
+            write_value(out, module);
+            write_value(out, value);
+        }
+        Value::UserClass { class, value } => {
+            out.push(b'C');
+            write_value(out, class);
+            write_value(out, value);
+        }
+        Value::Data { class, value } => {
+            out.push(b'd');
+            write_value(out, class);
+            write_value(out, value);
+        }
+        Value::Class(name) => {
+            out.push(b'c');
+            write_bytes(out, name);
+        }
+        Value::Module(name) => {
+            out.push(b'm');
+            write_bytes(out, name);
+        }
+        Value::OldModule(name) => {
+            out.push(b'M');
+            write_bytes(out, name);
+        }
+        Value::Link(index) => {
+            out.push(b'@');
+            write_long(out, *index as i32);
+        }
+    }
+}
+
+/// Serializes a document back into a Marshal stream.
+pub fn dump(document: &Document) -> Vec<u8> {
+    let mut out = vec![MAJOR_VERSION, MINOR_VERSION];
+    write_value(&mut out, &document.root);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Marshal.dump([nil, "abc", 300, -1, :a, :a, 1.5, {1 => true}])
+    const SAMPLE: &[u8] = b"\x04\x08[\x0d0I\"\x08abc\x06:\x06ETi\x02\x2c\x01i\xfa:\x06a;\x06f\x081.5{\x06i\x06T";
+
+    #[test]
+    fn test_load_and_dump_round_trip() {
+        let document = load(SAMPLE).unwrap();
+        let Value::Array(items) = &document.root else { panic!("expected an array") };
+        assert_eq!(items.len(), 8);
+        assert_eq!(items[1].as_bytes(), Some(&b"abc"[..]));
+        assert_eq!(items[2].as_fixnum(), Some(300));
+        assert_eq!(items[3].as_fixnum(), Some(-1));
+        assert_eq!(document.symbol_name(&items[5]), Some(&b"a"[..]));
+        assert_eq!(dump(&document), SAMPLE);
+    }
+
+    #[test]
+    fn test_long_encoding() {
+        for x in [0, 1, 122, 123, 255, 256, -1, -123, -124, -256, -257, 65_536, i32::MAX, i32::MIN] {
+            let mut out = Vec::new();
+            write_long(&mut out, x);
+            let mut reader = Reader { bytes: &out, position: 0, symbols: Vec::new() };
+            assert_eq!(reader.long().unwrap(), x, "round trip of {}", x);
+            assert_eq!(reader.position, out.len());
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_data() {
+        assert!(load(b"\x04\x090").is_err());
+        assert!(load(b"\x04\x08[\x07").is_err());
+        assert!(load(b"\x04\x08;\x00").is_err());
+    }
+}
@@ -0,0 +1,5 @@
+// RPG Maker VX Ace games. Their data files (`Data/*.rvdata2`) are Ruby Marshal dumps of the RGSS3
+// `RPG::*` classes; text is extracted and written back in place, keeping every other byte.
+pub mod marshal;
+pub mod data;
+pub mod project;
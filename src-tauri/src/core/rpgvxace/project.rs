@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::Path;
+use crate::core::config::IgnoredFiles;
+use crate::core::rpgmv::filter::CompiledExtractionFilter;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use super::data;
+
+/// Folder of a VX Ace game's data files, relative to the project root.
+pub const DATA_DIR: &str = "Data";
+/// Extension of VX Ace data files.
+pub const DATA_EXTENSION: &str = "rvdata2";
+/// Encrypted archive of a deployed game; its files must be extracted before translating.
+const ARCHIVE_FILE: &str = "Game.rgss3a";
+
+// Database files with player-visible text. Animations, Tilesets and Scripts are left out.
+const TEXT_FILES: [&str; 12] = [
+    "Actors", "Classes", "Skills", "Items", "Weapons", "Armors",
+    "Enemies", "Troops", "States", "CommonEvents", "System", "MapInfos",
+];
+
+/// True for an RPG Maker VX Ace game: an editor project, unpacked data or a deployed archive.
+pub fn is_vx_ace_project(project_path: &str) -> bool {
+    let path = Path::new(project_path);
+    path.join("Game.rvproj2").is_file()
+        || path.join(DATA_DIR).join(format!("System.{}", DATA_EXTENSION)).is_file()
+        || path.join(ARCHIVE_FILE).is_file()
+}
+
+fn is_text_data_file(file_name: &str) -> bool {
+    let Some(stem) = file_name.strip_suffix(&format!(".{}", DATA_EXTENSION)) else { return false };
+    let is_map = stem.strip_prefix("Map").is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    is_map || TEXT_FILES.contains(&stem)
+}
+
+/// Relative paths (with `/`) of the data files with text, e.g. "Data/Actors.rvdata2". Backup
+/// copies ("Map001 - Copy.rvdata2") don't match the file names the game loads and are skipped.
+pub fn data_files(project_path: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(Path::new(project_path).join(DATA_DIR)) else { return Vec::new() };
+    let mut files: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| is_text_data_file(name))
+        .map(|name| format!("{}/{}", DATA_DIR, name))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Extracts the text of every data file of a VX Ace project. `should_stop` is checked before
+/// each file, as in the RPG Maker MV extraction.
+pub fn extract_translatable_strings(
+    project_path: &str,
+    ignored: &IgnoredFiles,
+    filter: &CompiledExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let files = data_files(project_path);
+    if files.is_empty() {
+        return Err(if Path::new(project_path).join(ARCHIVE_FILE).is_file() {
+            format!("The data of {} is packed in {}. Extract it into {}/ first.", project_path, ARCHIVE_FILE, DATA_DIR)
+        } else {
+            format!("No .{} data files found in {}/{}", DATA_EXTENSION, project_path, DATA_DIR)
+        });
+    }
+    let mut all_strings = Vec::new();
+    let mut parsing_errors = Vec::new();
+    for relative_file_path in files {
+        if should_stop() {
+            eprintln!("Extraction stopped after {} strings.", all_strings.len());
+            break;
+        }
+        if ignored.is_ignored(&relative_file_path) || !filter.allows_file(&relative_file_path) {
+            continue;
+        }
+        eprintln!("Processing file: {}", relative_file_path);
+        // Data files are binary, so they are read as bytes rather than through extract_listed_files.
+        match fs::read(Path::new(project_path).join(&relative_file_path)) {
+            Ok(bytes) => match data::extract_strings(&bytes, &relative_file_path) {
+                Ok(mut file_strings) => all_strings.append(&mut file_strings),
+                Err(e) => parsing_errors.push(format!("Error parsing {}: {}", relative_file_path, e)),
+            },
+            Err(e) => parsing_errors.push(format!("Failed to read file {}: {}", relative_file_path, e)),
+        }
+    }
+    all_strings.retain(|entry| filter.allows_entry(entry));
+    if !parsing_errors.is_empty() {
+        eprintln!("Errors during string extraction:\n{}", parsing_errors.join("\n"));
+    }
+    Ok(all_strings)
+}
+
+/// Rebuilds a data file with its translations applied.
+pub fn reconstruct_data_file(original: &[u8], translations: Vec<&WorkingTranslation>) -> Result<Vec<u8>, CoreError> {
+    data::reconstruct_data(original, translations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::core::rpgvxace::marshal::{self, Document, Value};
+    use crate::models::extraction::ExtractionFilter;
+    use tempfile::tempdir;
+
+    fn actors() -> Vec<u8> {
+        let symbol = |name: &str| Value::Symbol(name.as_bytes().to_vec());
+        let actor = Value::Object {
+            class: Box::new(symbol("RPG::Actor")),
+            ivars: vec![
+                (symbol("@id"), Value::Fixnum(1)),
+                (symbol("@name"), Value::String("ハロルド".as_bytes().to_vec())),
+                (symbol("@nickname"), Value::String(Vec::new())),
+            ],
+        };
+        marshal::dump(&Document { root: Value::Array(vec![Value::Nil, actor]), symbols: Vec::new() })
+    }
+
+    #[test]
+    fn test_extract_vx_ace_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("Data")).unwrap();
+        fs::write(root.join("Game.rvproj2"), "RPGVXAce 1.02").unwrap();
+        fs::write(root.join("Data/Actors.rvdata2"), actors()).unwrap();
+        fs::write(root.join("Data/Actors - Copy.rvdata2"), actors()).unwrap();
+        fs::write(root.join("Data/Scripts.rvdata2"), b"\x04\x08[\x00").unwrap();
+
+        let project_path = root.to_str().unwrap();
+        assert!(is_vx_ace_project(project_path));
+        assert_eq!(data_files(project_path), vec!["Data/Actors.rvdata2"]);
+
+        let filter = ExtractionFilter::default();
+        let compiled = CompiledExtractionFilter::new(&filter).unwrap();
+        let ignored = IgnoredFiles::new(&[]).unwrap();
+        let strings = extract_translatable_strings(project_path, &ignored, &compiled, &|| false).unwrap();
+        assert_eq!(strings.len(), 1);
+        assert_eq!((strings[0].object_id, strings[0].json_path.as_str()), (1, "[1].name"));
+
+        let translation = WorkingTranslation {
+            object_id: 1,
+            original_text: strings[0].original_text.clone(),
+            translated_text: "Harold".to_string(),
+            source_file: strings[0].source_file.clone(),
+            json_path: strings[0].json_path.clone(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        };
+        let rebuilt = reconstruct_data_file(&actors(), vec![&translation]).unwrap();
+        let strings = data::extract_strings(&rebuilt, "Data/Actors.rvdata2").unwrap();
+        assert_eq!(strings[0].original_text, "Harold");
+    }
+}
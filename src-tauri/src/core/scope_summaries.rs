@@ -0,0 +1,135 @@
+use std::fs;
+use crate::core::prompts::map_language_code_to_name;
+use crate::error::CoreError;
+use crate::models::prompt::{ScopeSummaries, ScopeSummary};
+use crate::models::translation::SourceStringData;
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// Kept in the project's app data folder.
+pub const SCOPE_SUMMARIES_FILE_NAME: &str = "scope_summaries.json";
+
+/// Source text sent to be summarized, at most; long maps are cut.
+pub const MAX_SUMMARY_SOURCE_CHARS: usize = 6_000;
+
+/// Whether `json_path` is `prefix` or a path under it ("events[3]" covers
+/// "events[3].pages[0].list[1].parameters[0]" but not "events[30].name").
+fn is_under(json_path: &str, prefix: &str) -> bool {
+    json_path
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+}
+
+/// The summary of the narrowest scope containing an entry.
+pub fn summary_for<'a>(summaries: &'a ScopeSummaries, source_file: &str, json_path: &str) -> Option<&'a str> {
+    summaries
+        .summaries
+        .iter()
+        .filter(|s| s.source_file == source_file)
+        .filter(|s| s.path_prefix.as_deref().map_or(true, |prefix| is_under(json_path, prefix)))
+        .max_by_key(|s| s.path_prefix.as_deref().map_or(0, str::len))
+        .map(|s| s.summary.as_str())
+}
+
+/// The entries of a scope, in extraction order.
+pub fn scope_entries<'a>(
+    entries: &'a [SourceStringData],
+    source_file: &str,
+    path_prefix: Option<&str>,
+) -> Vec<&'a SourceStringData> {
+    entries
+        .iter()
+        .filter(|e| e.source_file == source_file && path_prefix.map_or(true, |prefix| is_under(&e.json_path, prefix)))
+        .collect()
+}
+
+/// Asks for a short English synopsis of the scope's source lines, with speakers where known.
+pub fn summary_prompt(entries: &[&SourceStringData], source_lang: &str) -> String {
+    let mut lines = String::new();
+    for entry in entries {
+        let line = match &entry.speaker {
+            Some(speaker) => format!("{}: {}\n", speaker, entry.original_text.replace('\n', " ")),
+            None => format!("{}\n", entry.original_text.replace('\n', " ")),
+        };
+        if lines.chars().count() + line.chars().count() > MAX_SUMMARY_SOURCE_CHARS {
+            break;
+        }
+        lines.push_str(&line);
+    }
+    format!(
+        "The following lines in {} are the text of one scene of a video game, in order. Write a brief synopsis of the scene in English, in 3 to 5 sentences: who is present, what happens, and the tone and relationships between the characters. Output *only* the synopsis.\n\n{}",
+        map_language_code_to_name(source_lang),
+        lines.trim_end()
+    )
+}
+
+pub fn load_scope_summaries(project_path: &str) -> Result<ScopeSummaries, CoreError> {
+    let path = app_data_dir(project_path).join(SCOPE_SUMMARIES_FILE_NAME);
+    if !path.exists() {
+        return Ok(ScopeSummaries::default());
+    }
+    let json = fs::read_to_string(&path)?;
+    serde_json::from_str(&json).map_err(|e| CoreError::JsonParse(format!("Failed to parse scope summaries: {}", e)))
+}
+
+pub fn save_scope_summaries(project_path: &str, summaries: &ScopeSummaries) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, SCOPE_SUMMARIES_FILE_NAME)?;
+    let json = serde_json::to_string_pretty(summaries)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize scope summaries: {}", e)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Stores a summary, replacing the one of the same scope.
+pub fn store_scope_summary(project_path: &str, summary: ScopeSummary) -> Result<ScopeSummaries, CoreError> {
+    let mut summaries = load_scope_summaries(project_path)?;
+    summaries
+        .summaries
+        .retain(|s| s.source_file != summary.source_file || s.path_prefix != summary.path_prefix);
+    summaries.summaries.push(summary);
+    save_scope_summaries(project_path, &summaries)?;
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn summary(path_prefix: Option<&str>, text: &str) -> ScopeSummary {
+        ScopeSummary {
+            source_file: "www/data/Map001.json".to_string(),
+            path_prefix: path_prefix.map(str::to_string),
+            summary: text.to_string(),
+            provider: "ollama".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_narrowest_scope_summary_applies() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        store_scope_summary(project_path, summary(None, "The village.")).unwrap();
+        store_scope_summary(project_path, summary(Some("events[3]"), "The elder warns Harold.")).unwrap();
+        let summaries = store_scope_summary(project_path, summary(Some("events[3]"), "The elder greets Harold.")).unwrap();
+        assert_eq!(summaries.summaries.len(), 2);
+        assert_eq!(load_scope_summaries(project_path).unwrap(), summaries);
+
+        let file = "www/data/Map001.json";
+        assert_eq!(summary_for(&summaries, file, "events[3].pages[0].list[1].parameters[0]"), Some("The elder greets Harold."));
+        assert_eq!(summary_for(&summaries, file, "events[30].name"), Some("The village."));
+        assert_eq!(summary_for(&summaries, "www/data/Map002.json", "events[3].name"), None);
+
+        let entries = vec![SourceStringData {
+            object_id: 3,
+            original_text: "ようこそ、\n旅の方。".to_string(),
+            source_file: file.to_string(),
+            json_path: "events[3].pages[0].list[1].parameters[0]".to_string(),
+            dialogue_block_id: None,
+            speaker: Some("村長".to_string()),
+        }];
+        let scope = scope_entries(&entries, file, Some("events[3]"));
+        let prompt = summary_prompt(&scope, "ja");
+        assert!(prompt.starts_with("The following lines in Japanese"));
+        assert!(prompt.ends_with("\n\n村長: ようこそ、 旅の方。"));
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use regex::{Regex, RegexBuilder};
+use crate::core::bulk::entry_status;
+use crate::error::CoreError;
+use crate::models::bulk::EntryStatus;
+use crate::models::search::{SearchField, SearchHit, SearchQuery, SearchResults};
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+
+/// Hits per page when the query doesn't say.
+pub const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+struct IndexedEntry {
+    hit: SearchHit,
+    // Lowercased texts, searched when the query isn't case-sensitive.
+    original_lower: String,
+    translated_lower: String,
+}
+
+/// Extracted strings with their translations, and an index of the pairs of consecutive
+/// characters in their lowercased texts. Plain text searches only check the entries holding
+/// every pair of the query, which works for Japanese text without splitting it into words.
+pub struct SearchIndex {
+    entries: Vec<IndexedEntry>,
+    // Entry positions by character pair, ascending.
+    bigrams: HashMap<(char, char), Vec<u32>>,
+}
+
+fn bigrams(text: &str) -> impl Iterator<Item = (char, char)> + '_ {
+    text.chars().zip(text.chars().skip(1))
+}
+
+enum Matcher {
+    All,
+    Plain { text: String, lower: String, case_sensitive: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(query: &SearchQuery) -> Result<Self, CoreError> {
+        let Some(text) = query.text.as_deref().filter(|text| !text.is_empty()) else { return Ok(Matcher::All) };
+        if query.regex {
+            return RegexBuilder::new(text)
+                .case_insensitive(!query.case_sensitive)
+                .build()
+                .map(Matcher::Regex)
+                .map_err(|e| CoreError::Config(format!("Invalid search pattern '{}': {}", text, e)));
+        }
+        Ok(Matcher::Plain { text: text.to_string(), lower: text.to_lowercase(), case_sensitive: query.case_sensitive })
+    }
+
+    fn matches_text(&self, text: &str, lower: &str) -> bool {
+        match self {
+            Matcher::All => true,
+            Matcher::Plain { text: needle, case_sensitive: true, .. } => text.contains(needle.as_str()),
+            Matcher::Plain { lower: needle, .. } => lower.contains(needle.as_str()),
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+
+    fn matches(&self, entry: &IndexedEntry, field: SearchField) -> bool {
+        let original = || self.matches_text(&entry.hit.original_text, &entry.original_lower);
+        let translated = || self.matches_text(&entry.hit.translated_text, &entry.translated_lower);
+        match field {
+            SearchField::Original => original(),
+            SearchField::Translated => translated(),
+            SearchField::Both => original() || translated(),
+        }
+    }
+}
+
+impl SearchIndex {
+    /// Indexes `entries` in their order, each with its translation from `translations` if any.
+    pub fn new(entries: Vec<SourceStringData>, translations: &[WorkingTranslation]) -> Self {
+        let by_key: HashMap<(&str, &str), &WorkingTranslation> =
+            translations.iter().map(|t| ((t.source_file.as_str(), t.json_path.as_str()), t)).collect();
+        let mut index = SearchIndex { entries: Vec::with_capacity(entries.len()), bigrams: HashMap::new() };
+        for (position, entry) in entries.into_iter().enumerate() {
+            let translation = by_key.get(&(entry.source_file.as_str(), entry.json_path.as_str()));
+            let hit = SearchHit {
+                translated_text: translation.map_or(String::new(), |t| t.translated_text.clone()),
+                status: translation.map_or(EntryStatus::Untranslated, |t| entry_status(t)),
+                review_status: translation.map_or(TranslationStatus::Untranslated, |t| t.status),
+                object_id: entry.object_id,
+                source_file: entry.source_file,
+                json_path: entry.json_path,
+                original_text: entry.original_text,
+            };
+            let original_lower = hit.original_text.to_lowercase();
+            let translated_lower = hit.translated_text.to_lowercase();
+            let position = position as u32;
+            for pair in bigrams(&original_lower).chain(bigrams(&translated_lower)) {
+                let postings = index.bigrams.entry(pair).or_default();
+                if postings.last() != Some(&position) {
+                    postings.push(position);
+                }
+            }
+            index.entries.push(IndexedEntry { hit, original_lower, translated_lower });
+        }
+        index
+    }
+
+    /// Entries that may contain `lower`: those holding all its character pairs, or every entry
+    /// for a single character.
+    fn candidates(&self, lower: &str) -> Vec<u32> {
+        let mut postings = Vec::new();
+        for pair in bigrams(lower) {
+            match self.bigrams.get(&pair) {
+                Some(list) => postings.push(list),
+                None => return Vec::new(),
+            }
+        }
+        postings.sort_by_key(|list| list.len());
+        let Some((shortest, others)) = postings.split_first() else {
+            return (0..self.entries.len() as u32).collect();
+        };
+        let mut candidates = shortest.to_vec();
+        for list in others {
+            candidates.retain(|position| list.binary_search(position).is_ok());
+        }
+        candidates
+    }
+
+    /// One page of the entries matching `query`, in index order.
+    pub fn search(&self, query: &SearchQuery) -> Result<SearchResults, CoreError> {
+        let matcher = Matcher::new(query)?;
+        let candidates = match &matcher {
+            Matcher::Plain { lower, .. } => self.candidates(lower),
+            _ => (0..self.entries.len() as u32).collect(),
+        };
+        let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let mut results = SearchResults { offset: query.offset, ..Default::default() };
+        for position in candidates {
+            let entry = &self.entries[position as usize];
+            let hit = &entry.hit;
+            let selected = (query.source_files.is_empty() || query.source_files.contains(&hit.source_file))
+                && (query.statuses.is_empty() || query.statuses.contains(&hit.status))
+                && (query.object_ids.is_empty() || query.object_ids.contains(&hit.object_id))
+                && matcher.matches(entry, query.field);
+            if !selected {
+                continue;
+            }
+            if results.total >= query.offset && results.hits.len() < limit {
+                results.hits.push(hit.clone());
+            }
+            results.total += 1;
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(json_path: &str, text: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    fn translation(json_path: &str, text: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: String::new(),
+            translated_text: text.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    fn search(index: &SearchIndex, text: &str, query: SearchQuery) -> Vec<String> {
+        let query = SearchQuery { text: Some(text.to_string()), ..query };
+        index.search(&query).unwrap().hits.into_iter().map(|hit| hit.json_path).collect()
+    }
+
+    #[test]
+    fn test_search_text_regex_and_filters() {
+        let entries = vec![source("a", "勇者の剣"), source("b", "魔王の城"), source("c", "勇者")];
+        let index = SearchIndex::new(entries, &[translation("a", "The Hero's Sword"), translation("b", "Castle")]);
+        assert_eq!(search(&index, "勇者", SearchQuery::default()), vec!["a", "c"]);
+        assert_eq!(search(&index, "hero", SearchQuery::default()), vec!["a"]);
+        assert!(search(&index, "hero", SearchQuery { case_sensitive: true, ..Default::default() }).is_empty());
+        assert!(search(&index, "castle", SearchQuery { field: SearchField::Original, ..Default::default() }).is_empty());
+        assert_eq!(search(&index, "の", SearchQuery::default()), vec!["a", "b"]);
+        assert_eq!(search(&index, r"^(castle|勇者)$", SearchQuery { regex: true, ..Default::default() }), vec!["b", "c"]);
+
+        let untranslated = SearchQuery { statuses: vec![EntryStatus::Untranslated], ..Default::default() };
+        assert_eq!(search(&index, "", untranslated), vec!["c"]);
+
+        let page = index.search(&SearchQuery { offset: 1, limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!((page.total, page.hits[0].json_path.as_str()), (3, "b"));
+        assert!(index.search(&SearchQuery { text: Some("(".to_string()), regex: true, ..Default::default() }).is_err());
+    }
+}
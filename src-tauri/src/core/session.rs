@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use serde_json::Value;
+use crate::core::migration::{migrate_session_value, SESSION_FORMAT_VERSION};
+use crate::core::transliteration::is_placeholder;
+use crate::error::CoreError;
+use crate::models::migration::MigrationReport;
+use crate::models::session::TranslationSession;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// File name of the saved translation session (inside `.translate-ai/`).
+pub const SESSION_FILE_NAME: &str = "session.json";
+
+/// Reads the saved session file and upgrades it to the current format in memory.
+fn read_session_file(project_path: &str) -> Result<Option<(TranslationSession, MigrationReport)>, CoreError> {
+    let path = app_data_dir(project_path).join(SESSION_FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read session {:?}: {}", path, e)))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| CoreError::JsonParse(format!("Invalid session file {:?}: {}", path, e)))?;
+    let (value, report) = migrate_session_value(value)?;
+    let session = serde_json::from_value(value)
+        .map_err(|e| CoreError::JsonParse(format!("Invalid session file {:?}: {}", path, e)))?;
+    Ok(Some((session, report)))
+}
+
+/// Loads the project's saved session, or `None` if there is none. Sessions saved in an older
+/// format are upgraded on load; the file itself is rewritten on the next save.
+pub fn load_session(project_path: &str) -> Result<Option<TranslationSession>, CoreError> {
+    let Some((session, report)) = read_session_file(project_path)? else { return Ok(None) };
+    if report.is_needed() {
+        eprintln!(
+            "Upgraded session from format {} to {}: {} field(s) not migrated, {} entry(ies) dropped.",
+            report.from_version,
+            report.to_version,
+            report.unmigrated.len(),
+            report.dropped_entries
+        );
+    }
+    Ok(Some(session))
+}
+
+/// Dry run: what upgrading the project's session file would change. `None` without a session.
+pub fn check_session_migration(project_path: &str) -> Result<Option<MigrationReport>, CoreError> {
+    Ok(read_session_file(project_path)?.map(|(_, report)| report))
+}
+
+/// Upgrades the session file on disk, keeping the old file as `session.v<version>.json`.
+pub fn migrate_session_file(project_path: &str) -> Result<Option<MigrationReport>, CoreError> {
+    let Some((session, report)) = read_session_file(project_path)? else { return Ok(None) };
+    if report.is_needed() {
+        let dir = app_data_dir(project_path);
+        let backup = dir.join(format!("session.v{}.json", report.from_version));
+        fs::copy(dir.join(SESSION_FILE_NAME), &backup)
+            .map_err(|e| CoreError::Io(format!("Failed to back up session to {:?}: {}", backup, e)))?;
+        save_session(project_path, &session)?;
+    }
+    Ok(Some(report))
+}
+
+pub fn save_session(project_path: &str, session: &TranslationSession) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, SESSION_FILE_NAME)?;
+    let mut value = serde_json::to_value(session)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize session: {}", e)))?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), Value::from(SESSION_FORMAT_VERSION));
+    }
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize session: {}", e)))?;
+    fs::write(&path, json).map_err(|e| CoreError::Io(format!("Failed to write session to {:?}: {}", path, e)))
+}
+
+// Minimum time between two saves while a batch is running.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+fn entry_key(source_file: &str, json_path: &str) -> (String, String) {
+    (source_file.to_string(), json_path.to_string())
+}
+
+/// The project's session to record a batch in. Earlier batches for the same language pair stay
+/// in it. A saved session for another pair is only replaced with `replace_session`, since its
+/// translations would be lost.
+pub fn project_session(
+    project_path: &str,
+    source_language: &str,
+    target_language: &str,
+    replace_session: bool,
+) -> Result<TranslationSession, CoreError> {
+    let new_session = || TranslationSession {
+        source_language: source_language.to_string(),
+        target_language: target_language.to_string(),
+        ..Default::default()
+    };
+    match load_session(project_path)? {
+        Some(s) if s.source_language == source_language && s.target_language == target_language => Ok(s),
+        Some(s) if !replace_session && (!s.translations.is_empty() || !s.pending.is_empty()) => {
+            Err(CoreError::Config(format!(
+                "The project's session holds {} translation(s) from {} to {}; translating from {} to {} would \
+                 replace it. Export it first, or set replaceSession to start a new session.",
+                s.translations.len(),
+                s.source_language,
+                s.target_language,
+                source_language,
+                target_language
+            )))
+        }
+        _ => Ok(new_session()),
+    }
+}
+
+/// Entries a resumed run has to translate: the pending ones, then those that failed, came back
+/// empty or were given an offline placeholder.
+pub fn entries_to_resume(session: &TranslationSession) -> Vec<SourceStringData> {
+    let retry = session
+        .translations
+        .iter()
+        .filter(|t| t.error.is_some() || t.translated_text.trim().is_empty() || is_placeholder(t))
+        .map(|t| SourceStringData {
+            object_id: t.object_id,
+            original_text: t.original_text.clone(),
+            source_file: t.source_file.clone(),
+            json_path: t.json_path.clone(),
+            dialogue_block_id: None,
+            speaker: None,
+        });
+    session.pending.iter().cloned().chain(retry).collect()
+}
+
+/// `entries` the session has no usable translation of for their current text: never translated,
+/// failed, or changed since.
+pub fn untranslated_entries(session: &TranslationSession, entries: Vec<SourceStringData>) -> Vec<SourceStringData> {
+    let translated: HashMap<(String, String), &str> = session
+        .translations
+        .iter()
+        .filter(|t| t.error.is_none() && !t.translated_text.trim().is_empty())
+        .map(|t| (entry_key(&t.source_file, &t.json_path), t.original_text.as_str()))
+        .collect();
+    entries
+        .into_iter()
+        .filter(|e| translated.get(&entry_key(&e.source_file, &e.json_path)) != Some(&e.original_text.as_str()))
+        .collect()
+}
+
+/// Keeps the project's session file up to date while a batch runs, so an interrupted batch
+/// can be resumed. Saves are throttled; `finish` always saves.
+pub struct SessionRecorder {
+    project_path: String,
+    session: TranslationSession,
+    // (source_file, json_path) -> position in `session.translations`.
+    positions: HashMap<(String, String), usize>,
+    last_save: Instant,
+}
+
+impl SessionRecorder {
+    /// Records a batch over `entries` on top of `session`, whose existing translations are kept
+    /// (and replaced as entries are re-translated). Saves the session right away.
+    pub fn start(
+        project_path: &str,
+        mut session: TranslationSession,
+        entries: &[SourceStringData],
+    ) -> Result<Self, CoreError> {
+        session.pending = entries.to_vec();
+        let positions = session
+            .translations
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (entry_key(&t.source_file, &t.json_path), i))
+            .collect();
+        save_session(project_path, &session)?;
+        Ok(SessionRecorder { project_path: project_path.to_string(), session, positions, last_save: Instant::now() })
+    }
+
+    fn upsert(&mut self, entry: &WorkingTranslation) {
+        let key = entry_key(&entry.source_file, &entry.json_path);
+        match self.positions.get(&key) {
+            Some(&position) => self.session.translations[position] = entry.clone(),
+            None => {
+                self.positions.insert(key, self.session.translations.len());
+                self.session.translations.push(entry.clone());
+            }
+        }
+    }
+
+    fn save(&mut self) -> Result<(), CoreError> {
+        let positions = &self.positions;
+        self.session.pending.retain(|e| !positions.contains_key(&entry_key(&e.source_file, &e.json_path)));
+        self.last_save = Instant::now();
+        save_session(&self.project_path, &self.session)
+    }
+
+    pub fn record(&mut self, entry: &WorkingTranslation) {
+        self.upsert(entry);
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            if let Err(e) = self.save() {
+                eprintln!("Failed to save translation session: {}", e);
+            }
+        }
+    }
+
+    /// Stores the final results (which may differ from the recorded ones after escalation)
+    /// and saves. Returns every translation of the session.
+    pub fn finish(mut self, results: &[WorkingTranslation]) -> Result<Vec<WorkingTranslation>, CoreError> {
+        for entry in results {
+            self.upsert(entry);
+        }
+        self.save()?;
+        Ok(self.session.translations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::{TranslationStatus, WorkingTranslation};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        assert!(load_session(project_path).unwrap().is_none());
+
+        let session = TranslationSession {
+            source_language: "ja".to_string(),
+            target_language: "en".to_string(),
+            translations: vec![WorkingTranslation {
+                object_id: 1,
+                original_text: "ポーション".to_string(),
+                translated_text: "Potion".to_string(),
+                source_file: "www/data/Items.json".to_string(),
+                json_path: "[1].name".to_string(),
+                translation_source: "ollama".to_string(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            }],
+            pending: Vec::new(),
+        };
+        save_session(project_path, &session).unwrap();
+        let loaded = load_session(project_path).unwrap().unwrap();
+        assert_eq!(loaded.translations[0].translated_text, "Potion");
+    }
+
+    #[test]
+    fn test_legacy_session_is_migrated() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        let legacy = r#"{"source_language":"ja","target_language":"en","translations":[
+            {"object_id":1,"original_text":"ポーション","source_file":"www/data/Items.json","json_path":"[1].name",
+             "translated_text":"Potion","translation_source":"ollama","error":null}]}"#;
+        let path = ensure_app_data_file(project_path, SESSION_FILE_NAME).unwrap();
+        fs::write(&path, legacy).unwrap();
+
+        let report = check_session_migration(project_path).unwrap().unwrap();
+        assert!(report.is_needed());
+        assert_eq!(fs::read_to_string(&path).unwrap(), legacy);
+        assert_eq!(load_session(project_path).unwrap().unwrap().translations[0].translated_text, "Potion");
+
+        migrate_session_file(project_path).unwrap();
+        assert!(app_data_dir(project_path).join("session.v1.json").is_file());
+        assert!(!check_session_migration(project_path).unwrap().unwrap().is_needed());
+    }
+
+    fn source(path: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: format!("text {}", path),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    fn translated(path: &str, error: Option<&str>) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: format!("text {}", path),
+            translated_text: if error.is_some() { String::new() } else { format!("done {}", path) },
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: error.map(str::to_string),
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_interrupted_batch_can_be_resumed() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        let entries = vec![source("a"), source("b"), source("c")];
+        let session = TranslationSession { source_language: "ja".to_string(), target_language: "en".to_string(), ..Default::default() };
+
+        let mut recorder = SessionRecorder::start(project_path, session, &entries).unwrap();
+        recorder.record(&translated("a", None));
+        recorder.record(&translated("b", Some("timeout")));
+        // Simulates a crash after the next save: "c" was never translated.
+        recorder.save().unwrap();
+        drop(recorder);
+
+        let saved = load_session(project_path).unwrap().unwrap();
+        let to_resume = entries_to_resume(&saved);
+        let paths: Vec<&str> = to_resume.iter().map(|e| e.json_path.as_str()).collect();
+        assert_eq!(paths, vec!["c", "b"]);
+
+        let recorder = SessionRecorder::start(project_path, saved, &to_resume).unwrap();
+        let all = recorder.finish(&[translated("c", None), translated("b", None)]).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|t| t.error.is_none()));
+        assert!(entries_to_resume(&load_session(project_path).unwrap().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_untranslated_entries() {
+        let mut changed = source("c");
+        changed.original_text = "new text".to_string();
+        let session = TranslationSession {
+            translations: vec![translated("a", None), translated("b", Some("timeout")), translated("c", None)],
+            ..Default::default()
+        };
+        let missing = untranslated_entries(&session, vec![source("a"), source("b"), changed, source("d")]);
+        let paths: Vec<&str> = missing.iter().map(|e| e.json_path.as_str()).collect();
+        assert_eq!(paths, vec!["b", "c", "d"]);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use crate::error::CoreError;
+use crate::models::string_store::{StoreCounts, StoredString, StringPage, StringPageQuery};
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::utils::project_paths::ensure_app_data_file;
+
+pub const STRING_STORE_FILE_NAME: &str = "strings.db";
+
+/// Entries per page when the query doesn't say.
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+/// Keeps a single IPC response reasonably small.
+pub const MAX_PAGE_SIZE: usize = 5000;
+
+// `EntryStatus` of a row, as its serialized name.
+const STATUS_SQL: &str =
+    "CASE WHEN error IS NOT NULL THEN 'failed' WHEN translated_text = '' THEN 'untranslated' ELSE 'translated' END";
+
+const COLUMNS: &str = "object_id, source_file, json_path, original_text, dialogue_block_id, speaker,
+    translated_text, translation_source, error, status";
+
+fn status_name<T: serde::Serialize>(status: T) -> String {
+    serde_json::to_value(status).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+fn read_row(row: &Row) -> rusqlite::Result<StoredString> {
+    let status: String = row.get(9)?;
+    Ok(StoredString {
+        object_id: row.get(0)?,
+        source_file: row.get(1)?,
+        json_path: row.get(2)?,
+        original_text: row.get(3)?,
+        dialogue_block_id: row.get(4)?,
+        speaker: row.get(5)?,
+        translated_text: row.get(6)?,
+        translation_source: row.get(7)?,
+        error: row.get(8)?,
+        status: serde_json::from_value(serde_json::Value::String(status)).unwrap_or_default(),
+    })
+}
+
+/// Extracted strings and their translations, persisted per project in SQLite so large projects
+/// can be read and translated a page at a time instead of being held and sent over IPC whole.
+/// Entries are keyed by (source file, JSON path) and ordered by source file, then in extraction
+/// order. Translations are for one target language, the session's, which the store records.
+pub struct StringStore {
+    conn: Connection,
+}
+
+impl StringStore {
+    pub fn open(path: &Path) -> Result<Self, CoreError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens (creating if needed) `<project>/.translate-ai/strings.db`.
+    pub fn open_for_project(project_path: &str) -> Result<Self, CoreError> {
+        Self::open(&ensure_app_data_file(project_path, STRING_STORE_FILE_NAME)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, CoreError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, CoreError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS strings (
+                source_file        TEXT NOT NULL,
+                json_path          TEXT NOT NULL,
+                position           INTEGER NOT NULL,
+                object_id          INTEGER NOT NULL,
+                original_text      TEXT NOT NULL,
+                dialogue_block_id  TEXT,
+                speaker            TEXT,
+                translated_text    TEXT NOT NULL DEFAULT '',
+                translation_source TEXT NOT NULL DEFAULT '',
+                error              TEXT,
+                status             TEXT NOT NULL DEFAULT 'untranslated',
+                PRIMARY KEY (source_file, json_path)
+            );
+            CREATE INDEX IF NOT EXISTS strings_order ON strings (source_file, position);
+            CREATE TABLE IF NOT EXISTS store_info (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Stores an extraction. The files of `entries` are replaced, as are files `is_gone` says
+    /// were deleted; other files keep their entries, as with a filtered extraction. A string
+    /// whose original text didn't change keeps its translation.
+    pub fn replace_entries(
+        &mut self,
+        entries: &[SourceStringData],
+        is_gone: &dyn Fn(&str) -> bool,
+    ) -> Result<(), CoreError> {
+        let extracted: BTreeSet<&str> = entries.iter().map(|entry| entry.source_file.as_str()).collect();
+        let tx = self.conn.transaction()?;
+        {
+            let stored: Vec<String> = tx
+                .prepare("SELECT DISTINCT source_file FROM strings")?
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            // Rows of the replaced files not found again are left at position -1 and removed.
+            let mut mark = tx.prepare("UPDATE strings SET position = -1 WHERE source_file = ?1")?;
+            for source_file in stored.iter().filter(|file| extracted.contains(file.as_str()) || is_gone(file)) {
+                mark.execute([source_file])?;
+            }
+            let mut upsert = tx.prepare(
+                "INSERT INTO strings
+                    (source_file, json_path, position, object_id, original_text, dialogue_block_id, speaker)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (source_file, json_path) DO UPDATE SET
+                    position = excluded.position,
+                    object_id = excluded.object_id,
+                    dialogue_block_id = excluded.dialogue_block_id,
+                    speaker = excluded.speaker,
+                    translated_text = IIF(original_text = excluded.original_text, translated_text, ''),
+                    translation_source = IIF(original_text = excluded.original_text, translation_source, ''),
+                    error = IIF(original_text = excluded.original_text, error, NULL),
+                    status = IIF(original_text = excluded.original_text, status, 'untranslated'),
+                    original_text = excluded.original_text",
+            )?;
+            let mut positions = HashMap::<&str, i64>::new();
+            for entry in entries {
+                let position = positions.entry(entry.source_file.as_str()).or_default();
+                upsert.execute(params![
+                    entry.source_file,
+                    entry.json_path,
+                    *position,
+                    entry.object_id,
+                    entry.original_text,
+                    entry.dialogue_block_id,
+                    entry.speaker,
+                ])?;
+                *position += 1;
+            }
+            tx.execute("DELETE FROM strings WHERE position < 0", [])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The language of the stored translations; `None` before any are recorded.
+    pub fn target_language(&self) -> Result<Option<String>, CoreError> {
+        let language = self
+            .conn
+            .query_row("SELECT value FROM store_info WHERE key = 'target_language'", [], |row| row.get(0))
+            .optional()?;
+        Ok(language)
+    }
+
+    /// Saves translations into `target_language` of stored strings; others are ignored. Returns
+    /// how many were saved. Translations into another language than the stored ones replace them
+    /// all first, as a session for a new language pair replaces the old one.
+    pub fn record_translations(
+        &mut self,
+        target_language: &str,
+        translations: &[WorkingTranslation],
+    ) -> Result<usize, CoreError> {
+        let replaced = self.target_language()?.is_some_and(|language| language != target_language);
+        let tx = self.conn.transaction()?;
+        let mut saved = 0;
+        {
+            if replaced {
+                tx.execute(
+                    "UPDATE strings SET translated_text = '', translation_source = '', error = NULL,
+                        status = 'untranslated'",
+                    [],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO store_info (key, value) VALUES ('target_language', ?1)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+                [target_language],
+            )?;
+            let mut update = tx.prepare(
+                "UPDATE strings SET translated_text = ?3, translation_source = ?4, error = ?5, status = ?6
+                 WHERE source_file = ?1 AND json_path = ?2",
+            )?;
+            for t in translations {
+                saved += update.execute(params![
+                    t.source_file,
+                    t.json_path,
+                    t.translated_text,
+                    t.translation_source,
+                    t.error,
+                    status_name(t.status),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(saved)
+    }
+
+    fn where_clause(query: &StringPageQuery) -> (String, Vec<SqlValue>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if !query.source_files.is_empty() {
+            conditions.push(format!("source_file IN ({})", vec!["?"; query.source_files.len()].join(", ")));
+            values.extend(query.source_files.iter().cloned().map(SqlValue::Text));
+        }
+        if !query.statuses.is_empty() {
+            conditions.push(format!("{} IN ({})", STATUS_SQL, vec!["?"; query.statuses.len()].join(", ")));
+            values.extend(query.statuses.iter().map(|status| SqlValue::Text(status_name(status))));
+        }
+        match conditions.is_empty() {
+            true => (String::new(), values),
+            false => (format!("WHERE {}", conditions.join(" AND ")), values),
+        }
+    }
+
+    /// One page of the strings matching `query`, with their total.
+    pub fn page(&self, query: &StringPageQuery) -> Result<StringPage, CoreError> {
+        let (filter, values) = Self::where_clause(query);
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM strings {}", filter),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let mut page_values = values;
+        page_values.push(SqlValue::Integer(limit as i64));
+        page_values.push(SqlValue::Integer(query.offset as i64));
+        let entries = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM strings {} ORDER BY source_file, position LIMIT ? OFFSET ?",
+                COLUMNS, filter
+            ))?
+            .query_map(params_from_iter(page_values.iter()), read_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(StringPage { entries, total: total as usize, offset: query.offset })
+    }
+
+    pub fn counts(&self) -> Result<StoreCounts, CoreError> {
+        let mut counts = StoreCounts::default();
+        let mut statement = self
+            .conn
+            .prepare(&format!("SELECT {} AS entry_status, COUNT(*) FROM strings GROUP BY entry_status", STATUS_SQL))?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))?;
+        for row in rows {
+            let (status, count) = row?;
+            counts.total += count;
+            match status.as_str() {
+                "failed" => counts.failed = count,
+                "untranslated" => counts.untranslated = count,
+                _ => counts.translated = count,
+            }
+        }
+        let files: i64 =
+            self.conn.query_row("SELECT COUNT(DISTINCT source_file) FROM strings", [], |row| row.get(0))?;
+        counts.files = files as usize;
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bulk::EntryStatus;
+    use crate::models::translation::TranslationStatus;
+
+    fn source(source_file: &str, json_path: &str, text: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: source_file.to_string(),
+            json_path: json_path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_store_pages_and_keeps_translations() {
+        // STATUS_SQL spells out the serialized names of `EntryStatus`.
+        let names = [EntryStatus::Failed, EntryStatus::Untranslated, EntryStatus::Translated].map(status_name);
+        assert_eq!(names, ["failed", "untranslated", "translated"]);
+        let mut store = StringStore::open_in_memory().unwrap();
+        let map = "www/data/Map001.json";
+        let items = "www/data/Items.json";
+        let entries = [source(map, "b", "はい"), source(map, "a", "いいえ"), source(items, "[1].name", "薬")];
+        store.replace_entries(&entries, &|_| false).unwrap();
+        let mut translation = store.page(&StringPageQuery::default()).unwrap().entries[1].translation();
+        assert_eq!(translation.json_path, "b");
+        translation.translated_text = "Yes".to_string();
+        translation.status = TranslationStatus::Edited;
+        assert_eq!(store.record_translations("en", &[translation.clone()]).unwrap(), 1);
+
+        let translated = StringPageQuery { statuses: vec![EntryStatus::Translated], ..Default::default() };
+        let page = store.page(&translated).unwrap();
+        assert_eq!((page.total, page.entries[0].status), (1, TranslationStatus::Edited));
+        let second = store.page(&StringPageQuery { offset: 1, limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!((second.total, second.entries[0].json_path.as_str()), (3, "b"));
+
+        // Only the map is extracted again: "a" changed, "b" is kept with its translation.
+        store.replace_entries(&[source(map, "b", "はい"), source(map, "c", "え？")], &|_| false).unwrap();
+        let page = store.page(&StringPageQuery::default()).unwrap();
+        let paths: Vec<String> = page.entries.into_iter().map(|e| e.json_path).collect();
+        assert_eq!(paths, vec!["[1].name", "b", "c"]);
+        assert_eq!(store.page(&translated).unwrap().entries[0].translated_text, "Yes");
+        let counts = store.counts().unwrap();
+        assert_eq!((counts.total, counts.translated, counts.untranslated, counts.files), (3, 1, 2, 2));
+
+        store.replace_entries(&[], &|file| file == items).unwrap();
+        assert_eq!(store.counts().unwrap().total, 2);
+
+        // A session into another language starts over.
+        assert_eq!(store.record_translations("fr", &[]).unwrap(), 0);
+        assert_eq!((store.target_language().unwrap().as_deref(), store.counts().unwrap().translated), (Some("fr"), 0));
+    }
+}
@@ -0,0 +1,269 @@
+use std::path::Path;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use crate::error::CoreError;
+use crate::utils::project_paths::ensure_app_data_file;
+
+pub const TRANSLATION_MEMORY_FILE_NAME: &str = "translation_memory.db";
+
+/// Prefix of `translation_source` for entries answered by the translation memory,
+/// followed by the provider that originally produced the translation.
+pub const MEMORY_SOURCE_PREFIX: &str = "memory:";
+
+/// A stored translation for one source string and language pair.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEntry {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub original_text: String,
+    pub translated_text: String,
+    pub provider: String,
+}
+
+/// Translation memory persisted in a SQLite database. Entries are keyed by
+/// (source language, target language, original text); storing the same key again
+/// replaces the previous translation. Provider responses also record a hash of the prompt
+/// context they were made with (see `PromptContext::cache_key`), so a glossary change only
+/// invalidates the responses whose text contains a changed term.
+pub struct TranslationMemory {
+    conn: Connection,
+}
+
+impl TranslationMemory {
+    pub fn open(path: &Path) -> Result<Self, CoreError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens (creating if needed) `<project>/.translate-ai/translation_memory.db`.
+    pub fn open_for_project(project_path: &str) -> Result<Self, CoreError> {
+        Self::open(&ensure_app_data_file(project_path, TRANSLATION_MEMORY_FILE_NAME)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, CoreError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, CoreError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS translation_memory (
+                source_lang     TEXT NOT NULL,
+                target_lang     TEXT NOT NULL,
+                original_text   TEXT NOT NULL,
+                translated_text TEXT NOT NULL,
+                provider        TEXT NOT NULL,
+                updated_at      INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                context_hash    TEXT NOT NULL DEFAULT '',
+                PRIMARY KEY (source_lang, target_lang, original_text)
+            );",
+        )?;
+        // Memories created before context hashes keep their entries as context-free.
+        let has_context_hash = conn
+            .prepare("SELECT 1 FROM pragma_table_info('translation_memory') WHERE name = 'context_hash'")?
+            .exists([])?;
+        if !has_context_hash {
+            conn.execute_batch("ALTER TABLE translation_memory ADD COLUMN context_hash TEXT NOT NULL DEFAULT '';")?;
+        }
+        Ok(Self { conn })
+    }
+
+    /// The stored translation, whatever context it was made in.
+    pub fn lookup(&self, source_lang: &str, target_lang: &str, original_text: &str) -> Result<Option<MemoryEntry>, CoreError> {
+        self.lookup_where(source_lang, target_lang, original_text, None)
+    }
+
+    /// The stored translation if it was made with the same prompt context, or stored without one
+    /// (imported or reviewed translations).
+    pub fn lookup_in_context(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+        original_text: &str,
+        context_hash: &str,
+    ) -> Result<Option<MemoryEntry>, CoreError> {
+        self.lookup_where(source_lang, target_lang, original_text, Some(context_hash))
+    }
+
+    fn lookup_where(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+        original_text: &str,
+        context_hash: Option<&str>,
+    ) -> Result<Option<MemoryEntry>, CoreError> {
+        let entry = self
+            .conn
+            .query_row(
+                "SELECT translated_text, provider FROM translation_memory
+                 WHERE source_lang = ?1 AND target_lang = ?2 AND original_text = ?3
+                   AND (?4 IS NULL OR context_hash = '' OR context_hash = ?4)",
+                params![source_lang, target_lang, original_text, context_hash],
+                |row| {
+                    Ok(MemoryEntry {
+                        source_lang: source_lang.to_string(),
+                        target_lang: target_lang.to_string(),
+                        original_text: original_text.to_string(),
+                        translated_text: row.get(0)?,
+                        provider: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    /// Stores a translation that holds in any context.
+    pub fn store(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+        original_text: &str,
+        translated_text: &str,
+        provider: &str,
+    ) -> Result<(), CoreError> {
+        self.store_in_context(source_lang, target_lang, original_text, translated_text, provider, "")
+    }
+
+    /// Stores a provider response along with the hash of the prompt context it was made with.
+    pub fn store_in_context(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+        original_text: &str,
+        translated_text: &str,
+        provider: &str,
+        context_hash: &str,
+    ) -> Result<(), CoreError> {
+        self.conn.execute(
+            "INSERT INTO translation_memory (source_lang, target_lang, original_text, translated_text, provider, context_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (source_lang, target_lang, original_text) DO UPDATE SET
+                translated_text = excluded.translated_text,
+                provider = excluded.provider,
+                context_hash = excluded.context_hash,
+                updated_at = strftime('%s', 'now')",
+            params![source_lang, target_lang, original_text, translated_text, provider, context_hash],
+        )?;
+        Ok(())
+    }
+
+    /// Stores several entries in one transaction (e.g. when importing a TMX file).
+    pub fn store_entries(&self, entries: &[MemoryEntry]) -> Result<(), CoreError> {
+        let transaction = self.conn.unchecked_transaction()?;
+        for entry in entries {
+            self.store(
+                &entry.source_lang,
+                &entry.target_lang,
+                &entry.original_text,
+                &entry.translated_text,
+                &entry.provider,
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// All entries, sorted by language pair and original text.
+    pub fn entries(&self) -> Result<Vec<MemoryEntry>, CoreError> {
+        let mut statement = self.conn.prepare(
+            "SELECT source_lang, target_lang, original_text, translated_text, provider FROM translation_memory
+             ORDER BY source_lang, target_lang, original_text",
+        )?;
+        let entries = statement
+            .query_map([], |row| {
+                Ok(MemoryEntry {
+                    source_lang: row.get(0)?,
+                    target_lang: row.get(1)?,
+                    original_text: row.get(2)?,
+                    translated_text: row.get(3)?,
+                    provider: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Entries stored with a prompt context hash, i.e. those a glossary change can invalidate.
+    pub fn context_bound_entries(&self) -> Result<Vec<MemoryEntry>, CoreError> {
+        let mut statement = self.conn.prepare(
+            "SELECT source_lang, target_lang, original_text, translated_text, provider FROM translation_memory
+             WHERE context_hash != '' ORDER BY source_lang, target_lang, original_text",
+        )?;
+        let entries = statement
+            .query_map([], |row| {
+                Ok(MemoryEntry {
+                    source_lang: row.get(0)?,
+                    target_lang: row.get(1)?,
+                    original_text: row.get(2)?,
+                    translated_text: row.get(3)?,
+                    provider: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    pub fn len(&self) -> Result<usize, CoreError> {
+        let count: i64 = self.conn.query_row("SELECT COUNT(*) FROM translation_memory", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, CoreError> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn clear(&self) -> Result<(), CoreError> {
+        self.conn.execute("DELETE FROM translation_memory", [])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_store_and_lookup() {
+        let tm = TranslationMemory::open_in_memory().unwrap();
+        assert!(tm.lookup("ja", "en", "はい").unwrap().is_none());
+
+        tm.store("ja", "en", "はい", "Yes", "ollama").unwrap();
+        tm.store("ja", "fr", "はい", "Oui", "deepl").unwrap();
+        assert_eq!(tm.lookup("ja", "en", "はい").unwrap().unwrap().translated_text, "Yes");
+
+        // Same key replaces the previous translation.
+        tm.store("ja", "en", "はい", "Yeah", "openai").unwrap();
+        let entry = tm.lookup("ja", "en", "はい").unwrap().unwrap();
+        assert_eq!((entry.translated_text.as_str(), entry.provider.as_str()), ("Yeah", "openai"));
+        assert_eq!(tm.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_lookup_in_context() {
+        let tm = TranslationMemory::open_in_memory().unwrap();
+        tm.store_in_context("ja", "en", "勇者ハロルド", "Hero Harold", "ollama", "hash-a").unwrap();
+        tm.store("ja", "en", "はい", "Yes", "tmx").unwrap();
+
+        assert!(tm.lookup_in_context("ja", "en", "勇者ハロルド", "hash-a").unwrap().is_some());
+        assert!(tm.lookup_in_context("ja", "en", "勇者ハロルド", "hash-b").unwrap().is_none());
+        assert!(tm.lookup("ja", "en", "勇者ハロルド").unwrap().is_some());
+        // Context-free entries match any context.
+        assert!(tm.lookup_in_context("ja", "en", "はい", "hash-b").unwrap().is_some());
+        let bound: Vec<String> = tm.context_bound_entries().unwrap().into_iter().map(|e| e.original_text).collect();
+        assert_eq!(bound, vec!["勇者ハロルド"]);
+    }
+
+    #[test]
+    fn test_project_memory_persists() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        TranslationMemory::open_for_project(project_path)
+            .unwrap()
+            .store("ja", "en", "勇者", "Hero", "ollama")
+            .unwrap();
+
+        let reopened = TranslationMemory::open_for_project(project_path).unwrap();
+        assert_eq!(reopened.lookup("ja", "en", "勇者").unwrap().unwrap().translated_text, "Hero");
+    }
+}
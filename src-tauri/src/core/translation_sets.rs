@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::core::rpgmv::locale_output::validate_locale;
+use crate::error::CoreError;
+use crate::models::translation::{TranslationSet, WorkingTranslation};
+use crate::utils::project_paths::{app_data_dir, check_app_data_unlocked};
+
+/// Folder of the per-language translation sets (inside `.translate-ai/`), one `<language>.json`
+/// each.
+pub const TRANSLATION_SETS_DIR_NAME: &str = "translations";
+
+/// Languages name their set's file and output folder, so they must be usable as a locale suffix.
+fn set_path(project_path: &str, target_language: &str) -> Result<PathBuf, CoreError> {
+    validate_locale(target_language)?;
+    Ok(app_data_dir(project_path).join(TRANSLATION_SETS_DIR_NAME).join(format!("{}.json", target_language)))
+}
+
+/// The project's translations into `target_language`, or `None` if it was never translated into it.
+pub fn load_translation_set(project_path: &str, target_language: &str) -> Result<Option<TranslationSet>, CoreError> {
+    let path = set_path(project_path, target_language)?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CoreError::Io(format!("Failed to read translation set {:?}: {}", path, e)))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| CoreError::JsonParse(format!("Invalid translation set {:?}: {}", path, e)))
+}
+
+pub fn save_translation_set(project_path: &str, set: &TranslationSet) -> Result<(), CoreError> {
+    let path = set_path(project_path, &set.target_language)?;
+    check_app_data_unlocked(project_path)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(set)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize translation set: {}", e)))?;
+    fs::write(&path, json).map_err(|e| CoreError::Io(format!("Failed to write translation set to {:?}: {}", path, e)))
+}
+
+/// Adds a batch's results to the set of its language: an entry translated again replaces its
+/// earlier translation, new entries are appended. Returns the updated set.
+pub fn record_translations(
+    project_path: &str,
+    target_language: &str,
+    results: &[WorkingTranslation],
+) -> Result<TranslationSet, CoreError> {
+    let mut set = load_translation_set(project_path, target_language)?
+        .unwrap_or_else(|| TranslationSet { target_language: target_language.to_string(), ..Default::default() });
+    let positions: HashMap<(String, String), usize> = set
+        .translations
+        .iter()
+        .enumerate()
+        .map(|(i, t)| ((t.source_file.clone(), t.json_path.clone()), i))
+        .collect();
+    for result in results {
+        match positions.get(&(result.source_file.clone(), result.json_path.clone())) {
+            Some(&i) => set.translations[i] = result.clone(),
+            None => set.translations.push(result.clone()),
+        }
+    }
+    save_translation_set(project_path, &set)?;
+    Ok(set)
+}
+
+/// Languages the project has a translation set for, sorted.
+pub fn list_translation_sets(project_path: &str) -> Result<Vec<String>, CoreError> {
+    let dir = app_data_dir(project_path).join(TRANSLATION_SETS_DIR_NAME);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut languages = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                languages.push(stem.to_string());
+            }
+        }
+    }
+    languages.sort();
+    Ok(languages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use tempfile::tempdir;
+
+    fn translation(json_path: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: "はい".to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_sets_are_kept_per_language() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        record_translations(project_path, "en", &[translation("a", "Yes"), translation("b", "")]).unwrap();
+        record_translations(project_path, "fr", &[translation("a", "Oui")]).unwrap();
+        let en = record_translations(project_path, "en", &[translation("b", "Yes!")]).unwrap();
+
+        let texts: Vec<&str> = en.translations.iter().map(|t| t.translated_text.as_str()).collect();
+        assert_eq!(texts, vec!["Yes", "Yes!"]);
+        assert_eq!(load_translation_set(project_path, "fr").unwrap().unwrap().translations[0].translated_text, "Oui");
+        assert_eq!(list_translation_sets(project_path).unwrap(), vec!["en", "fr"]);
+        assert!(load_translation_set(project_path, "de").unwrap().is_none());
+        assert!(matches!(load_translation_set(project_path, "../en"), Err(CoreError::Config(_))));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::core::escaping::sanitize_translation;
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+
+/// `translation_source` of placeholders written by the offline fallback. They are built into the
+/// game like translations, but stay `Untranslated` and are picked up again when a session is
+/// resumed.
+pub const TRANSLITERATION_SOURCE: &str = "transliteration";
+
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Hepburn romanization of one kana (as hiragana).
+fn kana_romaji(c: char) -> Option<&'static str> {
+    let romaji = match c {
+        'あ' | 'ぁ' => "a", 'い' | 'ぃ' | 'ゐ' => "i", 'う' | 'ぅ' => "u", 'え' | 'ぇ' | 'ゑ' => "e", 'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka", 'き' => "ki", 'く' => "ku", 'け' | 'ゖ' => "ke", 'こ' => "ko",
+        'が' => "ga", 'ぎ' => "gi", 'ぐ' => "gu", 'げ' => "ge", 'ご' => "go",
+        'さ' => "sa", 'し' => "shi", 'す' => "su", 'せ' => "se", 'そ' => "so",
+        'ざ' => "za", 'じ' | 'ぢ' => "ji", 'ず' | 'づ' => "zu", 'ぜ' => "ze", 'ぞ' => "zo",
+        'た' => "ta", 'ち' => "chi", 'つ' => "tsu", 'て' => "te", 'と' => "to",
+        'だ' => "da", 'で' => "de", 'ど' => "do",
+        'な' => "na", 'に' => "ni", 'ぬ' => "nu", 'ね' => "ne", 'の' => "no",
+        'は' => "ha", 'ひ' => "hi", 'ふ' => "fu", 'へ' => "he", 'ほ' => "ho",
+        'ば' => "ba", 'び' => "bi", 'ぶ' => "bu", 'べ' => "be", 'ぼ' => "bo",
+        'ぱ' => "pa", 'ぴ' => "pi", 'ぷ' => "pu", 'ぺ' => "pe", 'ぽ' => "po",
+        'ま' => "ma", 'み' => "mi", 'む' => "mu", 'め' => "me", 'も' => "mo",
+        'や' | 'ゃ' => "ya", 'ゆ' | 'ゅ' => "yu", 'よ' | 'ょ' => "yo",
+        'ら' => "ra", 'り' => "ri", 'る' => "ru", 'れ' => "re", 'ろ' => "ro",
+        'わ' | 'ゎ' => "wa", 'ん' => "n", 'ゔ' => "vu",
+        _ => return None,
+    };
+    Some(romaji)
+}
+
+/// Romanization of a kana followed by a small kana (きゃ, しょ, ファ, ティ, ...).
+fn digraph_romaji(first: char, second: char) -> Option<String> {
+    let vowel = match second {
+        'ゃ' => "a",
+        'ゅ' => "u",
+        'ょ' => "o",
+        'ぁ' => "a",
+        'ぃ' => "i",
+        'ぅ' => "u",
+        'ぇ' => "e",
+        'ぉ' => "o",
+        _ => return None,
+    };
+    let yoon = matches!(second, 'ゃ' | 'ゅ' | 'ょ');
+    let consonant = match (first, yoon) {
+        ('き', true) => "ky",
+        ('ぎ', true) => "gy",
+        ('に', true) => "ny",
+        ('ひ', true) => "hy",
+        ('び', true) => "by",
+        ('ぴ', true) => "py",
+        ('み', true) => "my",
+        ('り', true) => "ry",
+        ('し', _) => "sh",
+        ('じ' | 'ぢ', _) => "j",
+        ('ち', _) => "ch",
+        ('ふ', false) => "f",
+        ('ゔ', false) => "v",
+        ('て', false) => "t",
+        ('で', false) => "d",
+        ('う', false) => "w",
+        ('つ', false) => "ts",
+        ('い', false) if second == 'ぇ' => "y",
+        _ => return None,
+    };
+    // The small vowel after し, じ and ち only sets the vowel (しぇ she, ちぇ che).
+    if !yoon && matches!(first, 'し' | 'じ' | 'ぢ' | 'ち') && second != 'ぇ' {
+        return None;
+    }
+    Some(format!("{}{}", consonant, vowel))
+}
+
+/// Japanese punctuation and full-width forms as ASCII.
+fn punctuation(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        '。' => ". ",
+        '、' => ", ",
+        '・' | '　' => " ",
+        '「' | '」' | '『' | '』' => "\"",
+        '（' => "(",
+        '）' => ")",
+        '…' => "...",
+        '〜' | '～' => "~",
+        '！' => "!",
+        '？' => "?",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+/// Hepburn romanization of the kana in `text` ("ポーションを手に入れた！" becomes
+/// "pooshon'o手ni入reta!"). Kanji need a dictionary and are kept as they are, like control codes
+/// and anything else that isn't kana.
+pub fn romanize_kana(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(to_hiragana).collect();
+    let mut out = String::with_capacity(text.len());
+    let (mut geminate, mut after_n) = (false, false);
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == 'っ' {
+            geminate = true;
+            i += 1;
+            continue;
+        }
+        let (romaji, used) = match chars.get(i + 1).and_then(|&next| digraph_romaji(c, next)) {
+            Some(romaji) => (Some(romaji), 2),
+            None => (kana_romaji(c).map(str::to_string), 1),
+        };
+        match romaji {
+            Some(romaji) => {
+                // ん before a vowel or y is written n' so "kan'i" doesn't read "ka-ni".
+                if after_n && romaji.starts_with(['a', 'i', 'u', 'e', 'o', 'y']) {
+                    out.push('\'');
+                }
+                if geminate {
+                    match romaji.strip_prefix("ch") {
+                        Some(_) => out.push('t'),
+                        None => out.extend(romaji.chars().next().filter(|c| !"aiueon".contains(*c))),
+                    }
+                }
+                out.push_str(&romaji);
+                after_n = c == 'ん';
+            }
+            None if c == 'ー' => {
+                if let Some(vowel) = out.chars().rev().find(|c| "aiueo".contains(*c)) {
+                    out.push(vowel);
+                }
+                after_n = false;
+            }
+            None => {
+                match punctuation(c) {
+                    Some(ascii) => out.push_str(ascii),
+                    None => match c {
+                        // Full-width ASCII.
+                        '！'..='～' => out.extend(char::from_u32(c as u32 - 0xFEE0)),
+                        _ => out.push(c),
+                    },
+                }
+                after_n = false;
+            }
+        }
+        geminate = false;
+        i += used;
+    }
+    out.lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+}
+
+/// Offline stand-in for a translation: the kana of Japanese text romanized, other languages as
+/// they are.
+pub fn transliterate(text: &str, source_lang: &str) -> String {
+    match source_lang.to_ascii_lowercase().as_str() {
+        "ja" => romanize_kana(text),
+        _ => text.to_string(),
+    }
+}
+
+/// Replaces the entry's translation (or error) with a transliterated placeholder.
+pub fn fill_placeholder(entry: &mut WorkingTranslation, source_lang: &str) {
+    entry.translated_text = transliterate(&entry.original_text, source_lang);
+    entry.translation_source = TRANSLITERATION_SOURCE.to_string();
+    entry.error = None;
+    entry.status = TranslationStatus::Untranslated;
+    sanitize_translation(entry);
+}
+
+/// A placeholder translation of `entry`.
+pub fn placeholder_for(entry: SourceStringData, source_lang: &str) -> WorkingTranslation {
+    let mut placeholder = WorkingTranslation {
+        object_id: entry.object_id,
+        original_text: entry.original_text,
+        translated_text: String::new(),
+        source_file: entry.source_file,
+        json_path: entry.json_path,
+        translation_source: TRANSLITERATION_SOURCE.to_string(),
+        error: None,
+        status: TranslationStatus::Untranslated,
+    };
+    fill_placeholder(&mut placeholder, source_lang);
+    placeholder
+}
+
+pub fn is_placeholder(entry: &WorkingTranslation) -> bool {
+    entry.translation_source == TRANSLITERATION_SOURCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kana_are_romanized() {
+        assert_eq!(romanize_kana("ようこそ、旅の方。"), "youkoso, 旅no方.");
+        assert_eq!(romanize_kana("ポーションを手に入れた！"), "pooshon'o手ni入reta!");
+        assert_eq!(romanize_kana("ちょっと待って"), "chotto待tte");
+        assert_eq!(romanize_kana("マッチ、コーヒー"), "matchi, koohii");
+        assert_eq!(romanize_kana("ファイア！ティータイム"), "faia!tiitaimu");
+        assert_eq!(romanize_kana("きんえん　しんや"), "kin'en shin'ya");
+        assert_eq!(romanize_kana("\\N[1]「ＨＰ１００」"), "\\N[1]\"HP100\"");
+
+        let placeholder = placeholder_for(
+            SourceStringData {
+                object_id: 1,
+                original_text: "はい".to_string(),
+                source_file: "www/data/Map001.json".to_string(),
+                json_path: "events[1].pages[0].list[1].parameters[0]".to_string(),
+                dialogue_block_id: None,
+                speaker: None,
+            },
+            "ja",
+        );
+        assert_eq!(placeholder.translated_text, "hai");
+        assert!(is_placeholder(&placeholder) && placeholder.status == TranslationStatus::Untranslated);
+        assert_eq!(transliterate("안녕", "ko"), "안녕");
+    }
+}
@@ -0,0 +1,5 @@
+// TyranoScript (and KAG-style) visual novels. Scenarios are the `.ks` files under
+// `data/scenario/`; narration, speaker names and `[glink]` choices are extracted with their inline
+// `[tags]` left in place, and translations are written back into the same lines.
+pub mod scenario;
+pub mod project;
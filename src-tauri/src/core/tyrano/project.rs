@@ -0,0 +1,101 @@
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use crate::core::config::IgnoredFiles;
+use crate::core::rpgmv::backups::{is_backup_dir_name, is_backup_file_name};
+use crate::core::rpgmv::filter::CompiledExtractionFilter;
+use crate::core::rpgmv::project::extract_listed_files;
+use crate::models::translation::SourceStringData;
+use super::scenario;
+
+/// Folder of a TyranoScript game's scenarios, relative to its root.
+pub const SCENARIO_DIR: &str = "data/scenario";
+/// Roots a game can be unpacked to: the project folder, or the app folder of an Electron build.
+const GAME_ROOTS: [&str; 2] = ["", "resources/app"];
+
+fn scenario_dirs(project_path: &str) -> Vec<PathBuf> {
+    GAME_ROOTS
+        .iter()
+        .map(|root| Path::new(project_path).join(root).join(SCENARIO_DIR))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+fn is_scenario(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "ks")
+}
+
+/// True for a TyranoScript game: a `data/scenario/` folder with `.ks` files.
+pub fn is_tyrano_project(project_path: &str) -> bool {
+    scenario_dirs(project_path).iter().any(|dir| {
+        WalkDir::new(dir).into_iter().filter_map(Result::ok).any(|e| e.file_type().is_file() && is_scenario(e.path()))
+    })
+}
+
+/// Relative paths (with `/`) of the `.ks` scenarios, leaving out backup folders and copies.
+pub fn scenario_files(project_path: &str) -> Vec<String> {
+    let mut files: Vec<String> = scenario_dirs(project_path)
+        .iter()
+        .flat_map(|dir| {
+            WalkDir::new(dir)
+                .into_iter()
+                .filter_entry(|e| {
+                    let name = e.file_name().to_str().unwrap_or("");
+                    e.depth() == 0
+                        || !((e.file_type().is_dir() && is_backup_dir_name(name))
+                            || (e.file_type().is_file() && is_backup_file_name(name.strip_suffix(".ks").unwrap_or(name))))
+                })
+                .filter_map(Result::ok)
+        })
+        .filter(|e| e.file_type().is_file() && is_scenario(e.path()))
+        .filter_map(|e| Some(e.path().strip_prefix(project_path).ok()?.to_str()?.replace('\\', "/")))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Extracts narration, speaker names and choices from every scenario of a TyranoScript game.
+pub fn extract_translatable_strings(
+    project_path: &str,
+    ignored: &IgnoredFiles,
+    filter: &CompiledExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let files = scenario_files(project_path);
+    if files.is_empty() {
+        return Err(format!("No .ks scenarios found in {}/{}", project_path, SCENARIO_DIR));
+    }
+    extract_listed_files(project_path, files, scenario::extract_strings, ignored, filter, should_stop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::models::extraction::ExtractionFilter;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_tyrano_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("resources/app/data/scenario/system")).unwrap();
+        fs::create_dir_all(root.join("resources/app/data/scenario_backup")).unwrap();
+        fs::write(root.join("resources/app/data/scenario/first.ks"), "*start\n#あかね\nこんにちは[p]\n").unwrap();
+        fs::write(root.join("resources/app/data/scenario/system/config.ks"), "[iscript]\nsf.a = 1;\n[endscript]\n").unwrap();
+        fs::write(root.join("resources/app/data/scenario/first - Copy.ks"), "古い[p]\n").unwrap();
+
+        let project_path = root.to_str().unwrap();
+        assert!(is_tyrano_project(project_path));
+        assert_eq!(scenario_files(project_path), vec![
+            "resources/app/data/scenario/first.ks",
+            "resources/app/data/scenario/system/config.ks",
+        ]);
+
+        let filter = ExtractionFilter::default();
+        let compiled = CompiledExtractionFilter::new(&filter).unwrap();
+        let ignored = IgnoredFiles::new(&[]).unwrap();
+        let strings = extract_translatable_strings(project_path, &ignored, &compiled, &|| false).unwrap();
+        let found: Vec<(&str, &str)> = strings.iter().map(|s| (s.json_path.as_str(), s.original_text.as_str())).collect();
+        assert_eq!(found, vec![("[1].speaker", "あかね"), ("[2].text", "こんにちは")]);
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+
+const BOM: char = '\u{feff}';
+
+/// Tags that only pace or clear the message window. Runs of them at the start or end of a text
+/// line stay in the file instead of going to the translator.
+const WAIT_TAGS: [&str; 7] = ["l", "r", "p", "cm", "er", "s", "wt"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+    // Narration or dialogue; inline tags such as `[ruby]` or `[emb]` stay in the text.
+    Text,
+    // The speaker of a `#name` or `#name:face` line.
+    Speaker,
+    // The `text` attribute of a `[glink]` choice button.
+    Choice,
+}
+
+/// A translatable string of a scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioString {
+    // Zero-based line index in the scenario.
+    pub line: usize,
+    pub kind: StringKind,
+    // Bytes of the string in its line; for choices the quotes are included.
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+impl ScenarioString {
+    pub fn json_path(&self) -> String {
+        let kind = match self.kind {
+            StringKind::Text => "text",
+            StringKind::Speaker => "speaker",
+            StringKind::Choice => "glink",
+        };
+        format!("[{}].{}", self.line, kind)
+    }
+}
+
+/// A `[tag ...]` of a line.
+struct Tag<'a> {
+    name: &'a str,
+    // Bytes of the tag in its line, brackets included.
+    span: Range<usize>,
+    // Attribute name and the bytes of its value (quotes included).
+    attributes: Vec<(&'a str, Range<usize>)>,
+}
+
+fn split_lines(content: &str) -> Vec<&str> {
+    content.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)).collect()
+}
+
+/// Parses the tag starting at `start` (a `[`, or the `@` of a tag line). `None` when it isn't
+/// closed on the line.
+fn parse_tag(line: &str, start: usize, tag_line: bool) -> Option<Tag<'_>> {
+    let bytes = line.as_bytes();
+    let mut position = start + 1;
+    let skip_spaces = |position: &mut usize| {
+        while bytes.get(*position).is_some_and(|b| *b == b' ' || *b == b'\t') {
+            *position += 1;
+        }
+    };
+    let is_word_end = |b: u8| b == b' ' || b == b'\t' || b == b']' || b == b'=';
+    let name_start = position;
+    while bytes.get(position).is_some_and(|b| !is_word_end(*b)) {
+        position += 1;
+    }
+    let name = &line[name_start..position];
+    let mut attributes = Vec::new();
+    loop {
+        skip_spaces(&mut position);
+        match bytes.get(position) {
+            None if tag_line => break,
+            None => return None,
+            Some(b']') if !tag_line => {
+                position += 1;
+                break;
+            }
+            Some(_) => {}
+        }
+        let key_start = position;
+        while bytes.get(position).is_some_and(|b| !is_word_end(*b)) {
+            position += 1;
+        }
+        let key = &line[key_start..position];
+        skip_spaces(&mut position);
+        if bytes.get(position) != Some(&b'=') {
+            // A flag attribute without a value, e.g. `[button ... hint]`.
+            if key.is_empty() {
+                position += 1;
+            }
+            continue;
+        }
+        position += 1;
+        skip_spaces(&mut position);
+        let value_start = position;
+        match bytes.get(position) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let close = line[position + 1..].find(quote as char)?;
+                position += close + 2;
+            }
+            _ => {
+                while bytes.get(position).is_some_and(|b| *b != b' ' && *b != b'\t' && (tag_line || *b != b']')) {
+                    position += 1;
+                }
+            }
+        }
+        attributes.push((key, value_start..position));
+    }
+    Some(Tag { name, span: start..position, attributes })
+}
+
+/// The `[tags]` of a text line, skipping `[[` (a literal bracket).
+fn inline_tags(line: &str) -> Vec<Tag<'_>> {
+    let mut tags = Vec::new();
+    let mut position = 0;
+    while let Some(offset) = line[position..].find('[') {
+        let start = position + offset;
+        if line[start + 1..].starts_with('[') {
+            position = start + 2;
+            continue;
+        }
+        match parse_tag(line, start, false) {
+            Some(tag) => {
+                position = tag.span.end;
+                tags.push(tag);
+            }
+            None => break,
+        }
+    }
+    tags
+}
+
+fn choice_of(line_index: usize, line: &str, tag: &Tag) -> Option<ScenarioString> {
+    if tag.name != "glink" {
+        return None;
+    }
+    let (_, span) = tag.attributes.iter().find(|(key, _)| *key == "text")?;
+    let text = unquote(&line[span.clone()]);
+    (!text.trim().is_empty()).then(|| ScenarioString { line: line_index, kind: StringKind::Choice, span: span.clone(), text })
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// The text of a text line between its leading and trailing runs of wait tags and spaces.
+fn text_span(line: &str, tags: &[Tag]) -> Range<usize> {
+    let is_wait = |tag: &Tag| WAIT_TAGS.contains(&tag.name);
+    let mut start = line.len() - line.trim_start().len();
+    for tag in tags.iter().take_while(|tag| is_wait(tag)) {
+        if line[start..tag.span.start].trim().is_empty() {
+            start = tag.span.end;
+        } else {
+            break;
+        }
+    }
+    let mut end = line.trim_end().len();
+    for tag in tags.iter().rev().take_while(|tag| is_wait(tag)) {
+        if tag.span.end >= start && line[tag.span.end..end].trim().is_empty() {
+            end = tag.span.start;
+        } else {
+            break;
+        }
+    }
+    start += line[start..].len() - line[start..].trim_start().len();
+    end = end.max(start);
+    end = start + line[start..end].trim_end().len();
+    start..end
+}
+
+/// Lists narration/dialogue lines, speaker names and choices. Comments, labels, `[iscript]` and
+/// `[html]` blocks are skipped.
+pub fn parse_scenario(content: &str) -> Vec<ScenarioString> {
+    let content = content.strip_prefix(BOM).unwrap_or(content);
+    let mut strings = Vec::new();
+    let mut in_comment = false;
+    // The closing tag of the `[iscript]` or `[html]` block being skipped.
+    let mut skipped_until: Option<&str> = None;
+    for (index, line) in split_lines(content).into_iter().enumerate() {
+        let body = line.trim_start();
+        let indent = line.len() - body.len();
+        if in_comment {
+            in_comment = !body.contains("*/");
+            continue;
+        }
+        if let Some(comment) = body.strip_prefix("/*") {
+            in_comment = !comment.contains("*/");
+            continue;
+        }
+        if body.is_empty() || body.starts_with(';') || body.starts_with('*') {
+            continue;
+        }
+        let tags = if body.starts_with('@') {
+            parse_tag(line, indent, true).into_iter().collect()
+        } else {
+            inline_tags(line)
+        };
+        if let Some(end_tag) = skipped_until {
+            if tags.iter().any(|tag| tag.name == end_tag) {
+                skipped_until = None;
+            }
+            continue;
+        }
+        if let Some(tag) = tags.iter().find(|tag| tag.name == "iscript" || tag.name == "html") {
+            let end_tag = if tag.name == "iscript" { "endscript" } else { "endhtml" };
+            if !tags.iter().any(|t| t.name == end_tag) {
+                skipped_until = Some(end_tag);
+            }
+            continue;
+        }
+        strings.extend(tags.iter().filter_map(|tag| choice_of(index, line, tag)));
+        if body.starts_with('@') {
+            continue;
+        }
+        if let Some(speaker) = body.strip_prefix('#') {
+            // `#name:face` shows `name` with the `face` expression; `#` alone clears the name.
+            let name = speaker.split(':').next().unwrap_or("");
+            let start = indent + 1 + (name.len() - name.trim_start().len());
+            let name = name.trim();
+            if !name.is_empty() {
+                strings.push(ScenarioString {
+                    line: index,
+                    kind: StringKind::Speaker,
+                    span: start..start + name.len(),
+                    text: name.to_string(),
+                });
+            }
+            continue;
+        }
+        let span = text_span(line, &tags);
+        let text = &line[span.clone()];
+        let outside_tags = tags
+            .iter()
+            .filter(|tag| tag.span.start >= span.start && tag.span.end <= span.end)
+            .fold(text.to_string(), |rest, tag| rest.replacen(&line[tag.span.clone()], "", 1));
+        if !outside_tags.trim().is_empty() {
+            strings.push(ScenarioString { line: index, kind: StringKind::Text, span, text: text.to_string() });
+        }
+    }
+    strings
+}
+
+/// Extracts narration, speaker names and choices. The json_path is the zero-based line and
+/// the kind, e.g. "[12].text", and the object id the line number.
+pub fn extract_strings(file_content: &str, source_file: &str) -> Result<Vec<SourceStringData>, String> {
+    Ok(parse_scenario(file_content)
+        .into_iter()
+        .map(|string| SourceStringData {
+            object_id: string.line as u32 + 1,
+            json_path: string.json_path(),
+            original_text: string.text,
+            source_file: source_file.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        })
+        .collect())
+}
+
+/// Writes a translation so it keeps its role on the line: line breaks become `[r]` in text,
+/// and a leading character that would turn the line into a label, comment, tag or speaker line
+/// is replaced by its full-width form.
+fn encode(kind: StringKind, text: &str) -> String {
+    match kind {
+        StringKind::Text => {
+            let text = text.trim_end_matches('\n').replace("\r\n", "\n").replace('\n', "[r]");
+            let mut chars = text.chars();
+            match chars.next() {
+                Some('#') => format!("＃{}", chars.as_str()),
+                Some(';') => format!("；{}", chars.as_str()),
+                Some('*') => format!("＊{}", chars.as_str()),
+                Some('@') => format!("＠{}", chars.as_str()),
+                _ => text,
+            }
+        }
+        StringKind::Speaker => text.replace(['\r', '\n'], " ").replace(':', "："),
+        StringKind::Choice => {
+            let text = text.replace(['\r', '\n'], " ");
+            if !text.contains('"') {
+                format!("\"{}\"", text)
+            } else if !text.contains('\'') {
+                format!("'{}'", text)
+            } else {
+                format!("\"{}\"", text.replace('"', "'"))
+            }
+        }
+    }
+}
+
+/// Writes translations into the scenario. Tags, comments, line endings and strings without a
+/// usable translation are kept as they were.
+pub fn reconstruct_scenario(original_content: &str, translations: Vec<&WorkingTranslation>) -> Result<String, CoreError> {
+    let (bom, content) = match original_content.strip_prefix(BOM) {
+        Some(rest) => (BOM.to_string(), rest),
+        None => (String::new(), original_content),
+    };
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let by_path: HashMap<&str, &str> = translations
+        .iter()
+        .filter(|entry| entry.error.is_none() && !entry.translated_text.is_empty())
+        .map(|entry| (entry.json_path.as_str(), entry.translated_text.as_str()))
+        .collect();
+    let mut lines: Vec<String> = split_lines(content).into_iter().map(str::to_string).collect();
+    // From the end of each line, so earlier spans on it stay valid.
+    for string in parse_scenario(content).iter().rev() {
+        if let Some(translated) = by_path.get(string.json_path().as_str()) {
+            lines[string.line].replace_range(string.span.clone(), &encode(string.kind, translated));
+        }
+    }
+    Ok(format!("{}{}", bom, lines.join(newline)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    const SCENARIO: &str = "*start\r\n\
+[cm]\r\n\
+;コメント\r\n\
+@bg storage=room.jpg time=500\r\n\
+#あかね:happy\r\n\
+[cm]こんにちは、[ruby text=\"せんせい\"]先生。[l][r]\r\n\
+今日は[emb exp=\"f.name\"]の日です。[p]\r\n\
+/* 未使用\r\n\
+古い台詞\r\n\
+*/\r\n\
+[iscript]\r\n\
+f.name = \"太郎\";\r\n\
+[endscript]\r\n\
+[glink text=\"はい\" target=*yes]\r\n\
+@glink text='いいえ' target=*no\r\n\
+#\r\n\
+[[括弧]はそのまま[p]\r\n";
+
+    #[test]
+    fn test_parse_scenario() {
+        let strings = parse_scenario(SCENARIO);
+        let paths: Vec<String> = strings.iter().map(ScenarioString::json_path).collect();
+        let texts: Vec<&str> = strings.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(paths, vec!["[4].speaker", "[5].text", "[6].text", "[13].glink", "[14].glink", "[16].text"]);
+        assert_eq!(texts, vec![
+            "あかね",
+            "こんにちは、[ruby text=\"せんせい\"]先生。",
+            "今日は[emb exp=\"f.name\"]の日です。",
+            "はい",
+            "いいえ",
+            "[[括弧]はそのまま",
+        ]);
+    }
+
+    #[test]
+    fn test_reconstruct_scenario() {
+        let entry = |json_path: &str, translated: &str, error: Option<&str>| WorkingTranslation {
+            object_id: 1,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "data/scenario/first.ks".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "test".to_string(),
+            error: error.map(str::to_string),
+            status: TranslationStatus::MachineTranslated,
+        };
+        let translations = [
+            entry("[4].speaker", "Akane", None),
+            entry("[5].text", "Hello, [ruby text=\"sensei\"]teacher.", None),
+            entry("[6].text", "#1 today\nis [emb exp=\"f.name\"]'s day.", None),
+            entry("[13].glink", "Say \"yes\"", None),
+            entry("[14].glink", "No", Some("timeout")),
+        ];
+        let rebuilt = reconstruct_scenario(&format!("\u{feff}{}", SCENARIO), translations.iter().collect()).unwrap();
+        let lines: Vec<&str> = rebuilt.split("\r\n").collect();
+        assert!(rebuilt.starts_with('\u{feff}'));
+        assert_eq!(lines[4], "#Akane:happy");
+        assert_eq!(lines[5], "[cm]Hello, [ruby text=\"sensei\"]teacher.[l][r]");
+        assert_eq!(lines[6], "＃1 today[r]is [emb exp=\"f.name\"]'s day.[p]");
+        assert_eq!(lines[13], "[glink text='Say \"yes\"' target=*yes]");
+        assert_eq!(lines[14], "@glink text='いいえ' target=*no");
+        assert_eq!(reconstruct_scenario(SCENARIO, Vec::new()).unwrap(), SCENARIO);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::models::translation::{EntryUnitMode, UnitMode};
+
+/// Unit modes of a batch: a default plus per-entry choices.
+#[derive(Debug, Clone, Default)]
+pub struct UnitModes {
+    default: UnitMode,
+    by_entry: HashMap<(String, String), UnitMode>,
+}
+
+impl UnitModes {
+    pub fn new(default: UnitMode, entries: Vec<EntryUnitMode>) -> Self {
+        let by_entry = entries.into_iter().map(|e| ((e.source_file, e.json_path), e.mode)).collect();
+        UnitModes { default, by_entry }
+    }
+
+    pub fn mode_for(&self, source_file: &str, json_path: &str) -> UnitMode {
+        self.by_entry
+            .get(&(source_file.to_string(), json_path.to_string()))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// A text cut into translation units, with the separators needed to put it back together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextUnits {
+    pub units: Vec<String>,
+    // separators[i] follows units[i]; one fewer than units.
+    separators: Vec<String>,
+}
+
+impl TextUnits {
+    /// Rejoins translated units (one per unit, in order) with the original separators.
+    pub fn join(&self, translated: &[String]) -> String {
+        let mut out = String::new();
+        for (i, unit) in translated.iter().enumerate() {
+            out.push_str(unit);
+            if let Some(separator) = self.separators.get(i) {
+                out.push_str(separator);
+            }
+        }
+        out
+    }
+}
+
+fn paragraph_break() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // A line break followed by at least one blank (or whitespace-only) line.
+    RE.get_or_init(|| Regex::new(r"\r?\n(?:[ \t\u{3000}]*\r?\n)+").expect("paragraph pattern is valid"))
+}
+
+/// Cuts `text` according to `mode`. Blank paragraphs are kept in the separators, so joining the
+/// untranslated units gives back the original text.
+pub fn split_units(text: &str, mode: UnitMode) -> TextUnits {
+    match mode {
+        UnitMode::Block => TextUnits { units: vec![text.to_string()], separators: Vec::new() },
+        UnitMode::Paragraphs => {
+            let mut units = Vec::new();
+            let mut separators = Vec::new();
+            let mut start = 0;
+            for separator in paragraph_break().find_iter(text) {
+                units.push(text[start..separator.start()].to_string());
+                separators.push(separator.as_str().to_string());
+                start = separator.end();
+            }
+            units.push(text[start..].to_string());
+            TextUnits { units, separators }
+        }
+    }
+}
+
+// Shortest repeated run (in characters) reported when a unit starts with the end of the previous one.
+const MIN_SEAM_OVERLAP_CHARS: usize = 12;
+
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '…' | '‥')
+}
+
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | ')' | ']' | '」' | '』' | '）' | '”' | '’' | '】')
+}
+
+/// True when `text` ends a sentence, allowing closing quotes and brackets after the punctuation.
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end().trim_end_matches(is_closing).chars().last().is_some_and(is_sentence_end)
+}
+
+fn sentences(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next_is_end = chars.peek().is_some_and(|&(_, next)| is_sentence_end(next) || is_closing(next));
+        if is_sentence_end(c) && !next_is_end {
+            out.push(text[start..i + c.len_utf8()].trim());
+            start = i + c.len_utf8();
+        }
+    }
+    out.push(text[start..].trim());
+    out.retain(|sentence| !sentence.is_empty());
+    out
+}
+
+/// Longest end of `before` that `after` starts with, in characters.
+fn overlap_chars(before: &str, after: &str) -> usize {
+    before
+        .char_indices()
+        .map(|(i, _)| &before[i..])
+        .find(|tail| after.starts_with(tail))
+        .map_or(0, |tail| tail.chars().count())
+}
+
+/// Checks a translation rejoined from paragraph units for artifacts at the seams: a paragraph
+/// lost or merged, a sentence repeated on both sides of a seam, or a paragraph that ended a
+/// sentence in the original but is cut off mid-sentence in the translation. Texts of a single
+/// paragraph have no seams.
+pub fn seam_problems(original: &str, translated: &str) -> Vec<String> {
+    let original = split_units(original, UnitMode::Paragraphs).units;
+    if original.len() < 2 {
+        return Vec::new();
+    }
+    let translated = split_units(translated, UnitMode::Paragraphs).units;
+    if translated.len() != original.len() {
+        return vec![format!(
+            "The translation has {} paragraph(s) instead of {}; a chunk was lost or merged",
+            translated.len(),
+            original.len()
+        )];
+    }
+
+    let mut problems = Vec::new();
+    for (i, (source, unit)) in original.iter().zip(&translated).enumerate() {
+        if unit.trim().is_empty() && !source.trim().is_empty() {
+            problems.push(format!("Paragraph {} is missing from the translation", i + 1));
+            continue;
+        }
+        let Some(next) = translated.get(i + 1) else { continue };
+        let (last, first) = (sentences(unit).pop(), sentences(next).first().copied());
+        if let (Some(last), Some(first)) = (last, first) {
+            if last == first || overlap_chars(unit.trim_end(), next.trim_start()) >= MIN_SEAM_OVERLAP_CHARS {
+                problems.push(format!("\"{}\" is repeated across the end of paragraph {}", last, i + 1));
+                continue;
+            }
+        }
+        if ends_sentence(source) && !ends_sentence(unit) {
+            problems.push(format!("Paragraph {} ends mid-sentence in the translation", i + 1));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_join_paragraphs() {
+        let text = "第一章\n王国の歴史。\n\n第二章\r\n\r\n\u{3000}\r\n終わり";
+        let units = split_units(text, UnitMode::Paragraphs);
+        assert_eq!(units.units, vec!["第一章\n王国の歴史。", "第二章", "終わり"]);
+        assert_eq!(units.join(&units.units), text);
+        let translated = ["Chapter 1\nHistory.".to_string(), "Chapter 2".to_string(), "The End".to_string()];
+        assert_eq!(units.join(&translated), "Chapter 1\nHistory.\n\nChapter 2\r\n\r\n\u{3000}\r\nThe End");
+
+        assert_eq!(split_units(text, UnitMode::Block).units, vec![text]);
+    }
+
+    #[test]
+    fn test_seam_problems() {
+        let original = "王は城にいる。\n\n姫は森へ行った。\n\n騎士が追った。";
+        assert!(seam_problems(original, "The king is in the castle.\n\nThe princess went to the forest.\n\nA knight followed.").is_empty());
+        assert!(seam_problems("一行だけ。", "Only one line").is_empty());
+
+        let repeated = "The king is in the castle.\n\nThe king is in the castle. The princess went to the forest.\n\nA knight followed.";
+        let problems = seam_problems(original, repeated);
+        assert_eq!(problems, vec!["\"The king is in the castle.\" is repeated across the end of paragraph 1"]);
+
+        let cut = "The king is in the castle.\n\nThe princess went to\n\nthe forest. A knight followed.";
+        assert_eq!(seam_problems(original, cut), vec!["Paragraph 2 ends mid-sentence in the translation"]);
+        assert_eq!(seam_problems(original, "The king is in the castle.\n\nA knight followed.").len(), 1);
+    }
+
+    #[test]
+    fn test_mode_for_entry() {
+        let modes = UnitModes::new(
+            UnitMode::Block,
+            vec![EntryUnitMode {
+                source_file: "www/data/Actors.json".to_string(),
+                json_path: "[1].profile".to_string(),
+                mode: UnitMode::Paragraphs,
+            }],
+        );
+        assert_eq!(modes.mode_for("www/data/Actors.json", "[1].profile"), UnitMode::Paragraphs);
+        assert_eq!(modes.mode_for("www/data/Actors.json", "[2].profile"), UnitMode::Block);
+    }
+}
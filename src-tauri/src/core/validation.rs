@@ -0,0 +1,281 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::core::control_codes::ControlCodes;
+use crate::core::escaping::{escape_context, simulate_engine_parse};
+use crate::core::units::seam_problems;
+use crate::error::CoreError;
+use crate::models::translation::WorkingTranslation;
+use crate::models::validation::{
+    ValidationIssue, ValidationRuleSet, ValidationSeverity, RULESET_FORMAT, RULESET_VERSION,
+};
+use crate::utils::project_paths::{app_data_dir, ensure_app_data_file};
+
+/// File name of the ruleset imported into a project (inside `.translate-ai/`).
+pub const PROJECT_RULESET_FILE_NAME: &str = "validation_rules.json";
+
+/// Returns the control codes/placeholders found in `text`, sorted so two texts can be compared
+/// regardless of word order.
+pub fn extract_placeholders(text: &str) -> Vec<String> {
+    static CODES: OnceLock<ControlCodes> = OnceLock::new();
+    CODES.get_or_init(ControlCodes::default).extract(text)
+}
+
+/// Checks that a ruleset is usable: known format/version, compilable regexes, sane budgets.
+pub fn check_ruleset(ruleset: &ValidationRuleSet) -> Result<(), CoreError> {
+    if ruleset.format != RULESET_FORMAT {
+        return Err(CoreError::Config(format!(
+            "Not a validation ruleset file (format '{}', expected '{}')",
+            ruleset.format, RULESET_FORMAT
+        )));
+    }
+    if ruleset.version > RULESET_VERSION {
+        return Err(CoreError::Config(format!(
+            "Ruleset version {} is newer than supported version {}",
+            ruleset.version, RULESET_VERSION
+        )));
+    }
+    for budget in &ruleset.length_budgets {
+        Regex::new(&budget.path_pattern).map_err(|e| {
+            CoreError::Config(format!("Invalid length budget pattern '{}': {}", budget.path_pattern, e))
+        })?;
+        if budget.max_chars == 0 {
+            return Err(CoreError::Config(format!(
+                "Length budget for '{}' must be greater than 0",
+                budget.path_pattern
+            )));
+        }
+    }
+    for filter in &ruleset.regex_filters {
+        Regex::new(&filter.pattern)
+            .map_err(|e| CoreError::Config(format!("Invalid regex filter '{}': {}", filter.pattern, e)))?;
+    }
+    ControlCodes::new(&ruleset.control_codes)?;
+    for term in &ruleset.locked_terms {
+        if term.source.trim().is_empty() || term.target.trim().is_empty() {
+            return Err(CoreError::Config("Locked terms need both a source and a target".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Runs every rule of the ruleset against a single translated entry.
+/// Entries that failed to translate or have no translation are not validated.
+pub fn validate_translation(entry: &WorkingTranslation, ruleset: &ValidationRuleSet) -> Vec<ValidationIssue> {
+    validate_translation_with(entry, ruleset, &ControlCodes::from_ruleset(ruleset))
+}
+
+/// `validate_translation` with the ruleset's control codes compiled once by the caller, for
+/// validating many entries.
+pub fn validate_translation_with(
+    entry: &WorkingTranslation,
+    ruleset: &ValidationRuleSet,
+    codes: &ControlCodes,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if entry.error.is_some() || entry.translated_text.is_empty() {
+        return issues;
+    }
+
+    let mut push_issue = |rule: &str, message: String, severity: ValidationSeverity| {
+        issues.push(ValidationIssue {
+            source_file: entry.source_file.clone(),
+            json_path: entry.json_path.clone(),
+            rule: rule.to_string(),
+            message,
+            severity,
+        });
+    };
+
+    if ruleset.check_placeholders {
+        let original = codes.extract(&entry.original_text);
+        let translated = codes.extract(&entry.translated_text);
+        if original != translated {
+            push_issue(
+                "placeholder",
+                format!("Placeholders differ: original {:?}, translation {:?}", original, translated),
+                ValidationSeverity::Error,
+            );
+        }
+    }
+
+    if ruleset.check_engine_syntax {
+        let context = escape_context(&entry.source_file, &entry.json_path);
+        let (original, translated) = (codes.strip_custom(&entry.original_text), codes.strip_custom(&entry.translated_text));
+        for problem in simulate_engine_parse(&original, &translated, context) {
+            push_issue("engineSyntax", problem, ValidationSeverity::Error);
+        }
+    }
+
+    if ruleset.check_chunk_seams {
+        for problem in seam_problems(&entry.original_text, &entry.translated_text) {
+            push_issue("chunkSeam", problem, ValidationSeverity::Warning);
+        }
+    }
+
+    for budget in &ruleset.length_budgets {
+        let Ok(re) = Regex::new(&budget.path_pattern) else { continue };
+        let length = entry.translated_text.chars().count();
+        if re.is_match(&entry.json_path) && length > budget.max_chars {
+            push_issue(
+                "length",
+                format!("Translation is {} characters long, budget is {}", length, budget.max_chars),
+                ValidationSeverity::Warning,
+            );
+        }
+    }
+
+    for filter in &ruleset.regex_filters {
+        let Ok(re) = Regex::new(&filter.pattern) else { continue };
+        if re.is_match(&entry.translated_text) {
+            push_issue("regex", filter.message.clone(), filter.severity);
+        }
+    }
+
+    for term in &ruleset.locked_terms {
+        if entry.original_text.contains(&term.source) && !entry.translated_text.contains(&term.target) {
+            push_issue(
+                "lockedTerm",
+                format!("'{}' must be translated as '{}'", term.source, term.target),
+                ValidationSeverity::Error,
+            );
+        }
+    }
+
+    issues
+}
+
+/// Writes a ruleset to a standalone file that can be shared with other users.
+pub fn export_ruleset(ruleset: &ValidationRuleSet, output_path: &Path) -> Result<(), CoreError> {
+    check_ruleset(ruleset)?;
+    let json = serde_json::to_string_pretty(ruleset)
+        .map_err(|e| CoreError::JsonSerialize(format!("Failed to serialize ruleset: {}", e)))?;
+    fs::write(output_path, json)
+        .map_err(|e| CoreError::Io(format!("Failed to write ruleset to {:?}: {}", output_path, e)))
+}
+
+/// Reads and checks a ruleset file.
+pub fn read_ruleset_file(path: &Path) -> Result<ValidationRuleSet, CoreError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CoreError::Io(format!("Failed to read ruleset {:?}: {}", path, e)))?;
+    let ruleset: ValidationRuleSet = serde_json::from_str(&content)
+        .map_err(|e| CoreError::JsonParse(format!("Invalid ruleset file {:?}: {}", path, e)))?;
+    check_ruleset(&ruleset)?;
+    Ok(ruleset)
+}
+
+/// Imports a shared ruleset file into the project, replacing the project's current ruleset.
+pub fn import_ruleset_into_project(project_path: &str, ruleset_path: &Path) -> Result<ValidationRuleSet, CoreError> {
+    let ruleset = read_ruleset_file(ruleset_path)?;
+    save_project_ruleset(project_path, &ruleset)?;
+    Ok(ruleset)
+}
+
+pub fn save_project_ruleset(project_path: &str, ruleset: &ValidationRuleSet) -> Result<(), CoreError> {
+    let path = ensure_app_data_file(project_path, PROJECT_RULESET_FILE_NAME)?;
+    export_ruleset(ruleset, &path)
+}
+
+/// Loads the project's ruleset, or the default one if the project has none yet.
+pub fn load_project_ruleset(project_path: &str) -> Result<ValidationRuleSet, CoreError> {
+    let path = app_data_dir(project_path).join(PROJECT_RULESET_FILE_NAME);
+    if !path.is_file() {
+        return Ok(ValidationRuleSet::default());
+    }
+    read_ruleset_file(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+    use crate::models::validation::{LengthBudget, LockedTerm, RegexFilter};
+    use tempfile::tempdir;
+
+    fn translation(original: &str, translated: &str, json_path: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "test".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    fn sample_ruleset() -> ValidationRuleSet {
+        ValidationRuleSet {
+            name: "RPG Maker JP->EN".to_string(),
+            length_budgets: vec![LengthBudget { path_pattern: r"\.name$".to_string(), max_chars: 10 }],
+            regex_filters: vec![RegexFilter {
+                pattern: r"[\p{Hiragana}\p{Katakana}]".to_string(),
+                message: "Untranslated kana left in text".to_string(),
+                severity: ValidationSeverity::Error,
+            }],
+            locked_terms: vec![LockedTerm { source: "セレン".to_string(), target: "Selen".to_string() }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_placeholders() {
+        assert_eq!(extract_placeholders(r"\C[2]%1\C[0] gains \V[10] gold!\!"), vec![r"%1", r"\!", r"\C[0]", r"\C[2]", r"\V[10]"]);
+        assert!(extract_placeholders("Plain text").is_empty());
+    }
+
+    #[test]
+    fn test_validate_translation_rules() {
+        let ruleset = sample_ruleset();
+
+        let ok = translation(r"\N[1]はセレンに会った", r"\N[1] met Selen", "events[1].pages[0].list[1].parameters[0]");
+        assert!(validate_translation(&ok, &ruleset).is_empty());
+
+        let broken = translation(r"\N[1]はセレンに会った", "Someone met Seren ね", "events[1].pages[0].list[1].parameters[0]");
+        let rules: Vec<String> = validate_translation(&broken, &ruleset).into_iter().map(|i| i.rule).collect();
+        assert_eq!(rules, vec!["placeholder", "regex", "lockedTerm"]);
+
+        let too_long = translation("剣", "A very long sword name", "[1].name");
+        let issues = validate_translation(&too_long, &ruleset);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "length");
+
+        let stray = translation("ちょっと待って", r"Wait\ a moment", "events[1].pages[0].list[1].parameters[0]");
+        let issues = validate_translation(&stray, &ruleset);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "engineSyntax");
+        let unchecked = ValidationRuleSet { check_engine_syntax: false, ..sample_ruleset() };
+        assert!(validate_translation(&stray, &unchecked).is_empty());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let dir = tempdir().unwrap();
+        let export_path = dir.path().join("shared.json");
+        let ruleset = sample_ruleset();
+        export_ruleset(&ruleset, &export_path).unwrap();
+
+        let project_dir = dir.path().join("project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let project_path = project_dir.to_str().unwrap();
+
+        assert_eq!(load_project_ruleset(project_path).unwrap(), ValidationRuleSet::default());
+        let imported = import_ruleset_into_project(project_path, &export_path).unwrap();
+        assert_eq!(imported, ruleset);
+        assert_eq!(load_project_ruleset(project_path).unwrap(), ruleset);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_ruleset() {
+        let dir = tempdir().unwrap();
+        let bad_regex = dir.path().join("bad.json");
+        fs::write(&bad_regex, r#"{"format":"translate-ai-ruleset","name":"x","regexFilters":[{"pattern":"(","message":"m"}]}"#).unwrap();
+        assert!(matches!(read_ruleset_file(&bad_regex), Err(CoreError::Config(_))));
+
+        let wrong_format = dir.path().join("other.json");
+        fs::write(&wrong_format, r#"{"format":"something-else","name":"x"}"#).unwrap();
+        assert!(matches!(read_ruleset_file(&wrong_format), Err(CoreError::Config(_))));
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use crate::error::CoreError;
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+use crate::core::rpgmv::warnings::{warn, WarningKind};
+
+/// First line of every dump file.
+pub const DUMP_HEADER: &str = "> WOLF TRANS PATCH FILE VERSION";
+const BEGIN_STRING: &str = "> BEGIN STRING";
+const CONTEXT_PREFIX: &str = "> CONTEXT";
+const END_STRING: &str = "> END STRING";
+/// Marker the dumper appends to the contexts of strings without a translation.
+const UNTRANSLATED_MARKER: &str = " < UNTRANSLATED";
+
+const BOM: char = '\u{feff}';
+
+/// True when `content` is a Wolf RPG text dump.
+pub fn is_dump(content: &str) -> bool {
+    content.strip_prefix(BOM).unwrap_or(content).starts_with(DUMP_HEADER)
+}
+
+/// A `> BEGIN STRING` ... `> END STRING` block, as line indexes into the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StringBlock {
+    // Lines of the original string.
+    original: std::ops::Range<usize>,
+    // `> CONTEXT` lines (where the string is used in the game data).
+    contexts: std::ops::Range<usize>,
+    // Lines of the current translation, up to `> END STRING`.
+    translation: std::ops::Range<usize>,
+}
+
+fn parse_blocks(lines: &[&str]) -> Result<Vec<StringBlock>, String> {
+    let mut blocks = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        if lines[index] != BEGIN_STRING {
+            index += 1;
+            continue;
+        }
+        let begin = index;
+        let original_start = index + 1;
+        let mut cursor = original_start;
+        while cursor < lines.len() && !lines[cursor].starts_with(CONTEXT_PREFIX) && lines[cursor] != END_STRING {
+            cursor += 1;
+        }
+        let contexts_start = cursor;
+        while cursor < lines.len() && lines[cursor].starts_with(CONTEXT_PREFIX) {
+            cursor += 1;
+        }
+        let translation_start = cursor;
+        while cursor < lines.len() && lines[cursor] != END_STRING {
+            cursor += 1;
+        }
+        if cursor == lines.len() || contexts_start == translation_start {
+            return Err(format!("String block at line {} has no context or is not closed", begin + 1));
+        }
+        blocks.push(StringBlock {
+            original: original_start..contexts_start,
+            contexts: contexts_start..translation_start,
+            translation: translation_start..cursor,
+        });
+        index = cursor + 1;
+    }
+    Ok(blocks)
+}
+
+fn split_lines(content: &str) -> Vec<&str> {
+    content.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)).collect()
+}
+
+/// Block index from a dump json_path, e.g. "[12]".
+fn block_index(json_path: &str) -> Option<usize> {
+    json_path.strip_prefix('[')?.strip_suffix(']')?.parse().ok()
+}
+
+/// Extracts the original text of every string block. The json_path is the zero-based block
+/// index, e.g. "[3]", and the object id the block number.
+pub fn extract_strings(file_content: &str, source_file: &str) -> Result<Vec<SourceStringData>, String> {
+    let content = file_content.strip_prefix(BOM).unwrap_or(file_content);
+    let lines = split_lines(content);
+    Ok(parse_blocks(&lines)?
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, block)| {
+            let text = lines[block.original].join("\n");
+            (!text.trim().is_empty()).then(|| SourceStringData {
+                object_id: index as u32 + 1,
+                original_text: text,
+                source_file: source_file.to_string(),
+                json_path: format!("[{}]", index),
+                dialogue_block_id: None,
+                speaker: None,
+            })
+        })
+        .collect())
+}
+
+/// Writes translations into their blocks and drops the untranslated marker from their contexts.
+/// Everything else, including line endings and blocks without a usable translation, is kept.
+pub fn reconstruct_dump(original_content: &str, translations: Vec<&WorkingTranslation>) -> Result<String, CoreError> {
+    let (bom, content) = match original_content.strip_prefix(BOM) {
+        Some(rest) => (BOM.to_string(), rest),
+        None => (String::new(), original_content),
+    };
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines = split_lines(content);
+    let blocks = parse_blocks(&lines).map_err(CoreError::Custom)?;
+
+    let mut by_block: HashMap<usize, &str> = HashMap::new();
+    for entry in translations {
+        let Some(index) = block_index(&entry.json_path).filter(|index| *index < blocks.len()) else {
+            warn(WarningKind::SkippedPath, Some(&entry.json_path), format!("Skipping translation with invalid block path {} in {}.", entry.json_path, entry.source_file));
+            continue;
+        };
+        if entry.error.is_none() && !entry.translated_text.is_empty() {
+            by_block.insert(index, entry.translated_text.as_str());
+        }
+    }
+
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+    let mut line = 0;
+    for (index, block) in blocks.iter().enumerate() {
+        let Some(translated) = by_block.get(&index) else { continue };
+        output.extend(lines[line..block.contexts.start].iter().map(|l| l.to_string()));
+        output.extend(lines[block.contexts.clone()].iter().map(|context| {
+            context.strip_suffix(UNTRANSLATED_MARKER).unwrap_or(context).to_string()
+        }));
+        output.extend(translated.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l).to_string()));
+        line = block.translation.end;
+    }
+    output.extend(lines[line..].iter().map(|l| l.to_string()));
+    Ok(format!("{}{}", bom, output.join(newline)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::translation::TranslationStatus;
+
+    const DUMP: &str = "> WOLF TRANS PATCH FILE VERSION 1.0\r\n\
+> BEGIN STRING\r\n\
+こんにちは\r\n\
+旅の方\r\n\
+> CONTEXT: MPS:Map001/events/0/pages/1/6/Message < UNTRANSLATED\r\n\
+> CONTEXT: MPS:Map001/events/2/pages/1/3/Message < UNTRANSLATED\r\n\
+\r\n\
+> END STRING\r\n\
+\r\n\
+> BEGIN STRING\r\n\
+薬草\r\n\
+> CONTEXT: DB:DataBase/[アイテム]/0/アイテム名\r\n\
+Herb\r\n\
+> END STRING\r\n";
+
+    fn translation(json_path: &str, translated: &str) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: 1,
+            original_text: String::new(),
+            translated_text: translated.to_string(),
+            source_file: "patch/dump/mps/Map001.txt".to_string(),
+            json_path: json_path.to_string(),
+            translation_source: "ollama".to_string(),
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        }
+    }
+
+    #[test]
+    fn test_extract_dump_strings() {
+        assert!(is_dump(DUMP));
+        assert!(!is_dump("Credits\nStaff"));
+        let strings = extract_strings(DUMP, "patch/dump/mps/Map001.txt").unwrap();
+        let found: Vec<(&str, &str)> = strings.iter().map(|s| (s.json_path.as_str(), s.original_text.as_str())).collect();
+        assert_eq!(found, vec![("[0]", "こんにちは\n旅の方"), ("[1]", "薬草")]);
+        assert!(extract_strings("> WOLF TRANS PATCH FILE VERSION 1.0\n> BEGIN STRING\nあ\n", "a.txt").is_err());
+    }
+
+    #[test]
+    fn test_reconstruct_dump() {
+        let hello = translation("[0]", "Hello,\ntraveller");
+        let failed = WorkingTranslation { error: Some("timeout".to_string()), ..translation("[1]", "") };
+        let rebuilt = reconstruct_dump(DUMP, vec![&hello, &failed]).unwrap();
+        assert_eq!(
+            rebuilt,
+            "> WOLF TRANS PATCH FILE VERSION 1.0\r\n> BEGIN STRING\r\nこんにちは\r\n旅の方\r\n\
+> CONTEXT: MPS:Map001/events/0/pages/1/6/Message\r\n> CONTEXT: MPS:Map001/events/2/pages/1/3/Message\r\n\
+Hello,\r\ntraveller\r\n> END STRING\r\n\r\n> BEGIN STRING\r\n薬草\r\n\
+> CONTEXT: DB:DataBase/[アイテム]/0/アイテム名\r\nHerb\r\n> END STRING\r\n"
+        );
+        assert_eq!(reconstruct_dump(DUMP, Vec::new()).unwrap(), DUMP);
+    }
+}
@@ -0,0 +1,4 @@
+// Wolf RPG Editor games. Their data (.project, CommonEvent.dat, Map*.mps) is binary, so the
+// app works on the text dumps a decrypter/dumper writes next to the game (WolfTrans patch format).
+pub mod dump;
+pub mod project;
@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::core::config::IgnoredFiles;
+use crate::core::rpgmv::backups::{is_backup_dir_name, is_backup_file_name};
+use crate::core::rpgmv::filter::CompiledExtractionFilter;
+use crate::core::rpgmv::project::extract_listed_files;
+use crate::models::translation::SourceStringData;
+use crate::utils::project_paths::APP_DATA_DIR_NAME;
+use super::dump;
+
+/// True for a Wolf RPG Editor game: packed (`Data.wolf`) or unpacked (`Data/BasicData`).
+pub fn is_wolf_rpg_project(project_path: &str) -> bool {
+    let path = Path::new(project_path);
+    path.join("Data.wolf").is_file()
+        || path.join("Data").join("BasicData").is_dir()
+        || path.join("data").join("BasicData").is_dir()
+}
+
+/// Relative paths (with `/`) of the text dumps found anywhere in the project.
+pub fn dump_files(project_path: &str) -> Vec<String> {
+    let mut files: Vec<String> = WalkDir::new(project_path)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or("");
+            e.depth() == 0
+                || !(name == APP_DATA_DIR_NAME
+                    || (e.file_type().is_dir() && is_backup_dir_name(name))
+                    || (e.file_type().is_file() && is_backup_file_name(name)))
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "txt"))
+        .filter(|e| fs::read_to_string(e.path()).is_ok_and(|content| dump::is_dump(&content)))
+        .filter_map(|e| Some(e.path().strip_prefix(project_path).ok()?.to_str()?.replace('\\', "/")))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Extracts the strings of every text dump in a Wolf RPG project. `should_stop` is checked
+/// before each file, as in the RPG Maker extraction.
+pub fn extract_translatable_strings(
+    project_path: &str,
+    ignored: &IgnoredFiles,
+    filter: &CompiledExtractionFilter,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<Vec<SourceStringData>, String> {
+    let files = dump_files(project_path);
+    if files.is_empty() {
+        return Err(format!(
+            "No Wolf RPG text dumps found in {}. Decrypt the game and dump its text first.",
+            project_path
+        ));
+    }
+    extract_listed_files(project_path, files, dump::extract_strings, ignored, filter, should_stop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::extraction::ExtractionFilter;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_wolf_rpg_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("Data/BasicData")).unwrap();
+        fs::create_dir_all(root.join("patch/dump/mps")).unwrap();
+        fs::create_dir_all(root.join("patch_backup")).unwrap();
+        let dump = "> WOLF TRANS PATCH FILE VERSION 1.0\n> BEGIN STRING\nこんにちは\n> CONTEXT: MPS:Map001/events/0/pages/1/6/Message < UNTRANSLATED\n\n> END STRING\n";
+        fs::write(root.join("patch/dump/mps/Map001.txt"), dump).unwrap();
+        fs::write(root.join("patch_backup/Map001.txt"), dump).unwrap();
+        fs::write(root.join("readme.txt"), "Thanks for playing").unwrap();
+
+        let project_path = root.to_str().unwrap();
+        assert!(is_wolf_rpg_project(project_path));
+        assert_eq!(dump_files(project_path), vec!["patch/dump/mps/Map001.txt"]);
+
+        let filter = ExtractionFilter::default();
+        let compiled = CompiledExtractionFilter::new(&filter).unwrap();
+        let ignored = IgnoredFiles::new(&[]).unwrap();
+        let strings = extract_translatable_strings(project_path, &ignored, &compiled, &|| false).unwrap();
+        assert_eq!(strings.len(), 1);
+        assert_eq!(strings[0].source_file, "patch/dump/mps/Map001.txt");
+        assert_eq!(strings[0].original_text, "こんにちは");
+    }
+}
@@ -48,4 +48,123 @@ impl From<zip::result::ZipError> for CoreError {
     fn from(err: zip::result::ZipError) -> Self {
         CoreError::Zip(err.to_string())
     }
-} 
\ No newline at end of file
+} 
+
+// Allow converting from rusqlite::Error for convenience
+impl From<rusqlite::Error> for CoreError {
+    fn from(err: rusqlite::Error) -> Self {
+        CoreError::Database(err.to_string())
+    }
+}
+
+/// What went wrong, so the frontend can choose how to present an error.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    JsonParse,
+    JsonSerialize,
+    Io,
+    Zip,
+    Unimplemented,
+    Config,
+    Database,
+    // A project, session or file the command needs doesn't exist.
+    NotFound,
+    // The translation provider failed or is unreachable.
+    Provider,
+    // Packaging was refused because the translation fails the project's quality gates.
+    QualityGate,
+    Other,
+}
+
+/// Error returned by every Tauri command, serialized as
+/// `{ "kind": "config", "message": "...", "file": "www/data/Map001.json", "path": "events[1].name" }`.
+/// `file` and `path` are set when the command knows which file or JSON path failed.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError { kind, message: message.into(), file: None, path: None }
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        match (&self.file, &self.path) {
+            (Some(file), Some(path)) => write!(f, " ({} at {})", file, path),
+            (Some(file), None) => write!(f, " ({})", file),
+            (None, Some(path)) => write!(f, " (at {})", path),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl From<CoreError> for AppError {
+    fn from(err: CoreError) -> Self {
+        let kind = match &err {
+            CoreError::JsonParse(_) => ErrorKind::JsonParse,
+            CoreError::JsonSerialize(_) => ErrorKind::JsonSerialize,
+            CoreError::Io(_) => ErrorKind::Io,
+            CoreError::Zip(_) => ErrorKind::Zip,
+            CoreError::Unimplemented(_) => ErrorKind::Unimplemented,
+            CoreError::Config(_) => ErrorKind::Config,
+            CoreError::Database(_) => ErrorKind::Database,
+            CoreError::Custom(_) => ErrorKind::Other,
+        };
+        AppError::new(kind, err.to_string())
+    }
+}
+
+// Parsers and services that still report plain strings.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::new(ErrorKind::Other, message)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::from(CoreError::from(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_error_serialization() {
+        let error = AppError::from(CoreError::Config("Invalid selector".to_string())).with_file("translate-ai.json");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "config",
+                "message": "Configuration Error: Invalid selector",
+                "file": "translate-ai.json"
+            })
+        );
+        assert_eq!(error.to_string(), "Configuration Error: Invalid selector (translate-ai.json)");
+        let provider = AppError::new(ErrorKind::Provider, "Ollama is not running").with_path("[1].name");
+        assert_eq!(serde_json::to_value(&provider).unwrap()["kind"], "provider");
+    }
+}
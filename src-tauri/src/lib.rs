@@ -5,12 +5,16 @@ pub mod core;     // Declare the new core module
 pub mod models;
 pub mod error;
 pub mod utils;
+#[cfg(feature = "cli")]
+pub mod cli; // Headless `translate-ai` tool, see src/bin/translate-ai.rs
+
+use tauri::Manager;
 
 #[cfg(test)]
 mod tests; // Added for integration tests
 
 #[tauri::command]
-async fn simple_ipc_test(text: String, source_lang: String, target_lang: String) -> Result<String, String> {
+async fn simple_ipc_test(text: String, source_lang: String, target_lang: String) -> Result<String, error::AppError> {
     // Log received data to the backend console (requires tauri-plugin-log or just println! for basic cases)
     // For tauri-plugin-log, you'd use: log::info!(...);
     println!(
@@ -36,7 +40,20 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
+        .manage(services::job_manager::JobManager::default())
+        .manage(services::provider_warmup::ProviderWarmup::default())
+        .manage(services::temp_workspace::TempWorkspaces::default())
+        .manage(services::project_watcher::ProjectWatchers::default())
+        .manage(services::search_indexes::SearchIndexes::default())
         .setup(|app| {
+            // Workspaces left by a crashed or killed run, and packaged output that was never saved.
+            match app.state::<services::temp_workspace::TempWorkspaces>().cleanup_orphans() {
+                Ok(report) if report.removed > 0 => {
+                    println!("Removed {} orphaned temp workspace(s), {} bytes.", report.removed, report.bytes_freed)
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to clean up temp workspaces: {}", e),
+            }
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -52,10 +69,109 @@ pub fn run() {
             commands::project::select_project_folder_command,
             commands::project::detect_rpg_maker_mv_project_command,
             commands::project::extract_project_strings_command,
+            commands::project::check_project_schema_command,
+            commands::project::analyze_event_codes_command,
+            commands::project::generate_sample_project_command,
+            commands::project::compare_data_backups_command,
+            commands::project::get_project_config_command,
+            commands::project::save_project_config_command,
+            commands::project::get_note_tag_config_command,
+            commands::project::save_note_tag_config_command,
             commands::translation::batch_translate_strings_command,
+            commands::translation::preview_quality_command,
+            commands::translation::resume_translation_session_command,
+            commands::translation::abort_streamed_translation_command,
+            commands::translation::detect_target_language_strings_command,
+            commands::translation::find_duplicate_strings_command,
+            commands::translation::batch_translate_multi_target_command,
+            commands::translation::list_translation_sets_command,
+            commands::translation::get_translation_set_command,
             commands::project::reconstruct_translated_project_files,
+            commands::project::reconstruct_multi_target_command,
+            commands::project::generate_language_pack_command,
+            commands::queue::process_project_queue_command,
+            commands::watch::watch_project_command,
+            commands::watch::unwatch_project_command,
+            commands::watch::get_watched_projects_command,
+            commands::search::search_strings_command,
+            commands::strings::get_string_page_command,
+            commands::strings::get_store_counts_command,
+            commands::project::write_translations_in_place_command,
+            commands::project::list_backups_command,
+            commands::project::restore_backup_command,
+            commands::project::verify_reconstruction_command,
+            commands::project::preview_reconstruction_command,
+            commands::project::get_reconstruction_warnings_command,
+            commands::project::generate_renpy_translations_command,
+            commands::project::get_value_at_path_command,
             commands::project::save_zip_archive_command,
-            commands::project::open_folder_command
+            commands::project::get_archive_formats_command,
+            commands::project::open_folder_command,
+            commands::validation::get_project_validation_ruleset_command,
+            commands::validation::save_project_validation_ruleset_command,
+            commands::validation::export_validation_ruleset_command,
+            commands::validation::import_validation_ruleset_command,
+            commands::validation::validate_translations_command,
+            commands::validation::get_file_coverage_command,
+            commands::validation::check_character_coverage_command,
+            commands::validation::generate_qa_report_command,
+            commands::session::get_session_lock_state_command,
+            commands::session::lock_session_command,
+            commands::session::unlock_session_command,
+            commands::session::recover_session_command,
+            commands::session::get_translation_session_command,
+            commands::session::check_session_migration_command,
+            commands::session::migrate_session_command,
+            commands::session::save_project_command,
+            commands::session::load_project_command,
+            commands::glossary::get_project_glossary_command,
+            commands::glossary::save_project_glossary_command,
+            commands::glossary::add_glossary_terms_command,
+            commands::glossary::import_glossary_command,
+            commands::glossary::export_glossary_command,
+            commands::glossary::apply_glossary_command,
+            commands::glossary::preview_glossary_cache_impact_command,
+            commands::references::get_entry_references_command,
+            commands::references::set_entry_references_command,
+            commands::characters::get_character_dashboard_command,
+            commands::characters::save_character_profile_command,
+            commands::characters::find_catchphrases_command,
+            commands::characters::translate_catchphrases_command,
+            commands::characters::save_catchphrase_command,
+            commands::jobs::cancel_job_command,
+            commands::jobs::get_running_jobs_command,
+            commands::providers::start_provider_warmup_command,
+            commands::providers::stop_provider_warmup_command,
+            commands::providers::get_provider_warmup_status_command,
+            commands::providers::check_provider_connection_command,
+            commands::providers::estimate_translation_cost_command,
+            commands::providers::create_deepl_glossary_command,
+            commands::exchange::export_xliff_command,
+            commands::exchange::export_po_command,
+            commands::exchange::import_translations_command,
+            commands::exchange::export_tmx_command,
+            commands::exchange::import_tmx_command,
+            commands::exchange::export_patch_command,
+            commands::exchange::apply_patch_command,
+            commands::exchange::export_review_site_command,
+            commands::exchange::import_review_comments_command,
+            commands::exchange::get_review_comments_command,
+            commands::bulk::bulk_operation_command,
+            commands::bulk::set_review_status_command,
+            commands::bulk::update_translation_entry_command,
+            commands::bulk::bulk_find_replace_command,
+            commands::workspaces::get_temp_usage_command,
+            commands::workspaces::cleanup_temp_workspaces_command,
+            commands::presets::get_translation_presets_command,
+            commands::presets::get_project_preset_command,
+            commands::prompts::list_prompt_templates_command,
+            commands::prompts::get_prompt_template_command,
+            commands::prompts::save_prompt_template_command,
+            commands::prompts::delete_prompt_template_command,
+            commands::prompts::summarize_scope_command,
+            commands::prompts::list_scope_summaries_command,
+            commands::prompts::save_scope_summary_command,
+            commands::prompts::delete_scope_summary_command
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// What a saved ZIP holds besides the translated files.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ZipContent {
+    // Only the files the translation changed.
+    #[default]
+    ModifiedOnly,
+    // Every file of the data folder, translated or not.
+    WholeData,
+    // A full playable copy of the project, with the translated files in place.
+    FullCopy,
+}
+
+/// File format of a saved archive.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveFormat {
+    #[default]
+    Zip,
+    TarGz,
+    // Written with the 7-Zip command line tool, which must be installed.
+    SevenZip,
+}
+
+impl ArchiveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::SevenZip => "7z",
+        }
+    }
+}
+
+/// Options of `save_zip_archive_command`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipSaveOptions {
+    #[serde(default)]
+    pub content: ZipContent,
+    // Project the untranslated files are copied from; required unless only modified files are saved.
+    #[serde(default)]
+    pub project_path: Option<String>,
+    // Compression level, 0 (fastest) to 9 (smallest). Defaults to the format's default.
+    #[serde(default)]
+    pub compression_level: Option<i64>,
+    #[serde(default)]
+    pub format: ArchiveFormat,
+}
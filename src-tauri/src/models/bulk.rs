@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::core::quality::SampleCategory;
+use crate::models::translation::{TranslationStatus, WorkingTranslation};
+
+/// State of an entry, derived from its translation and error.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EntryStatus {
+    Untranslated,
+    Translated,
+    // Has an error; picked up again by `resume_translation_session_command`.
+    Failed,
+}
+
+/// Selects the entries a bulk operation applies to. Empty lists and missing patterns match
+/// everything; all given criteria must match.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryFilter {
+    #[serde(default)]
+    pub source_files: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<SampleCategory>,
+    #[serde(default)]
+    pub statuses: Vec<EntryStatus>,
+    #[serde(default)]
+    pub review_statuses: Vec<TranslationStatus>,
+    // Regexes searched in the original text and in the translation.
+    #[serde(default)]
+    pub original_pattern: Option<String>,
+    #[serde(default)]
+    pub translation_pattern: Option<String>,
+}
+
+/// Sent by the frontend as e.g. `{ "kind": "regexReplace", "pattern": "\\s+$", "replacement": "" }`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PostProcessRule {
+    TrimWhitespace,
+    // Uses the project glossary, like `apply_glossary_command`.
+    ApplyGlossary,
+    // Escapes or replaces characters that would break the engine's parser (see `core::escaping`).
+    EscapeEngineCharacters,
+    RegexReplace { pattern: String, replacement: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BulkOperation {
+    SetStatus { status: EntryStatus },
+    // Approving, rejecting or marking as edited needs a usable translation; marking as
+    // untranslated clears it.
+    SetReviewStatus { status: TranslationStatus },
+    ClearTranslation,
+    AppendSuffix { suffix: String },
+    PostProcess { rule: PostProcessRule },
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkSummary {
+    pub matched: usize,
+    pub changed: usize,
+    // Changed entries per source file.
+    pub changed_by_file: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkResult {
+    pub translations: Vec<WorkingTranslation>,
+    pub summary: BulkSummary,
+}
+
+/// A hand edit of one entry, found by its source file and JSON path.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryUpdate {
+    pub source_file: String,
+    pub json_path: String,
+    // Empty marks the entry as untranslated.
+    pub translated_text: String,
+    // Review status set after the edit; a changed text is marked as edited when absent.
+    #[serde(default)]
+    pub status: Option<TranslationStatus>,
+}
+
+/// Regex find/replace across the translations of the entries matching `filter`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceRequest {
+    pub pattern: String,
+    // May refer to capture groups ("$1").
+    pub replacement: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub filter: EntryFilter,
+    // Only returns the changes it would make.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceChange {
+    pub source_file: String,
+    pub json_path: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceResult {
+    pub changes: Vec<FindReplaceChange>,
+    pub summary: BulkSummary,
+    // False for a dry run.
+    pub applied: bool,
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/// One message line attributed to a character.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterLine {
+    pub source_file: String,
+    pub json_path: String,
+    pub text: String,
+}
+
+/// Lines a character speaks in one file. `map_name` is set for map files (from MapInfos.json).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterAppearance {
+    pub source_file: String,
+    pub map_name: Option<String>,
+    pub line_count: usize,
+}
+
+/// User-maintained notes on how a character talks, kept in `.translate-ai/characters.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterProfile {
+    pub key: String,
+    #[serde(default)]
+    pub style: String,
+}
+
+/// How a character talks, set in the project config. Sent with every line the character speaks
+/// so their voice stays the same from one message box to the next.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterSheet {
+    // Original name, as shown in the name box or the actor's name; matched against the speaker.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+    // E.g. "Brash, short sentences, calls everyone by their family name".
+    #[serde(default)]
+    pub speech_style: String,
+    // How the character refers to themself, e.g. "俺 (rough, masculine)".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_person: Option<String>,
+    // How others refer to the character in the translation, e.g. "she/her".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub third_person: Option<String>,
+}
+
+/// A sample line with its current translation, if any.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterSampleLine {
+    pub source_file: String,
+    pub json_path: String,
+    pub text: String,
+    pub translation: Option<String>,
+}
+
+/// Everything the characters tab shows for one speaker.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterSummary {
+    // Stable identifier: "actor:<id>", "name:<name box text>" or "face:<faceName>#<faceIndex>".
+    pub key: String,
+    pub name: String,
+    pub actor_id: Option<u32>,
+    pub line_count: usize,
+    pub appearances: Vec<CharacterAppearance>,
+    pub name_translation: Option<String>,
+    pub style_profile: Option<String>,
+    pub sample_lines: Vec<CharacterSampleLine>,
+}
+
+/// A line one speaker says word for word in several places (a catchphrase, a battle cry). It is
+/// translated once and, when locked, every occurrence gets that translation unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Catchphrase {
+    // Speaker name as extracted (the name box text or the actor's original name).
+    pub speaker: String,
+    pub text: String,
+    // Times it occurs in the project, as of the last detection.
+    #[serde(default)]
+    pub occurrences: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+    #[serde(default)]
+    pub locked: bool,
+}
+
+/// The project's catchphrases, kept in `.translate-ai/catchphrases.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Catchphrases {
+    #[serde(default)]
+    pub catchphrases: Vec<Catchphrase>,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// At-a-glance state of a file, from its translated share and validation pass rate.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CoverageBadge {
+    // Nothing translated yet.
+    Untranslated,
+    // Under 80% translated, or under 90% of the translations pass validation.
+    NeedsWork,
+    Good,
+    // Everything translated and passing validation.
+    Complete,
+}
+
+/// Coverage and quality of one source file, as shown in QA reports and project stats.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCoverage {
+    pub source_file: String,
+    pub entries: usize,
+    pub translated: usize,
+    // Entries with at least one reviewer comment.
+    pub reviewed: usize,
+    // Translated entries without validation errors (warnings don't count against it).
+    pub validation_passed: usize,
+    pub percent_translated: f64,
+    pub percent_reviewed: f64,
+    // Share of the translated entries passing validation; `None` when nothing is translated.
+    pub validation_pass_rate: Option<f64>,
+    pub badge: CoverageBadge,
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use crate::models::translation::WorkingTranslation;
+
+/// One translated row read from an XLIFF unit or a CSV line.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedTranslation {
+    pub source_file: String,
+    pub json_path: String,
+    pub original_text: String,
+    pub translated_text: String,
+}
+
+/// A row that could not be merged, with the reason shown to the user.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedImport {
+    pub row: ImportedTranslation,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub rows: usize,
+    // Entries whose translation was replaced by the imported one.
+    pub updated: usize,
+    // Rows identical to the current translation.
+    pub unchanged: usize,
+    // Rows without a translation (e.g. XLIFF units still in the "initial" state).
+    pub empty: usize,
+    pub unmatched: Vec<UnmatchedImport>,
+}
+
+/// Result of `import_translations_command`: the merged working set and what happened to each row.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationImport {
+    pub translations: Vec<WorkingTranslation>,
+    pub report: ImportReport,
+}
+
+/// Result of importing a TMX file into the project translation memory.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TmxImportReport {
+    // Translation units in the file.
+    pub units: usize,
+    pub imported: usize,
+    // Pairs repeated within the file; the first occurrence is kept.
+    pub duplicates: usize,
+    // Pairs already in the memory with the same translation.
+    pub unchanged: usize,
+    // Pairs already in the memory with another translation, kept because overwrite was off.
+    pub kept_existing: usize,
+    // Units without a source and at least one target segment.
+    pub skipped: usize,
+}
+
+/// A reviewer's comment on one entry, as written by the HTML review site.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewComment {
+    pub source_file: String,
+    pub json_path: String,
+    pub comment: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewer: Option<String>,
+}
+
+/// Result of importing a comments file sent back by a reviewer.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewCommentImport {
+    pub rows: usize,
+    // New comments, or comments replacing the same reviewer's earlier one on the entry.
+    pub imported: usize,
+    // Blank comments, which clear the reviewer's earlier comment.
+    pub cleared: usize,
+    // Comments on entries the session doesn't have.
+    pub unmatched: Vec<ReviewComment>,
+}
+
+/// One line of a translation patch (`.jsonl`): the translation of the string at `path` in
+/// `file`, valid only while that string still hashes to `original_hash`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PatchLine {
+    // Relative to the project root, e.g. "www/data/Map001.json".
+    pub file: String,
+    // JSON path of the string, as produced by extraction.
+    pub path: String,
+    // Lowercase hex MD5 of the original text's UTF-8 bytes.
+    pub original_hash: String,
+    pub translation: String,
+}
+
+/// A patch line that could not be applied, with the reason shown to the user.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedPatchLine {
+    pub line: PatchLine,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchMatchReport {
+    pub lines: usize,
+    pub matched: usize,
+    pub unmatched: Vec<UnmatchedPatchLine>,
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use crate::models::translation::SourceStringData;
+
+/// Groups of project files an extraction can be limited to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileKind {
+    // MapXXX.json
+    Maps,
+    MapInfos,
+    // Actors, Classes, Skills, Items, Weapons, Armors, Enemies, States.
+    Database,
+    CommonEvents,
+    Troops,
+    System,
+    // plugins.js and other plugin scripts.
+    Plugins,
+    // Anything else, e.g. text assets extracted through a parser override.
+    Other,
+}
+
+/// Limits an extraction so a project can be translated in stages. Empty include lists mean
+/// everything; excludes win over includes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionFilter {
+    // File globs, as in the project config's `ignoredFiles`.
+    #[serde(default)]
+    pub include_files: Vec<String>,
+    #[serde(default)]
+    pub exclude_files: Vec<String>,
+    #[serde(default)]
+    pub include_kinds: Vec<FileKind>,
+    #[serde(default)]
+    pub exclude_kinds: Vec<FileKind>,
+    // Object fields left out, e.g. "note" or "nickname".
+    #[serde(default)]
+    pub exclude_fields: Vec<String>,
+}
+
+/// Part of a streamed extraction's entries, in extraction order. The last chunk has
+/// `index == chunk_count - 1`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionChunk {
+    // The extraction's `job_id`, so listeners can tell concurrent extractions apart.
+    pub job_id: Option<String>,
+    pub index: usize,
+    pub chunk_count: usize,
+    pub entries: Vec<SourceStringData>,
+}
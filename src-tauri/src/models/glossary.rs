@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use crate::models::reference::ReferenceLink;
+
+/// A fixed translation for a term (character name, item name, place, ...).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryTerm {
+    pub source: String,
+    pub target: String,
+    // Optional lore context (wiki pages, notes) shown to prompt-based providers and in exports.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<ReferenceLink>,
+}
+
+/// Cached provider responses a glossary edit would invalidate.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheImpact {
+    // Source terms added, removed or given another translation or references.
+    pub changed_terms: Vec<String>,
+    pub affected_entries: usize,
+    // Cached responses tied to a prompt context; imported and reviewed translations never expire.
+    pub cached_entries: usize,
+    // Original texts of the first affected entries.
+    pub examples: Vec<String>,
+}
+
+/// The project's termbase, stored in `.translate-ai/glossary.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Glossary {
+    #[serde(default)]
+    pub terms: Vec<GlossaryTerm>,
+}
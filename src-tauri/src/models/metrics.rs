@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// Time one entry spent in each stage of the batch pipeline, in milliseconds. Stages the
+/// entry did not go through (e.g. the provider for a memory hit) are `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryTimings {
+    pub source_file: String,
+    pub json_path: String,
+    // From the start of the batch until the entry left the queue.
+    pub queue_wait_ms: f64,
+    pub memory_lookup_ms: Option<f64>,
+    pub prompt_build_ms: Option<f64>,
+    // Includes waiting for a rate limit slot.
+    pub provider_ms: Option<f64>,
+    // Glossary enforcement on the provider output.
+    pub post_process_ms: Option<f64>,
+    pub from_memory: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StageStats {
+    pub count: usize,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+impl StageStats {
+    pub fn add(&mut self, ms: f64) {
+        self.count += 1;
+        self.total_ms += ms;
+        self.mean_ms = self.total_ms / self.count as f64;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    fn add_optional(&mut self, ms: Option<f64>) {
+        if let Some(ms) = ms {
+            self.add(ms);
+        }
+    }
+}
+
+/// Per-stage totals for one batch run, sent to the frontend when the batch ends.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineMetrics {
+    pub entries: usize,
+    pub memory_hits: usize,
+    pub wall_ms: f64,
+    pub queue_wait: StageStats,
+    pub memory_lookup: StageStats,
+    pub prompt_build: StageStats,
+    pub provider: StageStats,
+    pub post_process: StageStats,
+    // Validation of the final results, per entry.
+    pub validation: StageStats,
+    // Adaptive quality second pass, as a whole.
+    pub escalation_ms: Option<f64>,
+}
+
+impl PipelineMetrics {
+    pub fn record(&mut self, timings: &EntryTimings) {
+        self.entries += 1;
+        if timings.from_memory {
+            self.memory_hits += 1;
+        }
+        self.queue_wait.add(timings.queue_wait_ms);
+        self.memory_lookup.add_optional(timings.memory_lookup_ms);
+        self.prompt_build.add_optional(timings.prompt_build_ms);
+        self.provider.add_optional(timings.provider_ms);
+        self.post_process.add_optional(timings.post_process_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_skips_missing_stages() {
+        let mut metrics = PipelineMetrics::default();
+        metrics.record(&EntryTimings { queue_wait_ms: 1.0, provider_ms: Some(300.0), ..Default::default() });
+        metrics.record(&EntryTimings { queue_wait_ms: 3.0, provider_ms: Some(100.0), ..Default::default() });
+        metrics.record(&EntryTimings { queue_wait_ms: 5.0, memory_lookup_ms: Some(0.5), from_memory: true, ..Default::default() });
+
+        assert_eq!((metrics.entries, metrics.memory_hits), (3, 1));
+        assert_eq!(metrics.queue_wait.mean_ms, 3.0);
+        assert_eq!(
+            metrics.provider,
+            StageStats { count: 2, total_ms: 400.0, mean_ms: 200.0, max_ms: 300.0 }
+        );
+        assert_eq!(metrics.memory_lookup.count, 1);
+        assert_eq!(metrics.prompt_build.count, 0);
+    }
+}
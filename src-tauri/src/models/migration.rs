@@ -0,0 +1,32 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+/// A value of an old file that could not be carried over to the current format.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmigratedField {
+    // Location in the old file, e.g. "translations[3].engine".
+    pub path: String,
+    pub reason: String,
+}
+
+/// What upgrading a saved file to the current format changed, or would change (dry run).
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    // "original_text -> originalText" -> number of values renamed.
+    pub renamed_fields: BTreeMap<String, usize>,
+    // Fields missing from the old file that were given a default value, with counts.
+    pub defaulted_fields: BTreeMap<String, usize>,
+    pub unmigrated: Vec<UnmigratedField>,
+    // Entries left out because a required field (file, path or text) was missing.
+    pub dropped_entries: usize,
+}
+
+impl MigrationReport {
+    pub fn is_needed(&self) -> bool {
+        self.from_version != self.to_version
+    }
+}
@@ -1,3 +1,26 @@
 pub mod translation;
+pub mod provider;
+pub mod validation;
+pub mod glossary;
+pub mod reference;
+pub mod character;
+pub mod session;
+pub mod exchange;
+pub mod bulk;
+pub mod metrics;
+pub mod note_tag;
+pub mod project_config;
+pub mod extraction;
+pub mod migration;
+pub mod coverage;
+pub mod preset;
+pub mod prompt;
+pub mod project_document;
+pub mod qa_report;
+pub mod archive;
+pub mod queue;
+pub mod watch;
+pub mod search;
+pub mod string_store;
 // pub mod app_settings; // Example of other models that might be added
 // pub mod project_data; // Example 
\ No newline at end of file
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Which note tags hold player-facing text, stored in `.translate-ai/note_tags.json`.
+/// With no patterns, notes are extracted whole.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTagConfig {
+    // Regexes matched against the whole tag name, e.g. "拡張説明" or "Help( Description)?".
+    #[serde(default)]
+    pub tag_patterns: Vec<String>,
+}
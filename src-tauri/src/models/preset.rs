@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// How honorifics of the source language are rendered in the translation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HonorificPolicy {
+    // Kept in romanized form: "Harold-san".
+    Keep,
+    // Replaced by the closest form of the target language ("Mr. Harold"), or dropped.
+    Adapt,
+    Drop,
+}
+
+/// Order of the parts of a full name in the translation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NameOrder {
+    // As in the source: family name first for Japanese, Korean and Chinese.
+    #[default]
+    Source,
+    // Given name first: "Seren Kirishima".
+    Western,
+}
+
+/// An honorific of the source language, e.g. さん.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Honorific {
+    pub term: String,
+    // Used by the `keep` policy, e.g. "-san".
+    pub romanized: String,
+    // What it conveys, given to the provider to pick an equivalent.
+    pub meaning: String,
+}
+
+/// Defaults for translating from one source language, whatever the target language.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationPreset {
+    // The source language code ("ja", "ko", "zh").
+    pub id: String,
+    pub name: String,
+    pub honorific_policy: HonorificPolicy,
+    #[serde(default)]
+    pub name_order: NameOrder,
+    pub honorifics: Vec<Honorific>,
+    // Sound effects and onomatopoeia -> default rendering, e.g. "ドキドキ" -> "*thump thump*".
+    pub sfx: BTreeMap<String, String>,
+    // Source punctuation -> replacement, e.g. "。" -> ".".
+    pub punctuation: BTreeMap<String, String>,
+}
+
+/// Preset selection and overrides of a project, in `translate-ai.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetOverrides {
+    // Built-in preset id; defaults to the project's source language. "none" disables presets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub honorific_policy: Option<HonorificPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_order: Option<NameOrder>,
+    // Added to the preset's honorifics, replacing those with the same term.
+    #[serde(default)]
+    pub honorifics: Vec<Honorific>,
+    // Merged over the preset's maps; a `null` value removes the preset's entry.
+    #[serde(default)]
+    pub sfx: BTreeMap<String, Option<String>>,
+    #[serde(default)]
+    pub punctuation: BTreeMap<String, Option<String>>,
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use crate::models::character::CharacterSheet;
+use crate::models::note_tag::NoteTagConfig;
+use crate::models::preset::PresetOverrides;
+use crate::models::provider::ProviderConfig;
+use crate::models::validation::QualityGates;
+
+/// Per-project settings, kept in `translate-ai.json` at the project root so they can be
+/// edited by hand and shared with the game files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_language: Option<String>,
+    // Used by batch commands called without a provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderConfig>,
+    // Glossary file, relative to the project root. Defaults to `.translate-ai/glossary.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glossary_path: Option<String>,
+    // Files left out of extraction: globs (`*`, `?`) matched against the path relative to the
+    // project root, or against the file name when the pattern has no `/`.
+    #[serde(default)]
+    pub ignored_files: Vec<String>,
+    #[serde(default)]
+    pub note_tags: NoteTagConfig,
+    // First matching override wins.
+    #[serde(default)]
+    pub parser_overrides: Vec<ParserOverride>,
+    // Locale suffix of the output, e.g. "en": translated data files are written next to the
+    // originals (Map001_en.json) with a loader plugin, instead of replacing them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_locale: Option<String>,
+    // Modification time given to every file of the output archives, as Unix seconds. Defaults
+    // to 1980-01-01 so that rebuilding unchanged translations gives byte-identical archives.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_timestamp: Option<i64>,
+    // Generic JSON/YAML projects: the string values to translate. First matching rule wins.
+    #[serde(default)]
+    pub generic_rules: Vec<GenericRule>,
+    // Translation preset (honorifics, name order, SFX, punctuation) and its overrides; the
+    // preset of the source language is used when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<PresetOverrides>,
+    // Checked before the translation is packaged or exported as a patch.
+    #[serde(default)]
+    pub quality_gates: QualityGates,
+    // Strings never sent for translation, whatever the engine.
+    #[serde(default)]
+    pub do_not_translate: DoNotTranslate,
+    // String literals of script calls (event commands 355/655) to extract; none by default.
+    #[serde(default)]
+    pub script_calls: ScriptCallConfig,
+    // Re-wraps translated dialogue to the message window when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_wrap: Option<WordWrapSettings>,
+    // Unicode ranges the game's font can draw, e.g. "0020-007E"; the defaults of the source
+    // language when empty.
+    #[serde(default)]
+    pub font_ranges: Vec<String>,
+    // Normalization applied to every translation before reconstruction; off by default.
+    #[serde(default)]
+    pub postprocess: PostprocessSettings,
+    // Voice of the main characters, attached to the dialogue they speak.
+    #[serde(default)]
+    pub characters: Vec<CharacterSheet>,
+}
+
+/// Do-not-translate rules: extracted strings matching any of them are dropped from extraction.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DoNotTranslate {
+    // Texts compared as they are, surrounding whitespace aside.
+    #[serde(default)]
+    pub exact: Vec<String>,
+    // Regexes that must match the whole text, e.g. `EV\d+` or `[\w-]+\.(png|ogg)`.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    // Globs (`*`, `?`) matched against the JSON path, e.g. `events[*].name`.
+    #[serde(default)]
+    pub json_paths: Vec<String>,
+}
+
+/// Which script calls hold player-facing text. With no patterns, script calls aren't extracted.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptCallConfig {
+    // Regexes matched against each script line; the string literals opening inside or right
+    // after a match are extracted, e.g. `ShowText\(` or `\$gameMessage\.add\(`.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// How text width is counted when wrapping dialogue.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WidthUnit {
+    // CJK and full-width characters count two, others one.
+    #[default]
+    Columns,
+    Characters,
+    // UTF-8 bytes, for message plugins that measure lines that way.
+    Bytes,
+}
+
+/// Width of the message window lines (control codes aside) translated dialogue is wrapped to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WordWrapSettings {
+    pub max_width: usize,
+    // Narrower width of messages shown with a face image; `max_width` when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_width_with_face: Option<usize>,
+    #[serde(default)]
+    pub unit: WidthUnit,
+    // Lines a message window shows; longer messages continue in another window.
+    #[serde(default = "default_max_lines")]
+    pub max_lines: usize,
+}
+
+fn default_max_lines() -> usize {
+    4
+}
+
+/// Which quotation marks translations are written with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteStyle {
+    // "..." and '...'.
+    Straight,
+    // “...” and ‘...’, the apostrophe as ’.
+    Curly,
+}
+
+/// How an ellipsis is written.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EllipsisStyle {
+    // "...".
+    ThreeDots,
+    // "…" (U+2026), one character wide.
+    Character,
+}
+
+/// Post-processing of translations before reconstruction, run in the order of the fields.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PostprocessSettings {
+    // Full-width letters, digits, punctuation and spaces ("ＡＢＣ！") to their half-width forms.
+    #[serde(default)]
+    pub full_width_to_half_width: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quotes: Option<QuoteStyle>,
+    // Also covers "。。。" and "・・・" runs left over from the source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ellipsis: Option<EllipsisStyle>,
+    // Spaces and tabs at the end of every line.
+    #[serde(default)]
+    pub trim_trailing_spaces: bool,
+}
+
+/// Parsers a file can be assigned to with a parser override.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileParser {
+    // One entry per non-blank line (credits, ending text, ...).
+    PlainText,
+    // A `$plugins` script like `js/plugins.js`.
+    Plugins,
+}
+
+/// Extracts the files matching `files` (same globs as `ignoredFiles`) with `parser`. Files outside
+/// the data folder are only extracted when an override matches them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParserOverride {
+    pub files: String,
+    pub parser: FileParser,
+}
+
+/// Translates the string values chosen by `selectors` (e.g. `quests[*].title`, `..description`)
+/// in the JSON and YAML files matching `files` (same globs as `ignoredFiles`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GenericRule {
+    pub files: String,
+    pub selectors: Vec<String>,
+}
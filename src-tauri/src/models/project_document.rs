@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use crate::models::translation::TranslationStatus;
+
+/// An extracted string with its translation, if any.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEntry {
+    pub object_id: u32,
+    pub original_text: String,
+    pub source_file: String,
+    pub json_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translated_text: Option<String>,
+    // Untranslated when there is no usable translation.
+    #[serde(default)]
+    pub status: TranslationStatus,
+    // What produced the translation, e.g. "ollama", "deepl", "glossary".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One save of the document, with the counts at that point.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEvent {
+    // Seconds since the Unix epoch.
+    pub at: u64,
+    pub entries: usize,
+    pub translated: usize,
+    pub reviewed: usize,
+}
+
+/// A translation project saved by the backend (`.taiproj`), so work survives restarts
+/// independently of the frontend store.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDocument {
+    pub version: u32,
+    pub project_path: String,
+    pub source_language: String,
+    pub target_language: String,
+    // The provider kind last used ("ollama", "deepl", "openAiCompatible"); never its settings,
+    // which may hold an API key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    pub entries: Vec<ProjectEntry>,
+    // Oldest first.
+    #[serde(default)]
+    pub history: Vec<HistoryEvent>,
+}
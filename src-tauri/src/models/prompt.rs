@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Kind of content a prompt template is written for, derived from where a string was extracted.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptKind {
+    // Message and choice text of event command lists.
+    Dialogue,
+    // Database descriptions and actor profiles.
+    ItemDescription,
+    // Names, nicknames and System terms: short labels shown in menus.
+    SystemTerm,
+    // Values of tags in database notes.
+    NoteTag,
+    // Anything else.
+    General,
+}
+
+/// A project's own prompt templates; kinds without one use the built-in template.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplates {
+    #[serde(default)]
+    pub templates: BTreeMap<PromptKind, String>,
+}
+
+/// The template in effect for one kind, as shown in the prompt editor.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub kind: PromptKind,
+    pub template: String,
+    // False when this is the built-in template.
+    pub customized: bool,
+}
+
+/// A synopsis of a file (a map, a scene) or part of one, given to the provider with every entry
+/// in it so lines are translated knowing what the scene is about.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeSummary {
+    pub source_file: String,
+    // Narrows the scope to the entries under this path, e.g. "events[3]"; the whole file when
+    // absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    pub summary: String,
+    // `TranslationProvider::name` of the provider that wrote it; "manual" when edited by hand.
+    pub provider: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeSummaries {
+    #[serde(default)]
+    pub summaries: Vec<ScopeSummary>,
+}
@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+/// Selects which translation backend handles a batch.
+/// Sent by the frontend as e.g. `{ "kind": "deepl", "apiKey": "..." }`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ProviderConfig {
+    #[default]
+    Ollama,
+    Deepl(DeeplSettings),
+    OpenAiCompatible(OpenAiSettings),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeeplSettings {
+    pub api_key: String,
+    // Id of a glossary created on the DeepL account (see create_deepl_glossary_command).
+    #[serde(default)]
+    pub glossary_id: Option<String>,
+    // Forces the free/pro endpoint. When unset, keys ending in ":fx" use the free endpoint.
+    #[serde(default)]
+    pub use_free_api: Option<bool>,
+    // Fall back to Ollama when DeepL does not support the requested language pair.
+    #[serde(default = "default_true")]
+    pub fallback_to_ollama: bool,
+}
+
+impl DeeplSettings {
+    /// Free-tier keys end with ":fx" and must use the api-free host.
+    pub fn uses_free_api(&self) -> bool {
+        self.use_free_api.unwrap_or_else(|| self.api_key.ends_with(":fx"))
+    }
+}
+
+/// Settings for any endpoint speaking the OpenAI chat completions API
+/// (OpenAI, OpenRouter, LM Studio, vLLM, ...).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAiSettings {
+    // e.g. "https://api.openai.com/v1" or "http://localhost:1234/v1"
+    pub base_url: String,
+    // Local servers usually don't need a key.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    pub model: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    // Replaces the built-in translation instructions when set.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    // Price of `model` for cost estimates, when it isn't one the app knows.
+    #[serde(default)]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// Price of a hosted model, in USD per million tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// What translating a set of entries is expected to take with one provider, shown before a
+/// batch is started.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostEstimate {
+    // "ollama", "deepl" or "openai".
+    pub provider: String,
+    pub model: Option<String>,
+    pub requests: u64,
+    // Source characters, which DeepL bills.
+    pub characters: u64,
+    // Approximate; 0 for DeepL.
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    // USD; `None` when the model's price is unknown.
+    pub estimated_cost_usd: Option<f64>,
+    pub notes: Vec<String>,
+}
+
+/// Adaptive quality mode: after the first (cheap) pass, "hard" lines are re-translated
+/// with `premium_provider`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EscalationSettings {
+    pub premium_provider: ProviderConfig,
+    // Re-translate entries that fail the project's validation ruleset.
+    #[serde(default = "default_true")]
+    pub on_validation_failure: bool,
+    // Re-translate entries the first provider failed on.
+    #[serde(default = "default_true")]
+    pub on_provider_error: bool,
+    // Re-translate entries whose output looks unreliable (unchanged, source script left, odd length).
+    #[serde(default = "default_true")]
+    pub on_low_confidence: bool,
+    // Sources longer than this (in characters) are considered hard. 0 disables the check.
+    #[serde(default = "default_long_source_chars")]
+    pub long_source_chars: usize,
+}
+
+fn default_long_source_chars() -> usize {
+    200
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// State of the background provider warm-up, as shown in the settings panel.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WarmupStatus {
+    pub active: bool,
+    // `TranslationProvider::name` of the provider being kept warm.
+    pub provider: Option<String>,
+    pub interval_secs: u64,
+    pub pings: u64,
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+/// Developer mode simulating provider failures, to exercise error handling, escalation and
+/// session resume without a misbehaving backend. Each rate is the chance (0 to 1) that a request
+/// fails that way; together they can't exceed 1.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultInjectionSettings {
+    #[serde(default)]
+    pub timeout_rate: f64,
+    #[serde(default)]
+    pub rate_limit_rate: f64,
+    // The request "succeeds" with an answer that isn't a clean translation.
+    #[serde(default)]
+    pub malformed_rate: f64,
+    // How long a simulated timeout hangs before failing. Defaults to 0.
+    #[serde(default)]
+    pub timeout_ms: u64,
+    // Makes the sequence of failures reproducible; seeded from the clock when absent.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// Retries of requests failing for a passing reason (HTTP 429 or 5xx, timeouts, unreachable
+/// endpoint), waiting twice as long before each new attempt, and a circuit breaker that stops
+/// sending requests for a while once too many in a row have failed that way.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrySettings {
+    // Attempts after the first one. 0 disables retries.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    // Wait before the first retry; doubled for each next one.
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    // Requests failing in a row (after their retries) that open the circuit. 0 disables it.
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    // How long an open circuit fails requests at once, before letting one through again.
+    #[serde(default = "default_circuit_open_ms")]
+    pub circuit_open_ms: u64,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        RetrySettings {
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_open_ms: default_circuit_open_ms(),
+        }
+    }
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30_000
+}
+
+fn default_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_open_ms() -> u64 {
+    60_000
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Passed,
+    Warning,
+    Failed,
+    // Not run because an earlier check failed, or not applicable to the provider.
+    Skipped,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    // "connection", "model" or "translation".
+    pub name: String,
+    pub status: CheckStatus,
+    // What was found and, when something is wrong, what to do about it.
+    pub message: String,
+    pub latency_ms: Option<u64>,
+}
+
+/// Result of checking a provider before a long batch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiagnostic {
+    // `TranslationProvider::name` of the checked provider.
+    pub provider: String,
+    // No check failed; warnings are worth a look but don't block a batch.
+    pub healthy: bool,
+    pub checks: Vec<DiagnosticCheck>,
+}
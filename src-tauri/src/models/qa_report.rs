@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum QaCategory {
+    // Control codes or placeholders differ from the original.
+    PlaceholderMismatch,
+    // No translation, or the translation failed.
+    Untranslated,
+    // Wider than the message window, or over a length budget of the ruleset.
+    LongLine,
+    // A glossary or locked term isn't rendered as its fixed target.
+    GlossaryViolation,
+    IdenticalToSource,
+    // Raised by the last reconstruction.
+    ReconstructionWarning,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QaIssue {
+    pub category: QaCategory,
+    pub json_path: Option<String>,
+    pub original_text: Option<String>,
+    pub translated_text: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QaFileReport {
+    pub source_file: String,
+    pub issues: Vec<QaIssue>,
+}
+
+/// Issues of a translation grouped by source file, files in path order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QaReport {
+    pub files: Vec<QaFileReport>,
+    // Issues per category, over all files.
+    pub counts: BTreeMap<QaCategory, usize>,
+    pub total_issues: usize,
+}
+
+/// File format of an exported QA report.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QaReportFormat {
+    Html,
+    // One row per issue.
+    Csv,
+}
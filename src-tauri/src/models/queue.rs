@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use crate::models::extraction::ExtractionFilter;
+use crate::models::provider::ProviderConfig;
+use crate::models::translation::BatchTranslationOptions;
+
+/// Settings shared by every project of a queue.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectQueueSettings {
+    pub source_language: String,
+    pub target_language: String,
+    // Defaults to Ollama when omitted, as for single batches.
+    #[serde(default)]
+    pub provider: Option<ProviderConfig>,
+    // Batch options; `projectPath` is set to each project in turn.
+    #[serde(default)]
+    pub options: BatchTranslationOptions,
+    #[serde(default)]
+    pub filter: ExtractionFilter,
+    // Folder the archives are written to, as `<project folder>_translated.zip`.
+    pub output_dir: String,
+    // Archive projects whose translation fails their quality gates anyway. Defaults to false.
+    #[serde(default)]
+    pub override_quality_gates: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueStage {
+    Extracting,
+    Translating,
+    Reconstructing,
+    Archiving,
+    Done,
+    Failed,
+    // Left out after the queue was cancelled.
+    Skipped,
+}
+
+/// Sent to the frontend each time a project of the queue enters a stage.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueProgress {
+    pub project_path: String,
+    // Position of the project in the queue, from 0.
+    pub index: usize,
+    pub total: usize,
+    pub stage: QueueStage,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectQueueResult {
+    pub project_path: String,
+    // Done, Failed or Skipped.
+    pub stage: QueueStage,
+    pub archive_path: Option<String>,
+    pub entries: usize,
+    pub translated: usize,
+    // Entries whose translation failed.
+    pub failed: usize,
+    pub error: Option<String>,
+}
+
+/// Outcome of a queue, one result per project in queue order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSummary {
+    pub projects: Vec<ProjectQueueResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// An external reference (wiki page, termbase entry) and/or a free-form note explaining
+/// how a term or line should be rendered.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceLink {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// References attached to one extracted entry, identified like a `SourceStringData`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryReferences {
+    pub source_file: String,
+    pub json_path: String,
+    pub references: Vec<ReferenceLink>,
+}
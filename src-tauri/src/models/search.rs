@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use crate::models::bulk::EntryStatus;
+use crate::models::translation::TranslationStatus;
+
+/// Which texts a search looks in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    Original,
+    Translated,
+    #[default]
+    Both,
+}
+
+/// Sent by `search_strings_command`. Empty lists match everything; all given criteria must match.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    // Text to find; without it, every entry passing the filters is returned.
+    #[serde(default)]
+    pub text: Option<String>,
+    // Treat `text` as a regex instead of plain text.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub field: SearchField,
+    #[serde(default)]
+    pub source_files: Vec<String>,
+    #[serde(default)]
+    pub statuses: Vec<EntryStatus>,
+    #[serde(default)]
+    pub object_ids: Vec<u32>,
+    #[serde(default)]
+    pub offset: usize,
+    // Hits per page; `DEFAULT_SEARCH_LIMIT` when absent.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub object_id: u32,
+    pub source_file: String,
+    pub json_path: String,
+    pub original_text: String,
+    // Empty when the entry has no translation in the session.
+    pub translated_text: String,
+    pub status: EntryStatus,
+    pub review_status: TranslationStatus,
+}
+
+/// One page of hits, by source file and then in extraction order.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    // Hits across all pages.
+    pub total: usize,
+    pub offset: usize,
+}
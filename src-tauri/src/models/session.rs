@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use crate::models::translation::{SourceStringData, WorkingTranslation};
+
+/// A project's working translations, saved to `.translate-ai/session.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationSession {
+    pub source_language: String,
+    pub target_language: String,
+    pub translations: Vec<WorkingTranslation>,
+    // Entries of the last batch that had not been translated when the session was saved.
+    #[serde(default)]
+    pub pending: Vec<SourceStringData>,
+}
+
+/// Counts from aligning an original project with a previously translated output.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    pub files_compared: usize,
+    // Entries whose text differs between the original and the output.
+    pub recovered: usize,
+    // Entries present in both with identical text; left untranslated in the session.
+    pub unchanged: usize,
+    // Original entries with no counterpart in the output (file or path missing).
+    pub missing_in_output: usize,
+    // Output entries whose path does not exist in the original project.
+    pub unmatched_in_output: usize,
+    pub stored_in_memory: usize,
+    pub errors: Vec<String>,
+}
+
+/// Result of `recover_session_command`: the rebuilt session entries and what was found.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecovery {
+    pub translations: Vec<WorkingTranslation>,
+    pub report: RecoveryReport,
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use crate::models::bulk::EntryStatus;
+use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
+
+/// An extracted string with its translation, as kept in the project's string store.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredString {
+    pub object_id: u32,
+    pub source_file: String,
+    pub json_path: String,
+    pub original_text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialogue_block_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    // Empty until the string is translated.
+    pub translated_text: String,
+    pub translation_source: String,
+    pub error: Option<String>,
+    pub status: TranslationStatus,
+}
+
+impl StoredString {
+    pub fn source(&self) -> SourceStringData {
+        SourceStringData {
+            object_id: self.object_id,
+            original_text: self.original_text.clone(),
+            source_file: self.source_file.clone(),
+            json_path: self.json_path.clone(),
+            dialogue_block_id: self.dialogue_block_id.clone(),
+            speaker: self.speaker.clone(),
+        }
+    }
+
+    pub fn translation(&self) -> WorkingTranslation {
+        WorkingTranslation {
+            object_id: self.object_id,
+            original_text: self.original_text.clone(),
+            translated_text: self.translated_text.clone(),
+            source_file: self.source_file.clone(),
+            json_path: self.json_path.clone(),
+            translation_source: self.translation_source.clone(),
+            error: self.error.clone(),
+            status: self.status,
+        }
+    }
+}
+
+/// A range of the store, by source file and then in extraction order. Empty lists match
+/// everything.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StringPageQuery {
+    #[serde(default)]
+    pub offset: usize,
+    // `DEFAULT_PAGE_SIZE` when absent, at most `MAX_PAGE_SIZE`.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub source_files: Vec<String>,
+    #[serde(default)]
+    pub statuses: Vec<EntryStatus>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StringPage {
+    pub entries: Vec<StoredString>,
+    // Entries matching the query across all pages.
+    pub total: usize,
+    pub offset: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreCounts {
+    pub total: usize,
+    pub translated: usize,
+    pub untranslated: usize,
+    pub failed: usize,
+    pub files: usize,
+}
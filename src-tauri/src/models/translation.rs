@@ -1,4 +1,7 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use crate::models::provider::{EscalationSettings, FaultInjectionSettings, RetrySettings};
+use crate::models::string_store::StringPageQuery;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -7,6 +10,13 @@ pub struct SourceStringData {
     pub original_text: String,          // The actual string to be translated
     pub source_file: String,   // Relative path to the file from the project root, e.g., "www/data/Actors.json"
     pub json_path: String,     // A string representing the path within the JSON, e.g., "[1].name"
+    // Shared by the lines of one message box (consecutive 401 commands), which are translated
+    // together; see `core::rpgmv::common::dialogue_block_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialogue_block_id: Option<String>,
+    // Original name of who speaks a message line, given to the provider as context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,6 +29,218 @@ pub struct WorkingTranslation {
     pub json_path: String,
     pub translation_source: String, // e.g., "ollama", "deepl", "glossary"
     pub error: Option<String>,     // To capture individual translation errors
+    #[serde(default)]
+    pub status: TranslationStatus,
+}
+
+/// Review state of a translation, set by the translator as entries are checked.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum TranslationStatus {
+    // No usable translation yet (never translated, cleared, or the provider failed).
+    Untranslated,
+    // As produced by a provider, the memory or the glossary. Also the state of entries saved
+    // before review states existed.
+    #[default]
+    MachineTranslated,
+    // Changed by hand after machine translation.
+    Edited,
+    Approved,
+    Rejected,
+}
+
+/// How an entry's text is cut into translation requests.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnitMode {
+    // The whole text in one request.
+    #[default]
+    Block,
+    // One request per paragraph (blocks separated by blank lines), rejoined with the original
+    // separators. Useful for credits, long lore notes and multi-paragraph profiles.
+    Paragraphs,
+}
+
+/// Unit mode chosen for one entry before translation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryUnitMode {
+    pub source_file: String,
+    pub json_path: String,
+    pub mode: UnitMode,
+}
+
+/// What makes two strings of a batch the same string for deduplication.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DedupScope {
+    // The original text alone.
+    #[default]
+    Text,
+    // The original text and the kind of entry (dialogue, description, other), so a menu label
+    // and a line of dialogue with the same text are translated separately.
+    TextAndContext,
+}
+
+/// An occurrence of a repeated string.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateOccurrence {
+    pub source_file: String,
+    pub json_path: String,
+}
+
+/// Repeated strings are translated once and the translation copied to every occurrence.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupSettings {
+    #[serde(default)]
+    pub scope: DedupScope,
+    // Occurrences the shared translation doesn't fit, found in review; each is translated on its own.
+    #[serde(default)]
+    pub separate: Vec<DuplicateOccurrence>,
+}
+
+/// Optional per-batch behaviour for `batch_translate_strings_command`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTranslationOptions {
+    // Used to load the project's glossary, validation ruleset and translation memory.
+    #[serde(default)]
+    pub project_path: Option<String>,
+    // Adaptive quality mode: re-translate hard lines with a premium provider after the first pass.
+    #[serde(default)]
+    pub escalation: Option<EscalationSettings>,
+    // Consult and update `<project>/.translate-ai/translation_memory.db`. Defaults to true when a project path is given.
+    #[serde(default)]
+    pub use_translation_memory: Option<bool>,
+    // Send glossary/entry reference links and notes to prompt-based providers. Defaults to true.
+    #[serde(default)]
+    pub include_references: Option<bool>,
+    // Registers the batch under this id so `cancel_job_command` can stop it.
+    #[serde(default)]
+    pub job_id: Option<String>,
+    // Provider requests in flight at once; results keep entry order. Defaults to 1.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    // Provider name ("ollama", "deepl", "openai") -> maximum requests started per minute.
+    #[serde(default)]
+    pub requests_per_minute: HashMap<String, u32>,
+    // Unit mode of entries without their own setting. Defaults to one block per entry.
+    #[serde(default)]
+    pub unit_mode: Option<UnitMode>,
+    #[serde(default)]
+    pub entry_unit_modes: Vec<EntryUnitMode>,
+    // Developer setting: simulate provider timeouts, rate limits and malformed answers.
+    #[serde(default)]
+    pub fault_injection: Option<FaultInjectionSettings>,
+    // Translate the lines of each message box in one request. Defaults to true.
+    #[serde(default)]
+    pub dialogue_blocks: Option<bool>,
+    // Source lines before and after each entry, in the same event, sent as read-only context.
+    // Defaults to 0 (none).
+    #[serde(default)]
+    pub context_window: Option<usize>,
+    // Retries of transient provider errors and the circuit breaker. Defaults to `RetrySettings::default()`.
+    #[serde(default)]
+    pub retry: Option<RetrySettings>,
+    // Stream Ollama and OpenAI-compatible answers and emit their partial text. Defaults to false.
+    #[serde(default)]
+    pub stream: Option<bool>,
+    // When the provider can't be reached, or fails on an entry, fill in a transliterated
+    // placeholder (see `core::transliteration`) so a testable build can still be made. Defaults
+    // to false.
+    #[serde(default)]
+    pub offline_fallback: Option<bool>,
+    // Keep the text of entries already in the target language (e.g. English UI terms) instead of
+    // sending them to the provider. Defaults to false.
+    #[serde(default)]
+    pub skip_target_language: Option<bool>,
+    // Translate each repeated string once (lines of message boxes aside). Off when absent.
+    #[serde(default)]
+    pub dedup: Option<DedupSettings>,
+    // Start a new project session when the saved one is for another language pair, discarding
+    // its translations. Defaults to false: such a batch is refused.
+    #[serde(default)]
+    pub replace_session: Option<bool>,
+    // Translate this page of the project's string store (e.g. the next untranslated strings)
+    // instead of the entries given.
+    #[serde(default)]
+    pub range: Option<StringPageQuery>,
+}
+
+/// Text of a translation still being generated, sent to the frontend while it streams in. For
+/// a message box or a message group, the text of the whole request, reported for its first entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialTranslation {
+    pub source_file: String,
+    pub json_path: String,
+    // Everything generated so far, not just the latest tokens.
+    pub text: String,
+}
+
+/// Progress of a running batch, sent to the frontend after every entry.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub current_file: String,
+    pub elapsed_ms: u64,
+    // Estimated time left, based on the average time per entry so far.
+    pub eta_ms: Option<u64>,
+}
+
+/// Sample sizes and time limit for `preview_quality_command`. Unset values use the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewOptions {
+    // Used to load the project's glossary and entry references, as for a full batch.
+    #[serde(default)]
+    pub project_path: Option<String>,
+    #[serde(default)]
+    pub dialogue_lines: Option<usize>,
+    #[serde(default)]
+    pub descriptions: Option<usize>,
+    // Names, terms and other short strings.
+    #[serde(default)]
+    pub other_entries: Option<usize>,
+    #[serde(default)]
+    pub time_limit_ms: Option<u64>,
+}
+
+/// Translated sample returned by `preview_quality_command`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityPreview {
+    pub results: Vec<WorkingTranslation>,
+    pub sample_size: usize,
+    // The time limit was hit before the whole sample was translated.
+    pub timed_out: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Translations of a project into one of its target languages, kept apart from those of the
+/// other languages (see `core::translation_sets`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationSet {
+    pub target_language: String,
+    pub translations: Vec<WorkingTranslation>,
+}
+
+/// How the outputs of a reconstruction into several languages are packaged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MultiTargetLayout {
+    // One ZIP per language, each a complete translated project.
+    #[default]
+    ZipPerLanguage,
+    // One ZIP with the data files of each language under `data_<language>/`, next to `data/`.
+    DataFolders,
 }
 
 // Future: May add other shared translation-related models here. 
\ No newline at end of file
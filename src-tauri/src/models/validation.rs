@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+/// Identifies a ruleset file when it is shared outside the app.
+pub const RULESET_FORMAT: &str = "translate-ai-ruleset";
+pub const RULESET_VERSION: u32 = 1;
+
+/// A shareable collection of QA rules applied to translations.
+/// Communities can standardize these per engine or genre and import them per project.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationRuleSet {
+    #[serde(default = "default_format")]
+    pub format: String,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // Optional engine tag, e.g. "rpgmv", purely informational.
+    #[serde(default)]
+    pub engine: Option<String>,
+    // Require control codes (\V[1], \N[2], %1, ...) to survive translation unchanged.
+    #[serde(default = "default_true")]
+    pub check_placeholders: bool,
+    // Read risky translations like the engine would and report characters that break it.
+    #[serde(default = "default_true")]
+    pub check_engine_syntax: bool,
+    // Look for duplicated or cut-off sentences where multi-paragraph texts were split and rejoined.
+    #[serde(default = "default_true")]
+    pub check_chunk_seams: bool,
+    #[serde(default)]
+    pub length_budgets: Vec<LengthBudget>,
+    #[serde(default)]
+    pub regex_filters: Vec<RegexFilter>,
+    #[serde(default)]
+    pub locked_terms: Vec<LockedTerm>,
+    // Regexes of control codes added by message plugins (e.g. `\\AF\[[^\]]+\]`), kept intact like
+    // the engine's own codes.
+    #[serde(default)]
+    pub control_codes: Vec<String>,
+}
+
+impl Default for ValidationRuleSet {
+    fn default() -> Self {
+        ValidationRuleSet {
+            format: default_format(),
+            version: default_version(),
+            name: "Default".to_string(),
+            description: String::new(),
+            engine: None,
+            check_placeholders: true,
+            check_engine_syntax: true,
+            check_chunk_seams: true,
+            length_budgets: Vec::new(),
+            regex_filters: Vec::new(),
+            locked_terms: Vec::new(),
+            control_codes: Vec::new(),
+        }
+    }
+}
+
+/// Maximum translated length for entries whose json_path matches `path_pattern` (a regex).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthBudget {
+    pub path_pattern: String,
+    pub max_chars: usize,
+}
+
+/// Flags translations matching `pattern` (e.g. leftover Japanese characters).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegexFilter {
+    pub pattern: String,
+    pub message: String,
+    #[serde(default)]
+    pub severity: ValidationSeverity,
+}
+
+/// A source term that must always be rendered as `target` when it appears in the original text.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedTerm {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationSeverity {
+    #[default]
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub source_file: String,
+    pub json_path: String,
+    pub rule: String, // "placeholder", "engineSyntax", "chunkSeam", "length", "regex", "lockedTerm", "namePolicy"
+    pub message: String,
+    pub severity: ValidationSeverity,
+}
+
+/// A translated string with characters the game's font can't draw.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterCoverageIssue {
+    pub source_file: String,
+    pub json_path: String,
+    pub translated_text: String,
+    pub characters: Vec<String>,
+}
+
+fn default_format() -> String {
+    RULESET_FORMAT.to_string()
+}
+
+fn default_version() -> u32 {
+    RULESET_VERSION
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Thresholds a translation must meet before it is packaged or exported as a patch. Unset gates
+/// aren't checked.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityGates {
+    // Translated entries with validation errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_validation_errors: Option<usize>,
+    // Share of the entries that have a translation, 0 to 100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_percent_translated: Option<f64>,
+    // Translated entries whose control codes or placeholders differ from the original.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_placeholder_mismatches: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GateResult {
+    // "validationErrors", "percentTranslated" or "placeholderMismatches", prefixed with the
+    // language in a package of several languages ("fr.percentTranslated").
+    pub gate: String,
+    pub threshold: f64,
+    pub actual: f64,
+    pub passed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityGateReport {
+    pub passed: bool,
+    // One per configured gate.
+    pub gates: Vec<GateResult>,
+    // Why the gates couldn't be checked; such a report doesn't pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use crate::models::provider::ProviderConfig;
+use crate::models::translation::BatchTranslationOptions;
+
+/// How a watched project is translated and where its build goes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSettings {
+    pub source_language: String,
+    pub target_language: String,
+    // Defaults to the project config's provider, then Ollama, as for single batches.
+    #[serde(default)]
+    pub provider: Option<ProviderConfig>,
+    // Batch options; `projectPath` is always the watched project.
+    #[serde(default)]
+    pub options: BatchTranslationOptions,
+    // Folder kept as a translated copy of the project. Must not be the project itself.
+    pub output_dir: String,
+}
+
+/// Sent to the frontend after each pass over a watched project.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchUpdate {
+    pub project_path: String,
+    // Data files that changed, relative to the project; empty for the first pass, which covers
+    // the whole project.
+    pub changed_files: Vec<String>,
+    // New or changed strings found in them.
+    pub new_entries: usize,
+    pub translated: usize,
+    pub failed: usize,
+    // Build files rewritten because their content changed.
+    pub files_written: Vec<String>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,1351 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Instant;
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Serialize;
+use crate::core::catchphrases::{catchphrase_for, catchphrase_instruction, locked_translation};
+use crate::core::characters::{character_instruction, sheet_for};
+use crate::core::control_codes::{ControlCodes, MaskedText};
+use crate::core::escaping::sanitize_translation;
+use crate::core::glossary::{apply_glossary, terms_in_text};
+use crate::core::language_detection::is_in_target_language;
+use crate::core::name_policy::policy_instructions;
+use crate::core::prompts::{prompt_kind_for, template_for};
+use crate::core::quality::{hard_line_signals, HardLineSignal};
+use crate::core::references::ReferenceIndex;
+use crate::core::rpgmv::system::{message_group_for, MessageGroup};
+use crate::core::scope_summaries::summary_for;
+use crate::core::translation_memory::{TranslationMemory, MEMORY_SOURCE_PREFIX};
+use crate::core::units::{split_units, UnitModes};
+use crate::models::character::{Catchphrases, CharacterSheet};
+use crate::models::glossary::Glossary;
+use crate::models::metrics::EntryTimings;
+use crate::models::preset::TranslationPreset;
+use crate::models::prompt::{PromptTemplates, ScopeSummaries};
+use crate::models::provider::EscalationSettings;
+use crate::models::translation::{
+    BatchProgress, PartialTranslation, SourceStringData, TranslationStatus, UnitMode, WorkingTranslation,
+};
+use crate::models::validation::ValidationRuleSet;
+use crate::services::job_manager::CancellationToken;
+use crate::services::translation_provider::{PromptContext, ProviderTranslation, TranslationProvider};
+
+/// `translation_source` of entries given the locked translation of their catchphrase.
+pub const CATCHPHRASE_SOURCE: &str = "catchphrase";
+
+/// `translation_source` of entries skipped because their text is already in the target language.
+pub const TARGET_LANGUAGE_SOURCE: &str = "skipped:targetLanguage";
+
+/// Receives the partial text of streamed translations; returning false stops that generation.
+pub type PartialTranslationSink<'a> = dyn Fn(&PartialTranslation) -> bool + Send + Sync + 'a;
+
+/// Settings shared by every entry of a batch.
+pub struct BatchContext<'a> {
+    pub source_language: &'a str,
+    pub target_language: &'a str,
+    pub glossary: &'a Glossary,
+    pub references: &'a ReferenceIndex,
+    // When false, reference links/notes are left out of prompts (glossary terms are still sent).
+    pub include_references: bool,
+    // Checked before each entry (and during streamed generations); once cancelled, the entries
+    // finished so far are returned.
+    pub cancellation: Option<&'a CancellationToken>,
+    // Provider requests allowed in flight at once (at least 1).
+    pub concurrency: usize,
+    // How each entry is cut into provider requests; every entry is one block when absent.
+    pub unit_modes: Option<&'a UnitModes>,
+    // Custom control codes of the project, masked in requests; only the engine's own when absent.
+    pub control_codes: Option<&'a ControlCodes>,
+    // Prompt templates of the project; providers use their own wording when absent.
+    pub prompt_templates: Option<&'a PromptTemplates>,
+    // Translate the lines of a message box (same `dialogue_block_id`) in one request.
+    pub dialogue_blocks: bool,
+    // Neighbouring source lines given to the provider as read-only context; none when absent.
+    pub surrounding_lines: Option<&'a SurroundingLines>,
+    // Synopses of the project's maps and scenes; none when absent.
+    pub scope_summaries: Option<&'a ScopeSummaries>,
+    // Catchphrases of the project (see `core::catchphrases`): lines saying one are translated as
+    // such, and locked ones get their translation without a request.
+    pub catchphrases: Option<&'a Catchphrases>,
+    // Stream translations and report their text as it is generated; answers come in one go when absent.
+    pub on_partial: Option<&'a PartialTranslationSink<'a>>,
+    // Keep the text of entries already in the target language instead of translating them.
+    pub skip_target_language: bool,
+    // Translation preset of the project (see `core::presets`): entries with honorifics or full
+    // names are sent with its honorific and name order policy; no policy when absent.
+    pub preset: Option<&'a TranslationPreset>,
+    // Character sheets of the project: lines whose speaker has one are sent with it; none when
+    // absent.
+    pub characters: Option<&'a [CharacterSheet]>,
+}
+
+/// The texts of each event command list of a batch, so each entry can be sent with the `window`
+/// lines extracted before and after it in the same event.
+#[derive(Debug, Clone, Default)]
+pub struct SurroundingLines {
+    window: usize,
+    lists: Vec<Vec<String>>,
+    // (source file, json path) -> (index in `lists`, position in the list).
+    positions: HashMap<(String, String), (usize, usize)>,
+}
+
+/// The command list a path points into, e.g. "events[1].pages[0].list" for
+/// "events[1].pages[0].list[3].parameters[0]".
+fn command_list_of(json_path: &str) -> Option<&str> {
+    let start = json_path.rfind("list[")?;
+    (start == 0 || json_path[..start].ends_with('.')).then(|| &json_path[..start + 4])
+}
+
+impl SurroundingLines {
+    pub fn new(entries: &[SourceStringData], window: usize) -> Self {
+        let mut surrounding = SurroundingLines { window, ..Default::default() };
+        if window == 0 {
+            return surrounding;
+        }
+        let mut list_indices: HashMap<(&str, &str), usize> = HashMap::new();
+        for entry in entries {
+            let Some(list) = command_list_of(&entry.json_path) else { continue };
+            let index = *list_indices.entry((&entry.source_file, list)).or_insert_with(|| {
+                surrounding.lists.push(Vec::new());
+                surrounding.lists.len() - 1
+            });
+            let lines = &mut surrounding.lists[index];
+            surrounding.positions.insert((entry.source_file.clone(), entry.json_path.clone()), (index, lines.len()));
+            lines.push(entry.original_text.clone());
+        }
+        surrounding
+    }
+
+    /// The lines before `first` and after `last`, which must be in the same command list (e.g.
+    /// the first and last line of a message box); nothing otherwise.
+    pub fn around(&self, first: &SourceStringData, last: &SourceStringData) -> (Vec<String>, Vec<String>) {
+        let position = |entry: &SourceStringData| self.positions.get(&(entry.source_file.clone(), entry.json_path.clone()));
+        match (position(first), position(last)) {
+            (Some(&(list, start)), Some(&(last_list, end))) if list == last_list && start <= end => {
+                let lines = &self.lists[list];
+                let after = (end + 1 + self.window).min(lines.len());
+                (lines[start.saturating_sub(self.window)..start].to_vec(), lines[end + 1..after].to_vec())
+            }
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+}
+
+impl BatchContext<'_> {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Prompt context for one entry: the glossary terms its text contains, the instructions of
+    /// its message group and the preset's name policy and, optionally, the reference links
+    /// attached to those terms and to the entry. With project templates, the one for the entry's
+    /// kind, and with scope summaries, the synopsis of the entry's scene.
+    pub fn prompt_context_for(&self, text: &str, source_file: &str, json_path: &str) -> PromptContext {
+        let mut glossary: Vec<_> = terms_in_text(self.glossary, text).into_iter().cloned().collect();
+        let mut instructions: Vec<String> = message_group_for(source_file, json_path)
+            .map(|group| vec![group.instructions.to_string()])
+            .unwrap_or_default();
+        if let Some(preset) = self.preset {
+            instructions.extend(policy_instructions(preset, text, &glossary));
+        }
+        let template = self.prompt_templates.map(|templates| template_for(templates, prompt_kind_for(source_file, json_path)));
+        let scope_summary = self
+            .scope_summaries
+            .and_then(|summaries| summary_for(summaries, source_file, json_path))
+            .map(str::to_string);
+        if !self.include_references {
+            glossary.iter_mut().for_each(|t| t.references.clear());
+            return PromptContext { glossary, instructions, scope_summary, template, ..Default::default() };
+        }
+        let references = self
+            .references
+            .get(&(source_file.to_string(), json_path.to_string()))
+            .cloned()
+            .unwrap_or_default();
+        PromptContext { glossary, references, instructions, scope_summary, template, ..Default::default() }
+    }
+
+    /// `text` (from `entries`, at least one) with its custom control codes masked, and its prompt
+    /// context telling the provider who speaks it, what is said around it and to keep the mask
+    /// tokens. The speaker and surrounding lines are left out of the memory's keys, which are
+    /// built from finished translations.
+    fn masked_request(&self, text: &str, entries: &[SourceStringData]) -> (MaskedText, PromptContext) {
+        let (first, last) = (&entries[0], &entries[entries.len() - 1]);
+        let mut context = self.prompt_context_for(text, &first.source_file, &first.json_path);
+        context.speaker = first.speaker.clone();
+        if let Some(surrounding) = self.surrounding_lines {
+            (context.previous_lines, context.next_lines) = surrounding.around(first, last);
+        }
+        let sheet = self.characters.zip(first.speaker.as_deref()).and_then(|(sheets, speaker)| sheet_for(sheets, speaker));
+        if let Some(sheet) = sheet {
+            context.instructions.push(character_instruction(sheet));
+        }
+        if let Some(phrase) = self.catchphrases.and_then(|c| catchphrase_for(c, first)).filter(|_| entries.len() == 1) {
+            context.instructions.push(catchphrase_instruction(phrase));
+        }
+        let masked = mask_control_codes(self.control_codes, text, &mut context);
+        (masked, context)
+    }
+
+    /// Sends a request, streamed when the batch reports partial text; the partial text is
+    /// reported for the entry at `json_path` (the first one of a group), with its control codes
+    /// unmasked. The generation stops when the batch is cancelled.
+    async fn request(
+        &self,
+        provider: &dyn TranslationProvider,
+        masked: &MaskedText,
+        context: &PromptContext,
+        (source_file, json_path): (&str, &str),
+    ) -> Result<ProviderTranslation, String> {
+        let Some(sink) = self.on_partial else {
+            return provider.translate_with_context(&masked.text, self.source_language, self.target_language, context).await;
+        };
+        let on_partial = |text: &str| {
+            let partial = PartialTranslation {
+                source_file: source_file.to_string(),
+                json_path: json_path.to_string(),
+                text: masked.unmask(text),
+            };
+            sink(&partial) && !self.is_cancelled()
+        };
+        provider
+            .translate_streaming(&masked.text, self.source_language, self.target_language, context, &on_partial)
+            .await
+    }
+
+    /// The translation of an entry that needs no request, with its `translation_source`: the
+    /// locked translation of its catchphrase or, with `skip_target_language`, its own text when
+    /// it is already in the target language.
+    fn answer_without_request(&self, entry: &SourceStringData) -> Option<(String, &'static str)> {
+        if let Some(translation) = self.catchphrases.and_then(|c| locked_translation(c, entry)) {
+            return Some((translation.to_string(), CATCHPHRASE_SOURCE));
+        }
+        (self.skip_target_language
+            && is_in_target_language(&entry.original_text, self.source_language, self.target_language))
+        .then(|| (entry.original_text.clone(), TARGET_LANGUAGE_SOURCE))
+    }
+
+    fn remember(&self, memory: &TranslationMemory, entry: &WorkingTranslation) {
+        if entry.error.is_some() || entry.translated_text.trim().is_empty() {
+            return;
+        }
+        let context = self.prompt_context_for(&entry.original_text, &entry.source_file, &entry.json_path);
+        if let Err(e) = memory.store_in_context(
+            self.source_language,
+            self.target_language,
+            &entry.original_text,
+            &entry.translated_text,
+            &entry.translation_source,
+            &context.cache_key(),
+        ) {
+            eprintln!("Failed to store translation memory entry for {}: {}", entry.json_path, e);
+        }
+    }
+}
+
+fn elapsed_ms(since: Instant) -> f64 {
+    since.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Translates a single extracted entry, turning provider failures into an entry-level error.
+/// Glossary terms found in the text are given to the provider and enforced on the result.
+pub async fn translate_entry(
+    provider: &dyn TranslationProvider,
+    entry: SourceStringData,
+    batch: &BatchContext<'_>,
+) -> WorkingTranslation {
+    translate_entry_timed(provider, entry, batch).await.0
+}
+
+/// `translate_entry`, also measuring the prompt, provider and post-processing stages.
+pub async fn translate_entry_timed(
+    provider: &dyn TranslationProvider,
+    entry: SourceStringData,
+    batch: &BatchContext<'_>,
+) -> (WorkingTranslation, EntryTimings) {
+    let mut timings = EntryTimings {
+        source_file: entry.source_file.clone(),
+        json_path: entry.json_path.clone(),
+        ..Default::default()
+    };
+    let mode = batch.unit_modes.map_or(UnitMode::Block, |modes| modes.mode_for(&entry.source_file, &entry.json_path));
+    let units = split_units(&entry.original_text, mode);
+    let (mut prompt_build_ms, mut provider_ms) = (0.0, 0.0);
+    let mut translated_units = Vec::with_capacity(units.units.len());
+    let mut response = Ok(ProviderTranslation { text: String::new(), provider: provider.name().to_string() });
+    // Each unit gets its own prompt (with the glossary terms it contains); the first failure
+    // fails the whole entry.
+    for unit in &units.units {
+        let stage = Instant::now();
+        let (masked, context) = batch.masked_request(unit, std::slice::from_ref(&entry));
+        prompt_build_ms += elapsed_ms(stage);
+
+        let stage = Instant::now();
+        let unit_response = batch.request(provider, &masked, &context, (&entry.source_file, &entry.json_path)).await;
+        provider_ms += elapsed_ms(stage);
+        match unit_response {
+            Ok(translation) => {
+                translated_units.push(masked.unmask(&translation.text));
+                response = Ok(ProviderTranslation { text: units.join(&translated_units), provider: translation.provider });
+            }
+            Err(e) => {
+                response = Err(e);
+                break;
+            }
+        }
+    }
+    timings.prompt_build_ms = Some(prompt_build_ms);
+    timings.provider_ms = Some(provider_ms);
+    let mut translated = match response {
+        Ok(translation) => WorkingTranslation {
+            object_id: entry.object_id,
+            original_text: entry.original_text,
+            translated_text: translation.text,
+            source_file: entry.source_file,
+            json_path: entry.json_path,
+            translation_source: translation.provider,
+            error: None,
+            status: TranslationStatus::MachineTranslated,
+        },
+        Err(e) => WorkingTranslation {
+            object_id: entry.object_id,
+            original_text: entry.original_text,
+            translated_text: String::new(),
+            source_file: entry.source_file,
+            json_path: entry.json_path,
+            translation_source: provider.name().to_string(),
+            error: Some(e),
+            status: TranslationStatus::Untranslated,
+        },
+    };
+    let stage = Instant::now();
+    apply_glossary(&mut translated, batch.glossary);
+    sanitize_translation(&mut translated);
+    timings.post_process_ms = Some(elapsed_ms(stage));
+    (translated, timings)
+}
+
+/// One request holding several messages, each on a line starting with its "[n]" marker.
+fn group_request(texts: &[&str]) -> String {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| format!("[{}] {}", index + 1, text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits a grouped answer back into its messages; `None` unless exactly `count` markers come
+/// back in order. Lines without a marker continue the previous message.
+fn split_group_response(response: &str, count: usize) -> Option<Vec<String>> {
+    let mut parts: Vec<String> = Vec::with_capacity(count);
+    for line in response.lines() {
+        let marker = format!("[{}]", parts.len() + 1);
+        if let Some(rest) = line.trim_start().strip_prefix(&marker) {
+            parts.push(rest.trim_start().to_string());
+        } else if let Some(last) = parts.last_mut() {
+            last.push('\n');
+            last.push_str(line);
+        } else if !line.trim().is_empty() {
+            return None;
+        }
+    }
+    parts.iter_mut().for_each(|part| part.truncate(part.trim_end().len()));
+    (parts.len() == count).then_some(parts)
+}
+
+/// Entries translated together in one request.
+enum EntryGroup {
+    // System messages the battle log shows together (see `MESSAGE_GROUPS`).
+    Messages(&'static MessageGroup),
+    // The lines of one message box, by `dialogue_block_id`.
+    DialogueBlock(String),
+}
+
+impl EntryGroup {
+    fn name(&self) -> String {
+        match self {
+            EntryGroup::Messages(group) => format!("{} messages", group.name),
+            EntryGroup::DialogueBlock(id) => format!("lines of message box {}", id),
+        }
+    }
+
+    fn instructions(&self, count: usize) -> String {
+        match self {
+            EntryGroup::Messages(_) => format!(
+                "The text holds {} numbered messages. Translate each one on its own line and keep its [n] marker.",
+                count
+            ),
+            EntryGroup::DialogueBlock(_) => format!(
+                "The text holds the {} numbered lines of one message box. Translate them as one passage, spread over the same {} lines, and keep each line's [n] marker.",
+                count, count
+            ),
+        }
+    }
+}
+
+/// Translates the members of a group in one request, so they share the group's instructions and
+/// read as a set. When the provider fails or its answer doesn't split back into the same number
+/// of messages, each member is translated on its own (still with the group's instructions). The
+/// timings of the shared request are reported for every member.
+async fn translate_group(
+    provider: &dyn TranslationProvider,
+    group: &EntryGroup,
+    entries: Vec<SourceStringData>,
+    batch: &BatchContext<'_>,
+) -> Vec<(WorkingTranslation, EntryTimings)> {
+    let stage = Instant::now();
+    let texts: Vec<&str> = entries.iter().map(|entry| entry.original_text.as_str()).collect();
+    let (request, mut context) = batch.masked_request(&group_request(&texts), &entries);
+    for entry in &entries[1..] {
+        context.references.extend(batch.prompt_context_for("", &entry.source_file, &entry.json_path).references);
+    }
+    context.instructions.push(group.instructions(entries.len()));
+    let prompt_build_ms = elapsed_ms(stage);
+
+    let stage = Instant::now();
+    let response = batch
+        .request(provider, &request, &context, (&entries[0].source_file, &entries[0].json_path))
+        .await
+        .map(|translation| ProviderTranslation { text: request.unmask(&translation.text), ..translation });
+    let provider_ms = elapsed_ms(stage);
+    let parts = response.as_ref().ok().and_then(|translation| split_group_response(&translation.text, entries.len()));
+    let (Some(parts), Ok(translation)) = (parts, response) else {
+        eprintln!("Grouped translation of the {} failed; translating them one by one.", group.name());
+        let mut results = Vec::with_capacity(entries.len());
+        for entry in entries {
+            results.push(translate_entry_timed(provider, entry, batch).await);
+        }
+        return results;
+    };
+
+    entries
+        .into_iter()
+        .zip(parts)
+        .map(|(entry, text)| {
+            let stage = Instant::now();
+            let timings = EntryTimings {
+                source_file: entry.source_file.clone(),
+                json_path: entry.json_path.clone(),
+                prompt_build_ms: Some(prompt_build_ms),
+                provider_ms: Some(provider_ms),
+                ..Default::default()
+            };
+            let mut translated = WorkingTranslation {
+                object_id: entry.object_id,
+                original_text: entry.original_text,
+                translated_text: text,
+                source_file: entry.source_file,
+                json_path: entry.json_path,
+                translation_source: translation.provider.clone(),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            };
+            apply_glossary(&mut translated, batch.glossary);
+            sanitize_translation(&mut translated);
+            (translated, EntryTimings { post_process_ms: Some(elapsed_ms(stage)), ..timings })
+        })
+        .collect()
+}
+
+// Entries with their batch indexes, in entry order.
+type IndexedEntries = Vec<(usize, SourceStringData)>;
+// Entries of the same message group and file.
+type GroupedEntries = Vec<(&'static MessageGroup, IndexedEntries)>;
+
+/// What the batch queue sends to the provider: one entry, or the lines of a message box.
+enum BatchUnit {
+    Entry(usize, SourceStringData),
+    DialogueBlock(String, IndexedEntries),
+}
+
+/// Sets aside the entries whose message group has at least two members in the batch. The others
+/// stay in the queue in entry order; a lone member still gets its group's instructions. With
+/// `dialogue_blocks`, lines of the same message box are queued together where the first of them
+/// is.
+fn partition_message_groups(
+    entries: IndexedEntries,
+    dialogue_blocks: bool,
+) -> (GroupedEntries, VecDeque<BatchUnit>) {
+    let mut groups: Vec<(&'static MessageGroup, String, IndexedEntries)> = Vec::new();
+    let mut rest = Vec::new();
+    for (index, entry) in entries {
+        let Some(group) = message_group_for(&entry.source_file, &entry.json_path) else {
+            rest.push((index, entry));
+            continue;
+        };
+        match groups.iter_mut().find(|(g, file, _)| g.name == group.name && *file == entry.source_file) {
+            Some((_, _, members)) => members.push((index, entry)),
+            None => groups.push((group, entry.source_file.clone(), vec![(index, entry)])),
+        }
+    }
+    let mut grouped = Vec::new();
+    for (group, _, members) in groups {
+        if members.len() > 1 {
+            grouped.push((group, members));
+        } else {
+            rest.extend(members);
+        }
+    }
+    rest.sort_by_key(|(index, _)| *index);
+
+    let mut queue = VecDeque::new();
+    let mut block_positions: HashMap<String, usize> = HashMap::new();
+    for (index, entry) in rest {
+        let block_id = entry.dialogue_block_id.clone().filter(|_| dialogue_blocks);
+        match block_id {
+            Some(id) => match block_positions.get(&id) {
+                Some(&position) => {
+                    if let BatchUnit::DialogueBlock(_, members) = &mut queue[position] {
+                        members.push((index, entry));
+                    }
+                }
+                None => {
+                    block_positions.insert(id.clone(), queue.len());
+                    queue.push_back(BatchUnit::DialogueBlock(id, vec![(index, entry)]));
+                }
+            },
+            None => queue.push_back(BatchUnit::Entry(index, entry)),
+        }
+    }
+    // A box with a single line in the batch is an ordinary entry.
+    for unit in queue.iter_mut() {
+        if let BatchUnit::DialogueBlock(_, members) = unit {
+            if members.len() == 1 {
+                let (index, entry) = members.pop().expect("one member");
+                *unit = BatchUnit::Entry(index, entry);
+            }
+        }
+    }
+    (grouped, queue)
+}
+
+/// Translates one queued unit; the results keep the entries' batch indexes.
+async fn translate_unit(
+    provider: &dyn TranslationProvider,
+    unit: BatchUnit,
+    batch: &BatchContext<'_>,
+    queue_wait_ms: f64,
+    memory_lookup_ms: Option<f64>,
+) -> Vec<(usize, WorkingTranslation, EntryTimings)> {
+    let with_wait = |timings: EntryTimings| EntryTimings { queue_wait_ms, memory_lookup_ms, ..timings };
+    match unit {
+        BatchUnit::Entry(index, entry) => {
+            let (translated, timings) = translate_entry_timed(provider, entry, batch).await;
+            vec![(index, translated, with_wait(timings))]
+        }
+        BatchUnit::DialogueBlock(id, members) => {
+            let (indexes, members): (Vec<usize>, Vec<SourceStringData>) = members.into_iter().unzip();
+            let translated = translate_group(provider, &EntryGroup::DialogueBlock(id), members, batch).await;
+            indexes
+                .into_iter()
+                .zip(translated)
+                .map(|(index, (translated, timings))| (index, translated, with_wait(timings)))
+                .collect()
+        }
+    }
+}
+
+/// Callback receiving batch progress, the finished entry and its stage timings after each entry.
+pub type ProgressCallback<'a> = &'a mut (dyn FnMut(&BatchProgress, &WorkingTranslation, &EntryTimings) + Send);
+
+/// Counts finished entries and estimates the remaining time of a batch.
+pub struct ProgressTracker {
+    started: Instant,
+    progress: BatchProgress,
+}
+
+impl ProgressTracker {
+    pub fn new(total: usize) -> Self {
+        ProgressTracker { started: Instant::now(), progress: BatchProgress { total, ..Default::default() } }
+    }
+
+    pub fn record(&mut self, entry: &WorkingTranslation) -> &BatchProgress {
+        let progress = &mut self.progress;
+        progress.processed += 1;
+        if entry.error.is_some() {
+            progress.failed += 1;
+        } else {
+            progress.succeeded += 1;
+        }
+        progress.current_file = entry.source_file.clone();
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        progress.elapsed_ms = elapsed;
+        let remaining = progress.total.saturating_sub(progress.processed) as u64;
+        progress.eta_ms = Some(remaining_ms(elapsed, progress.processed as u64, remaining));
+        progress
+    }
+}
+
+/// Time left at the mean time per entry so far. Multiplied before dividing, so entries faster
+/// than a millisecond don't estimate zero.
+fn remaining_ms(elapsed_ms: u64, processed: u64, remaining: u64) -> u64 {
+    let estimate = u128::from(elapsed_ms) * u128::from(remaining) / u128::from(processed.max(1));
+    u64::try_from(estimate).unwrap_or(u64::MAX)
+}
+
+fn recall(memory: &TranslationMemory, entry: &SourceStringData, batch: &BatchContext<'_>) -> Option<WorkingTranslation> {
+    let context_hash = batch.prompt_context_for(&entry.original_text, &entry.source_file, &entry.json_path).cache_key();
+    match memory.lookup_in_context(batch.source_language, batch.target_language, &entry.original_text, &context_hash) {
+        Ok(Some(hit)) => {
+            let mut remembered = WorkingTranslation {
+                object_id: entry.object_id,
+                original_text: entry.original_text.clone(),
+                translated_text: hit.translated_text,
+                source_file: entry.source_file.clone(),
+                json_path: entry.json_path.clone(),
+                translation_source: format!("{}{}", MEMORY_SOURCE_PREFIX, hit.provider),
+                error: None,
+                status: TranslationStatus::MachineTranslated,
+            };
+            // The glossary may have changed since the translation was stored.
+            apply_glossary(&mut remembered, batch.glossary);
+            Some(remembered)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            eprintln!("Translation memory lookup failed for {}: {}", entry.json_path, e);
+            None
+        }
+    }
+}
+
+/// Translates entries with up to `batch.concurrency` provider requests in flight and returns the
+/// results in entry order. When a translation memory is given, it is consulted before the provider
+/// and every new translation is written back; an entry whose text is already in flight waits for
+/// that request, so repeated strings (including repeats within this batch) only reach the provider
+/// once. System messages the battle log shows together (see `MESSAGE_GROUPS`) are translated first,
+/// one request per group, without consulting the memory. With `batch.dialogue_blocks`, the lines
+/// of a message box are one request too, unless the memory has all of them. `on_progress` is called after each entry,
+/// in completion order. If the batch is cancelled, no new requests are started and only the
+/// entries finished by then are returned.
+pub async fn translate_entries(
+    provider: &dyn TranslationProvider,
+    entries: Vec<SourceStringData>,
+    batch: &BatchContext<'_>,
+    mut memory: Option<&mut TranslationMemory>,
+    mut on_progress: Option<ProgressCallback<'_>>,
+) -> Vec<WorkingTranslation> {
+    let total = entries.len();
+    let started = Instant::now();
+    let mut tracker = ProgressTracker::new(total);
+    let mut results: Vec<Option<WorkingTranslation>> = vec![None; total];
+    let mut record = |index: usize, translated: WorkingTranslation, timings: EntryTimings| {
+        let progress = tracker.record(&translated);
+        if let Some(callback) = on_progress.as_deref_mut() {
+            callback(progress, &translated, &timings);
+        }
+        results[index] = Some(translated);
+    };
+
+    // Locked catchphrases and strings already in the target language are answered first.
+    let mut pending = Vec::with_capacity(total);
+    for (index, entry) in entries.into_iter().enumerate() {
+        match batch.answer_without_request(&entry) {
+            Some((translation, source)) => {
+                let mut answered = WorkingTranslation {
+                    object_id: entry.object_id,
+                    original_text: entry.original_text.clone(),
+                    translated_text: translation,
+                    source_file: entry.source_file.clone(),
+                    json_path: entry.json_path.clone(),
+                    translation_source: source.to_string(),
+                    error: None,
+                    status: TranslationStatus::MachineTranslated,
+                };
+                sanitize_translation(&mut answered);
+                let timings = EntryTimings { source_file: entry.source_file, json_path: entry.json_path, ..Default::default() };
+                record(index, answered, timings);
+            }
+            None => pending.push((index, entry)),
+        }
+    }
+
+    let (groups, mut queue) = partition_message_groups(pending, batch.dialogue_blocks);
+    for (group, members) in groups {
+        if batch.is_cancelled() {
+            break;
+        }
+        let queue_wait_ms = elapsed_ms(started);
+        let (indexes, members): (Vec<usize>, Vec<SourceStringData>) = members.into_iter().unzip();
+        let translated = translate_group(provider, &EntryGroup::Messages(group), members, batch).await;
+        for (index, (translated, timings)) in indexes.into_iter().zip(translated) {
+            if let Some(memory) = memory.as_deref_mut() {
+                batch.remember(memory, &translated);
+            }
+            record(index, translated, EntryTimings { queue_wait_ms, ..timings });
+        }
+    }
+
+    let mut in_flight = FuturesUnordered::new();
+    let mut in_flight_texts: HashSet<String> = HashSet::new();
+    // Entries waiting for an in-flight request with the same text, by text.
+    let mut waiting: HashMap<String, Vec<(usize, SourceStringData)>> = HashMap::new();
+    let concurrency = batch.concurrency.max(1);
+
+    loop {
+        while in_flight.len() < concurrency && !batch.is_cancelled() {
+            let (index, entry) = match queue.pop_front() {
+                None => break,
+                Some(BatchUnit::Entry(index, entry)) => (index, entry),
+                Some(BatchUnit::DialogueBlock(id, members)) => {
+                    let queue_wait_ms = elapsed_ms(started);
+                    // A box is answered by the memory only when all of its lines are.
+                    let lookup = Instant::now();
+                    let remembered: Option<Vec<WorkingTranslation>> = memory
+                        .as_deref()
+                        .and_then(|memory| members.iter().map(|(_, entry)| recall(memory, entry, batch)).collect());
+                    let memory_lookup_ms = memory.is_some().then(|| elapsed_ms(lookup));
+                    match remembered {
+                        Some(remembered) => {
+                            for ((index, entry), remembered) in members.into_iter().zip(remembered) {
+                                let timings = EntryTimings {
+                                    source_file: entry.source_file,
+                                    json_path: entry.json_path,
+                                    queue_wait_ms,
+                                    memory_lookup_ms,
+                                    from_memory: true,
+                                    ..Default::default()
+                                };
+                                record(index, remembered, timings);
+                            }
+                        }
+                        None => in_flight.push(translate_unit(
+                            provider,
+                            BatchUnit::DialogueBlock(id, members),
+                            batch,
+                            queue_wait_ms,
+                            memory_lookup_ms,
+                        )),
+                    }
+                    continue;
+                }
+            };
+            let queue_wait_ms = elapsed_ms(started);
+            let mut memory_lookup_ms = None;
+            if let Some(memory) = memory.as_deref() {
+                let lookup = Instant::now();
+                let remembered = recall(memory, &entry, batch);
+                memory_lookup_ms = Some(elapsed_ms(lookup));
+                if let Some(remembered) = remembered {
+                    let timings = EntryTimings {
+                        source_file: entry.source_file,
+                        json_path: entry.json_path,
+                        queue_wait_ms,
+                        memory_lookup_ms,
+                        from_memory: true,
+                        ..Default::default()
+                    };
+                    record(index, remembered, timings);
+                    continue;
+                }
+                if in_flight_texts.contains(&entry.original_text) {
+                    waiting.entry(entry.original_text.clone()).or_default().push((index, entry));
+                    continue;
+                }
+                in_flight_texts.insert(entry.original_text.clone());
+            }
+            in_flight.push(translate_unit(provider, BatchUnit::Entry(index, entry), batch, queue_wait_ms, memory_lookup_ms));
+        }
+
+        let Some(finished) = in_flight.next().await else { break };
+        for (index, translated, timings) in finished {
+            if let Some(memory) = memory.as_deref_mut() {
+                batch.remember(memory, &translated);
+            }
+            in_flight_texts.remove(&translated.original_text);
+            // Repeats are now answered by the memory, or retried if this request failed.
+            if let Some(repeats) = waiting.remove(&translated.original_text) {
+                for (index, entry) in repeats.into_iter().rev() {
+                    queue.push_front(BatchUnit::Entry(index, entry));
+                }
+            }
+            record(index, translated, timings);
+        }
+    }
+
+    if batch.is_cancelled() {
+        let finished = results.iter().filter(|r| r.is_some()).count();
+        eprintln!("Batch cancelled after {} of {} entries.", finished, total);
+    }
+    results.into_iter().flatten().collect()
+}
+
+/// Translates entries in order until `deadline`. The entry in flight at the deadline is dropped.
+/// Returns the finished translations and whether the deadline was hit. The memory is not used,
+/// so the result reflects what the provider produces with the current settings.
+pub async fn translate_until(
+    provider: &dyn TranslationProvider,
+    entries: Vec<SourceStringData>,
+    batch: &BatchContext<'_>,
+    deadline: tokio::time::Instant,
+) -> (Vec<WorkingTranslation>, bool) {
+    let mut results = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match tokio::time::timeout_at(deadline, translate_entry(provider, entry, batch)).await {
+            Ok(translated) => results.push(translated),
+            Err(_) => return (results, true),
+        }
+    }
+    (results, false)
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EscalationSummary {
+    pub hard_lines: usize,
+    pub escalated: usize,
+    // Premium provider also failed; the first-pass result was kept.
+    pub failed: usize,
+}
+
+/// `text` with its custom codes masked, telling the provider in `context` to keep the tokens.
+fn mask_control_codes(codes: Option<&ControlCodes>, text: &str, context: &mut PromptContext) -> MaskedText {
+    let masked = match codes {
+        Some(codes) => codes.mask_custom(text),
+        None => MaskedText { text: text.to_string(), codes: Vec::new() },
+    };
+    if !masked.codes.is_empty() {
+        context.instructions.push(
+            "Tokens like ⟦1⟧ stand for formatting codes: copy each of them unchanged to the matching place.".to_string(),
+        );
+    }
+    masked
+}
+
+/// Second pass of the adaptive quality mode: re-translates only the entries showing hard-line
+/// signals with the premium provider. A premium result replaces the first pass unless it fails,
+/// and also replaces the first-pass translation in the memory.
+pub async fn escalate_hard_lines(
+    results: &mut [WorkingTranslation],
+    premium: &dyn TranslationProvider,
+    ruleset: &ValidationRuleSet,
+    settings: &EscalationSettings,
+    batch: &BatchContext<'_>,
+    mut memory: Option<&mut TranslationMemory>,
+) -> EscalationSummary {
+    let mut summary = EscalationSummary::default();
+    let codes = ControlCodes::from_ruleset(ruleset);
+    for entry in results.iter_mut() {
+        if batch.is_cancelled() {
+            break;
+        }
+        let signals: Vec<HardLineSignal> = hard_line_signals(entry, batch.target_language, ruleset, &codes, settings);
+        if signals.is_empty() {
+            continue;
+        }
+        summary.hard_lines += 1;
+        let mut context = batch.prompt_context_for(&entry.original_text, &entry.source_file, &entry.json_path);
+        let request = mask_control_codes(Some(&codes), &entry.original_text, &mut context);
+        match batch.request(premium, &request, &context, (&entry.source_file, &entry.json_path)).await {
+            Ok(translation) => {
+                entry.translated_text = request.unmask(&translation.text);
+                entry.translation_source = translation.provider;
+                entry.error = None;
+                entry.status = TranslationStatus::MachineTranslated;
+                apply_glossary(entry, batch.glossary);
+                sanitize_translation(entry);
+                summary.escalated += 1;
+                if let Some(memory) = memory.as_deref_mut() {
+                    batch.remember(memory, entry);
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "Escalation of {} {} ({:?}) failed: {}",
+                    entry.source_file, entry.json_path, signals, e
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use crate::models::glossary::GlossaryTerm;
+    use crate::models::provider::ProviderConfig;
+    use crate::models::reference::ReferenceLink;
+    use crate::services::translation_provider::{PartialTextSink, ProviderTranslation, GENERATION_ABORTED_ERROR};
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    // Provider returning a fixed answer per input, or an error for unknown inputs.
+    struct FakeProvider {
+        name: &'static str,
+        answers: Vec<(&'static str, &'static str)>,
+    }
+
+    #[async_trait]
+    impl TranslationProvider for FakeProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn translate(&self, text: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            self.answers
+                .iter()
+                .find(|(src, _)| *src == text)
+                .map(|(_, out)| ProviderTranslation { text: out.to_string(), provider: self.name.to_string() })
+                .ok_or_else(|| format!("no answer for {}", text))
+        }
+    }
+
+    fn batch<'a>(glossary: &'a Glossary, references: &'a ReferenceIndex) -> BatchContext<'a> {
+        BatchContext {
+            source_language: "ja",
+            target_language: "en",
+            glossary,
+            references,
+            include_references: true,
+            cancellation: None,
+            concurrency: 1,
+            unit_modes: None,
+            control_codes: None,
+            prompt_templates: None,
+            dialogue_blocks: true,
+            surrounding_lines: None,
+            scope_summaries: None,
+            on_partial: None,
+            catchphrases: None,
+            skip_target_language: false,
+            preset: None,
+            characters: None,
+        }
+    }
+
+    fn source(text: &str, path: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 1,
+            original_text: text.to_string(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: path.to_string(),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_hard_lines_are_escalated() {
+        let cheap = FakeProvider { name: "cheap", answers: vec![("はい", "Yes"), ("いいえ", "いいえ")] };
+        let premium = FakeProvider {
+            name: "premium",
+            answers: vec![("いいえ", "No"), ("こんにちは", "Hello")],
+        };
+        let entries = vec![source("はい", "a"), source("いいえ", "b"), source("こんにちは", "c"), source("ねこ", "d")];
+
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let mut results = translate_entries(&cheap, entries, &batch(&glossary, &references), None, None).await;
+        assert_eq!(results.iter().filter(|r| r.error.is_some()).count(), 2);
+
+        let settings = EscalationSettings {
+            premium_provider: ProviderConfig::Ollama,
+            on_validation_failure: true,
+            on_provider_error: true,
+            on_low_confidence: true,
+            long_source_chars: 0,
+        };
+        let summary = escalate_hard_lines(
+            &mut results,
+            &premium,
+            &ValidationRuleSet::default(),
+            &settings,
+            &batch(&glossary, &references),
+            None,
+        )
+        .await;
+
+        assert_eq!(summary, EscalationSummary { hard_lines: 3, escalated: 2, failed: 1 });
+        assert_eq!((results[0].translated_text.as_str(), results[0].translation_source.as_str()), ("Yes", "cheap"));
+        assert_eq!((results[1].translated_text.as_str(), results[1].translation_source.as_str()), ("No", "premium"));
+        assert_eq!(results[2].translated_text, "Hello");
+        assert!(results[3].error.is_some(), "Failed escalation keeps the first-pass result");
+    }
+
+    #[tokio::test]
+    async fn test_escalated_lines_keep_their_control_codes() {
+        let line = r"\AF[Harold]\C[2]はい\C[0]";
+        let premium = FakeProvider { name: "premium", answers: vec![(r"⟦1⟧\C[2]はい\C[0]", r"⟦1⟧\C[2]Yes\C[0]")] };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let mut results = vec![WorkingTranslation {
+            object_id: 1,
+            original_text: line.to_string(),
+            translated_text: String::new(),
+            source_file: "www/data/Map001.json".to_string(),
+            json_path: "a".to_string(),
+            translation_source: "cheap".to_string(),
+            error: Some("timeout".to_string()),
+            status: TranslationStatus::Untranslated,
+        }];
+        let ruleset = ValidationRuleSet { control_codes: vec![r"\\AF\[[^\]]+\]".to_string()], ..Default::default() };
+        let settings = EscalationSettings {
+            premium_provider: ProviderConfig::Ollama,
+            on_validation_failure: true,
+            on_provider_error: true,
+            on_low_confidence: false,
+            long_source_chars: 0,
+        };
+        let batch = batch(&glossary, &references);
+        let summary = escalate_hard_lines(&mut results, &premium, &ruleset, &settings, &batch, None).await;
+
+        assert_eq!(summary.escalated, 1);
+        assert_eq!(results[0].translated_text, r"\AF[Harold]\C[2]Yes\C[0]");
+    }
+
+    #[tokio::test]
+    async fn test_translation_memory_is_consulted_first() {
+        let provider = FakeProvider { name: "cheap", answers: vec![("はい", "Yes"), ("勇者", "Hero")] };
+        let mut memory = TranslationMemory::open_in_memory().unwrap();
+        memory.store("ja", "en", "勇者", "Champion", "deepl").unwrap();
+
+        let entries = vec![source("はい", "a"), source("勇者", "b"), source("はい", "c")];
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let results = translate_entries(&provider, entries, &batch(&glossary, &references), Some(&mut memory), None).await;
+
+        assert_eq!(results[0].translation_source, "cheap");
+        assert_eq!((results[1].translated_text.as_str(), results[1].translation_source.as_str()), ("Champion", "memory:deepl"));
+        // The repeat within the same batch is answered from memory.
+        assert_eq!((results[2].translated_text.as_str(), results[2].translation_source.as_str()), ("Yes", "memory:cheap"));
+        assert_eq!(memory.len().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_progress_reports_stage_timings() {
+        let provider = FakeProvider { name: "cheap", answers: vec![("はい", "Yes")] };
+        let mut memory = TranslationMemory::open_in_memory().unwrap();
+        memory.store("ja", "en", "勇者", "Hero", "deepl").unwrap();
+        let entries = vec![source("はい", "a"), source("勇者", "b")];
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+
+        let mut timings = Vec::new();
+        let mut on_progress = |_: &BatchProgress, _: &WorkingTranslation, t: &EntryTimings| timings.push(t.clone());
+        let batch = batch(&glossary, &references);
+        translate_entries(&provider, entries, &batch, Some(&mut memory), Some(&mut on_progress)).await;
+
+        let translated = timings.iter().find(|t| t.json_path == "a").unwrap();
+        assert!(!translated.from_memory);
+        assert!(translated.memory_lookup_ms.is_some() && translated.provider_ms.is_some());
+        assert!(translated.prompt_build_ms.is_some() && translated.post_process_ms.is_some());
+        let remembered = timings.iter().find(|t| t.json_path == "b").unwrap();
+        assert!(remembered.from_memory && remembered.provider_ms.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_glossary_and_references_reach_the_prompt() {
+        let provider = FakeProvider { name: "cheap", answers: vec![("ハロルドが来た", "ハロルド has arrived")] };
+        let glossary = Glossary {
+            terms: vec![GlossaryTerm {
+                source: "ハロルド".to_string(),
+                target: "Harold".to_string(),
+                references: vec![ReferenceLink { url: None, note: Some("The king".to_string()) }],
+            }],
+        };
+        let references = ReferenceIndex::from([(
+            ("www/data/Map001.json".to_string(), "a".to_string()),
+            vec![ReferenceLink { url: Some("https://example.org/wiki/Harold".to_string()), note: None }],
+        )]);
+        let mut batch = batch(&glossary, &references);
+        let context = batch.prompt_context_for("ハロルドが来た", "www/data/Map001.json", "a");
+        assert_eq!(context.glossary, glossary.terms);
+        assert_eq!(context.references.len(), 1);
+
+        batch.include_references = false;
+        let context = batch.prompt_context_for("ハロルドが来た", "www/data/Map001.json", "a");
+        assert!(context.references.is_empty() && context.glossary[0].references.is_empty());
+
+        let results = translate_entries(&provider, vec![source("ハロルドが来た", "a")], &batch, None, None).await;
+        assert_eq!(results[0].translated_text, "Harold has arrived");
+    }
+
+    #[tokio::test]
+    async fn test_progress_is_reported_per_entry() {
+        let provider = FakeProvider { name: "cheap", answers: vec![("はい", "Yes")] };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let entries = vec![source("はい", "a"), source("いいえ", "b")];
+
+        let mut reports: Vec<BatchProgress> = Vec::new();
+        let mut on_progress = |p: &BatchProgress, _: &WorkingTranslation, _: &EntryTimings| reports.push(p.clone());
+        translate_entries(&provider, entries, &batch(&glossary, &references), None, Some(&mut on_progress)).await;
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!((reports[0].processed, reports[0].succeeded, reports[0].failed), (1, 1, 0));
+        assert_eq!((reports[1].processed, reports[1].total, reports[1].failed), (2, 2, 1));
+        assert_eq!(reports[1].current_file, "www/data/Map001.json");
+        assert_eq!(reports[1].eta_ms, Some(0));
+        assert_eq!(remaining_ms(999, 1000, 1000), 999);
+        assert_eq!(remaining_ms(u64::MAX, 1, 2), u64::MAX);
+    }
+
+    // Streams its answer to "ハロルドが来た" word by word.
+    struct StreamingProvider;
+
+    #[async_trait]
+    impl TranslationProvider for StreamingProvider {
+        fn name(&self) -> &str {
+            "stream"
+        }
+
+        async fn translate(&self, _: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            Ok(ProviderTranslation { text: "Harold has arrived".to_string(), provider: "stream".to_string() })
+        }
+
+        async fn translate_streaming(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: &PromptContext,
+            on_partial: &PartialTextSink<'_>,
+        ) -> Result<ProviderTranslation, String> {
+            for partial in ["Harold", "Harold has", "Harold has arrived"] {
+                if !on_partial(partial) {
+                    return Err(GENERATION_ABORTED_ERROR.to_string());
+                }
+            }
+            self.translate("", "", "").await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streamed_text_is_reported_and_can_be_aborted() {
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let partials = std::sync::Mutex::new(Vec::new());
+        let collect = |partial: &PartialTranslation| {
+            partials.lock().unwrap().push(partial.text.clone());
+            partial.json_path != "b"
+        };
+        let batch = BatchContext { on_partial: Some(&collect), ..batch(&glossary, &references) };
+        let entries = vec![source("ハロルドが来た", "a"), source("ハロルドが来た", "b")];
+
+        let results = translate_entries(&StreamingProvider, entries, &batch, None, None).await;
+        assert_eq!(results[0].translated_text, "Harold has arrived");
+        assert_eq!(results[1].error.as_deref(), Some(GENERATION_ABORTED_ERROR));
+        assert_eq!(*partials.lock().unwrap(), vec!["Harold", "Harold has", "Harold has arrived", "Harold"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_batch_returns_finished_entries() {
+        let provider = FakeProvider { name: "cheap", answers: vec![("はい", "Yes"), ("いいえ", "No")] };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let token = CancellationToken::default();
+        let batch = BatchContext { cancellation: Some(&token), ..batch(&glossary, &references) };
+        let entries = vec![source("はい", "a"), source("いいえ", "b"), source("はい", "c")];
+
+        let cancel_after_first = token.clone();
+        let mut on_progress = |_: &BatchProgress, _: &WorkingTranslation, _: &EntryTimings| cancel_after_first.cancel();
+        let results = translate_entries(&provider, entries, &batch, None, Some(&mut on_progress)).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].translated_text, "Yes");
+    }
+
+    #[tokio::test]
+    async fn test_translate_until_stops_at_deadline() {
+        let provider = FakeProvider { name: "cheap", answers: vec![("はい", "Yes")] };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let batch = batch(&glossary, &references);
+
+        let later = tokio::time::Instant::now() + std::time::Duration::from_secs(60);
+        let (results, timed_out) = translate_until(&provider, vec![source("はい", "a")], &batch, later).await;
+        assert_eq!((results.len(), timed_out), (1, false));
+
+        struct HangingProvider;
+        #[async_trait]
+        impl TranslationProvider for HangingProvider {
+            fn name(&self) -> &str {
+                "hanging"
+            }
+            async fn translate(&self, _: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+                std::future::pending().await
+            }
+        }
+        let soon = tokio::time::Instant::now() + std::time::Duration::from_millis(20);
+        let entries = vec![source("はい", "a"), source("いいえ", "b")];
+        let (results, timed_out) = translate_until(&HangingProvider, entries, &batch, soon).await;
+        assert_eq!((results.len(), timed_out), (0, true));
+    }
+
+    // Answers "[text]" after a delay given by the text, counting requests and peak concurrency.
+    #[derive(Default)]
+    struct DelayedProvider {
+        calls: AtomicUsize,
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TranslationProvider for DelayedProvider {
+        fn name(&self) -> &str {
+            "delayed"
+        }
+
+        async fn translate(&self, text: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            self.calls.fetch_add(1, SeqCst);
+            let now = self.in_flight.fetch_add(1, SeqCst) + 1;
+            self.peak.fetch_max(now, SeqCst);
+            let delay = if text.starts_with("遅") { 60 } else { 5 };
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, SeqCst);
+            Ok(ProviderTranslation { text: format!("[{}]", text), provider: "delayed".to_string() })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_batch_keeps_order_and_deduplicates() {
+        let provider = DelayedProvider::default();
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let batch = BatchContext { concurrency: 3, ..batch(&glossary, &references) };
+        let mut memory = TranslationMemory::open_in_memory().unwrap();
+        let entries = vec![source("遅い", "a"), source("はい", "b"), source("いいえ", "c"), source("はい", "d")];
+
+        let results = translate_entries(&provider, entries, &batch, Some(&mut memory), None).await;
+
+        let paths: Vec<&str> = results.iter().map(|r| r.json_path.as_str()).collect();
+        assert_eq!(paths, vec!["a", "b", "c", "d"]);
+        assert_eq!(results[3].translated_text, "[はい]");
+        assert_eq!(results[3].translation_source, "memory:delayed");
+        // The repeated "はい" waited for the first request instead of sending its own.
+        assert_eq!(provider.calls.load(SeqCst), 3);
+        assert_eq!(provider.peak.load(SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_paragraph_units_are_translated_separately() {
+        let provider = FakeProvider {
+            name: "cheap",
+            answers: vec![("制作", "Staff"), ("音楽\n山田", "Music\nYamada"), ("おわり", "The End")],
+        };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let modes = UnitModes::new(UnitMode::Paragraphs, Vec::new());
+        let paragraphs = BatchContext { unit_modes: Some(&modes), ..batch(&glossary, &references) };
+
+        let (credits, timings) =
+            translate_entry_timed(&provider, source("制作\n\n音楽\n山田\n\n\nおわり", "a"), &paragraphs).await;
+        assert_eq!(credits.translated_text, "Staff\n\nMusic\nYamada\n\n\nThe End");
+        assert!(credits.error.is_none());
+        assert!(timings.provider_ms.is_some());
+
+        let failed = translate_entry(&provider, source("制作\n\n不明", "b"), &paragraphs).await;
+        assert!(failed.error.is_some());
+        assert!(failed.translated_text.is_empty());
+
+        // As one block, the text is sent whole (and this provider has no answer for it).
+        let block = batch(&glossary, &references);
+        assert!(translate_entry(&provider, source("制作\n\nおわり", "c"), &block).await.error.is_some());
+    }
+
+    fn system_message(text: &str, key: &str) -> SourceStringData {
+        SourceStringData {
+            object_id: 0,
+            original_text: text.to_string(),
+            source_file: "www/data/System.json".to_string(),
+            json_path: format!("terms.messages.{}", key),
+            dialogue_block_id: None,
+            speaker: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_battle_start_messages_are_translated_together() {
+        let provider = FakeProvider {
+            name: "cheap",
+            answers: vec![
+                ("[1] %1が出現！\n[2] %1は先手を取った！", "[1] %1 appeared!\n[2] %1 got the upper hand!"),
+                ("はい", "Yes"),
+            ],
+        };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let batch = batch(&glossary, &references);
+        let context = batch.prompt_context_for("%1が出現！", "www/data/System.json", "terms.messages.emerge");
+        assert_eq!(context.instructions.len(), 1);
+
+        let entries = vec![
+            source("はい", "a"),
+            system_message("%1が出現！", "emerge"),
+            system_message("%1は先手を取った！", "preemptive"),
+        ];
+        let results = translate_entries(&provider, entries, &batch, None, None).await;
+        let texts: Vec<&str> = results.iter().map(|r| r.translated_text.as_str()).collect();
+        assert_eq!(texts, vec!["Yes", "%1 appeared!", "%1 got the upper hand!"]);
+    }
+
+    #[tokio::test]
+    async fn test_group_falls_back_to_single_requests() {
+        let provider = FakeProvider {
+            name: "cheap",
+            answers: vec![
+                ("[1] %1の勝利！\n[2] %1は全滅した…", "%1 won! %1 was defeated..."),
+                ("%1の勝利！", "%1 is victorious!"),
+            ],
+        };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let entries = vec![system_message("%1の勝利！", "victory"), system_message("%1は全滅した…", "defeat")];
+        let results = translate_entries(&provider, entries, &batch(&glossary, &references), None, None).await;
+        assert_eq!(results[0].translated_text, "%1 is victorious!");
+        assert!(results[1].error.is_some());
+
+        assert_eq!(split_group_response("[1] A\n  more\n[2] B\n", 2), Some(vec!["A\n  more".to_string(), "B".to_string()]));
+        assert_eq!(split_group_response("Sure! [1] A", 1), None);
+    }
+
+    #[tokio::test]
+    async fn test_message_box_lines_are_translated_as_one_block() {
+        let provider = FakeProvider {
+            name: "cheap",
+            answers: vec![
+                ("[1] 北の森には\n[2] 魔物が出る。", "[1] Monsters roam\n[2] the northern forest."),
+                ("はい", "Yes"),
+                ("北の森には", "In the northern forest"),
+            ],
+        };
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let line = |text: &str, path: &str| SourceStringData {
+            dialogue_block_id: Some("www/data/Map001.json:events[1].pages[0].list[0]".to_string()),
+            ..source(text, path)
+        };
+        let entries = vec![
+            line("北の森には", "events[1].pages[0].list[1].parameters[0]"),
+            source("はい", "a"),
+            line("魔物が出る。", "events[1].pages[0].list[2].parameters[0]"),
+        ];
+        let results = translate_entries(&provider, entries.clone(), &batch(&glossary, &references), None, None).await;
+        let texts: Vec<&str> = results.iter().map(|r| r.translated_text.as_str()).collect();
+        assert_eq!(texts, vec!["Monsters roam", "Yes", "the northern forest."]);
+
+        // Line by line, the second line has no answer.
+        let single = BatchContext { dialogue_blocks: false, ..batch(&glossary, &references) };
+        let results = translate_entries(&provider, entries, &single, None, None).await;
+        assert_eq!(results[0].translated_text, "In the northern forest");
+        assert!(results[2].error.is_some());
+    }
+
+    #[test]
+    fn test_surrounding_lines_stay_within_the_event() {
+        let line = |event: u32, index: usize, text: &str| {
+            source(text, &format!("events[{}].pages[0].list[{}].parameters[0]", event, index))
+        };
+        let entries = vec![
+            line(1, 0, "おはよう。"),
+            line(1, 1, "北の森には"),
+            line(1, 2, "魔物が出る。"),
+            line(1, 3, "気をつけて。"),
+            line(2, 0, "この先、魔物注意。"),
+            source("村", "displayName"),
+        ];
+        let surrounding = SurroundingLines::new(&entries, 1);
+        assert_eq!(surrounding.around(&entries[1], &entries[2]), (vec!["おはよう。".to_string()], vec!["気をつけて。".to_string()]));
+        assert_eq!(surrounding.around(&entries[3], &entries[3]), (vec!["魔物が出る。".to_string()], Vec::new()));
+        assert_eq!(surrounding.around(&entries[4], &entries[4]), (Vec::new(), Vec::new()));
+        assert_eq!(surrounding.around(&entries[5], &entries[5]), (Vec::new(), Vec::new()));
+
+        let (glossary, references) = (Glossary::default(), ReferenceIndex::new());
+        let batch = BatchContext { surrounding_lines: Some(&surrounding), ..batch(&glossary, &references) };
+        let (_, context) = batch.masked_request("北の森には", &entries[1..2]);
+        let prompt = context.render();
+        assert!(prompt.contains("Lines before this text, for context only (do not translate them):\n- おはよう。\n"));
+        assert!(prompt.contains("Lines after this text, for context only (do not translate them):\n- 魔物が出る。\n"));
+        assert!(SurroundingLines::new(&entries, 0).around(&entries[1], &entries[1]).0.is_empty());
+    }
+}
@@ -0,0 +1,331 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use crate::core::prompts::language_family;
+use crate::models::provider::DeeplSettings;
+use crate::services::translation_provider::{ProviderTranslation, TranslationProvider};
+
+const DEEPL_FREE_API_URL: &str = "https://api-free.deepl.com/v2";
+const DEEPL_PRO_API_URL: &str = "https://api.deepl.com/v2";
+
+// Languages DeepL accepts as `source_lang` (upper-case, no region variant).
+const SUPPORTED_SOURCE_LANGS: &[&str] = &[
+    "AR", "BG", "CS", "DA", "DE", "EL", "EN", "ES", "ET", "FI", "FR", "HU", "ID", "IT", "JA", "KO",
+    "LT", "LV", "NB", "NL", "PL", "PT", "RO", "RU", "SK", "SL", "SV", "TR", "UK", "ZH",
+];
+
+// Languages DeepL accepts as `target_lang`. EN and PT must carry a region variant.
+const SUPPORTED_TARGET_LANGS: &[&str] = &[
+    "AR", "BG", "CS", "DA", "DE", "EL", "EN-GB", "EN-US", "ES", "ET", "FI", "FR", "HU", "ID", "IT",
+    "JA", "KO", "LT", "LV", "NB", "NL", "PL", "PT-BR", "PT-PT", "RO", "RU", "SK", "SL", "SV", "TR",
+    "UK", "ZH", "ZH-HANS", "ZH-HANT",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeeplError {
+    // DeepL cannot translate between these languages; callers may fall back to another provider.
+    UnsupportedLanguagePair(String),
+    Request(String),
+}
+
+impl std::fmt::Display for DeeplError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeeplError::UnsupportedLanguagePair(s) => write!(f, "DeepL does not support this language pair: {}", s),
+            DeeplError::Request(s) => write!(f, "DeepL request failed: {}", s),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct DeeplTranslateRequest<'a> {
+    text: Vec<&'a str>,
+    target_lang: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    glossary_id: Option<&'a str>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeeplTranslateResponse {
+    translations: Vec<DeeplTranslation>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeeplTranslation {
+    text: String,
+}
+
+#[derive(Serialize, Debug)]
+struct DeeplGlossaryRequest<'a> {
+    name: &'a str,
+    source_lang: String,
+    target_lang: String,
+    entries: String,
+    entries_format: &'static str,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeeplGlossaryResponse {
+    glossary_id: String,
+}
+
+/// Maps the app's language codes (e.g. "en", "ja") to a DeepL source language code.
+pub fn to_deepl_source_lang(code: &str) -> Option<String> {
+    let base = language_family(code).to_uppercase();
+    if SUPPORTED_SOURCE_LANGS.contains(&base.as_str()) {
+        Some(base)
+    } else {
+        None
+    }
+}
+
+/// Maps the app's language codes to a DeepL target language code, adding the region variant
+/// DeepL requires for English and Portuguese.
+pub fn to_deepl_target_lang(code: &str) -> Option<String> {
+    let upper = code.replace('_', "-").to_uppercase();
+    let mapped = match upper.as_str() {
+        "EN" => "EN-US".to_string(),
+        "PT" => "PT-PT".to_string(),
+        _ => upper,
+    };
+    if SUPPORTED_TARGET_LANGS.contains(&mapped.as_str()) {
+        Some(mapped)
+    } else {
+        None
+    }
+}
+
+pub fn api_base_url(settings: &DeeplSettings) -> &'static str {
+    if settings.uses_free_api() {
+        DEEPL_FREE_API_URL
+    } else {
+        DEEPL_PRO_API_URL
+    }
+}
+
+fn resolve_language_pair(source_lang: &str, target_lang: &str) -> Result<(String, String), DeeplError> {
+    match (to_deepl_source_lang(source_lang), to_deepl_target_lang(target_lang)) {
+        (Some(source), Some(target)) => Ok((source, target)),
+        _ => Err(DeeplError::UnsupportedLanguagePair(format!("{} -> {}", source_lang, target_lang))),
+    }
+}
+
+// DeepL answers 400 with a message like "Value for 'target_lang' not supported." for unknown languages.
+fn is_language_rejection(status: reqwest::StatusCode, body: &str) -> bool {
+    status == reqwest::StatusCode::BAD_REQUEST
+        && (body.contains("target_lang") || body.contains("source_lang"))
+        && body.contains("not supported")
+}
+
+pub struct DeeplClient {
+    settings: DeeplSettings,
+    http: reqwest::Client,
+    // Provider used when DeepL rejects the language pair.
+    fallback: Option<Box<dyn TranslationProvider>>,
+}
+
+impl DeeplClient {
+    pub fn new(settings: DeeplSettings, fallback: Option<Box<dyn TranslationProvider>>) -> Self {
+        DeeplClient {
+            settings,
+            http: reqwest::Client::new(),
+            fallback,
+        }
+    }
+
+    pub async fn translate_with_deepl(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> Result<String, DeeplError> {
+        if self.settings.api_key.trim().is_empty() {
+            return Err(DeeplError::Request("No DeepL API key configured.".to_string()));
+        }
+        let (source, target) = resolve_language_pair(source_lang, target_lang)?;
+
+        let body = DeeplTranslateRequest {
+            text: vec![text],
+            target_lang: target,
+            source_lang: Some(source),
+            glossary_id: self.settings.glossary_id.as_deref().filter(|id| !id.is_empty()),
+        };
+
+        let response = self
+            .http
+            .post(format!("{}/translate", api_base_url(&self.settings)))
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.settings.api_key))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| DeeplError::Request(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            if is_language_rejection(status, &error_body) {
+                return Err(DeeplError::UnsupportedLanguagePair(error_body));
+            }
+            return Err(DeeplError::Request(format!("HTTP {}: {}", status, error_body)));
+        }
+
+        let parsed: DeeplTranslateResponse = response
+            .json()
+            .await
+            .map_err(|e| DeeplError::Request(format!("Invalid response: {}", e)))?;
+        parsed
+            .translations
+            .into_iter()
+            .next()
+            .map(|t| t.text)
+            .ok_or_else(|| DeeplError::Request("Response contained no translations.".to_string()))
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for DeeplClient {
+    fn name(&self) -> &str {
+        "deepl"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> Result<ProviderTranslation, String> {
+        match self.translate_with_deepl(text, source_lang, target_lang).await {
+            Ok(translated) => Ok(ProviderTranslation { text: translated, provider: self.name().to_string() }),
+            Err(DeeplError::UnsupportedLanguagePair(reason)) => match &self.fallback {
+                Some(fallback) => {
+                    println!("DeepL rejected {} -> {} ({}), falling back to {}", source_lang, target_lang, reason, fallback.name());
+                    fallback.translate(text, source_lang, target_lang).await
+                }
+                None => Err(DeeplError::UnsupportedLanguagePair(reason).to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    // DeepL itself needs no warm-up, but a local fallback would start cold.
+    async fn warm_up(&self) -> Result<(), String> {
+        match &self.fallback {
+            Some(fallback) => fallback.warm_up().await,
+            None => Ok(()),
+        }
+    }
+
+    // DeepL has no models; /usage checks the key without spending characters.
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        if self.settings.api_key.trim().is_empty() {
+            return Err("No DeepL API key configured.".to_string());
+        }
+        let response = self
+            .http
+            .get(format!("{}/usage", api_base_url(&self.settings)))
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.settings.api_key))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach DeepL: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Err(format!("DeepL returned HTTP {} for /usage: {}", status, error_body));
+        }
+        Ok(None)
+    }
+}
+
+/// Creates a glossary on the DeepL account from (source term, target term) pairs and returns its id,
+/// which can then be stored in `DeeplSettings::glossary_id`.
+pub async fn create_glossary(
+    settings: &DeeplSettings,
+    name: &str,
+    source_lang: &str,
+    target_lang: &str,
+    entries: &[(String, String)],
+) -> Result<String, DeeplError> {
+    // Glossaries use plain language codes without region variants (e.g. "en", not "EN-US").
+    let source = to_deepl_source_lang(source_lang)
+        .ok_or_else(|| DeeplError::UnsupportedLanguagePair(source_lang.to_string()))?;
+    let target = to_deepl_source_lang(target_lang)
+        .ok_or_else(|| DeeplError::UnsupportedLanguagePair(target_lang.to_string()))?;
+
+    let tsv = entries
+        .iter()
+        .filter(|(src, tgt)| !src.trim().is_empty() && !tgt.trim().is_empty())
+        .map(|(src, tgt)| format!("{}\t{}", src.replace(['\t', '\n'], " "), tgt.replace(['\t', '\n'], " ")))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let body = DeeplGlossaryRequest {
+        name,
+        source_lang: source.to_lowercase(),
+        target_lang: target.to_lowercase(),
+        entries: tsv,
+        entries_format: "tsv",
+    };
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/glossaries", api_base_url(settings)))
+        .header("Authorization", format!("DeepL-Auth-Key {}", settings.api_key))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| DeeplError::Request(e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        return Err(DeeplError::Request(format!("HTTP {}: {}", status, error_body)));
+    }
+
+    let parsed: DeeplGlossaryResponse = response
+        .json()
+        .await
+        .map_err(|e| DeeplError::Request(format!("Invalid glossary response: {}", e)))?;
+    Ok(parsed.glossary_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_code_mapping() {
+        assert_eq!(to_deepl_source_lang("ja"), Some("JA".to_string()));
+        assert_eq!(to_deepl_source_lang("en-US"), Some("EN".to_string()));
+        assert_eq!(to_deepl_target_lang("en"), Some("EN-US".to_string()));
+        assert_eq!(to_deepl_target_lang("pt_br"), Some("PT-BR".to_string()));
+        assert_eq!(to_deepl_target_lang("zh"), Some("ZH".to_string()));
+        assert_eq!(to_deepl_target_lang("vi"), None);
+    }
+
+    #[test]
+    fn test_unsupported_pair_is_reported() {
+        assert_eq!(resolve_language_pair("ja", "en"), Ok(("JA".to_string(), "EN-US".to_string())));
+        match resolve_language_pair("ja", "vi") {
+            Err(DeeplError::UnsupportedLanguagePair(_)) => {}
+            other => panic!("Expected UnsupportedLanguagePair, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_api_base_url_selection() {
+        let mut settings = DeeplSettings { api_key: "abc:fx".to_string(), ..Default::default() };
+        assert_eq!(api_base_url(&settings), DEEPL_FREE_API_URL);
+        settings.api_key = "abc".to_string();
+        assert_eq!(api_base_url(&settings), DEEPL_PRO_API_URL);
+        settings.use_free_api = Some(true);
+        assert_eq!(api_base_url(&settings), DEEPL_FREE_API_URL);
+    }
+
+    #[test]
+    fn test_language_rejection_detection() {
+        let body = r#"{"message":"Value for 'target_lang' not supported."}"#;
+        assert!(is_language_rejection(reqwest::StatusCode::BAD_REQUEST, body));
+        assert!(!is_language_rejection(reqwest::StatusCode::FORBIDDEN, body));
+        assert!(!is_language_rejection(reqwest::StatusCode::BAD_REQUEST, "Bad request"));
+    }
+}
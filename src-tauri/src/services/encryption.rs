@@ -0,0 +1,220 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::Serialize;
+use crate::error::CoreError;
+use crate::utils::project_paths::{app_data_dir, ENCRYPTED_EXTENSION};
+
+// Encrypted file layout: MAGIC | salt (16 bytes) | nonce (12 bytes) | ciphertext+tag.
+const MAGIC: &[u8; 7] = b"TAIENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLockState {
+    pub locked: bool,
+    pub encrypted_files: usize,
+    pub plain_files: usize,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, CoreError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| CoreError::Custom(format!("Failed to derive encryption key: {}", e)))?;
+    Ok(Key::from(key))
+}
+
+/// Encrypts `plaintext` with a key derived from `passphrase`. A fresh salt and nonce are used per call.
+pub fn encrypt_bytes(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, CoreError> {
+    if passphrase.is_empty() {
+        return Err(CoreError::Config("Passphrase must not be empty".to_string()));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| CoreError::Custom("Encryption failed".to_string()))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(nonce.as_slice());
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Returns true if `data` starts with the encrypted file header.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn decrypt_bytes(data: &[u8], passphrase: &str) -> Result<Vec<u8>, CoreError> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if !is_encrypted(data) || data.len() < header_len {
+        return Err(CoreError::Custom("Data is not an encrypted session file".to_string()));
+    }
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + SALT_LEN..header_len]);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(nonce, &data[header_len..])
+        .map_err(|_| CoreError::Custom("Wrong passphrase or corrupted encrypted file".to_string()))
+}
+
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>, CoreError> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(Result::ok) {
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_encrypted_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == ENCRYPTED_EXTENSION)
+}
+
+pub fn session_lock_state(project_path: &str) -> Result<SessionLockState, CoreError> {
+    let files = list_files(&app_data_dir(project_path))?;
+    let encrypted_files = files.iter().filter(|p| is_encrypted_path(p)).count();
+    let plain_files = files.len() - encrypted_files;
+    Ok(SessionLockState { locked: encrypted_files > 0 && plain_files == 0, encrypted_files, plain_files })
+}
+
+/// Encrypts every plain file in the project's `.translate-ai` folder (sessions, rulesets, caches)
+/// and removes the plaintext. All files are encrypted in memory first so a failure leaves the
+/// folder untouched. Refused if the folder already holds encrypted files, which could be under
+/// another passphrase.
+pub fn lock_session(project_path: &str, passphrase: &str) -> Result<SessionLockState, CoreError> {
+    let files = list_files(&app_data_dir(project_path))?;
+    if files.iter().any(|path| is_encrypted_path(path)) {
+        return Err(CoreError::Config("The session is already locked; unlock it before locking it again".to_string()));
+    }
+    let mut pending = Vec::new();
+    for path in files {
+        let plaintext = fs::read(&path)?;
+        pending.push((path, encrypt_bytes(&plaintext, passphrase)?));
+    }
+
+    for (path, ciphertext) in pending {
+        let mut encrypted_name = path.clone().into_os_string();
+        encrypted_name.push(".");
+        encrypted_name.push(ENCRYPTED_EXTENSION);
+        fs::write(PathBuf::from(encrypted_name), ciphertext)?;
+        fs::remove_file(&path)?;
+    }
+    session_lock_state(project_path)
+}
+
+/// Decrypts all `.enc` files in the project's `.translate-ai` folder. Fails without writing anything
+/// if any file cannot be decrypted with the passphrase, or if a decrypted file would replace a
+/// plain file written since the session was locked.
+pub fn unlock_session(project_path: &str, passphrase: &str) -> Result<SessionLockState, CoreError> {
+    let mut pending = Vec::new();
+    for path in list_files(&app_data_dir(project_path))? {
+        if !is_encrypted_path(&path) {
+            continue;
+        }
+        if path.with_extension("").exists() {
+            return Err(CoreError::Config(format!(
+                "{:?} was written while the session was locked; move it away before unlocking",
+                path.with_extension("")
+            )));
+        }
+        let data = fs::read(&path)?;
+        pending.push((path, decrypt_bytes(&data, passphrase)?));
+    }
+
+    for (path, plaintext) in pending {
+        fs::write(path.with_extension(""), plaintext)?;
+        fs::remove_file(&path)?;
+    }
+    session_lock_state(project_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::validation::{save_project_ruleset, PROJECT_RULESET_FILE_NAME};
+    use crate::models::validation::ValidationRuleSet;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let encrypted = encrypt_bytes(b"secret script", "hunter2").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt_bytes(&encrypted, "hunter2").unwrap(), b"secret script");
+        assert!(decrypt_bytes(&encrypted, "wrong").is_err());
+        assert!(encrypt_bytes(b"x", "").is_err());
+    }
+
+    #[test]
+    fn test_lock_and_unlock_app_data_folder() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        let data_dir = app_data_dir(project_path);
+        fs::create_dir_all(data_dir.join("sessions")).unwrap();
+        fs::write(data_dir.join("validation_rules.json"), "{}").unwrap();
+        fs::write(data_dir.join("sessions").join("session.json"), "[1,2,3]").unwrap();
+
+        let locked = lock_session(project_path, "pass").unwrap();
+        assert_eq!(locked, SessionLockState { locked: true, encrypted_files: 2, plain_files: 0 });
+        assert!(!data_dir.join("validation_rules.json").exists());
+
+        assert!(unlock_session(project_path, "nope").is_err());
+        assert!(session_lock_state(project_path).unwrap().locked, "Failed unlock must not touch files");
+
+        let unlocked = unlock_session(project_path, "pass").unwrap();
+        assert!(!unlocked.locked);
+        assert_eq!(fs::read_to_string(data_dir.join("sessions").join("session.json")).unwrap(), "[1,2,3]");
+    }
+
+    #[test]
+    fn test_writes_while_locked_are_refused() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        let data_dir = app_data_dir(project_path);
+        save_project_ruleset(project_path, &ValidationRuleSet::default()).unwrap();
+        let saved = fs::read_to_string(data_dir.join(PROJECT_RULESET_FILE_NAME)).unwrap();
+        lock_session(project_path, "pass").unwrap();
+
+        let changed = ValidationRuleSet { check_placeholders: false, ..Default::default() };
+        assert!(save_project_ruleset(project_path, &changed).is_err());
+        assert!(!data_dir.join(PROJECT_RULESET_FILE_NAME).exists());
+
+        // A file put there by other means is kept, and the unlock is refused.
+        fs::write(data_dir.join(PROJECT_RULESET_FILE_NAME), "newer").unwrap();
+        assert!(unlock_session(project_path, "pass").is_err());
+        assert_eq!(fs::read_to_string(data_dir.join(PROJECT_RULESET_FILE_NAME)).unwrap(), "newer");
+        fs::remove_file(data_dir.join(PROJECT_RULESET_FILE_NAME)).unwrap();
+        unlock_session(project_path, "pass").unwrap();
+        assert_eq!(fs::read_to_string(data_dir.join(PROJECT_RULESET_FILE_NAME)).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_locking_twice_is_refused() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_str().unwrap();
+        let data_dir = app_data_dir(project_path);
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("session.json"), "{}").unwrap();
+
+        lock_session(project_path, "first").unwrap();
+        assert!(lock_session(project_path, "second").is_err());
+        assert!(unlock_session(project_path, "second").is_err());
+        unlock_session(project_path, "first").unwrap();
+        assert_eq!(fs::read_to_string(data_dir.join("session.json")).unwrap(), "{}");
+    }
+}
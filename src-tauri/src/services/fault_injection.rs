@@ -0,0 +1,218 @@
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use async_trait::async_trait;
+use crate::error::CoreError;
+use crate::models::provider::FaultInjectionSettings;
+use crate::services::translation_provider::{PartialTextSink, PromptContext, ProviderTranslation, TranslationProvider};
+
+/// Prefix of the errors of simulated failures, so they can't be mistaken for real ones.
+pub const INJECTED_ERROR_PREFIX: &str = "[injected]";
+
+enum Fault {
+    Timeout,
+    RateLimit,
+    Malformed,
+}
+
+/// Wraps a provider so a share of its requests fail the way real backends do: hanging until a
+/// timeout, answering HTTP 429, or returning something that isn't a clean translation. The other
+/// requests reach the wrapped provider.
+pub struct FaultInjectingProvider {
+    inner: Box<dyn TranslationProvider>,
+    settings: FaultInjectionSettings,
+    // splitmix64 state.
+    state: Mutex<u64>,
+}
+
+impl FaultInjectingProvider {
+    pub fn new(inner: Box<dyn TranslationProvider>, settings: FaultInjectionSettings) -> Result<Self, CoreError> {
+        let rates = [settings.timeout_rate, settings.rate_limit_rate, settings.malformed_rate];
+        if rates.iter().any(|rate| !(0.0..=1.0).contains(rate)) || rates.iter().sum::<f64>() > 1.0 {
+            return Err(CoreError::Config(
+                "Fault injection rates must be between 0 and 1 and add up to at most 1".to_string(),
+            ));
+        }
+        let seed = settings
+            .seed
+            .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64));
+        Ok(FaultInjectingProvider { inner, settings, state: Mutex::new(seed) })
+    }
+
+    fn next_u64(&self) -> u64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn draw(&self) -> Option<Fault> {
+        let roll = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        let FaultInjectionSettings { timeout_rate, rate_limit_rate, malformed_rate, .. } = self.settings;
+        if roll < timeout_rate {
+            Some(Fault::Timeout)
+        } else if roll < timeout_rate + rate_limit_rate {
+            Some(Fault::RateLimit)
+        } else if roll < timeout_rate + rate_limit_rate + malformed_rate {
+            Some(Fault::Malformed)
+        } else {
+            None
+        }
+    }
+
+    /// Answers seen from misbehaving models: nothing, a chatty preamble around a cut-off text,
+    /// or the text with its "[n]" markers lost.
+    fn malformed_answer(&self, text: &str) -> String {
+        let half: String = text.chars().take(text.chars().count() / 2).collect();
+        match self.next_u64() % 3 {
+            0 => String::new(),
+            1 => format!("Sure! Here is the translation:\n{}", half),
+            _ => text
+                .lines()
+                .map(|line| line.trim_start().trim_start_matches(|c: char| c == '[' || c == ']' || c.is_ascii_digit()).trim_start())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    /// The simulated outcome of a request, or `None` to forward it.
+    async fn inject(&self, text: &str) -> Option<Result<ProviderTranslation, String>> {
+        match self.draw()? {
+            Fault::Timeout => {
+                tokio::time::sleep(Duration::from_millis(self.settings.timeout_ms)).await;
+                Some(Err(format!("{} Request to {} timed out", INJECTED_ERROR_PREFIX, self.inner.name())))
+            }
+            Fault::RateLimit => Some(Err(format!(
+                "{} {} returned HTTP 429 Too Many Requests",
+                INJECTED_ERROR_PREFIX,
+                self.inner.name()
+            ))),
+            Fault::Malformed => {
+                Some(Ok(ProviderTranslation { text: self.malformed_answer(text), provider: self.inner.name().to_string() }))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for FaultInjectingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn translate(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<ProviderTranslation, String> {
+        match self.inject(text).await {
+            Some(outcome) => outcome,
+            None => self.inner.translate(text, source_lang, target_lang).await,
+        }
+    }
+
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+    ) -> Result<ProviderTranslation, String> {
+        match self.inject(text).await {
+            Some(outcome) => outcome,
+            None => self.inner.translate_with_context(text, source_lang, target_lang, context).await,
+        }
+    }
+
+    async fn translate_streaming(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+        on_partial: &PartialTextSink<'_>,
+    ) -> Result<ProviderTranslation, String> {
+        match self.inject(text).await {
+            Some(outcome) => outcome,
+            None => self.inner.translate_streaming(text, source_lang, target_lang, context, on_partial).await,
+        }
+    }
+
+    async fn warm_up(&self) -> Result<(), String> {
+        self.inner.warm_up().await
+    }
+
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        self.inner.list_models().await
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, String> {
+        self.inner.complete(prompt).await
+    }
+}
+
+/// Wraps the provider when fault injection is enabled.
+pub fn with_fault_injection(
+    provider: Box<dyn TranslationProvider>,
+    settings: Option<&FaultInjectionSettings>,
+) -> Result<Box<dyn TranslationProvider>, CoreError> {
+    match settings {
+        Some(settings) => Ok(Box::new(FaultInjectingProvider::new(provider, settings.clone())?)),
+        None => Ok(provider),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl TranslationProvider for EchoProvider {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        async fn translate(&self, text: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            Ok(ProviderTranslation { text: format!("<{}>", text), provider: "echo".to_string() })
+        }
+    }
+
+    async fn outcomes(settings: &FaultInjectionSettings) -> Vec<Result<String, String>> {
+        let provider = with_fault_injection(Box::new(EchoProvider), Some(settings)).unwrap();
+        let mut outcomes = Vec::new();
+        for _ in 0..200 {
+            outcomes.push(provider.translate("[1] やあ", "ja", "en").await.map(|t| t.text));
+        }
+        outcomes
+    }
+
+    #[tokio::test]
+    async fn test_injected_failures_follow_rates_and_seed() {
+        let settings = FaultInjectionSettings {
+            timeout_rate: 0.2,
+            rate_limit_rate: 0.2,
+            malformed_rate: 0.2,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let first = outcomes(&settings).await;
+        assert_eq!(first, outcomes(&settings).await);
+
+        let timeouts = first.iter().filter(|o| o.as_ref().is_err_and(|e| e.contains("timed out"))).count();
+        let rate_limited = first.iter().filter(|o| o.as_ref().is_err_and(|e| e.contains("429"))).count();
+        let forwarded = first.iter().filter(|o| o.as_deref() == Ok("<[1] やあ>")).count();
+        let malformed = first.len() - timeouts - rate_limited - forwarded;
+        for count in [timeouts, rate_limited, malformed] {
+            assert!((20..=60).contains(&count), "{} of 200", count);
+        }
+        assert!(first.iter().filter_map(|o| o.as_ref().err()).all(|e| e.starts_with(INJECTED_ERROR_PREFIX)));
+
+        let off = FaultInjectionSettings { seed: Some(7), ..Default::default() };
+        assert!(outcomes(&off).await.iter().all(|o| o.as_deref() == Ok("<[1] やあ>")));
+        let invalid = FaultInjectionSettings { timeout_rate: 0.6, malformed_rate: 0.6, ..Default::default() };
+        assert!(with_fault_injection(Box::new(EchoProvider), Some(&invalid)).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Shared flag a long-running job polls between units of work.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// In-flight jobs by the id the frontend chose for them. Managed as Tauri state so
+/// `cancel_job_command` can reach jobs started by other commands.
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, CancellationToken>>,
+    // (job id, item) pairs running jobs were asked to give up.
+    skipped: Mutex<HashSet<(String, String)>>,
+}
+
+impl JobManager {
+    /// Registers a job; it is unregistered when the returned guard is dropped.
+    /// Starting a job with the id of a running one replaces (and cancels) the old token.
+    pub fn start(&self, job_id: &str) -> JobGuard<'_> {
+        let token = CancellationToken::default();
+        let previous = self.lock().insert(job_id.to_string(), token.clone());
+        if let Some(previous) = previous {
+            previous.cancel();
+        }
+        JobGuard { manager: self, job_id: job_id.to_string(), token }
+    }
+
+    /// Requests cancellation. Returns false if no job with that id is running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.lock().get(job_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Asks a running job to give up one of its items (e.g. the translation being generated for
+    /// one string) and carry on with the others. Returns false if no job with that id is running.
+    pub fn skip(&self, job_id: &str, item: &str) -> bool {
+        if !self.lock().contains_key(job_id) {
+            return false;
+        }
+        self.lock_skipped().insert((job_id.to_string(), item.to_string()));
+        true
+    }
+
+    pub fn is_skipped(&self, job_id: &str, item: &str) -> bool {
+        self.lock_skipped().contains(&(job_id.to_string(), item.to_string()))
+    }
+
+    pub fn running_jobs(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.lock().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CancellationToken>> {
+        // A panic while holding the lock cannot leave the map inconsistent.
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_skipped(&self) -> std::sync::MutexGuard<'_, HashSet<(String, String)>> {
+        self.skipped.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub struct JobGuard<'a> {
+    manager: &'a JobManager,
+    job_id: String,
+    token: CancellationToken,
+}
+
+impl JobGuard<'_> {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for JobGuard<'_> {
+    fn drop(&mut self) {
+        let mut jobs = self.manager.lock();
+        // Only remove our own registration, not a newer job reusing the id.
+        if jobs.get(&self.job_id).is_some_and(|t| Arc::ptr_eq(&t.0, &self.token.0)) {
+            jobs.remove(&self.job_id);
+            self.manager.lock_skipped().retain(|(job_id, _)| *job_id != self.job_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_running_job() {
+        let jobs = JobManager::default();
+        assert!(!jobs.cancel("batch-1"));
+        {
+            let job = jobs.start("batch-1");
+            assert_eq!(jobs.running_jobs(), vec!["batch-1"]);
+            assert!(!job.token().is_cancelled());
+            assert!(jobs.cancel("batch-1"));
+            assert!(job.token().is_cancelled());
+        }
+        assert!(jobs.running_jobs().is_empty());
+    }
+
+    #[test]
+    fn test_skipped_items_are_forgotten_with_their_job() {
+        let jobs = JobManager::default();
+        assert!(!jobs.skip("batch-1", "www/data/Map001.json:events[1]"));
+        {
+            let job = jobs.start("batch-1");
+            assert!(jobs.skip("batch-1", "www/data/Map001.json:events[1]"));
+            assert!(jobs.is_skipped("batch-1", "www/data/Map001.json:events[1]"));
+            assert!(!jobs.is_skipped("batch-1", "www/data/Map002.json:events[1]"));
+            assert!(!job.token().is_cancelled());
+        }
+        assert!(!jobs.is_skipped("batch-1", "www/data/Map001.json:events[1]"));
+    }
+
+    #[test]
+    fn test_restarting_job_cancels_previous() {
+        let jobs = JobManager::default();
+        let first = jobs.start("extract");
+        let second = jobs.start("extract");
+        assert!(first.token().is_cancelled());
+        drop(first);
+        assert_eq!(jobs.running_jobs(), vec!["extract"]);
+        assert!(!second.token().is_cancelled());
+    }
+}
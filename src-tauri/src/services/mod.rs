@@ -1,4 +1,21 @@
 pub mod ollama_client;
 pub mod zip_service;
+pub mod deepl;
+pub mod openai_client;
+pub mod streaming;
+pub mod encryption;
+pub mod batch_translation;
+pub mod translation_provider;
+pub mod job_manager;
+pub mod rate_limiter;
+pub mod fault_injection;
+pub mod retry;
+pub mod provider_warmup;
+pub mod provider_health;
+pub mod temp_workspace;
+pub mod pipeline;
+pub mod project_watcher;
+pub mod search_indexes;
+pub mod packaging;
 // Potentially other service modules in the future, e.g.:
 // pub mod zip_service;
@@ -1,22 +1,39 @@
 use ollama_rs::Ollama;
 use ollama_rs::generation::completion::request::GenerationRequest;
+use ollama_rs::generation::parameters::{KeepAlive, TimeUnit};
+use async_trait::async_trait;
+use serde::Deserialize;
+use crate::core::prompts::map_language_code_to_name;
+use crate::services::streaming::for_each_line;
+use crate::services::translation_provider::{
+    PartialTextSink, PromptContext, ProviderTranslation, TranslationProvider, GENERATION_ABORTED_ERROR,
+};
 // Removed unused HashMap import if it was only for the old options
 
 // OllamaGenerateRequest and OllamaGenerateResponse structs are no longer needed,
 // as ollama-rs provides its own types.
 
-// A helper function to map ISO codes to full language names for the prompt
-fn map_language_code_to_name(code: &str) -> &str {
-    match code.to_lowercase().as_str() {
-        "en" => "English",
-        "es" => "Spanish",
-        "fr" => "French",
-        "de" => "German",
-        "ja" => "Japanese",
-        "ko" => "Korean",
-        "zh" => "Chinese", // Assuming simplified for now, could be more specific
-        _ => code, // Fallback to the code itself if not mapped
+const OLLAMA_MODEL: &str = "mistral"; // Hardcoded for now
+
+// Generate endpoint, called directly for streamed requests (ollama-rs is built without streaming).
+const OLLAMA_GENERATE_URL: &str = "http://localhost:11434/api/generate";
+
+// How long Ollama keeps the model loaded after a warm-up ping.
+const WARM_UP_KEEP_ALIVE_MINUTES: u64 = 10;
+
+fn build_prompt(text_to_translate: &str, source_language_code: &str, target_language_code: &str, context: &PromptContext) -> String {
+    if let Some(prompt) = context.render_template(text_to_translate, source_language_code, target_language_code) {
+        return prompt;
     }
+    let source_lang_name = map_language_code_to_name(source_language_code);
+    let target_lang_name = map_language_code_to_name(target_language_code);
+    // Context goes first so the prompt still ends with the text to translate.
+    let context_block = if context.is_empty() { String::new() } else { format!("{}\n", context.render()) };
+
+    format!(
+        "{}Translate the following text from {} to {}. Output *only* the translated text. Do not include the original text, any explanations, commentary, phonetic transcription, or romanization:\n\n{}",
+        context_block, source_lang_name, target_lang_name, text_to_translate
+    )
 }
 
 pub async fn translate_with_ollama(
@@ -25,16 +42,20 @@ pub async fn translate_with_ollama(
     target_language_code: String,
     // model_name: String, // No longer passed as a parameter
 ) -> Result<String, String> {
-    
-    let model_name = "mistral".to_string(); // Hardcode model_name here for now
+    translate_with_ollama_context(text_to_translate, source_language_code, target_language_code, &PromptContext::default()).await
+}
 
-    let source_lang_name = map_language_code_to_name(&source_language_code);
-    let target_lang_name = map_language_code_to_name(&target_language_code);
+/// `translate_with_ollama` with glossary terms (and other context) injected into the prompt.
+pub async fn translate_with_ollama_context(
+    text_to_translate: String,
+    source_language_code: String,
+    target_language_code: String,
+    context: &PromptContext,
+) -> Result<String, String> {
+    
+    let model_name = OLLAMA_MODEL.to_string();
 
-    let prompt = format!(
-        "Translate the following text from {} to {}. Output *only* the translated text. Do not include the original text, any explanations, commentary, phonetic transcription, or romanization:\n\n{}",
-        source_lang_name, target_lang_name, text_to_translate
-    );
+    let prompt = build_prompt(&text_to_translate, &source_language_code, &target_language_code, context);
     // NOTE: With the current prompt and the 'mistral' model,
     // translations to Japanese may include romanization (e.g., "こんにちは (Kon'nichiwa)").
     // This is despite the prompt asking to exclude it. Further prompt engineering
@@ -61,6 +82,146 @@ pub async fn translate_with_ollama(
     }
 }
 
+// One line of a streamed /api/generate response.
+#[derive(Deserialize, Debug)]
+struct GenerateChunk {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+    error: Option<String>,
+}
+
+/// `translate_with_ollama_context` with the answer streamed: `on_partial` gets the text generated
+/// so far after every token, and stops the generation by returning false.
+pub async fn stream_with_ollama(
+    text_to_translate: &str,
+    source_language_code: &str,
+    target_language_code: &str,
+    context: &PromptContext,
+    on_partial: &PartialTextSink<'_>,
+) -> Result<String, String> {
+    let prompt = build_prompt(text_to_translate, source_language_code, target_language_code, context);
+    let body = serde_json::json!({ "model": OLLAMA_MODEL, "prompt": prompt, "stream": true });
+    let response = reqwest::Client::new()
+        .post(OLLAMA_GENERATE_URL)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama at http://localhost:11434: {}. Is Ollama running?", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned HTTP {}: {}", status, error_body));
+    }
+
+    let (mut generated, mut aborted) = (String::new(), false);
+    for_each_line(response, |line| {
+        let chunk: GenerateChunk =
+            serde_json::from_str(line).map_err(|e| format!("Invalid Ollama stream line: {}", e))?;
+        if let Some(error) = chunk.error {
+            return Err(format!("Failed to translate with Ollama model '{}': {}", OLLAMA_MODEL, error));
+        }
+        generated.push_str(&chunk.response);
+        if !chunk.response.is_empty() && !on_partial(&generated) {
+            aborted = true;
+            return Ok(false);
+        }
+        Ok(!chunk.done)
+    })
+    .await?;
+    if aborted {
+        return Err(GENERATION_ABORTED_ERROR.to_string());
+    }
+    Ok(generated.trim().to_string())
+}
+
+/// Sends `prompt` as it is, for requests other than translations.
+pub async fn complete_with_ollama(prompt: &str) -> Result<String, String> {
+    Ollama::default()
+        .generate(GenerationRequest::new(OLLAMA_MODEL.to_string(), prompt.to_string()))
+        .await
+        .map(|res| res.response.trim().to_string())
+        .map_err(|e| format!("Failed to query Ollama model '{}': {}. Ensure Ollama is running and the model is available.", OLLAMA_MODEL, e))
+}
+
+/// Loads the model without generating anything: Ollama treats a request with an empty
+/// prompt as a load request.
+pub async fn warm_up_ollama() -> Result<(), String> {
+    let request = GenerationRequest::new(OLLAMA_MODEL.to_string(), "")
+        .keep_alive(KeepAlive::Until { time: WARM_UP_KEEP_ALIVE_MINUTES, unit: TimeUnit::Minutes });
+    Ollama::default()
+        .generate(request)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to load Ollama model '{}': {}", OLLAMA_MODEL, e))
+}
+
+/// `TranslationProvider` wrapper around `translate_with_ollama`.
+#[derive(Debug, Default, Clone)]
+pub struct OllamaClient;
+
+#[async_trait]
+impl TranslationProvider for OllamaClient {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> Result<ProviderTranslation, String> {
+        let text = translate_with_ollama(text.to_string(), source_lang.to_string(), target_lang.to_string()).await?;
+        Ok(ProviderTranslation { text, provider: self.name().to_string() })
+    }
+
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+    ) -> Result<ProviderTranslation, String> {
+        let text =
+            translate_with_ollama_context(text.to_string(), source_lang.to_string(), target_lang.to_string(), context).await?;
+        Ok(ProviderTranslation { text, provider: self.name().to_string() })
+    }
+
+    async fn translate_streaming(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+        on_partial: &PartialTextSink<'_>,
+    ) -> Result<ProviderTranslation, String> {
+        let text = stream_with_ollama(text, source_lang, target_lang, context, on_partial).await?;
+        Ok(ProviderTranslation { text, provider: self.name().to_string() })
+    }
+
+    async fn warm_up(&self) -> Result<(), String> {
+        warm_up_ollama().await
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(OLLAMA_MODEL)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, String> {
+        complete_with_ollama(prompt).await
+    }
+
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        let models = Ollama::default()
+            .list_local_models()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama at http://localhost:11434: {}. Is Ollama running?", e))?;
+        Ok(Some(models.into_iter().map(|model| model.name).collect()))
+    }
+}
+
 /* // Placeholder for the actual function to be implemented - REMOVED
 pub async fn translate_with_ollama(
     _text_to_translate: String,
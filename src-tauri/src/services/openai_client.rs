@@ -0,0 +1,328 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use crate::core::prompts::map_language_code_to_name;
+use crate::models::provider::OpenAiSettings;
+use crate::services::streaming::for_each_line;
+use crate::services::translation_provider::{
+    PartialTextSink, PromptContext, ProviderTranslation, TranslationProvider, GENERATION_ABORTED_ERROR,
+};
+
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a professional video game translator. Translate the user's text from {source_lang} to {target_lang}. Output *only* the translated text. Do not include the original text, any explanations, commentary, phonetic transcription, or romanization. Preserve control codes such as \\V[1], \\N[1], \\C[2] and %1 exactly.";
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+// One server-sent event of a streamed chat completion.
+#[derive(Deserialize, Debug)]
+struct ChatCompletionChunk {
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChunkChoice {
+    delta: ChunkDelta,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ModelListResponse {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ModelEntry {
+    id: String,
+}
+
+/// Client for OpenAI-compatible `/chat/completions` endpoints.
+pub struct OpenAiClient {
+    settings: OpenAiSettings,
+    http: reqwest::Client,
+}
+
+impl OpenAiClient {
+    pub fn new(settings: OpenAiSettings) -> Self {
+        OpenAiClient {
+            settings,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.settings.base_url.trim_end_matches('/'))
+    }
+
+    fn models_url(&self) -> String {
+        format!("{}/models", self.settings.base_url.trim_end_matches('/'))
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.settings.api_key.as_deref().filter(|k| !k.is_empty()) {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    fn build_messages(&self, text: &str, source_lang: &str, target_lang: &str, context: &PromptContext) -> Vec<ChatMessage> {
+        let mut system_prompt = self
+            .settings
+            .system_prompt
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or(DEFAULT_SYSTEM_PROMPT)
+            .replace("{source_lang}", map_language_code_to_name(source_lang))
+            .replace("{target_lang}", map_language_code_to_name(target_lang));
+        // A project template already holds the context, and becomes the user message.
+        if let Some(prompt) = context.render_template(text, source_lang, target_lang) {
+            return vec![
+                ChatMessage { role: "system".to_string(), content: system_prompt },
+                ChatMessage { role: "user".to_string(), content: prompt },
+            ];
+        }
+        if !context.is_empty() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(context.render().trim_end());
+        }
+        vec![
+            ChatMessage { role: "system".to_string(), content: system_prompt },
+            ChatMessage { role: "user".to_string(), content: text.to_string() },
+        ]
+    }
+
+    /// Sends a chat completion request, returning the response once its status is a success.
+    async fn send(&self, messages: Vec<ChatMessage>, stream: bool) -> Result<reqwest::Response, String> {
+        let body = ChatCompletionRequest {
+            model: &self.settings.model,
+            messages,
+            temperature: self.settings.temperature,
+            stream,
+        };
+
+        let request = self.authorize(self.http.post(self.completions_url()).json(&body));
+        let response = request.send().await.map_err(|e| {
+            format!("Failed to reach OpenAI-compatible endpoint {}: {}", self.settings.base_url, e)
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Err(format!("OpenAI-compatible endpoint returned HTTP {}: {}", status, error_body));
+        }
+        Ok(response)
+    }
+
+    /// Sends a chat completion request and returns the trimmed answer.
+    async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, String> {
+        let parsed: ChatCompletionResponse = self
+            .send(messages, false)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid chat completion response: {}", e))?;
+        let content = parsed
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .ok_or_else(|| "Chat completion response contained no choices.".to_string())?;
+
+        Ok(content.trim().to_string())
+    }
+
+    /// `chat` with the answer streamed as server-sent events: `on_partial` gets the text generated
+    /// so far after every delta, and stops the generation by returning false.
+    async fn chat_streaming(&self, messages: Vec<ChatMessage>, on_partial: &PartialTextSink<'_>) -> Result<String, String> {
+        let response = self.send(messages, true).await?;
+        let (mut generated, mut aborted) = (String::new(), false);
+        for_each_line(response, |line| {
+            // Comments and other fields (keep-alives, event names) carry no text.
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                return Ok(true);
+            };
+            if data == "[DONE]" {
+                return Ok(false);
+            }
+            let chunk: ChatCompletionChunk =
+                serde_json::from_str(data).map_err(|e| format!("Invalid chat completion chunk: {}", e))?;
+            let delta = chunk.choices.into_iter().next().and_then(|c| c.delta.content).unwrap_or_default();
+            generated.push_str(&delta);
+            if !delta.is_empty() && !on_partial(&generated) {
+                aborted = true;
+                return Ok(false);
+            }
+            Ok(true)
+        })
+        .await?;
+        if aborted {
+            return Err(GENERATION_ABORTED_ERROR.to_string());
+        }
+        Ok(generated.trim().to_string())
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for OpenAiClient {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> Result<ProviderTranslation, String> {
+        self.translate_with_context(text, source_lang, target_lang, &PromptContext::default()).await
+    }
+
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+    ) -> Result<ProviderTranslation, String> {
+        let text = self.chat(self.build_messages(text, source_lang, target_lang, context)).await?;
+        Ok(ProviderTranslation { text, provider: self.name().to_string() })
+    }
+
+    async fn translate_streaming(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+        on_partial: &PartialTextSink<'_>,
+    ) -> Result<ProviderTranslation, String> {
+        let text = self.chat_streaming(self.build_messages(text, source_lang, target_lang, context), on_partial).await?;
+        Ok(ProviderTranslation { text, provider: self.name().to_string() })
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, String> {
+        self.chat(vec![ChatMessage { role: "user".to_string(), content: prompt.to_string() }]).await
+    }
+
+    /// Lists the models, which keeps the connection pool open and wakes up local servers
+    /// (LM Studio, vLLM) without spending tokens.
+    async fn warm_up(&self) -> Result<(), String> {
+        let response = self
+            .authorize(self.http.get(self.models_url()))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach OpenAI-compatible endpoint {}: {}", self.settings.base_url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("OpenAI-compatible endpoint returned HTTP {} for /models", response.status()));
+        }
+        Ok(())
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.settings.model)
+    }
+
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        let response = self
+            .authorize(self.http.get(self.models_url()))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach OpenAI-compatible endpoint {}: {}", self.settings.base_url, e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Err(format!("OpenAI-compatible endpoint returned HTTP {} for /models: {}", status, error_body));
+        }
+        let parsed: ModelListResponse =
+            response.json().await.map_err(|e| format!("Invalid /models response: {}", e))?;
+        Ok(Some(parsed.data.into_iter().map(|model| model.id).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::glossary::GlossaryTerm;
+    use crate::models::reference::ReferenceLink;
+
+    fn settings() -> OpenAiSettings {
+        OpenAiSettings {
+            base_url: "http://localhost:1234/v1/".to_string(),
+            api_key: None,
+            model: "local-model".to_string(),
+            temperature: Some(0.2),
+            system_prompt: None,
+            pricing: None,
+        }
+    }
+
+    #[test]
+    fn test_completions_url_trims_trailing_slash() {
+        let client = OpenAiClient::new(settings());
+        assert_eq!(client.completions_url(), "http://localhost:1234/v1/chat/completions");
+        assert_eq!(client.models_url(), "http://localhost:1234/v1/models");
+    }
+
+    #[test]
+    fn test_build_messages_fills_languages() {
+        let client = OpenAiClient::new(settings());
+        let messages = client.build_messages("こんにちは", "ja", "en", &PromptContext::default());
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].content.contains("from Japanese to English"));
+        assert_eq!(messages[1], ChatMessage { role: "user".to_string(), content: "こんにちは".to_string() });
+
+        let mut custom = settings();
+        custom.system_prompt = Some("Translate {source_lang} into casual {target_lang}.".to_string());
+        let messages = OpenAiClient::new(custom).build_messages("x", "ja", "fr", &PromptContext::default());
+        assert_eq!(messages[0].content, "Translate Japanese into casual French.");
+    }
+
+    #[test]
+    fn test_build_messages_includes_glossary_context() {
+        let context = PromptContext {
+            glossary: vec![GlossaryTerm {
+                source: "ハロルド".to_string(),
+                target: "Harold".to_string(),
+                references: Vec::new(),
+            }],
+            references: vec![ReferenceLink { url: None, note: Some("Harold is the narrator".to_string()) }],
+            ..Default::default()
+        };
+        let messages = OpenAiClient::new(settings()).build_messages("ハロルドだ", "ja", "en", &context);
+        assert!(messages[0].content.contains("- ハロルド => Harold\n"));
+        assert!(messages[0].content.ends_with("Background notes for this text:\n- Harold is the narrator"));
+        assert_eq!(messages[1].content, "ハロルドだ");
+    }
+
+    #[test]
+    fn test_parse_chat_completion_response() {
+        let body = r#"{"id":"1","choices":[{"index":0,"message":{"role":"assistant","content":" Hello \n"},"finish_reason":"stop"}]}"#;
+        let parsed: ChatCompletionResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(parsed.choices[0].message.content.trim(), "Hello");
+    }
+}
@@ -0,0 +1,186 @@
+// Packaging a translated project into an archive, shared by the commands that produce one and
+// the command-line interface.
+
+use std::fs;
+use std::path::Path;
+use crate::core::config::load_project_config;
+use crate::core::postprocess::postprocess_translations;
+use crate::core::quality;
+use crate::core::rpgmv::{locale_output, verification, warnings, word_wrap};
+use crate::core::rpgmv::warnings::ReconstructionReport;
+use crate::error::{AppError, ErrorKind};
+use crate::models::translation::WorkingTranslation;
+use crate::services::temp_workspace::{TempWorkspaces, WorkspaceKind};
+use crate::services::zip_service::{self, ArchiveOptions, DEFAULT_ARCHIVE_TIMESTAMP};
+
+/// Reconstructs, verifies and zips the project with `translated_entries`, for
+/// `reconstruct_translated_project_files`, the project queue and the CLI. Returns the ZIP, in an output
+/// workspace next to its quality gate report.
+pub fn package_translated_project(
+    workspaces: &TempWorkspaces,
+    project_path: &str,
+    translated_entries: &[WorkingTranslation],
+) -> Result<String, AppError> {
+    let translated_entries = postprocess_translations(project_path, translated_entries).map_err(AppError::from)?;
+    let ReconstructionReport { output: reconstructed, warnings: reconstruction_warnings } =
+        crate::core::rpgmv::project::reconstruct_project_files(project_path, &translated_entries);
+    if !reconstruction_warnings.is_empty() {
+        eprintln!("Reconstruction skipped {} translation(s), see the reconstruction warnings.", reconstruction_warnings.len());
+    }
+    if let Err(e) = warnings::save_warnings(project_path, &reconstruction_warnings) {
+        eprintln!("Failed to save reconstruction warnings: {}", e);
+    }
+
+    // Integrity gate: re-extract the output and compare it with the expected translated set.
+    let mut report = verification::verify_against_project(project_path, &reconstructed.files, &translated_entries);
+    report.warnings = reconstruction_warnings;
+    match quality::project_file_coverage(project_path, &translated_entries) {
+        Ok(coverage) => report.coverage = coverage,
+        Err(e) => eprintln!("Failed to compute file coverage for the verification report: {}", e),
+    }
+    if !report.passed {
+        eprintln!(
+            "Reconstruction verification found {} mismatch(es) and {} error(s) across {} file(s).",
+            report.mismatches.len(), report.errors.len(), report.files_checked
+        );
+    }
+    if let Err(e) = verification::save_report(project_path, &report) {
+        eprintln!("Failed to save verification report: {}", e);
+    }
+
+    // Dialogue is re-wrapped to the message window once verified. With an output locale,
+    // translated data files are packaged next to the originals (Map001_en.json) together with
+    // the loader plugin.
+    let config = load_project_config(project_path).map_err(AppError::from)?;
+    let archive_options =
+        ArchiveOptions { timestamp: config.output_timestamp.unwrap_or(DEFAULT_ARCHIVE_TIMESTAMP), ..Default::default() };
+    let reconstructed = word_wrap::apply_word_wrap(project_path, reconstructed).map_err(AppError::from)?;
+    let reconstructed = match config.output_locale {
+        Some(locale) => locale_output::apply_locale_suffix(project_path, &locale, reconstructed).map_err(AppError::from)?,
+        None => reconstructed,
+    };
+    for error_msg in &reconstructed.errors {
+        eprintln!("{}", error_msg);
+    }
+    let reconstruction_errors = reconstructed.errors;
+    let all_reconstructed_content = reconstructed.files;
+    let binary_content = reconstructed.binary_files;
+
+    let nothing_reconstructed = all_reconstructed_content.is_empty() && binary_content.is_empty();
+    if !reconstruction_errors.is_empty() && nothing_reconstructed {
+        // All files failed reconstruction or reading
+        return Err(AppError::from(format!("All file processing failed. Errors: {}", reconstruction_errors.join("; "))));
+    }
+    
+    if nothing_reconstructed && !translated_entries.is_empty() {
+        return Err(AppError::from("No files were successfully reconstructed, though translated entries were provided.".to_string()));
+    }
+    if nothing_reconstructed && translated_entries.is_empty() {
+        // This is not an error, just means nothing to zip.
+        // However, the frontend should ideally not call this if there are no entries.
+        // For now, let's return an empty path or a specific signal if we decide so.
+        // Or, let the zip creation handle an empty map (it should create an empty zip).
+        println!("No reconstructed content to package into ZIP.");
+        // To create an empty zip, we'd still proceed. If an error is preferred:
+        // return Err("No content to package.".to_string()); 
+    }
+
+    // The ZIP waits in an output workspace until save_zip_archive_command moves it; the
+    // workspace is removed if packaging fails.
+    let workspace = workspaces.create(WorkspaceKind::Output).map_err(AppError::from)?;
+    let output_zip_file_path = workspace.path().join("translated_project_output.zip");
+
+    match zip_service::create_zip_archive_with_binary_files(
+        &all_reconstructed_content,
+        &binary_content,
+        &output_zip_file_path,
+        &archive_options,
+    ) {
+        Ok(_) => {
+            // Checked again when the archive is saved; gates that can't be evaluated fail.
+            let gates = quality::project_quality_gates(project_path, &translated_entries).unwrap_or_else(|e| {
+                eprintln!("Failed to check quality gates: {}", e);
+                quality::unchecked_gates(&e)
+            });
+            quality::save_gate_report(workspace.path(), &gates).map_err(AppError::from)?;
+            if !reconstruction_errors.is_empty() {
+                // Partial success: ZIP created, but some files had errors
+                // The frontend should be notified of these errors separately.
+                // For now, returning the ZIP path but logging errors.
+                eprintln!("ZIP created with some reconstruction errors: {}", reconstruction_errors.join("; "));
+            }
+            workspace.keep();
+            Ok(output_zip_file_path.to_string_lossy().into_owned())
+        }
+        Err(e) => Err(AppError::from(e).with_file(output_zip_file_path.to_string_lossy())),
+    }
+}
+
+/// Moves a ZIP made by `package_translated_project` to `target` and removes its workspace. A
+/// translation that fails its quality gates is refused unless `override_quality_gates`, and so is
+/// an existing `target`.
+pub fn save_packaged_project(
+    workspaces: &TempWorkspaces,
+    zip_path: &Path,
+    target: &Path,
+    override_quality_gates: bool,
+) -> Result<(), AppError> {
+    check_packaged_gates(workspaces, zip_path, override_quality_gates)?;
+    move_packaged_archive(workspaces, zip_path, zip_path, target)
+}
+
+/// Moves `archive`, the ZIP made by `package_translated_project` at `zip_path` or an archive
+/// written from it, to `target` and removes the ZIP's workspace. An existing `target` is refused
+/// and the workspace kept, so the save can be retried.
+pub fn move_packaged_archive(
+    workspaces: &TempWorkspaces,
+    zip_path: &Path,
+    archive: &Path,
+    target: &Path,
+) -> Result<(), AppError> {
+    move_file(archive, target).map_err(|e| AppError::from(e).with_file(target.to_string_lossy()))?;
+    discard_output(workspaces, zip_path);
+    Ok(())
+}
+
+/// Refuses a ZIP made by `package_translated_project` whose translation failed the project's
+/// quality gates, unless `override_quality_gates`; its workspace is removed then.
+pub fn check_packaged_gates(
+    workspaces: &TempWorkspaces,
+    zip_path: &Path,
+    override_quality_gates: bool,
+) -> Result<(), AppError> {
+    let gates = match zip_path.parent() {
+        Some(dir) => quality::load_gate_report(dir).map_err(AppError::from)?,
+        None => None,
+    };
+    if let Some(gates) = gates.filter(|gates| !gates.passed && !override_quality_gates) {
+        discard_output(workspaces, zip_path);
+        return Err(AppError::new(
+            ErrorKind::QualityGate,
+            format!("The translation fails the project's quality gates: {}", quality::describe_gate_failures(&gates)),
+        ));
+    }
+    Ok(())
+}
+
+fn discard_output(workspaces: &TempWorkspaces, zip_path: &Path) {
+    if let Err(e) = workspaces.discard(zip_path) {
+        eprintln!("Failed to remove the output workspace of {:?}: {}", zip_path, e);
+    }
+}
+
+/// Renames `from` to `to`, or copies it when they are on different filesystems. An existing
+/// `to` is never replaced.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        let message = format!("{} already exists", to.display());
+        return Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, message));
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    let mut target = fs::OpenOptions::new().write(true).create_new(true).open(to)?;
+    std::io::copy(&mut fs::File::open(from)?, &mut target)?;
+    fs::remove_file(from)
+}
@@ -0,0 +1,275 @@
+// The batch translation pipeline shared by the translation commands, the project queue and the
+// command-line tool.
+
+use std::time::Instant;
+use crate::core::catchphrases::load_catchphrases;
+use crate::core::config::load_project_config;
+use crate::core::control_codes::ControlCodes;
+use crate::core::dedup::deduplicate;
+use crate::core::glossary::{as_locked_terms, load_project_glossary};
+use crate::core::name_policy::check_name_policy;
+use crate::core::presets::project_preset;
+use crate::core::prompts::load_prompt_templates;
+use crate::core::references::{index_references, load_project_references, ReferenceIndex};
+use crate::core::scope_summaries::load_scope_summaries;
+use crate::core::session::SessionRecorder;
+use crate::core::string_store::StringStore;
+use crate::core::translation_memory::TranslationMemory;
+use crate::core::transliteration::{fill_placeholder, placeholder_for};
+use crate::core::units::UnitModes;
+use crate::core::validation::{load_project_ruleset, validate_translation_with};
+use crate::error::CoreError;
+use crate::models::glossary::Glossary;
+use crate::models::metrics::{EntryTimings, PipelineMetrics};
+use crate::models::provider::ProviderConfig;
+use crate::models::session::TranslationSession;
+use crate::models::translation::{
+    BatchProgress, BatchTranslationOptions, PartialTranslation, SourceStringData, WorkingTranslation,
+};
+use crate::models::validation::ValidationRuleSet;
+use crate::services::batch_translation::{self, BatchContext, PartialTranslationSink, SurroundingLines};
+use crate::services::fault_injection::with_fault_injection;
+use crate::services::job_manager::JobManager;
+use crate::services::rate_limiter::with_rate_limit;
+use crate::services::retry::with_retry;
+use crate::services::translation_provider::{build_provider, TranslationProvider};
+
+/// Provider requests in flight at once when the options don't say otherwise.
+const DEFAULT_CONCURRENCY: usize = 1;
+
+/// Item of a batch job standing for the generation of one entry's translation.
+pub fn streamed_item(source_file: &str, json_path: &str) -> String {
+    format!("{}:{}", source_file, json_path)
+}
+
+/// What runs a batch: the app forwards its progress to the frontend as events, the command-line
+/// tool prints it.
+pub trait BatchHost: Sync {
+    /// Jobs the batch registers in when its options have a `jobId`.
+    fn jobs(&self) -> &JobManager;
+    /// Called after each entry.
+    fn progress(&self, _progress: &BatchProgress, _timings: &EntryTimings) {}
+    /// Called while a streamed translation is generated.
+    fn partial(&self, _partial: &PartialTranslation) {}
+    /// Called once the batch is finished, with its aggregated metrics.
+    fn metrics(&self, _metrics: &PipelineMetrics) {}
+}
+
+/// Translates `entries` with the project's glossary, references, rules and memory (when
+/// `options.projectPath` is set), escalates and validates the results. Returns the batch results
+/// and, when a session is recorded (project path given), every translation of the session.
+pub async fn run_batch(
+    host: &dyn BatchHost,
+    entries: Vec<SourceStringData>,
+    source_language: &str,
+    target_language: &str,
+    provider: Option<ProviderConfig>,
+    options: BatchTranslationOptions,
+    session: Option<TranslationSession>,
+) -> Result<(Vec<WorkingTranslation>, Option<Vec<WorkingTranslation>>), CoreError> {
+    let BatchTranslationOptions {
+        project_path,
+        escalation,
+        use_translation_memory,
+        include_references,
+        job_id,
+        concurrency,
+        requests_per_minute,
+        unit_mode,
+        entry_unit_modes,
+        fault_injection,
+        dialogue_blocks,
+        context_window,
+        retry,
+        stream,
+        offline_fallback,
+        skip_target_language,
+        dedup,
+        // Used when the session and entries are picked, before the batch.
+        replace_session: _,
+        range: _,
+    } = options;
+    let unit_modes = UnitModes::new(unit_mode.unwrap_or_default(), entry_unit_modes);
+    let retry = retry.unwrap_or_default();
+    // Injected failures still take a rate limit slot, like real ones, and so does every retry.
+    let rate_limit = |provider: Box<dyn TranslationProvider>| -> Result<Box<dyn TranslationProvider>, CoreError> {
+        let limit = requests_per_minute.get(provider.name()).copied();
+        let provider = with_fault_injection(provider, fault_injection.as_ref())?;
+        Ok(with_retry(with_rate_limit(provider, limit), &retry))
+    };
+    let jobs = host.jobs();
+    let job = job_id.as_deref().map(|id| jobs.start(id));
+    // Without an explicit provider, the project config's provider is used.
+    let provider = provider.or_else(|| load_project_config(project_path.as_deref()?).ok()?.provider);
+    let provider = rate_limit(build_provider(provider))?;
+    let mut memory = match &project_path {
+        Some(path) if use_translation_memory.unwrap_or(true) => {
+            Some(TranslationMemory::open_for_project(path)?)
+        }
+        _ => None,
+    };
+    let (glossary, mut ruleset, references) = match &project_path {
+        Some(path) => (
+            load_project_glossary(path)?,
+            load_project_ruleset(path)?,
+            index_references(load_project_references(path)?),
+        ),
+        None => (Glossary::default(), ValidationRuleSet::default(), ReferenceIndex::new()),
+    };
+    let mut recorder = match (&project_path, session) {
+        (Some(path), Some(session)) => {
+            Some(SessionRecorder::start(path, session, &entries)?)
+        }
+        _ => None,
+    };
+    // Glossary terms are post-validated like locked terms (and escalated when they fail).
+    ruleset.locked_terms.extend(as_locked_terms(&glossary));
+    let control_codes = ControlCodes::from_ruleset(&ruleset);
+    let prompt_templates = project_path.as_deref().map(load_prompt_templates).transpose()?;
+    let scope_summaries = project_path.as_deref().map(load_scope_summaries).transpose()?;
+    let catchphrases = project_path.as_deref().map(load_catchphrases).transpose()?;
+    let preset = project_path.as_deref().map(project_preset).transpose()?.flatten();
+    let characters = project_path.as_deref().map(load_project_config).transpose()?.map(|c| c.characters);
+    let surrounding_lines = SurroundingLines::new(&entries, context_window.unwrap_or(0));
+    // Repeated strings are sent once; the plan copies their translations back after escalation.
+    let (entries, dedup_plan) = match &dedup {
+        Some(settings) => {
+            let (unique, plan) = deduplicate(entries, settings);
+            println!("Deduplication: {} repeated string(s) reuse another entry's translation.", plan.copies());
+            (unique, Some(plan))
+        }
+        None => (entries, None),
+    };
+    let on_partial = |partial: &PartialTranslation| {
+        host.partial(partial);
+        let item = streamed_item(&partial.source_file, &partial.json_path);
+        job_id.as_deref().map_or(true, |id| !jobs.is_skipped(id, &item))
+    };
+    let batch = BatchContext {
+        source_language,
+        target_language,
+        glossary: &glossary,
+        references: &references,
+        include_references: include_references.unwrap_or(true),
+        cancellation: job.as_ref().map(|job| job.token()),
+        concurrency: concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+        unit_modes: Some(&unit_modes),
+        control_codes: Some(&control_codes),
+        prompt_templates: prompt_templates.as_ref(),
+        dialogue_blocks: dialogue_blocks.unwrap_or(true),
+        surrounding_lines: Some(&surrounding_lines),
+        scope_summaries: scope_summaries.as_ref(),
+        on_partial: stream.unwrap_or(false).then_some(&on_partial as &PartialTranslationSink),
+        catchphrases: catchphrases.as_ref(),
+        skip_target_language: skip_target_language.unwrap_or(false),
+        preset: preset.as_ref(),
+        characters: characters.as_deref(),
+    };
+
+    let started = Instant::now();
+    let mut metrics = PipelineMetrics::default();
+    let mut on_progress = |progress: &BatchProgress, entry: &WorkingTranslation, timings: &EntryTimings| {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(entry);
+        }
+        metrics.record(timings);
+        host.progress(progress, timings);
+    };
+    let offline_fallback = offline_fallback.unwrap_or(false);
+    let unreachable = match offline_fallback {
+        true => provider.list_models().await.err(),
+        false => None,
+    };
+    let mut results = match unreachable {
+        Some(reason) => {
+            eprintln!("{} can't be reached ({}); writing transliterated placeholders instead.", provider.name(), reason);
+            entries.into_iter().map(|entry| placeholder_for(entry, source_language)).collect()
+        }
+        None => {
+            batch_translation::translate_entries(
+                provider.as_ref(),
+                entries,
+                &batch,
+                memory.as_mut(),
+                Some(&mut on_progress),
+            )
+            .await
+        }
+    };
+
+    if let Some(escalation) = escalation {
+        let escalation_started = Instant::now();
+        let premium = rate_limit(build_provider(Some(escalation.premium_provider.clone())))?;
+        let summary = batch_translation::escalate_hard_lines(
+            &mut results,
+            premium.as_ref(),
+            &ruleset,
+            &escalation,
+            &batch,
+            memory.as_mut(),
+        )
+        .await;
+        println!(
+            "Adaptive quality: {} hard line(s), {} escalated to {}, {} failed.",
+            summary.hard_lines, summary.escalated, premium.name(), summary.failed
+        );
+        metrics.escalation_ms = Some(escalation_started.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    if offline_fallback {
+        for entry in results.iter_mut().filter(|entry| entry.error.is_some()) {
+            fill_placeholder(entry, source_language);
+        }
+    }
+    if let Some(plan) = &dedup_plan {
+        results = plan.fan_out(results);
+    }
+
+    let (mut glossary_violations, mut seam_issues, mut name_policy_issues) = (0, 0, 0);
+    for entry in &results {
+        let validation_started = Instant::now();
+        let issues = validate_translation_with(entry, &ruleset, &control_codes);
+        glossary_violations += issues.iter().filter(|issue| issue.rule == "lockedTerm").count();
+        seam_issues += usize::from(issues.iter().any(|issue| issue.rule == "chunkSeam"));
+        if let Some(preset) = &preset {
+            name_policy_issues += usize::from(!check_name_policy(entry, preset, &glossary).is_empty());
+        }
+        metrics.validation.add(validation_started.elapsed().as_secs_f64() * 1000.0);
+    }
+    if glossary_violations > 0 {
+        eprintln!("Warning: {} translation(s) do not use the required glossary/locked terms.", glossary_violations);
+    }
+    if name_policy_issues > 0 {
+        eprintln!("Warning: {} translation(s) do not follow the project's honorific or name order policy.", name_policy_issues);
+    }
+    if seam_issues > 0 {
+        eprintln!("Warning: {} translation(s) have duplicated or cut sentences between paragraphs; review them.", seam_issues);
+    }
+
+    metrics.wall_ms = started.elapsed().as_secs_f64() * 1000.0;
+    println!(
+        "Batch timings: {} entries in {:.0} ms; provider {:.0} ms total (mean {:.1} ms), queue wait mean {:.1} ms, {} memory hit(s).",
+        metrics.entries,
+        metrics.wall_ms,
+        metrics.provider.total_ms,
+        metrics.provider.mean_ms,
+        metrics.queue_wait.mean_ms,
+        metrics.memory_hits
+    );
+    host.metrics(&metrics);
+
+    let session_translations = match recorder {
+        Some(recorder) => Some(recorder.finish(&results)?),
+        None => None,
+    };
+    // Strings extracted into the store take the session's new translations; others are left
+    // out. The session is saved already, so the store failing doesn't fail the batch.
+    if let (Some(path), Some(_)) = (&project_path, &session_translations) {
+        let recorded = StringStore::open_for_project(path)
+            .and_then(|mut store| store.record_translations(target_language, &results));
+        if let Err(e) = recorded {
+            eprintln!("Warning: failed to save the translations to the string store: {}", e);
+        }
+    }
+    Ok((results, session_translations))
+}
@@ -0,0 +1,113 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::task::JoinHandle;
+use crate::error::CoreError;
+
+/// Editors and RPG Maker save several files in a row; changes are handled once the data folder
+/// has been quiet this long.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches `dir` (not its subfolders) for created, modified and removed JSON files. Each changed
+/// file is sent on the returned channel until the watcher is dropped.
+pub fn watch_json_files(dir: &Path) -> Result<(RecommendedWatcher, UnboundedReceiver<PathBuf>), CoreError> {
+    let (sender, receiver) = unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) => {
+            for path in event.paths.into_iter().filter(|path| path.extension().is_some_and(|ext| ext == "json")) {
+                // The receiver is gone once the watch is stopped.
+                let _ = sender.send(path);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("File watcher error: {}", e),
+    })
+    .map_err(|e| CoreError::Io(format!("Failed to create a file watcher: {}", e)))?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| CoreError::Io(format!("Failed to watch {:?}: {}", dir, e)))?;
+    Ok((watcher, receiver))
+}
+
+/// Waits for the next changed file, then collects the ones that follow until nothing changed
+/// for `quiet`. Returns `None` once the watcher is gone.
+pub async fn next_changes(changes: &mut UnboundedReceiver<PathBuf>, quiet: Duration) -> Option<BTreeSet<PathBuf>> {
+    let mut changed = BTreeSet::from([changes.recv().await?]);
+    while let Ok(Some(path)) = tokio::time::timeout(quiet, changes.recv()).await {
+        changed.insert(path);
+    }
+    Some(changed)
+}
+
+struct ProjectWatch {
+    _watcher: RecommendedWatcher,
+    handle: JoinHandle<()>,
+}
+
+/// Watched projects by path. Managed as Tauri state; watching a project again replaces its
+/// previous watch.
+#[derive(Default)]
+pub struct ProjectWatchers {
+    watches: Mutex<HashMap<String, ProjectWatch>>,
+}
+
+impl ProjectWatchers {
+    /// Keeps `watcher` alive while `handle` processes its changes.
+    pub fn start(&self, project_path: &str, watcher: RecommendedWatcher, handle: JoinHandle<()>) {
+        let watch = ProjectWatch { _watcher: watcher, handle };
+        if let Some(previous) = self.lock().insert(project_path.to_string(), watch) {
+            previous.handle.abort();
+        }
+    }
+
+    /// Stops watching the project. Returns false if it wasn't watched.
+    pub fn stop(&self, project_path: &str) -> bool {
+        match self.lock().remove(project_path) {
+            Some(watch) => {
+                watch.handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Paths of the watched projects, sorted.
+    pub fn watched(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.lock().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ProjectWatch>> {
+        self.watches.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for ProjectWatchers {
+    fn drop(&mut self) {
+        for (_, watch) in self.lock().drain() {
+            watch.handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_changes_are_collected_until_quiet() {
+        let (sender, mut receiver) = unbounded_channel();
+        sender.send(PathBuf::from("Map002.json")).unwrap();
+        sender.send(PathBuf::from("Map001.json")).unwrap();
+        sender.send(PathBuf::from("Map002.json")).unwrap();
+        let changed = next_changes(&mut receiver, Duration::from_millis(20)).await.unwrap();
+        assert_eq!(changed, BTreeSet::from([PathBuf::from("Map001.json"), PathBuf::from("Map002.json")]));
+
+        drop(sender);
+        assert!(next_changes(&mut receiver, Duration::from_millis(20)).await.is_none());
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::Instant;
+use crate::core::prompts::language_family;
+use crate::models::provider::{CheckStatus, DiagnosticCheck, ProviderDiagnostic};
+use crate::services::translation_provider::TranslationProvider;
+
+fn check(name: &str, status: CheckStatus, message: impl Into<String>, latency_ms: Option<u64>) -> DiagnosticCheck {
+    DiagnosticCheck { name: name.to_string(), status, message: message.into(), latency_ms }
+}
+
+/// A short line in the source language for the smoke translation.
+fn sample_text(source_lang: &str) -> &'static str {
+    match language_family(source_lang).as_str() {
+        "ja" => "こんにちは、世界。",
+        "zh" => "你好，世界。",
+        "ko" => "안녕하세요, 세계.",
+        _ => "Hello, world.",
+    }
+}
+
+/// Ollama lists models with their tag ("mistral:latest") while settings often leave it out.
+fn has_model(available: &[String], model: &str) -> bool {
+    available.iter().any(|name| {
+        name == model || name.strip_suffix(":latest") == Some(model) || name.starts_with(&format!("{}:", model))
+    })
+}
+
+/// Checks that the provider is reachable, serves its configured model and can translate a
+/// sample line, timing each step. Later checks are skipped once one fails.
+pub async fn check_provider(provider: &dyn TranslationProvider, source_lang: &str, target_lang: &str) -> ProviderDiagnostic {
+    let mut checks = Vec::new();
+
+    let started = Instant::now();
+    let models = provider.list_models().await;
+    let latency = Some(started.elapsed().as_millis() as u64);
+    let models = match models {
+        Ok(models) => {
+            checks.push(check("connection", CheckStatus::Passed, format!("{} is reachable", provider.name()), latency));
+            models
+        }
+        Err(e) => {
+            checks.push(check("connection", CheckStatus::Failed, e, latency));
+            checks.push(check("model", CheckStatus::Skipped, "Skipped: the provider is unreachable", None));
+            checks.push(check("translation", CheckStatus::Skipped, "Skipped: the provider is unreachable", None));
+            return ProviderDiagnostic { provider: provider.name().to_string(), healthy: false, checks };
+        }
+    };
+
+    let model_check = match (provider.model(), &models) {
+        (Some(model), Some(available)) if has_model(available, model) => {
+            check("model", CheckStatus::Passed, format!("Model '{}' is available", model), None)
+        }
+        (Some(model), Some(available)) => {
+            let listed = if available.is_empty() { "none".to_string() } else { available.join(", ") };
+            check(
+                "model",
+                CheckStatus::Failed,
+                format!("Model '{}' is not available (found: {}). Pull it or pick another model.", model, listed),
+                None,
+            )
+        }
+        (Some(model), None) => {
+            check("model", CheckStatus::Skipped, format!("The provider doesn't list its models; using '{}'", model), None)
+        }
+        (None, _) => check("model", CheckStatus::Skipped, "The provider has no model to choose", None),
+    };
+    let model_failed = model_check.status == CheckStatus::Failed;
+    checks.push(model_check);
+    if model_failed {
+        checks.push(check("translation", CheckStatus::Skipped, "Skipped: the model is not available", None));
+        return ProviderDiagnostic { provider: provider.name().to_string(), healthy: false, checks };
+    }
+
+    let sample = sample_text(source_lang);
+    let started = Instant::now();
+    let result = provider.translate(sample, source_lang, target_lang).await;
+    let latency = Some(started.elapsed().as_millis() as u64);
+    checks.push(match result {
+        Ok(translation) if translation.text.trim().is_empty() => {
+            check("translation", CheckStatus::Warning, format!("'{}' came back empty", sample), latency)
+        }
+        Ok(translation) if translation.text.trim() == sample => check(
+            "translation",
+            CheckStatus::Warning,
+            format!("'{}' came back untranslated; check the language settings", sample),
+            latency,
+        ),
+        Ok(translation) => {
+            check("translation", CheckStatus::Passed, format!("'{}' → '{}'", sample, translation.text.trim()), latency)
+        }
+        Err(e) => check("translation", CheckStatus::Failed, e, latency),
+    });
+
+    let healthy = checks.iter().all(|c| c.status != CheckStatus::Failed);
+    ProviderDiagnostic { provider: provider.name().to_string(), healthy, checks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use crate::services::translation_provider::ProviderTranslation;
+
+    struct FakeProvider {
+        models: Result<Option<Vec<String>>, String>,
+        answer: &'static str,
+    }
+
+    #[async_trait]
+    impl TranslationProvider for FakeProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        async fn translate(&self, _: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            Ok(ProviderTranslation { text: self.answer.to_string(), provider: "fake".to_string() })
+        }
+
+        fn model(&self) -> Option<&str> {
+            Some("mistral")
+        }
+
+        async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+            self.models.clone()
+        }
+    }
+
+    fn statuses(diagnostic: &ProviderDiagnostic) -> Vec<CheckStatus> {
+        diagnostic.checks.iter().map(|c| c.status).collect()
+    }
+
+    #[tokio::test]
+    async fn test_check_provider_reports_each_step() {
+        let ok = FakeProvider { models: Ok(Some(vec!["mistral:latest".to_string()])), answer: "Hello, world." };
+        let diagnostic = check_provider(&ok, "ja", "en").await;
+        assert!(diagnostic.healthy);
+        assert_eq!(statuses(&diagnostic), vec![CheckStatus::Passed; 3]);
+        assert!(diagnostic.checks[2].message.contains("こんにちは"));
+
+        let missing = FakeProvider { models: Ok(Some(vec!["llama3:8b".to_string()])), answer: "" };
+        let diagnostic = check_provider(&missing, "ja", "en").await;
+        assert!(!diagnostic.healthy);
+        assert_eq!(statuses(&diagnostic), vec![CheckStatus::Passed, CheckStatus::Failed, CheckStatus::Skipped]);
+        assert!(diagnostic.checks[1].message.contains("llama3:8b"));
+
+        let down = FakeProvider { models: Err("connection refused".to_string()), answer: "" };
+        let diagnostic = check_provider(&down, "ja", "en").await;
+        assert_eq!(statuses(&diagnostic), vec![CheckStatus::Failed, CheckStatus::Skipped, CheckStatus::Skipped]);
+
+        let echo = FakeProvider { models: Ok(None), answer: "こんにちは、世界。" };
+        let diagnostic = check_provider(&echo, "ja", "en").await;
+        assert!(diagnostic.healthy);
+        assert_eq!(statuses(&diagnostic), vec![CheckStatus::Passed, CheckStatus::Skipped, CheckStatus::Warning]);
+    }
+}
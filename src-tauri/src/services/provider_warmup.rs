@@ -0,0 +1,136 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use crate::models::provider::WarmupStatus;
+use crate::services::translation_provider::TranslationProvider;
+
+/// Ollama unloads idle models after 5 minutes by default, so ping a bit more often than that.
+pub const DEFAULT_WARMUP_INTERVAL_SECS: u64 = 240;
+
+struct WarmupTask {
+    handle: JoinHandle<()>,
+    status: Arc<Mutex<WarmupStatus>>,
+}
+
+/// Pings one provider periodically while a project is open, so single-line translations and
+/// small batches don't pay the model load time. Managed as Tauri state; starting again
+/// replaces the previous provider.
+#[derive(Default)]
+pub struct ProviderWarmup {
+    task: Mutex<Option<WarmupTask>>,
+}
+
+impl ProviderWarmup {
+    /// Must be called from within the Tokio runtime. The first ping is sent immediately.
+    pub fn start(&self, provider: Box<dyn TranslationProvider>, interval: Duration) {
+        let status = Arc::new(Mutex::new(WarmupStatus {
+            active: true,
+            provider: Some(provider.name().to_string()),
+            interval_secs: interval.as_secs(),
+            ..Default::default()
+        }));
+        let task_status = Arc::clone(&status);
+        let handle = tokio::spawn(async move {
+            loop {
+                let started = Instant::now();
+                let result = provider.warm_up().await;
+                {
+                    let mut status = lock(&task_status);
+                    status.pings += 1;
+                    status.last_latency_ms = Some(started.elapsed().as_millis() as u64);
+                    status.last_error = result.err();
+                    if let Some(error) = &status.last_error {
+                        eprintln!("Warm-up of provider {} failed: {}", provider.name(), error);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+        if let Some(previous) = self.lock().replace(WarmupTask { handle, status }) {
+            previous.handle.abort();
+        }
+    }
+
+    /// Stops pinging. Returns false if no warm-up was running.
+    pub fn stop(&self) -> bool {
+        match self.lock().take() {
+            Some(task) => {
+                task.handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn status(&self) -> WarmupStatus {
+        match self.lock().as_ref() {
+            Some(task) => lock(&task.status).clone(),
+            None => WarmupStatus::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<WarmupTask>> {
+        lock(&self.task)
+    }
+}
+
+impl Drop for ProviderWarmup {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // Status updates are single assignments; a poisoned lock still holds usable data.
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use async_trait::async_trait;
+    use crate::services::translation_provider::ProviderTranslation;
+
+    struct CountingProvider(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl TranslationProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn translate(&self, text: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            Ok(ProviderTranslation { text: text.to_string(), provider: self.name().to_string() })
+        }
+
+        async fn warm_up(&self) -> Result<(), String> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pings_until_stopped() {
+        let pings = Arc::new(AtomicUsize::new(0));
+        let warmup = ProviderWarmup::default();
+        warmup.start(Box::new(CountingProvider(Arc::clone(&pings))), Duration::from_millis(10));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pings.load(Ordering::SeqCst) < 3 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let status = warmup.status();
+        assert!(status.active && status.pings >= 2 && status.last_error.is_none());
+        assert_eq!(status.provider.as_deref(), Some("counting"));
+
+        assert!(warmup.stop());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let after_stop = pings.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(pings.load(Ordering::SeqCst), after_stop);
+        assert_eq!(warmup.status(), WarmupStatus::default());
+        assert!(!warmup.stop());
+    }
+}
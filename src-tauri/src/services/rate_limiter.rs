@@ -0,0 +1,124 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+use crate::services::translation_provider::{PartialTextSink, PromptContext, ProviderTranslation, TranslationProvider};
+
+/// Spaces request starts evenly so no more than `n` start per minute, however many
+/// requests are in flight concurrently.
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimiter {
+            interval: Duration::from_secs(60) / requests.max(1),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits for the next free slot.
+    pub async fn acquire(&self) {
+        let start = {
+            let mut next_slot = self.next_slot.lock().await;
+            let start = (*next_slot).max(Instant::now());
+            *next_slot = start + self.interval;
+            start
+        };
+        sleep_until(start).await;
+    }
+}
+
+/// Wraps a provider so every request first takes a slot from its own rate limiter.
+pub struct RateLimitedProvider {
+    inner: Box<dyn TranslationProvider>,
+    limiter: RateLimiter,
+}
+
+impl RateLimitedProvider {
+    pub fn new(inner: Box<dyn TranslationProvider>, requests_per_minute: u32) -> Self {
+        RateLimitedProvider { inner, limiter: RateLimiter::per_minute(requests_per_minute) }
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for RateLimitedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn translate(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<ProviderTranslation, String> {
+        self.limiter.acquire().await;
+        self.inner.translate(text, source_lang, target_lang).await
+    }
+
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+    ) -> Result<ProviderTranslation, String> {
+        self.limiter.acquire().await;
+        self.inner.translate_with_context(text, source_lang, target_lang, context).await
+    }
+
+    async fn translate_streaming(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+        on_partial: &PartialTextSink<'_>,
+    ) -> Result<ProviderTranslation, String> {
+        self.limiter.acquire().await;
+        self.inner.translate_streaming(text, source_lang, target_lang, context, on_partial).await
+    }
+
+    // Pings don't count against the translation budget.
+    async fn warm_up(&self) -> Result<(), String> {
+        self.inner.warm_up().await
+    }
+
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        self.inner.list_models().await
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, String> {
+        self.limiter.acquire().await;
+        self.inner.complete(prompt).await
+    }
+}
+
+/// Applies the limit configured for the provider's name, if any.
+pub fn with_rate_limit(
+    provider: Box<dyn TranslationProvider>,
+    requests_per_minute: Option<u32>,
+) -> Box<dyn TranslationProvider> {
+    match requests_per_minute {
+        Some(limit) if limit > 0 => Box::new(RateLimitedProvider::new(provider, limit)),
+        _ => provider,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_are_spaced() {
+        let limiter = RateLimiter::per_minute(1200);
+        let started = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        // First request is immediate, the next two wait 50ms each.
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,215 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use async_trait::async_trait;
+use regex::Regex;
+use std::sync::OnceLock;
+use tokio::time::{sleep, Instant};
+use crate::models::provider::RetrySettings;
+use crate::services::translation_provider::{PartialTextSink, PromptContext, ProviderTranslation, TranslationProvider};
+
+/// Start of the error of requests refused by an open circuit.
+pub const CIRCUIT_OPEN_ERROR: &str = "Circuit open";
+
+/// Whether a provider error is worth retrying: rate limited (HTTP 429), a server error (HTTP
+/// 5xx), a timeout or an endpoint that couldn't be reached. Refused credentials, unsupported
+/// languages and exhausted quotas aren't.
+pub fn is_transient_error(error: &str) -> bool {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let re = PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)HTTP (429|5\d\d)\b|timed out|timeout|failed to reach").expect("transient error pattern is valid")
+    });
+    re.is_match(error)
+}
+
+#[derive(Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Wraps a provider so transient failures are retried with exponential backoff, and a provider
+/// that keeps failing is left alone for a while instead of failing every remaining entry slowly.
+/// Wrap it around the rate limiter so retries wait for their own slot.
+pub struct RetryingProvider {
+    inner: Box<dyn TranslationProvider>,
+    settings: RetrySettings,
+    circuit: Mutex<Circuit>,
+}
+
+impl RetryingProvider {
+    pub fn new(inner: Box<dyn TranslationProvider>, settings: RetrySettings) -> Self {
+        RetryingProvider { inner, settings, circuit: Mutex::new(Circuit::default()) }
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry);
+        Duration::from_millis(self.settings.initial_backoff_ms.saturating_mul(factor).min(self.settings.max_backoff_ms))
+    }
+
+    /// Refuses the request while the circuit is open; once the open period is over, requests go
+    /// through again and the next failure reopens it.
+    fn check_circuit(&self) -> Result<(), String> {
+        let circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        match circuit.open_until {
+            Some(until) if Instant::now() < until => Err(format!(
+                "{}: {} failed {} requests in a row; retrying in {} s",
+                CIRCUIT_OPEN_ERROR,
+                self.inner.name(),
+                circuit.consecutive_failures,
+                until.saturating_duration_since(Instant::now()).as_secs()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn record<T>(&self, outcome: &Result<T, String>) {
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        match outcome {
+            Err(e) if is_transient_error(e) => {
+                circuit.consecutive_failures += 1;
+                let threshold = self.settings.circuit_breaker_threshold;
+                if threshold > 0 && circuit.consecutive_failures >= threshold {
+                    circuit.open_until = Some(Instant::now() + Duration::from_millis(self.settings.circuit_open_ms));
+                }
+            }
+            _ => *circuit = Circuit::default(),
+        }
+    }
+
+    async fn with_retries<T, F, Fut>(&self, request: F) -> Result<T, String>
+    where
+        F: Fn() -> Fut + Send + Sync,
+        Fut: std::future::Future<Output = Result<T, String>> + Send,
+        T: Send,
+    {
+        self.check_circuit()?;
+        let mut retry = 0;
+        let outcome = loop {
+            match request().await {
+                Err(e) if is_transient_error(&e) && retry < self.settings.max_retries => {
+                    eprintln!("{} failed ({}); retry {} of {}", self.inner.name(), e, retry + 1, self.settings.max_retries);
+                    sleep(self.backoff(retry)).await;
+                    retry += 1;
+                }
+                outcome => break outcome,
+            }
+        };
+        self.record(&outcome);
+        outcome
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for RetryingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn translate(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<ProviderTranslation, String> {
+        self.with_retries(|| self.inner.translate(text, source_lang, target_lang)).await
+    }
+
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+    ) -> Result<ProviderTranslation, String> {
+        self.with_retries(|| self.inner.translate_with_context(text, source_lang, target_lang, context)).await
+    }
+
+    async fn translate_streaming(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+        on_partial: &PartialTextSink<'_>,
+    ) -> Result<ProviderTranslation, String> {
+        self.with_retries(|| self.inner.translate_streaming(text, source_lang, target_lang, context, on_partial)).await
+    }
+
+    async fn warm_up(&self) -> Result<(), String> {
+        self.inner.warm_up().await
+    }
+
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        self.inner.list_models().await
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, String> {
+        self.with_retries(|| self.inner.complete(prompt)).await
+    }
+}
+
+/// Wraps the provider unless retries and the circuit breaker are both disabled.
+pub fn with_retry(provider: Box<dyn TranslationProvider>, settings: &RetrySettings) -> Box<dyn TranslationProvider> {
+    if settings.max_retries == 0 && settings.circuit_breaker_threshold == 0 {
+        return provider;
+    }
+    Box::new(RetryingProvider::new(provider, settings.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Fails its first `failures` requests with `error`.
+    struct FlakyProvider {
+        calls: AtomicUsize,
+        failures: usize,
+        error: &'static str,
+    }
+
+    #[async_trait]
+    impl TranslationProvider for FlakyProvider {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn translate(&self, text: &str, _: &str, _: &str) -> Result<ProviderTranslation, String> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(self.error.to_string());
+            }
+            Ok(ProviderTranslation { text: format!("<{}>", text), provider: "flaky".to_string() })
+        }
+    }
+
+    fn flaky(failures: usize, error: &'static str) -> FlakyProvider {
+        FlakyProvider { calls: AtomicUsize::new(0), failures, error }
+    }
+
+    fn settings(max_retries: u32, circuit_breaker_threshold: u32) -> RetrySettings {
+        RetrySettings { max_retries, initial_backoff_ms: 1, max_backoff_ms: 4, circuit_breaker_threshold, circuit_open_ms: 60_000 }
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried_until_the_circuit_opens() {
+        assert!(is_transient_error("OpenAI-compatible endpoint returned HTTP 429 Too Many Requests: slow down"));
+        assert!(is_transient_error("HTTP 503 Service Unavailable: "));
+        assert!(!is_transient_error("HTTP 403 Forbidden: wrong key"));
+
+        let provider = RetryingProvider::new(Box::new(flaky(2, "HTTP 502 Bad Gateway")), settings(3, 0));
+        assert_eq!(provider.translate("やあ", "ja", "en").await.unwrap().text, "<やあ>");
+        assert_eq!(provider.backoff(0), Duration::from_millis(1));
+        assert_eq!(provider.backoff(5), Duration::from_millis(4));
+
+        // Errors that won't go away are returned at once.
+        let provider = RetryingProvider::new(Box::new(flaky(1, "HTTP 401 Unauthorized")), settings(3, 0));
+        assert!(provider.translate("やあ", "ja", "en").await.is_err());
+        assert_eq!(provider.translate("やあ", "ja", "en").await.unwrap().text, "<やあ>");
+
+        // Two requests exhaust their retries, then the circuit refuses without calling the provider.
+        let provider = RetryingProvider::new(Box::new(flaky(usize::MAX, "request timed out")), settings(1, 2));
+        for _ in 0..2 {
+            assert_eq!(provider.translate("やあ", "ja", "en").await.unwrap_err(), "request timed out");
+        }
+        assert!(provider.translate("やあ", "ja", "en").await.unwrap_err().starts_with(CIRCUIT_OPEN_ERROR));
+    }
+}
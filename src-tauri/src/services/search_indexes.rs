@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use crate::core::rpgmv::snapshot::{load_snapshot, EXTRACTION_SNAPSHOT_FILE_NAME};
+use crate::core::search::SearchIndex;
+use crate::core::session::{load_session, SESSION_FILE_NAME};
+use crate::error::CoreError;
+use crate::utils::project_paths::app_data_dir;
+
+// Modification time and size of a file an index was built from.
+type FileStamp = Option<(SystemTime, u64)>;
+// The index with the stamps of the extraction snapshot and the session it was built from.
+type CachedIndex = ((FileStamp, FileStamp), Arc<SearchIndex>);
+
+fn file_stamp(project_path: &str, file_name: &str) -> FileStamp {
+    let metadata = fs::metadata(app_data_dir(project_path).join(file_name)).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn stamps(project_path: &str) -> (FileStamp, FileStamp) {
+    (file_stamp(project_path, EXTRACTION_SNAPSHOT_FILE_NAME), file_stamp(project_path, SESSION_FILE_NAME))
+}
+
+/// Search indexes by project, over its last extraction and its session's translations. Managed
+/// as Tauri state; an index is rebuilt when either file changed since it was built, so
+/// searching a large project doesn't re-read and re-scan it for every query.
+#[derive(Default)]
+pub struct SearchIndexes {
+    indexes: Mutex<HashMap<String, CachedIndex>>,
+}
+
+impl SearchIndexes {
+    /// The project's index, or `None` if it was never extracted.
+    pub fn get(&self, project_path: &str) -> Result<Option<Arc<SearchIndex>>, CoreError> {
+        let stamps = stamps(project_path);
+        if let Some((built_from, index)) = self.lock().get(project_path) {
+            if *built_from == stamps {
+                return Ok(Some(Arc::clone(index)));
+            }
+        }
+        let Some(snapshot) = load_snapshot(project_path)? else { return Ok(None) };
+        let translations = load_session(project_path)?.map(|session| session.translations).unwrap_or_default();
+        let entries = snapshot.files.into_values().flat_map(|file| file.entries).collect();
+        let index = Arc::new(SearchIndex::new(entries, &translations));
+        self.lock().insert(project_path.to_string(), (stamps, Arc::clone(&index)));
+        Ok(Some(index))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedIndex>> {
+        self.indexes.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,56 @@
+/// Complete lines at the start of `buffer`, removed from it; a trailing partial line stays until
+/// the rest of it arrives. Lines are cut on bytes, so multi-byte characters split across chunks
+/// are decoded whole.
+pub fn drain_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let Some(end) = buffer.iter().rposition(|&b| b == b'\n') else {
+        return Vec::new();
+    };
+    let complete: Vec<u8> = buffer.drain(..=end).collect();
+    String::from_utf8_lossy(&complete)
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Reads a streamed response (newline-delimited JSON or server-sent events) line by line, handing
+/// each line to `on_line` until it returns `Ok(false)` or the body ends.
+pub async fn for_each_line<F>(mut response: reqwest::Response, mut on_line: F) -> Result<(), String>
+where
+    F: FnMut(&str) -> Result<bool, String>,
+{
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Streamed response interrupted: {}", e))? {
+        buffer.extend_from_slice(&chunk);
+        for line in drain_lines(&mut buffer) {
+            if !on_line(&line)? {
+                return Ok(());
+            }
+        }
+    }
+    buffer.push(b'\n');
+    for line in drain_lines(&mut buffer) {
+        if !on_line(&line)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_are_split_across_chunks() {
+        let text = "{\"response\":\"こん\"}\r\n\n{\"response\":\"にちは\"}\n{\"done\":";
+        let bytes = text.as_bytes();
+        // Cut in the middle of "に".
+        let cut = text.find('に').unwrap() + 1;
+        let mut buffer = bytes[..cut].to_vec();
+        assert_eq!(drain_lines(&mut buffer), vec!["{\"response\":\"こん\"}"]);
+        buffer.extend_from_slice(&bytes[cut..]);
+        assert_eq!(drain_lines(&mut buffer), vec!["{\"response\":\"にちは\"}"]);
+        assert_eq!(buffer, b"{\"done\":");
+    }
+}
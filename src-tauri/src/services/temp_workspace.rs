@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use walkdir::WalkDir;
+use crate::error::CoreError;
+
+/// Folder under the system temp directory holding every workspace the app creates.
+pub const WORKSPACES_DIR_NAME: &str = "translate-ai-workspaces";
+/// Workspaces are refused once the folder holds this much (4 GiB).
+pub const DEFAULT_QUOTA_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// What a workspace is for; it prefixes the folder name so usage can be broken down.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceKind {
+    // Packaged translated projects waiting to be saved.
+    Output,
+    // Files prepared before they are moved into place.
+    Staging,
+}
+
+impl WorkspaceKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            WorkspaceKind::Output => "output",
+            WorkspaceKind::Staging => "staging",
+        }
+    }
+
+    fn from_dir_name(name: &str) -> Option<Self> {
+        let prefix = name.split('-').next()?;
+        [WorkspaceKind::Output, WorkspaceKind::Staging].into_iter().find(|kind| kind.prefix() == prefix)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceUsage {
+    pub name: String,
+    pub kind: Option<WorkspaceKind>,
+    pub bytes: u64,
+    // In use by a running command, or kept by this run and not yet discarded; everything else
+    // is removed by the orphan cleanup.
+    pub active: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TempUsage {
+    pub root: String,
+    pub total_bytes: u64,
+    pub quota_bytes: u64,
+    pub workspaces: Vec<WorkspaceUsage>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub removed: usize,
+    pub bytes_freed: u64,
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Creates and cleans up the app's temp workspaces. Managed as Tauri state; only one app
+/// instance is expected to use the folder at a time.
+pub struct TempWorkspaces {
+    root: PathBuf,
+    quota_bytes: u64,
+    // Workspaces of this run that are in use or kept, which the orphan cleanup leaves alone.
+    active: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl Default for TempWorkspaces {
+    fn default() -> Self {
+        TempWorkspaces::new(std::env::temp_dir().join(WORKSPACES_DIR_NAME), DEFAULT_QUOTA_BYTES)
+    }
+}
+
+impl TempWorkspaces {
+    pub fn new(root: PathBuf, quota_bytes: u64) -> Self {
+        TempWorkspaces { root, quota_bytes, active: Arc::new(Mutex::new(HashSet::new())) }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<PathBuf>> {
+        self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Creates an empty workspace. It is deleted when the returned guard is dropped, so a failed
+    /// or cancelled command leaves nothing behind; call `Workspace::keep` for output that must
+    /// outlive the command. Fails when the workspaces already use the quota, after removing
+    /// orphans; kept workspaces count until they are discarded.
+    pub fn create(&self, kind: WorkspaceKind) -> Result<Workspace, CoreError> {
+        if self.usage().total_bytes >= self.quota_bytes {
+            self.cleanup_orphans()?;
+            let total = self.usage().total_bytes;
+            if total >= self.quota_bytes {
+                return Err(CoreError::Io(format!(
+                    "Temp workspaces in {} use {} bytes, over the {} byte quota",
+                    self.root.display(), total, self.quota_bytes
+                )));
+            }
+        }
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let path = self.root.join(format!("{}-{}-{}", kind.prefix(), std::process::id(), nanos));
+        fs::create_dir_all(&path)
+            .map_err(|e| CoreError::Io(format!("Failed to create temp workspace {}: {}", path.display(), e)))?;
+        self.lock().insert(path.clone());
+        Ok(Workspace { active: Arc::clone(&self.active), path, keep: false })
+    }
+
+    /// Removes the workspace containing `path` (e.g. a packaged ZIP once it has been saved).
+    /// Paths outside the workspaces folder are left alone.
+    pub fn discard(&self, path: &Path) -> Result<(), CoreError> {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return Ok(());
+        };
+        let Some(workspace) = relative.components().next().map(|c| self.root.join(c)) else {
+            return Ok(());
+        };
+        self.lock().remove(&workspace);
+        if workspace.exists() {
+            fs::remove_dir_all(&workspace)?;
+        }
+        Ok(())
+    }
+
+    /// Removes every workspace not in use or kept by this process: leftovers of crashed or killed
+    /// runs, and output they kept that was never saved. Run at startup, and when over the quota.
+    pub fn cleanup_orphans(&self) -> Result<CleanupReport, CoreError> {
+        let mut report = CleanupReport::default();
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Ok(report);
+        };
+        let active = self.lock().clone();
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if active.contains(&path) {
+                continue;
+            }
+            let bytes = dir_size(&path);
+            let removed = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+            match removed {
+                Ok(()) => {
+                    report.removed += 1;
+                    report.bytes_freed += bytes;
+                }
+                Err(e) => eprintln!("Failed to remove orphaned temp workspace {}: {}", path.display(), e),
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn usage(&self) -> TempUsage {
+        let active = self.lock().clone();
+        let mut workspaces: Vec<WorkspaceUsage> = fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| {
+                        let name = entry.file_name().to_string_lossy().into_owned();
+                        WorkspaceUsage {
+                            kind: WorkspaceKind::from_dir_name(&name),
+                            bytes: dir_size(&entry.path()),
+                            active: active.contains(&entry.path()),
+                            name,
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+        TempUsage {
+            root: self.root.to_string_lossy().into_owned(),
+            total_bytes: workspaces.iter().map(|w| w.bytes).sum(),
+            quota_bytes: self.quota_bytes,
+            workspaces,
+        }
+    }
+}
+
+/// A temp workspace folder, deleted on drop unless kept.
+pub struct Workspace {
+    active: Arc<Mutex<HashSet<PathBuf>>>,
+    path: PathBuf,
+    keep: bool,
+}
+
+impl Workspace {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keeps the folder after the guard is dropped, e.g. a packaged ZIP the frontend refers to. It
+    /// stays until `TempWorkspaces::discard` or the next startup's orphan cleanup.
+    pub fn keep(mut self) -> PathBuf {
+        self.keep = true;
+        self.path.clone()
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if self.keep {
+            return;
+        }
+        self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&self.path);
+        if self.path.exists() {
+            if let Err(e) = fs::remove_dir_all(&self.path) {
+                eprintln!("Failed to remove temp workspace {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_workspace_lifecycle() {
+        let dir = tempdir().unwrap();
+        let workspaces = TempWorkspaces::new(dir.path().join("workspaces"), 1024);
+
+        let dropped_path = {
+            let workspace = workspaces.create(WorkspaceKind::Staging).unwrap();
+            fs::write(workspace.path().join("Map001.json"), "{}").unwrap();
+            let usage = workspaces.usage();
+            assert_eq!(usage.total_bytes, 2);
+            assert_eq!(usage.workspaces[0].kind, Some(WorkspaceKind::Staging));
+            assert!(usage.workspaces[0].active);
+            workspace.path().to_path_buf()
+        };
+        assert!(!dropped_path.exists());
+
+        let kept = workspaces.create(WorkspaceKind::Output).unwrap().keep();
+        fs::write(kept.join("output.zip"), vec![0u8; 2048]).unwrap();
+        assert!(workspaces.usage().workspaces[0].active);
+        // Over the quota: the kept output is still referenced, so it isn't removed to make room.
+        assert!(workspaces.create(WorkspaceKind::Staging).is_err());
+        assert!(kept.exists());
+        // A previous run's leftovers are.
+        let leftover = dir.path().join("workspaces").join("output-1-1");
+        fs::create_dir_all(&leftover).unwrap();
+        assert_eq!(workspaces.cleanup_orphans().unwrap().removed, 1);
+        assert!(kept.exists() && !leftover.exists());
+
+        workspaces.discard(&kept.join("output.zip")).unwrap();
+        let active = workspaces.create(WorkspaceKind::Staging).unwrap();
+        assert!(!kept.exists());
+        assert_eq!(workspaces.cleanup_orphans().unwrap(), CleanupReport::default());
+        assert!(active.path().exists());
+
+        workspaces.discard(&active.path().join("anything")).unwrap();
+        assert!(!active.path().exists());
+    }
+}
@@ -0,0 +1,265 @@
+use async_trait::async_trait;
+use crate::core::prompts::{map_language_code_to_name, render_template};
+use crate::core::references::describe_reference;
+use crate::models::glossary::GlossaryTerm;
+use crate::models::provider::ProviderConfig;
+use crate::models::reference::ReferenceLink;
+use crate::services::deepl::DeeplClient;
+use crate::services::ollama_client::OllamaClient;
+use crate::services::openai_client::OpenAiClient;
+
+/// Text returned by a provider, along with the name of the provider that actually produced it
+/// (which may differ from the requested one when a fallback kicked in).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderTranslation {
+    pub text: String,
+    pub provider: String,
+}
+
+/// Receives the text generated so far while a translation streams in; returning false stops the
+/// generation.
+pub type PartialTextSink<'a> = dyn Fn(&str) -> bool + Send + Sync + 'a;
+
+/// Error of a streamed translation stopped by its `PartialTextSink`.
+pub const GENERATION_ABORTED_ERROR: &str = "Generation aborted";
+
+/// Version of the built-in prompt wording. Bump it when the prompts change so that responses
+/// cached with the old wording are no longer reused.
+pub const PROMPT_TEMPLATE_VERSION: u32 = 1;
+
+/// Extra information for prompt-based providers, gathered per entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptContext {
+    // Glossary terms occurring in the text, which must be translated as given.
+    pub glossary: Vec<GlossaryTerm>,
+    // Reference links/notes attached to the entry itself.
+    pub references: Vec<ReferenceLink>,
+    // How to translate the text, e.g. the shared instructions of a System message group.
+    pub instructions: Vec<String>,
+    // Who says the line, for dialogue.
+    pub speaker: Option<String>,
+    // Synopsis of the map or scene the text belongs to (see `core::scope_summaries`).
+    pub scope_summary: Option<String>,
+    // Source lines said just before and after the text (nearest last / first), shown to the
+    // provider as read-only context.
+    pub previous_lines: Vec<String>,
+    pub next_lines: Vec<String>,
+    // Project prompt template for the entry's kind (see `core::prompts`); prompt-based providers
+    // use it instead of their own wording.
+    pub template: Option<String>,
+}
+
+impl PromptContext {
+    pub fn is_empty(&self) -> bool {
+        self.glossary.is_empty()
+            && self.references.is_empty()
+            && self.instructions.is_empty()
+            && self.speaker.is_none()
+            && self.scope_summary.is_none()
+            && self.previous_lines.is_empty()
+            && self.next_lines.is_empty()
+    }
+
+    /// Hash identifying this context and the prompt version in the translation memory. It only
+    /// depends on what is sent for the entry, so editing glossary terms the text doesn't contain
+    /// (or reordering the glossary) keeps it.
+    pub fn cache_key(&self) -> String {
+        let mut terms: Vec<String> = self
+            .glossary
+            .iter()
+            .map(|term| format!("{}\t{}\t{}", term.source, term.target, term.references.iter().map(describe_reference).collect::<Vec<_>>().join("; ")))
+            .collect();
+        terms.sort();
+        let mut references: Vec<String> = self.references.iter().map(describe_reference).collect();
+        references.sort();
+        let mut key = format!(
+            "v{}\n{}\n{}\n{}",
+            PROMPT_TEMPLATE_VERSION,
+            self.instructions.join("\n"),
+            terms.join("\n"),
+            references.join("\n")
+        );
+        // Appended only when set, so keys of entries without them stay the same.
+        if let Some(speaker) = &self.speaker {
+            key.push_str(&format!("\nspeaker:{}", speaker));
+        }
+        if let Some(summary) = &self.scope_summary {
+            key.push_str(&format!("\nscope:{}", summary));
+        }
+        if !self.previous_lines.is_empty() || !self.next_lines.is_empty() {
+            key.push_str(&format!("\nbefore:{}\nafter:{}", self.previous_lines.join("\n"), self.next_lines.join("\n")));
+        }
+        if let Some(template) = &self.template {
+            key.push_str(&format!("\ntemplate:{}", template));
+        }
+        format!("{:x}", md5::compute(key))
+    }
+
+    fn glossary_block(&self) -> String {
+        let mut out = String::new();
+        if !self.glossary.is_empty() {
+            out.push_str("Always translate these terms exactly as given:\n");
+            for term in &self.glossary {
+                let notes: Vec<String> = term.references.iter().map(describe_reference).collect();
+                if notes.is_empty() {
+                    out.push_str(&format!("- {} => {}\n", term.source, term.target));
+                } else {
+                    out.push_str(&format!("- {} => {} ({})\n", term.source, term.target, notes.join("; ")));
+                }
+            }
+        }
+        out
+    }
+
+    fn summary_block(&self) -> String {
+        match &self.scope_summary {
+            Some(summary) => format!("What happens in this scene: {}\n", summary.trim()),
+            None => String::new(),
+        }
+    }
+
+    fn surrounding_block(&self) -> String {
+        let mut out = String::new();
+        for (lines, position) in [(&self.previous_lines, "before"), (&self.next_lines, "after")] {
+            if !lines.is_empty() {
+                out.push_str(&format!("Lines {} this text, for context only (do not translate them):\n", position));
+                for line in lines {
+                    out.push_str(&format!("- {}\n", line.replace('\n', " ")));
+                }
+            }
+        }
+        out
+    }
+
+    fn references_block(&self) -> String {
+        let mut out = String::new();
+        if !self.references.is_empty() {
+            out.push_str("Background notes for this text:\n");
+            for reference in &self.references {
+                out.push_str(&format!("- {}\n", describe_reference(reference)));
+            }
+        }
+        out
+    }
+
+    /// Instructions appended to the prompt, or an empty string when there is no context.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for instruction in &self.instructions {
+            out.push_str(instruction);
+            out.push('\n');
+        }
+        if let Some(speaker) = &self.speaker {
+            out.push_str(&format!("The line is spoken by {}.\n", speaker));
+        }
+        out.push_str(&self.summary_block());
+        out.push_str(&self.surrounding_block());
+        out.push_str(&self.glossary_block());
+        out.push_str(&self.references_block());
+        out
+    }
+
+    /// The full prompt from the project template, or `None` without one. `{context}` holds the
+    /// instructions, scene summary, surrounding lines and background notes, `{glossary}` the terms.
+    pub fn render_template(&self, text: &str, source_lang: &str, target_lang: &str) -> Option<String> {
+        let template = self.template.as_deref()?;
+        let mut notes: String = self.instructions.iter().map(|i| format!("{}\n", i)).collect();
+        notes.push_str(&self.summary_block());
+        notes.push_str(&self.surrounding_block());
+        notes.push_str(&self.references_block());
+        let glossary = self.glossary_block();
+        Some(render_template(
+            template,
+            &[
+                ("source_lang", map_language_code_to_name(source_lang)),
+                ("target_lang", map_language_code_to_name(target_lang)),
+                ("glossary", glossary.trim_end()),
+                ("speaker", self.speaker.as_deref().unwrap_or_default()),
+                ("context", notes.trim_end()),
+                ("text", text),
+            ],
+        ))
+    }
+}
+
+/// Common interface for all translation backends (Ollama, DeepL, ...).
+/// Batch translation only talks to this trait, so providers can be swapped per batch.
+#[async_trait]
+pub trait TranslationProvider: Send + Sync {
+    /// Short identifier recorded in `WorkingTranslation::translation_source`.
+    fn name(&self) -> &str;
+
+    async fn translate(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> Result<ProviderTranslation, String>;
+
+    /// Same as `translate`, with extra context to inject into the prompt.
+    /// Providers that are not prompt-based ignore the context.
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+    ) -> Result<ProviderTranslation, String> {
+        let _ = context;
+        self.translate(text, source_lang, target_lang).await
+    }
+
+    /// `translate_with_context`, handing the text generated so far to `on_partial` as it comes in.
+    /// Backends that can't stream answer in one go, without partial text.
+    async fn translate_streaming(
+        &self,
+        text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        context: &PromptContext,
+        on_partial: &PartialTextSink<'_>,
+    ) -> Result<ProviderTranslation, String> {
+        let _ = on_partial;
+        self.translate_with_context(text, source_lang, target_lang, context).await
+    }
+
+    /// Cheap request that keeps the backend ready (e.g. a local model loaded in memory).
+    /// Hosted APIs have nothing to warm up.
+    async fn warm_up(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// The model requests go to, for backends that have models.
+    fn model(&self) -> Option<&str> {
+        None
+    }
+
+    /// Cheap request checking that the backend is reachable (and the credentials accepted),
+    /// returning the models it offers. `Ok(None)` when the backend has no models to list.
+    async fn list_models(&self) -> Result<Option<Vec<String>>, String> {
+        Ok(None)
+    }
+
+    /// Answers a free-form prompt (e.g. summarizing a scene) with plain text. Only language
+    /// models can; other backends refuse.
+    async fn complete(&self, prompt: &str) -> Result<String, String> {
+        let _ = prompt;
+        Err(format!("{} only translates and can't answer prompts; use a language model provider", self.name()))
+    }
+}
+
+/// Builds the provider selected by the frontend. `None` keeps the historical Ollama default.
+pub fn build_provider(config: Option<ProviderConfig>) -> Box<dyn TranslationProvider> {
+    match config.unwrap_or_default() {
+        ProviderConfig::Ollama => Box::new(OllamaClient),
+        ProviderConfig::Deepl(settings) => {
+            let fallback: Option<Box<dyn TranslationProvider>> = if settings.fallback_to_ollama {
+                Some(Box::new(OllamaClient))
+            } else {
+                None
+            };
+            Box::new(DeeplClient::new(settings, fallback))
+        }
+        ProviderConfig::OpenAiCompatible(settings) => Box::new(OpenAiClient::new(settings)),
+    }
+}
@@ -1,28 +1,83 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use walkdir::WalkDir;
 use zip::write::{FileOptions, ZipWriter};
-use zip::CompressionMethod;
+use zip::{CompressionMethod, ZipArchive};
 use crate::error::CoreError;
+use crate::models::archive::ArchiveFormat;
+use crate::utils::archive_time::archive_timestamp;
+use crate::utils::project_paths::APP_DATA_DIR_NAME;
+
+/// 1980-01-01 00:00 UTC, the earliest time a ZIP entry can carry.
+pub const DEFAULT_ARCHIVE_TIMESTAMP: i64 = 315_532_800;
+
+/// How archives are written. Entries are always in path order and all carry the same
+/// modification time, so packaging the same files twice gives the same bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveOptions {
+    // Modification time of every entry, as Unix seconds (UTC).
+    pub timestamp: i64,
+    // 0 to 9; the format's default when absent.
+    pub compression_level: Option<i64>,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        ArchiveOptions { timestamp: DEFAULT_ARCHIVE_TIMESTAMP, compression_level: None }
+    }
+}
+
+pub fn validate_compression_level(level: i64) -> Result<(), CoreError> {
+    if (0..=9).contains(&level) {
+        Ok(())
+    } else {
+        Err(CoreError::Config(format!("Compression level {} is outside 0-9", level)))
+    }
+}
 
 pub fn create_zip_archive_from_memory(
     data: &HashMap<String, String>,
     output_zip_path: &Path,
 ) -> Result<(), CoreError> {
+    create_zip_archive_with_binary_files(data, &HashMap::new(), output_zip_path, &ArchiveOptions::default())
+}
+
+/// Same as `create_zip_archive_from_memory`, with binary files (e.g. VX Ace `.rvdata2`) added
+/// alongside the text files.
+pub fn create_zip_archive_with_binary_files(
+    data: &HashMap<String, String>,
+    binary_data: &HashMap<String, Vec<u8>>,
+    output_zip_path: &Path,
+    archive_options: &ArchiveOptions,
+) -> Result<(), CoreError> {
+    let modified_at = archive_timestamp(archive_options.timestamp)?;
+    if let Some(level) = archive_options.compression_level {
+        validate_compression_level(level)?;
+    }
     let file = File::create(output_zip_path)
         .map_err(|e| CoreError::Io(format!("Failed to create ZIP file at {:?}: {}", output_zip_path, e)))?;
 
     let mut zip_writer = ZipWriter::new(file);
     let options = FileOptions::<'static, ()>::default()
         .compression_method(CompressionMethod::Deflated)
+        .compression_level(archive_options.compression_level)
+        .last_modified_time(modified_at)
         .unix_permissions(0o755); // Set some default permissions, common for executables/data
 
-    for (relative_path, content_string) in data {
+    let text_files = data.iter().map(|(path, content)| (path, content.as_bytes()));
+    let binary_files = binary_data.iter().map(|(path, content)| (path, content.as_slice()));
+    let mut files: Vec<(&String, &[u8])> = text_files.chain(binary_files).collect();
+    files.sort_by(|a, b| a.0.cmp(b.0));
+    for (relative_path, content) in files {
         zip_writer.start_file(relative_path, options)
             .map_err(|e| CoreError::Zip(format!("Failed to start file {} in ZIP: {}", relative_path, e)))?;
         
-        zip_writer.write_all(content_string.as_bytes())
+        zip_writer.write_all(content)
             .map_err(|e| CoreError::Io(format!("Failed to write content for {} to ZIP: {}", relative_path, e)))?;
     }
 
@@ -32,6 +87,216 @@ pub fn create_zip_archive_from_memory(
     Ok(())
 }
 
+/// Content of a file to archive: in memory, or read from disk while the archive is written so a
+/// full copy of a game doesn't have to fit in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveEntry {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+impl ArchiveEntry {
+    fn open(&self) -> Result<Box<dyn Read + '_>, CoreError> {
+        match self {
+            ArchiveEntry::Bytes(content) => Ok(Box::new(content.as_slice())),
+            ArchiveEntry::File(path) => File::open(path)
+                .map(|file| Box::new(file) as Box<dyn Read>)
+                .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", path, e))),
+        }
+    }
+
+    fn len(&self) -> Result<u64, CoreError> {
+        match self {
+            ArchiveEntry::Bytes(content) => Ok(content.len() as u64),
+            ArchiveEntry::File(path) => fs::metadata(path)
+                .map(|metadata| metadata.len())
+                .map_err(|e| CoreError::Io(format!("Failed to read {:?}: {}", path, e))),
+        }
+    }
+}
+
+/// Files to archive by path, which keeps the entries in path order.
+pub type ArchiveFiles = BTreeMap<String, ArchiveEntry>;
+
+/// Files under `dir` (relative to the project; the whole project when empty), keyed by their
+/// path relative to the project with `/` separators. The app's own folder is left out. Only
+/// the paths are collected; the files are read when archived.
+pub fn project_files(project_path: &str, dir: &str) -> Result<ArchiveFiles, CoreError> {
+    let root = Path::new(project_path);
+    let mut files = ArchiveFiles::new();
+    let entries = WalkDir::new(root.join(dir)).into_iter().filter_entry(|e| e.file_name() != APP_DATA_DIR_NAME);
+    for entry in entries {
+        let entry = entry.map_err(|e| CoreError::Io(format!("Failed to list {:?}: {}", root.join(dir), e)))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative_path) = entry.path().strip_prefix(root) else { continue };
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        files.insert(relative_path, ArchiveEntry::File(entry.path().to_path_buf()));
+    }
+    Ok(files)
+}
+
+/// Every file of the ZIP at `zip_path`, by path.
+pub fn read_zip_archive(zip_path: &Path) -> Result<ArchiveFiles, CoreError> {
+    let file = File::open(zip_path).map_err(|e| CoreError::Io(format!("Failed to open ZIP file at {:?}: {}", zip_path, e)))?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut files = ArchiveFiles::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| CoreError::Io(format!("Failed to read {} from {:?}: {}", entry.name(), zip_path, e)))?;
+        files.insert(entry.name().to_string(), ArchiveEntry::Bytes(content));
+    }
+    Ok(files)
+}
+
+/// Writes `files` as a new ZIP archive at `output_path`, in path order.
+pub fn write_zip_archive(
+    files: &ArchiveFiles,
+    output_path: &Path,
+    archive_options: &ArchiveOptions,
+) -> Result<(), CoreError> {
+    let modified_at = archive_timestamp(archive_options.timestamp)?;
+    if let Some(level) = archive_options.compression_level {
+        validate_compression_level(level)?;
+    }
+    let file = File::create(output_path)
+        .map_err(|e| CoreError::Io(format!("Failed to create ZIP file at {:?}: {}", output_path, e)))?;
+    let mut zip_writer = ZipWriter::new(file);
+    let options = FileOptions::<'static, ()>::default()
+        .compression_method(CompressionMethod::Deflated)
+        .compression_level(archive_options.compression_level)
+        .last_modified_time(modified_at)
+        .unix_permissions(0o755);
+    for (relative_path, entry) in files {
+        // Files streamed from disk may need ZIP64.
+        let options = options.large_file(entry.len()? >= u64::from(u32::MAX));
+        zip_writer
+            .start_file(relative_path, options)
+            .map_err(|e| CoreError::Zip(format!("Failed to start file {} in ZIP: {}", relative_path, e)))?;
+        std::io::copy(&mut entry.open()?, &mut zip_writer)
+            .map_err(|e| CoreError::Io(format!("Failed to write content for {} to ZIP: {}", relative_path, e)))?;
+    }
+    zip_writer.finish().map_err(|e| CoreError::Zip(format!("Failed to finish ZIP archive: {}", e)))?;
+    Ok(())
+}
+
+/// Header of a regular file in a tar archive. GNU headers take paths of any length (through a
+/// long name entry) and sizes past the 8 GiB a ustar header can hold.
+fn tar_header(size: u64, mtime: i64) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(mtime.max(0) as u64);
+    header
+}
+
+/// Writes `files` as a gzip-compressed tar archive, entries in path order with the archive
+/// timestamp, like ZIP archives.
+pub fn create_tar_gz_archive(
+    files: &ArchiveFiles,
+    output_path: &Path,
+    archive_options: &ArchiveOptions,
+) -> Result<(), CoreError> {
+    let level = match archive_options.compression_level {
+        Some(level) => {
+            validate_compression_level(level)?;
+            Compression::new(level as u32)
+        }
+        None => Compression::default(),
+    };
+    let file = File::create(output_path)
+        .map_err(|e| CoreError::Io(format!("Failed to create tar.gz file at {:?}: {}", output_path, e)))?;
+    let write_error = |e: std::io::Error| CoreError::Io(format!("Failed to write tar.gz file at {:?}: {}", output_path, e));
+    let mut builder = tar::Builder::new(GzEncoder::new(file, level));
+    for (path, entry) in files {
+        let size = entry.len()?;
+        let mut header = tar_header(size, archive_options.timestamp);
+        builder.append_data(&mut header, path, entry.open()?.take(size)).map_err(write_error)?;
+    }
+    builder.into_inner().and_then(|encoder| encoder.finish()).map_err(write_error)?;
+    Ok(())
+}
+
+/// Command line programs of 7-Zip, tried in order.
+const SEVEN_ZIP_PROGRAMS: [&str; 3] = ["7z", "7zz", "7za"];
+
+/// The 7-Zip command line program on the PATH, which 7z archives need. 7-Zip isn't bundled
+/// with the app.
+pub fn seven_zip_program() -> Option<&'static str> {
+    SEVEN_ZIP_PROGRAMS.into_iter().find(|program| {
+        // `7z i` lists the supported formats; only whether it runs matters.
+        Command::new(program).arg("i").stdout(Stdio::null()).stderr(Stdio::null()).status().is_ok()
+    })
+}
+
+/// Formats archives can be saved in on this machine.
+pub fn available_archive_formats() -> Vec<ArchiveFormat> {
+    let mut formats = vec![ArchiveFormat::Zip, ArchiveFormat::TarGz];
+    if seven_zip_program().is_some() {
+        formats.push(ArchiveFormat::SevenZip);
+    }
+    formats
+}
+
+pub fn seven_zip_missing() -> CoreError {
+    CoreError::Unimplemented("7z archives need the 7-Zip command line tool (7z, 7zz or 7za) on the PATH".to_string())
+}
+
+/// Writes `files` as a 7z archive with the 7-Zip command line tool, from a copy of them in
+/// `staging_dir` (which should be empty).
+pub fn create_7z_archive(
+    files: &ArchiveFiles,
+    output_path: &Path,
+    staging_dir: &Path,
+    archive_options: &ArchiveOptions,
+) -> Result<(), CoreError> {
+    if let Some(level) = archive_options.compression_level {
+        validate_compression_level(level)?;
+    }
+    let program = seven_zip_program().ok_or_else(seven_zip_missing)?;
+    for (path, entry) in files {
+        let target = staging_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let write_error = |e: std::io::Error| CoreError::Io(format!("Failed to write {:?}: {}", target, e));
+        let mut copy = File::create(&target).map_err(write_error)?;
+        std::io::copy(&mut entry.open()?, &mut copy).map_err(write_error)?;
+    }
+    // 7-Zip runs in the staging folder.
+    let output_path = std::env::current_dir()?.join(output_path);
+    let mut arguments = vec!["a".to_string(), "-t7z".to_string(), "-bd".to_string()];
+    if let Some(level) = archive_options.compression_level {
+        arguments.push(format!("-mx={}", level));
+    }
+    arguments.push(output_path.to_string_lossy().into_owned());
+    arguments.push(".".to_string());
+    let output = Command::new(program)
+        .args(&arguments)
+        .current_dir(staging_dir)
+        .output()
+        .map_err(|e| CoreError::Io(format!("Failed to run {}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(CoreError::Io(format!(
+            "{} failed to create {:?}: {}",
+            program,
+            output_path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,4 +367,115 @@ mod tests {
         dir.close().map_err(|e| format!("Failed to close temp dir: {}", e))?;
         Ok(())
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_create_zip_with_binary_files() -> Result<(), String> {
+        let dir = tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+        let zip_path = dir.path().join("binary_archive.zip");
+        let text = HashMap::from([("www/data/Actors.json".to_string(), "[]".to_string())]);
+        let binary = HashMap::from([("Data/Actors.rvdata2".to_string(), vec![0x04, 0x08, 0xff, 0x00])]);
+
+        create_zip_archive_with_binary_files(&text, &binary, &zip_path, &ArchiveOptions::default())
+            .map_err(|e| format!("create_zip_archive_with_binary_files failed: {:?}", e))?;
+
+        let zip_file = File::open(&zip_path).map_err(|e| format!("Failed to open created ZIP: {}", e))?;
+        let mut archive = ZipArchive::new(zip_file).map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+        assert_eq!(archive.len(), 2);
+        let mut contents = Vec::new();
+        archive
+            .by_name("Data/Actors.rvdata2")
+            .map_err(|e| format!("Data/Actors.rvdata2 not found in ZIP: {}", e))?
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read Data/Actors.rvdata2: {}", e))?;
+        assert_eq!(contents, vec![0x04, 0x08, 0xff, 0x00]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_archives_are_reproducible() {
+        let dir = tempdir().unwrap();
+        let data: HashMap<String, String> =
+            (1..=20).map(|i| (format!("www/data/Map{:03}.json", i), format!("{{\"id\":{}}}", i))).collect();
+        let build = |name: &str, options: &ArchiveOptions| {
+            let path = dir.path().join(name);
+            create_zip_archive_with_binary_files(&data, &HashMap::new(), &path, options).unwrap();
+            std::fs::read(path).unwrap()
+        };
+        assert_eq!(build("a.zip", &ArchiveOptions::default()), build("b.zip", &ArchiveOptions::default()));
+
+        let pinned = ArchiveOptions { timestamp: 1_700_000_000, ..Default::default() };
+        let bytes = build("c.zip", &pinned);
+        let mut archive = ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().name(), "www/data/Map001.json");
+        let modified = archive.by_index(19).unwrap().last_modified().unwrap();
+        // 2023-11-14 22:13:20 UTC.
+        assert_eq!(
+            (modified.year(), modified.month(), modified.day(), modified.hour(), modified.minute(), modified.second()),
+            (2023, 11, 14, 22, 13, 20)
+        );
+        assert!(archive_timestamp(0).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_adds_project_files() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("game");
+        std::fs::create_dir_all(project.join("www/data")).unwrap();
+        std::fs::create_dir_all(project.join(APP_DATA_DIR_NAME)).unwrap();
+        std::fs::write(project.join("www/data/Actors.json"), "[\"ハロルド\"]").unwrap();
+        std::fs::write(project.join("www/data/Items.json"), "[]").unwrap();
+        std::fs::write(project.join("www/index.html"), "<html></html>").unwrap();
+        std::fs::write(project.join(APP_DATA_DIR_NAME).join("session.json"), "{}").unwrap();
+        let project_path = project.to_str().unwrap();
+        let zip_path = dir.path().join("out.zip");
+        let translated = HashMap::from([("www/data/Actors.json".to_string(), "[\"Harold\"]".to_string())]);
+        create_zip_archive_from_memory(&translated, &zip_path).unwrap();
+
+        let options = ArchiveOptions { compression_level: Some(9), ..Default::default() };
+        let mut files = project_files(project_path, "").unwrap();
+        assert_eq!(files["www/index.html"], ArchiveEntry::File(project.join("www/index.html")));
+        files.extend(read_zip_archive(&zip_path).unwrap());
+        let full_path = dir.path().join("full.zip");
+        write_zip_archive(&files, &full_path, &options).unwrap();
+        let mut archive = ZipArchive::new(File::open(&full_path).unwrap()).unwrap();
+        let names: Vec<String> = (0..archive.len()).map(|i| archive.by_index(i).unwrap().name().to_string()).collect();
+        assert_eq!(names, vec!["www/data/Actors.json", "www/data/Items.json", "www/index.html"]);
+        let mut actors = String::new();
+        archive.by_name("www/data/Actors.json").unwrap().read_to_string(&mut actors).unwrap();
+        assert_eq!(actors, "[\"Harold\"]");
+
+        assert_eq!(project_files(project_path, "www/data").unwrap().len(), 2);
+        let too_high = ArchiveOptions { compression_level: Some(12), ..Default::default() };
+        assert!(matches!(write_zip_archive(&files, &full_path, &too_high), Err(CoreError::Config(_))));
+    }
+
+    #[test]
+    fn test_create_tar_gz_archive() {
+        let dir = tempdir().unwrap();
+        let long_path = format!("www/{}/Map001.json", "d".repeat(120));
+        let longer_path = format!("www/{}.json", "e".repeat(300));
+        let files = ArchiveFiles::from([
+            ("www/data/Actors.json".to_string(), ArchiveEntry::Bytes(b"[\"Harold\"]".to_vec())),
+            (long_path.clone(), ArchiveEntry::Bytes(vec![b'x'; 600])),
+            (longer_path.clone(), ArchiveEntry::Bytes(Vec::new())),
+        ]);
+        let path = dir.path().join("out.tar.gz");
+        create_tar_gz_archive(&files, &path, &ArchiveOptions::default()).unwrap();
+
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(File::open(&path).unwrap()));
+        let mut entries = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            assert_eq!(entry.header().mtime().unwrap(), DEFAULT_ARCHIVE_TIMESTAMP as u64);
+            entries.push((name, content.len()));
+        }
+        assert_eq!(entries, vec![("www/data/Actors.json".to_string(), 10), (long_path, 600), (longer_path, 0)]);
+
+        // Sizes past what a ustar header holds.
+        let size = 9 * 1024 * 1024 * 1024;
+        assert_eq!(tar_header(size, 0).entry_size().unwrap(), size);
+    }
+}
@@ -1,6 +1,7 @@
 use std::path::Path;
 use crate::models::translation::SourceStringData;
 use crate::core::rpgmv::project::extract_translatable_strings_from_project;
+use crate::models::extraction::ExtractionFilter;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{PathBuf};
@@ -13,7 +14,7 @@ pub fn setup_and_extract_all_strings() -> Vec<SourceStringData> {
     let data_path = project_path.join("www").join("data");
     assert!(data_path.exists(), "Sample project www/data directory does not exist at: {:?}", data_path);
     let project_path_str = project_path.to_str().expect("Path to string conversion failed");
-    let result = extract_translatable_strings_from_project(project_path_str);
+    let result = extract_translatable_strings_from_project(project_path_str, &ExtractionFilter::default());
     assert!(result.is_ok(), "Extraction failed: {:?}", result.err());
     result.unwrap()
 }
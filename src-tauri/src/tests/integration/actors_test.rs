@@ -5,7 +5,7 @@ mod tests {
     // use crate::core::rpgmv::project::extract_translatable_strings_from_project; // No longer needed here
     use crate::tests::common_test_utils::setup_project_and_extract_strings;
     use crate::core::rpgmv::actors; // Updated import
-    use crate::models::translation::WorkingTranslation; // Added import
+    use crate::models::translation::{TranslationStatus, WorkingTranslation};
     use serde_json::json;
 
     const TEST_PROJECT_SUBPATH: &str = "test_projects/rpg_mv_project_1";
@@ -29,6 +29,7 @@ mod tests {
             json_path: json_path.to_string(),
             translation_source: "test_engine".to_string(), // Added field
             error: None,
+            status: TranslationStatus::MachineTranslated,
         }
     }
 
@@ -9,7 +9,7 @@ mod items_extraction_tests {
 #[cfg(test)]
 mod tests {
     use crate::core::rpgmv::items;
-    use crate::models::translation::{SourceStringData, WorkingTranslation};
+    use crate::models::translation::{SourceStringData, TranslationStatus, WorkingTranslation};
     use crate::tests::common_test_utils::setup_project_and_extract_strings;
     use serde_json::json;
 
@@ -32,6 +32,7 @@ mod tests {
             json_path: json_path.to_string(),
             translation_source: "test_engine".to_string(),
             error: None,
+            status: TranslationStatus::MachineTranslated,
         }
     }
 
@@ -0,0 +1,30 @@
+use zip::DateTime;
+use crate::error::CoreError;
+
+/// Unix seconds as a ZIP timestamp, rounded down to the format's two-second resolution. ZIP
+/// can only store times from 1980 to 2107.
+pub fn archive_timestamp(unix_seconds: i64) -> Result<DateTime, CoreError> {
+    let (days, seconds) = (unix_seconds.div_euclid(86_400), unix_seconds.rem_euclid(86_400));
+    // Days since 1970-01-01 to a proleptic Gregorian date.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let out_of_range = || CoreError::Config(format!("Archive timestamp {} is outside 1980-2107", unix_seconds));
+    let year = u16::try_from(year).map_err(|_| out_of_range())?;
+    DateTime::from_date_and_time(
+        year,
+        month as u8,
+        day as u8,
+        (seconds / 3_600) as u8,
+        (seconds % 3_600 / 60) as u8,
+        (seconds % 60) as u8,
+    )
+    .map_err(|_| out_of_range())
+}
@@ -3,11 +3,14 @@ use crate::error::CoreError; // Using our existing CoreError
 
 /// Updates a `serde_json::Value` at a given path string.
 /// The path string uses dot notation for object fields and brackets for array indices (e.g., "fieldName.arrayName[0].nestedField").
+/// Nested arrays chain their indices, e.g. "parameters[0][2]" for a Show Choices choice.
 pub fn update_value_at_path(
     root: &mut Value,
     path_str: &str,
     new_text: &str,
 ) -> Result<(), CoreError> {
+    // "a[0][2]" is read as "a[0].[2]": an index segment without a key applies to the current value.
+    let path_str = path_str.replace("][", "].[");
     let mut parts = path_str.split('.');
     let first_part = parts.next().ok_or_else(|| CoreError::Custom(format!("Empty path string provided to update_value_at_path")))?;
     
@@ -1 +1,5 @@
-pub mod json_utils; 
\ No newline at end of file
+pub mod archive_time;
+pub mod json_utils;
+pub mod project_paths;
+pub mod text_diff;
+ 
\ No newline at end of file
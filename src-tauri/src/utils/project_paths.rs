@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+
+/// Name of the folder inside a game project where the app keeps its own files
+/// (rulesets, sessions, caches). Never shipped as part of the translated output.
+pub const APP_DATA_DIR_NAME: &str = ".translate-ai";
+
+/// Returns `<project>/.translate-ai`, without creating it.
+pub fn app_data_dir(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(APP_DATA_DIR_NAME)
+}
+
+/// Extension appended to files in the app data folder while the session is locked.
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+/// Fails while the app data folder holds encrypted files: anything written there then would be
+/// overwritten by, or stand in the way of, the decrypted files when the session is unlocked.
+pub fn check_app_data_unlocked(project_path: &str) -> std::io::Result<()> {
+    let locked = walkdir::WalkDir::new(app_data_dir(project_path))
+        .into_iter()
+        .filter_map(Result::ok)
+        .any(|entry| {
+            entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == ENCRYPTED_EXTENSION)
+        });
+    if locked {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "The project's session files are locked; unlock them before saving",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the path of a file inside the project's app data folder, creating the folder if needed.
+/// Fails while the session is locked (see `check_app_data_unlocked`).
+pub fn ensure_app_data_file(project_path: &str, file_name: &str) -> std::io::Result<PathBuf> {
+    check_app_data_unlocked(project_path)?;
+    let dir = app_data_dir(project_path);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(file_name))
+}
@@ -0,0 +1,191 @@
+// Line-based unified diffs, for previewing reconstructed files against the originals.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+// Beyond this many edits the middle of the file is shown as one replaced block; reconstructed
+// files with that many changes have the same line count and are aligned instead.
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+/// Myers' shortest edit script between `a` and `b`, or `None` if it needs more than `max_d` edits.
+fn myers<'a>(a: &[&'a str], b: &[&'a str], max_d: usize) -> Option<Vec<(Op, &'a str)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let offset = max_d as isize + 1;
+    let mut v = vec![0isize; 2 * max_d + 3];
+    let mut trace = Vec::new();
+    let mut found = false;
+    for d in 0..=max_d as isize {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[(offset + k - 1) as usize] < v[(offset + k + 1) as usize]) {
+                v[(offset + k + 1) as usize]
+            } else {
+                v[(offset + k - 1) as usize] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(offset + k) as usize] = x;
+            if x >= n && y >= m {
+                found = true;
+                break;
+            }
+        }
+        if found {
+            break;
+        }
+    }
+    if !found {
+        return None;
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[(offset + k - 1) as usize] < v[(offset + k + 1) as usize]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(offset + prev_k) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push((Op::Equal, a[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push((Op::Insert, b[prev_y as usize]));
+            } else {
+                ops.push((Op::Delete, a[prev_x as usize]));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    Some(ops)
+}
+
+/// Line i of `a` against line i of `b`; runs of changed lines are grouped as deletions then insertions.
+fn aligned<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let mut ops = Vec::new();
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] == b[i] {
+            ops.push((Op::Equal, a[i]));
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < a.len() && a[i] != b[i] {
+            i += 1;
+        }
+        ops.extend(a[start..i].iter().map(|line| (Op::Delete, *line)));
+        ops.extend(b[start..i].iter().map(|line| (Op::Insert, *line)));
+    }
+    ops
+}
+
+fn line_ops<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops: Vec<(Op, &str)> = a[..prefix].iter().map(|line| (Op::Equal, *line)).collect();
+    match myers(a_mid, b_mid, MAX_EDIT_DISTANCE) {
+        Some(middle) => ops.extend(middle),
+        None if a_mid.len() == b_mid.len() => ops.extend(aligned(a_mid, b_mid)),
+        None => {
+            ops.extend(a_mid.iter().map(|line| (Op::Delete, *line)));
+            ops.extend(b_mid.iter().map(|line| (Op::Insert, *line)));
+        }
+    }
+    ops.extend(a[a.len() - suffix..].iter().map(|line| (Op::Equal, *line)));
+    ops
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    // An empty range names the line before it, as in `diff -u`.
+    let start = if count == 0 { start } else { start + 1 };
+    format!("{},{}", start, count)
+}
+
+/// Unified diff of `old` and `new` with `context` lines around each change, or an empty string
+/// when they have the same lines.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str, context: usize) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = line_ops(&old_lines, &new_lines);
+    let changes: Vec<usize> = ops.iter().enumerate().filter(|(_, (op, _))| *op != Op::Equal).map(|(i, _)| i).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Changes at most twice the context apart share a hunk.
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        match groups.last_mut() {
+            Some((_, last)) if i - *last <= 2 * context + 1 => *last = i,
+            _ => groups.push((i, i)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for (first, last) in groups {
+        let start = first.saturating_sub(context);
+        let end = (last + context + 1).min(ops.len());
+        let old_start = ops[..start].iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_start = ops[..start].iter().filter(|(op, _)| *op != Op::Delete).count();
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_count = hunk.iter().filter(|(op, _)| *op != Op::Delete).count();
+        out.push_str(&format!("@@ -{} +{} @@\n", hunk_range(old_start, old_count), hunk_range(new_start, new_count)));
+        for (op, line) in hunk {
+            let marker = match op {
+                Op::Equal => ' ',
+                Op::Delete => '-',
+                Op::Insert => '+',
+            };
+            out.push(marker);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(
+            unified_diff(old, new, "a/x.txt", "b/x.txt", 1),
+            "--- a/x.txt\n+++ b/x.txt\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -10,1 +10,2 @@\n j\n+k\n"
+        );
+        assert_eq!(unified_diff(old, old, "a", "b", 3), "");
+        assert_eq!(unified_diff("", "x\n", "a", "b", 3), "--- a\n+++ b\n@@ -0,0 +1,1 @@\n+x\n");
+    }
+
+    #[test]
+    fn test_large_diff_with_same_line_count_is_aligned() {
+        let old: String = (0..3000).map(|i| format!("line {}\n", i)).collect();
+        let new: String = (0..3000).map(|i| if i % 2 == 0 { format!("LINE {}\n", i) } else { format!("line {}\n", i) }).collect();
+        let diff = unified_diff(&old, &new, "a", "b", 0);
+        assert!(diff.contains("@@ -1,1 +1,1 @@\n-line 0\n+LINE 0\n@@ -3,1 +3,1 @@\n-line 2\n+LINE 2\n"));
+        assert_eq!(diff.matches("\n-").count(), 1500);
+    }
+}
@@ -1,10 +1,12 @@
 import { defineStore } from 'pinia'
 import { ref } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { useToast, navigateTo } from '#imports'
 import type { SourceStringData, WorkingTranslation } from '~/types/translation'
 import { useTranslationStore } from './translation'
-import type { RpgMakerDetectionResultType } from '~/types/project'
+import type { ExtractionChunk, ExtractionResult, ReconstructionWarning, RpgMakerDetectionResultType } from '~/types/project'
+import { isAppError, formatAppError } from '~/types/error'
 
 // Define the enum/type for the detection result on the frontend
 // Matches the RpgMakerDetectionResult enum in Rust
@@ -12,6 +14,9 @@ import type { RpgMakerDetectionResultType } from '~/types/project'
 // TranslatableStringEntry and TranslatedStringEntry are now in translation.ts
 // Consider moving to a shared types/ file in the future.
 
+// Entries per "extraction://chunk" event.
+const EXTRACTION_CHUNK_SIZE = 2000
+
 export const useProjectStore = defineStore('project', () => {
   // --- State ---
   const selectedProjectFolderPath = ref<string | null>(null)
@@ -24,6 +29,7 @@ export const useProjectStore = defineStore('project', () => {
 
   const isLoadingReconstruction = ref(false)
   const reconstructionError = ref<string | null>(null)
+  const reconstructionWarnings = ref<ReconstructionWarning[]>([])
 
   // State for ZIP file paths (moved from translation.ts)
   const tempZipPath = ref<string | null>(null)
@@ -39,6 +45,7 @@ export const useProjectStore = defineStore('project', () => {
   // --- Helper for error messages ---
   const getErrorMessage = (err: unknown, context: string): string => {
     if (typeof err === 'string') return err;
+    if (isAppError(err)) return formatAppError(err);
     if (err instanceof Error) return err.message;
     return `Unknown error during ${context}`;
   };
@@ -50,7 +57,7 @@ export const useProjectStore = defineStore('project', () => {
 
     try {
       const result = await invoke<
-        [string, RpgMakerDetectionResultType] | null
+        [string, RpgMakerDetectionResultType, Record<string, unknown>] | null
       >('select_project_folder_command');
 
       if (result && result[0]) {
@@ -97,10 +104,26 @@ export const useProjectStore = defineStore('project', () => {
     extractedStrings.value = [] 
     extractionError.value = null 
 
+    // Entries arrive in chunks so large projects don't block the UI on one huge IPC message.
+    // Chunks of other extractions (an earlier one still sending) carry another job id.
+    const jobId = crypto.randomUUID()
+    const received: SourceStringData[] = []
+    let lastChunkReceived: () => void = () => {}
+    const allChunks = new Promise<void>((resolve) => { lastChunkReceived = resolve })
+    const unlisten = await listen<ExtractionChunk>('extraction://chunk', (event) => {
+      if (event.payload.jobId !== jobId) return
+      received.push(...event.payload.entries)
+      if (event.payload.index === event.payload.chunkCount - 1) lastChunkReceived()
+    })
     try {
-      const result: SourceStringData[] = await invoke('extract_project_strings_command', {
+      await invoke<ExtractionResult>('extract_project_strings_command', {
         projectPath: selectedProjectFolderPath.value,
+        jobId,
+        chunkSize: EXTRACTION_CHUNK_SIZE,
       })
+      // The command returns once every chunk is sent, which may be before the last one arrives.
+      await allChunks
+      const result = received
       extractedStrings.value = result
       if (result.length === 0) {
         toast.add({ 
@@ -118,6 +141,7 @@ export const useProjectStore = defineStore('project', () => {
       await navigateTo('/project');
     } catch (err) {
       extractionError.value = getErrorMessage(err, 'extraction');
+      received.length = 0
       extractedStrings.value = [] 
       toast.add({ 
         title: 'Extraction Failed',
@@ -125,6 +149,7 @@ export const useProjectStore = defineStore('project', () => {
         color: 'error',
       })
     } finally {
+      unlisten()
       isLoadingExtractedStrings.value = false
     }
   }
@@ -141,6 +166,7 @@ export const useProjectStore = defineStore('project', () => {
 
     isLoadingReconstruction.value = true;
     reconstructionError.value = null;
+    reconstructionWarnings.value = [];
     tempZipPath.value = null; // Reset temp path before new operation
     finalZipSavedPath.value = null; // Reset final saved path too
 
@@ -152,10 +178,16 @@ export const useProjectStore = defineStore('project', () => {
 
       if (tempZipFilePath) {
         tempZipPath.value = tempZipFilePath; // Set the new temp path here
+        reconstructionWarnings.value = await invoke<ReconstructionWarning[]>('get_reconstruction_warnings_command', {
+          projectPath: selectedProjectFolderPath.value,
+        });
+        const skipped = reconstructionWarnings.value.length;
         toast.add({ 
           title: 'Reconstruction Successful', 
-          description: `Project files reconstructed and packaged into a temporary ZIP.`,
-          color: 'success'
+          description: skipped > 0
+            ? `Project files packaged into a temporary ZIP; ${skipped} translation(s) were skipped.`
+            : `Project files reconstructed and packaged into a temporary ZIP.`,
+          color: skipped > 0 ? 'warning' : 'success'
         });
       } else {
         reconstructionError.value = "Reconstruction command returned an empty path without error.";
@@ -237,6 +269,7 @@ export const useProjectStore = defineStore('project', () => {
     extractionError.value = null;
     isLoadingReconstruction.value = false;
     reconstructionError.value = null;
+    reconstructionWarnings.value = [];
 
     // Reset new ZIP related state
     tempZipPath.value = null;
@@ -257,6 +290,7 @@ export const useProjectStore = defineStore('project', () => {
     extractionError,
     isLoadingReconstruction,
     reconstructionError,
+    reconstructionWarnings,
     // Expose new state and actions
     tempZipPath,
     finalZipSavedPath,
@@ -1,6 +1,7 @@
 import { defineStore } from 'pinia';
 import { ref } from 'vue';
-import type { LanguageOption, EngineOption } from '~/types/setting';
+import { invoke } from '@tauri-apps/api/core';
+import type { LanguageOption, EngineOption, TranslationPreset, FaultInjectionSettings } from '~/types/setting';
 
 // Define a simple type for language options for clarity
 // export interface LanguageOption {
@@ -32,12 +33,36 @@ export const useSettingsStore = defineStore('settings', () => {
     // { id: 'deepl', label: 'DeepL (Online)' }, // Future option
   ]);
 
+  // Built-in translation presets (honorifics, SFX, punctuation per source language).
+  const translationPresets = ref<TranslationPreset[]>([]);
+
+  // Developer setting: simulated provider failures for batches. Off when null.
+  const faultInjection = ref<FaultInjectionSettings | null>(null);
+
   // --- Getters (if any needed later, e.g., to find a language by id) ---
 
   // --- Actions (if any needed later, e.g., to load settings from persistent storage) ---
+  async function loadTranslationPresets() {
+    if (translationPresets.value.length > 0) return;
+    try {
+      translationPresets.value = await invoke<TranslationPreset[]>('get_translation_presets_command');
+    } catch (error) {
+      console.error('Failed to load translation presets:', error);
+    }
+  }
+
+  // Preset for a source language: "ja-JP" uses the "ja" preset.
+  function presetForLanguage(languageId: string): TranslationPreset | undefined {
+    const family = languageId.split(/[-_]/)[0]?.toLowerCase();
+    return translationPresets.value.find(preset => preset.id === family);
+  }
 
   return {
     languageOptions,
     engineOptions,
+    translationPresets,
+    faultInjection,
+    loadTranslationPresets,
+    presetForLanguage,
   };
 }); 
\ No newline at end of file
@@ -2,10 +2,17 @@ import { defineStore } from 'pinia'
 import { ref } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
 import { useToast, navigateTo } from '#imports' // Assuming navigateTo is auto-imported or available
-import type { SourceStringData, WorkingTranslation } from '~/types/translation'; // Updated import
+import type {
+  SourceStringData, StoreCounts, StringPageQuery, WorkingTranslation,
+} from '~/types/translation'; // Updated import
+import { isAppError, formatAppError } from '~/types/error'
+import { useSettingsStore } from '~/stores/settings'
 
 // LanguageOption interface removed as it's now in stores/settings.ts
 
+// Strings per batch when translating a project's string store by range.
+const TRANSLATION_PAGE_SIZE = 500
+
 export const useTranslationStore = defineStore('translation', () => {
   const toast = useToast()
 
@@ -20,16 +27,45 @@ export const useTranslationStore = defineStore('translation', () => {
   // --- Helper for error messages ---
   const getErrorMessage = (err: unknown, context: string): string => {
     if (typeof err === 'string') return err;
+    if (isAppError(err)) return formatAppError(err);
     if (err instanceof Error) return err.message;
     return `Unknown error during ${context}`;
   };
 
+  // Translates the project's untranslated stored strings a page at a time, so the whole project
+  // is never sent over IPC at once. Failed strings leave the untranslated ones, so every page is
+  // new; the page count is still capped in case a page couldn't be saved to the store.
+  async function translateProjectByPage(
+    projectPath: string,
+    sourceLanguage: string,
+    targetLanguage: string,
+    engineName: string
+  ): Promise<WorkingTranslation[]> {
+    const counts = await invoke<StoreCounts>('get_store_counts_command', { projectPath })
+    const range: StringPageQuery = { statuses: ['untranslated'], limit: TRANSLATION_PAGE_SIZE }
+    const results: WorkingTranslation[] = []
+    for (let page = 0; page < Math.ceil(counts.untranslated / TRANSLATION_PAGE_SIZE); page++) {
+      const translated = await invoke<WorkingTranslation[]>('batch_translate_strings_command', {
+        sourceLanguage,
+        targetLanguage,
+        engineName,
+        options: { projectPath, range, faultInjection: useSettingsStore().faultInjection },
+      })
+      if (translated.length === 0) break
+      results.push(...translated)
+    }
+    return results
+  }
+
   // --- Actions ---
+  // With `projectPath`, the project's string store is translated by page instead of sending
+  // `entriesToTranslate`.
   async function performBatchTranslation(
     entriesToTranslate: SourceStringData[], // Updated type
     sourceLanguage: string, 
     targetLanguage: string, 
-    engineName: string
+    engineName: string,
+    projectPath: string | null = null
   ) {
     if (!entriesToTranslate || entriesToTranslate.length === 0) {
       toast.add({ title: 'Batch Translation Error', description: 'No strings provided to translate.', color: 'error' });
@@ -41,12 +77,15 @@ export const useTranslationStore = defineStore('translation', () => {
     batchTranslationError.value = null;
 
     try {
-      const results: WorkingTranslation[] = await invoke('batch_translate_strings_command', { // Updated type
-        entries: entriesToTranslate,
-        sourceLanguage,
-        targetLanguage,
-        engineName, 
-      });
+      const results: WorkingTranslation[] = projectPath
+        ? await translateProjectByPage(projectPath, sourceLanguage, targetLanguage, engineName)
+        : await invoke('batch_translate_strings_command', { // Updated type
+          entries: entriesToTranslate,
+          sourceLanguage,
+          targetLanguage,
+          engineName, 
+          options: { faultInjection: useSettingsStore().faultInjection },
+        });
       batchTranslatedStrings.value = results;
 
       const successCount = results.filter(r => r.error === null).length;
@@ -0,0 +1,32 @@
+// Matches the ErrorKind enum in Rust (src-tauri/src/error.rs)
+export type AppErrorKind =
+  | 'jsonParse'
+  | 'jsonSerialize'
+  | 'io'
+  | 'zip'
+  | 'unimplemented'
+  | 'config'
+  | 'database'
+  | 'notFound'
+  | 'provider'
+  | 'qualityGate'
+  | 'other'
+
+// Error returned by every Tauri command
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+  file?: string; // project-relative or absolute file the error is about
+  path?: string; // JSON path inside that file
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return typeof err === 'object' && err !== null && 'kind' in err && 'message' in err;
+}
+
+export function formatAppError(err: AppError): string {
+  if (err.file && err.path) return `${err.message} (${err.file} at ${err.path})`;
+  if (err.file) return `${err.message} (${err.file})`;
+  if (err.path) return `${err.message} (at ${err.path})`;
+  return err.message;
+}
@@ -1,4 +1,260 @@
+import type { SourceStringData, TranslationStatus } from '~/types/translation';
+
 export type RpgMakerDetectionResultType = 
   | 'DetectedByProjectFile' 
   | 'DetectedByWwwData' 
+  | 'DetectedWolfRpg'
+  | 'DetectedRenPy'
+  | 'DetectedRpgVxAce'
+  | 'DetectedTyranoScript'
+  | 'DetectedGenericRules'
   | 'NotDetected'
+
+// Matches ReconstructionWarning in src-tauri/src/core/rpgmv/warnings.rs
+export interface ReconstructionWarning {
+  kind: 'skippedPath' | 'idMismatch' | 'outOfBounds' | 'updateFailed';
+  sourceFile: string;
+  jsonPath: string | null;
+  message: string;
+}
+
+// Matches FileCoverage in src-tauri/src/models/coverage.rs
+export interface FileCoverage {
+  sourceFile: string;
+  entries: number;
+  translated: number;
+  reviewed: number;
+  validationPassed: number;
+  percentTranslated: number;
+  percentReviewed: number;
+  validationPassRate: number | null;
+  badge: 'untranslated' | 'needsWork' | 'good' | 'complete';
+}
+
+// Matches CharacterCoverageIssue in src-tauri/src/models/validation.rs
+export interface CharacterCoverageIssue {
+  sourceFile: string;
+  jsonPath: string;
+  translatedText: string;
+  characters: string[]; // characters the game's font can't draw
+}
+
+// Matches QaReport in src-tauri/src/models/qa_report.rs
+export type QaCategory =
+  | 'placeholderMismatch'
+  | 'untranslated'
+  | 'longLine'
+  | 'glossaryViolation'
+  | 'identicalToSource'
+  | 'reconstructionWarning';
+
+export interface QaIssue {
+  category: QaCategory;
+  jsonPath: string | null;
+  originalText: string | null;
+  translatedText: string | null;
+  message: string;
+}
+
+export interface QaReport {
+  files: { sourceFile: string; issues: QaIssue[] }[];
+  counts: Partial<Record<QaCategory, number>>;
+  totalIssues: number;
+}
+
+// Matches ReconstructionPreview in src-tauri/src/core/rpgmv/preview.rs
+export interface FileDiff {
+  sourceFile: string;
+  diff: string; // unified diff, empty for binary files
+  linesAdded: number;
+  linesRemoved: number;
+  binary: boolean;
+}
+
+export interface ReconstructionPreview {
+  files: FileDiff[];
+  errors: string[];
+  warnings: ReconstructionWarning[];
+}
+
+// Matches TempUsage in src-tauri/src/services/temp_workspace.rs
+export interface TempUsage {
+  root: string;
+  totalBytes: number;
+  quotaBytes: number;
+  workspaces: {
+    name: string;
+    kind: 'output' | 'staging' | null;
+    bytes: number;
+    active: boolean;
+  }[];
+}
+
+// Matches EventCodeReport in src-tauri/src/core/rpgmv/event_codes.rs
+export type EventCodeStatus = 'extracted' | 'notExtracted' | 'noText' | 'unknown';
+
+export interface EventCodeStats {
+  code: number;
+  name: string | null;
+  status: EventCodeStatus;
+  occurrences: number;
+  textOccurrences: number;
+  files: string[];
+  example: { sourceFile: string; jsonPath: string; text: string } | null;
+}
+
+export interface EventCodeReport {
+  totalCommands: number;
+  extractedCommands: number;
+  codes: EventCodeStats[];
+  attention: number[];
+}
+
+// Matches InPlaceReport in src-tauri/src/core/rpgmv/patching.rs
+export interface InPlaceReport {
+  backupId: string | null;
+  written: string[];
+  errors: string[];
+  warnings: ReconstructionWarning[];
+}
+
+// Matches BackupSummary and RestoreReport in src-tauri/src/core/rpgmv/patching.rs
+export interface BackupSummary {
+  manifest: {
+    id: string;
+    createdAt: number;
+    files: { path: string; originalMd5: string | null; patchedMd5: string }[];
+  };
+  problems: string[];
+}
+
+export interface RestoreReport {
+  restored: string[];
+  modifiedSincePatch: string[];
+}
+
+// Matches SampleProject in src-tauri/src/core/rpgmv/sample_project.rs
+export interface SampleProject {
+  projectPath: string;
+  files: string[];
+}
+
+// Matches ExtractionResult in src-tauri/src/core/rpgmv/snapshot.rs
+export interface ExtractionResult {
+  entries: SourceStringData[];
+  removed: SourceStringData[];
+  incremental: boolean;
+  // Entries sent in ExtractionChunk events instead of in `entries`; 0 unless streamed.
+  streamedEntries: number;
+}
+
+// Matches ExtractionChunk in src-tauri/src/models/extraction.rs, the payload of
+// "extraction://chunk" events.
+export interface ExtractionChunk {
+  jobId: string | null;
+  index: number;
+  chunkCount: number;
+  entries: SourceStringData[];
+}
+
+// Matches ProjectDocument in src-tauri/src/models/project_document.rs
+export interface ProjectEntry extends SourceStringData {
+  translatedText?: string;
+  status: TranslationStatus;
+  provider?: string;
+  error?: string;
+}
+
+export interface ProjectDocument {
+  version: number;
+  projectPath: string;
+  sourceLanguage: string;
+  targetLanguage: string;
+  provider?: string;
+  entries: ProjectEntry[];
+  history: { at: number; entries: number; translated: number; reviewed: number }[];
+}
+
+// Matches PatchLine and PatchMatchReport in src-tauri/src/models/exchange.rs
+export interface PatchLine {
+  file: string;
+  path: string;
+  original_hash: string;
+  translation: string;
+}
+
+export interface PatchMatchReport {
+  lines: number;
+  matched: number;
+  unmatched: { line: PatchLine; reason: string }[];
+}
+
+// Matches PatchApplication in src-tauri/src/core/exchange/patch.rs
+export interface PatchApplication {
+  report: PatchMatchReport;
+  written: InPlaceReport;
+}
+
+// Matches ZipContent, ArchiveFormat and ZipSaveOptions in src-tauri/src/models/archive.rs
+export type ZipContent = 'modifiedOnly' | 'wholeData' | 'fullCopy';
+
+// 'sevenZip' needs the 7-Zip command line tool; get_archive_formats_command lists the formats
+// available on this machine.
+export type ArchiveFormat = 'zip' | 'tarGz' | 'sevenZip';
+
+export interface ZipSaveOptions {
+  content?: ZipContent;
+  // Required unless only modified files are saved.
+  projectPath?: string;
+  // 0 (fastest) to 9 (smallest).
+  compressionLevel?: number;
+  format?: ArchiveFormat;
+}
+
+// Matches QueueStage, QueueProgress, ProjectQueueResult and QueueSummary in
+// src-tauri/src/models/queue.rs; progress is the payload of the "queue://progress" event.
+export type QueueStage =
+  | 'extracting'
+  | 'translating'
+  | 'reconstructing'
+  | 'archiving'
+  | 'done'
+  | 'failed'
+  | 'skipped';
+
+export interface QueueProgress {
+  projectPath: string;
+  index: number;
+  total: number;
+  stage: QueueStage;
+}
+
+export interface ProjectQueueResult {
+  projectPath: string;
+  stage: QueueStage;
+  archivePath: string | null;
+  entries: number;
+  translated: number;
+  failed: number;
+  error: string | null;
+}
+
+export interface QueueSummary {
+  projects: ProjectQueueResult[];
+  succeeded: number;
+  failed: number;
+  skipped: number;
+}
+
+// Matches WatchUpdate in src-tauri/src/models/watch.rs, the payload of the "watch://update"
+// event emitted after each pass over a project watched with watch_project_command.
+export interface WatchUpdate {
+  projectPath: string;
+  // Empty for the first pass, which covers the whole project.
+  changedFiles: string[];
+  newEntries: number;
+  translated: number;
+  failed: number;
+  filesWritten: string[];
+  error: string | null;
+}
@@ -7,3 +7,94 @@ export interface EngineOption {
   id: string;
   label: string;
 }
+
+// Matches TranslationPreset in src-tauri/src/models/preset.rs
+export type HonorificPolicy = 'keep' | 'adapt' | 'drop';
+export type NameOrder = 'source' | 'western';
+
+export interface Honorific {
+  term: string;
+  romanized: string;
+  meaning: string;
+}
+
+export interface TranslationPreset {
+  id: string;
+  name: string;
+  honorificPolicy: HonorificPolicy;
+  nameOrder: NameOrder;
+  honorifics: Honorific[];
+  sfx: Record<string, string>;
+  punctuation: Record<string, string>;
+}
+
+// Matches FaultInjectionSettings in src-tauri/src/models/provider.rs
+export interface FaultInjectionSettings {
+  timeoutRate: number;
+  rateLimitRate: number;
+  malformedRate: number;
+  timeoutMs?: number;
+  seed?: number;
+}
+
+// Matches RetrySettings in src-tauri/src/models/provider.rs
+export interface RetrySettings {
+  maxRetries?: number;
+  initialBackoffMs?: number;
+  maxBackoffMs?: number;
+  circuitBreakerThreshold?: number;
+  circuitOpenMs?: number;
+}
+
+// Matches ProviderDiagnostic in src-tauri/src/models/provider.rs
+export type CheckStatus = 'passed' | 'warning' | 'failed' | 'skipped';
+
+export interface DiagnosticCheck {
+  name: 'connection' | 'model' | 'translation';
+  status: CheckStatus;
+  message: string;
+  latencyMs: number | null;
+}
+
+export interface ProviderDiagnostic {
+  provider: string;
+  healthy: boolean;
+  checks: DiagnosticCheck[];
+}
+
+// Matches CostEstimate in src-tauri/src/models/provider.rs
+export interface CostEstimate {
+  provider: 'ollama' | 'deepl' | 'openai';
+  model: string | null;
+  requests: number;
+  characters: number;
+  inputTokens: number;
+  outputTokens: number;
+  // USD; null when the model's price is unknown.
+  estimatedCostUsd: number | null;
+  notes: string[];
+}
+
+// Matches PromptTemplate in src-tauri/src/models/prompt.rs
+export type PromptKind = 'dialogue' | 'itemDescription' | 'systemTerm' | 'noteTag' | 'general';
+
+export interface PromptTemplate {
+  kind: PromptKind;
+  // Variables: {source_lang}, {target_lang}, {glossary}, {speaker}, {context}, {text}
+  template: string;
+  customized: boolean;
+}
+
+// Matches ScopeSummary in src-tauri/src/models/prompt.rs
+export interface ScopeSummary {
+  sourceFile: string;
+  // e.g. "events[3]"; the whole file when absent.
+  pathPrefix?: string;
+  summary: string;
+  // Provider that wrote it, or "manual".
+  provider: string;
+}
+
+export interface ScopeSummaries {
+  summaries: ScopeSummary[];
+}
@@ -3,6 +3,10 @@ export interface SourceStringData {
   originalText: string;
   sourceFile: string;
   jsonPath: string;
+  // Shared by the lines of one message box, which are translated together.
+  dialogueBlockId?: string;
+  // Original name of who speaks a message line.
+  speaker?: string;
 }
 
 export interface WorkingTranslation {
@@ -13,4 +17,137 @@ export interface WorkingTranslation {
   jsonPath: string;
   translationSource: string;
   error: string | null;
+  status: TranslationStatus;
+}
+
+// Matches PartialTranslation in src-tauri/src/models/translation.rs; payload of the
+// "translation://partial" event of streamed batches.
+export interface PartialTranslation {
+  sourceFile: string;
+  jsonPath: string;
+  // Everything generated so far.
+  text: string;
+}
+
+// Matches TranslationStatus in src-tauri/src/models/translation.rs
+export type TranslationStatus = 'untranslated' | 'machineTranslated' | 'edited' | 'approved' | 'rejected';
+
+// Matches CacheImpact in src-tauri/src/models/glossary.rs
+export interface CacheImpact {
+  changedTerms: string[];
+  affectedEntries: number;
+  cachedEntries: number;
+  examples: string[];
+}
+
+// Matches Catchphrase in src-tauri/src/models/character.rs
+export interface Catchphrase {
+  speaker: string;
+  text: string;
+  occurrences: number;
+  translation?: string;
+  // Locked catchphrases give every occurrence this translation without a request.
+  locked: boolean;
+}
+
+export interface Catchphrases {
+  catchphrases: Catchphrase[];
+}
+
+// Matches DedupScope and DuplicateOccurrence in src-tauri/src/models/translation.rs
+export type DedupScope = 'text' | 'textAndContext';
+
+export interface DuplicateOccurrence {
+  sourceFile: string;
+  jsonPath: string;
+}
+
+// Matches DuplicateGroup in src-tauri/src/core/dedup.rs
+export interface DuplicateGroup {
+  originalText: string;
+  context: 'dialogue' | 'description' | 'other' | null;
+  occurrences: DuplicateOccurrence[];
+}
+
+// Matches TranslationSet and MultiTargetLayout in src-tauri/src/models/translation.rs
+export interface TranslationSet {
+  targetLanguage: string;
+  translations: WorkingTranslation[];
+}
+
+// 'dataFolders' packages every language in one ZIP, under data_<language>/.
+export type MultiTargetLayout = 'zipPerLanguage' | 'dataFolders';
+
+// Matches SearchField, SearchQuery, SearchHit and SearchResults in
+// src-tauri/src/models/search.rs, used by search_strings_command.
+export type SearchField = 'original' | 'translated' | 'both';
+
+export type EntryStatus = 'untranslated' | 'translated' | 'failed';
+
+export interface SearchQuery {
+  text?: string | null;
+  regex?: boolean;
+  caseSensitive?: boolean;
+  field?: SearchField;
+  sourceFiles?: string[];
+  statuses?: EntryStatus[];
+  objectIds?: number[];
+  offset?: number;
+  // 100 when omitted.
+  limit?: number | null;
+}
+
+export interface SearchHit {
+  objectId: number;
+  sourceFile: string;
+  jsonPath: string;
+  originalText: string;
+  translatedText: string;
+  status: EntryStatus;
+  reviewStatus: TranslationStatus;
+}
+
+export interface SearchResults {
+  hits: SearchHit[];
+  total: number;
+  offset: number;
+}
+
+// Matches StoredString, StringPageQuery, StringPage and StoreCounts in
+// src-tauri/src/models/string_store.rs, used by the commands in src-tauri/src/commands/strings.rs
+// and by batch_translate_strings_command's `options.range`.
+export interface StoredString {
+  objectId: number;
+  sourceFile: string;
+  jsonPath: string;
+  originalText: string;
+  dialogueBlockId?: string;
+  speaker?: string;
+  // Empty until the string is translated.
+  translatedText: string;
+  translationSource: string;
+  error: string | null;
+  status: TranslationStatus;
+}
+
+export interface StringPageQuery {
+  offset?: number;
+  // 500 when omitted, at most 5000.
+  limit?: number | null;
+  sourceFiles?: string[];
+  statuses?: EntryStatus[];
+}
+
+export interface StringPage {
+  entries: StoredString[];
+  total: number;
+  offset: number;
+}
+
+export interface StoreCounts {
+  total: number;
+  translated: number;
+  untranslated: number;
+  failed: number;
+  files: number;
 }